@@ -471,13 +471,13 @@ pub fn query<T: IbcClient>(
         QueryMsg::Misbehaviour {
             caller,
             client_id,
-            message,
             relayer,
         } => {
+            let ibc_host = deps.storage.read_item::<IbcHost>()?;
+            let message = deps.querier.read_item::<QueryStore>(&ibc_host)?;
             let misbehaviour = T::Misbehaviour::decode_as::<T::Encoding>(&message)
                 .map_err(DecodeError::Misbehaviour)?;
 
-            let ibc_host = deps.storage.read_item::<IbcHost>()?;
             let client_state = T::misbehaviour(
                 IbcClientCtx::new(client_id, ibc_host, deps, env),
                 Addr::unchecked(caller),
@@ -90,11 +90,10 @@ pub enum QueryMsg {
         client_id: ClientId,
         relayer: String,
     },
-    /// TODO: Should read state through the [`QueryStore`].
+    /// NOTE: Reads state through the [`QueryStore`].
     Misbehaviour {
         caller: String,
         client_id: ClientId,
-        message: Bytes,
         relayer: String,
     },
 }
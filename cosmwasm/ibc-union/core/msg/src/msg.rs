@@ -25,6 +25,7 @@ pub enum ExecuteMsg {
     RegisterClient(MsgRegisterClient),
     CreateClient(MsgCreateClient),
     UpdateClient(MsgUpdateClient),
+    SubmitMisbehaviour(MsgSubmitMisbehaviour),
     ConnectionOpenInit(MsgConnectionOpenInit),
     ConnectionOpenTry(MsgConnectionOpenTry),
     ForceConnectionOpenTry(MsgConnectionOpenTry),
@@ -78,6 +79,14 @@ pub struct MsgUpdateClient {
     pub relayer: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MsgSubmitMisbehaviour {
+    pub client_id: ClientId,
+    pub client_message: Bytes,
+    pub relayer: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct MsgConnectionOpenInit {
@@ -11,8 +11,8 @@ use depolama::{RawStore, StorageExt};
 use frissitheto::{UpgradeError, UpgradeMsg};
 use ibc_union_msg::{
     lightclient::{
-        QueryMsg as LightClientQuery, Status, UpdateStateResponse, VerifyCreationResponse,
-        VerifyCreationResponseEvent,
+        MisbehaviourResponse, QueryMsg as LightClientQuery, Status, UpdateStateResponse,
+        VerifyCreationResponse, VerifyCreationResponseEvent,
     },
     module::{ExecuteMsg as ModuleMsg, IbcUnionMsg},
     msg::{
@@ -21,7 +21,7 @@ use ibc_union_msg::{
         MsgChannelOpenTry, MsgConnectionOpenAck, MsgConnectionOpenConfirm, MsgConnectionOpenInit,
         MsgConnectionOpenTry, MsgCreateClient, MsgIntentPacketRecv, MsgMigrateState,
         MsgPacketAcknowledgement, MsgPacketRecv, MsgPacketTimeout, MsgRegisterClient,
-        MsgSendPacket, MsgUpdateClient, MsgWriteAcknowledgement,
+        MsgSendPacket, MsgSubmitMisbehaviour, MsgUpdateClient, MsgWriteAcknowledgement,
     },
     query::QueryMsg,
 };
@@ -55,6 +55,7 @@ pub mod events {
         pub const REGISTER: &str = "register_client";
         pub const CREATE: &str = "create_client";
         pub const UPDATE: &str = "update_client";
+        pub const MISBEHAVIOUR: &str = "client_misbehaviour";
     }
     pub mod connection {
         pub const OPEN_INIT: &str = "connection_open_init";
@@ -208,6 +209,15 @@ pub fn execute(
                 relayer,
             )
         }
+        ExecuteMsg::SubmitMisbehaviour(MsgSubmitMisbehaviour {
+            client_id,
+            client_message,
+            relayer,
+        }) => {
+            ensure_relayer(deps.storage, &info.sender)?;
+            let relayer = deps.api.addr_validate(&relayer)?;
+            submit_misbehaviour(deps.branch(), info, client_id, client_message, relayer)
+        }
         ExecuteMsg::ConnectionOpenInit(MsgConnectionOpenInit {
             client_id,
             counterparty_client_id,
@@ -1093,6 +1103,56 @@ fn update_client(
     )
 }
 
+fn submit_misbehaviour(
+    mut deps: DepsMut,
+    info: MessageInfo,
+    client_id: ClientId,
+    client_message: Bytes,
+    relayer: Addr,
+) -> Result<Response, ContractError> {
+    let client_impl = client_impl(deps.as_ref(), client_id)?;
+
+    let status = query_light_client::<Status>(
+        deps.as_ref(),
+        client_impl.clone(),
+        LightClientQuery::GetStatus { client_id },
+    )?;
+
+    if !matches!(status, Status::Active) {
+        return Err(ContractError::ClientNotActive { client_id, status });
+    }
+
+    // Same as in `update_client`, the message is threaded through the store to allow for >64K
+    // messages.
+    deps.storage.write_item::<QueryStore>(&client_message);
+
+    let misbehaviour = query_light_client::<MisbehaviourResponse>(
+        deps.as_ref(),
+        client_impl,
+        LightClientQuery::Misbehaviour {
+            caller: info.sender.into(),
+            client_id,
+            relayer: relayer.into(),
+        },
+    )?;
+
+    deps.storage.delete_item::<QueryStore>();
+
+    // the light client is expected to return the frozen client state if the misbehaviour is valid
+    store_commit(
+        deps.branch(),
+        &ClientStatePath { client_id }.key(),
+        &commit(&misbehaviour.client_state),
+    );
+    deps.storage
+        .write::<ClientStates>(&client_id, &misbehaviour.client_state.into_vec().into());
+
+    Ok(Response::new().add_event(
+        Event::new(events::client::MISBEHAVIOUR)
+            .add_attribute(events::attribute::CLIENT_ID, client_id.to_string()),
+    ))
+}
+
 fn connection_open_init(
     mut deps: DepsMut,
     client_id: ClientId,
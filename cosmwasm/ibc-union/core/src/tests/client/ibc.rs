@@ -4,14 +4,19 @@ use cosmwasm_std::{
 };
 use depolama::StorageExt;
 use ibc_union_msg::{
-    lightclient::{QueryMsg as LightClientQueryMsg, UpdateStateResponse, VerifyCreationResponse},
-    msg::{ExecuteMsg, InitMsg, MsgUpdateClient},
+    lightclient::{
+        MisbehaviourResponse, QueryMsg as LightClientQueryMsg, UpdateStateResponse,
+        VerifyCreationResponse,
+    },
+    msg::{ExecuteMsg, InitMsg, MsgSubmitMisbehaviour, MsgUpdateClient},
 };
 
 use super::*;
 use crate::{
     contract::{events, execute, init},
-    state::{ClientConsensusStates, ClientImpls, ClientRegistry, ClientStates, ClientTypes},
+    state::{
+        ClientConsensusStates, ClientImpls, ClientRegistry, ClientStates, ClientTypes, QueryStore,
+    },
     ContractError,
 };
 
@@ -342,3 +347,153 @@ fn update_client_commitments_saved() {
         vec![3, 2, 1]
     );
 }
+
+#[test]
+fn submit_misbehaviour_ok() {
+    let mut deps = mock_dependencies();
+
+    init(
+        deps.as_mut(),
+        InitMsg {
+            relayers_admin: None,
+            relayers: vec![mock_addr(SENDER).to_string()],
+        },
+    )
+    .unwrap();
+    deps.querier
+        .update_wasm(wasm_query_handler(|msg| match msg {
+            LightClientQueryMsg::VerifyCreation { .. } => to_json_binary(&VerifyCreationResponse {
+                counterparty_chain_id: "testchain".to_owned(),
+                events: vec![],
+                storage_writes: Default::default(),
+                client_state_bytes: None,
+            }),
+            LightClientQueryMsg::Misbehaviour { .. } => to_json_binary(&MisbehaviourResponse {
+                client_state: vec![4, 5, 6].into(),
+            }),
+            LightClientQueryMsg::GetStatus { .. } => to_json_binary(&Status::Active),
+            LightClientQueryMsg::GetLatestHeight { .. } => to_json_binary(&1),
+            msg => panic!("should not be called: {:?}", msg),
+        }));
+
+    register_client(deps.as_mut()).expect("register client ok");
+    let res = create_client(deps.as_mut()).expect("create client ok");
+    let client_id = res
+        .events
+        .iter()
+        .find(|event| event.ty.eq(events::client::CREATE))
+        .expect("create client event exists")
+        .attributes
+        .iter()
+        .find(|attribute| attribute.key.eq(events::attribute::CLIENT_ID))
+        .expect("client type attribute exists")
+        .value
+        .parse::<ClientId>()
+        .expect("client type string is u32");
+
+    let msg = ExecuteMsg::SubmitMisbehaviour(MsgSubmitMisbehaviour {
+        client_id,
+        client_message: vec![1, 1, 1].into(),
+        relayer: mock_addr(RELAYER).into_string(),
+    });
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        message_info(&mock_addr(SENDER), &[]),
+        msg,
+    )
+    .expect("submit misbehaviour ok");
+
+    assert!(res.events.into_iter().any(|e| e
+        == Event::new(events::client::MISBEHAVIOUR)
+            .add_attribute(events::attribute::CLIENT_ID, client_id.to_string())));
+    assert_eq!(
+        deps.storage.read::<ClientStates>(&client_id).unwrap(),
+        vec![4, 5, 6]
+    );
+    // consensus states are left untouched
+    assert_eq!(
+        deps.storage
+            .read::<ClientConsensusStates>(&(client_id, 1))
+            .unwrap(),
+        vec![1, 2, 3]
+    );
+    // the message is only stored for the duration of the query
+    assert_eq!(deps.storage.maybe_read_item::<QueryStore>().unwrap(), None);
+}
+
+#[test]
+fn submit_misbehaviour_fails_when_client_not_active() {
+    let mut deps = mock_dependencies();
+
+    init(
+        deps.as_mut(),
+        InitMsg {
+            relayers_admin: None,
+            relayers: vec![mock_addr(SENDER).to_string()],
+        },
+    )
+    .unwrap();
+    deps.querier
+        .update_wasm(wasm_query_handler(|msg| match msg {
+            LightClientQueryMsg::VerifyCreation { .. } => to_json_binary(&VerifyCreationResponse {
+                counterparty_chain_id: "testchain".to_owned(),
+                events: vec![],
+                storage_writes: Default::default(),
+                client_state_bytes: None,
+            }),
+            LightClientQueryMsg::GetStatus { .. } => to_json_binary(&Status::Frozen),
+            LightClientQueryMsg::GetLatestHeight { .. } => to_json_binary(&1),
+            msg => panic!("should not be called: {:?}", msg),
+        }));
+
+    register_client(deps.as_mut()).expect("register client ok");
+    create_client(deps.as_mut()).expect("create client ok");
+
+    let msg = ExecuteMsg::SubmitMisbehaviour(MsgSubmitMisbehaviour {
+        client_id: ClientId!(1),
+        client_message: vec![1, 1, 1].into(),
+        relayer: mock_addr(RELAYER).into_string(),
+    });
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&mock_addr(SENDER), &[]),
+            msg,
+        ),
+        Err(ContractError::ClientNotActive {
+            client_id: ClientId!(1),
+            status: Status::Frozen,
+        })
+    );
+}
+
+#[test]
+fn submit_misbehaviour_fails_when_not_relayer() {
+    let mut deps = mock_dependencies();
+
+    init(
+        deps.as_mut(),
+        InitMsg {
+            relayers_admin: None,
+            relayers: vec![mock_addr(SENDER).to_string()],
+        },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::SubmitMisbehaviour(MsgSubmitMisbehaviour {
+        client_id: ClientId!(1),
+        client_message: vec![1, 1, 1].into(),
+        relayer: mock_addr(RELAYER).into_string(),
+    });
+    assert_eq!(
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&mock_addr("not_a_relayer"), &[]),
+            msg,
+        ),
+        Err(ContractError::OnlyWhitelistedRelayer)
+    );
+}
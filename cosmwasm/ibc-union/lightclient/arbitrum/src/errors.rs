@@ -31,8 +31,8 @@ pub enum Error {
     #[error("failed to verify arbitrum header: {0}")]
     HeaderVerify(#[from] arbitrum_verifier::Error),
 
-    #[error("the operation has not been implemented yet")]
-    Unimplemented,
+    #[error("failed to verify arbitrum misbehaviour: {0}")]
+    MisbehaviourVerify(#[source] arbitrum_verifier::Error),
 
     #[error(transparent)]
     EvmIbcClient(#[from] IbcClientError<EthereumLightClient>),
@@ -1,4 +1,4 @@
-use bob_light_client_types::{ClientState, ConsensusState, Header};
+use bob_light_client_types::{ClientState, ConsensusState, Header, Misbehaviour};
 use bob_verifier::FINALIZATION_PERIOD_SECONDS;
 use cosmwasm_std::{Addr, Empty};
 use ethereum_light_client::client::EthereumLightClient;
@@ -18,7 +18,7 @@ impl IbcClient for BobLightClient {
 
     type Header = Header;
 
-    type Misbehaviour = ();
+    type Misbehaviour = Misbehaviour;
 
     type ClientState = ClientState;
 
@@ -76,13 +76,19 @@ impl IbcClient for BobLightClient {
         client_state.chain_id.to_string()
     }
 
-    fn status(ctx: IbcClientCtx<Self>, client_state: &Self::ClientState) -> Status {
-        let _ = client_state;
+    fn status(
+        ctx: IbcClientCtx<Self>,
+        ClientState::V1(client_state): &Self::ClientState,
+    ) -> Status {
         let _ = ctx;
         // FIXME: expose the ctx to this call to allow threading this call to L1
         // client. generally, we want to thread if a client is an L2 so always
         // provide the ctx?
-        Status::Active
+        if client_state.frozen_height != 0 {
+            Status::Frozen
+        } else {
+            Status::Active
+        }
     }
 
     fn verify_creation(
@@ -137,11 +143,39 @@ impl IbcClient for BobLightClient {
     }
 
     fn misbehaviour(
-        _ctx: IbcClientCtx<Self>,
+        ctx: IbcClientCtx<Self>,
         _caller: Addr,
-        _misbehaviour: Self::Misbehaviour,
+        misbehaviour: Self::Misbehaviour,
         _relayer: Addr,
     ) -> Result<Self::ClientState, IbcClientError<Self>> {
-        Err(Error::Unimplemented.into())
+        let ClientState::V1(mut client_state) = ctx.read_self_client_state()?;
+
+        let l1_consensus_state_a = ctx
+            .read_consensus_state::<EthereumLightClient>(
+                client_state.l1_client_id,
+                misbehaviour.header_a.l1_height,
+            )
+            .map_err(Into::<Error>::into)?;
+        let l1_consensus_state_b = ctx
+            .read_consensus_state::<EthereumLightClient>(
+                client_state.l1_client_id,
+                misbehaviour.header_b.l1_height,
+            )
+            .map_err(Into::<Error>::into)?;
+
+        bob_verifier::verify_misbehaviour(
+            &client_state,
+            &misbehaviour.header_a,
+            l1_consensus_state_a.state_root,
+            &misbehaviour.header_b,
+            l1_consensus_state_b.state_root,
+            ctx.env.block.time.seconds(),
+            FINALIZATION_PERIOD_SECONDS,
+        )
+        .map_err(Into::<Error>::into)?;
+
+        client_state.frozen_height = 1;
+
+        Ok(ClientState::V1(client_state))
     }
 }
@@ -5,9 +5,6 @@ use crate::client::BobLightClient;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("unimplemented")]
-    Unimplemented,
-
     #[error(transparent)]
     Verify(#[from] bob_verifier::Error),
 
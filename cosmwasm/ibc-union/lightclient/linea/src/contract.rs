@@ -1,43 +1,38 @@
-use cosmwasm_std::{entry_point, DepsMut, Env, MessageInfo, Response};
-use ics008_wasm_client::{
-    define_cosmwasm_light_client_contract,
-    storage_utils::{save_proto_client_state, save_proto_consensus_state},
-    CustomQueryOf, InstantiateMsg,
+use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use frissitheto::UpgradeMsg;
+use ibc_union_light_client::{
+    msg::{InitMsg, QueryMsg},
+    IbcClientError,
 };
-use linea_light_client_types::ClientState;
-use protos::ibc::lightclients::wasm::v1::{
-    ClientState as ProtoClientState, ConsensusState as ProtoConsensusState,
-};
-use unionlabs::encoding::{DecodeAs, Proto};
 
-use crate::{client::LineaLightClient, errors::Error};
+use crate::client::LineaLightClient;
 
 #[entry_point]
-pub fn instantiate(
-    mut deps: DepsMut<CustomQueryOf<LineaLightClient>>,
-    _env: Env,
-    _info: MessageInfo,
-    msg: InstantiateMsg,
-) -> Result<Response, Error> {
-    let client_state =
-        ClientState::decode_as::<Proto>(&msg.client_state).map_err(Error::ClientStateDecode)?;
+pub fn instantiate(_: DepsMut, _: Env, _: MessageInfo, _: ()) -> StdResult<Response> {
+    panic!("this contract cannot be instantiated directly, but must be migrated from an existing instantiated contract.");
+}
 
-    save_proto_consensus_state::<LineaLightClient>(
-        deps.branch(),
-        ProtoConsensusState {
-            data: msg.consensus_state.into(),
-        },
-        &client_state.l1_latest_height,
-    );
-    save_proto_client_state::<LineaLightClient>(
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    ibc_union_light_client::query::<LineaLightClient>(deps, env, msg).map_err(Into::into)
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MigrateMsg {}
+
+#[entry_point]
+pub fn migrate(
+    deps: DepsMut,
+    _env: Env,
+    msg: UpgradeMsg<InitMsg, MigrateMsg>,
+) -> Result<Response, IbcClientError<LineaLightClient>> {
+    msg.run(
         deps,
-        ProtoClientState {
-            data: msg.client_state.into(),
-            checksum: msg.checksum.into(),
-            latest_height: Some(client_state.l1_latest_height.into()),
+        |deps, init_msg| {
+            let res = ibc_union_light_client::init(deps, init_msg)?;
+
+            Ok((res, None))
         },
-    );
-    Ok(Response::default())
+        |_deps, _migrate_msg, _current_version| Ok((Response::default(), None)),
+    )
 }
-
-define_cosmwasm_light_client_contract!(LineaLightClient, Linea);
@@ -1,79 +1,43 @@
-use ethereum_light_client::errors::{CanonicalizeStoredValueError, InvalidCommitmentKey};
-use ics008_wasm_client::IbcClientError;
-use linea_light_client_types::{ClientState, ConsensusState};
-use unionlabs::{
-    encoding::{DecodeErrorOf, Proto},
-    ibc::core::client::height::Height,
-    ics24::PathParseError,
-    linea::proof::InclusionProof,
-    primitives::H256,
-};
+use ethereum_light_client::client::EthereumLightClient;
+use ibc_union_light_client::IbcClientError;
+use unionlabs::primitives::H256;
 
 use crate::client::LineaLightClient;
 
-#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[derive(Debug, thiserror::Error)]
 pub enum Error {
-    #[error("unable to decode storage proof")]
-    InclusionProofDecode(#[source] DecodeErrorOf<Proto, InclusionProof>),
-
-    // #[error("unable to decode counterparty's stored cometbls client state")]
-    // CometblsClientStateDecode(
-    //     #[source] DecodeErrorOf<Proto, Any<cometbls::client_state::ClientState>>,
-    // ),
-    // #[error("unable to decode counterparty's stored cometbls consensus state")]
-    // CometblsConsensusStateDecode(
-    //     #[source]
-    //     DecodeErrorOf<
-    //         Proto,
-    //         Any<wasm::consensus_state::ConsensusState<cometbls::consensus_state::ConsensusState>>,
-    //     >,
-    // ),
-    #[error("unable to decode client state")]
-    ClientStateDecode(#[source] DecodeErrorOf<Proto, ClientState>),
-    #[error("unable to decode consensus state")]
-    ConsensusStateDecode(#[source] DecodeErrorOf<Proto, ConsensusState>),
-
     #[error(transparent)]
-    CanonicalizeStoredValue(#[from] CanonicalizeStoredValueError),
+    Verify(#[from] linea_verifier::Error),
 
-    // REVIEW: Move this variant to IbcClientError?
-    #[error("consensus state not found at height {0}")]
-    ConsensusStateNotFound(Height),
+    #[error("failed to verify linea membership proof")]
+    InvalidMembershipProof(#[from] linea_zktrie::verify::Error),
 
-    #[error("IBC path is empty")]
-    EmptyIbcPath,
+    #[error("membership can only be verified with an inclusion proof")]
+    ExpectedInclusionProof,
 
-    #[error(transparent)]
-    InvalidCommitmentKey(#[from] InvalidCommitmentKey),
+    #[error("non-membership can only be verified with a non-inclusion proof")]
+    ExpectedNonInclusionProof,
+
+    #[error("proof key must be at most 32 bytes but we got: {0:?}")]
+    InvalidProofKey(Vec<u8>),
 
-    #[error("proof is empty")]
-    EmptyProof,
+    #[error("commitment key must be 32 bytes but we got: {0:?}")]
+    InvalidCommitmentKeyLength(Vec<u8>),
 
-    #[error("batching proofs are not supported")]
-    BatchingProofsNotSupported,
+    #[error("commitment value must be 32 bytes but we got: {0:?}")]
+    InvalidCommitmentValueLength(Vec<u8>),
 
     #[error("expected value ({expected}) and stored value ({stored}) don't match")]
     StoredValueMismatch { expected: H256, stored: H256 },
 
-    #[error("unable to parse ics24 path")]
-    PathParse(#[from] PathParseError),
-
-    #[error("failed to verify linea header: {0}")]
-    Verify(#[from] linea_verifier::Error),
-
-    #[error("the operation has not been implemented yet")]
-    Unimplemented,
-
-    #[error("error while calling custom query: {0}")]
-    CustomQuery(#[from] unionlabs::cosmwasm::wasm::union::custom_query::Error),
-
-    #[error("L2 account proof must be an inclusion proof")]
-    InvalidL2AccountProof,
+    #[error(transparent)]
+    Evm(#[from] ethereum_light_client::errors::Error),
 
-    #[error("failed to verify linea membership proof {0}")]
-    InvalidMembershipProof(#[from] linea_zktrie::verify::Error),
+    #[error(transparent)]
+    EvmIbcClient(#[from] IbcClientError<EthereumLightClient>),
 }
 
+// required for IbcClient trait
 impl From<Error> for IbcClientError<LineaLightClient> {
     fn from(value: Error) -> Self {
         IbcClientError::ClientSpecific(value)
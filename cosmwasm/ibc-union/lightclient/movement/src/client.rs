@@ -63,12 +63,20 @@ impl IbcClient for MovementLightClient {
     }
 
     fn verify_non_membership(
-        _ctx: IbcClientCtx<Self>,
-        _height: u64,
-        _key: Vec<u8>,
-        _storage_proof: Self::StorageProof,
+        ctx: IbcClientCtx<Self>,
+        height: u64,
+        key: Vec<u8>,
+        storage_proof: Self::StorageProof,
     ) -> Result<(), IbcClientError<Self>> {
-        unimplemented!()
+        let client_state = ctx.read_self_client_state()?;
+        let consensus_state = ctx.read_self_consensus_state(height)?;
+        verify_non_membership(
+            &key,
+            consensus_state.state_root,
+            client_state.table_handle,
+            storage_proof,
+        )
+        .map_err(Into::into)
     }
 
     fn get_timestamp(consensus_state: &Self::ConsensusState) -> Timestamp {
@@ -256,6 +264,26 @@ pub fn verify_membership(
     )?)
 }
 
+pub fn verify_non_membership(
+    path: &[u8],
+    state_root: H256,
+    table_handle: AccountAddress,
+    proof: StorageProof,
+) -> Result<(), Error> {
+    if proof.state_value.is_some() {
+        return Err(Error::NonMembershipProofWithValue);
+    }
+
+    let key =
+        aptos_verifier::hash_table_key(&bcs::to_bytes(path).expect("cannot fail"), &table_handle);
+
+    Ok(aptos_verifier::verify_non_membership(
+        proof.proof,
+        state_root.into(),
+        key,
+    )?)
+}
+
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(rename = "StateValue")]
 enum PersistedStateValue {
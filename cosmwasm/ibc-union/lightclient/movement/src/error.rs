@@ -16,6 +16,8 @@ pub enum Error {
     ConsensusStateNotFound(Height),
     #[error("membership proof with no value")]
     MembershipProofWithoutValue,
+    #[error("non-membership proof with a value")]
+    NonMembershipProofWithValue,
     #[error("proof value {proof_value} doesn't match the given value {given})", proof_value = serde_utils::to_hex(.0), given = serde_utils::to_hex(.1))]
     ProofValueMismatch(Vec<u8>, Vec<u8>),
     #[error("proof value hash doesn't match the calculated one")]
@@ -1,11 +1,13 @@
-use cosmwasm_std::Empty;
+use cosmwasm_std::{Addr, Empty};
 use ethereum_light_client::client::{check_commitment_key, EthereumLightClient};
 use ethereum_light_client_types::StorageProof;
-use ibc_union_light_client::{IbcClient, IbcClientCtx, IbcClientError};
+use ibc_union_light_client::{
+    spec::Timestamp, ClientCreationResult, IbcClient, IbcClientCtx, IbcClientError, StateUpdate,
+};
 use ibc_union_msg::lightclient::Status;
 use scroll_codec::batch_header::BatchHeaderV3;
-use scroll_light_client_types::{ClientState, ConsensusState, Header};
-use unionlabs::{encoding::Proto, primitives::H256};
+use scroll_light_client_types::{ClientState, ConsensusState, Header, Misbehaviour};
+use unionlabs::{encoding::Bincode, ibc::core::client::height::Height, primitives::H256};
 
 use crate::errors::Error;
 
@@ -18,7 +20,7 @@ impl IbcClient for ScrollLightClient {
 
     type Header = Header;
 
-    type Misbehaviour = Header;
+    type Misbehaviour = Misbehaviour;
 
     type ClientState = ClientState;
 
@@ -26,7 +28,7 @@ impl IbcClient for ScrollLightClient {
 
     type StorageProof = StorageProof;
 
-    type Encoding = Proto;
+    type Encoding = Bincode;
 
     fn verify_membership(
         ctx: IbcClientCtx<Self>,
@@ -74,6 +76,7 @@ impl IbcClient for ScrollLightClient {
         storage_proof: Self::StorageProof,
     ) -> Result<(), IbcClientError<Self>> {
         let consensus_state = ctx.read_self_consensus_state(height)?;
+
         check_commitment_key(
             H256::try_from(&key).map_err(|_| Error::InvalidCommitmentKeyLength(key))?,
             storage_proof.key,
@@ -82,12 +85,12 @@ impl IbcClient for ScrollLightClient {
 
         scroll_verifier::verify_zktrie_storage_absence(
             consensus_state.ibc_storage_root,
-            H256::new(storage_proof.key.to_be_bytes()),
+            storage_proof.key.to_be_bytes().into(),
             &storage_proof.proof,
         )
         .map_err(Into::<Error>::into)?;
 
-        todo!()
+        Ok(())
     }
 
     fn get_timestamp(consensus_state: &Self::ConsensusState) -> Timestamp {
@@ -95,10 +98,14 @@ impl IbcClient for ScrollLightClient {
     }
 
     fn get_latest_height(client_state: &Self::ClientState) -> u64 {
-        client_state.latest_slot
+        client_state.latest_height
+    }
+
+    fn get_counterparty_chain_id(client_state: &Self::ClientState) -> String {
+        client_state.chain_id.to_string()
     }
 
-    fn status(client_state: &Self::ClientState) -> Status {
+    fn status(_ctx: IbcClientCtx<Self>, client_state: &Self::ClientState) -> Status {
         if client_state.frozen_height.height() == 0 {
             Status::Active
         } else {
@@ -107,364 +114,214 @@ impl IbcClient for ScrollLightClient {
     }
 
     fn verify_creation(
-        _client_state: &ClientState,
-        _consensus_state: &ConsensusState,
-    ) -> Result<(), IbcClientError<Self>> {
-        Ok(())
+        _caller: Addr,
+        _client_state: &Self::ClientState,
+        _consensus_state: &Self::ConsensusState,
+        _relayer: Addr,
+    ) -> Result<ClientCreationResult<Self>, IbcClientError<Self>> {
+        Ok(ClientCreationResult::new())
     }
 
     fn verify_header(
         ctx: IbcClientCtx<Self>,
-        header: Header,
-    ) -> Result<(u64, Self::ClientState, Self::ConsensusState), IbcClientError<Self>> {
+        _caller: Addr,
+        header: Self::Header,
+        _relayer: Addr,
+    ) -> Result<StateUpdate<Self>, IbcClientError<Self>> {
+        let client_state = ctx.read_self_client_state()?;
+
+        let l1_consensus_state = ctx
+            .read_consensus_state::<EthereumLightClient>(
+                client_state.l1_client_id,
+                header.l1_height.height(),
+            )
+            .map_err(Into::<Error>::into)?;
+
+        // the height is the number of the last L2 block in the batch, which is proven against the
+        // data hash of the batch header
+        let update_height =
+            scroll_verifier::verify_header(&client_state, &header, l1_consensus_state.state_root)
+                .map_err(Into::<Error>::into)?;
+
+        Ok(state_update(client_state, &header, update_height)?)
+    }
+
+    fn misbehaviour(
+        ctx: IbcClientCtx<Self>,
+        _caller: Addr,
+        misbehaviour: Self::Misbehaviour,
+        _relayer: Addr,
+    ) -> Result<Self::ClientState, IbcClientError<Self>> {
         let mut client_state = ctx.read_self_client_state()?;
-        verify_header(&ctx, &client_state, &header)?;
 
-        let batch_header =
-            BatchHeaderV3::decode(&header.batch_header).map_err(Error::BatchHeaderDecode)?;
+        let l1_consensus_state_a = ctx
+            .read_consensus_state::<EthereumLightClient>(
+                client_state.l1_client_id,
+                misbehaviour.header_a.l1_height.height(),
+            )
+            .map_err(Into::<Error>::into)?;
+        let l1_consensus_state_b = ctx
+            .read_consensus_state::<EthereumLightClient>(
+                client_state.l1_client_id,
+                misbehaviour.header_b.l1_height.height(),
+            )
+            .map_err(Into::<Error>::into)?;
+
+        scroll_verifier::verify_misbehaviour(
+            &client_state,
+            &misbehaviour.header_a,
+            l1_consensus_state_a.state_root,
+            &misbehaviour.header_b,
+            l1_consensus_state_b.state_root,
+        )
+        .map_err(Into::<Error>::into)?;
+
+        client_state.frozen_height = Height::new(1);
 
-        let timestamp = batch_header.last_block_timestamp;
+        Ok(client_state)
+    }
+}
 
-        let updated_height = header.l1_height.height();
+/// Build the state update for a verified header, which is stored at `update_height`.
+fn state_update(
+    mut client_state: ClientState,
+    header: &Header,
+    update_height: u64,
+) -> Result<StateUpdate<ScrollLightClient>, Error> {
+    let batch_header = BatchHeaderV3::decode(&header.batch_header)?;
+
+    let consensus_state = ConsensusState {
+        state_root: header.l2_state_root_proof.value.to_be_bytes().into(),
+        timestamp: Timestamp::from_secs(batch_header.last_block_timestamp),
+        ibc_storage_root: header.l2_ibc_account_proof.storage_root,
+    };
+
+    let state_update = StateUpdate::new(update_height, consensus_state);
+
+    if client_state.latest_height < update_height {
+        client_state.latest_height = update_height;
+        Ok(state_update.overwrite_client_state(client_state))
+    } else {
+        Ok(state_update)
+    }
+}
 
-        if client_state.latest_slot < updated_height {
-            client_state.latest_slot = updated_height;
+#[cfg(test)]
+mod tests {
+    use ethereum_light_client_types::AccountProof;
+    use ibc_union_light_client::spec::ClientId;
+    use scroll_light_client_types::ChunkProof;
+    use unionlabs::primitives::{H160, U256};
+
+    use super::*;
+
+    const LAST_BLOCK_TIMESTAMP: u64 = 1_724_000_000;
+
+    fn client_state(latest_height: u64) -> ClientState {
+        ClientState {
+            chain_id: 534_352,
+            latest_height,
+            l1_client_id: ClientId::from_raw(1).unwrap(),
+            frozen_height: Height::new(0),
+            ibc_contract_address: H160::default(),
+            l2_committed_batches_slot: U256::from(157_u64),
+            l2_contract_address: H160::default(),
+            l2_finalized_state_roots_slot: U256::from(158_u64),
+            latest_batch_index_slot: U256::from(156_u64),
         }
+    }
 
-        let consensus_state = ConsensusState {
-            state_root: header.l2_state_root_proof.value.to_be_bytes().into(),
-            // must be nanos
-            timestamp: 1_000_000_000 * timestamp,
-            ibc_storage_root: header.l2_ibc_account_proof.storage_root,
+    fn storage_proof(value: U256) -> StorageProof {
+        StorageProof {
+            key: U256::from(0_u64),
+            value,
+            proof: vec![],
+        }
+    }
+
+    fn header() -> Header {
+        let batch_header = BatchHeaderV3 {
+            version: 3,
+            batch_index: 10,
+            l1_message_popped: 0,
+            total_l1_message_popped: 0,
+            data_hash: H256::default(),
+            blob_versioned_hash: H256::default(),
+            parent_batch_hash: H256::default(),
+            last_block_timestamp: LAST_BLOCK_TIMESTAMP,
+            blob_data_proof: Default::default(),
         };
 
-        Ok((updated_height, client_state, consensus_state))
+        let mut encoded_batch_header = vec![batch_header.version];
+        encoded_batch_header.extend(batch_header.batch_index.to_be_bytes());
+        encoded_batch_header.extend(batch_header.l1_message_popped.to_be_bytes());
+        encoded_batch_header.extend(batch_header.total_l1_message_popped.to_be_bytes());
+        encoded_batch_header.extend(batch_header.data_hash.get());
+        encoded_batch_header.extend(batch_header.blob_versioned_hash.get());
+        encoded_batch_header.extend(batch_header.parent_batch_hash.get());
+        encoded_batch_header.extend(batch_header.last_block_timestamp.to_be_bytes());
+        encoded_batch_header.extend(batch_header.blob_data_proof.get());
+
+        Header {
+            l1_height: Height::new(100),
+            l1_account_proof: AccountProof {
+                storage_root: H256::default(),
+                proof: vec![],
+            },
+            l2_state_root_proof: storage_proof(U256::from(0xAA_u64)),
+            last_batch_index_proof: storage_proof(U256::from(10_u64)),
+            batch_hash_proof: storage_proof(U256::from(0_u64)),
+            l2_ibc_account_proof: AccountProof {
+                storage_root: H256::new([0xBB; 32]),
+                proof: vec![],
+            },
+            batch_header: encoded_batch_header,
+            last_chunk_proof: ChunkProof {
+                previous_chunk_hashes: vec![],
+                block_contexts: vec![],
+                l1_message_hashes: vec![],
+            },
+        }
     }
 
-    fn misbehaviour(
-        _ctx: IbcClientCtx<Self>,
-        _misbehaviour: Self::Misbehaviour,
-    ) -> Result<Self::ClientState, IbcClientError<Self>> {
-        todo!()
+    #[test]
+    fn consensus_state_is_built_from_proven_values() {
+        let state_update = state_update(client_state(5), &header(), 20).unwrap();
+
+        assert_eq!(state_update.height, 20);
+        assert_eq!(
+            state_update.consensus_state,
+            ConsensusState {
+                state_root: U256::from(0xAA_u64).to_be_bytes().into(),
+                timestamp: Timestamp::from_secs(LAST_BLOCK_TIMESTAMP),
+                ibc_storage_root: H256::new([0xBB; 32]),
+            }
+        );
     }
-}
 
-pub fn verify_header(
-    ctx: &IbcClientCtx<ScrollLightClient>,
-    client_state: &ClientState,
-    header: &Header,
-) -> Result<(), Error> {
-    let l1_consensus_state = ctx.read_consensus_state::<EthereumLightClient>(
-        client_state.l1_client_id,
-        header.l1_height.height(),
-    )?;
-    scroll_verifier::verify_header(client_state, header, l1_consensus_state.state_root)?;
-    Ok(())
-}
+    #[test]
+    fn newer_height_updates_latest_height() {
+        let state_update = state_update(client_state(5), &header(), 20).unwrap();
+
+        assert_eq!(state_update.client_state, Some(client_state(20)));
+    }
 
-// #[cfg(test)]
-// mod test {
-//     use cosmwasm_std::{
-//         testing::{MockApi, MockQuerier, MockStorage},
-//         DepsMut, OwnedDeps,
-//     };
-//     use ics008_wasm_client::{
-//         storage_utils::{
-//             consensus_db_key, read_subject_client_state, HOST_CLIENT_STATE_KEY,
-//             SUBJECT_CLIENT_STORE_PREFIX, SUBSTITUTE_CLIENT_STORE_PREFIX,
-//         },
-//         IbcClient,
-//     };
-//     use unionlabs::{
-//         cosmwasm::wasm::union::custom_query::UnionCustomQuery,
-//         encoding::{EncodeAs, Proto},
-//         google::protobuf::any::Any,
-//         primitives::{H160, H256},
-//         ibc::core::client::height::Height,
-//         primitives::U256,
-//     };
-
-//     use super::{
-//         ClientState, ConsensusState, ScrollLightClient, WasmClientState, WasmConsensusState,
-//     };
-//     use crate::errors::Error;
-
-//     const INITIAL_CONSENSUS_STATE_HEIGHT: Height = Height {
-//         revision_number: 0,
-//         revision_height: 950,
-//     };
-
-//     const INITIAL_SUBSTITUTE_CONSENSUS_STATE_HEIGHT: Height = Height {
-//         revision_number: 0,
-//         revision_height: 970,
-//     };
-
-//     #[allow(clippy::type_complexity)]
-//     fn mock_dependencies(
-//     ) -> OwnedDeps<MockStorage, MockApi, MockQuerier<UnionCustomQuery>, UnionCustomQuery> {
-//         OwnedDeps::<_, _, _, UnionCustomQuery> {
-//             storage: MockStorage::default(),
-//             api: MockApi::default(),
-//             querier: MockQuerier::<UnionCustomQuery>::new(&[]),
-//             custom_query_type: std::marker::PhantomData,
-//         }
-//     }
-
-//     fn create_client_state(
-//         l1_client_id: String,
-//         chain_id: U256,
-//         latest_slot: u64,
-//         height: Height,
-//         frozen_height: Height,
-//     ) -> WasmClientState {
-//         WasmClientState {
-//             data: ClientState {
-//                 l1_client_id,
-//                 chain_id,
-//                 latest_slot,
-//                 frozen_height,
-//                 latest_batch_index_slot: U256::from(10),
-//                 l2_contract_address: H160::default(),
-//                 l2_finalized_state_roots_slot: U256::from(10),
-//                 l2_committed_batches_slot: U256::from(10),
-//                 ibc_contract_address: H160::default(),
-//             },
-//             latest_height: height,
-//             checksum: H256::default(),
-//         }
-//     }
-
-//     fn save_states_to_migrate_store(
-//         deps: DepsMut<UnionCustomQuery>,
-//         subject_client_state: &WasmClientState,
-//         substitute_client_state: &WasmClientState,
-//         subject_consensus_state: &WasmConsensusState,
-//         substitute_consensus_state: &WasmConsensusState,
-//     ) {
-//         deps.storage.set(
-//             format!("{SUBJECT_CLIENT_STORE_PREFIX}{HOST_CLIENT_STATE_KEY}").as_bytes(),
-//             &Any(subject_client_state.clone()).encode_as::<Proto>(),
-//         );
-//         deps.storage.set(
-//             format!(
-//                 "{SUBJECT_CLIENT_STORE_PREFIX}{}",
-//                 consensus_db_key(&INITIAL_CONSENSUS_STATE_HEIGHT)
-//             )
-//             .as_bytes(),
-//             &Any(subject_consensus_state.clone()).encode_as::<Proto>(),
-//         );
-//         deps.storage.set(
-//             format!("{SUBSTITUTE_CLIENT_STORE_PREFIX}{HOST_CLIENT_STATE_KEY}").as_bytes(),
-//             &Any(substitute_client_state.clone()).encode_as::<Proto>(),
-//         );
-//         deps.storage.set(
-//             format!(
-//                 "{SUBSTITUTE_CLIENT_STORE_PREFIX}{}",
-//                 consensus_db_key(&INITIAL_SUBSTITUTE_CONSENSUS_STATE_HEIGHT)
-//             )
-//             .as_bytes(),
-//             &Any(substitute_consensus_state.clone()).encode_as::<Proto>(),
-//         );
-//     }
-
-//     #[allow(clippy::type_complexity)]
-//     fn prepare_migrate_tests() -> (
-//         OwnedDeps<MockStorage, MockApi, MockQuerier<UnionCustomQuery>, UnionCustomQuery>,
-//         WasmClientState,
-//         WasmConsensusState,
-//         WasmClientState,
-//         WasmConsensusState,
-//     ) {
-//         let deps = mock_dependencies();
-
-//         let subject_client_state = create_client_state(
-//             "client_1".to_string(),
-//             U256::from(1),
-//             INITIAL_CONSENSUS_STATE_HEIGHT.revision_height,
-//             INITIAL_CONSENSUS_STATE_HEIGHT,
-//             Height::default(),
-//         );
-//         let substitute_client_state = create_client_state(
-//             "client_1".to_string(),
-//             U256::from(1),
-//             INITIAL_SUBSTITUTE_CONSENSUS_STATE_HEIGHT.revision_height,
-//             INITIAL_SUBSTITUTE_CONSENSUS_STATE_HEIGHT,
-//             Height::default(),
-//         );
-
-//         let subject_consensus_state = WasmConsensusState {
-//             data: ConsensusState {
-//                 state_root: H256::default(),
-//                 timestamp: 1000,
-//                 ibc_storage_root: H256::default(),
-//             },
-//         };
-//         let substitute_consensus_state = WasmConsensusState {
-//             data: ConsensusState {
-//                 state_root: H256::default(),
-//                 timestamp: 2000,
-//                 ibc_storage_root: H256::default(),
-//             },
-//         };
-
-//         (
-//             deps,
-//             subject_client_state,
-//             subject_consensus_state,
-//             substitute_client_state,
-//             substitute_consensus_state,
-//         )
-//     }
-
-//     #[test]
-//     fn migrate_client_store_succeeds_with_valid_data() {
-//         let (
-//             mut deps,
-//             mut subject_client_state,
-//             subject_consensus_state,
-//             mut substitute_client_state,
-//             substitute_consensus_state,
-//         ) = prepare_migrate_tests();
-
-//         subject_client_state.data.frozen_height = Height {
-//             revision_number: 0,
-//             revision_height: 1000,
-//         };
-
-//         substitute_client_state.data.frozen_height = Height::default();
-
-//         save_states_to_migrate_store(
-//             deps.as_mut(),
-//             &subject_client_state,
-//             &substitute_client_state,
-//             &subject_consensus_state,
-//             &substitute_consensus_state,
-//         );
-
-//         let original_subject_client_state: WasmClientState =
-//             read_subject_client_state::<ScrollLightClient>(deps.as_ref()).unwrap();
-
-//         assert_eq!(
-//             original_subject_client_state.data.frozen_height,
-//             Height {
-//                 revision_number: 0,
-//                 revision_height: 1000,
-//             }
-//         );
-
-//         // Perform migration
-//         let result = ScrollLightClient::migrate_client_store(deps.as_mut());
-
-//         // Assert success, print error if any
-//         if let Err(ref e) = result {
-//             println!("Migration failed with error: {:?}", e);
-//         }
-//         // Assert success
-//         assert!(result.is_ok());
-//         let updated_subject_client_state: WasmClientState =
-//             read_subject_client_state::<ScrollLightClient>(deps.as_ref()).unwrap();
-//         assert_eq!(
-//             updated_subject_client_state.data.frozen_height,
-//             Height::default()
-//         );
-
-//         assert_eq!(
-//             updated_subject_client_state.latest_height,
-//             substitute_client_state.latest_height
-//         );
-//     }
-//     #[test]
-//     fn migrate_client_store_fails_when_substitute_client_frozen() {
-//         let (
-//             mut deps,
-//             subject_client_state,
-//             subject_consensus_state,
-//             mut substitute_client_state,
-//             substitute_consensus_state,
-//         ) = prepare_migrate_tests();
-
-//         // Make the substitute client frozen
-//         substitute_client_state.data.frozen_height = Height {
-//             revision_number: 0,
-//             revision_height: 100,
-//         };
-
-//         // Save the states into the mock storage
-//         save_states_to_migrate_store(
-//             deps.as_mut(),
-//             &subject_client_state,
-//             &substitute_client_state,
-//             &subject_consensus_state,
-//             &substitute_consensus_state,
-//         );
-
-//         // Perform migration
-//         let result = ScrollLightClient::migrate_client_store(deps.as_mut());
-
-//         // Assert failure
-//         assert_eq!(result, Err(Error::SubstituteClientFrozen.into()));
-//     }
-
-//     #[test]
-//     fn migrate_client_store_fails_when_fields_differ() {
-//         let (
-//             mut deps,
-//             subject_client_state,
-//             subject_consensus_state,
-//             mut substitute_client_state,
-//             substitute_consensus_state,
-//         ) = prepare_migrate_tests();
-
-//         // Alter the chain_id in the substitute client state
-//         substitute_client_state.data.chain_id = U256::from(999);
-
-//         // Save the states into the mock storage
-//         save_states_to_migrate_store(
-//             deps.as_mut(),
-//             &subject_client_state,
-//             &substitute_client_state,
-//             &subject_consensus_state,
-//             &substitute_consensus_state,
-//         );
-
-//         // Perform migration
-//         let result = ScrollLightClient::migrate_client_store(deps.as_mut());
-
-//         // Assert failure
-//         assert_eq!(result, Err(Error::MigrateFieldsChanged.into()));
-//     }
-//     #[test]
-//     fn migrate_client_store_fails_when_substitute_consensus_not_found() {
-//         let (
-//             mut deps,
-//             subject_client_state,
-//             subject_consensus_state,
-//             mut substitute_client_state,
-//             _substitute_consensus_state, // we won't save this to storage
-//         ) = prepare_migrate_tests();
-
-//         // modify latest height to a height where the consensus state is not found
-//         substitute_client_state.latest_height = Height {
-//             revision_number: 0,
-//             revision_height: 15,
-//         };
-
-//         // Save only the client states and subject consensus state (skip substitute consensus state)
-//         save_states_to_migrate_store(
-//             deps.as_mut(),
-//             &subject_client_state,
-//             &substitute_client_state,
-//             &subject_consensus_state,
-//             &subject_consensus_state, // Reusing subject consensus intentionally
-//         );
-
-//         // Perform migration
-//         let result = ScrollLightClient::migrate_client_store(deps.as_mut());
-
-//         // Assert failure
-//         assert_eq!(
-//             result,
-//             Err(Error::ConsensusStateNotFound(substitute_client_state.latest_height).into())
-//         );
-//     }
-// }
+    #[test]
+    fn older_height_does_not_update_client_state() {
+        let state_update = state_update(client_state(30), &header(), 20).unwrap();
+
+        assert_eq!(state_update.height, 20);
+        assert_eq!(state_update.client_state, None);
+    }
+
+    #[test]
+    fn invalid_batch_header_is_rejected() {
+        let mut header = header();
+        header.batch_header.pop();
+
+        assert!(matches!(
+            state_update(client_state(5), &header, 20),
+            Err(Error::BatchHeaderDecode(_))
+        ));
+    }
+}
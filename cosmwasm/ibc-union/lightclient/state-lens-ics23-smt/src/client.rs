@@ -57,12 +57,20 @@ impl IbcClient for StateLensIcs23SmtLightClient {
     }
 
     fn verify_non_membership(
-        _ctx: IbcClientCtx<Self>,
-        _height: u64,
-        _key: Vec<u8>,
-        _storage_proof: Self::StorageProof,
+        ctx: IbcClientCtx<Self>,
+        height: u64,
+        key: Vec<u8>,
+        storage_proof: Self::StorageProof,
     ) -> Result<(), IbcClientError<Self>> {
-        unimplemented!()
+        let client_state = ctx.read_self_client_state()?;
+        let consensus_state = ctx.read_self_consensus_state(height)?;
+        verify_non_membership(
+            &key,
+            consensus_state.state_root,
+            AccountAddress(client_state.extra.table_handle),
+            storage_proof,
+        )
+        .map_err(Into::into)
     }
 
     fn get_timestamp(consensus_state: &Self::ConsensusState) -> Timestamp {
@@ -214,6 +222,26 @@ pub fn verify_membership(
     )?)
 }
 
+pub fn verify_non_membership(
+    path: &[u8],
+    state_root: H256,
+    table_handle: AccountAddress,
+    proof: StorageProof,
+) -> Result<(), Error> {
+    if proof.state_value.is_some() {
+        return Err(Error::NonMembershipProofWithValue);
+    }
+
+    let key =
+        aptos_verifier::hash_table_key(&bcs::to_bytes(path).expect("cannot fail"), &table_handle);
+
+    Ok(aptos_verifier::verify_non_membership(
+        proof.proof,
+        state_root.into(),
+        key,
+    )?)
+}
+
 pub fn check_commitment_key(path: H256, key: U256) -> Result<(), Error> {
     let expected_commitment_key = ibc_commitment_key(path);
 
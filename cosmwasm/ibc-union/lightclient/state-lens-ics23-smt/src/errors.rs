@@ -52,6 +52,9 @@ pub enum Error {
     #[error("membership proof without a value")]
     MembershipProofWithoutValue,
 
+    #[error("non-membership proof with a value")]
+    NonMembershipProofWithValue,
+
     #[error("proof value {proof_value} doesn't match the given value {given})", proof_value = serde_utils::to_hex(.0), given = serde_utils::to_hex(.1))]
     ProofValueMismatch(Vec<u8>, Vec<u8>),
 
@@ -6,7 +6,7 @@ use ibc_union_light_client::{
 use ibc_union_msg::lightclient::Status;
 use sui_light_client_types::{
     client_state::ClientState, committee::Committee, consensus_state::ConsensusState,
-    header::Header, storage_proof::StorageProof, U64,
+    header::Header, misbehaviour::Misbehaviour, storage_proof::StorageProof, U64,
 };
 use unionlabs::encoding::{Bincode, DecodeAs, EncodeAs};
 
@@ -21,7 +21,7 @@ impl IbcClient for SuiLightClient {
 
     type Header = Header;
 
-    type Misbehaviour = Header;
+    type Misbehaviour = Misbehaviour;
 
     type ClientState = ClientState;
 
@@ -81,9 +81,15 @@ impl IbcClient for SuiLightClient {
 
     fn status(
         _ctx: ibc_union_light_client::IbcClientCtx<Self>,
-        _client_state: &Self::ClientState,
+        client_state: &Self::ClientState,
     ) -> Status {
-        Status::Active
+        let ClientState::V1(client_state) = client_state;
+
+        if client_state.frozen_height != 0 {
+            Status::Frozen
+        } else {
+            Status::Active
+        }
     }
 
     fn verify_creation(
@@ -154,12 +160,30 @@ impl IbcClient for SuiLightClient {
     }
 
     fn misbehaviour(
-        _ctx: ibc_union_light_client::IbcClientCtx<Self>,
+        ctx: ibc_union_light_client::IbcClientCtx<Self>,
         _caller: cosmwasm_std::Addr,
-        _misbehaviour: Self::Misbehaviour,
+        misbehaviour: Self::Misbehaviour,
         _relayer: cosmwasm_std::Addr,
     ) -> Result<Self::ClientState, ibc_union_light_client::IbcClientError<Self>> {
-        todo!()
+        let ClientState::V1(mut client_state) = ctx.read_self_client_state()?;
+
+        let committee_a =
+            ctx.read_self_storage::<CommitteeStore>(misbehaviour.checkpoint_a.data.epoch)?;
+        let committee_b =
+            ctx.read_self_storage::<CommitteeStore>(misbehaviour.checkpoint_b.data.epoch)?;
+
+        sui_verifier::verify_checkpoint_equivocation(
+            &committee_a,
+            &misbehaviour.checkpoint_a,
+            &committee_b,
+            &misbehaviour.checkpoint_b,
+            &Verifier { deps: ctx.deps },
+        )
+        .map_err(Into::<Error>::into)?;
+
+        client_state.frozen_height = 1;
+
+        Ok(ClientState::V1(client_state))
     }
 }
 
@@ -200,3 +224,47 @@ impl ValueCodec<Committee> for CommitteeStore {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env},
+        Addr,
+    };
+    use ibc_union_light_client::IbcClientCtx;
+    use sui_light_client_types::client_state::ClientStateV1;
+    use unionlabs::primitives::FixedBytes;
+
+    use super::*;
+
+    fn client_state(frozen_height: u64) -> ClientState {
+        ClientState::V1(ClientStateV1 {
+            chain_id: "sui-devnet".to_owned(),
+            latest_checkpoint: 10,
+            frozen_height,
+            ibc_commitments_object_id: FixedBytes::new([0xAA; 32]),
+            initial_committee: None,
+        })
+    }
+
+    #[test]
+    fn status() {
+        let deps = mock_dependencies();
+
+        let ctx = || IbcClientCtx::<SuiLightClient> {
+            client_id: 1.try_into().unwrap(),
+            ibc_host: Addr::unchecked("ibc-host"),
+            deps: deps.as_ref(),
+            env: mock_env(),
+        };
+
+        assert_eq!(
+            SuiLightClient::status(ctx(), &client_state(0)),
+            Status::Active
+        );
+        assert_eq!(
+            SuiLightClient::status(ctx(), &client_state(1)),
+            Status::Frozen
+        );
+    }
+}
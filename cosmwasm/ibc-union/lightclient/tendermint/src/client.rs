@@ -9,7 +9,7 @@ use ibc_union_light_client::{
 use ibc_union_msg::lightclient::Status;
 use ibc_union_spec::path::IBC_UNION_COSMWASM_COMMITMENT_PREFIX;
 use ics23::ibc_api::SDK_SPECS;
-use tendermint_light_client_types::{ClientState, ConsensusState, Header};
+use tendermint_light_client_types::{ClientState, ConsensusState, Header, Misbehaviour};
 use tendermint_verifier::types::{HostFns, SignatureVerifier};
 use unionlabs::{
     bounded::BoundedI64,
@@ -39,8 +39,7 @@ impl IbcClient for TendermintLightClient {
 
     type Header = Header;
 
-    // TODO(aeryz): Change this to appropriate misbehavior type when it is implemented
-    type Misbehaviour = Header;
+    type Misbehaviour = Misbehaviour;
 
     type ClientState = ClientState;
 
@@ -119,12 +118,47 @@ impl IbcClient for TendermintLightClient {
     }
 
     fn misbehaviour(
-        _ctx: IbcClientCtx<Self>,
+        ctx: IbcClientCtx<Self>,
         _caller: Addr,
-        _misbehaviour: Self::Misbehaviour,
+        misbehaviour: Self::Misbehaviour,
         _relayer: Addr,
     ) -> Result<Self::ClientState, IbcClientError<Self>> {
-        Err(Error::Unimplemented.into())
+        let mut client_state = ctx.read_self_client_state()?;
+        let consensus_state_a =
+            ctx.read_self_consensus_state(misbehaviour.header_a.trusted_height.height())?;
+        let consensus_state_b =
+            ctx.read_self_consensus_state(misbehaviour.header_b.trusted_height.height())?;
+
+        match misbehaviour
+            .header_a
+            .validator_set
+            .validators
+            .first()
+            .map(|v| &v.pub_key)
+        {
+            #[cfg(feature = "bls")]
+            Some(PublicKey::Bls12_381(_)) => verify_misbehaviour(
+                &client_state,
+                &consensus_state_a,
+                &consensus_state_b,
+                misbehaviour,
+                ctx.env.block.time,
+                &SignatureVerifier::new(crate::verifier::bls::Bls12Verifier::new(ctx.deps)),
+            )?,
+            Some(PublicKey::Ed25519(_)) => verify_misbehaviour(
+                &client_state,
+                &consensus_state_a,
+                &consensus_state_b,
+                misbehaviour,
+                ctx.env.block.time,
+                &SignatureVerifier::new(Ed25519Verifier::new(ctx.deps)),
+            )?,
+            _ => return Err(Error::InvalidValidatorSet.into()),
+        }
+
+        client_state.frozen_height = Some(Height::new(1));
+
+        Ok(client_state)
     }
 
     fn status(ctx: IbcClientCtx<Self>, client_state: &Self::ClientState) -> Status {
@@ -277,6 +311,179 @@ pub fn verify_header<V: HostFns>(
     }
 }
 
+/// Verify that `misbehaviour` proves that the counterparty chain has misbehaved, as per ICS-07.
+///
+/// Both headers are verified independently against the trusted consensus state at their respective
+/// trusted heights, after which they must either be two different blocks at the same height
+/// (equivocation), or `header_a` must be higher than `header_b` without having a more recent
+/// timestamp (BFT time violation).
+pub fn verify_misbehaviour<V: HostFns>(
+    client_state: &ClientState,
+    consensus_state_a: &ConsensusState,
+    consensus_state_b: &ConsensusState,
+    mut misbehaviour: Misbehaviour,
+    block_timestamp: cosmwasm_std::Timestamp,
+    signature_verifier: &SignatureVerifier<V>,
+) -> Result<(), Error> {
+    let header_a = &misbehaviour.header_a.signed_header;
+    let header_b = &misbehaviour.header_b.signed_header;
+
+    if header_a.header.height < header_b.header.height {
+        return Err(Error::InvalidMisbehaviourHeaderSequence);
+    }
+
+    verify_misbehaviour_header(
+        client_state,
+        consensus_state_a,
+        &mut misbehaviour.header_a,
+        block_timestamp,
+        signature_verifier,
+    )?;
+    verify_misbehaviour_header(
+        client_state,
+        consensus_state_b,
+        &mut misbehaviour.header_b,
+        block_timestamp,
+        signature_verifier,
+    )?;
+
+    let header_a = &misbehaviour.header_a.signed_header;
+    let header_b = &misbehaviour.header_b.signed_header;
+
+    let is_misbehaviour = if header_a.header.height == header_b.header.height {
+        // the block ids are verified to commit to the headers, so different block ids mean that two
+        // different blocks were committed at the same height
+        header_a.commit.block_id.hash != header_b.commit.block_id.hash
+    } else {
+        // header_a is higher than header_b, and as such must have a strictly greater timestamp
+        header_a.header.time <= header_b.header.time
+    };
+
+    if is_misbehaviour {
+        Ok(())
+    } else {
+        Err(Error::MisbehaviourNotFound)
+    }
+}
+
+/// Verify a single header of a misbehaviour against the trusted consensus state.
+///
+/// Unlike [`verify_header`], the header is not required to be more recent than the trusted
+/// consensus state or to be within the max clock drift, since a misbehaving chain can produce
+/// headers with arbitrary timestamps. It is sufficient that the header is signed by a trusted
+/// portion of the trusted validators, and that the trusted consensus state is not expired.
+fn verify_misbehaviour_header<V: HostFns>(
+    client_state: &ClientState,
+    consensus_state: &ConsensusState,
+    header: &mut Header,
+    block_timestamp: cosmwasm_std::Timestamp,
+    signature_verifier: &SignatureVerifier<V>,
+) -> Result<(), Error> {
+    set_total_voting_power(&mut header.validator_set).map_err(Error::from)?;
+    set_total_voting_power(&mut header.trusted_validators).map_err(Error::from)?;
+
+    check_trusted_header(header, consensus_state.next_validators_hash.as_encoding())
+        .map_err(Error::from)?;
+
+    let signed_header = &header.signed_header;
+
+    if signed_header.header.chain_id != client_state.chain_id {
+        return Err(InvalidHeaderError::ChainIdMismatch {
+            header_chain_id: signed_header.header.chain_id.clone(),
+            client_chain_id: client_state.chain_id.clone(),
+        }
+        .into());
+    }
+
+    let revision_number = parse_revision_number(&signed_header.header.chain_id).ok_or(
+        Error::from(InvalidChainId(signed_header.header.chain_id.clone())),
+    )?;
+
+    if revision_number != header.trusted_height.revision() {
+        return Err(Error::from(RevisionNumberMismatch {
+            trusted_revision_number: revision_number,
+            header_revision_number: header.trusted_height.revision(),
+        }));
+    }
+
+    let signed_height = signed_header
+        .header
+        .height
+        .inner()
+        .try_into()
+        .expect("value is bounded >= 0; qed;");
+
+    if signed_height <= header.trusted_height.height() {
+        return Err(InvalidHeaderError::SignedHeaderHeightMustBeMoreRecent {
+            signed_height,
+            trusted_height: header.trusted_height.height(),
+        }
+        .into());
+    }
+
+    // FIXME: unionlabs is tied to cosmwasm <2, the TryFrom impl can't be used
+    let block_timestamp_proto = unionlabs::google::protobuf::timestamp::Timestamp {
+        seconds: i64::try_from(block_timestamp.seconds())
+            .expect("impossible")
+            .try_into()
+            .expect("impossible"),
+        nanos: i32::try_from(block_timestamp.subsec_nanos())
+            .expect("impossible")
+            .try_into()
+            .expect("impossible"),
+    };
+
+    if is_client_expired(
+        &consensus_state.timestamp,
+        client_state.trusting_period,
+        block_timestamp_proto,
+    ) {
+        return Err(
+            InvalidHeaderError::HeaderExpired(consensus_state.timestamp.as_unix_nanos()).into(),
+        );
+    }
+
+    if signed_header.commit.height != signed_header.header.height {
+        return Err(InvalidHeaderError::CommitHeightMismatch {
+            signed_height: signed_header.header.height.inner(),
+            commit_height: signed_header.commit.height.inner(),
+        }
+        .into());
+    }
+
+    let header_hash = signed_header.header.calculate_merkle_root();
+    let commit_hash = signed_header
+        .commit
+        .block_id
+        .hash
+        .map(|hash| hash.into_encoding());
+    if header_hash.is_none() || header_hash != commit_hash {
+        return Err(InvalidHeaderError::CommitBlockIdMismatch {
+            header_hash,
+            commit_hash,
+        }
+        .into());
+    }
+
+    let validators_hash = tendermint_verifier::utils::validators_hash(&header.validator_set);
+    if validators_hash != signed_header.header.validators_hash.into_encoding() {
+        return Err(InvalidHeaderError::ValidatorsHashMismatch {
+            expected: signed_header.header.validators_hash.into_encoding(),
+            actual: validators_hash,
+        }
+        .into());
+    }
+
+    tendermint_verifier::verify::verify_commit_light_trusting(
+        &client_state.chain_id,
+        &header.trusted_validators,
+        &signed_header.commit,
+        &client_state.trust_level,
+        signature_verifier,
+    )
+    .map_err(Error::TendermintVerify)
+}
+
 pub fn set_total_voting_power(validator_set: &mut ValidatorSet) -> Result<(), MathOverflow> {
     validator_set.total_voting_power =
         validator_set
@@ -414,6 +621,299 @@ pub fn verify_non_membership(
     .map_err(Error::VerifyMembership)
 }
 
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU64;
+
+    use cometbft_types::{
+        types::{
+            block_id::BlockId, commit_sig::CommitSig, part_set_header::PartSetHeader,
+            validator::Validator,
+        },
+        version::consensus::Consensus,
+    };
+    use cosmwasm_std::testing::mock_dependencies;
+    use ed25519_dalek::{Signer, SigningKey};
+    use tendermint_light_client_types::Fraction;
+    use tendermint_verifier::utils::{canonical_vote, validators_hash};
+    use unionlabs::primitives::H160;
+
+    use super::*;
+
+    const CHAIN_ID: &str = "union-devnet-1";
+
+    const TRUSTED_HEIGHT: u64 = 10;
+
+    const TRUSTED_TIMESTAMP: i64 = 1_700_000_000;
+
+    fn signing_keys(seed: u8) -> Vec<SigningKey> {
+        (0..4)
+            .map(|i| SigningKey::from_bytes(&[seed + i; 32]))
+            .collect()
+    }
+
+    fn address(key: &SigningKey) -> H160 {
+        H160::new(key.verifying_key().to_bytes()[..20].try_into().unwrap())
+    }
+
+    fn validator_set(keys: &[SigningKey]) -> ValidatorSet {
+        let validators = keys
+            .iter()
+            .map(|key| Validator {
+                address: address(key).into_encoding(),
+                pub_key: PublicKey::Ed25519(key.verifying_key().to_bytes().to_vec().into()),
+                voting_power: 10.try_into().unwrap(),
+                proposer_priority: 0,
+            })
+            .collect::<Vec<_>>();
+
+        ValidatorSet {
+            proposer: validators[0].clone(),
+            validators,
+            total_voting_power: 0,
+        }
+    }
+
+    fn timestamp(seconds: i64) -> Timestamp {
+        Timestamp {
+            seconds: seconds.try_into().unwrap(),
+            nanos: 0.try_into().unwrap(),
+        }
+    }
+
+    /// Build a header at `height` with the given time and app hash, signed by all of `keys`.
+    fn mk_header(keys: &[SigningKey], height: i64, time: i64, app_hash: u8) -> Header {
+        let validators_hash = validators_hash(&validator_set(keys)).into_encoding();
+
+        let header = cometbft_types::types::header::Header {
+            version: Consensus { block: 11, app: 0 },
+            chain_id: CHAIN_ID.to_owned(),
+            height: height.try_into().unwrap(),
+            time: timestamp(time),
+            last_block_id: BlockId {
+                hash: Some(H256::new([0xAA; 32])),
+                part_set_header: PartSetHeader {
+                    total: 1,
+                    hash: Some(H256::new([0xAA; 32])),
+                },
+            },
+            last_commit_hash: H256::new([0xAA; 32]),
+            data_hash: H256::new([0xAA; 32]),
+            validators_hash,
+            next_validators_hash: validators_hash,
+            consensus_hash: H256::new([0xAA; 32]),
+            app_hash: H256::new([app_hash; 32]),
+            last_results_hash: H256::new([0xAA; 32]),
+            evidence_hash: H256::new([0xAA; 32]),
+            proposer_address: address(&keys[0]).into_encoding(),
+        };
+
+        let mut commit = Commit {
+            height: header.height,
+            round: 0.try_into().unwrap(),
+            block_id: BlockId {
+                hash: Some(header.calculate_merkle_root().unwrap().into_encoding()),
+                part_set_header: PartSetHeader {
+                    total: 1,
+                    hash: Some(H256::new([0xBB; 32])),
+                },
+            },
+            signatures: vec![],
+        };
+
+        commit.signatures = keys
+            .iter()
+            .map(|key| {
+                let unsigned = CommitSig::Commit {
+                    validator_address: address(key),
+                    timestamp: header.time,
+                    signature: Vec::new().into(),
+                };
+
+                let vote = canonical_vote(&commit, &unsigned, &header.time, CHAIN_ID).unwrap();
+
+                CommitSig::Commit {
+                    validator_address: address(key),
+                    timestamp: header.time,
+                    signature: key.sign(&vote).to_bytes().to_vec().into(),
+                }
+            })
+            .collect();
+
+        Header {
+            signed_header: SignedHeader { header, commit },
+            validator_set: validator_set(keys),
+            trusted_height: Height::new_with_revision(1, TRUSTED_HEIGHT),
+            trusted_validators: validator_set(&signing_keys(1)),
+        }
+    }
+
+    fn client_state() -> ClientState {
+        ClientState {
+            chain_id: CHAIN_ID.to_owned(),
+            trust_level: Fraction {
+                numerator: 1,
+                denominator: NonZeroU64::new(3).unwrap(),
+            },
+            trusting_period: Duration::new(86_400, 0).unwrap(),
+            unbonding_period: Duration::new(3 * 86_400, 0).unwrap(),
+            max_clock_drift: Duration::new(10, 0).unwrap(),
+            frozen_height: None,
+            latest_height: Height::new_with_revision(1, TRUSTED_HEIGHT),
+            proof_specs: vec![],
+            upgrade_path: vec![],
+            contract_address: H256::default(),
+        }
+    }
+
+    fn consensus_state() -> ConsensusState {
+        ConsensusState {
+            timestamp: timestamp(TRUSTED_TIMESTAMP),
+            root: MerkleRoot {
+                hash: H256::new([0xAA; 32]),
+            },
+            next_validators_hash: validators_hash(&validator_set(&signing_keys(1))).into_encoding(),
+        }
+    }
+
+    fn check(misbehaviour: Misbehaviour, block_timestamp: i64) -> Result<(), Error> {
+        let deps = mock_dependencies();
+
+        verify_misbehaviour(
+            &client_state(),
+            &consensus_state(),
+            &consensus_state(),
+            misbehaviour,
+            cosmwasm_std::Timestamp::from_seconds(block_timestamp.try_into().unwrap()),
+            &SignatureVerifier::new(Ed25519Verifier::new(deps.as_ref())),
+        )
+    }
+
+    #[test]
+    fn duplicate_header_is_misbehaviour() {
+        let keys = signing_keys(1);
+
+        let misbehaviour = Misbehaviour {
+            header_a: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x01),
+            header_b: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x02),
+        };
+
+        assert_eq!(check(misbehaviour, TRUSTED_TIMESTAMP + 100), Ok(()));
+    }
+
+    #[test]
+    fn time_violation_is_misbehaviour() {
+        let keys = signing_keys(1);
+
+        let misbehaviour = Misbehaviour {
+            header_a: mk_header(&keys, 20, TRUSTED_TIMESTAMP + 50, 0x01),
+            header_b: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 60, 0x01),
+        };
+
+        assert_eq!(check(misbehaviour, TRUSTED_TIMESTAMP + 100), Ok(()));
+
+        // equal timestamps at different heights are also a violation
+        let misbehaviour = Misbehaviour {
+            header_a: mk_header(&keys, 20, TRUSTED_TIMESTAMP + 50, 0x01),
+            header_b: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x01),
+        };
+
+        assert_eq!(check(misbehaviour, TRUSTED_TIMESTAMP + 100), Ok(()));
+    }
+
+    #[test]
+    fn valid_headers_are_not_misbehaviour() {
+        let keys = signing_keys(1);
+
+        let misbehaviour = Misbehaviour {
+            header_a: mk_header(&keys, 20, TRUSTED_TIMESTAMP + 60, 0x01),
+            header_b: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x01),
+        };
+
+        assert_eq!(
+            check(misbehaviour, TRUSTED_TIMESTAMP + 100),
+            Err(Error::MisbehaviourNotFound)
+        );
+
+        let header = mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x01);
+
+        let misbehaviour = Misbehaviour {
+            header_a: header.clone(),
+            header_b: header,
+        };
+
+        assert_eq!(
+            check(misbehaviour, TRUSTED_TIMESTAMP + 100),
+            Err(Error::MisbehaviourNotFound)
+        );
+    }
+
+    #[test]
+    fn headers_must_be_ordered_by_height() {
+        let keys = signing_keys(1);
+
+        let misbehaviour = Misbehaviour {
+            header_a: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 60, 0x01),
+            header_b: mk_header(&keys, 20, TRUSTED_TIMESTAMP + 50, 0x01),
+        };
+
+        assert_eq!(
+            check(misbehaviour, TRUSTED_TIMESTAMP + 100),
+            Err(Error::InvalidMisbehaviourHeaderSequence)
+        );
+    }
+
+    #[test]
+    fn headers_must_be_signed_by_trusted_validators() {
+        let misbehaviour = Misbehaviour {
+            header_a: mk_header(&signing_keys(1), 15, TRUSTED_TIMESTAMP + 50, 0x01),
+            header_b: mk_header(&signing_keys(100), 15, TRUSTED_TIMESTAMP + 50, 0x02),
+        };
+
+        assert!(matches!(
+            check(misbehaviour, TRUSTED_TIMESTAMP + 100),
+            Err(Error::TendermintVerify(
+                tendermint_verifier::error::Error::NotEnoughVotingPower { have: 0, .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn tampered_header_is_rejected() {
+        let keys = signing_keys(1);
+
+        let mut header_b = mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x01);
+        header_b.signed_header.header.app_hash = H256::new([0x02; 32]);
+
+        let misbehaviour = Misbehaviour {
+            header_a: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x01),
+            header_b,
+        };
+
+        assert!(matches!(
+            check(misbehaviour, TRUSTED_TIMESTAMP + 100),
+            Err(Error::InvalidHeader(
+                InvalidHeaderError::CommitBlockIdMismatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn trusted_consensus_state_must_not_be_expired() {
+        let keys = signing_keys(1);
+
+        let misbehaviour = Misbehaviour {
+            header_a: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x01),
+            header_b: mk_header(&keys, 15, TRUSTED_TIMESTAMP + 50, 0x02),
+        };
+
+        assert!(matches!(
+            check(misbehaviour, TRUSTED_TIMESTAMP + 86_401),
+            Err(Error::InvalidHeader(InvalidHeaderError::HeaderExpired(_)))
+        ));
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use std::fs;
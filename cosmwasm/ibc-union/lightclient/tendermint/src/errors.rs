@@ -60,6 +60,12 @@ pub enum Error {
 
     #[error("invalid or empty validator set, supported keys are: bls12381 and ed25519")]
     InvalidValidatorSet,
+
+    #[error("header_a.height should be greater than or equal to header_b.height")]
+    InvalidMisbehaviourHeaderSequence,
+
+    #[error("given headers don't prove a misbehaviour")]
+    MisbehaviourNotFound,
 }
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
@@ -83,6 +89,23 @@ pub enum InvalidHeaderError {
         signed_timestamp: u64,
         max_clock_drift: u64,
     },
+    #[error("signed header's chain id ({header_chain_id}) does not match the client's chain id ({client_chain_id})")]
+    ChainIdMismatch {
+        header_chain_id: String,
+        client_chain_id: String,
+    },
+    #[error("signed header's height ({signed_height}) does not match the commit's height ({commit_height})")]
+    CommitHeightMismatch {
+        signed_height: i64,
+        commit_height: i64,
+    },
+    #[error("signed header's hash ({header_hash:?}) does not match the commit's block id ({commit_hash:?})")]
+    CommitBlockIdMismatch {
+        header_hash: Option<H256>,
+        commit_hash: Option<H256>,
+    },
+    #[error("header validator set hash ({actual}) does not match the signed header's validators hash ({expected})")]
+    ValidatorsHashMismatch { expected: H256, actual: H256 },
 }
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
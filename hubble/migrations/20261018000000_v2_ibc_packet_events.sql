@@ -0,0 +1,44 @@
+-- Typed columns for the packet lifecycle events (packet_send, packet_recv, intent_packet_recv,
+-- write_ack, packet_ack, packet_timeout) in v2_ibc.events. Rows are removed together with the
+-- event they belong to.
+
+CREATE TABLE v2_ibc.packet_events (
+    internal_chain_id INTEGER NOT NULL,
+    height BIGINT NOT NULL,
+    event_index INTEGER NOT NULL,
+    event_type TEXT NOT NULL,
+    packet_hash BYTEA NOT NULL,
+    source_channel_id BIGINT NOT NULL,
+    destination_channel_id BIGINT NOT NULL,
+    timeout_height NUMERIC NOT NULL,
+    timeout_timestamp NUMERIC NOT NULL,
+    maker_msg BYTEA,
+    acknowledgement BYTEA,
+    PRIMARY KEY (internal_chain_id, height, event_index),
+    FOREIGN KEY (internal_chain_id, height, event_index)
+        REFERENCES v2_ibc.events (internal_chain_id, height, event_index)
+        ON DELETE CASCADE
+);
+
+CREATE INDEX ON v2_ibc.packet_events (packet_hash);
+CREATE INDEX ON v2_ibc.packet_events (event_type);
+CREATE INDEX ON v2_ibc.packet_events (internal_chain_id, source_channel_id);
+CREATE INDEX ON v2_ibc.packet_events (internal_chain_id, destination_channel_id);
+
+-- Backfill the packet events that were indexed before this table existed.
+INSERT INTO v2_ibc.packet_events (internal_chain_id, height, event_index, event_type, packet_hash, source_channel_id, destination_channel_id, timeout_height, timeout_timestamp, maker_msg, acknowledgement)
+SELECT
+    e.internal_chain_id,
+    e.height,
+    e.event_index,
+    e.event_type,
+    e.packet_hash,
+    p.source_channel_id,
+    p.destination_channel_id,
+    p.timeout_height,
+    p.timeout_timestamp,
+    decode(substr(coalesce(e.data->'@value'->>'maker_msg', e.data->'@value'->>'market_maker_msg'), 3), 'hex'),
+    decode(substr(e.data->'@value'->>'acknowledgement', 3), 'hex')
+FROM v2_ibc.events e
+JOIN v2_ibc.packets p ON p.packet_hash = e.packet_hash
+WHERE e.event_type IN ('packet_send', 'packet_recv', 'intent_packet_recv', 'write_ack', 'packet_ack', 'packet_timeout');
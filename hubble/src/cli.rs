@@ -117,6 +117,10 @@ pub enum IndexerConfig {
     Ethereum(indexer::ethereum::config::Config),
     #[serde(rename = "tendermint")]
     Tendermint(indexer::tendermint::config::Config),
+    #[serde(rename = "sui")]
+    Sui(indexer::sui::config::Config),
+    #[serde(rename = "aptos")]
+    Aptos(indexer::aptos::config::Config),
 }
 
 impl IndexerConfig {
@@ -125,6 +129,8 @@ impl IndexerConfig {
             Self::Dummy(cfg) => &cfg.indexer_id,
             Self::Ethereum(cfg) => &cfg.indexer_id,
             Self::Tendermint(cfg) => &cfg.indexer_id,
+            Self::Sui(cfg) => &cfg.indexer_id,
+            Self::Aptos(cfg) => &cfg.indexer_id,
         }
     }
 }
@@ -165,6 +171,22 @@ impl IndexerConfig {
                     .instrument(indexer_span)
                     .await
             }
+            Self::Sui(cfg) => {
+                cfg.build(db, nats)
+                    .instrument(initializer_span)
+                    .await?
+                    .index()
+                    .instrument(indexer_span)
+                    .await
+            }
+            Self::Aptos(cfg) => {
+                cfg.build(db, nats)
+                    .instrument(initializer_span)
+                    .await?
+                    .index()
+                    .instrument(indexer_span)
+                    .await
+            }
         }
     }
 }
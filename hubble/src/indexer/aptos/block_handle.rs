@@ -0,0 +1,23 @@
+use color_eyre::eyre::Report;
+use time::OffsetDateTime;
+
+use crate::indexer::{
+    api::{BlockReference, BlockReferenceProvider, IndexerError},
+    aptos::{fetcher_client::AptosFetcherClient, provider::Block},
+    move_chain::MoveBlockHandle,
+};
+
+impl BlockReferenceProvider for Block {
+    fn block_reference(&self) -> Result<BlockReference, Report> {
+        Ok(BlockReference {
+            height: self.block_height,
+            hash: self.block_hash.clone(),
+            timestamp: OffsetDateTime::from_unix_timestamp_nanos(
+                i128::from(self.block_timestamp) * 1_000,
+            )
+            .map_err(|err| IndexerError::ProviderError(err.into()))?,
+        })
+    }
+}
+
+pub type AptosBlockHandle = MoveBlockHandle<AptosFetcherClient>;
@@ -0,0 +1,56 @@
+use color_eyre::eyre::Report;
+use sqlx::PgPool;
+use url::Url;
+
+use crate::indexer::{
+    api::{BlockHeight, IndexerId, UniversalChainId},
+    aptos::fetcher_client::AptosFetcherClient,
+    move_chain::MoveContext,
+    nats::NatsConnection,
+    ConsumerConfig, FinalizerConfig, FixerConfig, Indexer, PublisherConfig,
+};
+
+const DEFAULT_CHUNK_SIZE: usize = 20;
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Config {
+    pub indexer_id: IndexerId,
+    pub universal_chain_id: UniversalChainId,
+    /// The block height to start indexing from.
+    pub start_height: BlockHeight,
+    pub chunk_size: Option<usize>,
+    /// The urls of the REST API of the nodes, including the `/v1` path.
+    pub rpc_urls: Vec<Url>,
+    #[serde(default)]
+    pub finalizer: FinalizerConfig,
+    #[serde(default)]
+    pub fixer: FixerConfig,
+    #[serde(default)]
+    pub publisher: PublisherConfig,
+    #[serde(default)]
+    pub consumer: ConsumerConfig,
+}
+
+impl Config {
+    pub async fn build(
+        self,
+        pg_pool: PgPool,
+        nats: Option<NatsConnection>,
+    ) -> Result<Indexer<AptosFetcherClient>, Report> {
+        Ok(Indexer::new(
+            pg_pool,
+            nats,
+            self.indexer_id,
+            self.universal_chain_id,
+            self.start_height,
+            self.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
+            self.finalizer,
+            self.fixer,
+            self.publisher,
+            self.consumer,
+            MoveContext {
+                rpc_urls: self.rpc_urls,
+            },
+        ))
+    }
+}
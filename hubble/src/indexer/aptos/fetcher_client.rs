@@ -0,0 +1,245 @@
+use std::fmt::Display;
+
+use axum::async_trait;
+use color_eyre::eyre::eyre;
+use tokio::task::JoinSet;
+use tracing::{debug, info, info_span, trace, Instrument};
+
+use crate::{
+    indexer::{
+        api::{
+            BlockHeight, BlockReferenceProvider, BlockSelection, FetchMode, FetcherClient,
+            IndexerError,
+        },
+        aptos::{
+            block_handle::AptosBlockHandle,
+            postgres::active_contracts,
+            provider::{Block, Provider, RpcProviderId},
+        },
+        event::BlockEvent,
+        move_chain::{
+            filter_events, BlockDetails, BlockInsert, EventInsert, MoveClient, MoveContext,
+        },
+    },
+    postgres::{fetch_chain_id_tx, ChainId},
+};
+
+#[derive(Clone)]
+pub struct AptosFetcherClient {
+    pub chain_id: ChainId,
+    pub provider: Provider,
+    pub event_filter: EventFilter,
+}
+
+#[derive(Clone, Debug)]
+pub struct EventFilter {
+    pub chain_id: ChainId,
+    pub pg_pool: sqlx::PgPool,
+}
+
+impl EventFilter {
+    pub(crate) async fn addresses_at(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Vec<String>, IndexerError> {
+        Ok(active_contracts(&mut self.pg_pool.begin().await?, self.chain_id.db, height).await?)
+    }
+}
+
+impl Display for AptosFetcherClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "chain_id: {}", self.chain_id)
+    }
+}
+
+#[async_trait]
+impl MoveClient for AptosFetcherClient {
+    type Block = Block;
+    type ProviderId = RpcProviderId;
+    type TransactionPosition = u64;
+
+    async fn fetch_single_with_provider(
+        &self,
+        selection: BlockSelection,
+        mode: FetchMode,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<AptosBlockHandle, IndexerError> {
+        // blocks are final once they are committed, so the latest block is also the last
+        // finalized block. the block is fetched from the same provider, which could otherwise be
+        // behind.
+        let (height, provider_id) = match selection {
+            BlockSelection::LastFinalized => {
+                let ledger_info = self.provider.ledger_info(provider_id).await?;
+
+                (
+                    ledger_info.response.block_height,
+                    Some(ledger_info.provider_id),
+                )
+            }
+            BlockSelection::Height(height) => (height, provider_id),
+        };
+
+        match self.provider.block_by_height(height, provider_id).await {
+            Ok(Some(result)) => {
+                let block = result.response;
+                debug!(
+                    "{}: fetched (provider index: {:?})",
+                    selection, result.provider_id
+                );
+
+                Ok(AptosBlockHandle {
+                    reference: block.block_reference()?,
+                    details: match mode {
+                        FetchMode::Lazy => BlockDetails::Lazy(block),
+                        FetchMode::Eager => BlockDetails::Eager(
+                            self.fetch_details(&block, result.provider_id).await?,
+                        ),
+                    },
+                    client: self.clone(),
+                    provider_id: result.provider_id,
+                })
+            }
+            Ok(None) => {
+                info!("{}: does not exist", selection);
+
+                Err(IndexerError::NoBlock(selection))
+            }
+            Err(err) => {
+                info!("{}: error: {}", selection, err);
+
+                Err(err.into())
+            }
+        }
+    }
+
+    async fn fetch_details(
+        &self,
+        block: &Block,
+        provider_id: RpcProviderId,
+    ) -> Result<Option<BlockInsert<u64>>, IndexerError> {
+        let block_reference = block.block_reference()?;
+
+        info!(
+            "{}: fetch (versions: {}..={})",
+            block_reference, block.first_version, block.last_version
+        );
+
+        let addresses = self
+            .event_filter
+            .addresses_at(block_reference.height)
+            .await?;
+        debug!("{}: addresses: {:?}", block_reference, &addresses);
+
+        // no need to fetch the transactions if there is nothing to index.
+        if addresses.is_empty() {
+            info!("{}: ignored (no addresses)", block_reference);
+            return Ok(None);
+        }
+
+        let transactions = self.provider.block_transactions(block, provider_id).await?;
+
+        let expected = block.last_version - block.first_version + 1;
+        if transactions.len() as u64 != expected {
+            return Err(IndexerError::ProviderError(eyre!(
+                "{}: expected {} transactions (versions: {}..={}), got {}",
+                block_reference,
+                expected,
+                block.first_version,
+                block.last_version,
+                transactions.len()
+            )));
+        }
+
+        let events = filter_events(
+            transactions.into_iter().flat_map(|transaction| {
+                let transaction_hash = transaction.hash;
+                let version = transaction.version;
+
+                transaction
+                    .events
+                    .into_iter()
+                    .map(move |data| (transaction_hash.clone(), version, data))
+            }),
+            &addresses,
+        );
+
+        trace!("{}: fetch => events: {}", block_reference, events.len());
+
+        if events.is_empty() {
+            info!("{}: fetch => ignored (no events)", block_reference);
+            return Ok(None);
+        }
+
+        debug!(
+            "{}: fetch => done (events: {})",
+            block_reference,
+            events.len()
+        );
+
+        Ok(Some(BlockInsert {
+            chain_id: self.chain_id,
+            hash: block_reference.hash,
+            height: block_reference.height,
+            time: block_reference.timestamp,
+            events,
+        }))
+    }
+
+    fn block_event(block: &BlockInsert<u64>, event: EventInsert<u64>) -> BlockEvent {
+        BlockEvent::AptosEvent {
+            internal_chain_id: block.chain_id.db,
+            block_hash: block.hash.clone(),
+            height: block.height,
+            transaction_hash: event.transaction_hash,
+            version: event.transaction_position,
+            index: event.index,
+            data: event.data,
+            time: block.time,
+        }
+    }
+}
+
+#[async_trait]
+impl FetcherClient for AptosFetcherClient {
+    type BlockHandle = AptosBlockHandle;
+    type Context = MoveContext;
+
+    async fn create(
+        pg_pool: sqlx::PgPool,
+        _join_set: &mut JoinSet<Result<(), IndexerError>>,
+        context: MoveContext,
+    ) -> Result<Self, IndexerError> {
+        let provider = Provider::new(context.rpc_urls);
+
+        info!("fetching chain-id from node");
+        let chain_id = provider.ledger_info(None).await?.response.chain_id;
+        info!("fetched chain-id from node: {}", chain_id);
+
+        let indexing_span = info_span!("indexer", chain_id = chain_id);
+        async move {
+            let mut tx = pg_pool.begin().await?;
+
+            let chain_id = fetch_chain_id_tx(&mut tx, chain_id.to_string()).await?;
+
+            tx.commit().await?;
+
+            let event_filter = EventFilter { chain_id, pg_pool };
+
+            Ok(AptosFetcherClient {
+                chain_id,
+                provider,
+                event_filter,
+            })
+        }
+        .instrument(indexing_span)
+        .await
+    }
+
+    async fn fetch_single(
+        &self,
+        selection: BlockSelection,
+        mode: FetchMode,
+    ) -> Result<Self::BlockHandle, IndexerError> {
+        self.fetch_single_with_provider(selection, mode, None).await
+    }
+}
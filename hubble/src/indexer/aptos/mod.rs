@@ -0,0 +1,15 @@
+use color_eyre::eyre::Report;
+
+use crate::indexer::api::IndexerError;
+
+mod block_handle;
+pub mod config;
+mod fetcher_client;
+mod postgres;
+mod provider;
+
+impl From<reqwest::Error> for IndexerError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::ProviderError(Report::from(error))
+    }
+}
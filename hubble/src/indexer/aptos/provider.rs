@@ -0,0 +1,193 @@
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use url::Url;
+
+use crate::{
+    indexer::api::BlockHeight,
+    race_client::{RaceClient, RaceClientId, RaceClientResponse},
+};
+
+/// The maximum number of transactions that can be fetched in a single `/transactions` request.
+const MAX_TRANSACTIONS_PER_REQUEST: u64 = 100;
+
+#[derive(Clone, Debug)]
+pub struct Provider {
+    pub rpc_client: RaceClient<RestClient>,
+}
+
+#[derive(Clone, Debug, Copy)]
+pub struct RpcProviderId {
+    race_client_id: RaceClientId,
+}
+
+impl From<RpcProviderId> for RaceClientId {
+    fn from(value: RpcProviderId) -> Self {
+        value.race_client_id
+    }
+}
+
+#[derive(Debug)]
+pub struct RpcResult<T> {
+    pub provider_id: RpcProviderId,
+    pub response: T,
+}
+
+impl<T> RpcResult<T> {
+    fn new(race_client_id: RaceClientId, result: T) -> Self {
+        Self {
+            provider_id: RpcProviderId { race_client_id },
+            response: result,
+        }
+    }
+}
+
+impl<T> From<RaceClientResponse<T>> for RpcResult<T> {
+    fn from(value: RaceClientResponse<T>) -> Self {
+        RpcResult::new(value.race_client_id, value.response)
+    }
+}
+
+/// A client for the REST API of an aptos (or movement) node.
+#[derive(Clone, Debug)]
+pub struct RestClient {
+    client: reqwest::Client,
+    url: Url,
+}
+
+impl RestClient {
+    fn new(url: Url) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, reqwest::Error> {
+        self.client
+            .get(self.endpoint(path))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+
+    /// Like [`RestClient::get`], but returns `None` if the resource does not exist (yet).
+    async fn get_optional<T: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<Option<T>, reqwest::Error> {
+        let response = self.client.get(self.endpoint(path)).send().await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        response.error_for_status()?.json().await.map(Some)
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{}", self.url.as_str().trim_end_matches('/'), path)
+    }
+}
+
+/// The fields of the ledger info (as returned by `/`) that are used by the indexer.
+#[derive(Clone, Debug, Deserialize)]
+pub struct LedgerInfo {
+    pub chain_id: u8,
+    #[serde(with = "serde_utils::string")]
+    pub block_height: BlockHeight,
+}
+
+/// The fields of a block (as returned by `/blocks/by_height/{height}`) that are used by the
+/// indexer. A block contains the transactions with versions `first_version..=last_version`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Block {
+    #[serde(with = "serde_utils::string")]
+    pub block_height: BlockHeight,
+    pub block_hash: String,
+    /// The timestamp of the block, in microseconds.
+    #[serde(with = "serde_utils::string")]
+    pub block_timestamp: u64,
+    #[serde(with = "serde_utils::string")]
+    pub first_version: u64,
+    #[serde(with = "serde_utils::string")]
+    pub last_version: u64,
+}
+
+/// A committed transaction (as returned by `/transactions`), only including its events.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Transaction {
+    #[serde(with = "serde_utils::string")]
+    pub version: u64,
+    pub hash: String,
+    #[serde(default)]
+    pub events: Vec<Value>,
+}
+
+impl Provider {
+    pub fn new(rpc_urls: Vec<Url>) -> Self {
+        Self {
+            rpc_client: RaceClient::new(rpc_urls.into_iter().map(RestClient::new).collect()),
+        }
+    }
+
+    pub async fn ledger_info(
+        &self,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<LedgerInfo>, reqwest::Error> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| c.get::<LedgerInfo>(""))
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn block_by_height(
+        &self,
+        height: BlockHeight,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<Option<RpcResult<Block>>, reqwest::Error> {
+        let path = format!("blocks/by_height/{height}?with_transactions=false");
+
+        self.rpc_client
+            .race_some(provider_id.map(Into::into), |c| {
+                c.get_optional::<Block>(&path)
+            })
+            .await
+            .map(|op| op.map(Into::into))
+    }
+
+    /// Fetch the transactions of the block, in the order of their version.
+    pub async fn block_transactions(
+        &self,
+        block: &Block,
+        provider_id: RpcProviderId,
+    ) -> Result<Vec<Transaction>, reqwest::Error> {
+        let mut transactions = Vec::new();
+        let mut start = block.first_version;
+
+        while start <= block.last_version {
+            let limit = (block.last_version - start + 1).min(MAX_TRANSACTIONS_PER_REQUEST);
+            let path = format!("transactions?start={start}&limit={limit}");
+
+            let response = self
+                .rpc_client
+                .race(Some(provider_id.into()), |c| {
+                    c.get::<Vec<Transaction>>(&path)
+                })
+                .await?
+                .response;
+
+            // the node can return fewer transactions than requested
+            match response.last() {
+                Some(last) => start = last.version + 1,
+                None => break,
+            }
+
+            transactions.extend(response);
+        }
+
+        Ok(transactions)
+    }
+}
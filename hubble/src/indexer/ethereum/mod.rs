@@ -3,7 +3,7 @@ use color_eyre::eyre::Report;
 
 use crate::indexer::api::IndexerError;
 
-mod block_handle;
+pub(crate) mod block_handle;
 pub mod config;
 mod context;
 mod fetcher_client;
@@ -242,8 +242,8 @@ impl BlockEvents {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "type")]
 pub enum BlockEvent {
-    // using database representation of fields. all these 'record representations' will
-    // be replaced by USC events
+    // using database representation of fields. ibc union events are decoded from these
+    // 'record representations' into typed events when they are stored (see `indexer::ibc`)
     #[serde(rename = "ethereum-log")]
     EthereumLog {
         internal_chain_id: i32,
@@ -288,6 +288,33 @@ pub enum BlockEvent {
         time: OffsetDateTime,
         flow: String,
     },
+
+    #[serde(rename = "sui-event")]
+    SuiEvent {
+        internal_chain_id: i32,
+        block_hash: String,
+        #[serde(with = "flexible_u64")]
+        height: BlockHeight,
+        transaction_hash: String,
+        transaction_index: i32,
+        index: i32,
+        data: Value,
+        time: OffsetDateTime,
+    },
+
+    #[serde(rename = "aptos-event")]
+    AptosEvent {
+        internal_chain_id: i32,
+        block_hash: String,
+        #[serde(with = "flexible_u64")]
+        height: BlockHeight,
+        transaction_hash: String,
+        #[serde(with = "flexible_u64")]
+        version: u64,
+        index: i32,
+        data: Value,
+        time: OffsetDateTime,
+    },
 }
 
 impl BlockEvent {
@@ -297,6 +324,8 @@ impl BlockEvent {
             BlockEvent::TendermintTransaction { height, .. } => *height,
             BlockEvent::TendermintEvent { height, .. } => *height,
             BlockEvent::EthereumLog { height, .. } => *height,
+            BlockEvent::SuiEvent { height, .. } => *height,
+            BlockEvent::AptosEvent { height, .. } => *height,
         }
     }
 }
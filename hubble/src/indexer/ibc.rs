@@ -0,0 +1,1527 @@
+use alloy::{rpc::types::Log, sol_types::SolEventInterface};
+use cosmos_sdk_event::CosmosSdkEvent;
+use ibc_solidity::Ibc;
+use ibc_union_spec::{ChannelId, ClientId, ConnectionId, Packet, Timestamp};
+use itertools::Itertools;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use time::OffsetDateTime;
+use tracing::{trace, warn};
+use unionlabs::primitives::{encoding::HexUnprefixed, Bytes, H256};
+
+use crate::indexer::{
+    api::BlockHeight, ethereum::block_handle::TransactionInsert, event::BlockEvent,
+    move_types::event_type,
+};
+
+/// An IBC union event as emitted on chain, decoded from the raw event data of a [`BlockEvent`].
+///
+/// On chain events only contain the data that changed, the remaining data required to construct an
+/// [`ibc_union_spec::event::FullEvent`] is resolved from previously indexed events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IbcEvent {
+    CreateClient {
+        client_id: ClientId,
+        client_type: String,
+    },
+    UpdateClient {
+        client_id: ClientId,
+        height: u64,
+    },
+    ClientMisbehaviour {
+        client_id: ClientId,
+    },
+    ConnectionOpenInit {
+        connection_id: ConnectionId,
+        client_id: ClientId,
+        counterparty_client_id: ClientId,
+    },
+    ConnectionOpenTry {
+        connection_id: ConnectionId,
+        client_id: ClientId,
+        counterparty_client_id: ClientId,
+        counterparty_connection_id: ConnectionId,
+    },
+    ConnectionOpenAck {
+        connection_id: ConnectionId,
+        client_id: ClientId,
+        counterparty_client_id: ClientId,
+        counterparty_connection_id: ConnectionId,
+    },
+    ConnectionOpenConfirm {
+        connection_id: ConnectionId,
+        client_id: ClientId,
+        counterparty_client_id: ClientId,
+        counterparty_connection_id: ConnectionId,
+    },
+    ChannelOpenInit {
+        port_id: Bytes,
+        channel_id: ChannelId,
+        counterparty_port_id: Bytes,
+        connection_id: ConnectionId,
+        version: String,
+    },
+    ChannelOpenTry {
+        port_id: Bytes,
+        channel_id: ChannelId,
+        counterparty_port_id: Bytes,
+        counterparty_channel_id: ChannelId,
+        connection_id: ConnectionId,
+        counterparty_version: String,
+    },
+    ChannelOpenAck {
+        port_id: Bytes,
+        channel_id: ChannelId,
+        counterparty_port_id: Bytes,
+        counterparty_channel_id: ChannelId,
+        connection_id: ConnectionId,
+    },
+    ChannelOpenConfirm {
+        port_id: Bytes,
+        channel_id: ChannelId,
+        counterparty_port_id: Bytes,
+        counterparty_channel_id: ChannelId,
+        connection_id: ConnectionId,
+    },
+    PacketSend {
+        packet: Packet,
+    },
+    BatchSend {
+        channel_id: ChannelId,
+        batch_hash: H256,
+    },
+    PacketRecv {
+        channel_id: ChannelId,
+        packet_hash: H256,
+        maker_msg: Bytes,
+    },
+    IntentPacketRecv {
+        channel_id: ChannelId,
+        packet_hash: H256,
+        maker_msg: Bytes,
+    },
+    WriteAck {
+        channel_id: ChannelId,
+        packet_hash: H256,
+        acknowledgement: Bytes,
+    },
+    PacketAck {
+        channel_id: ChannelId,
+        packet_hash: H256,
+        acknowledgement: Bytes,
+    },
+    PacketTimeout {
+        channel_id: ChannelId,
+        packet_hash: H256,
+    },
+}
+
+impl IbcEvent {
+    /// The hash of the packet this event is for, if this is a packet event.
+    pub fn packet_hash(&self) -> Option<H256> {
+        match self {
+            IbcEvent::PacketSend { packet } => Some(packet.hash()),
+            IbcEvent::PacketRecv { packet_hash, .. }
+            | IbcEvent::IntentPacketRecv { packet_hash, .. }
+            | IbcEvent::WriteAck { packet_hash, .. }
+            | IbcEvent::PacketAck { packet_hash, .. }
+            | IbcEvent::PacketTimeout { packet_hash, .. } => Some(*packet_hash),
+            _ => None,
+        }
+    }
+
+    fn from_ethereum_log(log: &Log) -> Option<Self> {
+        let event = match Ibc::IbcEvents::decode_log(&log.inner) {
+            Ok(event) => event.data,
+            Err(err) => {
+                trace!("not an ibc event: {err}");
+                return None;
+            }
+        };
+
+        let channel_id = |raw| ChannelId::from_raw(raw).expect("channel id is non-zero");
+        let connection_id = |raw| ConnectionId::from_raw(raw).expect("connection id is non-zero");
+        let client_id = |raw| ClientId::from_raw(raw).expect("client id is non-zero");
+
+        Some(match event {
+            Ibc::IbcEvents::CreateClient(e) => IbcEvent::CreateClient {
+                client_id: client_id(e.client_id),
+                client_type: e.clientType,
+            },
+            Ibc::IbcEvents::UpdateClient(e) => IbcEvent::UpdateClient {
+                client_id: client_id(e.client_id),
+                height: e.height,
+            },
+            Ibc::IbcEvents::ConnectionOpenInit(e) => IbcEvent::ConnectionOpenInit {
+                connection_id: connection_id(e.connection_id),
+                client_id: client_id(e.client_id),
+                counterparty_client_id: client_id(e.counterparty_client_id),
+            },
+            Ibc::IbcEvents::ConnectionOpenTry(e) => IbcEvent::ConnectionOpenTry {
+                connection_id: connection_id(e.connection_id),
+                client_id: client_id(e.client_id),
+                counterparty_client_id: client_id(e.counterparty_client_id),
+                counterparty_connection_id: connection_id(e.counterparty_connection_id),
+            },
+            Ibc::IbcEvents::ConnectionOpenAck(e) => IbcEvent::ConnectionOpenAck {
+                connection_id: connection_id(e.connection_id),
+                client_id: client_id(e.client_id),
+                counterparty_client_id: client_id(e.counterparty_client_id),
+                counterparty_connection_id: connection_id(e.counterparty_connection_id),
+            },
+            Ibc::IbcEvents::ConnectionOpenConfirm(e) => IbcEvent::ConnectionOpenConfirm {
+                connection_id: connection_id(e.connection_id),
+                client_id: client_id(e.client_id),
+                counterparty_client_id: client_id(e.counterparty_client_id),
+                counterparty_connection_id: connection_id(e.counterparty_connection_id),
+            },
+            Ibc::IbcEvents::ChannelOpenInit(e) => IbcEvent::ChannelOpenInit {
+                port_id: e.port_id.into(),
+                channel_id: channel_id(e.channel_id),
+                counterparty_port_id: e.counterparty_port_id.into(),
+                connection_id: connection_id(e.connection_id),
+                version: e.version,
+            },
+            Ibc::IbcEvents::ChannelOpenTry(e) => IbcEvent::ChannelOpenTry {
+                port_id: e.port_id.into(),
+                channel_id: channel_id(e.channel_id),
+                counterparty_port_id: e.counterparty_port_id.into(),
+                counterparty_channel_id: channel_id(e.counterparty_channel_id),
+                connection_id: connection_id(e.connection_id),
+                counterparty_version: e.counterparty_version,
+            },
+            Ibc::IbcEvents::ChannelOpenAck(e) => IbcEvent::ChannelOpenAck {
+                port_id: e.port_id.into(),
+                channel_id: channel_id(e.channel_id),
+                counterparty_port_id: e.counterparty_port_id.into(),
+                counterparty_channel_id: channel_id(e.counterparty_channel_id),
+                connection_id: connection_id(e.connection_id),
+            },
+            Ibc::IbcEvents::ChannelOpenConfirm(e) => IbcEvent::ChannelOpenConfirm {
+                port_id: e.port_id.into(),
+                channel_id: channel_id(e.channel_id),
+                counterparty_port_id: e.counterparty_portId.into(),
+                counterparty_channel_id: channel_id(e.counterparty_channel_id),
+                connection_id: connection_id(e.connection_id),
+            },
+            Ibc::IbcEvents::PacketSend(e) => IbcEvent::PacketSend {
+                packet: Packet {
+                    source_channel_id: channel_id(e.packet.source_channel_id),
+                    destination_channel_id: channel_id(e.packet.destination_channel_id),
+                    data: e.packet.data.into(),
+                    timeout_height: e.packet.timeout_height,
+                    timeout_timestamp: Timestamp::from_nanos(e.packet.timeout_timestamp),
+                },
+            },
+            Ibc::IbcEvents::BatchedPreviouslySent(e) => IbcEvent::BatchSend {
+                channel_id: channel_id(e.channel_id),
+                batch_hash: e.batch_hash.into(),
+            },
+            Ibc::IbcEvents::PacketRecv(e) => IbcEvent::PacketRecv {
+                channel_id: channel_id(e.channel_id),
+                packet_hash: e.packet_hash.into(),
+                maker_msg: e.maker_msg.into(),
+            },
+            Ibc::IbcEvents::IntentPacketRecv(e) => IbcEvent::IntentPacketRecv {
+                channel_id: channel_id(e.channel_id),
+                packet_hash: e.packet_hash.into(),
+                maker_msg: e.maker_msg.into(),
+            },
+            Ibc::IbcEvents::WriteAck(e) => IbcEvent::WriteAck {
+                channel_id: channel_id(e.channel_id),
+                packet_hash: e.packet_hash.into(),
+                acknowledgement: e.acknowledgement.into(),
+            },
+            Ibc::IbcEvents::PacketAck(e) => IbcEvent::PacketAck {
+                channel_id: channel_id(e.channel_id),
+                packet_hash: e.packet_hash.into(),
+                acknowledgement: e.acknowledgement.into(),
+            },
+            Ibc::IbcEvents::PacketTimeout(e) => IbcEvent::PacketTimeout {
+                channel_id: channel_id(e.channel_id),
+                packet_hash: e.packet_hash.into(),
+            },
+            event => {
+                trace!("ignoring ibc event: {event:?}");
+                return None;
+            }
+        })
+    }
+
+    fn from_tendermint_event(data: &Value) -> Option<Self> {
+        let event_type = data.get("type").and_then(Value::as_str)?;
+
+        if !WASM_IBC_EVENT_TYPES.contains(&event_type) {
+            return None;
+        }
+
+        let event = match cometbft_types::abci::event::Event::deserialize(data)
+            .map_err(cosmos_sdk_event::Error::from)
+            .and_then(CosmosSdkEvent::<WasmIbcEvent>::new)
+        {
+            Ok(event) => event.event,
+            Err(err) => {
+                warn!("unable to decode {event_type} event: {err}");
+                return None;
+            }
+        };
+
+        Some(match event {
+            WasmIbcEvent::CreateClient {
+                client_id,
+                client_type,
+            } => IbcEvent::CreateClient {
+                client_id,
+                client_type,
+            },
+            WasmIbcEvent::UpdateClient {
+                client_id,
+                counterparty_height,
+            } => IbcEvent::UpdateClient {
+                client_id,
+                height: counterparty_height,
+            },
+            WasmIbcEvent::ClientMisbehaviour { client_id } => {
+                IbcEvent::ClientMisbehaviour { client_id }
+            }
+            WasmIbcEvent::ConnectionOpenInit {
+                connection_id,
+                client_id,
+                counterparty_client_id,
+            } => IbcEvent::ConnectionOpenInit {
+                connection_id,
+                client_id,
+                counterparty_client_id,
+            },
+            WasmIbcEvent::ConnectionOpenTry {
+                connection_id,
+                client_id,
+                counterparty_client_id,
+                counterparty_connection_id,
+            } => IbcEvent::ConnectionOpenTry {
+                connection_id,
+                client_id,
+                counterparty_client_id,
+                counterparty_connection_id,
+            },
+            WasmIbcEvent::ConnectionOpenAck {
+                connection_id,
+                client_id,
+                counterparty_client_id,
+                counterparty_connection_id,
+            } => IbcEvent::ConnectionOpenAck {
+                connection_id,
+                client_id,
+                counterparty_client_id,
+                counterparty_connection_id,
+            },
+            WasmIbcEvent::ConnectionOpenConfirm {
+                connection_id,
+                client_id,
+                counterparty_client_id,
+                counterparty_connection_id,
+            } => IbcEvent::ConnectionOpenConfirm {
+                connection_id,
+                client_id,
+                counterparty_client_id,
+                counterparty_connection_id,
+            },
+            WasmIbcEvent::ChannelOpenInit {
+                port_id,
+                channel_id,
+                counterparty_port_id,
+                connection_id,
+                version,
+            } => IbcEvent::ChannelOpenInit {
+                port_id: port_id.into_bytes().into(),
+                channel_id,
+                counterparty_port_id: counterparty_port_id.into_encoding(),
+                connection_id,
+                version,
+            },
+            WasmIbcEvent::ChannelOpenTry {
+                port_id,
+                channel_id,
+                counterparty_port_id,
+                counterparty_channel_id,
+                connection_id,
+                counterparty_version,
+            } => IbcEvent::ChannelOpenTry {
+                port_id: port_id.into_bytes().into(),
+                channel_id,
+                counterparty_port_id: counterparty_port_id.into_encoding(),
+                counterparty_channel_id,
+                connection_id,
+                counterparty_version,
+            },
+            WasmIbcEvent::ChannelOpenAck {
+                port_id,
+                channel_id,
+                counterparty_port_id,
+                counterparty_channel_id,
+                connection_id,
+            } => IbcEvent::ChannelOpenAck {
+                port_id: port_id.into_bytes().into(),
+                channel_id,
+                counterparty_port_id: counterparty_port_id.into_encoding(),
+                counterparty_channel_id,
+                connection_id,
+            },
+            WasmIbcEvent::ChannelOpenConfirm {
+                port_id,
+                channel_id,
+                counterparty_port_id,
+                counterparty_channel_id,
+                connection_id,
+            } => IbcEvent::ChannelOpenConfirm {
+                port_id: port_id.into_bytes().into(),
+                channel_id,
+                counterparty_port_id: counterparty_port_id.into_encoding(),
+                counterparty_channel_id,
+                connection_id,
+            },
+            WasmIbcEvent::PacketSend {
+                packet_source_channel_id,
+                packet_destination_channel_id,
+                packet_data,
+                packet_timeout_height,
+                packet_timeout_timestamp,
+            } => IbcEvent::PacketSend {
+                packet: Packet {
+                    source_channel_id: packet_source_channel_id,
+                    destination_channel_id: packet_destination_channel_id,
+                    data: packet_data,
+                    timeout_height: packet_timeout_height,
+                    timeout_timestamp: packet_timeout_timestamp,
+                },
+            },
+            WasmIbcEvent::BatchSend {
+                channel_id,
+                batch_hash,
+            } => IbcEvent::BatchSend {
+                channel_id,
+                batch_hash,
+            },
+            WasmIbcEvent::PacketRecv {
+                channel_id,
+                packet_hash,
+                maker_msg,
+            } => IbcEvent::PacketRecv {
+                channel_id,
+                packet_hash,
+                maker_msg: maker_msg.into_encoding(),
+            },
+            WasmIbcEvent::PacketAck {
+                channel_id,
+                packet_hash,
+                acknowledgement,
+            } => IbcEvent::PacketAck {
+                channel_id,
+                packet_hash,
+                acknowledgement: acknowledgement.into_encoding(),
+            },
+            WasmIbcEvent::WriteAck {
+                channel_id,
+                packet_hash,
+                acknowledgement,
+            } => IbcEvent::WriteAck {
+                channel_id,
+                packet_hash,
+                acknowledgement: acknowledgement.into_encoding(),
+            },
+        })
+    }
+
+    fn from_sui_event(data: &Value) -> Option<Self> {
+        let event = decode_move_event::<SuiIbcEvent>(data, "parsedJson", SUI_IBC_EVENT_TYPES)?;
+
+        Some(match event {
+            SuiIbcEvent::CreateClient {
+                client_id,
+                client_type,
+            } => IbcEvent::CreateClient {
+                client_id,
+                client_type,
+            },
+            SuiIbcEvent::UpdateClient { client_id, height } => {
+                IbcEvent::UpdateClient { client_id, height }
+            }
+            SuiIbcEvent::SubmitMisbehaviour { client_id } => {
+                IbcEvent::ClientMisbehaviour { client_id }
+            }
+            SuiIbcEvent::ConnectionOpenInit(event) => event.into_init(),
+            SuiIbcEvent::ConnectionOpenTry(event) => event.into_try(),
+            SuiIbcEvent::ConnectionOpenAck(event) => event.into_ack(),
+            SuiIbcEvent::ConnectionOpenConfirm(event) => event.into_confirm(),
+            SuiIbcEvent::ChannelOpenInit(event) => event.into_init(),
+            SuiIbcEvent::ChannelOpenTry(event) => event.into_try(),
+            SuiIbcEvent::ChannelOpenAck(event) => event.into_ack(),
+            SuiIbcEvent::ChannelOpenConfirm(event) => event.into_confirm(),
+            SuiIbcEvent::PacketSend { packet } => IbcEvent::PacketSend {
+                packet: packet.into(),
+            },
+            SuiIbcEvent::PacketRecv {
+                channel_id,
+                packet_hash,
+                maker_msg,
+            } => IbcEvent::PacketRecv {
+                channel_id,
+                packet_hash: H256::new(packet_hash),
+                maker_msg: maker_msg.into(),
+            },
+            SuiIbcEvent::IntentPacketRecv {
+                channel_id,
+                packet_hash,
+                maker_msg,
+            } => IbcEvent::IntentPacketRecv {
+                channel_id,
+                packet_hash: H256::new(packet_hash),
+                maker_msg: maker_msg.into(),
+            },
+            SuiIbcEvent::WriteAck {
+                channel_id,
+                packet_hash,
+                acknowledgement,
+            } => IbcEvent::WriteAck {
+                channel_id,
+                packet_hash: H256::new(packet_hash),
+                acknowledgement: acknowledgement.into(),
+            },
+            SuiIbcEvent::PacketAck {
+                channel_id,
+                packet_hash,
+                acknowledgement,
+            } => IbcEvent::PacketAck {
+                channel_id,
+                packet_hash: H256::new(packet_hash),
+                acknowledgement: acknowledgement.into(),
+            },
+            SuiIbcEvent::TimeoutPacket { packet } => IbcEvent::packet_timeout(packet.into()),
+        })
+    }
+
+    fn from_aptos_event(data: &Value) -> Option<Self> {
+        let event = decode_move_event::<AptosIbcEvent>(data, "data", APTOS_IBC_EVENT_TYPES)?;
+
+        Some(match event {
+            AptosIbcEvent::CreateClient {
+                client_id,
+                client_type,
+            } => IbcEvent::CreateClient {
+                client_id,
+                client_type,
+            },
+            AptosIbcEvent::UpdateClient {
+                client_id,
+                counterparty_height,
+            } => IbcEvent::UpdateClient {
+                client_id,
+                height: counterparty_height,
+            },
+            AptosIbcEvent::SubmitMisbehaviour { client_id } => {
+                IbcEvent::ClientMisbehaviour { client_id }
+            }
+            AptosIbcEvent::ConnectionOpenInit(event) => event.into_init(),
+            AptosIbcEvent::ConnectionOpenTry(event) => event.into_try(),
+            AptosIbcEvent::ConnectionOpenAck(event) => event.into_ack(),
+            AptosIbcEvent::ConnectionOpenConfirm(event) => event.into_confirm(),
+            AptosIbcEvent::ChannelOpenInit(event) => event.into_init(),
+            AptosIbcEvent::ChannelOpenTry(event) => event.into_try(),
+            AptosIbcEvent::ChannelOpenAck(event) => event.into_ack(),
+            AptosIbcEvent::ChannelOpenConfirm(event) => event.into_confirm(),
+            AptosIbcEvent::PacketSend(packet) => IbcEvent::PacketSend {
+                packet: packet.into(),
+            },
+            AptosIbcEvent::PacketRecv { packet, maker_msg } => {
+                let packet = Packet::from(packet);
+
+                IbcEvent::PacketRecv {
+                    channel_id: packet.destination_channel_id,
+                    packet_hash: packet.hash(),
+                    maker_msg: maker_msg.into(),
+                }
+            }
+            AptosIbcEvent::RecvIntentPacket { packet, maker_msg } => {
+                let packet = Packet::from(packet);
+
+                IbcEvent::IntentPacketRecv {
+                    channel_id: packet.destination_channel_id,
+                    packet_hash: packet.hash(),
+                    maker_msg: maker_msg.into(),
+                }
+            }
+            AptosIbcEvent::WriteAck {
+                packet,
+                acknowledgement,
+            } => {
+                let packet = Packet::from(packet);
+
+                IbcEvent::WriteAck {
+                    channel_id: packet.destination_channel_id,
+                    packet_hash: packet.hash(),
+                    acknowledgement: acknowledgement.into(),
+                }
+            }
+            AptosIbcEvent::PacketAck {
+                packet,
+                acknowledgement,
+            } => {
+                let packet = Packet::from(packet);
+
+                IbcEvent::PacketAck {
+                    channel_id: packet.source_channel_id,
+                    packet_hash: packet.hash(),
+                    acknowledgement: acknowledgement.into(),
+                }
+            }
+            AptosIbcEvent::TimeoutPacket { packet } => IbcEvent::packet_timeout(packet.into()),
+        })
+    }
+
+    fn packet_timeout(packet: Packet) -> Self {
+        IbcEvent::PacketTimeout {
+            channel_id: packet.source_channel_id,
+            packet_hash: packet.hash(),
+        }
+    }
+}
+
+/// An [`IbcEvent`] along with the location it was emitted at.
+#[derive(Debug, Clone)]
+pub struct IbcEventRecord {
+    pub internal_chain_id: i32,
+    pub block_hash: String,
+    pub height: BlockHeight,
+    pub transaction_hash: Option<String>,
+    /// The index of the event in the block (the log index for ethereum logs).
+    pub event_index: i32,
+    pub time: OffsetDateTime,
+    pub event: IbcEvent,
+}
+
+/// Decode all IBC union events in `block_events`, in the order they were emitted.
+pub fn decode_ibc_events(block_events: &[&BlockEvent]) -> Vec<IbcEventRecord> {
+    block_events
+        .iter()
+        .flat_map(|block_event| match block_event {
+            BlockEvent::EthereumLog {
+                internal_chain_id,
+                block_hash,
+                data,
+                height,
+                time,
+            } => {
+                // only the transactions are required, the header is skipped
+                #[derive(Deserialize)]
+                struct Logs {
+                    transactions: Vec<TransactionInsert>,
+                }
+
+                let logs = match Logs::deserialize(data) {
+                    Ok(logs) => logs,
+                    Err(err) => {
+                        warn!("unable to decode ethereum logs at {height}: {err}");
+                        return vec![];
+                    }
+                };
+
+                logs.transactions
+                    .into_iter()
+                    .flat_map(|transaction| {
+                        transaction.events.into_iter().filter_map(move |event| {
+                            let log = Log::deserialize(&event.data)
+                                .inspect_err(|err| warn!("unable to decode ethereum log: {err}"))
+                                .ok()?;
+
+                            Some(IbcEventRecord {
+                                internal_chain_id: *internal_chain_id,
+                                block_hash: block_hash.clone(),
+                                height: *height,
+                                transaction_hash: Some(transaction.hash.clone()),
+                                event_index: event.log_index.try_into().expect("log index fits"),
+                                time: *time,
+                                event: IbcEvent::from_ethereum_log(&log)?,
+                            })
+                        })
+                    })
+                    .collect()
+            }
+            BlockEvent::TendermintEvent {
+                internal_chain_id,
+                block_hash,
+                height,
+                transaction_hash,
+                index,
+                data,
+                time,
+                ..
+            } => IbcEvent::from_tendermint_event(data)
+                .map(|event| IbcEventRecord {
+                    internal_chain_id: *internal_chain_id,
+                    block_hash: block_hash.clone(),
+                    height: *height,
+                    transaction_hash: transaction_hash.clone(),
+                    event_index: *index,
+                    time: *time,
+                    event,
+                })
+                .into_iter()
+                .collect(),
+            BlockEvent::SuiEvent {
+                internal_chain_id,
+                block_hash,
+                height,
+                transaction_hash,
+                index,
+                data,
+                time,
+                ..
+            } => IbcEvent::from_sui_event(data)
+                .map(|event| IbcEventRecord {
+                    internal_chain_id: *internal_chain_id,
+                    block_hash: block_hash.clone(),
+                    height: *height,
+                    transaction_hash: Some(transaction_hash.clone()),
+                    event_index: *index,
+                    time: *time,
+                    event,
+                })
+                .into_iter()
+                .collect(),
+            BlockEvent::AptosEvent {
+                internal_chain_id,
+                block_hash,
+                height,
+                transaction_hash,
+                index,
+                data,
+                time,
+                ..
+            } => IbcEvent::from_aptos_event(data)
+                .map(|event| IbcEventRecord {
+                    internal_chain_id: *internal_chain_id,
+                    block_hash: block_hash.clone(),
+                    height: *height,
+                    transaction_hash: Some(transaction_hash.clone()),
+                    event_index: *index,
+                    time: *time,
+                    event,
+                })
+                .into_iter()
+                .collect(),
+            // blocks and transactions contain no events
+            BlockEvent::TendermintBlock { .. } | BlockEvent::TendermintTransaction { .. } => {
+                vec![]
+            }
+        })
+        // tendermint events are stored once for every flow they are in
+        .unique_by(|record| (record.internal_chain_id, record.height, record.event_index))
+        .sorted_by_key(|record| (record.internal_chain_id, record.height, record.event_index))
+        .collect()
+}
+
+const WASM_IBC_EVENT_TYPES: &[&str] = &[
+    "wasm-create_client",
+    "wasm-update_client",
+    "wasm-client_misbehaviour",
+    "wasm-connection_open_init",
+    "wasm-connection_open_try",
+    "wasm-connection_open_ack",
+    "wasm-connection_open_confirm",
+    "wasm-channel_open_init",
+    "wasm-channel_open_try",
+    "wasm-channel_open_ack",
+    "wasm-channel_open_confirm",
+    "wasm-packet_send",
+    "wasm-batch_send",
+    "wasm-packet_recv",
+    "wasm-packet_ack",
+    "wasm-write_ack",
+];
+
+/// Events emitted by the cosmwasm implementation of IBC union.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "attributes")]
+enum WasmIbcEvent {
+    #[serde(rename = "wasm-create_client")]
+    CreateClient {
+        #[serde(with = "serde_utils::string")]
+        client_id: ClientId,
+        client_type: String,
+    },
+
+    #[serde(rename = "wasm-update_client")]
+    UpdateClient {
+        #[serde(with = "serde_utils::string")]
+        client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_height: u64,
+    },
+
+    #[serde(rename = "wasm-client_misbehaviour")]
+    ClientMisbehaviour {
+        #[serde(with = "serde_utils::string")]
+        client_id: ClientId,
+    },
+
+    #[serde(rename = "wasm-connection_open_init")]
+    ConnectionOpenInit {
+        #[serde(with = "serde_utils::string")]
+        connection_id: ConnectionId,
+        #[serde(with = "serde_utils::string")]
+        client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_client_id: ClientId,
+    },
+
+    #[serde(rename = "wasm-connection_open_try")]
+    ConnectionOpenTry {
+        #[serde(with = "serde_utils::string")]
+        connection_id: ConnectionId,
+        #[serde(with = "serde_utils::string")]
+        client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_connection_id: ConnectionId,
+    },
+
+    #[serde(rename = "wasm-connection_open_ack")]
+    ConnectionOpenAck {
+        #[serde(with = "serde_utils::string")]
+        connection_id: ConnectionId,
+        #[serde(with = "serde_utils::string")]
+        client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_connection_id: ConnectionId,
+    },
+
+    #[serde(rename = "wasm-connection_open_confirm")]
+    ConnectionOpenConfirm {
+        #[serde(with = "serde_utils::string")]
+        connection_id: ConnectionId,
+        #[serde(with = "serde_utils::string")]
+        client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_connection_id: ConnectionId,
+    },
+
+    #[serde(rename = "wasm-channel_open_init")]
+    ChannelOpenInit {
+        port_id: String,
+        #[serde(with = "serde_utils::string")]
+        channel_id: ChannelId,
+        counterparty_port_id: Bytes<HexUnprefixed>,
+        #[serde(with = "serde_utils::string")]
+        connection_id: ConnectionId,
+        version: String,
+    },
+
+    #[serde(rename = "wasm-channel_open_try")]
+    ChannelOpenTry {
+        port_id: String,
+        #[serde(with = "serde_utils::string")]
+        channel_id: ChannelId,
+        counterparty_port_id: Bytes<HexUnprefixed>,
+        #[serde(with = "serde_utils::string")]
+        counterparty_channel_id: ChannelId,
+        #[serde(with = "serde_utils::string")]
+        connection_id: ConnectionId,
+        counterparty_version: String,
+    },
+
+    #[serde(rename = "wasm-channel_open_ack")]
+    ChannelOpenAck {
+        port_id: String,
+        #[serde(with = "serde_utils::string")]
+        channel_id: ChannelId,
+        counterparty_port_id: Bytes<HexUnprefixed>,
+        #[serde(with = "serde_utils::string")]
+        counterparty_channel_id: ChannelId,
+        #[serde(with = "serde_utils::string")]
+        connection_id: ConnectionId,
+    },
+
+    #[serde(rename = "wasm-channel_open_confirm")]
+    ChannelOpenConfirm {
+        port_id: String,
+        #[serde(with = "serde_utils::string")]
+        channel_id: ChannelId,
+        counterparty_port_id: Bytes<HexUnprefixed>,
+        #[serde(with = "serde_utils::string")]
+        counterparty_channel_id: ChannelId,
+        #[serde(with = "serde_utils::string")]
+        connection_id: ConnectionId,
+    },
+
+    // `channel_id` and `packet_hash` are also emitted, but are redundant
+    #[serde(rename = "wasm-packet_send")]
+    PacketSend {
+        #[serde(with = "serde_utils::string")]
+        packet_source_channel_id: ChannelId,
+        #[serde(with = "serde_utils::string")]
+        packet_destination_channel_id: ChannelId,
+        packet_data: Bytes,
+        #[serde(with = "serde_utils::string")]
+        packet_timeout_height: u64,
+        #[serde(with = "serde_utils::string")]
+        packet_timeout_timestamp: Timestamp,
+    },
+
+    // `packet_hash` is also emitted, but the batch is only identified by its hash
+    #[serde(rename = "wasm-batch_send")]
+    BatchSend {
+        #[serde(with = "serde_utils::string")]
+        channel_id: ChannelId,
+        batch_hash: H256,
+    },
+
+    // `maker` is also emitted, but is not part of the full event
+    #[serde(rename = "wasm-packet_recv")]
+    PacketRecv {
+        #[serde(with = "serde_utils::string")]
+        channel_id: ChannelId,
+        packet_hash: H256,
+        maker_msg: Bytes<HexUnprefixed>,
+    },
+
+    #[serde(rename = "wasm-packet_ack")]
+    PacketAck {
+        #[serde(with = "serde_utils::string")]
+        channel_id: ChannelId,
+        packet_hash: H256,
+        acknowledgement: Bytes<HexUnprefixed>,
+    },
+
+    #[serde(rename = "wasm-write_ack")]
+    WriteAck {
+        #[serde(with = "serde_utils::string")]
+        channel_id: ChannelId,
+        packet_hash: H256,
+        acknowledgement: Bytes<HexUnprefixed>,
+    },
+}
+
+/// The module that the move implementations of IBC union emit their events from.
+const MOVE_IBC_MODULE: &str = "ibc";
+
+/// Decode the IBC union event `data` of a move chain. The fields of the event are in the `content`
+/// field of `data`, and the name of the event is taken from its type.
+fn decode_move_event<T: DeserializeOwned>(
+    data: &Value,
+    content: &str,
+    event_types: &[&str],
+) -> Option<T> {
+    let (module, name) = event_type(data)?;
+
+    if module != MOVE_IBC_MODULE || !event_types.contains(&name) {
+        return None;
+    }
+
+    T::deserialize(json!({ "type": name, "data": data.get(content)? }))
+        .inspect_err(|err| warn!("unable to decode {name} event: {err}"))
+        .ok()
+}
+
+const SUI_IBC_EVENT_TYPES: &[&str] = &[
+    "CreateClient",
+    "UpdateClient",
+    "SubmitMisbehaviour",
+    "ConnectionOpenInit",
+    "ConnectionOpenTry",
+    "ConnectionOpenAck",
+    "ConnectionOpenConfirm",
+    "ChannelOpenInit",
+    "ChannelOpenTry",
+    "ChannelOpenAck",
+    "ChannelOpenConfirm",
+    "PacketSend",
+    "PacketRecv",
+    "IntentPacketRecv",
+    "WriteAck",
+    "PacketAck",
+    "TimeoutPacket",
+];
+
+/// Events emitted by the sui implementation of IBC union, as found in the `parsedJson` of an event.
+///
+/// `u64`s are encoded as strings, and `vector<u8>`s as arrays of numbers.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "data")]
+enum SuiIbcEvent {
+    // `counterparty_chain_id` is also emitted, but is not part of the full event
+    CreateClient {
+        client_id: ClientId,
+        client_type: String,
+    },
+    UpdateClient {
+        client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        height: u64,
+    },
+    SubmitMisbehaviour {
+        client_id: ClientId,
+    },
+    ConnectionOpenInit(MoveConnectionOpenInit),
+    ConnectionOpenTry(MoveConnectionOpen),
+    ConnectionOpenAck(MoveConnectionOpen),
+    ConnectionOpenConfirm(MoveConnectionOpen),
+    ChannelOpenInit(MoveChannelOpenInit<Vec<u8>>),
+    ChannelOpenTry(MoveChannelOpenTry<Vec<u8>>),
+    ChannelOpenAck(MoveChannelOpen<Vec<u8>>),
+    ChannelOpenConfirm(MoveChannelOpen<Vec<u8>>),
+    // `channel_id` and `packet_hash` are also emitted, but are redundant
+    PacketSend {
+        packet: MovePacket<Vec<u8>>,
+    },
+    // `maker` is also emitted, but is not part of the full event
+    PacketRecv {
+        channel_id: ChannelId,
+        packet_hash: [u8; 32],
+        maker_msg: Vec<u8>,
+    },
+    IntentPacketRecv {
+        channel_id: ChannelId,
+        packet_hash: [u8; 32],
+        maker_msg: Vec<u8>,
+    },
+    WriteAck {
+        channel_id: ChannelId,
+        packet_hash: [u8; 32],
+        acknowledgement: Vec<u8>,
+    },
+    PacketAck {
+        channel_id: ChannelId,
+        packet_hash: [u8; 32],
+        acknowledgement: Vec<u8>,
+    },
+    TimeoutPacket {
+        packet: MovePacket<Vec<u8>>,
+    },
+}
+
+const APTOS_IBC_EVENT_TYPES: &[&str] = &[
+    "CreateClient",
+    "UpdateClient",
+    "SubmitMisbehaviour",
+    "ConnectionOpenInit",
+    "ConnectionOpenTry",
+    "ConnectionOpenAck",
+    "ConnectionOpenConfirm",
+    "ChannelOpenInit",
+    "ChannelOpenTry",
+    "ChannelOpenAck",
+    "ChannelOpenConfirm",
+    "PacketSend",
+    "PacketRecv",
+    "RecvIntentPacket",
+    "WriteAck",
+    "PacketAck",
+    "TimeoutPacket",
+];
+
+/// Events emitted by the aptos implementation of IBC union, as found in the `data` of an event.
+///
+/// `u64`s are encoded as strings, and `vector<u8>`s as `0x` prefixed hex strings. Packet events
+/// contain the full packet instead of its hash.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "data")]
+enum AptosIbcEvent {
+    // `counterparty_chain_id` is also emitted, but is not part of the full event
+    CreateClient {
+        client_id: ClientId,
+        client_type: String,
+    },
+    UpdateClient {
+        client_id: ClientId,
+        #[serde(with = "serde_utils::string")]
+        counterparty_height: u64,
+    },
+    SubmitMisbehaviour {
+        client_id: ClientId,
+    },
+    ConnectionOpenInit(MoveConnectionOpenInit),
+    ConnectionOpenTry(MoveConnectionOpen),
+    ConnectionOpenAck(MoveConnectionOpen),
+    ConnectionOpenConfirm(MoveConnectionOpen),
+    ChannelOpenInit(MoveChannelOpenInit<Bytes>),
+    ChannelOpenTry(MoveChannelOpenTry<Bytes>),
+    ChannelOpenAck(MoveChannelOpen<Bytes>),
+    ChannelOpenConfirm(MoveChannelOpen<Bytes>),
+    PacketSend(MovePacket<Bytes>),
+    // `maker` is also emitted, but is not part of the full event
+    PacketRecv {
+        packet: MovePacket<Bytes>,
+        maker_msg: Bytes,
+    },
+    RecvIntentPacket {
+        packet: MovePacket<Bytes>,
+        maker_msg: Bytes,
+    },
+    WriteAck {
+        packet: MovePacket<Bytes>,
+        acknowledgement: Bytes,
+    },
+    PacketAck {
+        packet: MovePacket<Bytes>,
+        acknowledgement: Bytes,
+    },
+    TimeoutPacket {
+        packet: MovePacket<Bytes>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct MoveConnectionOpenInit {
+    connection_id: ConnectionId,
+    client_id: ClientId,
+    counterparty_client_id: ClientId,
+}
+
+impl MoveConnectionOpenInit {
+    fn into_init(self) -> IbcEvent {
+        IbcEvent::ConnectionOpenInit {
+            connection_id: self.connection_id,
+            client_id: self.client_id,
+            counterparty_client_id: self.counterparty_client_id,
+        }
+    }
+}
+
+/// The connection handshake events after the init, which all have the same fields.
+#[derive(Debug, Deserialize)]
+struct MoveConnectionOpen {
+    connection_id: ConnectionId,
+    client_id: ClientId,
+    counterparty_client_id: ClientId,
+    counterparty_connection_id: ConnectionId,
+}
+
+impl MoveConnectionOpen {
+    fn into_try(self) -> IbcEvent {
+        IbcEvent::ConnectionOpenTry {
+            connection_id: self.connection_id,
+            client_id: self.client_id,
+            counterparty_client_id: self.counterparty_client_id,
+            counterparty_connection_id: self.counterparty_connection_id,
+        }
+    }
+
+    fn into_ack(self) -> IbcEvent {
+        IbcEvent::ConnectionOpenAck {
+            connection_id: self.connection_id,
+            client_id: self.client_id,
+            counterparty_client_id: self.counterparty_client_id,
+            counterparty_connection_id: self.counterparty_connection_id,
+        }
+    }
+
+    fn into_confirm(self) -> IbcEvent {
+        IbcEvent::ConnectionOpenConfirm {
+            connection_id: self.connection_id,
+            client_id: self.client_id,
+            counterparty_client_id: self.counterparty_client_id,
+            counterparty_connection_id: self.counterparty_connection_id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MoveChannelOpenInit<B> {
+    port_id: String,
+    channel_id: ChannelId,
+    counterparty_port_id: B,
+    connection_id: ConnectionId,
+    version: String,
+}
+
+impl<B: Into<Bytes>> MoveChannelOpenInit<B> {
+    fn into_init(self) -> IbcEvent {
+        IbcEvent::ChannelOpenInit {
+            port_id: self.port_id.into_bytes().into(),
+            channel_id: self.channel_id,
+            counterparty_port_id: self.counterparty_port_id.into(),
+            connection_id: self.connection_id,
+            version: self.version,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MoveChannelOpenTry<B> {
+    port_id: String,
+    channel_id: ChannelId,
+    counterparty_port_id: B,
+    counterparty_channel_id: ChannelId,
+    connection_id: ConnectionId,
+    version: String,
+}
+
+impl<B: Into<Bytes>> MoveChannelOpenTry<B> {
+    fn into_try(self) -> IbcEvent {
+        IbcEvent::ChannelOpenTry {
+            port_id: self.port_id.into_bytes().into(),
+            channel_id: self.channel_id,
+            counterparty_port_id: self.counterparty_port_id.into(),
+            counterparty_channel_id: self.counterparty_channel_id,
+            connection_id: self.connection_id,
+            counterparty_version: self.version,
+        }
+    }
+}
+
+/// The channel handshake events after the try, which both have the same fields.
+#[derive(Debug, Deserialize)]
+struct MoveChannelOpen<B> {
+    port_id: String,
+    channel_id: ChannelId,
+    counterparty_port_id: B,
+    counterparty_channel_id: ChannelId,
+    connection_id: ConnectionId,
+}
+
+impl<B: Into<Bytes>> MoveChannelOpen<B> {
+    fn into_ack(self) -> IbcEvent {
+        IbcEvent::ChannelOpenAck {
+            port_id: self.port_id.into_bytes().into(),
+            channel_id: self.channel_id,
+            counterparty_port_id: self.counterparty_port_id.into(),
+            counterparty_channel_id: self.counterparty_channel_id,
+            connection_id: self.connection_id,
+        }
+    }
+
+    fn into_confirm(self) -> IbcEvent {
+        IbcEvent::ChannelOpenConfirm {
+            port_id: self.port_id.into_bytes().into(),
+            channel_id: self.channel_id,
+            counterparty_port_id: self.counterparty_port_id.into(),
+            counterparty_channel_id: self.counterparty_channel_id,
+            connection_id: self.connection_id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MovePacket<D> {
+    source_channel_id: ChannelId,
+    destination_channel_id: ChannelId,
+    data: D,
+    #[serde(with = "serde_utils::string")]
+    timeout_height: u64,
+    #[serde(with = "serde_utils::string")]
+    timeout_timestamp: u64,
+}
+
+impl<D: Into<Bytes>> From<MovePacket<D>> for Packet {
+    fn from(packet: MovePacket<D>) -> Self {
+        Packet {
+            source_channel_id: packet.source_channel_id,
+            destination_channel_id: packet.destination_channel_id,
+            data: packet.data.into(),
+            timeout_height: packet.timeout_height,
+            timeout_timestamp: Timestamp::from_nanos(packet.timeout_timestamp),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    fn tendermint_event(index: i32, flow: &str, data: Value) -> BlockEvent {
+        BlockEvent::TendermintEvent {
+            internal_chain_id: 1,
+            block_hash: "block-hash".to_string(),
+            height: 100,
+            transaction_hash: Some("transaction-hash".to_string()),
+            index,
+            transaction_index: Some(0),
+            data,
+            time: OffsetDateTime::UNIX_EPOCH,
+            flow: flow.to_string(),
+        }
+    }
+
+    fn sui_event(index: i32, data: Value) -> BlockEvent {
+        BlockEvent::SuiEvent {
+            internal_chain_id: 2,
+            block_hash: "checkpoint-digest".to_string(),
+            height: 100,
+            transaction_hash: "transaction-digest".to_string(),
+            transaction_index: 0,
+            index,
+            data,
+            time: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+
+    fn aptos_event(index: i32, data: Value) -> BlockEvent {
+        BlockEvent::AptosEvent {
+            internal_chain_id: 3,
+            block_hash: "block-hash".to_string(),
+            height: 100,
+            transaction_hash: "transaction-hash".to_string(),
+            version: 1000,
+            index,
+            data,
+            time: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+
+    fn packet() -> Packet {
+        Packet {
+            source_channel_id: ChannelId::from_raw(1).unwrap(),
+            destination_channel_id: ChannelId::from_raw(2).unwrap(),
+            data: vec![0x12, 0x34].into(),
+            timeout_height: 0,
+            timeout_timestamp: Timestamp::from_nanos(1000),
+        }
+    }
+
+    fn attributes(attributes: &[(&str, &str)]) -> Value {
+        attributes
+            .iter()
+            .map(|(key, value)| json!({ "key": key, "value": value, "index": true }))
+            .collect()
+    }
+
+    #[test]
+    fn decodes_wasm_packet_send() {
+        let event = tendermint_event(
+            3,
+            "flow",
+            json!({
+                "type": "wasm-packet_send",
+                "attributes": attributes(&[
+                    ("_contract_address", "union17e93ukhcyesrvu72cgfvamdhyracghrx4f7ww89rqjg944ntdegscxepme"),
+                    ("msg_index", "0"),
+                    ("packet_source_channel_id", "1"),
+                    ("packet_destination_channel_id", "2"),
+                    ("packet_data", "0x1234"),
+                    ("packet_timeout_height", "0"),
+                    ("packet_timeout_timestamp", "1000"),
+                    ("channel_id", "1"),
+                    ("packet_hash", "0x0000000000000000000000000000000000000000000000000000000000000000"),
+                ]),
+            }),
+        );
+
+        let records = decode_ibc_events(&[&event]);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].event_index, 3);
+        assert_eq!(
+            records[0].event,
+            IbcEvent::PacketSend {
+                packet: Packet {
+                    source_channel_id: ChannelId::from_raw(1).unwrap(),
+                    destination_channel_id: ChannelId::from_raw(2).unwrap(),
+                    data: vec![0x12, 0x34].into(),
+                    timeout_height: 0,
+                    timeout_timestamp: Timestamp::from_nanos(1000),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn decodes_wasm_client_misbehaviour() {
+        let event = tendermint_event(
+            0,
+            "flow",
+            json!({
+                "type": "wasm-client_misbehaviour",
+                "attributes": attributes(&[
+                    ("_contract_address", "union17e93ukhcyesrvu72cgfvamdhyracghrx4f7ww89rqjg944ntdegscxepme"),
+                    ("msg_index", "0"),
+                    ("client_id", "3"),
+                ]),
+            }),
+        );
+
+        let records = decode_ibc_events(&[&event]);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].event,
+            IbcEvent::ClientMisbehaviour {
+                client_id: ClientId::from_raw(3).unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_non_ibc_events() {
+        let transfer = tendermint_event(
+            0,
+            "flow",
+            json!({
+                "type": "transfer",
+                "attributes": attributes(&[("recipient", "union1"), ("amount", "1muno")]),
+            }),
+        );
+        let wasm = tendermint_event(
+            1,
+            "flow",
+            json!({
+                "type": "wasm-token_bucket_update",
+                "attributes": attributes(&[("capacity", "1")]),
+            }),
+        );
+
+        assert!(decode_ibc_events(&[&transfer, &wasm]).is_empty());
+    }
+
+    #[test]
+    fn decodes_sui_events() {
+        let packet_send = sui_event(
+            1,
+            json!({
+                "type": "0xab::ibc::PacketSend",
+                "parsedJson": {
+                    "channel_id": 1,
+                    "packet_hash": packet().hash().get().to_vec(),
+                    "packet": {
+                        "source_channel_id": 1,
+                        "destination_channel_id": 2,
+                        "data": [0x12, 0x34],
+                        "timeout_height": "0",
+                        "timeout_timestamp": "1000",
+                    },
+                },
+            }),
+        );
+        let write_ack = sui_event(
+            0,
+            json!({
+                "type": "0xab::ibc::WriteAck",
+                "parsedJson": {
+                    "channel_id": 2,
+                    "packet_hash": vec![0xAA; 32],
+                    "acknowledgement": [1],
+                },
+            }),
+        );
+
+        let records = decode_ibc_events(&[&packet_send, &write_ack]);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].transaction_hash.as_deref(),
+            Some("transaction-digest")
+        );
+        assert_eq!(
+            records[0].event,
+            IbcEvent::WriteAck {
+                channel_id: ChannelId::from_raw(2).unwrap(),
+                packet_hash: H256::new([0xAA; 32]),
+                acknowledgement: vec![1].into(),
+            }
+        );
+        assert_eq!(records[1].event, IbcEvent::PacketSend { packet: packet() });
+    }
+
+    #[test]
+    fn decodes_aptos_events() {
+        let update_client = aptos_event(
+            0,
+            json!({
+                "guid": { "creation_number": "0", "account_address": "0x0" },
+                "sequence_number": "0",
+                "type": "0xab::ibc::UpdateClient",
+                "data": {
+                    "client_id": 4,
+                    "client_type": "cometbls",
+                    "counterparty_height": "42",
+                },
+            }),
+        );
+        let packet_recv = aptos_event(
+            1,
+            json!({
+                "type": "0xab::ibc::PacketRecv",
+                "data": {
+                    "packet": {
+                        "source_channel_id": 1,
+                        "destination_channel_id": 2,
+                        "data": "0x1234",
+                        "timeout_height": "0",
+                        "timeout_timestamp": "1000",
+                    },
+                    "maker": "0xcd",
+                    "maker_msg": "0x",
+                },
+            }),
+        );
+
+        let records = decode_ibc_events(&[&update_client, &packet_recv]);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].event,
+            IbcEvent::UpdateClient {
+                client_id: ClientId::from_raw(4).unwrap(),
+                height: 42,
+            }
+        );
+        assert_eq!(
+            records[1].event,
+            IbcEvent::PacketRecv {
+                channel_id: ChannelId::from_raw(2).unwrap(),
+                packet_hash: packet().hash(),
+                maker_msg: Vec::<u8>::new().into(),
+            }
+        );
+    }
+
+    #[test]
+    fn ignores_non_ibc_move_events() {
+        let zkgm = sui_event(
+            0,
+            json!({
+                "type": "0xab::zkgm::PacketSend",
+                "parsedJson": { "channel_id": 1 },
+            }),
+        );
+        let coin = aptos_event(
+            0,
+            json!({
+                "type": "0x1::coin::CoinDeposit<0x1::aptos_coin::AptosCoin>",
+                "data": { "amount": "1" },
+            }),
+        );
+
+        assert!(decode_ibc_events(&[&zkgm, &coin]).is_empty());
+    }
+
+    #[test]
+    fn skips_events_that_are_in_multiple_flows() {
+        let data = json!({
+            "type": "wasm-update_client",
+            "attributes": attributes(&[("client_id", "5"), ("counterparty_height", "42")]),
+        });
+
+        let records = decode_ibc_events(&[
+            &tendermint_event(7, "flow-a", data.clone()),
+            &tendermint_event(7, "flow-b", data),
+        ]);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].event,
+            IbcEvent::UpdateClient {
+                client_id: ClientId::from_raw(5).unwrap(),
+                height: 42,
+            }
+        );
+    }
+}
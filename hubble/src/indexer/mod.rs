@@ -1,5 +1,5 @@
 pub mod api;
-// pub mod aptos;
+pub mod aptos;
 mod consumer;
 pub mod dummy;
 pub mod ethereum;
@@ -7,9 +7,13 @@ pub mod event;
 mod fetcher;
 mod finalizer;
 mod fixer;
+mod ibc;
+mod move_chain;
+mod move_types;
 pub mod nats;
 mod postgres;
 mod publisher;
+pub mod sui;
 pub mod tendermint;
 
 use std::{future::Future, time::Duration};
@@ -0,0 +1,290 @@
+//! The block handle shared by the indexers of move based chains (sui and aptos).
+//!
+//! Blocks (sui checkpoints and aptos blocks) are final once they are produced. Only the events of
+//! the contracts that are registered for a chain are indexed, the rest of the block is skipped.
+
+use std::fmt::{Debug, Display};
+
+use axum::async_trait;
+use color_eyre::eyre::Report;
+use futures::{stream::FuturesOrdered, Stream};
+use itertools::Itertools;
+use serde_json::Value;
+use sqlx::Postgres;
+use time::OffsetDateTime;
+use tracing::debug;
+use url::Url;
+
+use crate::{
+    indexer::{
+        api::{
+            BlockHandle, BlockHeight, BlockRange, BlockReference, BlockReferenceProvider,
+            BlockSelection, FetchMode, IndexerError,
+        },
+        event::{BlockEvent, BlockEvents},
+        move_types::event_address,
+    },
+    postgres::ChainId,
+};
+
+/// A client of a move based chain, that fetches blocks and the events in them.
+#[async_trait]
+pub trait MoveClient: Clone + Send + Sync + Sized + 'static {
+    /// A block as returned by the provider, containing enough information to fetch its events.
+    type Block: BlockReferenceProvider + Clone + Send + Sync;
+    /// The provider a block was fetched from. Details and ranges are fetched from the same
+    /// provider, since other providers could be behind.
+    type ProviderId: Copy + Debug + Send + Sync;
+    /// The position of a transaction on chain: the index of the transaction in the checkpoint for
+    /// sui, and the ledger version of the transaction for aptos.
+    type TransactionPosition: Clone + Debug + Send + Sync;
+
+    async fn fetch_single_with_provider(
+        &self,
+        selection: BlockSelection,
+        mode: FetchMode,
+        provider_id: Option<Self::ProviderId>,
+    ) -> Result<MoveBlockHandle<Self>, IndexerError>;
+
+    async fn fetch_details(
+        &self,
+        block: &Self::Block,
+        provider_id: Self::ProviderId,
+    ) -> Result<Option<BlockInsert<Self::TransactionPosition>>, IndexerError>;
+
+    /// The [`BlockEvent`] that `event`, emitted in `block`, is published as.
+    fn block_event(
+        block: &BlockInsert<Self::TransactionPosition>,
+        event: EventInsert<Self::TransactionPosition>,
+    ) -> BlockEvent;
+}
+
+#[derive(Clone)]
+pub struct MoveContext {
+    pub rpc_urls: Vec<Url>,
+}
+
+impl Display for MoveContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rpc_urls: {}",
+            self.rpc_urls
+                .iter()
+                .enumerate()
+                .map(|(index, url)| format!("{}: {}", index, url.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+#[derive(Clone)]
+pub enum BlockDetails<C: MoveClient> {
+    Lazy(C::Block),
+    Eager(Option<BlockInsert<C::TransactionPosition>>),
+}
+
+#[must_use]
+#[derive(Debug, Clone)]
+pub struct BlockInsert<P> {
+    pub chain_id: ChainId,
+    pub hash: String,
+    pub height: BlockHeight,
+    pub time: OffsetDateTime,
+    pub events: Vec<EventInsert<P>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EventInsert<P> {
+    pub transaction_hash: String,
+    pub transaction_position: P,
+    /// The index of the event in the block.
+    pub index: i32,
+    pub data: Value,
+}
+
+/// Select the events emitted by one of `addresses` from all `events` of a block. The events are
+/// given in the order they were emitted, along with the hash and position of their transaction.
+///
+/// The index is the index of the event in the block, including events that are not indexed. This
+/// keeps the index stable when addresses are added.
+pub fn filter_events<P>(
+    events: impl IntoIterator<Item = (String, P, Value)>,
+    addresses: &[String],
+) -> Vec<EventInsert<P>> {
+    events
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (_, _, data))| {
+            event_address(data).is_some_and(|address| addresses.contains(&address))
+        })
+        .map(
+            |(index, (transaction_hash, transaction_position, data))| EventInsert {
+                transaction_hash,
+                transaction_position,
+                index: index.try_into().unwrap(),
+                data,
+            },
+        )
+        .collect_vec()
+}
+
+// provides original insert details during migration.
+pub fn insert_batch_events<C: MoveClient>(
+    blocks: impl IntoIterator<Item = BlockInsert<C::TransactionPosition>>,
+) -> Vec<BlockEvent> {
+    blocks
+        .into_iter()
+        .flat_map(|mut block| {
+            std::mem::take(&mut block.events)
+                .into_iter()
+                .map(|event| C::block_event(&block, event))
+                .collect_vec()
+        })
+        .collect_vec()
+}
+
+#[derive(Clone)]
+pub struct MoveBlockHandle<C: MoveClient> {
+    pub reference: BlockReference,
+    pub details: BlockDetails<C>,
+    pub client: C,
+    pub provider_id: C::ProviderId,
+}
+
+impl<C: MoveClient> MoveBlockHandle<C> {
+    async fn get_block_insert(
+        &self,
+    ) -> Result<Option<BlockInsert<C::TransactionPosition>>, Report> {
+        Ok(match self.details.clone() {
+            BlockDetails::Eager(block_insert) => block_insert,
+            BlockDetails::Lazy(block) => {
+                self.client.fetch_details(&block, self.provider_id).await?
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl<C: MoveClient> BlockHandle for MoveBlockHandle<C> {
+    fn reference(&self) -> BlockReference {
+        self.reference.clone()
+    }
+
+    fn fetch_range(
+        &self,
+        block_range: BlockRange,
+        fetch_mode: FetchMode,
+    ) -> Result<impl Stream<Item = Result<Self, IndexerError>>, IndexerError> {
+        debug!("{}: fetching", block_range);
+
+        Ok(FuturesOrdered::from_iter(
+            block_range.clone().into_iter().map(|height| async move {
+                self.client
+                    .fetch_single_with_provider(
+                        BlockSelection::Height(height),
+                        fetch_mode,
+                        Some(self.provider_id),
+                    )
+                    .await
+            }),
+        ))
+    }
+
+    async fn insert(
+        &self,
+        _tx: &mut sqlx::Transaction<'_, Postgres>,
+    ) -> Result<Option<BlockEvents>, IndexerError> {
+        let reference = self.reference();
+        debug!("{}: inserting", reference);
+
+        let block_to_insert = self.get_block_insert().await?;
+
+        let events = match block_to_insert {
+            Some(block_to_insert) => {
+                debug!(
+                    "{}: block with events ({}) => insert",
+                    reference,
+                    block_to_insert.events.len()
+                );
+
+                insert_batch_events::<C>(vec![block_to_insert])
+            }
+            None => {
+                debug!("{}: block without events => ignore", reference);
+
+                vec![]
+            }
+        };
+
+        debug!("{}: done", reference);
+
+        Ok((!events.is_empty()).then_some(BlockEvents::new(events)))
+    }
+
+    async fn update(
+        &self,
+        _tx: &mut sqlx::Transaction<'_, Postgres>,
+    ) -> Result<Option<BlockEvents>, IndexerError> {
+        let reference = self.reference();
+        debug!("{}: updating", reference);
+
+        let block_to_insert = self.get_block_insert().await?;
+
+        let events = if let Some(block_to_insert) = block_to_insert {
+            debug!(
+                "{}: block with events ({}) => upsert",
+                reference,
+                block_to_insert.events.len()
+            );
+            insert_batch_events::<C>(vec![block_to_insert])
+        } else {
+            debug!("{}: block without events => delete", reference);
+            vec![]
+        };
+
+        Ok((!events.is_empty()).then_some(BlockEvents::new(events)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::indexer::move_types::normalize_address;
+
+    #[test]
+    fn filters_events_by_address() {
+        let event = |address: &str| json!({ "type": format!("{address}::ibc::PacketSend") });
+
+        let events = filter_events(
+            [
+                ("tx-a".to_string(), 0, event("0x1")),
+                ("tx-a".to_string(), 0, event("0xab")),
+                ("tx-b".to_string(), 1, json!({ "data": {} })),
+                ("tx-b".to_string(), 1, event("0xAB")),
+            ],
+            &[normalize_address("0xab")],
+        );
+
+        assert_eq!(
+            events,
+            [
+                EventInsert {
+                    transaction_hash: "tx-a".to_string(),
+                    transaction_position: 0,
+                    index: 1,
+                    data: event("0xab"),
+                },
+                EventInsert {
+                    transaction_hash: "tx-b".to_string(),
+                    transaction_position: 1,
+                    index: 3,
+                    data: event("0xAB"),
+                },
+            ]
+        );
+    }
+}
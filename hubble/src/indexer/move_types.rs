@@ -0,0 +1,69 @@
+//! Helpers shared by the indexers of move based chains (sui and aptos).
+
+use serde_json::Value;
+
+/// Normalize a move address, such that the short and long form of the same address are equal.
+pub fn normalize_address(address: &str) -> String {
+    let address = address.strip_prefix("0x").unwrap_or(address);
+
+    format!("0x{:0>64}", address.to_lowercase())
+}
+
+/// The (normalized) address of the module that defines the type of the event.
+///
+/// Both sui and aptos events have a `type` field, containing the fully qualified name of the
+/// event's struct (`<address>::<module>::<name>`).
+pub fn event_address(event: &Value) -> Option<String> {
+    let (address, _) = event.get("type")?.as_str()?.split_once("::")?;
+
+    Some(normalize_address(address))
+}
+
+/// The module and name of the event's struct, e.g. `("ibc", "PacketSend")` for an event of type
+/// `0x1::ibc::PacketSend`.
+pub fn event_type(event: &Value) -> Option<(&str, &str)> {
+    let (_, r#type) = event.get("type")?.as_str()?.split_once("::")?;
+
+    r#type.split_once("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn normalizes_short_and_long_addresses() {
+        assert_eq!(
+            normalize_address("0x1"),
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            normalize_address("0x00000000000000000000000000000000000000000000000000000000000000AB"),
+            normalize_address("ab")
+        );
+    }
+
+    #[test]
+    fn extracts_event_address() {
+        assert_eq!(
+            event_address(&json!({ "type": "0xab::ibc::PacketSend", "data": {} })),
+            Some(normalize_address("0xab"))
+        );
+        assert_eq!(
+            event_address(&json!({ "type": "0x1::coin::CoinDeposit<0x1::aptos_coin::AptosCoin>" })),
+            Some(normalize_address("0x1"))
+        );
+        assert_eq!(event_address(&json!({ "data": {} })), None);
+    }
+
+    #[test]
+    fn extracts_event_type() {
+        assert_eq!(
+            event_type(&json!({ "type": "0xab::ibc::PacketSend", "data": {} })),
+            Some(("ibc", "PacketSend"))
+        );
+        assert_eq!(event_type(&json!({ "type": "0xab" })), None);
+    }
+}
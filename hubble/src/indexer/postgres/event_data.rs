@@ -4,6 +4,8 @@ use tracing::debug;
 use crate::indexer::{
     api::{BlockHeight, UniversalChainId},
     event::BlockEvent,
+    ibc::decode_ibc_events,
+    postgres::ibc_events::{delete_ibc_events_at_height, insert_ibc_events},
 };
 
 pub async fn delete_event_data_at_height(
@@ -24,6 +26,12 @@ pub async fn delete_event_data_at_height(
             ),
             delete_cosmos_blocks AS (
                 DELETE FROM v2_cosmos.blocks WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+            ),
+            delete_sui_events AS (
+                DELETE FROM v2_sui.events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+            ),
+            delete_aptos_events AS (
+                DELETE FROM v2_aptos.events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
             )
             DELETE FROM v2_evm.logs WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
             ",
@@ -33,6 +41,8 @@ pub async fn delete_event_data_at_height(
         .execute(tx.as_mut())
         .await?;
 
+        delete_ibc_events_at_height(tx, universal_chain_id, height).await?;
+
         true
     } else {
         debug!("delete_event_data_at_height: {universal_chain_id}@{height} => nothing to delete");
@@ -58,6 +68,10 @@ async fn has_event_data_at_height(
         SELECT 1 AS exists FROM v2_cosmos.blocks WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
         UNION ALL
         SELECT 1 AS exists FROM v2_evm.logs WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+        UNION ALL
+        SELECT 1 AS exists FROM v2_sui.events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+        UNION ALL
+        SELECT 1 AS exists FROM v2_aptos.events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
         LIMIT 1
         ",
         universal_chain_id,
@@ -147,10 +161,60 @@ pub async fn insert_event_data_with_events(
                 time,
                 flow)
             .execute(tx.as_mut()),
+            BlockEvent::SuiEvent {
+                internal_chain_id,
+                block_hash,
+                height,
+                transaction_hash,
+                transaction_index,
+                index,
+                data,
+                time,
+            } => sqlx::query!(
+                "
+                INSERT INTO v2_sui.events (internal_chain_id, block_hash, height, transaction_hash, transaction_index, index, data, time)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ",
+                internal_chain_id,
+                block_hash,
+                i64::try_from(*height).expect("height fits"),
+                transaction_hash,
+                transaction_index,
+                index,
+                data,
+                time
+            )
+            .execute(tx.as_mut()),
+            BlockEvent::AptosEvent {
+                internal_chain_id,
+                block_hash,
+                height,
+                transaction_hash,
+                version,
+                index,
+                data,
+                time,
+            } => sqlx::query!(
+                "
+                INSERT INTO v2_aptos.events (internal_chain_id, block_hash, height, transaction_hash, version, index, data, time)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ",
+                internal_chain_id,
+                block_hash,
+                i64::try_from(*height).expect("height fits"),
+                transaction_hash,
+                i64::try_from(*version).expect("version fits"),
+                index,
+                data,
+                time
+            )
+            .execute(tx.as_mut()),
         }
         .await?;
     }
 
+    insert_ibc_events(tx, &decode_ibc_events(block_events)).await?;
+
     Ok(true)
 }
 
@@ -166,7 +230,9 @@ pub async fn max_event_height(
             (SELECT MAX(height) FROM v2_cosmos.events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1)),
             (SELECT MAX(height) FROM v2_cosmos.transactions WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1)),
             (SELECT MAX(height) FROM v2_cosmos.blocks WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1)),
-            (SELECT MAX(height) FROM v2_evm.logs WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1))
+            (SELECT MAX(height) FROM v2_evm.logs WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1)),
+            (SELECT MAX(height) FROM v2_sui.events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1)),
+            (SELECT MAX(height) FROM v2_aptos.events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1))
         ) AS max_height
          ",
         universal_chain_id,
@@ -0,0 +1,921 @@
+use ibc_union_spec::{
+    event::{
+        BatchSend, ChannelMetadata, ChannelOpenAck, ChannelOpenConfirm, ChannelOpenInit,
+        ChannelOpenTry, ClientMisbehaviour, ConnectionMetadata, ConnectionOpenAck,
+        ConnectionOpenConfirm, ConnectionOpenInit, ConnectionOpenTry, CreateClient, FullEvent,
+        IntentPacketRecv, PacketAck, PacketMetadata, PacketRecv, PacketSend, PacketTimeout,
+        UpdateClient, WriteAck,
+    },
+    ChannelId, ClientId, Connection, ConnectionId, ConnectionState, Packet, Timestamp,
+};
+use sqlx::{types::BigDecimal, Postgres, Transaction};
+use tracing::{debug, warn};
+use unionlabs::primitives::{Bytes, H256};
+use voyager_primitives::ClientType;
+
+use crate::indexer::{
+    api::{BlockHeight, UniversalChainId},
+    ibc::{IbcEvent, IbcEventRecord},
+};
+
+/// Persist the decoded IBC events of a block, in the order they were emitted.
+///
+/// Every event is resolved into a [`FullEvent`] and stored in `v2_ibc.events`. The clients,
+/// connections, channels and packets that are required to resolve later events are tracked in
+/// their own tables. Events that cannot be resolved yet (i.e. a packet that is received before the
+/// chain it was sent from is indexed) are stored in `v2_ibc.unresolved_events`, and are resolved
+/// once the packet is indexed.
+pub async fn insert_ibc_events(
+    tx: &mut Transaction<'_, Postgres>,
+    records: &[IbcEventRecord],
+) -> sqlx::Result<()> {
+    for record in records {
+        update_state(tx, record).await?;
+
+        match resolve(tx, record.internal_chain_id, record.height, &record.event).await? {
+            Some(event) => insert_resolved_event(tx, record, &event).await?,
+            None => insert_unresolved_event(tx, record).await?,
+        }
+
+        if let IbcEvent::PacketSend { packet } = &record.event {
+            resolve_pending_events(tx, packet.hash()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn delete_ibc_events_at_height(
+    tx: &mut Transaction<'_, Postgres>,
+    universal_chain_id: &UniversalChainId,
+    height: BlockHeight,
+) -> sqlx::Result<()> {
+    debug!("delete_ibc_events_at_height: {universal_chain_id}@{height}");
+
+    sqlx::query!(
+        "
+        WITH delete_clients AS (
+            DELETE FROM v2_ibc.clients WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+        ),
+        delete_connections AS (
+            DELETE FROM v2_ibc.connections WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+        ),
+        delete_channels AS (
+            DELETE FROM v2_ibc.channels WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+        ),
+        delete_packets AS (
+            DELETE FROM v2_ibc.packets WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+        ),
+        delete_events AS (
+            DELETE FROM v2_ibc.events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+        )
+        DELETE FROM v2_ibc.unresolved_events WHERE internal_chain_id = (SELECT id FROM config.chains c WHERE c.family || '.' || c.chain_id = $1) AND height = $2
+        ",
+        universal_chain_id,
+        i64::try_from(height).expect("height fits"),
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Track the clients, connections, channels and packets created or updated by `record`.
+async fn update_state(
+    tx: &mut Transaction<'_, Postgres>,
+    record: &IbcEventRecord,
+) -> sqlx::Result<()> {
+    let internal_chain_id = record.internal_chain_id;
+    let height = record.height;
+
+    match &record.event {
+        IbcEvent::CreateClient {
+            client_id,
+            client_type,
+        } => {
+            sqlx::query!(
+                "
+                INSERT INTO v2_ibc.clients (internal_chain_id, client_id, height, client_type)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (internal_chain_id, client_id) DO UPDATE SET height = excluded.height, client_type = excluded.client_type
+                ",
+                internal_chain_id,
+                i64::from(client_id.raw()),
+                i64::try_from(height).expect("height fits"),
+                client_type,
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+        IbcEvent::ConnectionOpenInit {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+        } => {
+            let connection = Connection {
+                state: ConnectionState::Init,
+                client_id: *client_id,
+                counterparty_client_id: *counterparty_client_id,
+                counterparty_connection_id: None,
+            };
+
+            insert_connection(tx, internal_chain_id, height, *connection_id, &connection).await?;
+        }
+        IbcEvent::ConnectionOpenTry {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        }
+        | IbcEvent::ConnectionOpenAck {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        }
+        | IbcEvent::ConnectionOpenConfirm {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        } => {
+            let connection = Connection {
+                state: match record.event {
+                    IbcEvent::ConnectionOpenTry { .. } => ConnectionState::TryOpen,
+                    _ => ConnectionState::Open,
+                },
+                client_id: *client_id,
+                counterparty_client_id: *counterparty_client_id,
+                counterparty_connection_id: Some(*counterparty_connection_id),
+            };
+
+            insert_connection(tx, internal_chain_id, height, *connection_id, &connection).await?;
+        }
+        IbcEvent::ChannelOpenInit {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            connection_id,
+            version,
+        } => {
+            let channel = ChannelRow {
+                port_id: port_id.clone(),
+                counterparty_port_id: counterparty_port_id.clone(),
+                counterparty_channel_id: None,
+                connection_id: *connection_id,
+                version: version.clone(),
+            };
+
+            insert_channel(tx, internal_chain_id, height, *channel_id, &channel).await?;
+        }
+        IbcEvent::ChannelOpenTry {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection_id,
+            counterparty_version,
+        } => {
+            let channel = ChannelRow {
+                port_id: port_id.clone(),
+                counterparty_port_id: counterparty_port_id.clone(),
+                counterparty_channel_id: Some(*counterparty_channel_id),
+                connection_id: *connection_id,
+                version: counterparty_version.clone(),
+            };
+
+            insert_channel(tx, internal_chain_id, height, *channel_id, &channel).await?;
+        }
+        IbcEvent::ChannelOpenAck {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection_id,
+        }
+        | IbcEvent::ChannelOpenConfirm {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection_id,
+        } => {
+            // the version is only emitted in the first step of the handshake on each chain
+            let Some(previous) = channel(tx, internal_chain_id, height, *channel_id).await? else {
+                warn!(
+                    %internal_chain_id,
+                    %height,
+                    %channel_id,
+                    "channel handshake was not indexed, unable to track channel"
+                );
+                return Ok(());
+            };
+
+            let channel = ChannelRow {
+                port_id: port_id.clone(),
+                counterparty_port_id: counterparty_port_id.clone(),
+                counterparty_channel_id: Some(*counterparty_channel_id),
+                connection_id: *connection_id,
+                version: previous.version,
+            };
+
+            insert_channel(tx, internal_chain_id, height, *channel_id, &channel).await?;
+        }
+        IbcEvent::PacketSend { packet } => {
+            sqlx::query!(
+                "
+                INSERT INTO v2_ibc.packets (packet_hash, internal_chain_id, height, source_channel_id, destination_channel_id, data, timeout_height, timeout_timestamp)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (packet_hash) DO NOTHING
+                ",
+                packet.hash().as_ref(),
+                internal_chain_id,
+                i64::try_from(height).expect("height fits"),
+                i64::from(packet.source_channel_id.raw()),
+                i64::from(packet.destination_channel_id.raw()),
+                packet.data.as_ref(),
+                BigDecimal::from(packet.timeout_height),
+                BigDecimal::from(packet.timeout_timestamp.as_nanos()),
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+        IbcEvent::UpdateClient { .. }
+        | IbcEvent::ClientMisbehaviour { .. }
+        | IbcEvent::BatchSend { .. }
+        | IbcEvent::PacketRecv { .. }
+        | IbcEvent::IntentPacketRecv { .. }
+        | IbcEvent::WriteAck { .. }
+        | IbcEvent::PacketAck { .. }
+        | IbcEvent::PacketTimeout { .. } => {}
+    }
+
+    Ok(())
+}
+
+/// Construct the [`FullEvent`] for `event`, emitted on `internal_chain_id` at `height`. Returns
+/// `None` if the required clients, connections, channels or packets have not been indexed.
+async fn resolve(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+    event: &IbcEvent,
+) -> sqlx::Result<Option<FullEvent>> {
+    macro_rules! require {
+        ($expr:expr) => {
+            match $expr {
+                Some(value) => value,
+                None => return Ok(None),
+            }
+        };
+    }
+
+    Ok(Some(match event.clone() {
+        IbcEvent::CreateClient {
+            client_id,
+            client_type,
+        } => CreateClient {
+            client_type: ClientType::new(client_type),
+            client_id,
+        }
+        .into(),
+        IbcEvent::UpdateClient {
+            client_id,
+            height: counterparty_height,
+        } => UpdateClient {
+            client_type: require!(client_type(tx, internal_chain_id, client_id).await?),
+            client_id,
+            height: counterparty_height,
+        }
+        .into(),
+        IbcEvent::ClientMisbehaviour { client_id } => ClientMisbehaviour {
+            client_type: require!(client_type(tx, internal_chain_id, client_id).await?),
+            client_id,
+        }
+        .into(),
+        IbcEvent::ConnectionOpenInit {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+        } => ConnectionOpenInit {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+        }
+        .into(),
+        IbcEvent::ConnectionOpenTry {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        } => ConnectionOpenTry {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        }
+        .into(),
+        IbcEvent::ConnectionOpenAck {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        } => ConnectionOpenAck {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        }
+        .into(),
+        IbcEvent::ConnectionOpenConfirm {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        } => ConnectionOpenConfirm {
+            connection_id,
+            client_id,
+            counterparty_client_id,
+            counterparty_connection_id,
+        }
+        .into(),
+        IbcEvent::ChannelOpenInit {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            connection_id,
+            version,
+        } => ChannelOpenInit {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            connection: require!(connection(tx, internal_chain_id, height, connection_id).await?),
+            version,
+        }
+        .into(),
+        IbcEvent::ChannelOpenTry {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection_id,
+            counterparty_version,
+        } => ChannelOpenTry {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection: require!(connection(tx, internal_chain_id, height, connection_id).await?),
+            version: counterparty_version,
+        }
+        .into(),
+        IbcEvent::ChannelOpenAck {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection_id,
+        } => ChannelOpenAck {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection: require!(connection(tx, internal_chain_id, height, connection_id).await?),
+            version: require!(channel(tx, internal_chain_id, height, channel_id).await?).version,
+        }
+        .into(),
+        IbcEvent::ChannelOpenConfirm {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection_id,
+        } => ChannelOpenConfirm {
+            port_id,
+            channel_id,
+            counterparty_port_id,
+            counterparty_channel_id,
+            connection: require!(connection(tx, internal_chain_id, height, connection_id).await?),
+            version: require!(channel(tx, internal_chain_id, height, channel_id).await?).version,
+        }
+        .into(),
+        IbcEvent::PacketSend { packet } => PacketSend {
+            packet: require!(
+                packet_metadata(tx, internal_chain_id, height, &packet, Side::Source).await?
+            ),
+            packet_data: packet.data,
+        }
+        .into(),
+        IbcEvent::BatchSend {
+            channel_id,
+            batch_hash,
+        } => {
+            let (source_channel, destination_channel) =
+                require!(channel_metadata(tx, internal_chain_id, height, channel_id).await?);
+
+            BatchSend {
+                batch_hash,
+                source_channel,
+                destination_channel,
+            }
+            .into()
+        }
+        IbcEvent::PacketRecv {
+            packet_hash,
+            maker_msg,
+            ..
+        } => {
+            let packet = require!(packet(tx, packet_hash).await?);
+
+            PacketRecv {
+                packet: require!(
+                    packet_metadata(tx, internal_chain_id, height, &packet, Side::Destination)
+                        .await?
+                ),
+                packet_data: packet.data,
+                maker_msg,
+            }
+            .into()
+        }
+        IbcEvent::IntentPacketRecv {
+            packet_hash,
+            maker_msg,
+            ..
+        } => {
+            let packet = require!(packet(tx, packet_hash).await?);
+
+            IntentPacketRecv {
+                packet: require!(
+                    packet_metadata(tx, internal_chain_id, height, &packet, Side::Destination)
+                        .await?
+                ),
+                packet_data: packet.data,
+                market_maker_msg: maker_msg,
+            }
+            .into()
+        }
+        IbcEvent::WriteAck {
+            packet_hash,
+            acknowledgement,
+            ..
+        } => {
+            let packet = require!(packet(tx, packet_hash).await?);
+
+            WriteAck {
+                packet: require!(
+                    packet_metadata(tx, internal_chain_id, height, &packet, Side::Destination)
+                        .await?
+                ),
+                packet_data: packet.data,
+                acknowledgement,
+            }
+            .into()
+        }
+        IbcEvent::PacketAck {
+            packet_hash,
+            acknowledgement,
+            ..
+        } => {
+            let packet = require!(packet(tx, packet_hash).await?);
+
+            PacketAck {
+                packet: require!(
+                    packet_metadata(tx, internal_chain_id, height, &packet, Side::Source).await?
+                ),
+                packet_data: packet.data,
+                acknowledgement,
+            }
+            .into()
+        }
+        IbcEvent::PacketTimeout { packet_hash, .. } => {
+            let packet = require!(packet(tx, packet_hash).await?);
+
+            PacketTimeout {
+                packet: require!(
+                    packet_metadata(tx, internal_chain_id, height, &packet, Side::Source).await?
+                ),
+                packet_data: packet.data,
+            }
+            .into()
+        }
+    }))
+}
+
+async fn insert_resolved_event(
+    tx: &mut Transaction<'_, Postgres>,
+    record: &IbcEventRecord,
+    event: &FullEvent,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        "
+        INSERT INTO v2_ibc.events (internal_chain_id, block_hash, height, transaction_hash, event_index, time, event_type, packet_hash, data)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        ",
+        record.internal_chain_id,
+        record.block_hash,
+        i64::try_from(record.height).expect("height fits"),
+        record.transaction_hash as _,
+        record.event_index,
+        record.time,
+        event.name(),
+        record.event.packet_hash().map(Vec::from) as _,
+        serde_json::to_value(event).expect("event should be json serializable"),
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    insert_packet_event(tx, record, event).await
+}
+
+/// Store the typed fields of packet lifecycle events in `v2_ibc.packet_events`, so that they can be
+/// queried without going through the json in `v2_ibc.events`.
+async fn insert_packet_event(
+    tx: &mut Transaction<'_, Postgres>,
+    record: &IbcEventRecord,
+    event: &FullEvent,
+) -> sqlx::Result<()> {
+    let (packet, maker_msg, acknowledgement) = match event {
+        FullEvent::PacketSend(e) => (e.packet(), None, None),
+        FullEvent::PacketRecv(e) => (e.packet(), Some(&e.maker_msg), None),
+        FullEvent::IntentPacketRecv(e) => (e.packet(), Some(&e.market_maker_msg), None),
+        FullEvent::WriteAck(e) => (e.packet(), None, Some(&e.acknowledgement)),
+        FullEvent::PacketAck(e) => (e.packet(), None, Some(&e.acknowledgement)),
+        FullEvent::PacketTimeout(e) => (e.packet(), None, None),
+        _ => return Ok(()),
+    };
+
+    sqlx::query(
+        "
+        INSERT INTO v2_ibc.packet_events (internal_chain_id, height, event_index, event_type, packet_hash, source_channel_id, destination_channel_id, timeout_height, timeout_timestamp, maker_msg, acknowledgement)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        ",
+    )
+    .bind(record.internal_chain_id)
+    .bind(i64::try_from(record.height).expect("height fits"))
+    .bind(record.event_index)
+    .bind(event.name())
+    .bind(Vec::from(packet.hash()))
+    .bind(i64::from(packet.source_channel_id.raw()))
+    .bind(i64::from(packet.destination_channel_id.raw()))
+    .bind(BigDecimal::from(packet.timeout_height))
+    .bind(BigDecimal::from(packet.timeout_timestamp.as_nanos()))
+    .bind(maker_msg.map(|msg| msg.as_ref()))
+    .bind(acknowledgement.map(|ack| ack.as_ref()))
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+async fn insert_unresolved_event(
+    tx: &mut Transaction<'_, Postgres>,
+    record: &IbcEventRecord,
+) -> sqlx::Result<()> {
+    debug!(
+        internal_chain_id = %record.internal_chain_id,
+        height = %record.height,
+        event_index = %record.event_index,
+        "unable to resolve ibc event"
+    );
+
+    sqlx::query!(
+        "
+        INSERT INTO v2_ibc.unresolved_events (internal_chain_id, block_hash, height, transaction_hash, event_index, time, packet_hash, data)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ",
+        record.internal_chain_id,
+        record.block_hash,
+        i64::try_from(record.height).expect("height fits"),
+        record.transaction_hash as _,
+        record.event_index,
+        record.time,
+        record.event.packet_hash().map(Vec::from) as _,
+        serde_json::to_value(&record.event).expect("event should be json serializable"),
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+/// Resolve the events for the packet with `packet_hash` that were indexed before the packet was.
+async fn resolve_pending_events(
+    tx: &mut Transaction<'_, Postgres>,
+    packet_hash: H256,
+) -> sqlx::Result<()> {
+    let pending = sqlx::query!(
+        "
+        SELECT internal_chain_id, block_hash, height, transaction_hash, event_index, time, data
+        FROM v2_ibc.unresolved_events
+        WHERE packet_hash = $1
+        ",
+        packet_hash.as_ref(),
+    )
+    .fetch_all(tx.as_mut())
+    .await?;
+
+    for row in pending {
+        let record = IbcEventRecord {
+            internal_chain_id: row.internal_chain_id,
+            block_hash: row.block_hash,
+            height: BlockHeight::try_from(row.height).expect("height fits"),
+            transaction_hash: row.transaction_hash,
+            event_index: row.event_index,
+            time: row.time,
+            event: serde_json::from_value(row.data).expect("unresolved events are valid"),
+        };
+
+        let Some(event) =
+            resolve(tx, record.internal_chain_id, record.height, &record.event).await?
+        else {
+            continue;
+        };
+
+        debug!(
+            internal_chain_id = %record.internal_chain_id,
+            height = %record.height,
+            event_index = %record.event_index,
+            %packet_hash,
+            "resolved pending ibc event"
+        );
+
+        insert_resolved_event(tx, &record, &event).await?;
+
+        sqlx::query!(
+            "
+            DELETE FROM v2_ibc.unresolved_events
+            WHERE internal_chain_id = $1 AND height = $2 AND event_index = $3
+            ",
+            record.internal_chain_id,
+            i64::try_from(record.height).expect("height fits"),
+            record.event_index,
+        )
+        .execute(tx.as_mut())
+        .await?;
+    }
+
+    Ok(())
+}
+
+struct ChannelRow {
+    port_id: Bytes,
+    counterparty_port_id: Bytes,
+    counterparty_channel_id: Option<ChannelId>,
+    connection_id: ConnectionId,
+    version: String,
+}
+
+/// Which end of the packet the chain an event was emitted on is.
+enum Side {
+    Source,
+    Destination,
+}
+
+async fn insert_connection(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+    connection_id: ConnectionId,
+    connection: &Connection,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        "
+        INSERT INTO v2_ibc.connections (internal_chain_id, connection_id, height, state, client_id, counterparty_client_id, counterparty_connection_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        ON CONFLICT (internal_chain_id, connection_id, height) DO UPDATE SET state = excluded.state, client_id = excluded.client_id, counterparty_client_id = excluded.counterparty_client_id, counterparty_connection_id = excluded.counterparty_connection_id
+        ",
+        internal_chain_id,
+        i64::from(connection_id.raw()),
+        i64::try_from(height).expect("height fits"),
+        connection.state as i16,
+        i64::from(connection.client_id.raw()),
+        i64::from(connection.counterparty_client_id.raw()),
+        connection.counterparty_connection_id.map(|id| i64::from(id.raw())) as _,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+async fn insert_channel(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+    channel_id: ChannelId,
+    channel: &ChannelRow,
+) -> sqlx::Result<()> {
+    sqlx::query!(
+        "
+        INSERT INTO v2_ibc.channels (internal_chain_id, channel_id, height, port_id, counterparty_port_id, counterparty_channel_id, connection_id, version)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (internal_chain_id, channel_id, height) DO UPDATE SET port_id = excluded.port_id, counterparty_port_id = excluded.counterparty_port_id, counterparty_channel_id = excluded.counterparty_channel_id, connection_id = excluded.connection_id, version = excluded.version
+        ",
+        internal_chain_id,
+        i64::from(channel_id.raw()),
+        i64::try_from(height).expect("height fits"),
+        channel.port_id.as_ref(),
+        channel.counterparty_port_id.as_ref(),
+        channel.counterparty_channel_id.map(|id| i64::from(id.raw())) as _,
+        i64::from(channel.connection_id.raw()),
+        channel.version,
+    )
+    .execute(tx.as_mut())
+    .await?;
+
+    Ok(())
+}
+
+async fn client_type(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    client_id: ClientId,
+) -> sqlx::Result<Option<ClientType>> {
+    Ok(sqlx::query!(
+        "
+        SELECT client_type
+        FROM v2_ibc.clients
+        WHERE internal_chain_id = $1 AND client_id = $2
+        ",
+        internal_chain_id,
+        i64::from(client_id.raw()),
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|record| ClientType::new(record.client_type)))
+}
+
+/// The connection as of `height`.
+async fn connection(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+    connection_id: ConnectionId,
+) -> sqlx::Result<Option<Connection>> {
+    Ok(sqlx::query!(
+        "
+        SELECT state, client_id, counterparty_client_id, counterparty_connection_id
+        FROM v2_ibc.connections
+        WHERE internal_chain_id = $1 AND connection_id = $2 AND height <= $3
+        ORDER BY height DESC
+        LIMIT 1
+        ",
+        internal_chain_id,
+        i64::from(connection_id.raw()),
+        i64::try_from(height).expect("height fits"),
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|record| Connection {
+        state: u8::try_from(record.state)
+            .ok()
+            .and_then(|state| state.try_into().ok())
+            .expect("connection state is valid"),
+        client_id: id_from_db(record.client_id),
+        counterparty_client_id: id_from_db(record.counterparty_client_id),
+        counterparty_connection_id: record.counterparty_connection_id.map(id_from_db),
+    }))
+}
+
+/// The channel as of `height`.
+async fn channel(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+    channel_id: ChannelId,
+) -> sqlx::Result<Option<ChannelRow>> {
+    Ok(sqlx::query!(
+        "
+        SELECT port_id, counterparty_port_id, counterparty_channel_id, connection_id, version
+        FROM v2_ibc.channels
+        WHERE internal_chain_id = $1 AND channel_id = $2 AND height <= $3
+        ORDER BY height DESC
+        LIMIT 1
+        ",
+        internal_chain_id,
+        i64::from(channel_id.raw()),
+        i64::try_from(height).expect("height fits"),
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|record| ChannelRow {
+        port_id: record.port_id.into(),
+        counterparty_port_id: record.counterparty_port_id.into(),
+        counterparty_channel_id: record.counterparty_channel_id.map(id_from_db),
+        connection_id: id_from_db(record.connection_id),
+        version: record.version,
+    }))
+}
+
+/// The packet with `packet_hash`, which may have been sent from any indexed chain.
+async fn packet(
+    tx: &mut Transaction<'_, Postgres>,
+    packet_hash: H256,
+) -> sqlx::Result<Option<Packet>> {
+    Ok(sqlx::query!(
+        r#"
+        SELECT source_channel_id, destination_channel_id, data, timeout_height::text AS "timeout_height!", timeout_timestamp::text AS "timeout_timestamp!"
+        FROM v2_ibc.packets
+        WHERE packet_hash = $1
+        "#,
+        packet_hash.as_ref(),
+    )
+    .fetch_optional(tx.as_mut())
+    .await?
+    .map(|record| Packet {
+        source_channel_id: id_from_db(record.source_channel_id),
+        destination_channel_id: id_from_db(record.destination_channel_id),
+        data: record.data.into(),
+        timeout_height: record.timeout_height.parse().expect("timeout height fits"),
+        timeout_timestamp: Timestamp::from_nanos(
+            record
+                .timeout_timestamp
+                .parse()
+                .expect("timeout timestamp fits"),
+        ),
+    }))
+}
+
+/// The metadata of `channel_id` as of `height` and of its counterparty, as `(channel, counterparty)`.
+///
+/// The counterparty metadata is derived from the channel and its connection, since the
+/// counterparty chain is not necessarily indexed. Both ends of a channel have the same version.
+async fn channel_metadata(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+    channel_id: ChannelId,
+) -> sqlx::Result<Option<(ChannelMetadata, ChannelMetadata)>> {
+    let Some(channel) = channel(tx, internal_chain_id, height, channel_id).await? else {
+        return Ok(None);
+    };
+
+    let Some(connection) = connection(tx, internal_chain_id, height, channel.connection_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let (Some(counterparty_channel_id), Some(counterparty_connection_id)) = (
+        channel.counterparty_channel_id,
+        connection.counterparty_connection_id,
+    ) else {
+        return Ok(None);
+    };
+
+    Ok(Some((
+        ChannelMetadata {
+            channel_id,
+            version: channel.version.clone(),
+            connection: ConnectionMetadata {
+                client_id: connection.client_id,
+                connection_id: channel.connection_id,
+            },
+        },
+        ChannelMetadata {
+            channel_id: counterparty_channel_id,
+            version: channel.version,
+            connection: ConnectionMetadata {
+                client_id: connection.counterparty_client_id,
+                connection_id: counterparty_connection_id,
+            },
+        },
+    )))
+}
+
+async fn packet_metadata(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+    packet: &Packet,
+    side: Side,
+) -> sqlx::Result<Option<PacketMetadata>> {
+    let channel_id = match side {
+        Side::Source => packet.source_channel_id,
+        Side::Destination => packet.destination_channel_id,
+    };
+
+    let Some((channel, counterparty)) =
+        channel_metadata(tx, internal_chain_id, height, channel_id).await?
+    else {
+        return Ok(None);
+    };
+
+    let (source_channel, destination_channel) = match side {
+        Side::Source => (channel, counterparty),
+        Side::Destination => (counterparty, channel),
+    };
+
+    Ok(Some(PacketMetadata {
+        source_channel,
+        destination_channel,
+        timeout_height: packet.timeout_height,
+        timeout_timestamp: packet.timeout_timestamp,
+    }))
+}
+
+fn id_from_db<T: TryFrom<u32>>(raw: i64) -> T {
+    u32::try_from(raw)
+        .ok()
+        .and_then(|raw| T::try_from(raw).ok())
+        .expect("ids are valid")
+}
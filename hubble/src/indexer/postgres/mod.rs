@@ -2,5 +2,6 @@ pub(crate) mod block_fix;
 pub(crate) mod block_status;
 pub(crate) mod block_update;
 pub(crate) mod event_data;
+pub(crate) mod ibc_events;
 pub(crate) mod indexer_status;
 pub(crate) mod nats;
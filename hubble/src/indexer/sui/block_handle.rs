@@ -0,0 +1,23 @@
+use color_eyre::eyre::Report;
+use time::OffsetDateTime;
+
+use crate::indexer::{
+    api::{BlockReference, BlockReferenceProvider, IndexerError},
+    move_chain::MoveBlockHandle,
+    sui::{fetcher_client::SuiFetcherClient, provider::Checkpoint},
+};
+
+impl BlockReferenceProvider for Checkpoint {
+    fn block_reference(&self) -> Result<BlockReference, Report> {
+        Ok(BlockReference {
+            height: self.sequence_number,
+            hash: self.digest.clone(),
+            timestamp: OffsetDateTime::from_unix_timestamp_nanos(
+                i128::from(self.timestamp_ms) * 1_000_000,
+            )
+            .map_err(|err| IndexerError::ProviderError(err.into()))?,
+        })
+    }
+}
+
+pub type SuiBlockHandle = MoveBlockHandle<SuiFetcherClient>;
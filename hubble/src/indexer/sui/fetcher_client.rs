@@ -0,0 +1,244 @@
+use std::fmt::Display;
+
+use axum::async_trait;
+use tokio::task::JoinSet;
+use tracing::{debug, info, info_span, trace, Instrument};
+
+use crate::{
+    indexer::{
+        api::{
+            BlockHeight, BlockReferenceProvider, BlockSelection, FetchMode, FetcherClient,
+            IndexerError,
+        },
+        event::BlockEvent,
+        move_chain::{
+            filter_events, BlockDetails, BlockInsert, EventInsert, MoveClient, MoveContext,
+        },
+        sui::{
+            block_handle::SuiBlockHandle,
+            postgres::active_contracts,
+            provider::{Checkpoint, Provider, RpcProviderId},
+        },
+    },
+    postgres::{fetch_chain_id_tx, ChainId},
+};
+
+#[derive(Clone)]
+pub struct SuiFetcherClient {
+    pub chain_id: ChainId,
+    pub provider: Provider,
+    pub event_filter: EventFilter,
+}
+
+#[derive(Clone, Debug)]
+pub struct EventFilter {
+    pub chain_id: ChainId,
+    pub pg_pool: sqlx::PgPool,
+}
+
+impl EventFilter {
+    pub(crate) async fn packages_at(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Vec<String>, IndexerError> {
+        Ok(active_contracts(&mut self.pg_pool.begin().await?, self.chain_id.db, height).await?)
+    }
+}
+
+impl Display for SuiFetcherClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "chain_id: {}", self.chain_id)
+    }
+}
+
+#[async_trait]
+impl MoveClient for SuiFetcherClient {
+    type Block = Checkpoint;
+    type ProviderId = RpcProviderId;
+    type TransactionPosition = i32;
+
+    async fn fetch_single_with_provider(
+        &self,
+        selection: BlockSelection,
+        mode: FetchMode,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<SuiBlockHandle, IndexerError> {
+        // checkpoints are final once they are created, so the latest checkpoint is also the last
+        // finalized checkpoint. the checkpoint is fetched from the same provider, which could
+        // otherwise be behind.
+        let (sequence_number, provider_id) = match selection {
+            BlockSelection::LastFinalized => {
+                let latest = self
+                    .provider
+                    .latest_checkpoint_sequence_number(provider_id)
+                    .await?;
+
+                (latest.response, Some(latest.provider_id))
+            }
+            BlockSelection::Height(height) => (height, provider_id),
+        };
+
+        let result = match self.provider.checkpoint(sequence_number, provider_id).await {
+            Ok(result) => result,
+            Err(err) => {
+                // fetching a checkpoint that does not exist yet is an error, so we have to check
+                // whether the checkpoint is expected to exist.
+                let latest = self
+                    .provider
+                    .latest_checkpoint_sequence_number(provider_id)
+                    .await?
+                    .response;
+
+                if sequence_number > latest {
+                    info!("{}: does not exist (latest: {})", selection, latest);
+
+                    return Err(IndexerError::NoBlock(selection));
+                }
+
+                info!("{}: error: {}", selection, err);
+
+                return Err(err.into());
+            }
+        };
+
+        let checkpoint = result.response;
+        debug!(
+            "{}: fetched (provider index: {:?})",
+            selection, result.provider_id
+        );
+
+        Ok(SuiBlockHandle {
+            reference: checkpoint.block_reference()?,
+            details: match mode {
+                FetchMode::Lazy => BlockDetails::Lazy(checkpoint),
+                FetchMode::Eager => {
+                    BlockDetails::Eager(self.fetch_details(&checkpoint, result.provider_id).await?)
+                }
+            },
+            client: self.clone(),
+            provider_id: result.provider_id,
+        })
+    }
+
+    async fn fetch_details(
+        &self,
+        checkpoint: &Checkpoint,
+        provider_id: RpcProviderId,
+    ) -> Result<Option<BlockInsert<i32>>, IndexerError> {
+        let block_reference = checkpoint.block_reference()?;
+
+        info!("{}: fetch", block_reference);
+
+        let packages = self
+            .event_filter
+            .packages_at(block_reference.height)
+            .await?;
+        debug!("{}: packages: {:?}", block_reference, &packages);
+
+        // no need to fetch the transactions if there is nothing to index.
+        if packages.is_empty() || checkpoint.transactions.is_empty() {
+            info!("{}: ignored (no packages or transactions)", block_reference);
+            return Ok(None);
+        }
+
+        let transaction_blocks = self
+            .provider
+            .transaction_blocks(&checkpoint.transactions, provider_id)
+            .await
+            .map_err(|err| IndexerError::ProviderError(err.into()))?;
+
+        let events = filter_events(
+            transaction_blocks.into_iter().enumerate().flat_map(
+                |(transaction_index, transaction_block)| {
+                    let transaction_hash = transaction_block.digest;
+                    let transaction_index: i32 = transaction_index.try_into().unwrap();
+
+                    transaction_block
+                        .events
+                        .into_iter()
+                        .map(move |data| (transaction_hash.clone(), transaction_index, data))
+                },
+            ),
+            &packages,
+        );
+
+        trace!("{}: fetch => events: {}", block_reference, events.len());
+
+        if events.is_empty() {
+            info!("{}: fetch => ignored (no events)", block_reference);
+            return Ok(None);
+        }
+
+        debug!(
+            "{}: fetch => done (events: {})",
+            block_reference,
+            events.len()
+        );
+
+        Ok(Some(BlockInsert {
+            chain_id: self.chain_id,
+            hash: block_reference.hash,
+            height: block_reference.height,
+            time: block_reference.timestamp,
+            events,
+        }))
+    }
+
+    fn block_event(block: &BlockInsert<i32>, event: EventInsert<i32>) -> BlockEvent {
+        BlockEvent::SuiEvent {
+            internal_chain_id: block.chain_id.db,
+            block_hash: block.hash.clone(),
+            height: block.height,
+            transaction_hash: event.transaction_hash,
+            transaction_index: event.transaction_position,
+            index: event.index,
+            data: event.data,
+            time: block.time,
+        }
+    }
+}
+
+#[async_trait]
+impl FetcherClient for SuiFetcherClient {
+    type BlockHandle = SuiBlockHandle;
+    type Context = MoveContext;
+
+    async fn create(
+        pg_pool: sqlx::PgPool,
+        _join_set: &mut JoinSet<Result<(), IndexerError>>,
+        context: MoveContext,
+    ) -> Result<Self, IndexerError> {
+        let provider = Provider::new(context.rpc_urls)?;
+
+        info!("fetching chain-id from node");
+        let chain_id = provider.chain_identifier(None).await?.response;
+        info!("fetched chain-id from node: {}", chain_id);
+
+        let indexing_span = info_span!("indexer", chain_id = %chain_id);
+        async move {
+            let mut tx = pg_pool.begin().await?;
+
+            let chain_id = fetch_chain_id_tx(&mut tx, chain_id).await?;
+
+            tx.commit().await?;
+
+            let event_filter = EventFilter { chain_id, pg_pool };
+
+            Ok(SuiFetcherClient {
+                chain_id,
+                provider,
+                event_filter,
+            })
+        }
+        .instrument(indexing_span)
+        .await
+    }
+
+    async fn fetch_single(
+        &self,
+        selection: BlockSelection,
+        mode: FetchMode,
+    ) -> Result<Self::BlockHandle, IndexerError> {
+        self.fetch_single_with_provider(selection, mode, None).await
+    }
+}
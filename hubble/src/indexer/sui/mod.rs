@@ -0,0 +1,5 @@
+mod block_handle;
+pub mod config;
+mod fetcher_client;
+mod postgres;
+mod provider;
@@ -0,0 +1,30 @@
+use sqlx::{Postgres, Transaction};
+
+use crate::indexer::{api::BlockHeight, move_types::normalize_address};
+
+/// The (normalized) addresses of the packages whose events are indexed at `height`.
+pub async fn active_contracts(
+    tx: &mut Transaction<'_, Postgres>,
+    internal_chain_id: i32,
+    height: BlockHeight,
+) -> sqlx::Result<Vec<String>> {
+    let height: i64 = height.try_into().unwrap();
+
+    let result = sqlx::query!(
+        r#"
+        SELECT    address
+        FROM      v2_sui.contracts
+        WHERE     internal_chain_id = $1
+        AND       $2 between start_height and end_height
+        "#,
+        internal_chain_id,
+        height,
+    )
+    .fetch_all(tx.as_mut())
+    .await?
+    .into_iter()
+    .map(|record| normalize_address(&record.address))
+    .collect();
+
+    Ok(result)
+}
@@ -0,0 +1,156 @@
+use jsonrpsee::{
+    core::{client::ClientT, ClientError},
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    indexer::api::BlockHeight,
+    race_client::{RaceClient, RaceClientId, RaceClientResponse},
+};
+
+/// The maximum number of transactions that can be fetched in a single `sui_multiGetTransactionBlocks` request.
+const MAX_TRANSACTION_BLOCKS_PER_REQUEST: usize = 50;
+
+#[derive(Clone, Debug)]
+pub struct Provider {
+    pub rpc_client: RaceClient<HttpClient>,
+}
+
+#[derive(Clone, Debug, Copy)]
+pub struct RpcProviderId {
+    race_client_id: RaceClientId,
+}
+
+impl From<RpcProviderId> for RaceClientId {
+    fn from(value: RpcProviderId) -> Self {
+        value.race_client_id
+    }
+}
+
+#[derive(Debug)]
+pub struct RpcResult<T> {
+    pub provider_id: RpcProviderId,
+    pub response: T,
+}
+
+impl<T> RpcResult<T> {
+    fn new(race_client_id: RaceClientId, result: T) -> Self {
+        Self {
+            provider_id: RpcProviderId { race_client_id },
+            response: result,
+        }
+    }
+}
+
+impl<T> From<RaceClientResponse<T>> for RpcResult<T> {
+    fn from(value: RaceClientResponse<T>) -> Self {
+        RpcResult::new(value.race_client_id, value.response)
+    }
+}
+
+/// The fields of a checkpoint (as returned by `sui_getCheckpoint`) that are used by the indexer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    #[serde(with = "serde_utils::string")]
+    pub sequence_number: BlockHeight,
+    pub digest: String,
+    #[serde(with = "serde_utils::string")]
+    pub timestamp_ms: u64,
+    pub transactions: Vec<String>,
+}
+
+/// A transaction (as returned by `sui_multiGetTransactionBlocks`), only including its events.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionBlock {
+    pub digest: String,
+    #[serde(default)]
+    pub events: Vec<Value>,
+}
+
+impl Provider {
+    pub fn new(rpc_urls: Vec<Url>) -> Result<Self, ClientError> {
+        Ok(Self {
+            rpc_client: RaceClient::new(
+                rpc_urls
+                    .into_iter()
+                    .map(|url| HttpClientBuilder::default().build(url.as_str()))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+        })
+    }
+
+    pub async fn chain_identifier(
+        &self,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<String>, ClientError> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.request::<String, _>("sui_getChainIdentifier", rpc_params![])
+            })
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn latest_checkpoint_sequence_number(
+        &self,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<BlockHeight>, ClientError> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| async move {
+                c.request::<String, _>("sui_getLatestCheckpointSequenceNumber", rpc_params![])
+                    .await?
+                    .parse::<BlockHeight>()
+                    .map_err(|err| {
+                        ClientError::Custom(format!("invalid checkpoint sequence number: {err}"))
+                    })
+            })
+            .await
+            .map(Into::into)
+    }
+
+    pub async fn checkpoint(
+        &self,
+        sequence_number: BlockHeight,
+        provider_id: Option<RpcProviderId>,
+    ) -> Result<RpcResult<Checkpoint>, ClientError> {
+        self.rpc_client
+            .race(provider_id.map(Into::into), |c| {
+                c.request::<Checkpoint, _>(
+                    "sui_getCheckpoint",
+                    rpc_params![sequence_number.to_string()],
+                )
+            })
+            .await
+            .map(Into::into)
+    }
+
+    /// Fetch the transactions with their events, in the order of `digests`.
+    pub async fn transaction_blocks(
+        &self,
+        digests: &[String],
+        provider_id: RpcProviderId,
+    ) -> Result<Vec<TransactionBlock>, ClientError> {
+        let mut transaction_blocks = Vec::with_capacity(digests.len());
+
+        for digests in digests.chunks(MAX_TRANSACTION_BLOCKS_PER_REQUEST) {
+            let response = self
+                .rpc_client
+                .race(Some(provider_id.into()), |c| {
+                    c.request::<Vec<TransactionBlock>, _>(
+                        "sui_multiGetTransactionBlocks",
+                        rpc_params![digests, json!({ "showEvents": true })],
+                    )
+                })
+                .await?;
+
+            transaction_blocks.extend(response.response);
+        }
+
+        Ok(transaction_blocks)
+    }
+}
@@ -24,6 +24,12 @@ pub enum StorageVerificationError {
     ExpectedMembershipVerification,
     #[error("expected non-membership verification")]
     ExpectedNonMembershipVerification,
+    #[error("key ({key}) is not in the subtree of the proven leaf ({leaf_key}) at depth {depth}")]
+    LeafNotInSubtree {
+        key: H256,
+        leaf_key: H256,
+        depth: usize,
+    },
     #[error("root hash mismatch (({0}, {1}))")]
     RootHashMismatch(H256, H256),
 }
@@ -122,9 +122,66 @@ pub fn verify_existence_proof(
         .into());
     }
 
-    let current_hash = proof.leaf.map_or(SPARSE_MERKLE_PLACEHOLDER_HASH, |leaf| {
-        hash_sparse_merkle_leaf_node(&leaf)
-    });
+    verify_root_hash(&proof, expected_root_hash, element_key)
+}
+
+pub fn verify_non_membership(
+    proof: SparseMerkleProof,
+    expected_root_hash: [u8; 32],
+    element_key: [u8; 32],
+) -> Result<(), Error> {
+    verify_non_existence_proof(proof, expected_root_hash, element_key)
+}
+
+/// Verifies that `element_key` does not exist in the sparse merkle tree whose root hash is `expected_root_hash`.
+///
+/// If the proof contains a leaf, that leaf must be the only leaf in the subtree that `element_key` would be in if it existed. Otherwise, the subtree that `element_key` would be in must be empty.
+pub fn verify_non_existence_proof(
+    proof: SparseMerkleProof,
+    expected_root_hash: [u8; 32],
+    element_key: [u8; 32],
+) -> Result<(), Error> {
+    if proof.siblings.len() > 256 {
+        return Err(
+            StorageVerificationError::MaxSiblingsExceeded(256, proof.siblings.len()).into(),
+        );
+    }
+
+    if let Some(leaf) = proof.leaf {
+        if &element_key == leaf.key.get() {
+            return Err(StorageVerificationError::ExpectedNonMembershipVerification.into());
+        }
+
+        // The key must share a prefix with the proven leaf of at least the depth of the leaf,
+        // otherwise it would not have ended up in the subtree where the leaf is the only key.
+        let common_prefix_bits_len = BytesBitIterator::new(&element_key)
+            .zip(BytesBitIterator::new(leaf.key.get()))
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        if common_prefix_bits_len < proof.siblings.len() {
+            return Err(StorageVerificationError::LeafNotInSubtree {
+                key: H256::new(element_key),
+                leaf_key: H256::new(*leaf.key.get()),
+                depth: proof.siblings.len(),
+            }
+            .into());
+        }
+    }
+
+    verify_root_hash(&proof, expected_root_hash, element_key)
+}
+
+/// Computes the root hash of the tree from the leaf (or placeholder) and siblings of the proof along the path of `element_key`, and checks it against `expected_root_hash`.
+fn verify_root_hash(
+    proof: &SparseMerkleProof,
+    expected_root_hash: [u8; 32],
+    element_key: [u8; 32],
+) -> Result<(), Error> {
+    let current_hash = proof
+        .leaf
+        .as_ref()
+        .map_or(SPARSE_MERKLE_PLACEHOLDER_HASH, hash_sparse_merkle_leaf_node);
     let actual_root_hash = proof
         .siblings
         .iter()
@@ -235,3 +292,120 @@ impl SparseMerkleInternalNode {
         state.finalize().into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use unionlabs::primitives::{encoding::HexUnprefixed, H256};
+
+    use super::*;
+
+    fn key(first_byte: u8) -> [u8; 32] {
+        let mut key = [0; 32];
+        key[0] = first_byte;
+        key
+    }
+
+    fn leaf(key: [u8; 32]) -> SparseMerkleLeafNode {
+        SparseMerkleLeafNode {
+            key: H256::new(key),
+            value_hash: H256::new([0xAA; 32]),
+        }
+    }
+
+    fn sibling(hash: [u8; 32]) -> H256<HexUnprefixed> {
+        H256::new(hash)
+    }
+
+    /// A tree with two leaves, one on each side of the root.
+    fn two_leaf_tree() -> (SparseMerkleLeafNode, SparseMerkleLeafNode, [u8; 32]) {
+        let left = leaf(key(0b00));
+        let right = leaf(key(0b01));
+
+        let root = SparseMerkleInternalNode::new(
+            hash_sparse_merkle_leaf_node(&left),
+            hash_sparse_merkle_leaf_node(&right),
+        )
+        .hash();
+
+        (left, right, root)
+    }
+
+    #[test]
+    fn membership() {
+        let (left, right, root) = two_leaf_tree();
+
+        let proof = SparseMerkleProof {
+            leaf: Some(left),
+            siblings: vec![sibling(hash_sparse_merkle_leaf_node(&right))],
+        };
+
+        assert_eq!(verify_membership(proof, root), Ok(()));
+    }
+
+    #[test]
+    fn non_membership_leaf_in_subtree() {
+        let (left, right, root) = two_leaf_tree();
+
+        let proof = SparseMerkleProof {
+            leaf: Some(left),
+            siblings: vec![sibling(hash_sparse_merkle_leaf_node(&right))],
+        };
+
+        // shares the first bit with the left leaf
+        assert_eq!(verify_non_membership(proof, root, key(0b10)), Ok(()));
+    }
+
+    #[test]
+    fn non_membership_empty_subtree() {
+        let right = leaf(key(0b01));
+
+        let root = SparseMerkleInternalNode::new(
+            SPARSE_MERKLE_PLACEHOLDER_HASH,
+            hash_sparse_merkle_leaf_node(&right),
+        )
+        .hash();
+
+        let proof = SparseMerkleProof {
+            leaf: None,
+            siblings: vec![sibling(hash_sparse_merkle_leaf_node(&right))],
+        };
+
+        assert_eq!(verify_non_membership(proof, root, key(0b00)), Ok(()));
+    }
+
+    #[test]
+    fn non_membership_existing_key_fails() {
+        let (left, right, root) = two_leaf_tree();
+
+        let proof = SparseMerkleProof {
+            leaf: Some(left),
+            siblings: vec![sibling(hash_sparse_merkle_leaf_node(&right))],
+        };
+
+        assert_eq!(
+            verify_non_membership(proof, root, *left.key.get()),
+            Err(StorageVerificationError::ExpectedNonMembershipVerification.into())
+        );
+    }
+
+    #[test]
+    fn non_membership_leaf_not_in_subtree_fails() {
+        let (left, right, root) = two_leaf_tree();
+
+        let proof = SparseMerkleProof {
+            leaf: Some(left),
+            siblings: vec![sibling(hash_sparse_merkle_leaf_node(&right))],
+        };
+
+        // would be in the right subtree, not the left one
+        assert_eq!(
+            verify_non_membership(proof, root, key(0b11)),
+            Err(StorageVerificationError::LeafNotInSubtree {
+                key: H256::new(key(0b11)),
+                leaf_key: H256::new(key(0b00)),
+                depth: 1,
+            }
+            .into())
+        );
+    }
+}
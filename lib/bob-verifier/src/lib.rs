@@ -5,7 +5,10 @@ use bob_light_client_types::{
     header::{L2Header, OutputRootProof},
     ClientStateV1, Header,
 };
-use evm_storage_verifier::{verify_account_storage_root, verify_storage_proof};
+use evm_storage_verifier::{
+    misbehaviour::{self, L2StateRoot, MisbehaviourError},
+    verify_account_storage_root, verify_storage_proof,
+};
 use unionlabs::{
     ethereum::{keccak256, slot::Slot},
     primitives::{H256, U256},
@@ -26,6 +29,8 @@ pub enum Error {
     InvalidIbcContractProof(#[source] evm_storage_verifier::error::Error),
     #[error("the l2 header is not finalized")]
     HeaderNotFinalized,
+    #[error(transparent)]
+    Misbehaviour(#[from] MisbehaviourError),
 }
 
 pub fn verify_header(
@@ -82,6 +87,40 @@ pub fn verify_header(
     Ok(())
 }
 
+/// Verify that the provided headers prove that two different L2 state roots have been proposed to
+/// the L2OutputOracle for the same L2 block number.
+///
+/// Each header is verified with [`verify_header`] against the L1 state root at its own L1 height.
+pub fn verify_misbehaviour(
+    client_state: &ClientStateV1,
+    header_a: &Header,
+    l1_state_root_a: H256,
+    header_b: &Header,
+    l1_state_root_b: H256,
+    current_timestamp: u64,
+    l2_finalization_period_seconds: u64,
+) -> Result<(), Error> {
+    misbehaviour::verify_misbehaviour(
+        header_a,
+        l1_state_root_a,
+        header_b,
+        l1_state_root_b,
+        |header| L2StateRoot {
+            l2_height: header.l2_header.number,
+            state_root: header.l2_header.state_root,
+        },
+        |header, l1_state_root| {
+            verify_header(
+                client_state,
+                header,
+                l1_state_root,
+                current_timestamp,
+                l2_finalization_period_seconds,
+            )
+        },
+    )
+}
+
 pub fn compute_output_proposal_slot(l2_outputs_slot: U256, index: u32) -> U256 {
     let offset = Slot::Offset(l2_outputs_slot);
     // The size in slots of the OutputProposal structures, (sizeof(bytes32) + 2*sizeof(u128) / sizeof(u256)) = 2
@@ -127,8 +166,7 @@ mod tests {
 
     use super::*;
 
-    #[test]
-    fn test_update_ok() {
+    fn test_update_ok_inputs() -> (ClientStateV1, Header, H256) {
         // Outputs slot is 3
         // OutputIndex 700 = L2 Block 15141600 (0xE70AE0)
         // Slot index = 87903029871075914254377627908054574944891091886930582284385770809450030038483
@@ -217,6 +255,13 @@ mod tests {
         let l1_state_root =
             hex!("67bca5ce21aa5c48991a9aa2705371bd015fab01402dbcd2818c0b2cb19d5a6b").into();
 
+        (client_state, header, l1_state_root)
+    }
+
+    #[test]
+    fn test_update_ok() {
+        let (client_state, header, l1_state_root) = test_update_ok_inputs();
+
         let r1 = verify_header(
             &client_state,
             &header,
@@ -238,6 +283,84 @@ mod tests {
         assert_eq!(r2, Err(Error::HeaderNotFinalized));
     }
 
+    #[test]
+    fn misbehaviour_requires_same_l2_height() {
+        let (client_state, header, l1_state_root) = test_update_ok_inputs();
+
+        let mut other_header = header.clone();
+        other_header.l2_header.number = header.l2_header.number + U256::from(1_u64);
+
+        assert_eq!(
+            verify_misbehaviour(
+                &client_state,
+                &header,
+                l1_state_root,
+                &other_header,
+                l1_state_root,
+                header.l2_header.timestamp + FINALIZATION_PERIOD_SECONDS,
+                FINALIZATION_PERIOD_SECONDS,
+            ),
+            Err(Error::Misbehaviour(MisbehaviourError::L2HeightMismatch {
+                a: header.l2_header.number,
+                b: other_header.l2_header.number,
+            }))
+        );
+    }
+
+    #[test]
+    fn misbehaviour_requires_conflicting_state_roots() {
+        let (client_state, header, l1_state_root) = test_update_ok_inputs();
+
+        let current_timestamp = header.l2_header.timestamp + FINALIZATION_PERIOD_SECONDS;
+
+        assert_eq!(
+            verify_misbehaviour(
+                &client_state,
+                &header,
+                l1_state_root,
+                &header,
+                l1_state_root,
+                current_timestamp,
+                FINALIZATION_PERIOD_SECONDS,
+            ),
+            Err(Error::Misbehaviour(MisbehaviourError::NoMisbehaviour {
+                l2_height: header.l2_header.number,
+                state_root: header.l2_header.state_root,
+            }))
+        );
+
+        // a conflicting state root must also be proposed to the L2OutputOracle
+        let mut conflicting_header = header.clone();
+        conflicting_header.l2_header.state_root = H256::new([0xAA; 32]);
+
+        assert!(matches!(
+            verify_misbehaviour(
+                &client_state,
+                &header,
+                l1_state_root,
+                &conflicting_header,
+                l1_state_root,
+                current_timestamp,
+                FINALIZATION_PERIOD_SECONDS,
+            ),
+            Err(Error::OutputRootHashMismatch { .. })
+        ));
+
+        // both headers must be finalized
+        assert_eq!(
+            verify_misbehaviour(
+                &client_state,
+                &header,
+                l1_state_root,
+                &conflicting_header,
+                l1_state_root,
+                current_timestamp - 1,
+                FINALIZATION_PERIOD_SECONDS,
+            ),
+            Err(Error::HeaderNotFinalized)
+        );
+    }
+
     #[test]
     fn test_verify_l2_header_is_related_to_output_root_proof() {
         verify_l2_header_is_related_to_output_root_proof(
@@ -0,0 +1,283 @@
+//! Decryption of [EIP-2335] keystore files.
+//!
+//! Both the `scrypt` and `pbkdf2` key derivation functions are supported. The only supported cipher
+//! is `aes-128-ctr`, as required by the EIP.
+//!
+//! [EIP-2335]: https://eips.ethereum.org/EIPS/eip-2335
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use unicode_normalization::UnicodeNormalization;
+use unionlabs::primitives::Bytes;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Keystore {
+    pub crypto: KeystoreCrypto,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub pubkey: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    pub uuid: String,
+    pub version: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeystoreCrypto {
+    pub kdf: KeystoreModule<KdfParams>,
+    pub checksum: KeystoreModule<ChecksumParams>,
+    pub cipher: KeystoreModule<CipherParams>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeystoreModule<P> {
+    #[serde(flatten)]
+    pub params: P,
+    #[serde(with = "::serde_utils::hex_allow_unprefixed")]
+    pub message: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "function", content = "params", rename_all = "lowercase")]
+pub enum KdfParams {
+    Scrypt {
+        dklen: u32,
+        n: u32,
+        p: u32,
+        r: u32,
+        #[serde(with = "::serde_utils::hex_allow_unprefixed")]
+        salt: Vec<u8>,
+    },
+    Pbkdf2 {
+        dklen: u32,
+        c: u32,
+        prf: Prf,
+        #[serde(with = "::serde_utils::hex_allow_unprefixed")]
+        salt: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Prf {
+    #[serde(rename = "hmac-sha256")]
+    HmacSha256,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "function", content = "params", rename_all = "lowercase")]
+pub enum ChecksumParams {
+    Sha256 {},
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "function", content = "params")]
+pub enum CipherParams {
+    #[serde(rename = "aes-128-ctr")]
+    Aes128Ctr {
+        #[serde(with = "::serde_utils::hex_allow_unprefixed")]
+        iv: Vec<u8>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    #[error("invalid kdf params")]
+    InvalidKdfParams,
+    #[error("the derived key must be at least 32 bytes, found {0}")]
+    DerivedKeyTooShort(u32),
+    #[error("invalid cipher iv")]
+    InvalidIv,
+    #[error("checksum mismatch, the password is likely incorrect")]
+    ChecksumMismatch,
+}
+
+impl Keystore {
+    /// Decrypt the secret stored in this keystore.
+    pub fn decrypt(&self, password: &str) -> Result<Bytes, KeystoreError> {
+        let password = process_password(password);
+
+        let decryption_key = match &self.crypto.kdf.params {
+            KdfParams::Scrypt {
+                dklen,
+                n,
+                p,
+                r,
+                salt,
+            } => {
+                if !n.is_power_of_two() {
+                    return Err(KeystoreError::InvalidKdfParams);
+                }
+
+                let params = scrypt::Params::new(n.ilog2() as u8, *r, *p)
+                    .map_err(|_| KeystoreError::InvalidKdfParams)?;
+
+                let mut dk = vec![0; *dklen as usize];
+                scrypt::scrypt(password.as_bytes(), salt, &params, &mut dk)
+                    .map_err(|_| KeystoreError::InvalidKdfParams)?;
+                dk
+            }
+            KdfParams::Pbkdf2 {
+                dklen,
+                c,
+                prf: Prf::HmacSha256,
+                salt,
+            } => {
+                let mut dk = vec![0; *dklen as usize];
+                pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, *c, &mut dk);
+                dk
+            }
+        };
+
+        if decryption_key.len() < 32 {
+            return Err(KeystoreError::DerivedKeyTooShort(
+                decryption_key.len() as u32
+            ));
+        }
+
+        let ChecksumParams::Sha256 {} = self.crypto.checksum.params;
+
+        let checksum = Sha256::new()
+            .chain_update(&decryption_key[16..32])
+            .chain_update(&self.crypto.cipher.message)
+            .finalize();
+
+        if checksum.as_slice() != self.crypto.checksum.message {
+            return Err(KeystoreError::ChecksumMismatch);
+        }
+
+        let CipherParams::Aes128Ctr { iv } = &self.crypto.cipher.params;
+
+        let mut secret = self.crypto.cipher.message.clone();
+
+        Aes128Ctr::new_from_slices(&decryption_key[..16], iv)
+            .map_err(|_| KeystoreError::InvalidIv)?
+            .apply_keystream(&mut secret);
+
+        Ok(secret.into())
+    }
+}
+
+/// NFKD normalize the password, and strip all control codes.
+fn process_password(password: &str) -> String {
+    password.nfkd().filter(|c| !c.is_control()).collect()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use hex_literal::hex;
+
+    use super::*;
+
+    pub(crate) const PASSWORD: &str = "𝔱𝔢𝔰𝔱𝔭𝔞𝔰𝔰𝔴𝔬𝔯𝔡🔑";
+
+    pub(crate) const SECRET: [u8; 32] =
+        hex!("000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f");
+
+    // https://eips.ethereum.org/EIPS/eip-2335#pbkdf2-test-vector
+    const PBKDF2: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "pbkdf2",
+                "params": {
+                    "dklen": 32,
+                    "c": 262144,
+                    "prf": "hmac-sha256",
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "8a9f5d9912ed7e75ea794bc5a89bca5f193721d30868ade6f73043c6ea6febf1"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "cee03fde2af33149775b7223e7845e4fb2c8ae1792e5f99fe9ecf474cc8c16ad"
+            }
+        },
+        "description": "This is a test keystore that uses PBKDF2 to secure the secret.",
+        "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+        "path": "m/12381/60/0/0",
+        "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+        "version": 4
+    }"#;
+
+    // the secret, salt and iv of the EIP-2335 scrypt test vector, with a cheaper cost parameter
+    // (n = 2^10 instead of 2^18) to keep the test fast in debug builds
+    pub(crate) const SCRYPT: &str = r#"{
+        "crypto": {
+            "kdf": {
+                "function": "scrypt",
+                "params": {
+                    "dklen": 32,
+                    "n": 1024,
+                    "p": 1,
+                    "r": 8,
+                    "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                },
+                "message": ""
+            },
+            "checksum": {
+                "function": "sha256",
+                "params": {},
+                "message": "8ca4bc8dcb7b226d88b2f4c4fffc395f553d8a84227e30b6ca5f37e72aec73d5"
+            },
+            "cipher": {
+                "function": "aes-128-ctr",
+                "params": {
+                    "iv": "264daa3f303d7259501c93d997d84fe6"
+                },
+                "message": "f231aab75697116aed3d631d8e590946cab99199a17b7ad8817d4075eca0ce19"
+            }
+        },
+        "uuid": "1d85ae20-35c5-4611-98e8-aa14a633906f",
+        "version": 4
+    }"#;
+
+    #[test]
+    fn decrypt_pbkdf2() {
+        let keystore = serde_json::from_str::<Keystore>(PBKDF2).unwrap();
+
+        assert_eq!(&*keystore.decrypt(PASSWORD).unwrap(), SECRET);
+    }
+
+    #[test]
+    fn decrypt_scrypt() {
+        let keystore = serde_json::from_str::<Keystore>(SCRYPT).unwrap();
+
+        assert_eq!(&*keystore.decrypt(PASSWORD).unwrap(), SECRET);
+    }
+
+    #[test]
+    fn scrypt_cost_must_be_a_power_of_two() {
+        let mut keystore = serde_json::from_str::<Keystore>(SCRYPT).unwrap();
+
+        let KdfParams::Scrypt { n, .. } = &mut keystore.crypto.kdf.params else {
+            unreachable!()
+        };
+        *n = 1000;
+
+        assert!(matches!(
+            keystore.decrypt(PASSWORD),
+            Err(KeystoreError::InvalidKdfParams)
+        ));
+    }
+
+    #[test]
+    fn decrypt_invalid_password() {
+        let keystore = serde_json::from_str::<Keystore>(PBKDF2).unwrap();
+
+        assert!(matches!(
+            keystore.decrypt("wrong password"),
+            Err(KeystoreError::ChecksumMismatch)
+        ));
+    }
+}
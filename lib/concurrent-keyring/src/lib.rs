@@ -1,17 +1,29 @@
 #![feature(trait_alias)]
 
+pub mod keystore;
 pub mod private_key;
+pub mod remote;
 
 use std::{
-    collections::HashMap, fmt::Display, hash::Hash, panic::UnwindSafe, path::PathBuf, sync::Arc,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+    panic::UnwindSafe,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
 };
 
 use crossbeam_queue::ArrayQueue;
 use futures::{Future, FutureExt};
 use rand::prelude::SliceRandom;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info_span, warn, Instrument};
-use unionlabs::primitives::H256;
+use tracing::{debug, info, info_span, warn, Instrument};
+use unionlabs::primitives::{Bytes, H256};
+
+use crate::{
+    keystore::{Keystore, KeystoreError},
+    remote::RemoteSigner,
+};
 
 pub trait ChainKeyring {
     type Address: Hash + Eq + Clone + Display + Send + Sync;
@@ -29,6 +41,9 @@ pub struct SignerBalance<A> {
     #[serde(with = "::serde_utils::string")]
     pub balance: u128,
     pub denom: String,
+    /// Whether this signer is currently part of the keyring's rotation. See
+    /// [`ConcurrentKeyring::disable`].
+    pub enabled: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -39,6 +54,10 @@ pub struct ConcurrentKeyring<A: Hash + Eq, S> {
     addresses_buffer: Arc<ArrayQueue<A>>,
 
     signers: Arc<HashMap<A, S>>,
+
+    /// Signers that have been removed from the rotation. These remain in `addresses_buffer`, but
+    /// are skipped over when selecting a signer.
+    disabled: Arc<Mutex<HashSet<A>>>,
 }
 
 pub struct KeyringEntry<A, S> {
@@ -74,6 +93,7 @@ impl<A: Hash + Eq + Clone + Display, S: 'static> ConcurrentKeyring<A, S> {
             name: Arc::new(name.into()),
             addresses_buffer: Arc::new(addresses_buffer),
             signers: Arc::new(signers),
+            disabled: Default::default(),
         }
     }
 
@@ -81,13 +101,73 @@ impl<A: Hash + Eq + Clone + Display, S: 'static> ConcurrentKeyring<A, S> {
         self.signers.keys()
     }
 
+    /// Remove the signer with `address` from the rotation, such that it will not be used by
+    /// [`Self::with`] until it is enabled again with [`Self::enable`]. Any in progress usages of
+    /// the signer are not affected.
+    ///
+    /// Returns `false` if the signer is not in this keyring or is already disabled.
+    pub fn disable(&self, address: &A) -> bool {
+        if !self.signers.contains_key(address) {
+            return false;
+        }
+
+        let disabled = self.disabled().insert(address.clone());
+
+        if disabled {
+            warn!(keyring = %self.name, %address, "disabled signer");
+        }
+
+        disabled
+    }
+
+    /// Add a signer previously removed with [`Self::disable`] back into the rotation.
+    ///
+    /// Returns `false` if the signer is not disabled.
+    pub fn enable(&self, address: &A) -> bool {
+        let enabled = self.disabled().remove(address);
+
+        if enabled {
+            info!(keyring = %self.name, %address, "enabled signer");
+        }
+
+        enabled
+    }
+
+    /// Whether the signer with `address` is in this keyring and part of the rotation.
+    pub fn is_enabled(&self, address: &A) -> bool {
+        self.signers.contains_key(address) && !self.disabled().contains(address)
+    }
+
+    fn disabled(&self) -> MutexGuard<'_, HashSet<A>> {
+        self.disabled.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Pop the next enabled signer out of the buffer, pushing any disabled signers encountered back
+    /// on to the end.
+    fn next_address(&self) -> Option<A> {
+        for _ in 0..self.signers.len() {
+            let address = self.addresses_buffer.pop()?;
+
+            if !self.disabled().contains(&address) {
+                return Some(address);
+            }
+
+            self.addresses_buffer
+                .push(address)
+                .ok()
+                .expect("no additional items are added; qed;");
+        }
+
+        None
+    }
+
     pub async fn with<'a, F, Fut>(&'a self, f: F) -> Option<Fut::Output>
     where
         F: FnOnce(&'a S) -> Fut + 'a,
         Fut: Future<Output: 'a> + Sized + UnwindSafe + 'a,
     {
-        let Some(address) = self.addresses_buffer.pop() else {
-            debug!(keyring = %self.name, "high traffic in keyring");
+        let Some(address) = self.next_address() else {
+            debug!(keyring = %self.name, "high traffic in keyring, or all signers are disabled");
             return None;
         };
 
@@ -133,15 +213,55 @@ pub struct KeyringConfig {
 }
 
 impl KeyringConfigEntry {
+    /// Read the private key for this entry.
+    ///
+    /// # Panics
+    ///
+    /// This will panic if the key cannot be read, or if this entry is a [`Self::Remote`] signer,
+    /// since the private key of remote signers is not accessible. Use [`Self::signer`] to support
+    /// all entry types.
     pub fn value(&self) -> Vec<u8> {
+        match self.signer().expect("unable to read key") {
+            KeyringSigner::Local(key) => key.into(),
+            KeyringSigner::Remote(_) => {
+                panic!("the private key of a remote signer cannot be read")
+            }
+        }
+    }
+
+    /// Resolve this entry into a signer.
+    pub fn signer(&self) -> Result<KeyringSigner, KeyringError> {
         match &self {
-            KeyringConfigEntry::File { path } => std::fs::read_to_string(path)
-                .expect("key does not exist")
-                .trim()
-                .parse::<H256>()
-                .expect("key is in an invalid format")
-                .into(),
-            KeyringConfigEntry::Raw { name: _, key } => key.clone(),
+            KeyringConfigEntry::File { path } => Ok(KeyringSigner::Local(
+                std::fs::read_to_string(path)
+                    .map_err(KeyringError::Io)?
+                    .trim()
+                    .parse::<H256>()
+                    .map_err(|_| KeyringError::InvalidKey)?
+                    .get()
+                    .into(),
+            )),
+            KeyringConfigEntry::Raw { name: _, key } => {
+                Ok(KeyringSigner::Local(key.clone().into()))
+            }
+            KeyringConfigEntry::Keystore {
+                path,
+                password_file,
+            } => {
+                let keystore = serde_json::from_str::<Keystore>(
+                    &std::fs::read_to_string(path).map_err(KeyringError::Io)?,
+                )
+                .map_err(KeyringError::InvalidKeystore)?;
+
+                let password = std::fs::read_to_string(password_file).map_err(KeyringError::Io)?;
+
+                Ok(KeyringSigner::Local(
+                    keystore.decrypt(password.trim_end_matches(['\n', '\r']))?,
+                ))
+            }
+            KeyringConfigEntry::Remote { url, key_id } => Ok(KeyringSigner::Remote(
+                RemoteSigner::new(url, key_id).map_err(KeyringError::Remote)?,
+            )),
         }
     }
 }
@@ -157,4 +277,183 @@ pub enum KeyringConfigEntry {
         #[serde(with = "::serde_utils::hex_string")]
         key: Vec<u8>,
     },
+    /// An [EIP-2335] encrypted keystore file.
+    ///
+    /// [EIP-2335]: https://eips.ethereum.org/EIPS/eip-2335
+    Keystore {
+        path: PathBuf,
+        /// Path to a file containing the password for the keystore. Trailing newlines are ignored.
+        password_file: PathBuf,
+    },
+    /// A remote signer. See [`remote`] for the protocol used to communicate with the signer.
+    Remote {
+        url: String,
+        key_id: String,
+    },
+}
+
+/// A signer resolved from a [`KeyringConfigEntry`].
+#[derive(Debug, Clone)]
+pub enum KeyringSigner {
+    /// The raw private key, held in memory.
+    Local(Bytes),
+    /// A remote signer. The private key is not accessible.
+    Remote(RemoteSigner),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum KeyringError {
+    #[error("unable to read key")]
+    Io(#[source] std::io::Error),
+    #[error("key is in an invalid format")]
+    InvalidKey,
+    #[error("invalid keystore")]
+    InvalidKeystore(#[source] serde_json::Error),
+    #[error("unable to decrypt keystore")]
+    Keystore(#[from] KeystoreError),
+    #[error("invalid remote signer")]
+    Remote(#[source] jsonrpsee::core::ClientError),
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use super::*;
+    use crate::keystore::tests::{PASSWORD, SCRYPT, SECRET};
+
+    const KEY: [u8; 32] = hex!("0101010101010101010101010101010101010101010101010101010101010101");
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("concurrent-keyring-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn local_key(entry: KeyringConfigEntry) -> Bytes {
+        match entry.signer().unwrap() {
+            KeyringSigner::Local(key) => key,
+            KeyringSigner::Remote(signer) => panic!("expected a local signer, found {signer:?}"),
+        }
+    }
+
+    fn keyring() -> ConcurrentKeyring<u8, u8> {
+        ConcurrentKeyring::new(
+            "test",
+            [1, 2, 3]
+                .map(|address| KeyringEntry {
+                    address,
+                    signer: address,
+                })
+                .into_iter(),
+        )
+    }
+
+    #[tokio::test]
+    async fn disabled_signers_are_skipped() {
+        let keyring = keyring();
+
+        assert!(keyring.disable(&1));
+        assert!(keyring.disable(&2));
+        assert!(!keyring.disable(&2));
+        assert!(!keyring.disable(&4));
+
+        assert!(!keyring.is_enabled(&1));
+        assert!(keyring.is_enabled(&3));
+
+        for _ in 0..6 {
+            assert_eq!(keyring.with(|signer| async { *signer }).await, Some(3));
+        }
+    }
+
+    #[test]
+    fn file_entry() {
+        let path = temp_file("file", &format!("{}\n", <H256>::new(KEY)));
+
+        assert_eq!(&*local_key(KeyringConfigEntry::File { path }), KEY);
+    }
+
+    #[test]
+    fn file_entry_invalid_key() {
+        let path = temp_file("file-invalid", "not a key");
+
+        assert!(matches!(
+            KeyringConfigEntry::File { path }.signer(),
+            Err(KeyringError::InvalidKey)
+        ));
+    }
+
+    #[test]
+    fn raw_entry() {
+        let entry = KeyringConfigEntry::Raw {
+            name: "raw".to_owned(),
+            key: KEY.to_vec(),
+        };
+
+        assert_eq!(&*local_key(entry.clone()), KEY);
+        assert_eq!(entry.value(), KEY);
+    }
+
+    #[test]
+    fn keystore_entry() {
+        let entry = KeyringConfigEntry::Keystore {
+            path: temp_file("keystore.json", SCRYPT),
+            password_file: temp_file("keystore-password", &format!("{PASSWORD}\r\n")),
+        };
+
+        assert_eq!(&*local_key(entry), SECRET);
+    }
+
+    #[test]
+    fn keystore_entry_wrong_password() {
+        let entry = KeyringConfigEntry::Keystore {
+            path: temp_file("keystore-wrong.json", SCRYPT),
+            password_file: temp_file("keystore-wrong-password", "hunter2"),
+        };
+
+        assert!(matches!(
+            entry.signer(),
+            Err(KeyringError::Keystore(KeystoreError::ChecksumMismatch))
+        ));
+    }
+
+    #[test]
+    fn remote_entry() {
+        let entry = KeyringConfigEntry::Remote {
+            url: "http://127.0.0.1:1".to_owned(),
+            key_id: "relayer".to_owned(),
+        };
+
+        match entry.signer().unwrap() {
+            KeyringSigner::Remote(signer) => assert_eq!(signer.key_id(), "relayer"),
+            KeyringSigner::Local(_) => panic!("expected a remote signer"),
+        }
+    }
+
+    #[test]
+    #[should_panic = "the private key of a remote signer cannot be read"]
+    fn remote_entry_value_panics() {
+        KeyringConfigEntry::Remote {
+            url: "http://127.0.0.1:1".to_owned(),
+            key_id: "relayer".to_owned(),
+        }
+        .value();
+    }
+
+    #[tokio::test]
+    async fn all_signers_disabled() {
+        let keyring = keyring();
+
+        for address in [1, 2, 3] {
+            keyring.disable(&address);
+        }
+
+        assert_eq!(keyring.with(|signer| async { *signer }).await, None);
+
+        assert!(keyring.enable(&2));
+        assert!(!keyring.enable(&2));
+
+        assert_eq!(keyring.with(|signer| async { *signer }).await, Some(2));
+    }
 }
@@ -0,0 +1,168 @@
+//! A client for remote signers.
+//!
+//! Remote signers keep the private keys out of the relayer's memory and configuration entirely,
+//! only exposing the public key and signatures over a small JSON-RPC API over HTTP:
+//!
+//! - `keyring_publicKey(key_id: string) -> hex`: The public key of the key, in the key type's
+//!   standard encoding (i.e. a 33 byte SEC1 compressed point for secp256k1, or a 32 byte point for
+//!   ed25519).
+//! - `keyring_signDigest(key_id: string, digest: hex) -> hex`: Sign the 32 byte digest with the
+//!   key. For secp256k1 keys, this is expected to be the 65 byte `r || s || v` recoverable
+//!   signature, where `v` is the recovery id (0 or 1). For ed25519 keys, this is the 64 byte
+//!   signature over the digest.
+//! - `keyring_signMessage(key_id: string, message: hex) -> hex`: Sign the full message with the key,
+//!   for chains where transactions are signed over the message itself rather than a digest of it
+//!   (i.e. aptos). This is only required for ed25519 keys, and returns the 64 byte signature over
+//!   the message.
+//!
+//! The signer is responsible for any access control and auditing of the signing requests.
+
+use std::sync::Arc;
+
+use jsonrpsee::{
+    core::{client::ClientT, ClientError},
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use unionlabs::primitives::{Bytes, H256};
+
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    client: Arc<HttpClient>,
+    key_id: String,
+}
+
+impl RemoteSigner {
+    pub fn new(url: &str, key_id: impl Into<String>) -> Result<Self, ClientError> {
+        Ok(Self {
+            client: Arc::new(HttpClientBuilder::default().build(url)?),
+            key_id: key_id.into(),
+        })
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub async fn public_key(&self) -> Result<Bytes, ClientError> {
+        self.client
+            .request("keyring_publicKey", rpc_params![&self.key_id])
+            .await
+    }
+
+    pub async fn sign_digest(&self, digest: H256) -> Result<Bytes, ClientError> {
+        self.client
+            .request("keyring_signDigest", rpc_params![&self.key_id, digest])
+            .await
+    }
+
+    pub async fn sign_message(&self, message: Bytes) -> Result<Bytes, ClientError> {
+        self.client
+            .request("keyring_signMessage", rpc_params![&self.key_id, message])
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::{server::Server, types::ErrorObject, RpcModule};
+
+    use super::*;
+
+    const PUBLIC_KEY: [u8; 32] = [7; 32];
+
+    /// Serve a remote signer that only knows the key `relayer`, and "signs" digests and messages by
+    /// repeating them twice.
+    async fn serve() -> String {
+        let mut module = RpcModule::new(());
+
+        module
+            .register_method("keyring_publicKey", |params, _, _| {
+                match params.one::<String>()?.as_str() {
+                    "relayer" => Ok(Bytes::from(PUBLIC_KEY.to_vec())),
+                    key_id => Err(ErrorObject::owned(
+                        -1,
+                        format!("unknown key {key_id}"),
+                        None::<()>,
+                    )),
+                }
+            })
+            .unwrap();
+
+        module
+            .register_method("keyring_signDigest", |params, _, _| {
+                let (key_id, digest) = params.parse::<(String, H256)>()?;
+
+                match key_id.as_str() {
+                    "relayer" => Ok(Bytes::from(
+                        [digest.get().as_slice(), digest.get().as_slice()].concat(),
+                    )),
+                    key_id => Err(ErrorObject::owned(
+                        -1,
+                        format!("unknown key {key_id}"),
+                        None::<()>,
+                    )),
+                }
+            })
+            .unwrap();
+
+        module
+            .register_method("keyring_signMessage", |params, _, _| {
+                let (key_id, message) = params.parse::<(String, Bytes)>()?;
+
+                match key_id.as_str() {
+                    "relayer" => Ok(Bytes::from([&*message, &*message].concat())),
+                    key_id => Err(ErrorObject::owned(
+                        -1,
+                        format!("unknown key {key_id}"),
+                        None::<()>,
+                    )),
+                }
+            })
+            .unwrap();
+
+        let server = Server::builder().build("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+
+        tokio::spawn(server.start(module).stopped());
+
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        let signer = RemoteSigner::new(&serve().await, "relayer").unwrap();
+
+        assert_eq!(&*signer.public_key().await.unwrap(), PUBLIC_KEY);
+
+        let digest = H256::new([3; 32]);
+
+        assert_eq!(&*signer.sign_digest(digest).await.unwrap(), [3; 64]);
+
+        assert_eq!(
+            &*signer
+                .sign_message(b"message".to_vec().into())
+                .await
+                .unwrap(),
+            b"messagemessage"
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_key() {
+        let signer = RemoteSigner::new(&serve().await, "unknown").unwrap();
+
+        assert!(matches!(
+            signer.public_key().await,
+            Err(ClientError::Call(_))
+        ));
+        assert!(matches!(
+            signer.sign_digest(H256::new([3; 32])).await,
+            Err(ClientError::Call(_))
+        ));
+        assert!(matches!(
+            signer.sign_message(b"message".to_vec().into()).await,
+            Err(ClientError::Call(_))
+        ));
+    }
+}
@@ -29,7 +29,11 @@ use unionlabs::{
     ErrorReporter, Msg, TypeUrl,
 };
 
-use crate::{gas::GasFillerT, rpc::RpcT, wallet::WalletT};
+use crate::{
+    gas::GasFillerT,
+    rpc::RpcT,
+    wallet::{SignError, WalletT},
+};
 
 pub mod gas;
 pub mod rpc;
@@ -143,15 +147,19 @@ impl<W: WalletT, Q: RpcT, G: GasFillerT> TxClient<W, Q, G> {
         );
 
         // re-sign the new auth info with the simulated gas
-        let signature = self.wallet.sign(
-            &SignDoc {
-                body_bytes: tx_body.clone().encode_as::<Proto>(),
-                auth_info_bytes: auth_info.clone().encode_as::<Proto>(),
-                chain_id: self.rpc.chain_id().to_string(),
-                account_number: account.account_number,
-            }
-            .encode_as::<Proto>(),
-        );
+        let signature = self
+            .wallet
+            .sign(
+                &SignDoc {
+                    body_bytes: tx_body.clone().encode_as::<Proto>(),
+                    auth_info_bytes: auth_info.clone().encode_as::<Proto>(),
+                    chain_id: self.rpc.chain_id().to_string(),
+                    account_number: account.account_number,
+                }
+                .encode_as::<Proto>(),
+            )
+            .await
+            .map_err(BroadcastTxCommitError::Sign)?;
 
         let tx_raw_bytes = TxRaw {
             body_bytes: tx_body.clone().encode_as::<Proto>(),
@@ -246,15 +254,19 @@ impl<W: WalletT, Q: RpcT, G: GasFillerT> TxClient<W, Q, G> {
 
         let (tx_body, auth_info) = self.tx_info(messages, memo, &account).await;
 
-        let simulation_signature = self.wallet.sign(
-            &SignDoc {
-                body_bytes: tx_body.clone().encode_as::<Proto>(),
-                auth_info_bytes: auth_info.clone().encode_as::<Proto>(),
-                chain_id: self.rpc.chain_id().to_string(),
-                account_number: account.account_number,
-            }
-            .encode_as::<Proto>(),
-        );
+        let simulation_signature = self
+            .wallet
+            .sign(
+                &SignDoc {
+                    body_bytes: tx_body.clone().encode_as::<Proto>(),
+                    auth_info_bytes: auth_info.clone().encode_as::<Proto>(),
+                    chain_id: self.rpc.chain_id().to_string(),
+                    account_number: account.account_number,
+                }
+                .encode_as::<Proto>(),
+            )
+            .await
+            .map_err(BroadcastTxCommitError::Sign)?;
 
         let simulate_response = self
             .rpc
@@ -364,6 +376,8 @@ impl<W: WalletT, Q: RpcT, G: GasFillerT> TxClient<W, Q, G> {
 pub enum BroadcastTxCommitError {
     #[error("tx simulation returned an empty response")]
     NoResponse,
+    #[error("error signing tx")]
+    Sign(#[source] SignError),
     #[error("jsonrpc error")]
     JsonRpc(#[from] JsonRpcError),
     #[error("grpc abci query error")]
@@ -1,14 +1,19 @@
+use bip32::secp256k1::ecdsa::Signature;
+use concurrent_keyring::{remote::RemoteSigner, KeyringSigner};
+use ripemd::Digest;
 use unionlabs::{
     primitives::{Bech32, FixedBytes, H160, H256, H512},
     signer::CosmosSigner,
 };
 
+pub type SignError = Box<dyn core::error::Error + Send + Sync>;
+
 pub trait WalletT {
     fn address(&self) -> Bech32<H160>;
 
     fn public_key(&self) -> FixedBytes<33>;
 
-    fn sign(&self, bz: &[u8]) -> H512;
+    async fn sign(&self, bz: &[u8]) -> Result<H512, SignError>;
 }
 
 #[derive(Debug)]
@@ -37,12 +42,122 @@ impl WalletT for LocalSigner {
         self.signer.public_key()
     }
 
-    fn sign(&self, bz: &[u8]) -> H512 {
-        self.signer
+    async fn sign(&self, bz: &[u8]) -> Result<H512, SignError> {
+        Ok(self
+            .signer
             .try_sign(bz)
             .expect("infallible")
             .to_bytes()
-            .into()
+            .into())
+    }
+}
+
+/// A wallet backed by a [`RemoteSigner`]. The remote signer must hold a secp256k1 key.
+#[derive(Debug)]
+pub struct RemoteWallet {
+    signer: RemoteSigner,
+    public_key: FixedBytes<33>,
+    address: Bech32<H160>,
+}
+
+impl RemoteWallet {
+    /// Fetch the public key from the remote signer and derive the address from it.
+    pub async fn new(signer: RemoteSigner, prefix: impl Into<String>) -> Result<Self, SignError> {
+        let public_key = FixedBytes::<33>::try_from(signer.public_key().await?)
+            .map_err(|_| "remote signer public key is not a compressed secp256k1 point")?;
+
+        // bech32(prefix, ripemd(sha256(pubkey)))
+        let address = Bech32::new(
+            prefix.into(),
+            ripemd::Ripemd160::new()
+                .chain_update(sha2::Sha256::new().chain_update(public_key).finalize())
+                .finalize()
+                .into(),
+        );
+
+        Ok(Self {
+            signer,
+            public_key,
+            address,
+        })
+    }
+}
+
+impl WalletT for RemoteWallet {
+    fn address(&self) -> Bech32<H160> {
+        self.address.clone()
+    }
+
+    fn public_key(&self) -> FixedBytes<33> {
+        self.public_key
+    }
+
+    async fn sign(&self, bz: &[u8]) -> Result<H512, SignError> {
+        let digest: H256 = sha2::Sha256::new().chain_update(bz).finalize().into();
+
+        let signature = self.signer.sign_digest(digest).await?;
+
+        // the recovery id is not used in cosmos signatures
+        let signature = Signature::from_slice(signature.get(..64).ok_or_else(|| {
+            format!(
+                "remote signer returned an invalid signature of length {}",
+                signature.len()
+            )
+        })?)?;
+
+        // cosmos only accepts signatures with a low s value
+        Ok(signature
+            .normalize_s()
+            .unwrap_or(signature)
+            .to_bytes()
+            .into())
+    }
+}
+
+/// Either a [`LocalSigner`] or a [`RemoteWallet`], as resolved from a [`KeyringSigner`].
+#[derive(Debug)]
+pub enum AnyWallet {
+    Local(LocalSigner),
+    Remote(RemoteWallet),
+}
+
+impl AnyWallet {
+    pub async fn from_keyring_signer(
+        signer: KeyringSigner,
+        prefix: impl Into<String>,
+    ) -> Result<Self, SignError> {
+        match signer {
+            KeyringSigner::Local(key) => Ok(Self::Local(LocalSigner::new(
+                H256::try_from(key).map_err(|_| "private key must be 32 bytes")?,
+                prefix,
+            ))),
+            KeyringSigner::Remote(signer) => {
+                Ok(Self::Remote(RemoteWallet::new(signer, prefix).await?))
+            }
+        }
+    }
+}
+
+impl WalletT for AnyWallet {
+    fn address(&self) -> Bech32<H160> {
+        match self {
+            AnyWallet::Local(wallet) => wallet.address(),
+            AnyWallet::Remote(wallet) => wallet.address(),
+        }
+    }
+
+    fn public_key(&self) -> FixedBytes<33> {
+        match self {
+            AnyWallet::Local(wallet) => wallet.public_key(),
+            AnyWallet::Remote(wallet) => wallet.public_key(),
+        }
+    }
+
+    async fn sign(&self, bz: &[u8]) -> Result<H512, SignError> {
+        match self {
+            AnyWallet::Local(wallet) => wallet.sign(bz).await,
+            AnyWallet::Remote(wallet) => wallet.sign(bz).await,
+        }
     }
 }
 
@@ -55,7 +170,7 @@ impl<T: WalletT> WalletT for &T {
         (*self).public_key()
     }
 
-    fn sign(&self, bz: &[u8]) -> H512 {
-        (*self).sign(bz)
+    async fn sign(&self, bz: &[u8]) -> Result<H512, SignError> {
+        (*self).sign(bz).await
     }
 }
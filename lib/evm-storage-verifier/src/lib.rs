@@ -11,6 +11,7 @@ use unionlabs::{
 use crate::rlp_node_codec::{keccak_256, EthLayout, KeccakHasher};
 
 pub mod error;
+pub mod misbehaviour;
 mod rlp_node_codec;
 
 pub const GENESIS_SLOT: u64 = 0;
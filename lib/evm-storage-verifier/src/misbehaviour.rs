@@ -0,0 +1,182 @@
+//! Misbehaviour checks shared by the rollup light clients that settle on an EVM L1.
+//!
+//! These clients consider it misbehaviour if two different L2 state roots are proven to have been
+//! settled on the L1 for the same L2 height.
+
+use unionlabs::primitives::{H256, U256};
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum MisbehaviourError {
+    #[error("misbehaviour headers are for different L2 heights ({a} and {b})")]
+    L2HeightMismatch { a: U256, b: U256 },
+    #[error(
+        "misbehaviour headers have the same state root ({state_root}) at L2 height {l2_height}"
+    )]
+    NoMisbehaviour { l2_height: U256, state_root: H256 },
+}
+
+/// The L2 state root that a header proves has been settled on the L1, and the L2 height it was
+/// settled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L2StateRoot {
+    pub l2_height: U256,
+    pub state_root: H256,
+}
+
+/// Verify that the provided headers prove that two different L2 state roots have been settled on
+/// the L1 for the same L2 height.
+///
+/// The L2 heights and state roots are checked first, since they are cheap to compare. Each header
+/// is then verified with `verify_header` against the L1 state root at its own L1 height.
+pub fn verify_misbehaviour<H, E: From<MisbehaviourError>>(
+    header_a: &H,
+    l1_state_root_a: H256,
+    header_b: &H,
+    l1_state_root_b: H256,
+    l2_state_root: impl Fn(&H) -> L2StateRoot,
+    verify_header: impl Fn(&H, H256) -> Result<(), E>,
+) -> Result<(), E> {
+    let a = l2_state_root(header_a);
+    let b = l2_state_root(header_b);
+
+    if a.l2_height != b.l2_height {
+        return Err(MisbehaviourError::L2HeightMismatch {
+            a: a.l2_height,
+            b: b.l2_height,
+        }
+        .into());
+    }
+
+    if a.state_root == b.state_root {
+        return Err(MisbehaviourError::NoMisbehaviour {
+            l2_height: a.l2_height,
+            state_root: a.state_root,
+        }
+        .into());
+    }
+
+    verify_header(header_a, l1_state_root_a)?;
+    verify_header(header_b, l1_state_root_b)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        Misbehaviour(MisbehaviourError),
+        InvalidHeader(H256),
+    }
+
+    impl From<MisbehaviourError> for Error {
+        fn from(e: MisbehaviourError) -> Self {
+            Error::Misbehaviour(e)
+        }
+    }
+
+    const L1_STATE_ROOT_A: H256 = H256::new([0xA1; 32]);
+    const L1_STATE_ROOT_B: H256 = H256::new([0xB1; 32]);
+
+    fn header(l2_height: u64, state_root: u8) -> L2StateRoot {
+        L2StateRoot {
+            l2_height: l2_height.into(),
+            state_root: H256::new([state_root; 32]),
+        }
+    }
+
+    fn verify(
+        header_a: L2StateRoot,
+        header_b: L2StateRoot,
+        valid_l1_state_roots: &[H256],
+    ) -> (Result<(), Error>, Vec<(L2StateRoot, H256)>) {
+        let verified = RefCell::new(vec![]);
+
+        let res = verify_misbehaviour(
+            &header_a,
+            L1_STATE_ROOT_A,
+            &header_b,
+            L1_STATE_ROOT_B,
+            |header| *header,
+            |header, l1_state_root| {
+                verified.borrow_mut().push((*header, l1_state_root));
+
+                if valid_l1_state_roots.contains(&l1_state_root) {
+                    Ok(())
+                } else {
+                    Err(Error::InvalidHeader(l1_state_root))
+                }
+            },
+        );
+
+        (res, verified.into_inner())
+    }
+
+    #[test]
+    fn conflicting_state_roots() {
+        let (res, verified) = verify(
+            header(10, 1),
+            header(10, 2),
+            &[L1_STATE_ROOT_A, L1_STATE_ROOT_B],
+        );
+
+        assert_eq!(res, Ok(()));
+        assert_eq!(
+            verified,
+            [
+                (header(10, 1), L1_STATE_ROOT_A),
+                (header(10, 2), L1_STATE_ROOT_B)
+            ]
+        );
+    }
+
+    #[test]
+    fn different_l2_heights() {
+        let (res, verified) = verify(
+            header(10, 1),
+            header(11, 2),
+            &[L1_STATE_ROOT_A, L1_STATE_ROOT_B],
+        );
+
+        assert_eq!(
+            res,
+            Err(Error::Misbehaviour(MisbehaviourError::L2HeightMismatch {
+                a: 10_u64.into(),
+                b: 11_u64.into(),
+            }))
+        );
+        assert!(verified.is_empty());
+    }
+
+    #[test]
+    fn same_state_root() {
+        let (res, verified) = verify(
+            header(10, 1),
+            header(10, 1),
+            &[L1_STATE_ROOT_A, L1_STATE_ROOT_B],
+        );
+
+        assert_eq!(
+            res,
+            Err(Error::Misbehaviour(MisbehaviourError::NoMisbehaviour {
+                l2_height: 10_u64.into(),
+                state_root: H256::new([1; 32]),
+            }))
+        );
+        assert!(verified.is_empty());
+    }
+
+    #[test]
+    fn both_headers_must_be_valid() {
+        let (res, _) = verify(header(10, 1), header(10, 2), &[L1_STATE_ROOT_A]);
+        assert_eq!(res, Err(Error::InvalidHeader(L1_STATE_ROOT_B)));
+
+        let (res, verified) = verify(header(10, 1), header(10, 2), &[L1_STATE_ROOT_B]);
+        assert_eq!(res, Err(Error::InvalidHeader(L1_STATE_ROOT_A)));
+        assert_eq!(verified, [(header(10, 1), L1_STATE_ROOT_A)]);
+    }
+}
@@ -18,6 +18,7 @@ use crate::{
 pub enum Datagram {
     CreateClient(MsgCreateClient),
     UpdateClient(MsgUpdateClient),
+    SubmitMisbehaviour(MsgSubmitMisbehaviour),
     ConnectionOpenInit(MsgConnectionOpenInit),
     ConnectionOpenTry(MsgConnectionOpenTry),
     ConnectionOpenAck(MsgConnectionOpenAck),
@@ -43,6 +44,7 @@ impl Datagram {
         match self {
             Self::CreateClient(_) => None,
             Self::UpdateClient(_) => None,
+            Self::SubmitMisbehaviour(_) => None,
             Self::ConnectionOpenInit(_) => None,
             Self::ConnectionOpenTry(msg) => Some(Height::new(msg.proof_height)),
             Self::ConnectionOpenAck(msg) => Some(Height::new(msg.proof_height)),
@@ -66,6 +68,7 @@ impl Datagram {
         match self {
             Self::CreateClient(_) => "create_client",
             Self::UpdateClient(_) => "update_client",
+            Self::SubmitMisbehaviour(_) => "submit_misbehaviour",
             Self::ConnectionOpenInit(_) => "connection_open_init",
             Self::ConnectionOpenTry(_) => "connection_open_try",
             Self::ConnectionOpenAck(_) => "connection_open_ack",
@@ -111,6 +114,19 @@ pub struct MsgUpdateClient {
     pub client_message: Bytes,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case", deny_unknown_fields)
+)]
+pub struct MsgSubmitMisbehaviour {
+    pub client_id: ClientId,
+    /// The encoded misbehaviour, as understood by the light client tracking `client_id`.
+    pub client_message: Bytes,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(
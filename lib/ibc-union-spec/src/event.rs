@@ -18,6 +18,7 @@ use crate::{
 pub enum FullEvent {
     CreateClient(CreateClient),
     UpdateClient(UpdateClient),
+    ClientMisbehaviour(ClientMisbehaviour),
 
     ConnectionOpenInit(ConnectionOpenInit),
     ConnectionOpenTry(ConnectionOpenTry),
@@ -46,6 +47,7 @@ impl FullEvent {
         match self {
             Self::CreateClient(_) => None,
             Self::UpdateClient(_) => None,
+            Self::ClientMisbehaviour(_) => None,
             Self::ConnectionOpenInit(event) => Some(event.counterparty_client_id),
             Self::ConnectionOpenTry(event) => Some(event.counterparty_client_id),
             Self::ConnectionOpenAck(event) => Some(event.counterparty_client_id),
@@ -72,6 +74,7 @@ impl FullEvent {
         match self {
             Self::CreateClient(_) => "create_client",
             Self::UpdateClient(_) => "update_client",
+            Self::ClientMisbehaviour(_) => "client_misbehaviour",
             Self::ConnectionOpenInit(_) => "connection_open_init",
             Self::ConnectionOpenTry(_) => "connection_open_try",
             Self::ConnectionOpenAck(_) => "connection_open_ack",
@@ -118,6 +121,18 @@ pub struct UpdateClient {
     pub height: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case", deny_unknown_fields)
+)]
+pub struct ClientMisbehaviour {
+    pub client_type: ClientType,
+    pub client_id: ClientId,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[cfg_attr(
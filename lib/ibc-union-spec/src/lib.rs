@@ -82,6 +82,13 @@ pub fn log_event(e: &FullEvent, chain_id: &voyager_primitives::ChainId) {
             data.height = e.height,
             "event"
         ),
+        FullEvent::ClientMisbehaviour(e) => info!(
+            event,
+            %chain_id,
+            data.client_id = %e.client_id,
+            data.client_type = %e.client_type,
+            "event"
+        ),
         FullEvent::ConnectionOpenInit(e) => info!(
             event,
             %chain_id,
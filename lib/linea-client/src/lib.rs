@@ -0,0 +1,98 @@
+use std::error::Error;
+
+use alloy::{
+    eips::BlockNumberOrTag,
+    network::Network,
+    primitives::{Address, B256},
+    providers::Provider,
+};
+use linea_types::proof::GetProof;
+use tracing::{debug, instrument};
+use unionlabs::primitives::{H160, U256};
+
+/// Read the `currentL2BlockNumber` of the linea rollup contract at the specified L1 height.
+#[instrument(skip_all, fields(%l1_height, %rollup_contract_address))]
+pub async fn finalized_l2_block_number_of_l1_height(
+    l1_provider: impl Provider,
+    rollup_contract_address: H160,
+    current_l2_block_number_slot: U256,
+    l1_height: u64,
+) -> Result<u64, Box<dyn Error>> {
+    let l2_block_number = read_u64_slot(
+        l1_provider,
+        rollup_contract_address,
+        current_l2_block_number_slot,
+        l1_height,
+    )
+    .await?;
+
+    debug!("l1_height {l1_height} is l2 block number {l2_block_number}");
+
+    Ok(l2_block_number)
+}
+
+/// Read the `currentL2Timestamp` of the linea rollup contract at the specified L1 height.
+#[instrument(skip_all, fields(%l1_height, %rollup_contract_address))]
+pub async fn finalized_l2_timestamp_of_l1_height(
+    l1_provider: impl Provider,
+    rollup_contract_address: H160,
+    current_l2_timestamp_slot: U256,
+    l1_height: u64,
+) -> Result<u64, Box<dyn Error>> {
+    let l2_timestamp = read_u64_slot(
+        l1_provider,
+        rollup_contract_address,
+        current_l2_timestamp_slot,
+        l1_height,
+    )
+    .await?;
+
+    debug!("l1_height {l1_height} is l2 timestamp {l2_timestamp}");
+
+    Ok(l2_timestamp)
+}
+
+/// Fetch a proof of the account `address` and the provided storage slots against the sparse merkle
+/// trie state root of the linea L2 at `l2_height`, via `linea_getProof`.
+///
+/// Note that this is *not* the same as `eth_getProof`, as linea commits to a different state root
+/// on the L1 than the one in the L2 block headers.
+#[instrument(skip_all, fields(%l2_height, %address))]
+pub async fn get_proof<N: Network>(
+    l2_provider: impl Provider<N>,
+    address: H160,
+    slots: impl IntoIterator<Item = U256>,
+    l2_height: u64,
+) -> Result<GetProof, Box<dyn Error>> {
+    let slots = slots
+        .into_iter()
+        .map(|slot| B256::from(slot.to_be_bytes()))
+        .collect::<Vec<_>>();
+
+    let proof = l2_provider
+        .raw_request::<_, GetProof>(
+            "linea_getProof".into(),
+            (
+                Address::from(address),
+                slots,
+                BlockNumberOrTag::Number(l2_height),
+            ),
+        )
+        .await?;
+
+    Ok(proof)
+}
+
+async fn read_u64_slot(
+    l1_provider: impl Provider,
+    contract_address: H160,
+    slot: U256,
+    l1_height: u64,
+) -> Result<u64, Box<dyn Error>> {
+    let raw_slot = l1_provider
+        .get_storage_at(contract_address.into(), slot.into())
+        .block_id(l1_height.into())
+        .await?;
+
+    Ok(u64::try_from(raw_slot)?)
+}
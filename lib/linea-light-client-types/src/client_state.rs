@@ -1,3 +1,4 @@
+use ibc_union_spec::ClientId;
 use unionlabs::{
     ibc::core::client::height::Height,
     primitives::{H160, U256},
@@ -5,11 +6,13 @@ use unionlabs::{
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct ClientState {
     pub chain_id: u64,
-    // TODO: This should be ClientId
-    pub l1_client_id: String,
-    pub l1_latest_height: Height,
+    /// Latest height of the L2
+    pub latest_height: u64,
+    /// Client id of the client tracking the L1 that the chain this client tracks settles on
+    pub l1_client_id: ClientId,
     pub l1_rollup_contract_address: H160,
     pub l1_rollup_current_l2_timestamp_slot: U256,
     pub l1_rollup_current_l2_block_number_slot: U256,
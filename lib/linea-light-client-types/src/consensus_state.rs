@@ -1,8 +1,45 @@
+use ibc_union_spec::Timestamp;
 use unionlabs::primitives::H256;
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct ConsensusState {
     pub ibc_storage_root: H256,
-    pub timestamp: u64,
+    /// Timestamp of the block, *normalized to nanoseconds* in order to be compatible with ibc-go.
+    pub timestamp: Timestamp,
+}
+
+#[cfg(feature = "ethabi")]
+pub mod ethabi {
+    use unionlabs::impl_ethabi_via_try_from_into;
+
+    use super::*;
+
+    impl_ethabi_via_try_from_into!(ConsensusState => SolConsensusState);
+
+    alloy::sol! {
+        struct SolConsensusState {
+            bytes32 ibc_storage_root;
+            uint64 timestamp;
+        }
+    }
+
+    impl From<ConsensusState> for SolConsensusState {
+        fn from(value: ConsensusState) -> Self {
+            Self {
+                ibc_storage_root: value.ibc_storage_root.get().into(),
+                timestamp: value.timestamp.as_nanos(),
+            }
+        }
+    }
+
+    impl From<SolConsensusState> for ConsensusState {
+        fn from(value: SolConsensusState) -> Self {
+            Self {
+                ibc_storage_root: H256::new(value.ibc_storage_root.0),
+                timestamp: Timestamp::from_nanos(value.timestamp),
+            }
+        }
+    }
 }
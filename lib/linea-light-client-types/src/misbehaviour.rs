@@ -0,0 +1,10 @@
+use crate::Header;
+
+/// Two headers attesting to different L2 state roots for the same L2 block number, each proven against the L1 state at their respective L1 heights.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct Misbehaviour {
+    pub header_a: Header,
+    pub header_b: Header,
+}
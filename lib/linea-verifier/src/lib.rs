@@ -1,6 +1,9 @@
 use core::fmt::Debug;
 
-use evm_storage_verifier::{verify_account_storage_root, verify_storage_proof};
+use evm_storage_verifier::{
+    misbehaviour::{self, L2StateRoot, MisbehaviourError},
+    verify_account_storage_root, verify_storage_proof,
+};
 use gnark_mimc::new_mimc_constants_bls12_377;
 use linea_light_client_types::{ClientState, Header};
 use linea_types::account::ZkAccount;
@@ -21,6 +24,8 @@ pub enum Error {
     InvalidL2StateRootProof(evm_storage_verifier::error::Error),
     #[error("invalid l2 ibc contract proof {0}")]
     InvalidL2IbcContractProof(linea_zktrie::verify::Error),
+    #[error(transparent)]
+    Misbehaviour(#[from] MisbehaviourError),
 }
 
 // 1. assert rootHash(rollup) in l1StateRoot
@@ -29,8 +34,8 @@ pub enum Error {
 // 4. assert rollup.stateRootHashes[l2BlockNumber] = l2StateRoot
 // 5. assert rootHash(l2IbcContract) in l2StateRoot
 pub fn verify_header(
-    client_state: ClientState,
-    header: Header,
+    client_state: &ClientState,
+    header: &Header,
     l1_state_root: H256,
 ) -> Result<(), Error> {
     // 1.
@@ -85,6 +90,30 @@ pub fn verify_header(
     Ok(())
 }
 
+/// Verify that the provided headers prove that two different L2 state roots have been committed
+/// on the L1 for the same L2 block number.
+///
+/// Each header is verified with [`verify_header`] against the L1 state root at its own L1 height.
+pub fn verify_misbehaviour(
+    client_state: &ClientState,
+    header_a: &Header,
+    l1_state_root_a: H256,
+    header_b: &Header,
+    l1_state_root_b: H256,
+) -> Result<(), Error> {
+    misbehaviour::verify_misbehaviour(
+        header_a,
+        l1_state_root_a,
+        header_b,
+        l1_state_root_b,
+        |header| L2StateRoot {
+            l2_height: header.l2_block_number_proof.value,
+            state_root: header.l2_state_root_proof.value.to_be_bytes().into(),
+        },
+        |header, l1_state_root| verify_header(client_state, header, l1_state_root),
+    )
+}
+
 pub fn state_root_hashes_mapping_key(slot: &U256, l2_block_number: &U256) -> U256 {
     Slot::Mapping(&Slot::Offset(*slot), MappingKey::Uint256(*l2_block_number)).slot()
 }
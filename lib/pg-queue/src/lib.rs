@@ -23,7 +23,9 @@ use tracing::{debug, debug_span, error, info, info_span, instrument, trace, warn
 use voyager_vm::{
     filter::{FilterResult, Interest, InterestFilter},
     pass::{Pass, PassResult},
-    BoxDynError, Captures, EnqueueResult, ItemId, Op, QueueError, QueueMessage,
+    retry::{retry, Backoff, ErrorKind, Retry, RetryPolicy},
+    BoxDynError, Captures, EnqueueResult, ItemId, ItemStatus, Op, QueueError, QueueItem,
+    QueueMessage,
 };
 
 use crate::metrics::Metrics;
@@ -46,6 +48,7 @@ pub struct PgQueue<T> {
     optimize_batch_limit: Option<i64>,
     retryable_error_expo_backoff_max: f64,
     retryable_error_expo_backoff_multiplier: f64,
+    retry_policies: Vec<RetryPolicy>,
 
     metrics: Metrics,
 
@@ -70,6 +73,12 @@ pub struct PgQueueConfig {
     pub retryable_error_expo_backoff_max: f64,
     #[serde(default = "default_retryable_error_expo_backoff_multiplier")]
     pub retryable_error_expo_backoff_multiplier: f64,
+    /// Policies for retrying items that error. The first policy that matches an errored item
+    /// will be used. Retryable errors that don't match any policy will be retried indefinitely,
+    /// and fatal and unprocessable errors that don't match any policy will be moved to the
+    /// `failed` table immediately.
+    #[serde(default)]
+    pub retry_policies: Vec<RetryPolicy>,
 }
 
 pub const fn default_max_connections() -> u32 {
@@ -117,13 +126,36 @@ struct QueueRecord {
 #[derive(Debug, FromRow)]
 struct OptimizeRecord {
     id: i64,
-    #[allow(dead_code)]
     parents: Vec<i64>,
     item: String,
-    #[allow(dead_code)]
     created_at: time::OffsetDateTime,
 }
 
+#[derive(Debug, FromRow)]
+struct ItemRecord<T: QueueMessage> {
+    id: i64,
+    parents: Vec<i64>,
+    item: Json<Op<T>>,
+    tag: Option<String>,
+    message: Option<String>,
+}
+
+impl<T: QueueMessage> ItemRecord<T> {
+    fn into_queue_item(self) -> QueueItem<T> {
+        QueueItem {
+            id: ItemId::new(self.id).expect("invalid id returned from database"),
+            parents: self
+                .parents
+                .into_iter()
+                .map(|id| ItemId::new(id).expect("invalid id returned from database"))
+                .collect(),
+            item: self.item.0,
+            tag: self.tag,
+            message: self.message,
+        }
+    }
+}
+
 #[derive(Debug, FromRow, Serialize)]
 #[serde(bound(serialize = ""))]
 pub struct FailedRecord<T: QueueMessage> {
@@ -134,6 +166,57 @@ pub struct FailedRecord<T: QueueMessage> {
     // pub created_at: sqlx::types::time::OffsetDateTime,
 }
 
+#[derive(Debug, FromRow, Serialize)]
+#[serde(bound(serialize = ""))]
+pub struct HistoryRecord<T: QueueMessage> {
+    pub id: i64,
+    pub parents: Vec<i64>,
+    pub item: Json<Op<T>>,
+    /// The distance from the item that the history was queried for, starting at 1.
+    pub depth: i32,
+}
+
+/// An item in the queue, along with all of the items that produced it.
+#[derive(Debug, Serialize)]
+#[serde(bound(serialize = ""))]
+pub struct HistoryNode<T: QueueMessage> {
+    pub id: i64,
+    pub item: Op<T>,
+    pub parents: Vec<HistoryNode<T>>,
+}
+
+impl<T: QueueMessage> HistoryNode<T> {
+    /// Build the lineage tree of `id` out of the flat records returned by
+    /// [`PgQueue::query_history`]. Parents that were not returned (i.e. because they are past the
+    /// max depth or have been pruned from the database) are omitted.
+    pub fn from_records(id: i64, records: Vec<HistoryRecord<T>>) -> Option<Self> {
+        fn build<T: QueueMessage>(
+            id: i64,
+            records: &HashMap<i64, HistoryRecord<T>>,
+        ) -> Option<HistoryNode<T>> {
+            let record = records.get(&id)?;
+
+            Some(HistoryNode {
+                id,
+                item: record.item.0.clone(),
+                parents: records
+                    .get_many(&record.parents)
+                    .into_iter()
+                    .filter_map(|parent| build(parent.id, records))
+                    .collect(),
+            })
+        }
+
+        build(
+            id,
+            &records
+                .into_iter()
+                .map(|record| (record.id, record))
+                .collect(),
+        )
+    }
+}
+
 impl<T: QueueMessage> PgQueue<T> {
     pub async fn query_failed(
         &self,
@@ -205,6 +288,124 @@ impl<T: QueueMessage> PgQueue<T> {
         .await?
         .transpose()
     }
+
+    /// Move all failed items matching the provided filters back into the queue, returning the ids
+    /// of the requeued items. The filters have the same syntax as [`Self::query_failed`].
+    ///
+    /// Requeued items keep their original id and parents, and their attempt count is reset.
+    pub async fn requeue_failed(
+        &self,
+        mut item_filters: Vec<String>,
+        mut message_filters: Vec<String>,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        // default to all-inclusive filter if none are provided
+        if item_filters.is_empty() {
+            item_filters.push("%".to_owned())
+        }
+
+        if message_filters.is_empty() {
+            message_filters.push("%".to_owned())
+        }
+
+        sqlx::query(
+            r#"
+            WITH
+              requeued AS (
+                DELETE FROM
+                  failed
+                WHERE
+                  item::TEXT LIKE ANY($1)
+                  AND message LIKE ANY($2)
+                RETURNING
+                  id,
+                  parents,
+                  item,
+                  created_at
+              )
+            INSERT INTO
+              queue (id, parents, item, created_at)
+            SELECT
+              id,
+              parents,
+              item,
+              created_at
+            FROM
+              requeued
+            RETURNING
+              id
+            "#,
+        )
+        .bind(item_filters)
+        .bind(message_filters)
+        .try_map(|x| Id::from_row(&x))
+        .fetch_all(&self.client)
+        .await
+        .map(|ids| ids.into_iter().map(|id| id.id).collect())
+    }
+
+    /// Query the lineage of the item with the specified id, up to `max_depth` generations back.
+    ///
+    /// The item itself is included in the results with a depth of 1. Items are searched for in
+    /// the `queue`, `optimize`, `done` and `failed` tables.
+    pub async fn query_history(
+        &self,
+        id: i64,
+        max_depth: i32,
+    ) -> Result<Vec<HistoryRecord<T>>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            WITH RECURSIVE
+              items AS (
+                SELECT id, parents, item FROM queue
+                UNION ALL
+                SELECT id, parents, item FROM optimize
+                UNION ALL
+                SELECT id, parents, item FROM done
+                UNION ALL
+                SELECT id, parents, item FROM failed
+              ),
+              history (id, parents, item, depth) AS (
+                SELECT
+                  id,
+                  parents,
+                  item,
+                  1
+                FROM
+                  items
+                WHERE
+                  id = $1
+                UNION
+                SELECT
+                  items.id,
+                  items.parents,
+                  items.item,
+                  history.depth + 1
+                FROM
+                  items
+                  JOIN history ON items.id = ANY(history.parents)
+                WHERE
+                  history.depth < $2
+              )
+            SELECT DISTINCT ON (id)
+              id,
+              COALESCE(parents, '{}') AS parents,
+              item,
+              depth
+            FROM
+              history
+            ORDER BY
+              id DESC,
+              depth ASC
+            "#,
+        )
+        .bind(id)
+        .bind(max_depth)
+        .map(|row| HistoryRecord::<T>::from_row(&row))
+        .fetch_all(&self.client)
+        .await?
+        .into_iter()
+        .collect()
+    }
 }
 
 impl<T: QueueMessage> voyager_vm::Queue<T> for PgQueue<T> {
@@ -228,6 +429,7 @@ impl<T: QueueMessage> voyager_vm::Queue<T> for PgQueue<T> {
         let retryable_error_expo_backoff_multiplier =
             config.retryable_error_expo_backoff_multiplier;
         let retryable_error_expo_backoff_max = config.retryable_error_expo_backoff_max;
+        let retry_policies = config.retry_policies.clone();
 
         let pool = config.into_pg_pool().await?;
 
@@ -290,6 +492,7 @@ impl<T: QueueMessage> voyager_vm::Queue<T> for PgQueue<T> {
             optimize_batch_limit,
             retryable_error_expo_backoff_max,
             retryable_error_expo_backoff_multiplier,
+            retry_policies,
             metrics: Metrics::new(),
             __marker: PhantomData,
         })
@@ -424,6 +627,7 @@ impl<T: QueueMessage> voyager_vm::Queue<T> for PgQueue<T> {
                     filter,
                     self.retryable_error_expo_backoff_max,
                     self.retryable_error_expo_backoff_multiplier,
+                    &self.retry_policies,
                 )
                 .await?
             }
@@ -487,6 +691,25 @@ impl<T: QueueMessage> voyager_vm::Queue<T> for PgQueue<T> {
             return Ok(());
         }
 
+        // record the optimized items in done, so that the lineage of the items they produce can
+        // be tracked
+        for record in &msgs {
+            sqlx::query(
+                "
+                INSERT INTO
+                done   (id, parents, item,      created_at)
+                VALUES ($1, $2,      $3::JSONB, $4        )
+                ",
+            )
+            .bind(record.id)
+            .bind(&record.parents)
+            .bind(&record.item)
+            .bind(record.created_at)
+            .execute(tx.as_mut())
+            .await
+            .map_err(Either::Left)?;
+        }
+
         let (ids, msgs) = msgs
             .into_iter()
             .map(|r| {
@@ -628,6 +851,144 @@ impl<T: QueueMessage> voyager_vm::Queue<T> for PgQueue<T> {
 
         Ok(())
     }
+
+    async fn query_items(
+        &self,
+        status: ItemStatus,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<QueueItem<T>>, Self::Error> {
+        let query = match status {
+            ItemStatus::Queued => {
+                r#"
+                SELECT
+                  id,
+                  COALESCE(parents, '{}') AS parents,
+                  item,
+                  NULL::TEXT AS tag,
+                  NULL::TEXT AS message
+                FROM
+                  queue
+                ORDER BY
+                  id DESC
+                LIMIT
+                  $1
+                OFFSET
+                  $2
+                "#
+            }
+            ItemStatus::Optimize => {
+                r#"
+                SELECT
+                  id,
+                  COALESCE(parents, '{}') AS parents,
+                  item,
+                  tag,
+                  NULL::TEXT AS message
+                FROM
+                  optimize
+                ORDER BY
+                  id DESC
+                LIMIT
+                  $1
+                OFFSET
+                  $2
+                "#
+            }
+            ItemStatus::Failed => {
+                r#"
+                SELECT
+                  id,
+                  COALESCE(parents, '{}') AS parents,
+                  item,
+                  NULL::TEXT AS tag,
+                  COALESCE(message, '') AS message
+                FROM
+                  failed
+                ORDER BY
+                  id DESC
+                LIMIT
+                  $1
+                OFFSET
+                  $2
+                "#
+            }
+        };
+
+        sqlx::query(query)
+            .bind(i64::from(per_page))
+            .bind(i64::from(page.saturating_sub(1)) * i64::from(per_page))
+            .try_map(|row| ItemRecord::<T>::from_row(&row))
+            .fetch_all(&self.client)
+            .await
+            .map(|records| {
+                records
+                    .into_iter()
+                    .map(ItemRecord::into_queue_item)
+                    .collect()
+            })
+    }
+
+    async fn requeue_failed_items(&self, ids: Vec<ItemId>) -> Result<Vec<ItemId>, Self::Error> {
+        sqlx::query(
+            r#"
+            WITH
+              requeued AS (
+                DELETE FROM
+                  failed
+                WHERE
+                  id = ANY($1)
+                RETURNING
+                  id,
+                  parents,
+                  item,
+                  created_at
+              )
+            INSERT INTO
+              queue (id, parents, item, created_at)
+            SELECT
+              id,
+              parents,
+              item,
+              created_at
+            FROM
+              requeued
+            RETURNING
+              id
+            "#,
+        )
+        .bind(ids.iter().map(ItemId::raw).collect::<Vec<_>>())
+        .try_map(|x| Id::from_row(&x))
+        .fetch_all(&self.client)
+        .await
+        .map(|ids| {
+            ids.into_iter()
+                .map(|id| ItemId::new(id.id).expect("invalid id returned from database"))
+                .collect()
+        })
+    }
+
+    async fn delete_failed_items(&self, ids: Vec<ItemId>) -> Result<Vec<ItemId>, Self::Error> {
+        sqlx::query(
+            r#"
+            DELETE FROM
+              failed
+            WHERE
+              id = ANY($1)
+            RETURNING
+              id
+            "#,
+        )
+        .bind(ids.iter().map(ItemId::raw).collect::<Vec<_>>())
+        .try_map(|x| Id::from_row(&x))
+        .fetch_all(&self.client)
+        .await
+        .map(|ids| {
+            ids.into_iter()
+                .map(|id| ItemId::new(id.id).expect("invalid id returned from database"))
+                .collect()
+        })
+    }
 }
 
 #[instrument(
@@ -645,6 +1006,7 @@ async fn process_item<'a, T, F, Fut, R, Filter>(
     filter: &'a Filter,
     retryable_error_expo_backoff_max: f64,
     retryable_error_expo_backoff_multiplier: f64,
+    retry_policies: &[RetryPolicy],
 ) -> Result<Option<R>, sqlx::Error>
 where
     T: QueueMessage,
@@ -665,20 +1027,43 @@ where
         .record(Instant::now().duration_since(now).as_secs_f64(), &[]);
 
     match res {
-        Err(QueueError::Fatal(error)) => {
+        Err(error) => {
+            let (kind, error) = ErrorKind::from_queue_error(error);
             let error = full_error_string(error);
-            error!(%error, "fatal error");
-            insert_error(record, error, tx).await?;
-            metrics.fatal_errors_count.add(1, &[]);
-        }
-        Err(QueueError::Unprocessable(error)) => {
-            let error = full_error_string(error);
-            info!(%error, "unprocessable message");
-            insert_error(record, error, tx).await?;
-            metrics.unprocessable_count.add(1, &[]);
-        }
-        Err(QueueError::Retry(error)) => {
-            warn!(error = %full_error_string(error), "retryable error");
+
+            let policy = find_retry_policy(tx, retry_policies, kind, &record.item, &error).await?;
+
+            match kind {
+                ErrorKind::Fatal => {
+                    error!(%error, "fatal error");
+                    metrics.fatal_errors_count.add(1, &[]);
+                }
+                ErrorKind::Unprocessable => {
+                    info!(%error, "unprocessable message");
+                    metrics.unprocessable_count.add(1, &[]);
+                }
+                ErrorKind::Retry => {
+                    warn!(%error, "retryable error");
+                    metrics.retryable_errors_count.add(1, &[]);
+                }
+            }
+
+            let backoff = match retry(
+                policy,
+                kind,
+                record.attempt,
+                Backoff {
+                    max: retryable_error_expo_backoff_max,
+                    multiplier: retryable_error_expo_backoff_multiplier,
+                },
+            ) {
+                Retry::After(backoff) => backoff,
+                Retry::Fail => {
+                    insert_error(record, error, tx).await?;
+                    return Ok(Some(r));
+                }
+            };
+
             sqlx::query(
                 "
                 INSERT INTO
@@ -691,23 +1076,14 @@ where
             .bind(record.parents)
             .bind(record.attempt.saturating_add(1))
             .bind(
-                time::OffsetDateTime::now_utc().saturating_add(
-                    Duration::try_from_secs_f64(
-                        (record.attempt as f64)
-                            .powf(retryable_error_expo_backoff_multiplier)
-                            .clamp(f64::MIN, retryable_error_expo_backoff_max),
-                    )
-                    .unwrap_or(Duration::MAX)
-                    .try_into()
-                    .unwrap_or(time::Duration::MAX),
-                ),
+                time::OffsetDateTime::now_utc()
+                    .saturating_add(backoff.try_into().unwrap_or(time::Duration::MAX)),
             )
             .bind(record.created_at)
             .execute(tx.as_mut())
             .await?;
 
             tokio::time::sleep(Duration::from_millis(500)).await;
-            metrics.retryable_errors_count.add(1, &[]);
         }
         Ok(ops) => {
             'block: {
@@ -782,6 +1158,61 @@ where
     Ok(Some(r))
 }
 
+/// Find the first retry policy that applies to the errored item.
+async fn find_retry_policy<'a>(
+    tx: &mut Transaction<'static, Postgres>,
+    retry_policies: &'a [RetryPolicy],
+    kind: ErrorKind,
+    item: &str,
+    error: &str,
+) -> Result<Option<&'a RetryPolicy>, sqlx::Error> {
+    let policies = retry_policies
+        .iter()
+        .filter(|policy| policy.applies_to(kind))
+        .collect::<Vec<_>>();
+
+    if policies.is_empty() {
+        return Ok(None);
+    }
+
+    // reuse the database's LIKE implementation so that the filters behave exactly the same as the
+    // filters for querying failed items. all of the policies are checked at once, and the index of
+    // the first matching one is returned
+    let idx = sqlx::query_scalar::<_, i64>(
+        "
+        SELECT
+          idx - 1
+        FROM
+          jsonb_array_elements($3::JSONB) WITH ORDINALITY AS policies (policy, idx)
+        WHERE
+          (
+            jsonb_array_length(policy -> 'item_filters') = 0
+            OR $1::JSONB::TEXT LIKE ANY (ARRAY(SELECT jsonb_array_elements_text(policy -> 'item_filters')))
+          )
+          AND (
+            jsonb_array_length(policy -> 'message_filters') = 0
+            OR $2 LIKE ANY (ARRAY(SELECT jsonb_array_elements_text(policy -> 'message_filters')))
+          )
+        ORDER BY
+          idx ASC
+        LIMIT 1
+        ",
+    )
+    .bind(item)
+    .bind(error)
+    .bind(Json(&policies))
+    .fetch_optional(tx.as_mut())
+    .await?;
+
+    let policy = idx.and_then(|idx| policies.get(usize::try_from(idx).ok()?).copied());
+
+    if let Some(policy) = policy {
+        trace!(?policy, "found matching retry policy");
+    }
+
+    Ok(policy)
+}
+
 async fn insert_error(
     record: QueueRecord,
     error: String,
@@ -850,3 +1281,74 @@ impl<K: Hash + Eq, V> MapExt<K, V> for HashMap<K, V> {
         out
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use voyager_vm::defer;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum UnitMessage {}
+
+    impl QueueMessage for UnitMessage {
+        type Data = ();
+        type Call = ();
+        type Callback = ();
+    }
+
+    fn record(id: i64, parents: &[i64], depth: i32) -> HistoryRecord<UnitMessage> {
+        HistoryRecord {
+            id,
+            parents: parents.to_vec(),
+            item: Json(defer(id.try_into().unwrap())),
+            depth,
+        }
+    }
+
+    /// Render the tree as `id(parent, ...)`, checking that every node has the item of its record.
+    fn render(node: &HistoryNode<UnitMessage>) -> String {
+        assert_eq!(node.item, defer(node.id.try_into().unwrap()));
+
+        if node.parents.is_empty() {
+            node.id.to_string()
+        } else {
+            format!(
+                "{}({})",
+                node.id,
+                node.parents.iter().map(render).join(", ")
+            )
+        }
+    }
+
+    #[test]
+    fn history_tree() {
+        let node = HistoryNode::from_records(
+            4,
+            vec![
+                record(4, &[3, 2], 1),
+                record(3, &[1], 2),
+                record(2, &[1], 2),
+                record(1, &[], 3),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(render(&node), "4(3(1), 2(1))");
+    }
+
+    #[test]
+    fn history_missing_parents_are_omitted() {
+        // 2 is past the max depth, 1 has been pruned from the database
+        let node =
+            HistoryNode::from_records(4, vec![record(4, &[3, 1], 1), record(3, &[2], 2)]).unwrap();
+
+        assert_eq!(render(&node), "4(3)");
+    }
+
+    #[test]
+    fn history_unknown_item() {
+        assert!(HistoryNode::from_records(2, vec![record(1, &[], 1)]).is_none());
+        assert!(HistoryNode::<UnitMessage>::from_records(1, vec![]).is_none());
+    }
+}
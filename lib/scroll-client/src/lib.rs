@@ -0,0 +1,189 @@
+use std::error::Error;
+
+use alloy::{consensus::Transaction, network::AnyNetwork, providers::Provider, sol_types::SolCall};
+use scroll_api::{BatchResponseData, ScrollClient};
+use scroll_codec::{
+    chunk::{compute_chunk_hash, decode_chunk, BlockContext},
+    commitBatchWithBlobProofCall, finalizeBundleCall, finalizeBundleWithProofCall,
+};
+use scroll_light_client_types::ChunkProof;
+use serde::Deserialize;
+use tracing::{debug, instrument};
+use unionlabs::primitives::{H160, H256, U256};
+
+/// The transaction type of L1 messages included in L2 blocks.
+const L1_MESSAGE_TX_TYPE: &str = "0x7e";
+
+/// Read the `lastFinalizedBatchIndex` of the scroll rollup contract at the specified L1 height.
+#[instrument(skip_all, fields(%l1_height, %rollup_contract_address))]
+pub async fn last_finalized_batch_index_at_l1_height(
+    l1_provider: impl Provider,
+    rollup_contract_address: H160,
+    latest_batch_index_slot: U256,
+    l1_height: u64,
+) -> Result<u64, Box<dyn Error>> {
+    let raw_slot = l1_provider
+        .get_storage_at(
+            rollup_contract_address.into(),
+            latest_batch_index_slot.into(),
+        )
+        .block_id(l1_height.into())
+        .await?;
+
+    let batch_index = u64::try_from(raw_slot)?;
+
+    debug!("l1_height {l1_height} is last finalized batch index {batch_index}");
+
+    Ok(batch_index)
+}
+
+/// Fetch the last batch that has been finalized on the L1 as of the specified L1 height.
+#[instrument(skip_all, fields(%l1_height, %rollup_contract_address))]
+pub async fn finalized_batch_of_l1_height(
+    l1_provider: impl Provider,
+    scroll_api_client: &ScrollClient,
+    rollup_contract_address: H160,
+    latest_batch_index_slot: U256,
+    l1_height: u64,
+) -> Result<BatchResponseData, Box<dyn Error>> {
+    let batch_index = last_finalized_batch_index_at_l1_height(
+        &l1_provider,
+        rollup_contract_address,
+        latest_batch_index_slot,
+        l1_height,
+    )
+    .await?;
+
+    let batch = scroll_api_client.batch(batch_index).await.batch;
+
+    debug!(
+        "batch index {batch_index} is l2 block number {}",
+        batch.end_block_number
+    );
+
+    Ok(batch)
+}
+
+/// Extract the batch header from the calldata of the transaction that finalized the batch.
+///
+/// Both `finalizeBundle` and `finalizeBundleWithProof` are supported.
+#[instrument(skip_all, fields(%finalize_tx_hash))]
+pub async fn batch_header_of_finalize_tx(
+    l1_provider: impl Provider,
+    finalize_tx_hash: H256,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let tx = l1_provider
+        .get_transaction_by_hash(finalize_tx_hash.into())
+        .await?
+        .ok_or_else(|| format!("finalize tx {finalize_tx_hash} not found"))?;
+
+    let batch_header = match finalizeBundleWithProofCall::abi_decode_validate(tx.input()) {
+        Ok(call) => call._batchHeader,
+        Err(_) => finalizeBundleCall::abi_decode_validate(tx.input())?._batchHeader,
+    };
+
+    Ok(batch_header.to_vec())
+}
+
+/// Build the [`ChunkProof`] of the last block of a batch from the chunks in the calldata of the
+/// transaction that committed the batch.
+///
+/// Only `commitBatchWithBlobProof` is supported.
+#[instrument(skip_all, fields(%commit_tx_hash))]
+pub async fn last_chunk_proof_of_commit_tx(
+    l1_provider: impl Provider,
+    l2_provider: impl Provider<AnyNetwork>,
+    commit_tx_hash: H256,
+) -> Result<ChunkProof, Box<dyn Error>> {
+    let tx = l1_provider
+        .get_transaction_by_hash(commit_tx_hash.into())
+        .await?
+        .ok_or_else(|| format!("commit tx {commit_tx_hash} not found"))?;
+
+    let chunks = commitBatchWithBlobProofCall::abi_decode_validate(tx.input())?
+        ._chunks
+        .iter()
+        .map(decode_chunk)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut chunk_proofs = Vec::with_capacity(chunks.len());
+
+    for block_contexts in chunks {
+        let mut l1_message_hashes = vec![];
+
+        for block_context in &block_contexts {
+            l1_message_hashes
+                .extend(l1_message_hashes_of_block(&l2_provider, block_context).await?);
+        }
+
+        chunk_proofs.push((
+            block_contexts
+                .iter()
+                .flat_map(BlockContext::encode_hashed)
+                .collect::<Vec<_>>(),
+            l1_message_hashes,
+        ));
+    }
+
+    let (block_contexts, l1_message_hashes) = chunk_proofs
+        .pop()
+        .ok_or_else(|| format!("commit tx {commit_tx_hash} contains no chunks"))?;
+
+    debug!(
+        chunks = chunk_proofs.len() + 1,
+        "built chunk proof of commit tx"
+    );
+
+    Ok(ChunkProof {
+        previous_chunk_hashes: chunk_proofs
+            .iter()
+            .map(|(block_contexts, l1_message_hashes)| {
+                compute_chunk_hash(block_contexts, l1_message_hashes)
+            })
+            .collect(),
+        block_contexts,
+        l1_message_hashes,
+    })
+}
+
+/// The hashes of the L1 messages included in the block, in order.
+///
+/// The hash of an L1 message is the hash of the L1 message transaction on the L2.
+async fn l1_message_hashes_of_block(
+    l2_provider: &impl Provider<AnyNetwork>,
+    block_context: &BlockContext,
+) -> Result<Vec<H256>, Box<dyn Error>> {
+    // skipped L1 messages are counted in the block context but not included in the block
+    if block_context.num_l1_messages == 0 {
+        return Ok(vec![]);
+    }
+
+    #[derive(Deserialize)]
+    struct Block {
+        transactions: Vec<BlockTransaction>,
+    }
+
+    #[derive(Deserialize)]
+    struct BlockTransaction {
+        #[serde(rename = "type")]
+        ty: String,
+        hash: H256,
+    }
+
+    // the transactions are fetched as raw json since L1 message transactions can't be decoded by
+    // alloy
+    let block = l2_provider
+        .raw_request::<_, Option<Block>>(
+            "eth_getBlockByNumber".into(),
+            (format!("{:#x}", block_context.block_number), true),
+        )
+        .await?
+        .ok_or_else(|| format!("block {} not found", block_context.block_number))?;
+
+    Ok(block
+        .transactions
+        .into_iter()
+        .filter(|tx| tx.ty == L1_MESSAGE_TX_TYPE)
+        .map(|tx| tx.hash)
+        .collect())
+}
@@ -0,0 +1,218 @@
+use sha3::{Digest, Keccak256};
+use unionlabs::{
+    errors::{ExpectedLength, InvalidLength},
+    primitives::{ByteArrayExt, H256, U256},
+};
+
+/// @dev Below is the encoding for `BlockContext`, total 60 bytes.
+/// ```text
+///   * Field                   Bytes      Type         Index  Comments
+///   * blockNumber             8          uint64       0      The height of this block.
+///   * timestamp               8          uint64       8      The timestamp of this block.
+///   * baseFee                 32         uint256      16     The base fee of this block.
+///   * gasLimit                8          uint64       48     The gas limit of this block.
+///   * numTransactions         2          uint16       56     The number of transactions in this block, both L1 & L2 txs.
+///   * numL1Messages           2          uint16       58     The number of l1 messages in this block.
+/// ```
+/// Only the first 58 bytes (everything except `numL1Messages`) are included in the chunk hash.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockContext {
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub base_fee: U256,
+    pub gas_limit: u64,
+    pub num_transactions: u16,
+    pub num_l1_messages: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ChunkDecodeError {
+    #[error("chunk is empty")]
+    Empty,
+    #[error("invalid chunk length")]
+    InvalidLength(#[source] InvalidLength),
+}
+
+impl BlockContext {
+    pub const LENGTH: usize = 60;
+
+    /// The length of the prefix of the block context that is included in the chunk hash.
+    pub const HASHED_LENGTH: usize = 58;
+
+    /// Decode the block number and timestamp from the hashed prefix of a block context.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the length of the input is not [`Self::HASHED_LENGTH`].
+    pub fn decode_hashed(bz: impl AsRef<[u8]>) -> Result<(u64, u64), InvalidLength> {
+        let bz = bz.as_ref();
+
+        let slice: [u8; Self::HASHED_LENGTH] = bz.try_into().map_err(|_| InvalidLength {
+            expected: ExpectedLength::Exact(Self::HASHED_LENGTH),
+            found: bz.len(),
+        })?;
+
+        Ok((
+            u64::from_be_bytes(slice.array_slice::<0, 8>()),
+            u64::from_be_bytes(slice.array_slice::<8, 8>()),
+        ))
+    }
+
+    #[must_use]
+    pub fn decode(slice: [u8; Self::LENGTH]) -> Self {
+        Self {
+            block_number: u64::from_be_bytes(slice.array_slice::<0, 8>()),
+            timestamp: u64::from_be_bytes(slice.array_slice::<8, 8>()),
+            base_fee: U256::from_be_bytes(slice.array_slice::<16, 32>()),
+            gas_limit: u64::from_be_bytes(slice.array_slice::<48, 8>()),
+            num_transactions: u16::from_be_bytes(slice.array_slice::<56, 2>()),
+            num_l1_messages: u16::from_be_bytes(slice.array_slice::<58, 2>()),
+        }
+    }
+
+    /// The prefix of the block context that is included in the chunk hash.
+    #[must_use]
+    pub fn encode_hashed(&self) -> [u8; Self::HASHED_LENGTH] {
+        let mut out = [0; Self::HASHED_LENGTH];
+
+        out[0..8].copy_from_slice(&self.block_number.to_be_bytes());
+        out[8..16].copy_from_slice(&self.timestamp.to_be_bytes());
+        out[16..48].copy_from_slice(&self.base_fee.to_be_bytes());
+        out[48..56].copy_from_slice(&self.gas_limit.to_be_bytes());
+        out[56..58].copy_from_slice(&self.num_transactions.to_be_bytes());
+
+        out
+    }
+}
+
+/// Decode a chunk as it is passed to `commitBatchWithBlobProof`.
+///
+/// @dev Below is the encoding for `Chunk`, total 60*n+1 bytes.
+/// ```text
+///   * Field           Bytes       Type            Index       Comments
+///   * numBlocks       1           uint8           0           The number of blocks in this chunk
+///   * block[0]        60          BlockContext    1           The first block in this chunk
+///   * ......
+///   * block[i]        60          BlockContext    60*i+1      The (i+1)'th block in this chunk
+/// ```
+///
+/// # Errors
+///
+/// Fails if the chunk contains no blocks, or if the length doesn't match `numBlocks`.
+pub fn decode_chunk(bz: impl AsRef<[u8]>) -> Result<Vec<BlockContext>, ChunkDecodeError> {
+    let bz = bz.as_ref();
+
+    let (&num_blocks, block_contexts) = bz.split_first().ok_or(ChunkDecodeError::Empty)?;
+
+    if num_blocks == 0 {
+        return Err(ChunkDecodeError::Empty);
+    }
+
+    let expected = usize::from(num_blocks) * BlockContext::LENGTH;
+
+    if block_contexts.len() != expected {
+        return Err(ChunkDecodeError::InvalidLength(InvalidLength {
+            expected: ExpectedLength::Exact(expected + 1),
+            found: bz.len(),
+        }));
+    }
+
+    Ok(block_contexts
+        .iter()
+        .copied()
+        .array_chunks::<{ BlockContext::LENGTH }>()
+        .map(BlockContext::decode)
+        .collect())
+}
+
+/// Compute the hash of a chunk, given the concatenated hashed prefixes of its block contexts
+/// (see [`BlockContext::encode_hashed`]) and the hashes of the L1 messages included in it.
+#[must_use]
+pub fn compute_chunk_hash(block_contexts: &[u8], l1_message_hashes: &[H256]) -> H256 {
+    let mut hasher = Keccak256::new();
+
+    hasher.update(block_contexts);
+    for l1_message_hash in l1_message_hashes {
+        hasher.update(l1_message_hash);
+    }
+
+    hasher.finalize().into()
+}
+
+/// Compute the `dataHash` of a batch from the hashes of its chunks.
+#[must_use]
+pub fn compute_data_hash<'a>(chunk_hashes: impl IntoIterator<Item = &'a H256>) -> H256 {
+    let mut hasher = Keccak256::new();
+
+    for chunk_hash in chunk_hashes {
+        hasher.update(chunk_hash);
+    }
+
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use hex_literal::hex;
+
+    use super::*;
+
+    fn block_context(block_number: u64, timestamp: u64) -> BlockContext {
+        BlockContext {
+            block_number,
+            timestamp,
+            base_fee: U256::from(252_000_000_u64),
+            gas_limit: 10_000_000,
+            num_transactions: 3,
+            num_l1_messages: 1,
+        }
+    }
+
+    #[test]
+    fn decode_chunk_roundtrip() {
+        let blocks = [block_context(100, 1_000), block_context(101, 1_003)];
+
+        let mut chunk = vec![2];
+        for block in &blocks {
+            chunk.extend(block.encode_hashed());
+            chunk.extend(block.num_l1_messages.to_be_bytes());
+        }
+
+        assert_eq!(decode_chunk(&chunk).unwrap(), blocks);
+
+        assert_eq!(
+            BlockContext::decode_hashed(blocks[1].encode_hashed()).unwrap(),
+            (101, 1_003)
+        );
+    }
+
+    #[test]
+    fn decode_chunk_invalid_length() {
+        assert_eq!(decode_chunk([]), Err(ChunkDecodeError::Empty));
+        assert_eq!(decode_chunk([0]), Err(ChunkDecodeError::Empty));
+        assert!(matches!(
+            decode_chunk([1; 60]),
+            Err(ChunkDecodeError::InvalidLength(_))
+        ));
+    }
+
+    #[test]
+    fn chunk_hash_is_keccak_of_contexts_and_messages() {
+        let block = block_context(1, 2);
+        let l1_message_hash = <H256>::new(hex!(
+            "0101010101010101010101010101010101010101010101010101010101010101"
+        ));
+
+        let mut preimage = block.encode_hashed().to_vec();
+        preimage.extend(l1_message_hash.get());
+
+        let chunk_hash = compute_chunk_hash(&block.encode_hashed(), &[l1_message_hash]);
+
+        assert_eq!(chunk_hash, <H256>::from(Keccak256::digest(&preimage)));
+
+        assert_eq!(
+            compute_data_hash(&[chunk_hash]),
+            <H256>::from(Keccak256::digest(chunk_hash.get()))
+        );
+    }
+}
@@ -9,8 +9,18 @@ use unionlabs::primitives::H256;
 use crate::batch_header::{BatchHeaderV3, BatchHeaderV3DecodeError};
 
 pub mod batch_header;
+pub mod chunk;
 
 alloy_sol_types::sol! {
+    /// See <https://github.com/scroll-tech/scroll-contracts/blob/7bb751f9cf1b5fdde95297049e3407ce23d56ac6/src/L1/rollup/ScrollChain.sol#L308>
+    function commitBatchWithBlobProof(
+        uint8 _version,
+        bytes calldata _parentBatchHeader,
+        bytes[] memory _chunks,
+        bytes calldata _skippedL1MessageBitmap,
+        bytes calldata _blobDataProof
+    );
+
     /// See <https://github.com/scroll-tech/scroll-contracts/blob/7bb751f9cf1b5fdde95297049e3407ce23d56ac6/src/mocks/ScrollChainMockFinalize.sol#L59>
     function finalizeBundle(
         bytes calldata _batchHeader,
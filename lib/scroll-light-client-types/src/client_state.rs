@@ -1,3 +1,4 @@
+use ibc_union_spec::ClientId;
 use unionlabs::{
     ibc::core::client::height::Height,
     primitives::{H160, U256},
@@ -5,15 +6,17 @@ use unionlabs::{
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct ClientState {
     pub chain_id: u64,
+    /// Latest height of the L2
+    pub latest_height: u64,
+    /// Client id of the client tracking the L1 that the chain this client tracks settles on
+    pub l1_client_id: ClientId,
     pub frozen_height: Height,
     pub ibc_contract_address: H160,
-    // TODO: This should be ClientId
-    pub l1_client_id: u32,
     pub l2_committed_batches_slot: U256,
     pub l2_contract_address: H160,
     pub l2_finalized_state_roots_slot: U256,
     pub latest_batch_index_slot: U256,
-    pub latest_slot: u64,
 }
@@ -1,8 +1,9 @@
 use ethereum_light_client_types::{AccountProof, StorageProof};
-use unionlabs::ibc::core::client::height::Height;
+use unionlabs::{ibc::core::client::height::Height, primitives::H256};
 
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct Header {
     pub l1_height: Height,
     pub l1_account_proof: AccountProof,
@@ -12,4 +13,25 @@ pub struct Header {
     pub batch_hash_proof: StorageProof,
     pub l2_ibc_account_proof: AccountProof,
     pub batch_header: Vec<u8>,
+    /// Proof of the number of the last L2 block in the finalized batch, which the consensus state
+    /// is stored at.
+    pub last_chunk_proof: ChunkProof,
+}
+
+/// The preimage of the `dataHash` of a batch, opened up to the block contexts of its last chunk.
+///
+/// The rollup contract does not store L2 block numbers, but the `dataHash` committed to in the
+/// batch header is the hash of the chunk hashes, and each chunk hash commits to the block contexts
+/// (which start with the block number) of the blocks in the chunk.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct ChunkProof {
+    /// The hashes of all chunks in the batch before the last chunk.
+    pub previous_chunk_hashes: Vec<H256>,
+    /// The concatenated block contexts of the last chunk, truncated to the 58 bytes of each
+    /// context that are included in the chunk hash.
+    pub block_contexts: Vec<u8>,
+    /// The hashes of the L1 messages included in the last chunk.
+    pub l1_message_hashes: Vec<H256>,
 }
@@ -1,8 +1,15 @@
 use core::fmt::Debug;
 
-use evm_storage_verifier::{verify_account_storage_root, verify_storage_proof};
-use scroll_codec::{hash_batch, HashBatchError};
-use scroll_light_client_types::{ClientState, Header};
+use evm_storage_verifier::{
+    misbehaviour::{self, L2StateRoot, MisbehaviourError},
+    verify_account_storage_root, verify_storage_proof,
+};
+use scroll_codec::{
+    batch_header::{BatchHeaderV3, BatchHeaderV3DecodeError},
+    chunk::{compute_chunk_hash, compute_data_hash, BlockContext},
+    hash_batch, HashBatchError,
+};
+use scroll_light_client_types::{ChunkProof, ClientState, Header};
 use scroll_types::account::Account;
 use unionlabs::{
     ethereum::slot::{MappingKey, Slot},
@@ -26,6 +33,19 @@ pub enum Error {
     ValueMismatch,
     #[error(transparent)]
     HashBatch(#[from] HashBatchError),
+    #[error(transparent)]
+    BatchHeaderDecode(#[from] BatchHeaderV3DecodeError),
+    #[error(
+        "the block contexts of the last chunk must be a non-empty multiple of {} bytes, found {0} bytes",
+        BlockContext::HASHED_LENGTH
+    )]
+    InvalidBlockContextsLength(usize),
+    #[error("data hash mismatch, expected {expected} but found {found}")]
+    DataHashMismatch { expected: H256, found: H256 },
+    #[error("last block timestamp mismatch, expected {expected} but found {found}")]
+    LastBlockTimestampMismatch { expected: u64, found: u64 },
+    #[error(transparent)]
+    Misbehaviour(#[from] MisbehaviourError),
 }
 
 // 1. rollupContractOnL1 ∈ L1Stateroot
@@ -33,11 +53,15 @@ pub enum Error {
 // 3. L2stateRoot ≡ rollupContractOnL1.finalized[lastBatchIndex]
 // 4. batchHash ≡ rollupContractOnL1.batchHashes[lastBatchIndex]
 // 5. ibcContractOnL2 ∈ L2StateRoot
+// 6. lastBlockNumber ∈ batch.dataHash
+//
+/// Verify the header against the L1 state root, returning the number of the last L2 block in the
+/// finalized batch.
 pub fn verify_header(
     client_state: &ClientState,
     header: &Header,
     l1_state_root: H256,
-) -> Result<(), Error> {
+) -> Result<u64, Error> {
     // Verify that the rollup account root is part of the L1 root
     verify_account_storage_root(
         l1_state_root,
@@ -90,10 +114,90 @@ pub fn verify_header(
         &header.l2_ibc_account_proof.storage_root,
     )?;
 
-    Ok(())
+    // Verify that the last block number is part of the (now proven) batch header
+    verify_last_chunk_proof(
+        &BatchHeaderV3::decode(&header.batch_header)?,
+        &header.last_chunk_proof,
+    )
 }
 
-/// Storage slot of a `mapping(uint256 => bytes32)` mapping, where the mapping is at slot `slot` and the `uint256` is the `batch_index`.
+/// Verify that the chunk proof opens the `dataHash` of the batch header, returning the number of
+/// the last L2 block in the batch.
+///
+/// The split between the block contexts and the L1 message hashes is not committed to in the chunk
+/// hash, so the timestamp of the last block context is additionally checked against the
+/// `lastBlockTimestamp` of the batch header. L2 block timestamps are strictly increasing, so this
+/// pins the last block context to the last block of the batch.
+pub fn verify_last_chunk_proof(
+    batch_header: &BatchHeaderV3,
+    chunk_proof: &ChunkProof,
+) -> Result<u64, Error> {
+    let block_contexts_len = chunk_proof.block_contexts.len();
+
+    if block_contexts_len == 0 || block_contexts_len % BlockContext::HASHED_LENGTH != 0 {
+        return Err(Error::InvalidBlockContextsLength(block_contexts_len));
+    }
+
+    let last_chunk_hash =
+        compute_chunk_hash(&chunk_proof.block_contexts, &chunk_proof.l1_message_hashes);
+
+    let data_hash = compute_data_hash(
+        chunk_proof
+            .previous_chunk_hashes
+            .iter()
+            .chain([&last_chunk_hash]),
+    );
+
+    if data_hash != batch_header.data_hash {
+        return Err(Error::DataHashMismatch {
+            expected: batch_header.data_hash,
+            found: data_hash,
+        });
+    }
+
+    let (last_block_number, last_block_timestamp) = BlockContext::decode_hashed(
+        &chunk_proof.block_contexts[block_contexts_len - BlockContext::HASHED_LENGTH..],
+    )
+    .expect("length is checked above; qed;");
+
+    if last_block_timestamp != batch_header.last_block_timestamp {
+        return Err(Error::LastBlockTimestampMismatch {
+            expected: batch_header.last_block_timestamp,
+            found: last_block_timestamp,
+        });
+    }
+
+    Ok(last_block_number)
+}
+
+/// Verify that the provided headers prove that two different L2 state roots have been finalized
+/// on the L1 for the same batch index.
+///
+/// Scroll finalizes state roots per batch rather than per block, so the batch index is used as the
+/// L2 height here. Each header is verified with [`verify_header`] against the L1 state root at its
+/// own L1 height.
+pub fn verify_misbehaviour(
+    client_state: &ClientState,
+    header_a: &Header,
+    l1_state_root_a: H256,
+    header_b: &Header,
+    l1_state_root_b: H256,
+) -> Result<(), Error> {
+    misbehaviour::verify_misbehaviour(
+        header_a,
+        l1_state_root_a,
+        header_b,
+        l1_state_root_b,
+        |header| L2StateRoot {
+            l2_height: header.last_batch_index_proof.value,
+            state_root: header.l2_state_root_proof.value.to_be_bytes().into(),
+        },
+        |header, l1_state_root| verify_header(client_state, header, l1_state_root).map(|_| ()),
+    )
+}
+
+/// Storage slot of a `mapping(uint256 => bytes32)` mapping, where the mapping is at slot `slot` and
+/// the `uint256` is the `batch_index`.
 pub fn mapping_index_to_slot_key(slot: U256, batch_index: U256) -> U256 {
     Slot::Mapping(&Slot::Offset(slot), MappingKey::Uint256(batch_index)).slot()
 }
@@ -231,3 +335,254 @@ pub fn scroll_verify_zktrie_account_storage_root(
 //         )
 //     }
 // }
+
+#[cfg(test)]
+mod chunk_proof_tests {
+    use scroll_codec::{
+        batch_header::BatchHeaderV3,
+        chunk::{compute_chunk_hash, compute_data_hash, BlockContext},
+    };
+    use scroll_light_client_types::ChunkProof;
+    use unionlabs::primitives::{H256, U256};
+
+    use crate::{verify_last_chunk_proof, Error};
+
+    const LAST_BLOCK_TIMESTAMP: u64 = 1_724_000_000;
+
+    fn block_context(block_number: u64, timestamp: u64) -> BlockContext {
+        BlockContext {
+            block_number,
+            timestamp,
+            base_fee: U256::from(252_000_000_u64),
+            gas_limit: 10_000_000,
+            num_transactions: 1,
+            num_l1_messages: 0,
+        }
+    }
+
+    fn block_contexts(blocks: impl IntoIterator<Item = BlockContext>) -> Vec<u8> {
+        blocks
+            .into_iter()
+            .flat_map(|block| block.encode_hashed())
+            .collect()
+    }
+
+    /// A batch of two chunks, where the last chunk contains blocks 11 and 12.
+    fn batch() -> (BatchHeaderV3, ChunkProof) {
+        let first_chunk_hash = compute_chunk_hash(
+            &block_contexts([
+                block_context(9, LAST_BLOCK_TIMESTAMP - 9),
+                block_context(10, LAST_BLOCK_TIMESTAMP - 6),
+            ]),
+            &[],
+        );
+
+        let chunk_proof = ChunkProof {
+            previous_chunk_hashes: vec![first_chunk_hash],
+            block_contexts: block_contexts([
+                block_context(11, LAST_BLOCK_TIMESTAMP - 3),
+                block_context(12, LAST_BLOCK_TIMESTAMP),
+            ]),
+            l1_message_hashes: vec![H256::new([1; 32])],
+        };
+
+        let batch_header = BatchHeaderV3 {
+            version: 3,
+            batch_index: 1,
+            l1_message_popped: 1,
+            total_l1_message_popped: 1,
+            data_hash: compute_data_hash(&[
+                first_chunk_hash,
+                compute_chunk_hash(&chunk_proof.block_contexts, &chunk_proof.l1_message_hashes),
+            ]),
+            blob_versioned_hash: H256::default(),
+            parent_batch_hash: H256::default(),
+            last_block_timestamp: LAST_BLOCK_TIMESTAMP,
+            blob_data_proof: Default::default(),
+        };
+
+        (batch_header, chunk_proof)
+    }
+
+    #[test]
+    fn valid_proof_returns_last_block_number() {
+        let (batch_header, chunk_proof) = batch();
+
+        assert_eq!(verify_last_chunk_proof(&batch_header, &chunk_proof), Ok(12));
+    }
+
+    #[test]
+    fn different_block_number_is_rejected() {
+        let (batch_header, mut chunk_proof) = batch();
+
+        chunk_proof.block_contexts = block_contexts([
+            block_context(11, LAST_BLOCK_TIMESTAMP - 3),
+            block_context(1_000, LAST_BLOCK_TIMESTAMP),
+        ]);
+
+        assert!(matches!(
+            verify_last_chunk_proof(&batch_header, &chunk_proof),
+            Err(Error::DataHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn missing_previous_chunk_is_rejected() {
+        let (batch_header, mut chunk_proof) = batch();
+
+        chunk_proof.previous_chunk_hashes.clear();
+
+        assert!(matches!(
+            verify_last_chunk_proof(&batch_header, &chunk_proof),
+            Err(Error::DataHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn last_block_timestamp_mismatch_is_rejected() {
+        let (mut batch_header, chunk_proof) = batch();
+
+        batch_header.last_block_timestamp += 1;
+
+        assert_eq!(
+            verify_last_chunk_proof(&batch_header, &chunk_proof),
+            Err(Error::LastBlockTimestampMismatch {
+                expected: LAST_BLOCK_TIMESTAMP + 1,
+                found: LAST_BLOCK_TIMESTAMP,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_block_contexts_length_is_rejected() {
+        let (batch_header, mut chunk_proof) = batch();
+
+        chunk_proof.block_contexts.pop();
+
+        assert_eq!(
+            verify_last_chunk_proof(&batch_header, &chunk_proof),
+            Err(Error::InvalidBlockContextsLength(
+                2 * BlockContext::HASHED_LENGTH - 1
+            ))
+        );
+
+        chunk_proof.block_contexts.clear();
+
+        assert_eq!(
+            verify_last_chunk_proof(&batch_header, &chunk_proof),
+            Err(Error::InvalidBlockContextsLength(0))
+        );
+    }
+}
+
+#[cfg(test)]
+mod misbehaviour_tests {
+    use ethereum_light_client_types::{AccountProof, StorageProof};
+    use evm_storage_verifier::misbehaviour::MisbehaviourError;
+    use ibc_union_spec::ClientId;
+    use scroll_light_client_types::{ChunkProof, ClientState, Header};
+    use unionlabs::{
+        ibc::core::client::height::Height,
+        primitives::{H160, H256, U256},
+    };
+
+    use crate::{verify_misbehaviour, Error};
+
+    const L1_STATE_ROOT: H256 = H256::new([0xAA; 32]);
+
+    fn client_state() -> ClientState {
+        ClientState {
+            chain_id: 534352,
+            latest_height: 0,
+            l1_client_id: ClientId!(1),
+            frozen_height: Height::new(0),
+            ibc_contract_address: H160::new([0x01; 20]),
+            l2_committed_batches_slot: 157_u64.into(),
+            l2_contract_address: H160::new([0x02; 20]),
+            l2_finalized_state_roots_slot: 158_u64.into(),
+            latest_batch_index_slot: 156_u64.into(),
+        }
+    }
+
+    fn storage_proof(value: U256) -> StorageProof {
+        StorageProof {
+            key: U256::ZERO,
+            value,
+            proof: vec![],
+        }
+    }
+
+    fn account_proof() -> AccountProof {
+        AccountProof {
+            storage_root: H256::default(),
+            proof: vec![],
+        }
+    }
+
+    /// A header claiming that `state_root` has been finalized for `batch_index`, without any valid
+    /// proofs.
+    fn header(batch_index: u64, state_root: u64) -> Header {
+        Header {
+            l1_height: Height::new(1),
+            l1_account_proof: account_proof(),
+            l2_state_root_proof: storage_proof(state_root.into()),
+            last_batch_index_proof: storage_proof(batch_index.into()),
+            batch_hash_proof: storage_proof(U256::ZERO),
+            l2_ibc_account_proof: account_proof(),
+            batch_header: vec![],
+            last_chunk_proof: ChunkProof {
+                previous_chunk_hashes: vec![],
+                block_contexts: vec![],
+                l1_message_hashes: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn different_batch_indexes_are_rejected() {
+        assert_eq!(
+            verify_misbehaviour(
+                &client_state(),
+                &header(10, 1),
+                L1_STATE_ROOT,
+                &header(11, 2),
+                L1_STATE_ROOT,
+            ),
+            Err(Error::Misbehaviour(MisbehaviourError::L2HeightMismatch {
+                a: 10_u64.into(),
+                b: 11_u64.into(),
+            }))
+        );
+    }
+
+    #[test]
+    fn same_state_root_is_rejected() {
+        assert_eq!(
+            verify_misbehaviour(
+                &client_state(),
+                &header(10, 1),
+                L1_STATE_ROOT,
+                &header(10, 1),
+                L1_STATE_ROOT,
+            ),
+            Err(Error::Misbehaviour(MisbehaviourError::NoMisbehaviour {
+                l2_height: 10_u64.into(),
+                state_root: U256::from(1_u64).to_be_bytes().into(),
+            }))
+        );
+    }
+
+    #[test]
+    fn conflicting_state_roots_must_be_finalized_on_the_l1() {
+        assert!(matches!(
+            verify_misbehaviour(
+                &client_state(),
+                &header(10, 1),
+                L1_STATE_ROOT,
+                &header(10, 2),
+                L1_STATE_ROOT,
+            ),
+            Err(Error::InvalidL1AccountProof(_))
+        ));
+    }
+}
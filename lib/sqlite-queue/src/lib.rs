@@ -0,0 +1,1686 @@
+use std::{
+    fmt::Write,
+    future::Future,
+    marker::PhantomData,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures_util::TryStreamExt;
+use itertools::Itertools;
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::{
+    prelude::FromRow,
+    query::Query,
+    sqlite::{
+        SqliteArguments, SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions,
+        SqliteSynchronous,
+    },
+    types::Json,
+    Either, Executor, QueryBuilder, Row, Sqlite, SqliteConnection, SqlitePool,
+};
+use tracing::{debug, debug_span, error, info, info_span, instrument, trace, warn, Instrument};
+use voyager_vm::{
+    filter::{FilterResult, Interest, InterestFilter},
+    pass::{Pass, PassResult},
+    retry::{retry, Backoff, ErrorKind, Retry, RetryPolicy},
+    BoxDynError, Captures, EnqueueResult, ItemId, ItemStatus, Op, QueueError, QueueItem,
+    QueueMessage,
+};
+
+/// A fifo queue backed by an embedded sqlite database. This provides the same durability
+/// guarantees as [`pg_queue`](https://docs.rs/pg-queue), without requiring an external database
+/// server.
+///
+/// Items are claimed before they are handed to the processing function, and are only removed from
+/// the queue once the results of processing have been committed. Any items that were claimed but
+/// not finished (i.e. if voyager crashed mid-processing) are released again on startup, so each
+/// item will be processed *at least* once.
+///
+/// Since claims are released on startup, the database file must not be shared between multiple
+/// running voyager instances. Use [`SqliteQueue::connect`] to access the database of a running
+/// instance without releasing its claims.
+#[derive(Debug, Clone)]
+pub struct SqliteQueue<T> {
+    client: SqlitePool,
+    optimize_batch_limit: Option<i64>,
+    retryable_error_expo_backoff_max: f64,
+    retryable_error_expo_backoff_multiplier: f64,
+    retry_policies: Vec<RetryPolicy>,
+
+    __marker: PhantomData<fn() -> T>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteQueueConfig {
+    /// Path to the database file. This will be created if it does not exist.
+    pub path: PathBuf,
+    #[serde(default = "default_max_connections")]
+    pub max_connections: u32,
+    /// How long to wait for the database to be unlocked before returning an error.
+    #[serde(default = "default_busy_timeout")]
+    pub busy_timeout: Duration,
+    #[serde(default)]
+    pub optimize_batch_limit: Option<i64>,
+    #[serde(default = "default_retryable_error_expo_backoff_max")]
+    pub retryable_error_expo_backoff_max: f64,
+    #[serde(default = "default_retryable_error_expo_backoff_multiplier")]
+    pub retryable_error_expo_backoff_multiplier: f64,
+    /// Policies for retrying items that error. The first policy that matches an errored item
+    /// will be used. Retryable errors that don't match any policy will be retried indefinitely,
+    /// and fatal and unprocessable errors that don't match any policy will be moved to the
+    /// `failed` table immediately.
+    #[serde(default)]
+    pub retry_policies: Vec<RetryPolicy>,
+}
+
+pub const fn default_max_connections() -> u32 {
+    4
+}
+
+pub const fn default_busy_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+pub const fn default_retryable_error_expo_backoff_max() -> f64 {
+    60.0 * 5.0
+}
+
+pub const fn default_retryable_error_expo_backoff_multiplier() -> f64 {
+    2.0
+}
+
+impl SqliteQueueConfig {
+    pub async fn into_sqlite_pool(self) -> sqlx::Result<SqlitePool> {
+        SqlitePoolOptions::new()
+            .max_connections(self.max_connections)
+            .connect_with(
+                SqliteConnectOptions::new()
+                    .filename(&self.path)
+                    .create_if_missing(true)
+                    .journal_mode(SqliteJournalMode::Wal)
+                    .synchronous(SqliteSynchronous::Normal)
+                    .busy_timeout(self.busy_timeout),
+            )
+            .await
+    }
+}
+
+#[derive(FromRow)]
+struct Id {
+    id: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct QueueRecord {
+    id: i64,
+    parents: Json<Vec<i64>>,
+    item: String,
+    created_at: i64,
+    attempt: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct OptimizeRecord {
+    id: i64,
+    #[allow(dead_code)]
+    parents: Json<Vec<i64>>,
+    item: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(bound(serialize = ""))]
+pub struct FailedRecord<T: QueueMessage> {
+    pub id: i64,
+    pub parents: Vec<i64>,
+    pub item: Json<Op<T>>,
+    pub message: String,
+}
+
+impl<T: QueueMessage> FailedRecord<T> {
+    fn from_row(row: &sqlx::sqlite::SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            parents: row.try_get::<Json<Vec<i64>>, _>("parents")?.0,
+            item: Json(
+                de(row.try_get::<&str, _>("item")?)
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            ),
+            message: row.try_get("message")?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(bound(serialize = ""))]
+pub struct HistoryRecord<T: QueueMessage> {
+    pub id: i64,
+    pub parents: Vec<i64>,
+    pub item: Json<Op<T>>,
+    /// The distance from the item that the history was queried for, starting at 1.
+    pub depth: i32,
+}
+
+impl<T: QueueMessage> HistoryRecord<T> {
+    fn from_row(row: &sqlx::sqlite::SqliteRow) -> sqlx::Result<Self> {
+        Ok(Self {
+            id: row.try_get("id")?,
+            parents: row.try_get::<Json<Vec<i64>>, _>("parents")?.0,
+            item: Json(
+                de(row.try_get::<&str, _>("item")?)
+                    .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            ),
+            depth: row.try_get("depth")?,
+        })
+    }
+}
+
+impl<T: QueueMessage> SqliteQueue<T> {
+    /// Connect to the database configured in `config`, creating the tables if they don't exist.
+    ///
+    /// Unlike [`voyager_vm::Queue::new`], this does not release the claims of any items. This is
+    /// intended for inspecting and modifying a database that may be in use by a running voyager
+    /// instance (i.e. from the cli).
+    pub async fn connect(config: SqliteQueueConfig) -> Result<Self, sqlx::Error> {
+        let optimize_batch_limit = config.optimize_batch_limit;
+        let retryable_error_expo_backoff_multiplier =
+            config.retryable_error_expo_backoff_multiplier;
+        let retryable_error_expo_backoff_max = config.retryable_error_expo_backoff_max;
+        let retry_policies = config.retry_policies.clone();
+
+        let pool = config.into_sqlite_pool().await?;
+
+        pool.execute_many(
+            r#"
+            -- ids are shared between the queue and optimize tables
+            CREATE TABLE IF NOT EXISTS
+              id_sequence (
+                id INTEGER PRIMARY KEY AUTOINCREMENT
+              );
+
+            CREATE TABLE IF NOT EXISTS
+              queue (
+                id INTEGER PRIMARY KEY,
+                item TEXT NOT NULL,
+                parents TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                handle_at INTEGER NOT NULL,
+                attempt INTEGER NOT NULL DEFAULT 0,
+                claimed INTEGER NOT NULL DEFAULT 0
+              );
+
+            CREATE TABLE IF NOT EXISTS
+              optimize (
+                id INTEGER PRIMARY KEY,
+                item TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                parents TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                claimed INTEGER NOT NULL DEFAULT 0
+              );
+
+            CREATE TABLE IF NOT EXISTS
+              done (
+                id INTEGER NOT NULL,
+                item TEXT NOT NULL,
+                parents TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                PRIMARY KEY (id, created_at)
+              );
+
+            CREATE TABLE IF NOT EXISTS
+              failed (
+                id INTEGER PRIMARY KEY,
+                item TEXT NOT NULL,
+                parents TEXT NOT NULL DEFAULT '[]',
+                message TEXT,
+                created_at INTEGER NOT NULL
+              );
+
+            CREATE INDEX IF NOT EXISTS index_queue_handle_at ON queue (claimed, handle_at ASC);
+
+            CREATE INDEX IF NOT EXISTS index_optimize_tag ON optimize (tag, claimed, id ASC);
+            "#,
+        )
+        .try_for_each(|result| async move {
+            trace!("rows affected: {}", result.rows_affected());
+            Ok(())
+        })
+        .instrument(info_span!("init"))
+        .await?;
+
+        Ok(Self {
+            client: pool,
+            optimize_batch_limit,
+            retryable_error_expo_backoff_max,
+            retryable_error_expo_backoff_multiplier,
+            retry_policies,
+            __marker: PhantomData,
+        })
+    }
+
+    /// Query the failed items.
+    ///
+    /// Filters are `LIKE` patterns, and will be run against the compact JSON encoding of the item
+    /// (i.e. `{"a":{"b":"c"}}`). An item matches if it matches *any* of the item filters and
+    /// *any* of the message filters.
+    pub async fn query_failed(
+        &self,
+        page: i64,
+        per_page: i64,
+        item_filters: Vec<String>,
+        message_filters: Vec<String>,
+    ) -> Result<Vec<FailedRecord<T>>, sqlx::Error> {
+        let mut query =
+            QueryBuilder::<Sqlite>::new("SELECT id, parents, item, message FROM failed WHERE ");
+
+        push_failed_filters(&mut query, item_filters, message_filters);
+
+        query
+            .push(" ORDER BY id DESC LIMIT ")
+            .push_bind(per_page)
+            .push(" OFFSET ")
+            .push_bind((page - 1) * per_page);
+
+        query
+            .build()
+            .fetch_all(&self.client)
+            .await?
+            .iter()
+            .map(FailedRecord::from_row)
+            .collect()
+    }
+
+    /// Move all failed items matching the provided filters back into the queue, returning the ids
+    /// of the requeued items. The filters have the same syntax as [`Self::query_failed`].
+    ///
+    /// Requeued items keep their original id and parents, and their attempt count is reset.
+    pub async fn requeue_failed(
+        &self,
+        item_filters: Vec<String>,
+        message_filters: Vec<String>,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let mut query = QueryBuilder::<Sqlite>::new("DELETE FROM failed WHERE ");
+
+        push_failed_filters(&mut query, item_filters, message_filters);
+
+        query.push(" RETURNING id, parents, item, created_at");
+
+        requeue_deleted_failed(&self.client, query.build()).await
+    }
+
+    pub async fn query_failed_by_id(
+        &self,
+        id: i64,
+    ) -> Result<Option<FailedRecord<T>>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            SELECT
+               id,
+               parents,
+               item,
+               message
+            FROM
+               failed
+            WHERE
+               id = ?1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.client)
+        .await?
+        .as_ref()
+        .map(FailedRecord::from_row)
+        .transpose()
+    }
+
+    /// Query the lineage of the item with the specified id, up to `max_depth` generations back.
+    ///
+    /// The item itself is included in the results with a depth of 1. Items are searched for in
+    /// the `queue`, `optimize`, `done` and `failed` tables.
+    pub async fn query_history(
+        &self,
+        id: i64,
+        max_depth: i32,
+    ) -> Result<Vec<HistoryRecord<T>>, sqlx::Error> {
+        sqlx::query(
+            r#"
+            WITH RECURSIVE
+              items AS (
+                SELECT id, parents, item FROM queue
+                UNION ALL
+                SELECT id, parents, item FROM optimize
+                UNION ALL
+                SELECT id, parents, item FROM done
+                UNION ALL
+                SELECT id, parents, item FROM failed
+              ),
+              history (id, parents, item, depth) AS (
+                SELECT
+                  id,
+                  parents,
+                  item,
+                  1
+                FROM
+                  items
+                WHERE
+                  id = ?1
+                UNION
+                SELECT
+                  items.id,
+                  items.parents,
+                  items.item,
+                  history.depth + 1
+                FROM
+                  history,
+                  json_each(history.parents) AS parent
+                  JOIN items ON items.id = parent.value
+                WHERE
+                  history.depth < ?2
+              )
+            -- the other columns are taken from the row with the minimum depth
+            SELECT
+              id,
+              parents,
+              item,
+              MIN(depth) AS depth
+            FROM
+              history
+            GROUP BY
+              id
+            ORDER BY
+              id DESC
+            "#,
+        )
+        .bind(id)
+        .bind(max_depth)
+        .fetch_all(&self.client)
+        .await?
+        .iter()
+        .map(HistoryRecord::from_row)
+        .collect()
+    }
+}
+
+impl<T: QueueMessage> voyager_vm::Queue<T> for SqliteQueue<T> {
+    type Config = SqliteQueueConfig;
+    type Error = sqlx::Error;
+
+    async fn new(config: Self::Config) -> Result<Self, Self::Error> {
+        let queue = Self::connect(config).await?;
+
+        // release any claims left over from a previous run. this is only done here (i.e. when the
+        // queue is started to be processed), since claims held by a running instance must not be
+        // released by other connections to the same database.
+        for table in ["queue", "optimize"] {
+            let released =
+                sqlx::query(&format!("UPDATE {table} SET claimed = 0 WHERE claimed = 1"))
+                    .execute(&queue.client)
+                    .instrument(info_span!("release_claims", %table))
+                    .await?
+                    .rows_affected();
+
+            if released > 0 {
+                info!(%table, %released, "released stale claims");
+            }
+        }
+
+        Ok(queue)
+    }
+
+    async fn enqueue<'a, Filter: InterestFilter<T>>(
+        &'a self,
+        op: Op<T>,
+        filter: &'a Filter,
+    ) -> Result<EnqueueResult, Self::Error> {
+        trace!("enqueue");
+
+        let (optimize, ready): (Vec<_>, Vec<_>) =
+            op.normalize()
+                .into_iter()
+                .partition_map(|op| match filter.check_interest(&op) {
+                    FilterResult::Interest(interest) => Either::Left((op, interest)),
+                    FilterResult::NoInterest => Either::Right(op),
+                });
+
+        let mut tx = self.client.begin().await?;
+
+        let mut ready_ids = vec![];
+        for op in ready {
+            let id = insert_queue(&mut tx, &op, &[]).await?;
+            debug!(id, "enqueued ready item");
+            ready_ids.push(id);
+        }
+
+        let mut optimize_further_ids = vec![];
+        for (op, interest) in optimize {
+            for tag in interest.tags {
+                let id = insert_optimize(&mut tx, &op, &[], tag).await?;
+                debug!(id, "enqueued optimize item");
+                optimize_further_ids.push(id);
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(EnqueueResult {
+            queue: ready_ids
+                .into_iter()
+                .map(|id| ItemId::new(id).expect("invalid id returned from database"))
+                .collect(),
+            optimize: optimize_further_ids
+                .into_iter()
+                .map(|id| ItemId::new(id).expect("invalid id returned from database"))
+                .collect(),
+        })
+    }
+
+    #[instrument(skip_all)]
+    async fn process<'a, F, Fut, R, Filter>(
+        &'a self,
+        filter: &'a Filter,
+        f: F,
+    ) -> Result<Option<R>, Self::Error>
+    where
+        F: (FnOnce(Op<T>, ItemId) -> Fut) + Send + Captures<'a>,
+        Fut: Future<Output = (R, Result<Vec<Op<T>>, QueueError>)> + Send + Captures<'a>,
+        R: Send + Sync + 'static,
+        Filter: InterestFilter<T>,
+    {
+        trace!("process");
+
+        // claim the item outside of a transaction, since sqlite only allows one writer at a time
+        // and processing the item may take a while. the claim is released again if processing
+        // fails before its results are committed
+        let row = sqlx::query(
+            r#"
+            UPDATE
+              queue
+            SET
+              claimed = 1
+            WHERE
+              id = (
+                SELECT
+                  id
+                FROM
+                  queue
+                WHERE
+                  claimed = 0
+                  AND handle_at <= ?1
+                ORDER BY
+                  handle_at ASC
+                LIMIT 1)
+            RETURNING
+              id,
+              parents,
+              item,
+              attempt,
+              created_at
+            "#,
+        )
+        .bind(now_millis())
+        .try_map(|x| QueueRecord::from_row(&x))
+        .fetch_optional(&self.client)
+        .await?;
+
+        match row {
+            Some(record) => {
+                let id = record.id;
+
+                let res = process_item(
+                    &self.client,
+                    record,
+                    f,
+                    filter,
+                    self.retryable_error_expo_backoff_max,
+                    self.retryable_error_expo_backoff_multiplier,
+                    &self.retry_policies,
+                )
+                .await;
+
+                if res.is_err() {
+                    // nothing is committed on the error paths of process_item, so release the
+                    // claimed item so that it can be picked up again, mirroring a rolled back
+                    // transaction
+                    sqlx::query("UPDATE queue SET claimed = 0 WHERE id = ?1")
+                        .bind(id)
+                        .execute(&self.client)
+                        .await?;
+                }
+
+                res
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[instrument(skip_all, fields(%tag))]
+    async fn optimize<'a, O, Filter>(
+        &'a self,
+        tag: &'a str,
+        filter: &'a Filter,
+        optimizer: &'a O,
+    ) -> Result<(), Either<Self::Error, O::Error>>
+    where
+        O: Pass<T>,
+        Filter: InterestFilter<T>,
+    {
+        trace!(%tag, "optimize");
+
+        let mut msgs = sqlx::query(
+            r#"
+            UPDATE
+              optimize
+            SET
+              claimed = 1
+            WHERE
+              id IN (
+                SELECT
+                  id
+                FROM
+                  optimize
+                WHERE
+                  tag = ?1
+                  AND claimed = 0
+                ORDER BY
+                  id ASC
+                LIMIT ?2)
+            RETURNING
+              id,
+              parents,
+              item
+            "#,
+        )
+        .bind(tag)
+        // a negative limit is no limit
+        .bind(self.optimize_batch_limit.unwrap_or(-1))
+        .try_map(|x| OptimizeRecord::from_row(&x))
+        .fetch_all(&self.client)
+        .await
+        .map_err(Either::Left)?;
+
+        if msgs.is_empty() {
+            trace!("optimizer queue is empty");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            return Ok(());
+        }
+
+        // the order of RETURNING is unspecified
+        msgs.sort_by_key(|r| r.id);
+
+        let all_ids = Json(msgs.iter().map(|r| r.id).collect::<Vec<_>>());
+
+        let res = optimize_claimed(&self.client, &all_ids, msgs, filter, optimizer).await;
+
+        if res.is_err() {
+            // release the claimed items so that they can be picked up again, mirroring a rolled
+            // back transaction
+            sqlx::query(
+                "
+                UPDATE optimize SET claimed = 0
+                WHERE id IN (SELECT value FROM json_each(?1))
+                ",
+            )
+            .bind(&all_ids)
+            .execute(&self.client)
+            .await
+            .map_err(Either::Left)?;
+        }
+
+        res
+    }
+
+    async fn query_items(
+        &self,
+        status: ItemStatus,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<QueueItem<T>>, Self::Error> {
+        let query = match status {
+            ItemStatus::Queued => {
+                "
+                SELECT id, parents, item, NULL AS tag, NULL AS message FROM queue
+                ORDER BY id DESC LIMIT ?1 OFFSET ?2
+                "
+            }
+            ItemStatus::Optimize => {
+                "
+                SELECT id, parents, item, tag, NULL AS message FROM optimize
+                ORDER BY id DESC LIMIT ?1 OFFSET ?2
+                "
+            }
+            ItemStatus::Failed => {
+                "
+                SELECT id, parents, item, NULL AS tag, COALESCE(message, '') AS message FROM failed
+                ORDER BY id DESC LIMIT ?1 OFFSET ?2
+                "
+            }
+        };
+
+        sqlx::query(query)
+            .bind(i64::from(per_page))
+            .bind(i64::from(page.saturating_sub(1)) * i64::from(per_page))
+            .fetch_all(&self.client)
+            .await?
+            .iter()
+            .map(|row| {
+                Ok(QueueItem {
+                    id: ItemId::new(row.try_get("id")?).expect("invalid id returned from database"),
+                    parents: row
+                        .try_get::<Json<Vec<i64>>, _>("parents")?
+                        .0
+                        .into_iter()
+                        .map(|id| ItemId::new(id).expect("invalid id returned from database"))
+                        .collect(),
+                    item: de(row.try_get::<&str, _>("item")?)
+                        .map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+                    tag: row.try_get("tag")?,
+                    message: row.try_get("message")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn requeue_failed_items(&self, ids: Vec<ItemId>) -> Result<Vec<ItemId>, Self::Error> {
+        let query = sqlx::query(
+            "
+            DELETE FROM failed
+            WHERE id IN (SELECT value FROM json_each(?1))
+            RETURNING id, parents, item, created_at
+            ",
+        )
+        .bind(Json(ids.iter().map(ItemId::raw).collect::<Vec<_>>()));
+
+        requeue_deleted_failed(&self.client, query)
+            .await
+            .map(|ids| {
+                ids.into_iter()
+                    .map(|id| ItemId::new(id).expect("invalid id returned from database"))
+                    .collect()
+            })
+    }
+
+    async fn delete_failed_items(&self, ids: Vec<ItemId>) -> Result<Vec<ItemId>, Self::Error> {
+        sqlx::query_as::<_, Id>(
+            "
+            DELETE FROM failed
+            WHERE id IN (SELECT value FROM json_each(?1))
+            RETURNING id
+            ",
+        )
+        .bind(Json(ids.iter().map(ItemId::raw).collect::<Vec<_>>()))
+        .fetch_all(&self.client)
+        .await
+        .map(|ids| {
+            ids.into_iter()
+                .map(|id| ItemId::new(id.id).expect("invalid id returned from database"))
+                .collect()
+        })
+    }
+}
+
+async fn optimize_claimed<T, O, Filter>(
+    client: &SqlitePool,
+    all_ids: &Json<Vec<i64>>,
+    msgs: Vec<OptimizeRecord>,
+    filter: &Filter,
+    optimizer: &O,
+) -> Result<(), Either<sqlx::Error, O::Error>>
+where
+    T: QueueMessage,
+    O: Pass<T>,
+    Filter: InterestFilter<T>,
+{
+    let (ids, msgs) = msgs
+        .into_iter()
+        .map(|r| {
+            Ok((
+                r.id,
+                de(&r.item).map_err(|e| sqlx::Error::Decode(Box::new(e)))?,
+            ))
+        })
+        .collect::<Result<(Vec<_>, Vec<_>), sqlx::Error>>()
+        .map_err(Either::Left)?;
+
+    let PassResult {
+        optimize_further,
+        ready,
+    } = optimizer
+        .run_pass(msgs)
+        .instrument(debug_span!(
+            "optimizing items",
+            ids = ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        ))
+        .await
+        .map_err(Either::Right)?;
+
+    trace!(
+        ready = ready.len(),
+        optimize_further = optimize_further.len(),
+        "optimized items"
+    );
+
+    let get_parent_ids = |parent_idxs: &[usize]| {
+        ids.iter()
+            .enumerate()
+            .filter_map(|(idx, id)| parent_idxs.contains(&idx).then_some(*id))
+            .collect::<Vec<_>>()
+    };
+
+    let mut tx = client.begin().await.map_err(Either::Left)?;
+
+    sqlx::query("DELETE FROM optimize WHERE id IN (SELECT value FROM json_each(?1))")
+        .bind(all_ids)
+        .execute(tx.as_mut())
+        .await
+        .map_err(Either::Left)?;
+
+    for (parent_idxs, new_msg, tag) in optimize_further {
+        let parents = get_parent_ids(&parent_idxs);
+        trace!(parent_idxs = ?&parent_idxs, parents = ?&parents);
+
+        let id = insert_optimize(&mut tx, &new_msg, &parents, &tag)
+            .await
+            .map_err(Either::Left)?;
+
+        debug!(id, "inserted new optimizer message");
+    }
+
+    for (parent_idxs, op) in ready {
+        let parents = get_parent_ids(&parent_idxs);
+        trace!(parent_idxs = ?&parent_idxs, parents = ?&parents);
+
+        'block: for op in op.normalize() {
+            match filter.check_interest(&op) {
+                FilterResult::Interest(Interest { tags, remove }) => {
+                    for tag in tags {
+                        let id = insert_optimize(&mut tx, &op, &parents, tag)
+                            .await
+                            .map_err(Either::Left)?;
+
+                        debug!(id, "inserted new optimizer message");
+                    }
+
+                    if remove {
+                        break 'block;
+                    }
+                }
+                FilterResult::NoInterest => {}
+            }
+
+            let id = insert_queue(&mut tx, &op, &parents)
+                .await
+                .map_err(Either::Left)?;
+
+            debug!(id, "enqueued ready item");
+        }
+    }
+
+    tx.commit().await.map_err(Either::Left)?;
+
+    Ok(())
+}
+
+#[instrument(
+    skip_all,
+    fields(
+        item_id = record.id,
+        attempt = record.attempt
+    )
+)]
+async fn process_item<'a, T, F, Fut, R, Filter>(
+    client: &SqlitePool,
+    record: QueueRecord,
+    f: F,
+    filter: &'a Filter,
+    retryable_error_expo_backoff_max: f64,
+    retryable_error_expo_backoff_multiplier: f64,
+    retry_policies: &[RetryPolicy],
+) -> Result<Option<R>, sqlx::Error>
+where
+    T: QueueMessage,
+    F: (FnOnce(Op<T>, ItemId) -> Fut) + Send + Captures<'a>,
+    Fut: Future<Output = (R, Result<Vec<Op<T>>, QueueError>)> + Send + Captures<'a>,
+    R: Send + Sync + 'static,
+    Filter: InterestFilter<T>,
+{
+    trace!(%record.item);
+
+    let op = de::<Op<T>>(&record.item).map_err(|e| sqlx::Error::Decode(Box::new(e)))?;
+
+    let (r, res) = f(op, ItemId::new(record.id).unwrap()).await;
+
+    let mut tx = client.begin().await?;
+
+    match res {
+        Err(error) => {
+            let (kind, error) = ErrorKind::from_queue_error(error);
+            let error = full_error_string(error);
+
+            let policy =
+                find_retry_policy(&mut tx, retry_policies, kind, &record.item, &error).await?;
+
+            match kind {
+                ErrorKind::Fatal => error!(%error, "fatal error"),
+                ErrorKind::Unprocessable => info!(%error, "unprocessable message"),
+                ErrorKind::Retry => warn!(%error, "retryable error"),
+            }
+
+            match retry(
+                policy,
+                kind,
+                record.attempt,
+                Backoff {
+                    max: retryable_error_expo_backoff_max,
+                    multiplier: retryable_error_expo_backoff_multiplier,
+                },
+            ) {
+                Retry::After(backoff) => {
+                    sqlx::query(
+                        "
+                        UPDATE queue
+                        SET claimed = 0, attempt = ?2, handle_at = ?3
+                        WHERE id = ?1
+                        ",
+                    )
+                    .bind(record.id)
+                    .bind(record.attempt.saturating_add(1))
+                    .bind(
+                        now_millis()
+                            .saturating_add(backoff.as_millis().try_into().unwrap_or(i64::MAX)),
+                    )
+                    .execute(tx.as_mut())
+                    .await?;
+
+                    tx.commit().await?;
+
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+
+                    return Ok(Some(r));
+                }
+                Retry::Fail => insert_error(record, error, &mut tx).await?,
+            }
+        }
+        Ok(ops) => {
+            // insert the op we just processed into done
+            sqlx::query(
+                "
+                INSERT INTO
+                done   (id, parents, item, created_at)
+                VALUES (?1, ?2,      ?3,   ?4        )
+                ",
+            )
+            .bind(record.id)
+            .bind(&record.parents)
+            .bind(&record.item)
+            .bind(record.created_at)
+            .execute(tx.as_mut())
+            .await?;
+
+            for op in ops.into_iter().flat_map(Op::normalize) {
+                match filter.check_interest(&op) {
+                    FilterResult::Interest(interest) => {
+                        for tag in interest.tags {
+                            insert_optimize(&mut tx, &op, &[record.id], tag).await?;
+                        }
+                    }
+                    FilterResult::NoInterest => {
+                        insert_queue(&mut tx, &op, &[record.id]).await?;
+                    }
+                }
+            }
+        }
+    }
+
+    sqlx::query("DELETE FROM queue WHERE id = ?1")
+        .bind(record.id)
+        .execute(tx.as_mut())
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(r))
+}
+
+/// Find the first retry policy that applies to the errored item.
+async fn find_retry_policy<'a>(
+    tx: &mut SqliteConnection,
+    retry_policies: &'a [RetryPolicy],
+    kind: ErrorKind,
+    item: &str,
+    error: &str,
+) -> Result<Option<&'a RetryPolicy>, sqlx::Error> {
+    let policies = retry_policies
+        .iter()
+        .filter(|policy| policy.applies_to(kind))
+        .collect::<Vec<_>>();
+
+    if policies.is_empty() {
+        return Ok(None);
+    }
+
+    // reuse the database's LIKE implementation so that the filters behave exactly the same as the
+    // filters for querying failed items. all of the policies are checked at once, and the index of
+    // the first matching one is returned
+    let idx = sqlx::query_scalar::<_, i64>(
+        "
+        SELECT
+          policies.key
+        FROM
+          json_each(?3) AS policies
+        WHERE
+          (
+            json_array_length(policies.value, '$.item_filters') = 0
+            OR EXISTS (
+              SELECT 1 FROM json_each(policies.value, '$.item_filters') AS filters
+              WHERE ?1 LIKE filters.value
+            )
+          )
+          AND (
+            json_array_length(policies.value, '$.message_filters') = 0
+            OR EXISTS (
+              SELECT 1 FROM json_each(policies.value, '$.message_filters') AS filters
+              WHERE ?2 LIKE filters.value
+            )
+          )
+        ORDER BY
+          policies.key ASC
+        LIMIT 1
+        ",
+    )
+    .bind(item)
+    .bind(error)
+    .bind(Json(&policies))
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let policy = idx.and_then(|idx| policies.get(usize::try_from(idx).ok()?).copied());
+
+    if let Some(policy) = policy {
+        trace!(?policy, "found matching retry policy");
+    }
+
+    Ok(policy)
+}
+
+/// Push the `LIKE` filters for failed items onto `query`. An item matches if it matches *any* of
+/// the item filters and *any* of the message filters, and empty filters match everything.
+fn push_failed_filters(
+    query: &mut QueryBuilder<'_, Sqlite>,
+    mut item_filters: Vec<String>,
+    mut message_filters: Vec<String>,
+) {
+    // default to all-inclusive filter if none are provided
+    if item_filters.is_empty() {
+        item_filters.push("%".to_owned())
+    }
+
+    if message_filters.is_empty() {
+        message_filters.push("%".to_owned())
+    }
+
+    query.push("(");
+
+    let mut separated = query.separated(" OR ");
+    for item_filter in item_filters {
+        separated.push("item LIKE ");
+        separated.push_bind_unseparated(item_filter);
+    }
+
+    query.push(") AND (");
+
+    let mut separated = query.separated(" OR ");
+    for message_filter in message_filters {
+        separated.push("message LIKE ");
+        separated.push_bind_unseparated(message_filter);
+    }
+
+    query.push(")");
+}
+
+/// Run `delete`, a `DELETE FROM failed ... RETURNING id, parents, item, created_at` query, and
+/// move the deleted items back into the queue, returning their ids.
+///
+/// Requeued items keep their original id and parents, and their attempt count is reset.
+async fn requeue_deleted_failed(
+    client: &SqlitePool,
+    delete: Query<'_, Sqlite, SqliteArguments<'_>>,
+) -> Result<Vec<i64>, sqlx::Error> {
+    let mut tx = client.begin().await?;
+
+    let records = delete.fetch_all(tx.as_mut()).await?;
+
+    let mut ids = vec![];
+    for record in records {
+        let id = record.try_get::<i64, _>("id")?;
+
+        sqlx::query(
+            "
+            INSERT INTO
+            queue  (id, parents, item, created_at, handle_at)
+            VALUES (?1, ?2,      ?3,   ?4,         ?5       )
+            ",
+        )
+        .bind(id)
+        .bind(record.try_get::<&str, _>("parents")?)
+        .bind(record.try_get::<&str, _>("item")?)
+        .bind(record.try_get::<i64, _>("created_at")?)
+        .bind(now_millis())
+        .execute(tx.as_mut())
+        .await?;
+
+        ids.push(id);
+    }
+
+    tx.commit().await?;
+
+    Ok(ids)
+}
+
+async fn next_id(tx: &mut SqliteConnection) -> Result<i64, sqlx::Error> {
+    let id = sqlx::query("INSERT INTO id_sequence DEFAULT VALUES RETURNING id")
+        .try_map(|x| Id::from_row(&x))
+        .fetch_one(&mut *tx)
+        .await?
+        .id;
+
+    // AUTOINCREMENT guarantees ids are never reused, even once the rows are deleted
+    sqlx::query("DELETE FROM id_sequence")
+        .execute(&mut *tx)
+        .await?;
+
+    Ok(id)
+}
+
+async fn insert_queue<T: QueueMessage>(
+    tx: &mut SqliteConnection,
+    op: &Op<T>,
+    parents: &[i64],
+) -> Result<i64, sqlx::Error> {
+    let id = next_id(tx).await?;
+    let now = now_millis();
+
+    sqlx::query(
+        "
+        INSERT INTO
+        queue  (id, item, parents, created_at, handle_at)
+        VALUES (?1, ?2,   ?3,      ?4,         ?4       )
+        ",
+    )
+    .bind(id)
+    .bind(Json(op))
+    .bind(Json(parents))
+    .bind(now)
+    .execute(tx)
+    .await?;
+
+    Ok(id)
+}
+
+async fn insert_optimize<T: QueueMessage>(
+    tx: &mut SqliteConnection,
+    op: &Op<T>,
+    parents: &[i64],
+    tag: &str,
+) -> Result<i64, sqlx::Error> {
+    let id = next_id(tx).await?;
+
+    sqlx::query(
+        "
+        INSERT INTO
+        optimize (id, item, parents, tag, created_at)
+        VALUES   (?1, ?2,   ?3,      ?4,  ?5        )
+        ",
+    )
+    .bind(id)
+    .bind(Json(op))
+    .bind(Json(parents))
+    .bind(tag)
+    .bind(now_millis())
+    .execute(tx)
+    .await?;
+
+    Ok(id)
+}
+
+async fn insert_error(
+    record: QueueRecord,
+    error: String,
+    tx: &mut SqliteConnection,
+) -> Result<(), sqlx::Error> {
+    // insert error message and the op into failed
+
+    sqlx::query(
+        r#"
+        INSERT INTO
+        failed (id, parents, item, created_at, message)
+        VALUES (?1, ?2,      ?3,   ?4,         ?5     )
+        "#,
+    )
+    .bind(record.id)
+    .bind(record.parents)
+    .bind(record.item)
+    .bind(record.created_at)
+    .bind(error)
+    .execute(tx)
+    .await?;
+
+    Ok(())
+}
+
+/// The current unix timestamp, in milliseconds. sqlite has no native timestamp type, so all
+/// timestamps are stored as integers.
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time is before the unix epoch")
+        .as_millis()
+        .try_into()
+        .expect("the year is not 292278994")
+}
+
+// copied from unionlabs::ErrorReporter
+fn full_error_string(error: BoxDynError) -> String {
+    let mut s = String::new();
+
+    write!(s, "{}", error).unwrap();
+
+    for e in core::iter::successors(error.source(), |e| (*e).source()) {
+        write!(s, ": {e}").unwrap();
+    }
+
+    s
+}
+
+fn de<T: DeserializeOwned>(s: &str) -> Result<T, serde_json::Error> {
+    let mut deserializer = serde_json::Deserializer::from_str(s);
+    deserializer.disable_recursion_limit();
+    let json = T::deserialize(&mut deserializer)?;
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use voyager_vm::{data, defer, Queue};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum UnitMessage {}
+
+    impl QueueMessage for UnitMessage {
+        type Data = ();
+        type Call = ();
+        type Callback = ();
+    }
+
+    /// Expresses interest in all data ops, removing them from the queue.
+    struct DataFilter;
+
+    impl InterestFilter<UnitMessage> for DataFilter {
+        fn check_interest<'a>(&'a self, op: &Op<UnitMessage>) -> FilterResult<'a> {
+            match op {
+                Op::Data(()) => FilterResult::Interest(Interest {
+                    tags: vec!["data"],
+                    remove: true,
+                }),
+                _ => FilterResult::NoInterest,
+            }
+        }
+    }
+
+    /// Combines all ops in the pass into a single ready op.
+    struct Combine;
+
+    impl Pass<UnitMessage> for Combine {
+        type Error = Infallible;
+
+        async fn run_pass(
+            &self,
+            ops: Vec<Op<UnitMessage>>,
+        ) -> Result<PassResult<UnitMessage>, Self::Error> {
+            Ok(PassResult {
+                optimize_further: vec![],
+                ready: vec![((0..ops.len()).collect(), defer(1))],
+            })
+        }
+    }
+
+    async fn new_queue() -> SqliteQueue<UnitMessage> {
+        new_queue_with_retry_policies(vec![]).await
+    }
+
+    async fn new_queue_with_retry_policies(
+        retry_policies: Vec<RetryPolicy>,
+    ) -> SqliteQueue<UnitMessage> {
+        SqliteQueue::new(SqliteQueueConfig {
+            // every connection to an in-memory database opens a new, empty database
+            max_connections: 1,
+            retry_policies,
+            ..config(":memory:".into())
+        })
+        .await
+        .unwrap()
+    }
+
+    fn config(path: PathBuf) -> SqliteQueueConfig {
+        SqliteQueueConfig {
+            path,
+            max_connections: default_max_connections(),
+            busy_timeout: default_busy_timeout(),
+            optimize_batch_limit: None,
+            retryable_error_expo_backoff_max: default_retryable_error_expo_backoff_max(),
+            retryable_error_expo_backoff_multiplier:
+                default_retryable_error_expo_backoff_multiplier(),
+            retry_policies: vec![],
+        }
+    }
+
+    fn retry_policy(
+        errors: Vec<ErrorKind>,
+        message_filters: Vec<&str>,
+        max_attempts: Option<i64>,
+    ) -> RetryPolicy {
+        RetryPolicy {
+            item_filters: vec![],
+            message_filters: message_filters.into_iter().map(ToOwned::to_owned).collect(),
+            errors,
+            max_attempts,
+            backoff_max: None,
+            backoff_multiplier: None,
+        }
+    }
+
+    async fn process_error(queue: &SqliteQueue<UnitMessage>, error: fn() -> QueueError) {
+        queue
+            .process(&(), |_, _| async move { ((), Err(error())) })
+            .await
+            .unwrap();
+    }
+
+    async fn items(
+        queue: &SqliteQueue<UnitMessage>,
+        status: ItemStatus,
+    ) -> Vec<QueueItem<UnitMessage>> {
+        queue.query_items(status, 1, 100).await.unwrap()
+    }
+
+    async fn claimed(queue: &SqliteQueue<UnitMessage>, id: ItemId) -> (i64, i64) {
+        let row = sqlx::query("SELECT claimed, attempt FROM queue WHERE id = ?1")
+            .bind(id.raw())
+            .fetch_one(&queue.client)
+            .await
+            .unwrap();
+
+        (row.get("claimed"), row.get("attempt"))
+    }
+
+    #[tokio::test]
+    async fn enqueue_process() {
+        let queue = new_queue().await;
+
+        let res = queue.enqueue(defer(1), &()).await.unwrap();
+        assert_eq!(res.queue.len(), 1);
+        assert!(res.optimize.is_empty());
+        let id = res.queue[0];
+
+        let processed = queue
+            .process(&(), |op, item_id| async move {
+                assert_eq!(op, defer(1));
+                assert_eq!(item_id, id);
+                (item_id, Ok(vec![defer(2)]))
+            })
+            .await
+            .unwrap();
+        assert_eq!(processed, Some(id));
+
+        let queued = items(&queue, ItemStatus::Queued).await;
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].item, defer(2));
+        assert_eq!(queued[0].parents, vec![id]);
+
+        let done = sqlx::query("SELECT id FROM done")
+            .try_map(|x| Id::from_row(&x))
+            .fetch_all(&queue.client)
+            .await
+            .unwrap();
+        assert_eq!(
+            done.iter().map(|r| r.id).collect::<Vec<_>>(),
+            vec![id.raw()]
+        );
+    }
+
+    #[tokio::test]
+    async fn process_empty_queue() {
+        let queue = new_queue().await;
+
+        let processed = queue
+            .process(&(), |_, _| async { ((), Ok(vec![])) })
+            .await
+            .unwrap();
+        assert_eq!(processed, None);
+    }
+
+    #[tokio::test]
+    async fn process_failed() {
+        let queue = new_queue().await;
+
+        let id = queue.enqueue(defer(1), &()).await.unwrap().queue[0];
+
+        queue
+            .process(&(), |_, _| async {
+                ((), Err(QueueError::Fatal("something went wrong".into())))
+            })
+            .await
+            .unwrap();
+
+        assert!(items(&queue, ItemStatus::Queued).await.is_empty());
+
+        let failed = items(&queue, ItemStatus::Failed).await;
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, id);
+        assert_eq!(failed[0].item, defer(1));
+        assert_eq!(failed[0].message.as_deref(), Some("something went wrong"));
+
+        let failed = queue
+            .query_failed(1, 10, vec![], vec!["%went wrong%".to_owned()])
+            .await
+            .unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, id.raw());
+
+        assert_eq!(
+            queue.requeue_failed_items(vec![id]).await.unwrap(),
+            vec![id]
+        );
+
+        assert!(items(&queue, ItemStatus::Failed).await.is_empty());
+        let queued = items(&queue, ItemStatus::Queued).await;
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].id, id);
+        assert_eq!(claimed(&queue, id).await, (0, 0));
+    }
+
+    #[tokio::test]
+    async fn process_retry() {
+        let queue = new_queue().await;
+
+        let id = queue.enqueue(defer(1), &()).await.unwrap().queue[0];
+
+        queue
+            .process(&(), |_, _| async {
+                ((), Err(QueueError::Retry("try again".into())))
+            })
+            .await
+            .unwrap();
+
+        // the item stays in the queue, unclaimed, with the attempt count bumped
+        assert_eq!(claimed(&queue, id).await, (0, 1));
+        assert!(items(&queue, ItemStatus::Failed).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn retry_policy_max_attempts() {
+        let queue = new_queue_with_retry_policies(vec![retry_policy(
+            vec![ErrorKind::Retry],
+            vec![],
+            Some(2),
+        )])
+        .await;
+
+        let id = queue.enqueue(defer(1), &()).await.unwrap().queue[0];
+
+        process_error(&queue, || QueueError::Retry("try again".into())).await;
+        assert_eq!(claimed(&queue, id).await, (0, 1));
+
+        // the first retry has no backoff, so the item can be processed again immediately
+        process_error(&queue, || QueueError::Retry("try again".into())).await;
+
+        assert!(items(&queue, ItemStatus::Queued).await.is_empty());
+        let failed = items(&queue, ItemStatus::Failed).await;
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, id);
+        assert_eq!(failed[0].message.as_deref(), Some("try again"));
+    }
+
+    #[tokio::test]
+    async fn retry_policy_retries_matching_fatal_errors() {
+        let queue = new_queue_with_retry_policies(vec![retry_policy(
+            vec![ErrorKind::Fatal],
+            vec!["%temporarily%"],
+            None,
+        )])
+        .await;
+
+        let id = queue.enqueue(defer(1), &()).await.unwrap().queue[0];
+
+        process_error(&queue, || {
+            QueueError::Fatal("node temporarily unavailable".into())
+        })
+        .await;
+        assert_eq!(claimed(&queue, id).await, (0, 1));
+
+        process_error(&queue, || QueueError::Fatal("invalid proof".into())).await;
+
+        assert!(items(&queue, ItemStatus::Queued).await.is_empty());
+        assert_eq!(items(&queue, ItemStatus::Failed).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_policy_first_match() {
+        let queue = new_queue_with_retry_policies(vec![
+            retry_policy(vec![ErrorKind::Unprocessable], vec![], None),
+            retry_policy(vec![ErrorKind::Retry], vec!["%never matches%"], Some(1)),
+            retry_policy(vec![ErrorKind::Retry], vec![], Some(1)),
+            retry_policy(vec![ErrorKind::Retry], vec![], None),
+        ])
+        .await;
+
+        queue.enqueue(defer(1), &()).await.unwrap();
+
+        // the third policy is the first one that applies to the error and matches it
+        process_error(&queue, || QueueError::Retry("try again".into())).await;
+
+        assert!(items(&queue, ItemStatus::Queued).await.is_empty());
+        assert_eq!(items(&queue, ItemStatus::Failed).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn requeue_failed() {
+        let queue = new_queue().await;
+
+        let a = queue.enqueue(defer(1), &()).await.unwrap().queue[0];
+        let b = queue.enqueue(defer(2), &()).await.unwrap().queue[0];
+
+        for _ in 0..2 {
+            queue
+                .process(&(), |_, id| async move {
+                    (
+                        (),
+                        Err(QueueError::Fatal(format!("error {}", id.raw()).into())),
+                    )
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(items(&queue, ItemStatus::Failed).await.len(), 2);
+
+        assert_eq!(
+            queue
+                .requeue_failed(
+                    vec!["%defer%".to_owned()],
+                    vec![format!("error {}", b.raw())]
+                )
+                .await
+                .unwrap(),
+            vec![b.raw()]
+        );
+
+        let failed = items(&queue, ItemStatus::Failed).await;
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].id, a);
+
+        let queued = items(&queue, ItemStatus::Queued).await;
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].id, b);
+        assert_eq!(queued[0].item, defer(2));
+
+        assert_eq!(
+            queue.requeue_failed(vec![], vec![]).await.unwrap(),
+            vec![a.raw()]
+        );
+        assert!(items(&queue, ItemStatus::Failed).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn query_history() {
+        let queue = new_queue().await;
+
+        let a = queue.enqueue(defer(1), &()).await.unwrap().queue[0];
+
+        for next in [2, 3] {
+            queue
+                .process(&(), |_, _| async move { ((), Ok(vec![defer(next)])) })
+                .await
+                .unwrap();
+        }
+        let b = items(&queue, ItemStatus::Queued).await[0].parents[0];
+
+        let c = queue
+            .process(&(), |_, id| async move {
+                (id, Err(QueueError::Fatal("error".into())))
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        let history = |max_depth| {
+            let queue = &queue;
+            async move {
+                queue
+                    .query_history(c.raw(), max_depth)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|record| (record.id, record.parents, record.item.0, record.depth))
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(
+            history(10).await,
+            vec![
+                (c.raw(), vec![b.raw()], defer(3), 1),
+                (b.raw(), vec![a.raw()], defer(2), 2),
+                (a.raw(), vec![], defer(1), 3),
+            ]
+        );
+        assert_eq!(
+            history(2).await,
+            vec![
+                (c.raw(), vec![b.raw()], defer(3), 1),
+                (b.raw(), vec![a.raw()], defer(2), 2),
+            ]
+        );
+        assert!(queue
+            .query_history(c.raw() + 1, 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_new_releases_claims() {
+        let path = std::env::temp_dir().join(format!(
+            "sqlite-queue-only-new-releases-claims-{}.db",
+            std::process::id()
+        ));
+
+        let queue = SqliteQueue::<UnitMessage>::new(config(path.clone()))
+            .await
+            .unwrap();
+
+        let id = queue.enqueue(defer(1), &()).await.unwrap().queue[0];
+
+        // claimed by a running instance
+        sqlx::query("UPDATE queue SET claimed = 1 WHERE id = ?1")
+            .bind(id.raw())
+            .execute(&queue.client)
+            .await
+            .unwrap();
+
+        let cli = SqliteQueue::<UnitMessage>::connect(config(path.clone()))
+            .await
+            .unwrap();
+        assert_eq!(claimed(&cli, id).await, (1, 0));
+
+        let restarted = SqliteQueue::<UnitMessage>::new(config(path.clone()))
+            .await
+            .unwrap();
+        assert_eq!(claimed(&restarted, id).await, (0, 0));
+
+        for pool in [queue.client, cli.client, restarted.client] {
+            pool.close().await;
+        }
+
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{suffix}", path.display()));
+        }
+    }
+
+    #[tokio::test]
+    async fn process_error_releases_claim() {
+        let queue = new_queue().await;
+
+        // an item that can't be decoded causes process_item to error after the item was claimed
+        sqlx::query(
+            "
+            INSERT INTO queue (id, item, created_at, handle_at)
+            VALUES            (1,  '{}', 0,          0        )
+            ",
+        )
+        .execute(&queue.client)
+        .await
+        .unwrap();
+
+        let err = queue
+            .process(&(), |_, _| async { ((), Ok(vec![])) })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, sqlx::Error::Decode(_)), "{err:?}");
+
+        assert_eq!(claimed(&queue, ItemId::new(1).unwrap()).await, (0, 0));
+    }
+
+    #[tokio::test]
+    async fn optimize() {
+        let queue = new_queue().await;
+
+        let a = queue.enqueue(data(()), &DataFilter).await.unwrap();
+        let b = queue.enqueue(data(()), &DataFilter).await.unwrap();
+        assert!(a.queue.is_empty() && b.queue.is_empty());
+
+        let optimize = items(&queue, ItemStatus::Optimize).await;
+        assert_eq!(optimize.len(), 2);
+        assert!(optimize
+            .iter()
+            .all(|item| item.tag.as_deref() == Some("data")));
+
+        queue.optimize("data", &DataFilter, &Combine).await.unwrap();
+
+        assert!(items(&queue, ItemStatus::Optimize).await.is_empty());
+
+        let queued = items(&queue, ItemStatus::Queued).await;
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].item, defer(1));
+        assert_eq!(queued[0].parents, vec![a.optimize[0], b.optimize[0]]);
+    }
+}
@@ -48,6 +48,16 @@ pub struct CheckpointSummary {
     pub version_specific_data: Vec<u8>,
 }
 
+impl CheckpointSummary {
+    #[cfg(feature = "serde")]
+    pub fn digest(&self) -> Digest {
+        let mut hasher = Blake2b::<typenum::U32>::new();
+        hasher.update("CheckpointSummary::");
+        bcs::serialize_into(&mut hasher, self).unwrap();
+        Digest(FixedBytes::new(hasher.finalize().into()))
+    }
+}
+
 #[derive(Eq, PartialEq, Clone, Debug)]
 #[cfg_attr(
     feature = "serde",
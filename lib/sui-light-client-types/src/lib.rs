@@ -10,6 +10,7 @@ pub mod consensus_state;
 pub mod crypto;
 pub mod digest;
 pub mod header;
+pub mod misbehaviour;
 pub mod object;
 pub mod storage_proof;
 pub mod transaction;
@@ -151,6 +152,7 @@ impl<'de> serde::Deserialize<'de> for U64 {
 
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
 pub struct CertifiedCheckpointSummary {
     pub data: CheckpointSummary,
     pub auth_signature: AuthorityStrongQuorumSignInfo,
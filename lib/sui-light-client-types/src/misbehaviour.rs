@@ -0,0 +1,12 @@
+use crate::CertifiedCheckpointSummary;
+
+/// Two checkpoint summaries for the same sequence number with different digests, both certified by the committee of their epoch.
+///
+/// Checkpoints are final once certified, so a committee certifying two different checkpoints at the same sequence number is proof of the committee being malicious.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct Misbehaviour {
+    pub checkpoint_a: CertifiedCheckpointSummary,
+    pub checkpoint_b: CertifiedCheckpointSummary,
+}
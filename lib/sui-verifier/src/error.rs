@@ -27,4 +27,25 @@ pub enum Error {
         "proven contents digest ({proven}) does not match the given contents digest ({given})"
     )]
     ContentsDigestMismatch { given: Digest, proven: Digest },
+
+    #[error(
+        "checkpoint epoch ({checkpoint}) does not match the signature epoch ({signature}) or the committee epoch ({committee})"
+    )]
+    EpochMismatch {
+        checkpoint: u64,
+        signature: u64,
+        committee: u64,
+    },
+
+    #[error("signer ({0}) is not part of the committee")]
+    UnknownAuthority(u32),
+
+    #[error("signed stake ({signed}) is below the quorum threshold ({threshold})")]
+    InsufficientStake { signed: u64, threshold: u64 },
+
+    #[error("checkpoints have different sequence numbers ({a} and {b})")]
+    SequenceNumberMismatch { a: u64, b: u64 },
+
+    #[error("checkpoints have the same digest ({0}), no equivocation")]
+    NoEquivocation(Digest),
 }
@@ -9,7 +9,7 @@ use sui_light_client_types::{
     digest::Digest,
     object::{Data, ObjectInner, TypeTag},
     transaction_effects::{ObjectOut, TransactionEffects},
-    AppId, Intent, IntentMessage, IntentScope, IntentVersion, ObjectID,
+    AppId, CertifiedCheckpointSummary, Intent, IntentMessage, IntentScope, IntentVersion, ObjectID,
 };
 
 mod error;
@@ -48,7 +48,16 @@ pub fn verify_checkpoint<V: SignatureVerification>(
     sign_info: &AuthorityStrongQuorumSignInfo,
     signature_verifier: &V,
 ) -> Result<(), Error> {
+    if sign_info.epoch != checkpoint.epoch || committee.epoch.0 != checkpoint.epoch {
+        return Err(Error::EpochMismatch {
+            checkpoint: checkpoint.epoch,
+            signature: sign_info.epoch,
+            committee: committee.epoch.0,
+        });
+    }
+
     let mut selected_public_keys = vec![];
+    let mut signed_stake = 0_u64;
 
     let mut seen = std::collections::BTreeSet::new();
 
@@ -57,7 +66,21 @@ pub fn verify_checkpoint<V: SignatureVerification>(
             continue;
         }
 
-        selected_public_keys.push(committee.voting_rights[authority_index as usize].0.clone());
+        let (public_key, stake) = committee
+            .voting_rights
+            .get(authority_index as usize)
+            .ok_or(Error::UnknownAuthority(authority_index))?;
+
+        selected_public_keys.push(public_key.clone());
+        signed_stake = signed_stake.saturating_add(stake.0);
+    }
+
+    let quorum_threshold = quorum_threshold(committee);
+    if signed_stake < quorum_threshold {
+        return Err(Error::InsufficientStake {
+            signed: signed_stake,
+            threshold: quorum_threshold,
+        });
     }
 
     let intent_msg = IntentMessage {
@@ -82,6 +105,54 @@ pub fn verify_checkpoint<V: SignatureVerification>(
         .map_err(Into::into)
 }
 
+/// Verify that `checkpoint_a` and `checkpoint_b` are two different checkpoints with the same
+/// sequence number, each certified by the committee of its epoch.
+pub fn verify_checkpoint_equivocation<V: SignatureVerification>(
+    committee_a: &Committee,
+    checkpoint_a: &CertifiedCheckpointSummary,
+    committee_b: &Committee,
+    checkpoint_b: &CertifiedCheckpointSummary,
+    signature_verifier: &V,
+) -> Result<(), Error> {
+    if checkpoint_a.data.sequence_number != checkpoint_b.data.sequence_number {
+        return Err(Error::SequenceNumberMismatch {
+            a: checkpoint_a.data.sequence_number,
+            b: checkpoint_b.data.sequence_number,
+        });
+    }
+
+    let digest = checkpoint_a.data.digest();
+    if digest == checkpoint_b.data.digest() {
+        return Err(Error::NoEquivocation(digest));
+    }
+
+    verify_checkpoint(
+        committee_a,
+        &checkpoint_a.data,
+        &checkpoint_a.auth_signature,
+        signature_verifier,
+    )?;
+    verify_checkpoint(
+        committee_b,
+        &checkpoint_b.data,
+        &checkpoint_b.auth_signature,
+        signature_verifier,
+    )?;
+
+    Ok(())
+}
+
+/// The minimum stake required for a certificate to be valid, i.e. more than 2/3 of the total
+/// stake of the committee.
+pub fn quorum_threshold(committee: &Committee) -> u64 {
+    let total_stake = committee
+        .voting_rights
+        .iter()
+        .fold(0_u64, |acc, (_, stake)| acc.saturating_add(stake.0));
+
+    total_stake.saturating_mul(2) / 3 + 1
+}
+
 pub fn verify_membership(
     commitments_object: ObjectID,
     key: Bytes,
@@ -196,3 +267,262 @@ pub fn calculate_dynamic_field_object_id(parent: [u8; 32], key_bytes: &[u8]) ->
 
     ObjectID::new(hash.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use roaring::RoaringBitmap;
+    use sui_light_client_types::{
+        checkpoint_summary::GasCostSummary,
+        crypto::{CryptoBytes, SuiBitmap},
+        U64,
+    };
+    use unionlabs_primitives::FixedBytes;
+
+    use super::*;
+
+    const EPOCH: u64 = 7;
+
+    /// Accepts every signature, recording the public keys it was asked to verify against.
+    #[derive(Default)]
+    struct RecordingVerifier {
+        verified: RefCell<Vec<Vec<AuthorityPublicKeyBytes>>>,
+    }
+
+    impl SignatureVerification for RecordingVerifier {
+        type Error = Error;
+
+        fn verify_signature(
+            &self,
+            public_keys: &[AuthorityPublicKeyBytes],
+            _msg: &[u8],
+            _signature: &AggregateAuthoritySignature,
+        ) -> Result<(), Self::Error> {
+            self.verified.borrow_mut().push(public_keys.to_vec());
+            Ok(())
+        }
+    }
+
+    fn public_key(i: u8) -> AuthorityPublicKeyBytes {
+        CryptoBytes(FixedBytes::new([i; 96]))
+    }
+
+    /// A committee of four authorities with equal stake, the quorum threshold is 3 of them.
+    fn committee(epoch: u64) -> Committee {
+        Committee {
+            epoch: U64(epoch),
+            voting_rights: (0..4).map(|i| (public_key(i), U64(2500))).collect(),
+        }
+    }
+
+    fn checkpoint(sequence_number: u64, content_digest: u8) -> CheckpointSummary {
+        CheckpointSummary {
+            epoch: EPOCH,
+            sequence_number,
+            network_total_transactions: 100,
+            content_digest: Digest(FixedBytes::new([content_digest; 32])),
+            previous_digest: Some(Digest(FixedBytes::new([0xAA; 32]))),
+            epoch_rolling_gas_cost_summary: GasCostSummary {
+                computation_cost: U64(0),
+                storage_cost: U64(0),
+                storage_rebate: U64(0),
+                non_refundable_storage_fee: U64(0),
+            },
+            timestamp_ms: 1_700_000_000_000,
+            checkpoint_commitments: vec![],
+            end_of_epoch_data: None,
+            version_specific_data: vec![],
+        }
+    }
+
+    fn sign_info(
+        epoch: u64,
+        signers: impl IntoIterator<Item = u32>,
+    ) -> AuthorityStrongQuorumSignInfo {
+        AuthorityStrongQuorumSignInfo {
+            epoch,
+            signature: CryptoBytes(FixedBytes::new([0xBB; 48])),
+            signers_map: SuiBitmap(RoaringBitmap::from_iter(signers)),
+        }
+    }
+
+    fn certified(sequence_number: u64, content_digest: u8) -> CertifiedCheckpointSummary {
+        CertifiedCheckpointSummary {
+            data: checkpoint(sequence_number, content_digest),
+            auth_signature: sign_info(EPOCH, [0, 1, 2]),
+        }
+    }
+
+    #[test]
+    fn quorum_threshold_is_more_than_two_thirds() {
+        assert_eq!(quorum_threshold(&committee(EPOCH)), 6667);
+    }
+
+    #[test]
+    fn verify_checkpoint_with_quorum() {
+        let verifier = RecordingVerifier::default();
+
+        verify_checkpoint(
+            &committee(EPOCH),
+            &checkpoint(10, 1),
+            &sign_info(EPOCH, [0, 1, 3]),
+            &verifier,
+        )
+        .unwrap();
+
+        assert_eq!(
+            verifier.verified.into_inner(),
+            vec![vec![public_key(0), public_key(1), public_key(3)]]
+        );
+    }
+
+    #[test]
+    fn verify_checkpoint_below_quorum() {
+        let verifier = RecordingVerifier::default();
+
+        let err = verify_checkpoint(
+            &committee(EPOCH),
+            &checkpoint(10, 1),
+            &sign_info(EPOCH, [0, 1]),
+            &verifier,
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                Error::InsufficientStake {
+                    signed: 5000,
+                    threshold: 6667
+                }
+            ),
+            "{err:?}"
+        );
+        assert!(verifier.verified.into_inner().is_empty());
+    }
+
+    #[test]
+    fn verify_checkpoint_unknown_authority() {
+        let err = verify_checkpoint(
+            &committee(EPOCH),
+            &checkpoint(10, 1),
+            &sign_info(EPOCH, [0, 1, 4]),
+            &RecordingVerifier::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::UnknownAuthority(4)), "{err:?}");
+    }
+
+    #[test]
+    fn verify_checkpoint_epoch_mismatch() {
+        let err = verify_checkpoint(
+            &committee(EPOCH + 1),
+            &checkpoint(10, 1),
+            &sign_info(EPOCH, [0, 1, 2]),
+            &RecordingVerifier::default(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                Error::EpochMismatch {
+                    checkpoint: EPOCH,
+                    signature: EPOCH,
+                    committee: 8
+                }
+            ),
+            "{err:?}"
+        );
+
+        let err = verify_checkpoint(
+            &committee(EPOCH),
+            &checkpoint(10, 1),
+            &sign_info(EPOCH + 1, [0, 1, 2]),
+            &RecordingVerifier::default(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                Error::EpochMismatch {
+                    checkpoint: EPOCH,
+                    signature: 8,
+                    committee: EPOCH
+                }
+            ),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn equivocation_accepted() {
+        let verifier = RecordingVerifier::default();
+
+        verify_checkpoint_equivocation(
+            &committee(EPOCH),
+            &certified(10, 1),
+            &committee(EPOCH),
+            &certified(10, 2),
+            &verifier,
+        )
+        .unwrap();
+
+        // both checkpoints must be certified
+        assert_eq!(verifier.verified.into_inner().len(), 2);
+    }
+
+    #[test]
+    fn equivocation_identical_checkpoints() {
+        let err = verify_checkpoint_equivocation(
+            &committee(EPOCH),
+            &certified(10, 1),
+            &committee(EPOCH),
+            &certified(10, 1),
+            &RecordingVerifier::default(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(err, Error::NoEquivocation(digest) if digest == checkpoint(10, 1).digest()),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn equivocation_different_sequence_numbers() {
+        let err = verify_checkpoint_equivocation(
+            &committee(EPOCH),
+            &certified(10, 1),
+            &committee(EPOCH),
+            &certified(11, 2),
+            &RecordingVerifier::default(),
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(err, Error::SequenceNumberMismatch { a: 10, b: 11 }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn equivocation_below_quorum() {
+        let mut checkpoint_b = certified(10, 2);
+        checkpoint_b.auth_signature = sign_info(EPOCH, [0]);
+
+        let err = verify_checkpoint_equivocation(
+            &committee(EPOCH),
+            &certified(10, 1),
+            &committee(EPOCH),
+            &checkpoint_b,
+            &RecordingVerifier::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::InsufficientStake { .. }), "{err:?}");
+    }
+}
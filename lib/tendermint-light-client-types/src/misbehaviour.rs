@@ -0,0 +1,57 @@
+use crate::header::Header;
+
+/// Two conflicting headers for the same chain, both of which are signed by a sufficient amount of trusted validators.
+///
+/// This is either a fork (two different headers at the same height), or a violation of BFT time monotonicity (`header_a` is higher than `header_b`, but is not more recent).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+#[cfg_attr(feature = "bincode", derive(bincode::Encode, bincode::Decode))]
+pub struct Misbehaviour {
+    pub header_a: Header,
+    pub header_b: Header,
+}
+
+#[cfg(feature = "proto")]
+pub mod proto {
+    use unionlabs::{errors::MissingField, impl_proto_via_try_from_into, required};
+
+    use crate::{header, Misbehaviour};
+
+    impl_proto_via_try_from_into!(Misbehaviour => protos::ibc::lightclients::tendermint::v1::Misbehaviour);
+
+    impl From<Misbehaviour> for protos::ibc::lightclients::tendermint::v1::Misbehaviour {
+        fn from(value: Misbehaviour) -> Self {
+            #[allow(deprecated)]
+            Self {
+                client_id: String::new(),
+                header_1: Some(value.header_a.into()),
+                header_2: Some(value.header_b.into()),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq, Clone, thiserror::Error)]
+    pub enum Error {
+        #[error(transparent)]
+        MissingField(#[from] MissingField),
+        #[error("invalid header")]
+        Header(#[from] header::proto::Error),
+    }
+
+    impl TryFrom<protos::ibc::lightclients::tendermint::v1::Misbehaviour> for Misbehaviour {
+        type Error = Error;
+
+        fn try_from(
+            value: protos::ibc::lightclients::tendermint::v1::Misbehaviour,
+        ) -> Result<Self, Self::Error> {
+            Ok(Self {
+                header_a: required!(value.header_1)?.try_into()?,
+                header_b: required!(value.header_2)?.try_into()?,
+            })
+        }
+    }
+}
@@ -13,6 +13,7 @@ use serde::de::DeserializeOwned;
 use serde_json::{json, Value};
 use tracing::instrument;
 use unionlabs::{ibc::core::client::height::Height, primitives::Bytes, ErrorReporter};
+use voyager_message::VoyagerMessage;
 use voyager_primitives::{
     ChainId, ClientInfo, ClientStateMeta, ClientType, ConsensusStateMeta, IbcInterface, IbcQuery,
     IbcSpec, IbcSpecId, IbcStorePathKey, QueryHeight, Timestamp,
@@ -25,6 +26,7 @@ use voyager_rpc::{
     VoyagerRpcClient, FATAL_JSONRPC_ERROR_CODE, MISSING_STATE_ERROR_CODE,
 };
 use voyager_types::RawClientId;
+use voyager_vm::Op;
 
 #[derive(Debug, Clone)]
 pub struct VoyagerClient<C: ClientT>(C);
@@ -142,6 +144,30 @@ impl<C: VoyagerRpcClient> VoyagerClient<C> {
         Ok(header)
     }
 
+    #[instrument(
+        skip_all,
+        name = "voyager_client_encode_misbehaviour",
+        fields(
+            %client_type,
+            %ibc_interface,
+            %misbehaviour
+        )
+    )]
+    pub async fn encode_misbehaviour<V: IbcSpec>(
+        &self,
+        client_type: ClientType,
+        ibc_interface: IbcInterface,
+        misbehaviour: Value,
+    ) -> RpcResult<Bytes> {
+        let misbehaviour = self
+            .0
+            .encode_misbehaviour(client_type, ibc_interface, V::ID, misbehaviour)
+            .await
+            .map_err(json_rpc_error_to_error_object)?;
+
+        Ok(misbehaviour)
+    }
+
     pub async fn decode_client_state<V: IbcSpec, T: DeserializeOwned>(
         &self,
         client_type: ClientType,
@@ -553,6 +579,13 @@ impl<C: VoyagerRpcClient> VoyagerClient<C> {
             .map_err(json_rpc_error_to_error_object)
     }
 
+    pub async fn enqueue(&self, op: Op<VoyagerMessage>) -> RpcResult<()> {
+        self.0
+            .enqueue(op)
+            .await
+            .map_err(json_rpc_error_to_error_object)
+    }
+
     pub fn plugin_client(&self, plugin: impl Into<String>) -> VoyagerPluginClient<C> {
         VoyagerPluginClient {
             inner: self,
@@ -0,0 +1,239 @@
+use std::{error::Error, net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, Request, StatusCode},
+    middleware::{self, Next},
+    response::Response,
+    routing::{get, post},
+    Json, Router,
+};
+use futures::{channel::mpsc::UnboundedSender, SinkExt};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+use unionlabs::ErrorReporter;
+use voyager_message::VoyagerMessage;
+use voyager_rpc::types::{WorkerState, WorkerStatus};
+use voyager_vm::{ItemId, ItemStatus, Op, Queue, QueueItem};
+
+use crate::{
+    context::SharedContext,
+    pause::{PauseTarget, Paused, PausedState},
+};
+
+/// The maximum number of items that can be requested in a single page.
+const MAX_PER_PAGE: u32 = 1000;
+
+/// Start the REST server. Messages sent to `/enqueue` are forwarded to `queue_tx`.
+///
+/// The admin routes (under `/admin`) are only served if `admin_token` is set, and require all requests to be authenticated with `Authorization: Bearer <admin_token>`.
+pub fn run<Q: Queue<VoyagerMessage>>(
+    laddr: &SocketAddr,
+    context: SharedContext,
+    queue: Q,
+    paused: Paused,
+    admin_token: Option<String>,
+    queue_tx: UnboundedSender<Op<VoyagerMessage>>,
+) {
+    let mut app = axum::Router::new()
+        .route("/enqueue", post(enqueue))
+        .route("/health", get(async || StatusCode::OK))
+        .with_state(queue_tx)
+        .merge(
+            axum::Router::new()
+                .route("/ready", get(ready))
+                .with_state(context),
+        );
+
+    match admin_token {
+        Some(admin_token) => {
+            app = app.nest("/admin", admin_router(queue, paused, admin_token.into()));
+        }
+        None => {
+            info!("no admin token configured, the admin api is disabled");
+        }
+    }
+
+    tokio::spawn(axum::Server::bind(laddr).serve(app.into_make_service()));
+}
+
+// #[axum::debug_handler]
+async fn enqueue(
+    State(mut sender): State<UnboundedSender<Op<VoyagerMessage>>>,
+    Json(op): Json<Op<VoyagerMessage>>,
+) -> StatusCode {
+    sender.send(op).await.expect("receiver should not close");
+
+    StatusCode::OK
+}
+
+#[derive(Debug, Serialize)]
+struct Readiness {
+    ready: bool,
+    workers: Vec<WorkerStatus>,
+}
+
+/// Report the connectivity of all plugins and modules. Voyager is considered ready once it has
+/// started and all workers have passed their most recent health check.
+async fn ready(State(context): State<SharedContext>) -> (StatusCode, Json<Readiness>) {
+    let (ready, workers) = match context.load() {
+        Some(context) => {
+            let workers = context.supervisor.statuses();
+
+            (
+                workers.iter().all(|worker| worker.state == WorkerState::Up),
+                workers,
+            )
+        }
+        None => (false, vec![]),
+    };
+
+    (
+        if ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        },
+        Json(Readiness { ready, workers }),
+    )
+}
+
+#[derive(Clone)]
+struct Admin<Q> {
+    queue: Q,
+    paused: Paused,
+}
+
+fn admin_router<Q: Queue<VoyagerMessage>>(
+    queue: Q,
+    paused: Paused,
+    admin_token: Arc<str>,
+) -> Router {
+    Router::new()
+        .route("/queue/:status", get(query_items::<Q>))
+        .route("/failed/requeue", post(requeue_failed_items::<Q>))
+        .route("/failed/delete", post(delete_failed_items::<Q>))
+        .route("/paused", get(paused_state::<Q>))
+        .route("/pause", post(pause::<Q>))
+        .route("/resume", post(resume::<Q>))
+        .route_layer(middleware::from_fn_with_state(admin_token, authenticate))
+        .with_state(Admin { queue, paused })
+}
+
+async fn authenticate<B>(
+    State(admin_token): State<Arc<str>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, StatusCode> {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token.as_bytes().ct_eq(admin_token.as_bytes()).into());
+
+    if authorized {
+        Ok(next.run(request).await)
+    } else {
+        warn!(uri = %request.uri(), "rejected unauthorized admin request");
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+fn internal_error(error: impl Error) -> (StatusCode, String) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        ErrorReporter(error).to_string(),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Pagination {
+    #[serde(default = "default_page")]
+    page: u32,
+    #[serde(default = "default_per_page")]
+    per_page: u32,
+}
+
+const fn default_page() -> u32 {
+    1
+}
+
+const fn default_per_page() -> u32 {
+    100
+}
+
+async fn query_items<Q: Queue<VoyagerMessage>>(
+    State(admin): State<Admin<Q>>,
+    Path(status): Path<ItemStatus>,
+    Query(Pagination { page, per_page }): Query<Pagination>,
+) -> Result<Json<Vec<QueueItem<VoyagerMessage>>>, (StatusCode, String)> {
+    admin
+        .queue
+        .query_items(status, page.max(1), per_page.clamp(1, MAX_PER_PAGE))
+        .await
+        .map(Json)
+        .map_err(internal_error)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Ids {
+    ids: Vec<ItemId>,
+}
+
+async fn requeue_failed_items<Q: Queue<VoyagerMessage>>(
+    State(admin): State<Admin<Q>>,
+    Json(Ids { ids }): Json<Ids>,
+) -> Result<Json<Vec<ItemId>>, (StatusCode, String)> {
+    let requeued = admin
+        .queue
+        .requeue_failed_items(ids)
+        .await
+        .map_err(internal_error)?;
+
+    info!(?requeued, "requeued failed items");
+
+    Ok(Json(requeued))
+}
+
+async fn delete_failed_items<Q: Queue<VoyagerMessage>>(
+    State(admin): State<Admin<Q>>,
+    Json(Ids { ids }): Json<Ids>,
+) -> Result<Json<Vec<ItemId>>, (StatusCode, String)> {
+    let deleted = admin
+        .queue
+        .delete_failed_items(ids)
+        .await
+        .map_err(internal_error)?;
+
+    info!(?deleted, "deleted failed items");
+
+    Ok(Json(deleted))
+}
+
+async fn paused_state<Q: Queue<VoyagerMessage>>(
+    State(admin): State<Admin<Q>>,
+) -> Json<PausedState> {
+    Json(admin.paused.state())
+}
+
+async fn pause<Q: Queue<VoyagerMessage>>(
+    State(admin): State<Admin<Q>>,
+    Json(target): Json<PauseTarget>,
+) -> Json<PausedState> {
+    admin.paused.pause(target);
+
+    Json(admin.paused.state())
+}
+
+async fn resume<Q: Queue<VoyagerMessage>>(
+    State(admin): State<Admin<Q>>,
+    Json(target): Json<PauseTarget>,
+) -> Json<PausedState> {
+    admin.paused.resume(&target);
+
+    Json(admin.paused.state())
+}
@@ -0,0 +1,290 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use concurrent_keyring::SignerBalance;
+use opentelemetry::{
+    metrics::{Counter, Gauge},
+    KeyValue,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, error, info, instrument, trace, warn};
+use unionlabs::ErrorReporter;
+use voyager_plugin_protocol::WorkerClient;
+use voyager_rpc::PluginClient;
+use voyager_vm::BoxDynError;
+
+use crate::context::SharedContext;
+
+/// Custom plugin method returning the [`SignerBalance`] of every signer in the plugin's keyring.
+pub const KEYRING_BALANCES_METHOD: &str = "keyringBalances";
+
+/// Custom plugin method removing a signer (by address) from the keyring's rotation.
+pub const DISABLE_SIGNER_METHOD: &str = "disableSigner";
+
+/// Custom plugin method adding a previously disabled signer (by address) back into the keyring's rotation.
+pub const ENABLE_SIGNER_METHOD: &str = "enableSigner";
+
+/// Custom plugin method transferring an amount from the plugin's treasury to a signer, with params `[address, amount]`.
+pub const TOP_UP_SIGNER_METHOD: &str = "topUpSigner";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceMonitorConfig {
+    /// How often the signer balances are checked, in seconds.
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    /// The balance thresholds of the signers of each plugin, keyed by plugin name.
+    ///
+    /// The balances of all transaction plugins are exported as metrics, but signers are only reported as low, disabled, or topped up if their plugin is configured here.
+    #[serde(default)]
+    pub plugins: BTreeMap<String, SignerThresholds>,
+}
+
+const fn default_interval() -> u64 {
+    5 * 60
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SignerThresholds {
+    /// Signers with a balance below this are reported as low, and topped up if `top_up_amount` is set.
+    #[serde(with = "::serde_utils::string")]
+    #[schemars(with = "String")]
+    pub low_balance: u128,
+    /// Signers with a balance below this are removed from the keyring's rotation, and are added back once their balance is at least this again.
+    #[serde(with = "::serde_utils::string")]
+    #[schemars(with = "String")]
+    pub min_balance: u128,
+    /// The amount to transfer from the plugin's treasury to signers with a low balance. If not set, signers are never topped up.
+    #[serde(default, with = "::serde_utils::string_opt")]
+    #[schemars(with = "Option<String>")]
+    pub top_up_amount: Option<u128>,
+}
+
+/// Periodically checks the balances of the signers of all transaction plugins, as configured by [`BalanceMonitorConfig`].
+///
+/// Plugins opt in to being monitored by implementing the custom methods defined in this module. Plugins that don't implement [`KEYRING_BALANCES_METHOD`] are ignored.
+pub(crate) struct BalanceMonitor {
+    config: BalanceMonitorConfig,
+    context: SharedContext,
+    metrics: BalanceMonitorMetrics,
+}
+
+struct BalanceMonitorMetrics {
+    balance_gauge: Gauge<f64>,
+    low_balance_gauge: Gauge<u64>,
+    enabled_gauge: Gauge<u64>,
+    top_up_counter: Counter<u64>,
+}
+
+impl BalanceMonitorMetrics {
+    fn new() -> Self {
+        Self {
+            balance_gauge: opentelemetry::global::meter("voyager")
+                .f64_gauge("signer.balance")
+                .build(),
+            low_balance_gauge: opentelemetry::global::meter("voyager")
+                .u64_gauge("signer.low_balance")
+                .build(),
+            enabled_gauge: opentelemetry::global::meter("voyager")
+                .u64_gauge("signer.enabled")
+                .build(),
+            top_up_counter: opentelemetry::global::meter("voyager")
+                .u64_counter("signer.top_up")
+                .build(),
+        }
+    }
+}
+
+impl BalanceMonitor {
+    pub(crate) fn new(config: BalanceMonitorConfig, context: SharedContext) -> Self {
+        Self {
+            config,
+            context,
+            metrics: BalanceMonitorMetrics::new(),
+        }
+    }
+
+    pub(crate) async fn run(&self) -> Result<(), BoxDynError> {
+        info!(interval = %self.config.interval, "starting signer balance monitor");
+
+        loop {
+            if let Some(context) = self.context.load() {
+                for (plugin_name, plugin) in &context.plugins {
+                    self.check_plugin(plugin_name, plugin).await;
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(self.config.interval)).await;
+        }
+    }
+
+    #[instrument(skip_all, fields(%plugin_name))]
+    async fn check_plugin(&self, plugin_name: &str, plugin: &WorkerClient) {
+        let thresholds = self.config.plugins.get(plugin_name);
+
+        let balances = match PluginClient::<Value, Value>::custom(
+            plugin,
+            KEYRING_BALANCES_METHOD.to_owned(),
+            vec![],
+        )
+        .await
+        {
+            Ok(balances) => balances,
+            Err(err) if thresholds.is_some() => {
+                warn!(
+                    error = %ErrorReporter(err),
+                    "error fetching signer balances"
+                );
+                return;
+            }
+            Err(err) => {
+                // most likely not a transaction plugin
+                trace!(
+                    error = %ErrorReporter(err),
+                    "error fetching signer balances"
+                );
+                return;
+            }
+        };
+
+        let balances = match serde_json::from_value::<Vec<SignerBalance<String>>>(balances) {
+            Ok(balances) => balances,
+            Err(err) => {
+                warn!(
+                    error = %ErrorReporter(err),
+                    "invalid signer balances response"
+                );
+                return;
+            }
+        };
+
+        for signer in balances {
+            self.check_signer(plugin_name, plugin, thresholds, signer)
+                .await;
+        }
+    }
+
+    #[instrument(skip_all, fields(keyring = %signer.key_name, address = %signer.address))]
+    async fn check_signer(
+        &self,
+        plugin_name: &str,
+        plugin: &WorkerClient,
+        thresholds: Option<&SignerThresholds>,
+        signer: SignerBalance<String>,
+    ) {
+        let attributes = &[
+            KeyValue::new("plugin", plugin_name.to_owned()),
+            KeyValue::new("keyring", signer.key_name.clone()),
+            KeyValue::new("address", signer.address.clone()),
+            KeyValue::new("denom", signer.denom.clone()),
+        ];
+
+        self.metrics
+            .balance_gauge
+            .record(signer.balance as f64, attributes);
+
+        let Some(thresholds) = thresholds else {
+            self.metrics
+                .enabled_gauge
+                .record(signer.enabled.into(), attributes);
+            return;
+        };
+
+        let low = signer.balance < thresholds.low_balance;
+
+        self.metrics
+            .low_balance_gauge
+            .record(low.into(), attributes);
+
+        let mut balance = signer.balance;
+
+        if low {
+            warn!(
+                balance = %signer.balance,
+                denom = %signer.denom,
+                low_balance = %thresholds.low_balance,
+                "signer balance is low"
+            );
+
+            if let Some(amount) = thresholds.top_up_amount {
+                match PluginClient::<Value, Value>::custom(
+                    plugin,
+                    TOP_UP_SIGNER_METHOD.to_owned(),
+                    vec![
+                        Value::String(signer.address.clone()),
+                        Value::String(amount.to_string()),
+                    ],
+                )
+                .await
+                {
+                    Ok(tx_hash) => {
+                        info!(%tx_hash, %amount, denom = %signer.denom, "topped up signer");
+
+                        self.metrics.top_up_counter.add(1, attributes);
+
+                        balance = balance.saturating_add(amount);
+                    }
+                    Err(err) => {
+                        error!(error = %ErrorReporter(err), "error topping up signer");
+                    }
+                }
+            }
+        }
+
+        let drained = balance < thresholds.min_balance;
+
+        let enabled = match (drained, signer.enabled) {
+            (true, true) => {
+                error!(
+                    %balance,
+                    denom = %signer.denom,
+                    min_balance = %thresholds.min_balance,
+                    "signer is drained, removing it from the rotation"
+                );
+
+                !self
+                    .set_enabled(plugin, DISABLE_SIGNER_METHOD, &signer.address)
+                    .await
+            }
+            (false, false) => {
+                info!(
+                    %balance,
+                    denom = %signer.denom,
+                    "signer is no longer drained, adding it back into the rotation"
+                );
+
+                self.set_enabled(plugin, ENABLE_SIGNER_METHOD, &signer.address)
+                    .await
+            }
+            (_, enabled) => {
+                debug!(%balance, enabled, "signer balance checked");
+
+                enabled
+            }
+        };
+
+        self.metrics
+            .enabled_gauge
+            .record(enabled.into(), attributes);
+    }
+
+    /// Call either [`ENABLE_SIGNER_METHOD`] or [`DISABLE_SIGNER_METHOD`], returning whether the call succeeded.
+    async fn set_enabled(&self, plugin: &WorkerClient, method: &str, address: &str) -> bool {
+        match PluginClient::<Value, Value>::custom(
+            plugin,
+            method.to_owned(),
+            vec![Value::String(address.to_owned())],
+        )
+        .await
+        {
+            Ok(_) => true,
+            Err(err) => {
+                error!(error = %ErrorReporter(err), %method, "error updating signer rotation");
+
+                false
+            }
+        }
+    }
+}
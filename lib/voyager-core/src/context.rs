@@ -1,10 +1,19 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, PoisonError, RwLock},
+};
 
+use anyhow::anyhow;
 use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use voyager_plugin_protocol::WorkerClient;
+use voyager_plugin_protocol::{
+    remote::{client_tls_config, RemoteAuth},
+    WorkerClient,
+};
 use voyager_primitives::{ChainId, ClientType, ConsensusType, IbcInterface, IbcSpecId};
 use voyager_rpc::{
     types::{
@@ -15,7 +24,10 @@ use voyager_rpc::{
 };
 use voyager_vm::QueueError;
 
-use crate::{equivalent_chain_ids::EquivalentChainIds, ibc_spec_handlers::IbcSpecHandlers};
+use crate::{
+    equivalent_chain_ids::EquivalentChainIds, ibc_spec_handlers::IbcSpecHandlers,
+    supervisor::Supervisor,
+};
 
 pub struct Context {
     pub(crate) state_modules: HashMap<(ChainId, IbcSpecId), WorkerClient>,
@@ -37,18 +49,124 @@ pub struct Context {
     pub(crate) equivalent_chain_ids: EquivalentChainIds,
 
     // ibc version id => handler
-    pub(crate) ibc_spec_handlers: IbcSpecHandlers,
+    pub(crate) ibc_spec_handlers: Arc<IbcSpecHandlers>,
+
+    pub(crate) supervisor: Supervisor,
+}
+
+/// A handle to the currently loaded [`Context`].
+///
+/// The context is replaced as a whole whenever the plugin and module configuration is reloaded. Readers take a snapshot with [`SharedContext::load`], which remains valid (and unchanged) for as long as it is held.
+#[derive(Clone, Default)]
+pub struct SharedContext(Arc<RwLock<Option<Arc<Context>>>>);
+
+impl SharedContext {
+    /// Returns the current context, if it has been loaded.
+    pub fn load(&self) -> Option<Arc<Context>> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn store(&self, context: Context) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(context));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum PluginConfig {
+    /// A plugin binary that is spawned and managed by voyager.
+    Local(LocalPluginConfig),
+    /// An already running plugin that voyager connects to over the network.
+    Remote(RemotePluginConfig),
+}
+
+impl PluginConfig {
+    pub fn enabled(&self) -> bool {
+        match self {
+            PluginConfig::Local(config) => config.enabled,
+            PluginConfig::Remote(config) => config.enabled,
+        }
+    }
+
+    /// The path of the plugin binary, or the url of the remote plugin.
+    pub fn location(&self) -> Cow<'_, str> {
+        match self {
+            PluginConfig::Local(config) => config.path.to_string_lossy(),
+            PluginConfig::Remote(config) => Cow::Borrowed(&config.url),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
-pub struct PluginConfig {
+pub struct LocalPluginConfig {
     pub path: PathBuf,
     pub config: Value,
     #[serde(default = "default_enabled")]
     pub enabled: bool,
 }
 
+/// A plugin served with the `serve` subcommand of the plugin binary.
+///
+/// The plugin is configured on the host it is running on, and calls back into voyager through the voyager rpc server (`voyager.rpc_laddr`).
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RemotePluginConfig {
+    /// The websocket url the plugin is served on, i.e. `ws://10.0.0.2:7300` or `wss://plugins.example.com:7300`.
+    pub url: String,
+    /// The bearer token to authenticate with, if the plugin requires one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+    /// TLS configuration for `wss://` urls. If not set, the plugin's certificate is verified against the system root certificates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<RemotePluginTlsConfig>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RemotePluginTlsConfig {
+    /// PEM file containing the CA certificate(s) to verify the plugin's certificate against.
+    pub ca_cert: PathBuf,
+    /// PEM file containing the client certificate chain to authenticate with (mutual TLS).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert: Option<PathBuf>,
+    /// PEM file containing the private key of `client_cert`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<PathBuf>,
+}
+
+impl RemotePluginConfig {
+    pub fn auth(&self) -> anyhow::Result<RemoteAuth> {
+        let tls = self
+            .tls
+            .as_ref()
+            .map(|tls| {
+                let client_identity = match (&tls.client_cert, &tls.client_key) {
+                    (Some(cert), Some(key)) => Some((&**cert, &**key)),
+                    (None, None) => None,
+                    _ => {
+                        return Err(anyhow!(
+                            "both client_cert and client_key must be set for mutual tls"
+                        ))
+                    }
+                };
+
+                client_tls_config(&tls.ca_cert, client_identity)
+            })
+            .transpose()?;
+
+        Ok(RemoteAuth {
+            token: self.auth_token.clone(),
+            tls,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct ModulesConfig {
@@ -79,6 +197,214 @@ const fn default_enabled() -> bool {
 }
 
 impl Context {
+    pub(crate) fn new(
+        equivalent_chain_ids: EquivalentChainIds,
+        ibc_spec_handlers: Arc<IbcSpecHandlers>,
+        supervisor: Supervisor,
+    ) -> Self {
+        Self {
+            state_modules: Default::default(),
+            proof_modules: Default::default(),
+            finality_modules: Default::default(),
+            client_modules: Default::default(),
+            client_bootstrap_modules: Default::default(),
+            chain_consensus_types: Default::default(),
+            client_consensus_types: Default::default(),
+            plugins: Default::default(),
+            equivalent_chain_ids,
+            ibc_spec_handlers,
+            supervisor,
+        }
+    }
+
+    pub(crate) fn register_plugin(
+        &mut self,
+        name: &str,
+        rpc_client: WorkerClient,
+    ) -> anyhow::Result<()> {
+        let prev = self.plugins.insert(name.to_owned(), rpc_client);
+
+        if prev.is_some() {
+            return Err(anyhow!("multiple plugins configured with name `{name}`"));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn register_state_module(
+        &mut self,
+        StateModuleInfo {
+            chain_id,
+            ibc_spec_id,
+        }: &StateModuleInfo,
+        rpc_client: WorkerClient,
+    ) -> anyhow::Result<()> {
+        for equivalent_chain_id in self
+            .equivalent_chain_ids
+            .equivalents(chain_id)
+            .chain([chain_id])
+        {
+            let prev = self.state_modules.insert(
+                (equivalent_chain_id.clone(), ibc_spec_id.clone()),
+                rpc_client.clone(),
+            );
+
+            if prev.is_some() {
+                return Err(anyhow!(
+                    "multiple state modules configured for chain id \
+                    `{equivalent_chain_id}` and IBC version `{ibc_spec_id}`",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn register_proof_module(
+        &mut self,
+        ProofModuleInfo {
+            chain_id,
+            ibc_spec_id,
+        }: &ProofModuleInfo,
+        rpc_client: WorkerClient,
+    ) -> anyhow::Result<()> {
+        for equivalent_chain_id in self
+            .equivalent_chain_ids
+            .equivalents(chain_id)
+            .chain([chain_id])
+        {
+            let prev = self.proof_modules.insert(
+                (equivalent_chain_id.clone(), ibc_spec_id.clone()),
+                rpc_client.clone(),
+            );
+
+            if prev.is_some() {
+                return Err(anyhow!(
+                    "multiple proof modules configured for chain id \
+                    `{equivalent_chain_id}` and IBC version `{ibc_spec_id}`",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn register_finality_module(
+        &mut self,
+        FinalityModuleInfo {
+            chain_id,
+            consensus_type,
+        }: &FinalityModuleInfo,
+        rpc_client: WorkerClient,
+    ) -> anyhow::Result<()> {
+        for equivalent_chain_id in self
+            .equivalent_chain_ids
+            .equivalents(chain_id)
+            .chain([chain_id])
+        {
+            let prev = self
+                .finality_modules
+                .insert(equivalent_chain_id.clone(), rpc_client.clone());
+
+            if prev.is_some() {
+                return Err(anyhow!(
+                    "multiple consensus modules configured for chain id `{}`",
+                    equivalent_chain_id
+                ));
+            }
+
+            let None = self
+                .chain_consensus_types
+                .insert(equivalent_chain_id.clone(), consensus_type.clone())
+            else {
+                unreachable!()
+            };
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn register_client_module(
+        &mut self,
+        ClientModuleInfo {
+            client_type,
+            consensus_type,
+            ibc_interface,
+            ibc_spec_id,
+        }: &ClientModuleInfo,
+        rpc_client: WorkerClient,
+    ) -> anyhow::Result<()> {
+        if !self.ibc_spec_handlers.handlers.contains_key(ibc_spec_id) {
+            return Err(anyhow!(
+                "IBC version `{ibc_spec_id}` is not supported in this build of voyager"
+            ));
+        }
+
+        let prev = self.client_modules.insert(
+            (
+                client_type.clone(),
+                ibc_interface.clone(),
+                ibc_spec_id.clone(),
+            ),
+            rpc_client,
+        );
+
+        if prev.is_some() {
+            return Err(anyhow!(
+                "multiple client modules configured for client \
+                type `{client_type}`, IBC interface `{ibc_interface}`, \
+                and IBC version `{ibc_spec_id}`",
+            ));
+        }
+
+        if let Some(previous_consensus_type) = self
+            .client_consensus_types
+            .insert(client_type.clone(), consensus_type.clone())
+        {
+            if previous_consensus_type != *consensus_type {
+                return Err(anyhow!(
+                    "inconsistency in client consensus types: \
+                    client type `{client_type}` is registered \
+                    as tracking both `{previous_consensus_type}` \
+                    and `{consensus_type}`"
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn register_client_bootstrap_module(
+        &mut self,
+        ClientBootstrapModuleInfo {
+            client_type,
+            chain_id,
+        }: &ClientBootstrapModuleInfo,
+        rpc_client: WorkerClient,
+    ) -> anyhow::Result<()> {
+        for equivalent_chain_id in self
+            .equivalent_chain_ids
+            .equivalents(chain_id)
+            .chain([chain_id])
+        {
+            let prev = self.client_bootstrap_modules.insert(
+                (equivalent_chain_id.clone(), client_type.clone()),
+                rpc_client.clone(),
+            );
+
+            if prev.is_some() {
+                return Err(anyhow!(
+                    "multiple client bootstrap modules configured for client \
+                    type `{client_type}` and chain id `{equivalent_chain_id}`",
+                ));
+            }
+
+            // TODO: Check consistency with client_consensus_types and chain_id?
+        }
+
+        Ok(())
+    }
+
     pub fn info(&self) -> InfoResponse {
         let state = self
             .state_modules
@@ -138,6 +464,7 @@ impl Context {
             consensus,
             client,
             client_bootstrap,
+            workers: self.supervisor.statuses(),
         }
     }
 
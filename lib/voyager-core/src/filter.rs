@@ -1,4 +1,8 @@
-use std::fmt::Debug;
+use std::{
+    collections::HashSet,
+    fmt::Debug,
+    sync::{Arc, LazyLock, Mutex, PoisonError, RwLock},
+};
 
 use anyhow::anyhow;
 use jaq_core::{
@@ -15,19 +19,65 @@ use voyager_vm::{
 
 use crate::VoyagerMessage;
 
+/// The interest filters of all loaded plugins, in the order they were configured.
+///
+/// This is a shared handle; the filters can be replaced with [`InterestFilters::replace`] while the queue is running.
 #[derive(Clone)]
 pub struct InterestFilters {
-    pub filters: Vec<(Filter<Native<Val>>, String)>,
+    filters: Arc<RwLock<Vec<(Filter<Native<Val>>, &'static str)>>>,
 }
 
 impl InterestFilters {
     pub fn new(filters: Vec<PluginInfo>) -> anyhow::Result<Self> {
         Ok(Self {
-            filters: filters
-                .into_iter()
-                .map(make_filter)
-                .collect::<anyhow::Result<_>>()?,
+            filters: Arc::new(RwLock::new(compile_filters(filters)?)),
+        })
+    }
+
+    /// Replace the currently loaded filters. If any of the new filters fail to compile, the current filters are left unchanged.
+    pub fn replace(&self, filters: Vec<PluginInfo>) -> anyhow::Result<()> {
+        let filters = compile_filters(filters)?;
+
+        *self.filters.write().unwrap_or_else(PoisonError::into_inner) = filters;
+
+        Ok(())
+    }
+
+    /// The names of all plugins that currently have a filter loaded.
+    pub fn plugin_names(&self) -> Vec<&'static str> {
+        self.filters
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(_, plugin_name)| *plugin_name)
+            .collect()
+    }
+}
+
+fn compile_filters(
+    filters: Vec<PluginInfo>,
+) -> anyhow::Result<Vec<(Filter<Native<Val>>, &'static str)>> {
+    filters
+        .into_iter()
+        .map(|plugin_info| {
+            make_filter(plugin_info).map(|(filter, plugin_name)| (filter, intern(plugin_name)))
         })
+        .collect()
+}
+
+/// The tags returned from [`InterestFilter::check_interest`] borrow from the filter, but the filters can be replaced at any time. Plugin names are therefore interned for the lifetime of the process; there are only ever as many of them as there are distinct plugins configured.
+fn intern(plugin_name: String) -> &'static str {
+    static PLUGIN_NAMES: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Default::default);
+
+    let mut plugin_names = PLUGIN_NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+
+    match plugin_names.get(&*plugin_name) {
+        Some(plugin_name) => plugin_name,
+        None => {
+            let plugin_name = Box::leak(plugin_name.into_boxed_str());
+            plugin_names.insert(plugin_name);
+            plugin_name
+        }
     }
 }
 
@@ -109,7 +159,9 @@ impl InterestFilter<VoyagerMessage> for InterestFilters {
 
         let mut tags = vec![];
 
-        for (filter, plugin_name) in &self.filters {
+        let filters = self.filters.read().unwrap_or_else(PoisonError::into_inner);
+
+        for &(ref filter, plugin_name) in &*filters {
             match run_filter(filter, plugin_name, msg_json.clone()) {
                 Ok(JaqFilterResult::Copy(tag)) => tags.push(tag),
                 Ok(JaqFilterResult::Take(tag)) => {
@@ -1,28 +1,28 @@
 #![feature(trait_alias, slice_partition_dedup)]
 
 use std::{
-    collections::{HashMap, VecDeque},
+    any::Any,
+    collections::{HashSet, VecDeque},
     net::{IpAddr, Ipv4Addr, SocketAddr},
     panic::AssertUnwindSafe,
-    sync::{Arc, OnceLock},
+    sync::{Arc, Mutex, PoisonError},
     time::Duration,
 };
 
 use anyhow::{anyhow, Context as _};
 use futures::{
-    future::{self, BoxFuture},
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    future::BoxFuture,
     stream::{self, FuturesUnordered},
     Future, FutureExt, StreamExt, TryFutureExt, TryStreamExt,
 };
 use itertools::Itertools;
 use jsonrpsee::core::middleware::{RpcServiceBuilder, RpcServiceT};
 use opentelemetry::{metrics::Counter, KeyValue};
-use serde::Serialize;
 use serde_json::Value;
+use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
-use tracing::{
-    debug, debug_span, error, info, info_span, instrument, trace, trace_span, warn, Instrument,
-};
+use tracing::{debug, error, info, info_span, instrument, trace, trace_span, Instrument};
 use unionlabs::{traits::Member, ErrorReporter};
 use voyager_message::{
     call::{
@@ -34,15 +34,11 @@ use voyager_message::{
     PluginMessage, VoyagerMessage,
 };
 use voyager_plugin_protocol::{
-    coordinator_server, worker_child_process, WithId, WorkerClient, INVALID_CONFIG_EXIT_CODE,
+    remote::remote_plugin_info, ExtractItemIdServiceLayer, WithId, INVALID_CONFIG_EXIT_CODE,
 };
 use voyager_primitives::{ClientInfo, IbcSpec, QueryHeight};
 use voyager_rpc::{
-    error_object_to_queue_error, json_rpc_error_to_queue_error, missing_state,
-    types::{
-        ClientBootstrapModuleInfo, ClientModuleInfo, FinalityModuleInfo, PluginInfo,
-        ProofModuleInfo, StateModuleInfo,
-    },
+    error_object_to_queue_error, json_rpc_error_to_queue_error, missing_state, types::PluginInfo,
     ClientModuleClient, PluginClient, VoyagerRpcServer,
 };
 use voyager_vm::{
@@ -50,35 +46,53 @@ use voyager_vm::{
     in_memory::InMemoryQueue,
     noop, now,
     pass::{Pass, PassResult},
-    seq, BoxDynError, HandlerFactory, ItemId, Op, Queue, QueueError,
+    promise, seq, BoxDynError, HandlerFactory, ItemId, Op, Queue, QueueError,
 };
 
 use crate::{
-    context::{Context, ModuleConfig, ModulesConfig, PluginConfig},
+    balance_monitor::{BalanceMonitor, BalanceMonitorConfig},
+    context::{ModulesConfig, PluginConfig, SharedContext},
     equivalent_chain_ids::EquivalentChainIds,
     filter::InterestFilters,
     ibc_spec_handlers::IbcSpecHandlers,
+    pause::{Paused, PAUSED_DEFER_SECONDS},
     server::Server,
+    workers::{Loaded, Workers},
 };
 
+pub mod api;
+pub mod balance_monitor;
 pub mod cache;
 pub mod context;
 pub mod equivalent_chain_ids;
 pub mod filter;
 pub mod ibc_spec_handlers;
+pub mod pause;
 pub mod server;
+mod supervisor;
+mod workers;
 
 pub struct Engine<Q: Queue<VoyagerMessage>> {
-    context: Arc<OnceLock<Context>>,
+    context: SharedContext,
+    workers: tokio::sync::Mutex<Workers>,
+    /// Notified whenever the plugin and module configuration is reloaded.
+    reloaded: Notify,
     interest_filters: InterestFilters,
     cache: cache::Cache,
     queue: Q,
+    /// The ops enqueued through the REST and RPC apis. The receiving end is taken by
+    /// [`Engine::run`].
+    queue_tx: UnboundedSender<Op<VoyagerMessage>>,
+    queue_rx: Mutex<Option<UnboundedReceiver<Op<VoyagerMessage>>>>,
     cancellation_token: CancellationToken,
     // NOTE: non-zero
     num_workers: usize,
     rest_laddr: SocketAddr,
     rpc_laddr: SocketAddr,
     optimizer_delay_milliseconds: u64,
+    admin_token: Option<String>,
+    paused: Paused,
+    balance_monitor: Option<BalanceMonitor>,
     // TODO: Make this generic
     rpc_middleware: LoggerMiddlewareLayer,
 }
@@ -98,17 +112,25 @@ impl Engine<InMemoryQueue<VoyagerMessage>> {
             rest_laddr: default_rest_laddr(),
             rpc_laddr: default_rpc_laddr(),
             optimizer_delay_milliseconds: default_optimizer_delay_milliseconds(),
+            admin_token: None,
+            balance_monitor_config: None,
             queue_config: (),
         }
     }
 }
 
-impl HandlerFactory<VoyagerMessage> for Server {
+struct Handlers {
+    server: Server,
+    paused: Paused,
+}
+
+impl HandlerFactory<VoyagerMessage> for Handlers {
     type Handler = Handler;
 
     fn make_handler(&self, item_id: ItemId) -> Self::Handler {
         Handler {
-            server: self.with_id(Some(item_id)),
+            server: self.server.with_id(Some(item_id)),
+            paused: self.paused.clone(),
         }
     }
 }
@@ -119,15 +141,117 @@ impl<Q: Queue<VoyagerMessage>> Engine<Q> {
     }
 
     pub fn server(&self) -> Server {
-        Server::new(self.cache.clone(), self.context.clone())
+        Server::new(
+            self.cache.clone(),
+            self.context.clone(),
+            self.queue_tx.clone(),
+        )
+    }
+
+    /// Reload the plugin and module configuration of this running instance.
+    ///
+    /// Only the plugins and modules that were added, removed, or had their configuration changed
+    /// are affected; all other workers, as well as the queue, are left untouched. If the new
+    /// configuration is invalid or any of the affected workers fail to start, the current
+    /// configuration remains loaded.
+    pub async fn reload(
+        &self,
+        plugin_configs: Vec<PluginConfig>,
+        module_configs: ModulesConfig,
+    ) -> anyhow::Result<()> {
+        let mut workers = self.workers.lock().await;
+
+        info!("reloading plugin and module configuration");
+
+        let Loaded { context, plugins } = workers.load(plugin_configs, module_configs).await?;
+
+        self.interest_filters.replace(plugins)?;
+        self.context.store(context);
+
+        self.reloaded.notify_one();
+
+        info!("reloaded plugin and module configuration");
+
+        Ok(())
+    }
+
+    fn optimizer_task(
+        &self,
+        plugin_name: &'static str,
+    ) -> BoxFuture<'_, Result<Result<(), BoxDynError>, Box<dyn Any + Send>>> {
+        info!(%plugin_name, "spawning optimizer");
+
+        Box::pin(
+            AssertUnwindSafe(
+                async move {
+                    loop {
+                        trace!("optimizing");
+
+                        // the plugin may have been unloaded by a reload, or paused via the admin api
+                        if self.paused.is_plugin_paused(plugin_name) {
+                            trace!("plugin is paused, skipping optimization");
+                        } else if let Some(Ok(plugin)) = self
+                            .context
+                            .load()
+                            .as_deref()
+                            .map(|context| context.plugin(plugin_name).cloned())
+                        {
+                            let pass = PluginOptPass::new(plugin.client());
+
+                            let res = self
+                                .queue
+                                .optimize(plugin_name, &self.interest_filters, &pass)
+                                .await
+                                .map_err(|e| {
+                                    e.map_either::<_, _, BoxDynError, BoxDynError>(
+                                        |x| Box::new(x),
+                                        |x| Box::new(x),
+                                    )
+                                    .into_inner()
+                                });
+
+                            if let Err(error) = res {
+                                error!(
+                                    error = %ErrorReporter(&*error),
+                                    "optimization pass returned with error"
+                                );
+                            }
+                        }
+
+                        tokio::time::sleep(std::time::Duration::from_millis(
+                            self.optimizer_delay_milliseconds,
+                        ))
+                        .await;
+                    }
+                }
+                .instrument(info_span!("optimize", %plugin_name)),
+            )
+            .catch_unwind(),
+        )
     }
 
     #[allow(clippy::too_many_lines)]
     pub fn run(&self) -> impl Future<Output = ()> + use<'_, Q> {
-        let queue_rx = api::run(&self.rest_laddr);
+        api::run(
+            &self.rest_laddr,
+            self.context.clone(),
+            self.queue.clone(),
+            self.paused.clone(),
+            self.admin_token.clone(),
+            self.queue_tx.clone(),
+        );
+
+        let queue_rx = self
+            .queue_rx
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .expect("engine is only run once");
 
         let mut tasks = FuturesUnordered::<BoxFuture<Result<Result<(), BoxDynError>, _>>>::new();
 
+        let mut optimizers = HashSet::new();
+
         {
             tasks.push(Box::pin(
                 AssertUnwindSafe(async {
@@ -137,7 +261,11 @@ impl<Q: Queue<VoyagerMessage>> Engine<Q> {
                                 .layer(tower_http::cors::CorsLayer::permissive()),
                         )
                         .set_rpc_middleware(
-                            RpcServiceBuilder::new().layer(self.rpc_middleware.clone()),
+                            // remote plugins thread item ids through their requests the same as
+                            // local plugins do through the coordinator server
+                            RpcServiceBuilder::new()
+                                .layer(ExtractItemIdServiceLayer)
+                                .layer(self.rpc_middleware.clone()),
                         )
                         .build(&self.rpc_laddr)
                         .await?;
@@ -186,7 +314,10 @@ impl<Q: Queue<VoyagerMessage>> Engine<Q> {
                 tasks.push(Box::pin(
                     AssertUnwindSafe(
                         voyager_vm::engine::Engine::new(
-                            self.server(),
+                            Handlers {
+                                server: self.server(),
+                                paused: self.paused.clone(),
+                            },
                             &self.queue,
                             &self.interest_filters,
                         )
@@ -212,52 +343,17 @@ impl<Q: Queue<VoyagerMessage>> Engine<Q> {
                 ));
             }
 
-            for (_filter, plugin_name) in &self.interest_filters.filters {
-                info!(%plugin_name, "spawning optimizer");
+            for plugin_name in self.interest_filters.plugin_names() {
+                optimizers.insert(plugin_name);
+                tasks.push(self.optimizer_task(plugin_name));
+            }
 
+            if let Some(balance_monitor) = &self.balance_monitor {
                 tasks.push(Box::pin(
                     AssertUnwindSafe(
-                        async {
-                            let plugin_name = plugin_name.clone();
-
-                            let pass = PluginOptPass::new(
-                                self.context
-                                    .get()
-                                    .unwrap()
-                                    .plugin(&plugin_name)
-                                    .expect("plugin exists")
-                                    .client(),
-                            );
-
-                            loop {
-                                trace!("optimizing");
-
-                                let res = self
-                                    .queue
-                                    .optimize(&plugin_name, &self.interest_filters, &pass)
-                                    .await
-                                    .map_err(|e| {
-                                        e.map_either::<_, _, BoxDynError, BoxDynError>(
-                                            |x| Box::new(x),
-                                            |x| Box::new(x),
-                                        )
-                                        .into_inner()
-                                    });
-
-                                if let Err(error) = res {
-                                    error!(
-                                        error = %ErrorReporter(&*error),
-                                        "optimization pass returned with error"
-                                    );
-                                }
-
-                                tokio::time::sleep(std::time::Duration::from_millis(
-                                    self.optimizer_delay_milliseconds,
-                                ))
-                                .await;
-                            }
-                        }
-                        .instrument(info_span!("optimize", %plugin_name)), // .instrument(trace_span!("optimize_verbose", filter = %filter.to_string())),
+                        balance_monitor
+                            .run()
+                            .instrument(info_span!("balance_monitor")),
                     )
                     .catch_unwind(),
                 ));
@@ -266,7 +362,26 @@ impl<Q: Queue<VoyagerMessage>> Engine<Q> {
 
         self.cancellation_token
             .run_until_cancelled(async move {
-                while let Some(res) = tasks.next().await {
+                loop {
+                    let res = tokio::select! {
+                        res = tasks.next() => res,
+                        () = self.reloaded.notified() => {
+                            // optimizers of plugins that were unloaded are left running, and will
+                            // resume optimizing if the plugin is loaded again
+                            for plugin_name in self.interest_filters.plugin_names() {
+                                if optimizers.insert(plugin_name) {
+                                    tasks.push(self.optimizer_task(plugin_name));
+                                }
+                            }
+
+                            continue;
+                        }
+                    };
+
+                    let Some(res) = res else {
+                        break;
+                    };
+
                     match res {
                         Ok(Ok(())) => {
                             info!("task exited gracefully");
@@ -303,6 +418,8 @@ pub struct EngineBuilder<Q: Queue<VoyagerMessage> = InMemoryQueue<VoyagerMessage
     rest_laddr: SocketAddr,
     rpc_laddr: SocketAddr,
     optimizer_delay_milliseconds: u64,
+    admin_token: Option<String>,
+    balance_monitor_config: Option<BalanceMonitorConfig>,
 }
 
 impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
@@ -370,6 +487,24 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
         }
     }
 
+    /// Enable the admin api on the REST server, authenticated with the provided bearer token.
+    pub fn with_admin_token(self, admin_token: Option<String>) -> Self {
+        Self {
+            admin_token,
+            ..self
+        }
+    }
+
+    pub fn with_balance_monitor(
+        self,
+        balance_monitor_config: Option<BalanceMonitorConfig>,
+    ) -> Self {
+        Self {
+            balance_monitor_config,
+            ..self
+        }
+    }
+
     pub fn register_ibc_spec_handler<S: IbcSpec>(mut self) -> Self {
         self.ibc_spec_handlers.register::<S>();
         self
@@ -392,6 +527,8 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
             rest_laddr: self.rest_laddr,
             rpc_laddr: self.rpc_laddr,
             optimizer_delay_milliseconds: self.optimizer_delay_milliseconds,
+            admin_token: self.admin_token,
+            balance_monitor_config: self.balance_monitor_config,
         }
     }
 }
@@ -402,385 +539,65 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
 
         let queue = Q::new(self.queue_config).await?;
 
-        let mut context_inner = Context {
-            state_modules: Default::default(),
-            proof_modules: Default::default(),
-            client_modules: Default::default(),
-            client_bootstrap_modules: Default::default(),
-            finality_modules: Default::default(),
-            chain_consensus_types: Default::default(),
-            client_consensus_types: Default::default(),
-            plugins: Default::default(),
-            equivalent_chain_ids: self.equivalent_chain_ids,
-            ibc_spec_handlers: self.ibc_spec_handlers,
-        };
-
         let logger_middleware_layer = LoggerMiddlewareLayer::new();
 
-        let context = Arc::new(OnceLock::new());
-
-        let mut interest_filters = HashMap::new();
+        let context = SharedContext::default();
 
         let cache = cache::Cache::new(self.cache_config);
 
-        info!("spawning {} plugins", self.plugin_configs.len());
-
-        stream::iter(self.plugin_configs.into_iter().enumerate())
-            .filter(|(_, plugin_config)| {
-                future::ready(if !plugin_config.enabled {
-                    info!(
-                        plugin_path = %plugin_config.path.to_string_lossy(),
-                        "plugin is not enabled, skipping"
-                    );
-                    false
-                } else {
-                    true
-                })
-            })
-            .zip(stream::repeat(Server::new(cache.clone(), context.clone())))
-            .then(async |((idx, plugin_config), server)| {
-                let plugin_info = info_span!("get_plugin_info", %idx)
-                    .in_scope(|| get_plugin_info(&plugin_config))?;
-
-                debug!("starting rpc server for plugin {}", plugin_info.name);
-
-                tokio::spawn(
-                    coordinator_server(&plugin_info.name, server, logger_middleware_layer.clone())
-                        .await?,
-                );
-
-                debug!("started rpc server for plugin {}", plugin_info.name);
-
-                Ok((idx, plugin_config, plugin_info))
-            })
-            .try_for_each_concurrent(
-                None,
-                |(
-                    idx,
-                    plugin_config,
-                    PluginInfo {
-                        name,
-                        interest_filter,
-                    },
-                )| {
-                    debug!("registering plugin {}", name);
-
-                    tokio::spawn(worker_child_process(
-                        name.clone(),
-                        plugin_config.path,
-                        cancellation_token.clone(),
-                        [plugin_config.config.to_string()]
-                            .into_iter()
-                            .chain(self.metrics_endpoint.clone()),
-                    ));
-
-                    let rpc_client = WorkerClient::new(&name, self.ipc_client_request_timeout);
-
-                    let prev = context_inner
-                        .plugins
-                        .insert(name.clone(), rpc_client.clone());
-
-                    if prev.is_some() {
-                        return future::ready(Err(anyhow!(
-                            "multiple plugins configured with name `{name}`"
-                        )));
-                    }
-
-                    info!("registered plugin {name}");
-
-                    interest_filters.insert((idx, name), interest_filter);
-
-                    future::ready(Ok(()))
-                },
-            )
-            .await?;
-
-        modules_startup(
-            self.module_configs.state,
-            logger_middleware_layer.clone(),
-            cancellation_token.clone(),
-            Server::new(cache.clone(), context.clone()),
-            self.ipc_client_request_timeout,
-            |info| info.id(),
-            |StateModuleInfo {
-                 chain_id,
-                 ibc_spec_id,
-             },
-             rpc_client| {
-                for equivalent_chain_id in context_inner
-                    .equivalent_chain_ids
-                    .equivalents(chain_id)
-                    .chain([chain_id])
-                {
-                    let prev = context_inner.state_modules.insert(
-                        (equivalent_chain_id.clone(), ibc_spec_id.clone()),
-                        rpc_client.clone(),
-                    );
-
-                    if prev.is_some() {
-                        return Err(anyhow!(
-                            "multiple state modules configured for chain id \
-                            `{equivalent_chain_id}` and IBC version `{ibc_spec_id}`",
-                        ));
-                    }
-                }
-
-                Ok(())
-            },
-            self.metrics_endpoint.clone(),
-        )
-        .await?;
-
-        modules_startup(
-            self.module_configs.proof,
-            logger_middleware_layer.clone(),
-            cancellation_token.clone(),
-            Server::new(cache.clone(), context.clone()),
-            self.ipc_client_request_timeout,
-            |info| info.id(),
-            |ProofModuleInfo {
-                 chain_id,
-                 ibc_spec_id,
-             },
-             rpc_client| {
-                for equivalent_chain_id in context_inner
-                    .equivalent_chain_ids
-                    .equivalents(chain_id)
-                    .chain([chain_id])
-                {
-                    let prev = context_inner.proof_modules.insert(
-                        (equivalent_chain_id.clone(), ibc_spec_id.clone()),
-                        rpc_client.clone(),
-                    );
-
-                    if prev.is_some() {
-                        return Err(anyhow!(
-                            "multiple proof modules configured for chain id \
-                            `{equivalent_chain_id}` and IBC version `{ibc_spec_id}`",
-                        ));
-                    }
-                }
-
-                Ok(())
-            },
-            self.metrics_endpoint.clone(),
-        )
-        .await?;
-
-        modules_startup(
-            self.module_configs.consensus,
-            logger_middleware_layer.clone(),
-            cancellation_token.clone(),
-            Server::new(cache.clone(), context.clone()),
-            self.ipc_client_request_timeout,
-            |info| info.id(),
-            |FinalityModuleInfo {
-                 chain_id,
-                 consensus_type,
-             },
-             rpc_client| {
-                for equivalent_chain_id in context_inner
-                    .equivalent_chain_ids
-                    .equivalents(chain_id)
-                    .chain([chain_id])
-                {
-                    let prev = context_inner
-                        .finality_modules
-                        .insert(equivalent_chain_id.clone(), rpc_client.clone());
-
-                    if prev.is_some() {
-                        return Err(anyhow!(
-                            "multiple consensus modules configured for chain id `{}`",
-                            equivalent_chain_id
-                        ));
-                    }
-
-                    let None = context_inner
-                        .chain_consensus_types
-                        .insert(equivalent_chain_id.clone(), consensus_type.clone())
-                    else {
-                        unreachable!()
-                    };
-                }
-
-                Ok(())
-            },
-            self.metrics_endpoint.clone(),
-        )
-        .await?;
+        let (queue_tx, queue_rx) = unbounded();
 
-        modules_startup(
-            self.module_configs.client,
+        let mut workers = Workers::new(
+            Server::new(cache.clone(), context.clone(), queue_tx.clone()),
             logger_middleware_layer.clone(),
             cancellation_token.clone(),
-            Server::new(cache.clone(), context.clone()),
             self.ipc_client_request_timeout,
-            |info| info.id(),
-            |ClientModuleInfo {
-                 client_type,
-                 consensus_type,
-                 ibc_interface,
-                 ibc_spec_id,
-             },
-             rpc_client| {
-                if !context_inner
-                    .ibc_spec_handlers
-                    .handlers
-                    .contains_key(ibc_spec_id)
-                {
-                    return Err(anyhow!(
-                        "IBC version `{ibc_spec_id}` is not supported in this build of voyager"
-                    ));
-                }
-
-                let prev = context_inner.client_modules.insert(
-                    (
-                        client_type.clone(),
-                        ibc_interface.clone(),
-                        ibc_spec_id.clone(),
-                    ),
-                    rpc_client.clone(),
-                );
-
-                if prev.is_some() {
-                    return Err(anyhow!(
-                        "multiple client modules configured for client \
-                        type `{client_type}`, IBC interface `{ibc_interface}`, \
-                        and IBC version `{ibc_spec_id}`",
-                    ));
-                }
-
-                if let Some(previous_consensus_type) = context_inner
-                    .client_consensus_types
-                    .insert(client_type.clone(), consensus_type.clone())
-                {
-                    if previous_consensus_type != consensus_type {
-                        return Err(anyhow!(
-                            "inconsistency in client consensus types: \
-                            client type `{client_type}` is registered \
-                            as tracking both `{previous_consensus_type}` \
-                            and `{consensus_type}`"
-                        ));
-                    }
-                }
-
-                Ok(())
-            },
-            self.metrics_endpoint.clone(),
-        )
-        .await?;
-
-        modules_startup(
-            self.module_configs.client_bootstrap,
-            logger_middleware_layer.clone(),
-            cancellation_token.clone(),
-            Server::new(cache.clone(), context.clone()),
-            self.ipc_client_request_timeout,
-            |info| info.id(),
-            |ClientBootstrapModuleInfo {
-                 client_type,
-                 chain_id,
-             },
-             rpc_client| {
-                for equivalent_chain_id in context_inner
-                    .equivalent_chain_ids
-                    .equivalents(chain_id)
-                    .chain([chain_id])
-                {
-                    let prev = context_inner.client_bootstrap_modules.insert(
-                        (equivalent_chain_id.clone(), client_type.clone()),
-                        rpc_client.clone(),
-                    );
-
-                    if prev.is_some() {
-                        return Err(anyhow!(
-                            "multiple client bootstrap modules configured for client \
-                            type `{client_type}` and chain id `{equivalent_chain_id}`",
-                        ));
-                    }
-
-                    // TODO: Check consistency with client_consensus_types and chain_id?
-
-                    // if let Some(previous_consensus_type) = modules
-                    //     .client_consensus_types
-                    //     .insert(client_type.clone(), consensus_type.clone())
-                    // {
-                    //     if previous_consensus_type != consensus_type {
-                    //         return Err(anyhow!(
-                    //             "inconsistency in client consensus types: \
-                    //             client type `{client_type}` is registered \
-                    //             as tracking both `{previous_consensus_type}` \
-                    //             and `{consensus_type}`"
-                    //         ));
-                    //     }
-                    // }
-                }
+            self.metrics_endpoint,
+            self.equivalent_chain_ids,
+            Arc::new(self.ibc_spec_handlers),
+        );
 
-                Ok(())
-            },
-            self.metrics_endpoint.clone(),
-        )
-        .await?;
-
-        info!("checking for plugin health...");
-
-        let futures = context_inner
-            .plugins
-            .iter()
-            .map(|(name, client)| async move {
-                match client
-                    .inner()
-                    .wait_until_connected(Duration::from_secs(10))
-                    .instrument(debug_span!("health check", %name))
-                    .await
-                {
-                    Ok(()) => {
-                        info!("plugin {name} connected")
-                    }
-                    Err(_) => {
-                        warn!("plugin {name} failed to connect after 10 seconds")
-                    }
-                }
-            })
-            .collect::<FuturesUnordered<_>>();
+        info!("spawning {} plugins", self.plugin_configs.len());
 
-        match cancellation_token
-            .run_until_cancelled(futures.collect::<Vec<_>>())
+        let Some(loaded) = cancellation_token
+            .run_until_cancelled(workers.load(self.plugin_configs, self.module_configs))
             .await
-        {
-            Some(_) => {}
-            None => return Err(anyhow!("startup error")),
-        }
+        else {
+            return Err(anyhow!("startup error"));
+        };
+
+        let Loaded {
+            context: context_inner,
+            plugins,
+        } = loaded?;
 
-        context
-            .set(context_inner)
-            .ok()
-            .expect("context is only set once");
+        context.store(context_inner);
 
         info!("started");
 
-        let interest_filters = InterestFilters::new(
-            interest_filters
-                .into_iter()
-                .sorted_unstable_by(|((a, _), _), ((b, _), _)| a.cmp(b))
-                .map(|((_, k), v)| (k, v))
-                .map(|(name, interest_filter)| PluginInfo {
-                    name,
-                    interest_filter,
-                })
-                .collect(),
-        )?;
+        let interest_filters = InterestFilters::new(plugins)?;
+
+        let balance_monitor = self
+            .balance_monitor_config
+            .map(|config| BalanceMonitor::new(config, context.clone()));
 
         Ok(Engine {
             interest_filters,
             cancellation_token,
             context,
+            workers: tokio::sync::Mutex::new(workers),
+            reloaded: Notify::new(),
             cache,
             queue,
+            queue_tx,
+            queue_rx: Mutex::new(Some(queue_rx)),
             num_workers: self.num_workers,
             rest_laddr: self.rest_laddr,
             rpc_laddr: self.rpc_laddr,
             optimizer_delay_milliseconds: self.optimizer_delay_milliseconds,
+            admin_token: self.admin_token,
+            paused: Paused::default(),
+            balance_monitor,
             rpc_middleware: logger_middleware_layer,
         })
     }
@@ -788,11 +605,37 @@ impl<Q: Queue<VoyagerMessage>> EngineBuilder<Q> {
 
 pub struct Handler {
     server: Server,
+    paused: Paused,
 }
 
 impl voyager_vm::Handler<VoyagerMessage> for Handler {
     #[instrument(skip_all)]
     async fn call(&self, call: Call) -> Result<Op<VoyagerMessage>, QueueError> {
+        let paused = match &call {
+            Call::Plugin(PluginMessage { plugin, .. }) => self.paused.is_plugin_paused(plugin),
+            Call::WaitForHeight(WaitForHeight { chain_id, .. })
+            | Call::WaitForTimestamp(WaitForTimestamp { chain_id, .. })
+            | Call::WaitForHeightRelative(WaitForHeightRelative { chain_id, .. })
+            | Call::WaitForTrustedHeight(WaitForTrustedHeight { chain_id, .. })
+            | Call::WaitForTrustedTimestamp(WaitForTrustedTimestamp { chain_id, .. })
+            | Call::WaitForClientUpdate(WaitForClientUpdate { chain_id, .. }) => {
+                self.paused.is_chain_paused(chain_id)
+            }
+            Call::Index(_)
+            | Call::IndexRange(_)
+            | Call::FetchUpdateHeaders(_)
+            | Call::SubmitTx(_) => false,
+        };
+
+        if paused {
+            debug!("processing is paused, deferring call");
+
+            return Ok(seq([
+                defer(now() + PAUSED_DEFER_SECONDS),
+                voyager_vm::call(call),
+            ]));
+        }
+
         match call {
             Call::Index(Index {
                 start_height,
@@ -1131,6 +974,22 @@ impl voyager_vm::Handler<VoyagerMessage> for Handler {
         callback: Callback,
         data: VecDeque<Data>,
     ) -> Result<Op<VoyagerMessage>, QueueError> {
+        let paused = match &callback {
+            Callback::AggregateSubmitTxFromOrderedHeaders(
+                AggregateSubmitTxFromOrderedHeaders { chain_id, .. },
+            ) => self.paused.is_chain_paused(chain_id),
+            Callback::Plugin(PluginMessage { plugin, .. }) => self.paused.is_plugin_paused(plugin),
+        };
+
+        if paused {
+            debug!("processing is paused, deferring callback");
+
+            return Ok(seq([
+                defer(now() + PAUSED_DEFER_SECONDS),
+                promise([], data, callback),
+            ]));
+        }
+
         match callback {
             Callback::AggregateSubmitTxFromOrderedHeaders(
                 AggregateSubmitTxFromOrderedHeaders {
@@ -1171,17 +1030,13 @@ impl voyager_vm::Handler<VoyagerMessage> for Handler {
                     .ok_or_else(missing_state("client not found", None))
                     .map_err(error_object_to_queue_error)?;
 
-                let client_module = self
-                    .server
-                    .context()
-                    .map_err(error_object_to_queue_error)?
+                let context = self.server.context().map_err(error_object_to_queue_error)?;
+
+                let client_module = context
                     .client_module(&client_type, &ibc_interface, &ibc_spec_id)?
                     .with_id(self.server.id());
 
-                let ibc_spec_handler = self
-                    .server
-                    .context()
-                    .map_err(error_object_to_queue_error)?
+                let ibc_spec_handler = context
                     .ibc_spec_handlers
                     .get(&ibc_spec_id)
                     .map_err(error_object_to_queue_error)?;
@@ -1236,18 +1091,26 @@ impl voyager_vm::Handler<VoyagerMessage> for Handler {
     }
 }
 
-pub fn get_plugin_info(plugin_config: &PluginConfig) -> anyhow::Result<PluginInfo> {
+pub async fn get_plugin_info(plugin_config: &PluginConfig) -> anyhow::Result<PluginInfo> {
     debug!(
         "querying plugin info from plugin at {}",
-        &plugin_config.path.to_string_lossy(),
+        plugin_config.location(),
     );
 
-    let mut cmd = std::process::Command::new(&plugin_config.path);
+    let plugin_config = match plugin_config {
+        PluginConfig::Local(plugin_config) => plugin_config,
+        PluginConfig::Remote(plugin_config) => {
+            return remote_plugin_info(&plugin_config.url, &plugin_config.auth()?).await;
+        }
+    };
+
+    let mut cmd = tokio::process::Command::new(&plugin_config.path);
     cmd.arg("info");
     cmd.arg(plugin_config.config.to_string());
 
     let output = cmd
         .output()
+        .await
         .with_context(|| format!("spawning plugin at {}", plugin_config.path.display()))?;
 
     if !output.status.success() {
@@ -1276,130 +1139,6 @@ pub fn get_plugin_info(plugin_config: &PluginConfig) -> anyhow::Result<PluginInf
     Ok(serde_json::from_slice(&output.stdout).unwrap())
 }
 
-#[allow(clippy::too_many_arguments)] // coward
-async fn modules_startup<Info: Serialize + Clone + Unpin + Send + 'static>(
-    configs: Vec<ModuleConfig<Info>>,
-    logger_middleware_layer: LoggerMiddlewareLayer,
-    cancellation_token: CancellationToken,
-    server: Server,
-    ipc_client_request_timeout: Duration,
-    id_f: fn(&Info) -> String,
-    mut push_f: impl FnMut(&Info, WorkerClient) -> anyhow::Result<()>,
-    metrics_endpoint: Option<String>,
-) -> anyhow::Result<()> {
-    stream::iter(configs)
-        .filter(|module_config| {
-            future::ready(if !module_config.enabled {
-                info!(
-                    module_path = %module_config.path.to_string_lossy(),
-                    "module is not enabled, skipping"
-                );
-                false
-            } else {
-                true
-            })
-        })
-        .zip(stream::repeat((
-            server.clone(),
-            logger_middleware_layer.clone(),
-        )))
-        .map::<anyhow::Result<_>, _>(anyhow::Result::Ok)
-        .try_filter_map(
-            |(module_config, (server, logger_middleware_layer))| async move {
-                if !module_config.enabled {
-                    info!(
-                        module_path = %module_config.path.to_string_lossy(),
-                        "module is not enabled, skipping"
-                    );
-                    anyhow::Result::Ok(None)
-                } else {
-                    debug!(
-                        "starting rpc server for module {}",
-                        id_f(&module_config.info)
-                    );
-                    tokio::spawn(
-                        coordinator_server(
-                            &id_f(&module_config.info),
-                            server,
-                            logger_middleware_layer,
-                        )
-                        .await?,
-                    );
-
-                    anyhow::Result::Ok(Some(module_config))
-                }
-            },
-        )
-        .try_collect::<FuturesUnordered<_>>()
-        .await?
-        .into_iter()
-        .try_for_each(|module_config| {
-            let id = id_f(&module_config.info);
-
-            debug!("registering module {}", id);
-
-            tokio::spawn(worker_child_process(
-                id.clone(),
-                module_config.path,
-                cancellation_token.clone(),
-                [
-                    module_config.config.to_string(),
-                    serde_json::to_string(&module_config.info).unwrap(),
-                ]
-                .into_iter()
-                .chain(metrics_endpoint.clone()),
-            ));
-
-            let rpc_client = WorkerClient::new(&id, ipc_client_request_timeout);
-
-            push_f(&module_config.info, rpc_client)?;
-
-            info!("registered module {id}");
-
-            Ok(())
-        })
-}
-
-pub mod api {
-    use std::net::SocketAddr;
-
-    use axum::{
-        extract::State,
-        http::StatusCode,
-        routing::{get, post},
-        Json,
-    };
-    use futures::{
-        channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
-        SinkExt,
-    };
-    use voyager_message::VoyagerMessage;
-    use voyager_vm::Op;
-
-    pub fn run(laddr: &SocketAddr) -> UnboundedReceiver<Op<VoyagerMessage>> {
-        let (queue_tx, queue_rx) = unbounded::<Op<VoyagerMessage>>();
-
-        let app = axum::Router::new()
-            .route("/enqueue", post(enqueue))
-            .route("/health", get(async || StatusCode::OK))
-            .with_state(queue_tx.clone());
-
-        tokio::spawn(axum::Server::bind(laddr).serve(app.into_make_service()));
-
-        queue_rx
-    }
-
-    // #[axum::debug_handler]
-    async fn enqueue(
-        State(mut sender): State<UnboundedSender<Op<VoyagerMessage>>>,
-        Json(op): Json<Op<VoyagerMessage>>,
-    ) -> StatusCode {
-        sender.send(op).await.expect("receiver should not close");
-
-        StatusCode::OK
-    }
-}
-
 pub struct PluginOptPass<T> {
     client: T,
 }
@@ -0,0 +1,86 @@
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+use voyager_primitives::ChainId;
+
+/// The chains and plugins that processing is currently paused for.
+///
+/// Items targeting a paused chain or plugin are not processed, but are deferred and checked again later (see [`PAUSED_DEFER_SECONDS`]), and paused plugins are not run as optimizers. Pausing is not persisted, and all processing is resumed when voyager is restarted.
+#[derive(Debug, Clone, Default)]
+pub struct Paused(Arc<RwLock<PausedState>>);
+
+/// How long items targeting a paused chain or plugin are deferred for before being checked again.
+pub const PAUSED_DEFER_SECONDS: u64 = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PausedState {
+    pub chains: BTreeSet<ChainId>,
+    pub plugins: BTreeSet<String>,
+}
+
+/// A chain or plugin to pause or resume processing for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum PauseTarget {
+    /// Pause all items that target this chain directly, as well as all plugins for this chain.
+    ///
+    /// Plugins are considered to be for a chain if their name ends with `/<chain_id>`, which is the convention followed by all plugins in this repository.
+    Chain(ChainId),
+    /// Pause all items for this plugin, as well as its optimizer.
+    Plugin(String),
+}
+
+impl Paused {
+    /// Pause processing for `target`, returning `false` if it was already paused.
+    pub fn pause(&self, target: PauseTarget) -> bool {
+        info!(?target, "pausing processing");
+
+        let mut state = self.0.write().unwrap_or_else(PoisonError::into_inner);
+
+        match target {
+            PauseTarget::Chain(chain_id) => state.chains.insert(chain_id),
+            PauseTarget::Plugin(plugin) => state.plugins.insert(plugin),
+        }
+    }
+
+    /// Resume processing for `target`, returning `false` if it was not paused.
+    pub fn resume(&self, target: &PauseTarget) -> bool {
+        info!(?target, "resuming processing");
+
+        let mut state = self.0.write().unwrap_or_else(PoisonError::into_inner);
+
+        match target {
+            PauseTarget::Chain(chain_id) => state.chains.remove(chain_id),
+            PauseTarget::Plugin(plugin) => state.plugins.remove(plugin),
+        }
+    }
+
+    pub fn state(&self) -> PausedState {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub fn is_chain_paused(&self, chain_id: &ChainId) -> bool {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .chains
+            .contains(chain_id)
+    }
+
+    pub fn is_plugin_paused(&self, plugin: &str) -> bool {
+        let state = self.0.read().unwrap_or_else(PoisonError::into_inner);
+
+        state.plugins.contains(plugin)
+            || plugin
+                .rsplit_once('/')
+                .is_some_and(|(_, chain_id)| state.chains.iter().any(|c| c.as_str() == chain_id))
+    }
+}
@@ -1,9 +1,9 @@
 // #![warn(clippy::unwrap_used)]
 
-use std::sync::{Arc, OnceLock};
+use std::sync::Arc;
 
 use anyhow::{anyhow, Context as _};
-use futures::TryFutureExt;
+use futures::{channel::mpsc::UnboundedSender, TryFutureExt};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
     types::{ErrorObject, ErrorObjectOwned},
@@ -28,17 +28,20 @@ use voyager_rpc::{
     RawProofModuleClient, RawStateModuleClient, VoyagerRpcServer, FATAL_JSONRPC_ERROR_CODE,
 };
 use voyager_types::{IbcProof, RawClientId};
-use voyager_vm::ItemId;
+use voyager_vm::{ItemId, Op};
 
 use crate::{
     cache::{ClientInfoRequest, StateRequest},
-    context::Context,
+    context::{Context, SharedContext},
+    VoyagerMessage,
 };
 
 #[derive(Clone)]
 pub struct Server {
-    context: Arc<OnceLock<Context>>,
+    context: SharedContext,
     cache: crate::cache::Cache,
+    /// The sending end of the ops enqueued with [`VoyagerRpcServer::enqueue`].
+    queue_tx: UnboundedSender<Op<VoyagerMessage>>,
     item_id: Option<ItemId>,
     server_metrics: ServerMetrics,
 }
@@ -63,10 +66,15 @@ impl ServerMetrics {
 }
 
 impl Server {
-    pub fn new(cache: crate::cache::Cache, context: Arc<OnceLock<Context>>) -> Self {
+    pub fn new(
+        cache: crate::cache::Cache,
+        context: SharedContext,
+        queue_tx: UnboundedSender<Op<VoyagerMessage>>,
+    ) -> Self {
         Server {
             context,
             cache,
+            queue_tx,
             item_id: None,
             server_metrics: ServerMetrics::new(),
         }
@@ -81,6 +89,7 @@ impl Server {
         Server {
             context: self.context.clone(),
             cache: self.cache.clone(),
+            queue_tx: self.queue_tx.clone(),
             item_id,
             server_metrics: self.server_metrics.clone(),
         }
@@ -93,10 +102,10 @@ impl Server {
         }
     }
 
-    /// Returns the contained context, if it has been loaded.
-    pub fn context(&self) -> RpcResult<&Context> {
+    /// Returns a snapshot of the current context, if it has been loaded.
+    pub fn context(&self) -> RpcResult<Arc<Context>> {
         self.context
-            .get()
+            .load()
             .ok_or_else(|| ErrorObject::owned(-2, "server has not started", None::<()>))
     }
 
@@ -299,8 +308,7 @@ impl Server {
                     return Ok(None);
                 };
 
-                let ibc_spec_handler = self
-                    .context()?
+                let ibc_spec_handler = context
                     .ibc_spec_handlers
                     .handlers
                     .get(ibc_spec_id)
@@ -393,8 +401,7 @@ impl Server {
                     return Ok(None);
                 };
 
-                let ibc_spec_handler = self
-                    .context()?
+                let ibc_spec_handler = context
                     .ibc_spec_handlers
                     .handlers
                     .get(ibc_spec_id)
@@ -481,8 +488,9 @@ impl Server {
             .in_scope(|| async {
                 trace!("query");
 
-                let state_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let state_module = context
                     .state_module(&chain_id, &ibc_spec_id)?
                     .with_id(self.item_id);
 
@@ -513,8 +521,9 @@ impl Server {
 
                 let height = self.query_height(&chain_id, height).await?;
 
-                let state_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let state_module = context
                     .state_module(&chain_id, &ibc_spec_id)?
                     .with_id(self.item_id);
 
@@ -565,8 +574,9 @@ impl Server {
 
                 debug!("fetching ibc proof");
 
-                let proof_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let proof_module = context
                     .proof_module(&chain_id, &ibc_spec_id)?
                     .with_id(self.item_id);
 
@@ -602,8 +612,9 @@ impl Server {
             .in_scope(|| async {
                 trace!("fetching ibc state");
 
-                let state_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let state_module = context
                     .state_module(chain_id, &P::Spec::ID)?
                     .with_id(self.item_id);
 
@@ -650,8 +661,9 @@ impl Server {
             .in_scope(|| async {
                 trace!("fetching ibc state");
 
-                let proof_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let proof_module = context
                     .proof_module(chain_id, &P::Spec::ID)?
                     .with_id(self.item_id);
 
@@ -688,8 +700,9 @@ impl Server {
             .in_scope(|| async {
                 trace!("querying self client state");
 
-                let client_bootstrap_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let client_bootstrap_module = context
                     .client_bootstrap_module(&chain_id, &client_type)?
                     .with_id(self.item_id);
 
@@ -718,8 +731,9 @@ impl Server {
             .in_scope(|| async {
                 trace!("querying self consensus state");
 
-                let client_bootstrap_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let client_bootstrap_module = context
                     .client_bootstrap_module(&chain_id, &client_type)?
                     .with_id(self.item_id);
 
@@ -751,8 +765,9 @@ impl Server {
             .in_scope(|| async {
                 trace!("encoding proof");
 
-                let client_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let client_module = context
                     .client_module(client_type, ibc_interface, ibc_spec_id)?
                     .with_id(self.item_id);
 
@@ -781,8 +796,9 @@ impl Server {
             .in_scope(|| async {
                 trace!("encoding header");
 
-                let client_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let client_module = context
                     .client_module(client_type, ibc_interface, ibc_spec_id)?
                     .with_id(self.item_id);
 
@@ -798,6 +814,37 @@ impl Server {
             .await
     }
 
+    // TODO: Use valuable here
+    #[instrument(skip_all, fields(%client_type, %ibc_interface, %ibc_spec_id, %misbehaviour))]
+    pub async fn encode_misbehaviour(
+        &self,
+        client_type: &ClientType,
+        ibc_interface: &IbcInterface,
+        ibc_spec_id: &IbcSpecId,
+        misbehaviour: Value,
+    ) -> RpcResult<Bytes> {
+        self.span()
+            .in_scope(|| async {
+                trace!("encoding misbehaviour");
+
+                let context = self.context()?;
+
+                let client_module = context
+                    .client_module(client_type, ibc_interface, ibc_spec_id)?
+                    .with_id(self.item_id);
+
+                let misbehaviour = client_module
+                    .encode_misbehaviour(misbehaviour)
+                    .await
+                    .map_err(json_rpc_error_to_error_object)?;
+
+                trace!(%misbehaviour, "encoded misbehaviour");
+
+                Ok(misbehaviour)
+            })
+            .await
+    }
+
     // TODO: Use valuable here
     #[instrument(skip_all, fields(%client_type, %ibc_interface, %ibc_spec_id))]
     pub async fn decode_client_state_meta(
@@ -811,8 +858,9 @@ impl Server {
             .in_scope(|| async {
                 trace!("decoding client state meta");
 
-                let client_module = self
-                    .context()?
+                let context = self.context()?;
+
+                let client_module = context
                     .client_module(client_type, ibc_interface, ibc_spec_id)?
                     .with_id(self.item_id);
 
@@ -1113,6 +1161,19 @@ impl VoyagerRpcServer for Server {
             .await
     }
 
+    async fn encode_misbehaviour(
+        &self,
+        e: &Extensions,
+        client_type: ClientType,
+        ibc_interface: IbcInterface,
+        ibc_spec_id: IbcSpecId,
+        misbehaviour: Value,
+    ) -> RpcResult<Bytes> {
+        self.with_id(e.try_get().ok().cloned())
+            .encode_misbehaviour(&client_type, &ibc_interface, &ibc_spec_id, misbehaviour)
+            .await
+    }
+
     // TODO: Use valuable here
     async fn decode_client_state_meta(
         &self,
@@ -1206,6 +1267,16 @@ impl VoyagerRpcServer for Server {
         .await
         .map_err(json_rpc_error_to_error_object)
     }
+
+    // =====
+    // QUEUE
+    // =====
+
+    async fn enqueue(&self, _: &Extensions, op: Op<VoyagerMessage>) -> RpcResult<()> {
+        self.queue_tx
+            .unbounded_send(op)
+            .map_err(|_| ErrorObject::owned(-2, "queue has been shut down", None::<()>))
+    }
 }
 
 pub(crate) fn fatal_error(t: impl core::error::Error) -> ErrorObjectOwned {
@@ -0,0 +1,326 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    process::ExitStatus,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc, PoisonError, RwLock,
+    },
+    time::Duration,
+};
+
+use opentelemetry::{
+    metrics::{Counter, Gauge},
+    KeyValue,
+};
+use tokio::{sync::Notify, time::sleep};
+use tracing::{debug, warn};
+use voyager_plugin_protocol::{WorkerClient, WorkerMonitor};
+use voyager_rpc::types::{WorkerState, WorkerStatus};
+
+/// How often the connection to each worker is checked.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The number of consecutive failed health checks after which a running worker is considered
+/// unresponsive and is restarted.
+const UNHEALTHY_THRESHOLD: u32 = 3;
+
+/// Tracks the status of all running plugin and module workers.
+///
+/// Each worker is registered with [`Supervisor::register`] when it is started, and deregistered
+/// with [`Supervisor::deregister`] once it has been stopped. Crashed subprocesses are respawned by
+/// [`voyager_plugin_protocol::worker_child_process`]; the supervisor additionally restarts
+/// subprocesses that are running but have stopped responding (see [`Supervised::health_check`]).
+#[derive(Clone)]
+pub(crate) struct Supervisor {
+    workers: Arc<RwLock<BTreeMap<String, Arc<Supervised>>>>,
+    metrics: SupervisorMetrics,
+}
+
+#[derive(Clone)]
+struct SupervisorMetrics {
+    up_gauge: Gauge<u64>,
+    restarts_counter: Counter<u64>,
+}
+
+impl SupervisorMetrics {
+    fn new() -> Self {
+        Self {
+            up_gauge: opentelemetry::global::meter("voyager")
+                .u64_gauge("worker.up")
+                .build(),
+            restarts_counter: opentelemetry::global::meter("voyager")
+                .u64_counter("worker.restarts")
+                .build(),
+        }
+    }
+}
+
+impl Supervisor {
+    pub(crate) fn new() -> Self {
+        Self {
+            workers: Default::default(),
+            metrics: SupervisorMetrics::new(),
+        }
+    }
+
+    /// Register a new worker subprocess, replacing any previous worker with the same name.
+    pub(crate) fn register(&self, name: &str, rpc_client: WorkerClient) -> Arc<Supervised> {
+        self.insert(name, rpc_client, false)
+    }
+
+    /// Register a new remote worker, replacing any previous worker with the same name.
+    ///
+    /// Remote workers are always considered to be running, and are never restarted.
+    pub(crate) fn register_remote(&self, name: &str, rpc_client: WorkerClient) -> Arc<Supervised> {
+        self.insert(name, rpc_client, true)
+    }
+
+    fn insert(&self, name: &str, rpc_client: WorkerClient, remote: bool) -> Arc<Supervised> {
+        let supervised = Arc::new(Supervised {
+            name: name.to_owned(),
+            rpc_client,
+            metrics: self.metrics.clone(),
+            remote,
+            running: AtomicBool::new(remote),
+            healthy: AtomicBool::new(false),
+            pid: AtomicU32::new(0),
+            spawns: AtomicU64::new(0),
+            restart: Notify::new(),
+        });
+
+        self.workers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name.to_owned(), supervised.clone());
+
+        supervised
+    }
+
+    pub(crate) fn deregister(&self, name: &str) {
+        self.workers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(name);
+
+        self.metrics
+            .up_gauge
+            .record(0, &[KeyValue::new("worker", name.to_owned())]);
+    }
+
+    /// The status of all registered workers, ordered by name.
+    pub(crate) fn statuses(&self) -> Vec<WorkerStatus> {
+        self.workers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .map(|supervised| supervised.status())
+            .collect()
+    }
+}
+
+/// The supervision state of a single worker.
+pub(crate) struct Supervised {
+    name: String,
+    rpc_client: WorkerClient,
+    metrics: SupervisorMetrics,
+    remote: bool,
+
+    running: AtomicBool,
+    healthy: AtomicBool,
+    /// The pid of the running subprocess, or 0 if there is none.
+    pid: AtomicU32,
+    spawns: AtomicU64,
+
+    /// Notified when the running subprocess should be restarted. Only [`Notify::notify_waiters`]
+    /// is used, so that a restart requested while no subprocess is running (e.g. right as it
+    /// exited) is not stored and does not kill the next subprocess as soon as it is spawned.
+    restart: Notify,
+}
+
+impl Supervised {
+    fn attributes(&self) -> [KeyValue; 1] {
+        [KeyValue::new("worker", self.name.clone())]
+    }
+
+    fn status(&self) -> WorkerStatus {
+        let state = match (
+            self.running.load(Ordering::SeqCst),
+            self.healthy.load(Ordering::SeqCst),
+        ) {
+            (true, true) => WorkerState::Up,
+            (true, false) => WorkerState::Unhealthy,
+            (false, _) => WorkerState::Down,
+        };
+
+        WorkerStatus {
+            name: self.name.clone(),
+            state,
+            pid: match (state, self.pid.load(Ordering::SeqCst)) {
+                (WorkerState::Down, _) | (_, 0) => None,
+                (_, pid) => Some(pid),
+            },
+            restarts: self.spawns.load(Ordering::SeqCst).saturating_sub(1),
+        }
+    }
+
+    /// Periodically check the connection to the worker, requesting a restart if it has been
+    /// unhealthy for [`UNHEALTHY_THRESHOLD`] consecutive checks while the subprocess is running.
+    ///
+    /// Remote workers are only checked for connectivity, since their subprocess is not managed by
+    /// voyager.
+    ///
+    /// This never returns, and is expected to be cancelled when the worker is stopped.
+    pub(crate) async fn health_check(&self) {
+        let mut failed_checks = 0;
+
+        loop {
+            sleep(HEALTH_CHECK_INTERVAL).await;
+
+            let healthy = self.rpc_client.inner().is_healthy();
+
+            self.healthy.store(healthy, Ordering::SeqCst);
+
+            if self.remote {
+                self.metrics
+                    .up_gauge
+                    .record(u64::from(healthy), &self.attributes());
+                continue;
+            }
+
+            if healthy || !self.running.load(Ordering::SeqCst) {
+                failed_checks = 0;
+                continue;
+            }
+
+            failed_checks += 1;
+
+            debug!(
+                worker = %self.name,
+                %failed_checks,
+                "worker failed health check"
+            );
+
+            if failed_checks >= UNHEALTHY_THRESHOLD {
+                warn!(
+                    worker = %self.name,
+                    "worker has been unresponsive for {failed_checks} consecutive health checks, restarting"
+                );
+
+                self.restart.notify_waiters();
+
+                failed_checks = 0;
+            }
+        }
+    }
+}
+
+impl WorkerMonitor for Supervised {
+    fn spawned(&self, pid: u32) {
+        self.pid.store(pid, Ordering::SeqCst);
+        self.running.store(true, Ordering::SeqCst);
+
+        if self.spawns.fetch_add(1, Ordering::SeqCst) > 0 {
+            self.metrics.restarts_counter.add(1, &self.attributes());
+        }
+
+        self.metrics.up_gauge.record(1, &self.attributes());
+    }
+
+    fn exited(&self, _: Option<ExitStatus>) {
+        self.running.store(false, Ordering::SeqCst);
+        self.healthy.store(false, Ordering::SeqCst);
+
+        self.metrics.up_gauge.record(0, &self.attributes());
+    }
+
+    fn restart_requested(&self) -> impl Future<Output = ()> + Send {
+        self.restart.notified()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use super::*;
+
+    fn status(state: WorkerState, pid: Option<u32>, restarts: u64) -> WorkerStatus {
+        WorkerStatus {
+            name: "worker".to_owned(),
+            state,
+            pid,
+            restarts,
+        }
+    }
+
+    fn register(supervisor: &Supervisor) -> Arc<Supervised> {
+        supervisor.register(
+            "worker",
+            WorkerClient::new("worker", Duration::from_secs(1)),
+        )
+    }
+
+    #[tokio::test]
+    async fn status() {
+        let supervisor = Supervisor::new();
+        let supervised = register(&supervisor);
+
+        assert_eq!(supervisor.statuses(), [status(WorkerState::Down, None, 0)]);
+
+        supervised.spawned(1);
+        assert_eq!(
+            supervisor.statuses(),
+            [status(WorkerState::Unhealthy, Some(1), 0)]
+        );
+
+        supervised.healthy.store(true, Ordering::SeqCst);
+        assert_eq!(supervisor.statuses(), [status(WorkerState::Up, Some(1), 0)]);
+
+        supervised.exited(None);
+        assert_eq!(supervisor.statuses(), [status(WorkerState::Down, None, 0)]);
+
+        supervised.spawned(2);
+        assert_eq!(
+            supervisor.statuses(),
+            [status(WorkerState::Unhealthy, Some(2), 1)]
+        );
+
+        supervisor.deregister("worker");
+        assert!(supervisor.statuses().is_empty());
+    }
+
+    #[tokio::test]
+    async fn remote_status() {
+        let supervisor = Supervisor::new();
+        let supervised = supervisor.register_remote(
+            "worker",
+            WorkerClient::new("worker", Duration::from_secs(1)),
+        );
+
+        assert_eq!(
+            supervisor.statuses(),
+            [status(WorkerState::Unhealthy, None, 0)]
+        );
+
+        supervised.healthy.store(true, Ordering::SeqCst);
+        assert_eq!(supervisor.statuses(), [status(WorkerState::Up, None, 0)]);
+    }
+
+    #[tokio::test]
+    async fn restart_is_not_stored() {
+        let supervisor = Supervisor::new();
+        let supervised = register(&supervisor);
+
+        // requested while no subprocess is waiting for it
+        supervised.restart.notify_waiters();
+
+        assert!(supervised.restart_requested().now_or_never().is_none());
+
+        let restart_requested = supervised.restart_requested();
+
+        supervised.restart.notify_waiters();
+
+        assert!(restart_requested.now_or_never().is_some());
+    }
+}
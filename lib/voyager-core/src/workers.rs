@@ -0,0 +1,949 @@
+use std::{collections::HashMap, mem, path::PathBuf, sync::Arc, time::Duration};
+
+use anyhow::anyhow;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use serde::Serialize;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, debug_span, error, info, info_span, warn, Instrument};
+use unionlabs::ErrorReporter;
+use voyager_plugin_protocol::{coordinator_server, worker_child_process, WorkerClient};
+use voyager_rpc::types::{
+    ClientBootstrapModuleInfo, ClientModuleInfo, FinalityModuleInfo, PluginInfo, ProofModuleInfo,
+    StateModuleInfo,
+};
+
+use crate::{
+    context::{Context, ModuleConfig, ModulesConfig, PluginConfig},
+    equivalent_chain_ids::EquivalentChainIds,
+    filter::make_filter,
+    get_plugin_info,
+    ibc_spec_handlers::IbcSpecHandlers,
+    server::Server,
+    supervisor::Supervisor,
+    LoggerMiddlewareLayer,
+};
+
+/// How long to wait for a newly started worker to connect before considering it started.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The plugin and module subprocesses of a running voyager instance.
+///
+/// [`Workers::load`] diffs the provided configuration against the currently running workers, only
+/// starting, stopping, or restarting the workers that have changed.
+pub(crate) struct Workers {
+    spawner: Spawner,
+
+    equivalent_chain_ids: EquivalentChainIds,
+    ibc_spec_handlers: Arc<IbcSpecHandlers>,
+
+    plugins: HashMap<String, Running<PluginSpec>>,
+    state_modules: HashMap<String, Running<ModuleSpec<StateModuleInfo>>>,
+    proof_modules: HashMap<String, Running<ModuleSpec<ProofModuleInfo>>>,
+    finality_modules: HashMap<String, Running<ModuleSpec<FinalityModuleInfo>>>,
+    client_modules: HashMap<String, Running<ModuleSpec<ClientModuleInfo>>>,
+    client_bootstrap_modules: HashMap<String, Running<ModuleSpec<ClientBootstrapModuleInfo>>>,
+}
+
+/// The output of [`Workers::load`].
+pub(crate) struct Loaded {
+    /// A context containing all of the loaded plugins and modules.
+    pub(crate) context: Context,
+    /// The info of all loaded plugins, in the order they are configured.
+    pub(crate) plugins: Vec<PluginInfo>,
+}
+
+/// A configured plugin or module.
+trait Spec {
+    /// The name of the worker, used as the name of its subprocess and rpc server.
+    fn name(&self) -> String;
+
+    /// Whether a worker running with the configuration of `running` can be left running as-is for
+    /// this configuration.
+    fn unchanged(&self, running: &Self) -> bool;
+
+    fn rpc_client(&self) -> &WorkerClient;
+
+    fn launch(&self) -> Launch;
+}
+
+struct PluginSpec {
+    config: PluginConfig,
+    info: PluginInfo,
+    rpc_client: WorkerClient,
+}
+
+impl Spec for PluginSpec {
+    fn name(&self) -> String {
+        self.info.name.clone()
+    }
+
+    fn unchanged(&self, running: &Self) -> bool {
+        self.config == running.config
+    }
+
+    fn rpc_client(&self) -> &WorkerClient {
+        &self.rpc_client
+    }
+
+    fn launch(&self) -> Launch {
+        match &self.config {
+            PluginConfig::Local(local) => Launch::Spawn {
+                path: local.path.clone(),
+                args: vec![local.config.to_string()],
+            },
+            PluginConfig::Remote(_) => Launch::Connect,
+        }
+    }
+}
+
+struct ModuleSpec<Info> {
+    config: ModuleConfig<Info>,
+    rpc_client: WorkerClient,
+}
+
+impl<Info: ModuleInfo> Spec for ModuleSpec<Info> {
+    fn name(&self) -> String {
+        self.config.info.id()
+    }
+
+    fn unchanged(&self, running: &Self) -> bool {
+        self.config == running.config
+    }
+
+    fn rpc_client(&self) -> &WorkerClient {
+        &self.rpc_client
+    }
+
+    fn launch(&self) -> Launch {
+        Launch::Spawn {
+            path: self.config.path.clone(),
+            args: vec![
+                self.config.config.to_string(),
+                serde_json::to_string(&self.config.info).unwrap(),
+            ],
+        }
+    }
+}
+
+/// How a worker is started by the [`Spawner`].
+enum Launch {
+    /// Spawn and supervise a subprocess with the provided args.
+    Spawn { path: PathBuf, args: Vec<String> },
+    /// Connect to a remote worker.
+    Connect,
+}
+
+struct Running<S> {
+    spec: S,
+    worker: Worker,
+}
+
+/// A handle to a worker, started with [`Spawner::launch`].
+struct Worker {
+    stop: CancellationToken,
+    /// Cancelled if the worker exits on its own, which only happens if it was started with an
+    /// invalid config.
+    failed: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+impl Worker {
+    /// Stop the worker, waiting for the subprocess to exit.
+    async fn stop(self) {
+        self.stop.cancel();
+
+        let _ = self.handle.await;
+    }
+
+    /// Wait for a newly started worker to come up, returning an error if it exits with an invalid
+    /// config before it connects.
+    ///
+    /// A worker that has not connected after [`STARTUP_TIMEOUT`] is considered started.
+    async fn started(&self, name: &str, rpc_client: &WorkerClient) -> anyhow::Result<()> {
+        tokio::select! {
+            _ = self.failed.cancelled() => {
+                Err(anyhow!("worker {name} exited with an invalid config"))
+            }
+            res = rpc_client
+                .inner()
+                .wait_until_connected(STARTUP_TIMEOUT)
+                .instrument(debug_span!("health check", %name)) => {
+                match res {
+                    Ok(()) => info!("worker {name} connected"),
+                    Err(_) => warn!(
+                        "worker {name} failed to connect after {} seconds",
+                        STARTUP_TIMEOUT.as_secs()
+                    ),
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Shut down voyager if the worker exits with an invalid config from now on.
+    ///
+    /// Until a worker is committed, such an exit is only reported through [`Worker::started`], so
+    /// that a worker failing to start on a reload does not take down the whole relayer.
+    fn commit(&self, cancellation_token: CancellationToken) {
+        let stop = self.stop.clone();
+        let failed = self.failed.clone();
+
+        tokio::spawn(async move {
+            if stop.run_until_cancelled(failed.cancelled()).await.is_some() {
+                cancellation_token.cancel();
+            }
+        });
+    }
+}
+
+struct Spawner {
+    server: Server,
+    logger_middleware_layer: LoggerMiddlewareLayer,
+    cancellation_token: CancellationToken,
+    ipc_client_request_timeout: Duration,
+    metrics_endpoint: Option<String>,
+    supervisor: Supervisor,
+
+    /// The rpc clients of all workers that have been started at least once.
+    ///
+    /// The coordinator server for a worker (and the client connecting to it) is kept around after
+    /// the worker is stopped, since a worker with the same name may be started again on a later
+    /// reload.
+    rpc_clients: HashMap<String, WorkerClient>,
+}
+
+impl Spawner {
+    fn rpc_client(&self, name: &str) -> WorkerClient {
+        self.rpc_clients
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| WorkerClient::new(name, self.ipc_client_request_timeout))
+    }
+
+    async fn launch<S: Spec>(&mut self, spec: &S) -> anyhow::Result<Worker> {
+        let name = spec.name();
+
+        match spec.launch() {
+            Launch::Spawn { path, args } => {
+                self.start(&name, path, args, spec.rpc_client().clone())
+                    .await
+            }
+            Launch::Connect => Ok(self.connect(&name, spec.rpc_client().clone())),
+        }
+    }
+
+    async fn start(
+        &mut self,
+        name: &str,
+        path: PathBuf,
+        args: Vec<String>,
+        rpc_client: WorkerClient,
+    ) -> anyhow::Result<Worker> {
+        if !self.rpc_clients.contains_key(name) {
+            debug!("starting rpc server for {name}");
+
+            tokio::spawn(
+                coordinator_server(
+                    name,
+                    self.server.clone(),
+                    self.logger_middleware_layer.clone(),
+                )
+                .await?,
+            );
+
+            debug!("started rpc server for {name}");
+
+            self.rpc_clients.insert(name.to_owned(), rpc_client.clone());
+        }
+
+        let supervised = self.supervisor.register(name, rpc_client);
+
+        let stop = self.cancellation_token.child_token();
+        let failed = CancellationToken::new();
+
+        let handle = tokio::spawn({
+            let name = name.to_owned();
+            let worker_cancellation_token = stop.child_token();
+            let stop = stop.clone();
+            let failed = failed.clone();
+            let supervisor = self.supervisor.clone();
+            let args = args.into_iter().chain(self.metrics_endpoint.clone());
+
+            async move {
+                tokio::select! {
+                    _ = worker_child_process(
+                        name.clone(),
+                        path,
+                        worker_cancellation_token,
+                        args,
+                        &*supervised,
+                    ) => {}
+                    _ = supervised.health_check() => {}
+                }
+
+                supervisor.deregister(&name);
+
+                // the worker only cancels its own token if it exited with an invalid config
+                if !stop.is_cancelled() {
+                    failed.cancel();
+                }
+            }
+        });
+
+        Ok(Worker {
+            stop,
+            failed,
+            handle,
+        })
+    }
+
+    /// Supervise the connection to a remote worker.
+    ///
+    /// The lifecycle of a remote worker is not managed by voyager, so the connection is only
+    /// health checked; stopping the returned worker closes the connection.
+    fn connect(&self, name: &str, rpc_client: WorkerClient) -> Worker {
+        let supervised = self.supervisor.register_remote(name, rpc_client.clone());
+
+        let stop = self.cancellation_token.child_token();
+
+        let handle = tokio::spawn({
+            let name = name.to_owned();
+            let stop = stop.clone();
+            let supervisor = self.supervisor.clone();
+
+            async move {
+                stop.run_until_cancelled(supervised.health_check()).await;
+
+                rpc_client.inner().shutdown();
+
+                supervisor.deregister(&name);
+            }
+        });
+
+        Worker {
+            stop,
+            failed: CancellationToken::new(),
+            handle,
+        }
+    }
+}
+
+/// The workers of one kind as (re)started by [`Workers::load`], before the load is either
+/// committed or rolled back.
+struct Staged<S> {
+    /// The workers to run once the load is committed.
+    running: HashMap<String, Running<S>>,
+    /// The names of the workers in `running` that were (re)started by this load.
+    started: Vec<String>,
+    /// The previous configuration of the workers that were restarted by this load, which they are
+    /// restarted with again if the load is rolled back.
+    replaced: Vec<S>,
+}
+
+impl<S> Default for Staged<S> {
+    fn default() -> Self {
+        Self {
+            running: HashMap::new(),
+            started: vec![],
+            replaced: vec![],
+        }
+    }
+}
+
+impl<S: Spec> Staged<S> {
+    /// Diff the configured workers against the `running` workers, moving them into this stage and
+    /// (re)starting them as necessary.
+    ///
+    /// The workers that are no longer configured are left in `running`, and are only stopped once
+    /// the load is committed. If a worker fails to start, the workers staged so far are kept, so
+    /// that the load can be rolled back.
+    async fn stage(
+        &mut self,
+        spawner: &mut Spawner,
+        running: &mut HashMap<String, Running<S>>,
+        specs: Vec<S>,
+    ) -> anyhow::Result<()> {
+        for spec in specs {
+            let name = spec.name();
+
+            match diff(running, &name, |running| spec.unchanged(&running.spec)) {
+                Diff::Unchanged(running) => {
+                    self.running.insert(name, running);
+                }
+                change => {
+                    if let Diff::Changed(running) = change {
+                        info!("restarting {name}");
+                        running.worker.stop().await;
+                        self.replaced.push(running.spec);
+                    }
+
+                    debug!("starting {name}");
+
+                    let worker = spawner.launch(&spec).await?;
+
+                    info!("started {name}");
+
+                    self.started.push(name.clone());
+                    self.running.insert(name, Running { spec, worker });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Wait for all of the workers started by this load to come up.
+    fn started(&self) -> impl Iterator<Item = BoxFuture<'_, anyhow::Result<()>>> {
+        self.started.iter().map(|name| {
+            let running = &self.running[name];
+
+            running
+                .worker
+                .started(name, running.spec.rpc_client())
+                .boxed()
+        })
+    }
+
+    /// Stop the workers that are no longer configured, and replace the `running` workers with the
+    /// staged workers.
+    async fn commit(self, spawner: &Spawner, running: &mut HashMap<String, Running<S>>) {
+        for name in &self.started {
+            self.running[name]
+                .worker
+                .commit(spawner.cancellation_token.clone());
+        }
+
+        for (name, running) in mem::replace(running, self.running) {
+            info!("stopping {name}");
+            running.worker.stop().await;
+        }
+    }
+
+    /// Stop the workers started by this load, and restart the replaced workers with their previous
+    /// configuration.
+    async fn rollback(mut self, spawner: &mut Spawner, running: &mut HashMap<String, Running<S>>) {
+        for name in &self.started {
+            if let Some(started) = self.running.remove(name) {
+                info!("stopping {name}");
+                started.worker.stop().await;
+            }
+        }
+
+        running.extend(self.running);
+
+        for spec in self.replaced {
+            let name = spec.name();
+
+            info!("restarting {name} with its previous configuration");
+
+            match spawner.launch(&spec).await {
+                Ok(worker) => {
+                    worker.commit(spawner.cancellation_token.clone());
+                    running.insert(name, Running { spec, worker });
+                }
+                Err(err) => {
+                    error!(
+                        err = %ErrorReporter(&*err),
+                        "unable to restart {name} with its previous configuration"
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl Workers {
+    pub(crate) fn new(
+        server: Server,
+        logger_middleware_layer: LoggerMiddlewareLayer,
+        cancellation_token: CancellationToken,
+        ipc_client_request_timeout: Duration,
+        metrics_endpoint: Option<String>,
+        equivalent_chain_ids: EquivalentChainIds,
+        ibc_spec_handlers: Arc<IbcSpecHandlers>,
+    ) -> Self {
+        Self {
+            spawner: Spawner {
+                server,
+                logger_middleware_layer,
+                cancellation_token,
+                ipc_client_request_timeout,
+                metrics_endpoint,
+                supervisor: Supervisor::new(),
+                rpc_clients: HashMap::new(),
+            },
+            equivalent_chain_ids,
+            ibc_spec_handlers,
+            plugins: HashMap::new(),
+            state_modules: HashMap::new(),
+            proof_modules: HashMap::new(),
+            finality_modules: HashMap::new(),
+            client_modules: HashMap::new(),
+            client_bootstrap_modules: HashMap::new(),
+        }
+    }
+
+    /// Load the provided plugin and module configuration.
+    ///
+    /// Workers that are no longer configured (or are no longer enabled) are stopped, newly
+    /// configured workers are started, and workers with a changed configuration are restarted.
+    /// Workers whose configuration is unchanged are left running as-is.
+    ///
+    /// The load is all-or-nothing. The configuration is validated in full before any workers are
+    /// started or stopped, so an invalid configuration leaves the currently running workers
+    /// untouched. If a worker then fails to start (including exiting with an invalid config before
+    /// it connects), all workers started by this load are stopped, the restarted workers are
+    /// restarted with their previous configuration, and the workers that are no longer configured
+    /// are left running.
+    pub(crate) async fn load(
+        &mut self,
+        plugin_configs: Vec<PluginConfig>,
+        module_configs: ModulesConfig,
+    ) -> anyhow::Result<Loaded> {
+        let mut context = Context::new(
+            self.equivalent_chain_ids.clone(),
+            self.ibc_spec_handlers.clone(),
+            self.spawner.supervisor.clone(),
+        );
+
+        let plugins = self.plan_plugins(plugin_configs, &mut context).await?;
+        let state_modules = self.plan_modules(module_configs.state, &mut context)?;
+        let proof_modules = self.plan_modules(module_configs.proof, &mut context)?;
+        let finality_modules = self.plan_modules(module_configs.consensus, &mut context)?;
+        let client_modules = self.plan_modules(module_configs.client, &mut context)?;
+        let client_bootstrap_modules =
+            self.plan_modules(module_configs.client_bootstrap, &mut context)?;
+
+        let plugin_infos = plugins.iter().map(|spec| spec.info.clone()).collect();
+
+        let mut staged_plugins = Staged::default();
+        let mut staged_state_modules = Staged::default();
+        let mut staged_proof_modules = Staged::default();
+        let mut staged_finality_modules = Staged::default();
+        let mut staged_client_modules = Staged::default();
+        let mut staged_client_bootstrap_modules = Staged::default();
+
+        let Self {
+            spawner,
+            plugins: running_plugins,
+            state_modules: running_state_modules,
+            proof_modules: running_proof_modules,
+            finality_modules: running_finality_modules,
+            client_modules: running_client_modules,
+            client_bootstrap_modules: running_client_bootstrap_modules,
+            ..
+        } = self;
+
+        let res = async {
+            staged_plugins
+                .stage(spawner, running_plugins, plugins)
+                .await?;
+            staged_state_modules
+                .stage(spawner, running_state_modules, state_modules)
+                .await?;
+            staged_proof_modules
+                .stage(spawner, running_proof_modules, proof_modules)
+                .await?;
+            staged_finality_modules
+                .stage(spawner, running_finality_modules, finality_modules)
+                .await?;
+            staged_client_modules
+                .stage(spawner, running_client_modules, client_modules)
+                .await?;
+            staged_client_bootstrap_modules
+                .stage(
+                    spawner,
+                    running_client_bootstrap_modules,
+                    client_bootstrap_modules,
+                )
+                .await?;
+
+            info!("checking for worker health...");
+
+            staged_plugins
+                .started()
+                .chain(staged_state_modules.started())
+                .chain(staged_proof_modules.started())
+                .chain(staged_finality_modules.started())
+                .chain(staged_client_modules.started())
+                .chain(staged_client_bootstrap_modules.started())
+                .collect::<FuturesUnordered<_>>()
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<anyhow::Result<()>>()
+        }
+        .await;
+
+        if let Err(err) = res {
+            error!(
+                err = %ErrorReporter(&*err),
+                "error loading workers, rolling back"
+            );
+
+            staged_plugins.rollback(spawner, running_plugins).await;
+            staged_state_modules
+                .rollback(spawner, running_state_modules)
+                .await;
+            staged_proof_modules
+                .rollback(spawner, running_proof_modules)
+                .await;
+            staged_finality_modules
+                .rollback(spawner, running_finality_modules)
+                .await;
+            staged_client_modules
+                .rollback(spawner, running_client_modules)
+                .await;
+            staged_client_bootstrap_modules
+                .rollback(spawner, running_client_bootstrap_modules)
+                .await;
+
+            return Err(err);
+        }
+
+        staged_plugins.commit(spawner, running_plugins).await;
+        staged_state_modules
+            .commit(spawner, running_state_modules)
+            .await;
+        staged_proof_modules
+            .commit(spawner, running_proof_modules)
+            .await;
+        staged_finality_modules
+            .commit(spawner, running_finality_modules)
+            .await;
+        staged_client_modules
+            .commit(spawner, running_client_modules)
+            .await;
+        staged_client_bootstrap_modules
+            .commit(spawner, running_client_bootstrap_modules)
+            .await;
+
+        Ok(Loaded {
+            context,
+            plugins: plugin_infos,
+        })
+    }
+
+    async fn plan_plugins(
+        &self,
+        plugin_configs: Vec<PluginConfig>,
+        context: &mut Context,
+    ) -> anyhow::Result<Vec<PluginSpec>> {
+        let mut plugins = vec![];
+
+        for (idx, plugin_config) in plugin_configs.into_iter().enumerate() {
+            if !plugin_config.enabled() {
+                info!(
+                    plugin = %plugin_config.location(),
+                    "plugin is not enabled, skipping"
+                );
+                continue;
+            }
+
+            let running = self
+                .plugins
+                .values()
+                .find(|running| running.spec.config == plugin_config);
+
+            let (plugin_info, rpc_client) = match running {
+                Some(running) => (running.spec.info.clone(), running.spec.rpc_client.clone()),
+                None => {
+                    let plugin_info = get_plugin_info(&plugin_config)
+                        .instrument(info_span!("get_plugin_info", %idx))
+                        .await?;
+
+                    // ensure the interest filter compiles before any workers are touched
+                    make_filter(plugin_info.clone())?;
+
+                    let rpc_client = match &plugin_config {
+                        PluginConfig::Local(_) => self.spawner.rpc_client(&plugin_info.name),
+                        PluginConfig::Remote(remote) => WorkerClient::remote(
+                            &plugin_info.name,
+                            remote.url.clone(),
+                            self.spawner.ipc_client_request_timeout,
+                            remote.auth()?,
+                        )?,
+                    };
+
+                    (plugin_info, rpc_client)
+                }
+            };
+
+            context.register_plugin(&plugin_info.name, rpc_client.clone())?;
+
+            plugins.push(PluginSpec {
+                config: plugin_config,
+                info: plugin_info,
+                rpc_client,
+            });
+        }
+
+        Ok(plugins)
+    }
+
+    fn plan_modules<Info: ModuleInfo>(
+        &self,
+        module_configs: Vec<ModuleConfig<Info>>,
+        context: &mut Context,
+    ) -> anyhow::Result<Vec<ModuleSpec<Info>>> {
+        let mut modules = vec![];
+
+        for module_config in module_configs {
+            if !module_config.enabled {
+                info!(
+                    module_path = %module_config.path.to_string_lossy(),
+                    "module is not enabled, skipping"
+                );
+                continue;
+            }
+
+            let rpc_client = self.spawner.rpc_client(&module_config.info.id());
+
+            module_config.info.register(context, rpc_client.clone())?;
+
+            modules.push(ModuleSpec {
+                config: module_config,
+                rpc_client,
+            });
+        }
+
+        Ok(modules)
+    }
+}
+
+/// How a configured worker compares to the currently running workers.
+#[derive(Debug, PartialEq)]
+enum Diff<R> {
+    /// The worker is running with the same configuration, and is left running.
+    Unchanged(R),
+    /// The worker is running with a different configuration, and must be restarted.
+    Changed(R),
+    /// The worker is not running, and must be started.
+    Added,
+}
+
+/// Take the worker `name` out of `running`, comparing its configuration with `unchanged`.
+///
+/// Once all configured workers have been taken out, the workers remaining in `running` are no
+/// longer configured and must be stopped.
+fn diff<R>(
+    running: &mut HashMap<String, R>,
+    name: &str,
+    unchanged: impl FnOnce(&R) -> bool,
+) -> Diff<R> {
+    match running.remove(name) {
+        Some(running) if unchanged(&running) => Diff::Unchanged(running),
+        Some(running) => Diff::Changed(running),
+        None => Diff::Added,
+    }
+}
+
+/// The info for one of the module types, describing how it is registered in the [`Context`].
+pub(crate) trait ModuleInfo: Serialize + Clone + PartialEq + Send + Sync + 'static {
+    fn id(&self) -> String;
+
+    fn register(&self, context: &mut Context, rpc_client: WorkerClient) -> anyhow::Result<()>;
+}
+
+macro_rules! impl_module_info {
+    ($($Info:ident => $register:ident,)*) => {
+        $(
+            impl ModuleInfo for $Info {
+                fn id(&self) -> String {
+                    $Info::id(self)
+                }
+
+                fn register(
+                    &self,
+                    context: &mut Context,
+                    rpc_client: WorkerClient,
+                ) -> anyhow::Result<()> {
+                    context.$register(self, rpc_client)
+                }
+            }
+        )*
+    };
+}
+
+impl_module_info! {
+    StateModuleInfo => register_state_module,
+    ProofModuleInfo => register_proof_module,
+    FinalityModuleInfo => register_finality_module,
+    ClientModuleInfo => register_client_module,
+    ClientBootstrapModuleInfo => register_client_bootstrap_module,
+}
+
+#[cfg(test)]
+mod tests {
+    use voyager_primitives::{ChainId, IbcSpecId};
+
+    use super::*;
+    use crate::{cache::Cache, context::SharedContext};
+
+    fn running() -> HashMap<String, u32> {
+        [
+            ("a".to_owned(), 1),
+            ("b".to_owned(), 2),
+            ("c".to_owned(), 3),
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn diff_unchanged() {
+        let mut running = running();
+
+        assert_eq!(
+            diff(&mut running, "a", |config| *config == 1),
+            Diff::Unchanged(1)
+        );
+        assert!(!running.contains_key("a"));
+    }
+
+    #[test]
+    fn diff_changed() {
+        let mut running = running();
+
+        assert_eq!(
+            diff(&mut running, "b", |config| *config == 20),
+            Diff::Changed(2)
+        );
+        assert!(!running.contains_key("b"));
+    }
+
+    #[test]
+    fn diff_added() {
+        let mut running = running();
+
+        assert_eq!(diff(&mut running, "d", |_| true), Diff::Added);
+        assert_eq!(running, self::running());
+    }
+
+    #[test]
+    fn diff_removed() {
+        let mut running = running();
+
+        diff(&mut running, "a", |_| true);
+        diff(&mut running, "b", |_| false);
+        diff(&mut running, "d", |_| true);
+
+        // only the workers that are no longer configured remain, to be stopped
+        assert_eq!(running, [("c".to_owned(), 3)].into_iter().collect());
+    }
+
+    fn workers() -> Workers {
+        Workers::new(
+            Server::new(
+                Cache::new(Default::default()),
+                SharedContext::default(),
+                futures::channel::mpsc::unbounded().0,
+            ),
+            LoggerMiddlewareLayer::new(),
+            CancellationToken::new(),
+            Duration::from_secs(1),
+            None,
+            EquivalentChainIds::default(),
+            Arc::new(IbcSpecHandlers::new()),
+        )
+    }
+
+    fn context(workers: &Workers) -> Context {
+        Context::new(
+            workers.equivalent_chain_ids.clone(),
+            workers.ibc_spec_handlers.clone(),
+            workers.spawner.supervisor.clone(),
+        )
+    }
+
+    fn state_module(chain_id: &'static str) -> ModuleConfig<StateModuleInfo> {
+        ModuleConfig {
+            path: PathBuf::from("voyager-state-module"),
+            info: StateModuleInfo {
+                chain_id: ChainId::new_static(chain_id),
+                ibc_spec_id: IbcSpecId::new_static(IbcSpecId::UNION),
+            },
+            config: serde_json::Value::Null,
+            enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn plan_modules() {
+        let workers = workers();
+        let mut context = context(&workers);
+
+        let disabled = ModuleConfig {
+            enabled: false,
+            ..state_module("chain-c")
+        };
+
+        let planned = workers
+            .plan_modules(
+                vec![state_module("chain-a"), state_module("chain-b"), disabled],
+                &mut context,
+            )
+            .unwrap();
+
+        assert_eq!(
+            planned
+                .into_iter()
+                .map(|spec| spec.config)
+                .collect::<Vec<_>>(),
+            [state_module("chain-a"), state_module("chain-b")]
+        );
+    }
+
+    #[tokio::test]
+    async fn plan_modules_invalid_config() {
+        let workers = workers();
+        let mut context = context(&workers);
+
+        // two state modules for the same chain
+        workers
+            .plan_modules(
+                vec![state_module("chain-a"), state_module("chain-a")],
+                &mut context,
+            )
+            .unwrap_err();
+
+        assert!(workers.state_modules.is_empty());
+    }
+
+    #[tokio::test]
+    async fn load_invalid_config_exit() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // a worker that always exits with an invalid config
+        let path = std::env::temp_dir().join(format!(
+            "voyager-invalid-config-worker-{}",
+            std::process::id()
+        ));
+        std::fs::write(&path, "#!/bin/sh\nexit 13\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut workers = workers();
+
+        let res = workers
+            .load(
+                vec![],
+                ModulesConfig {
+                    state: vec![ModuleConfig {
+                        path: path.clone(),
+                        ..state_module("chain-a")
+                    }],
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        std::fs::remove_file(&path).unwrap();
+
+        // the failed worker is reported as a load error, rather than shutting down voyager
+        assert!(res.is_err());
+        assert!(workers.state_modules.is_empty());
+        assert!(!workers.spawner.cancellation_token.is_cancelled());
+    }
+}
@@ -23,12 +23,14 @@ use std::{
     fmt::Debug,
     future::Future,
     path::{Path, PathBuf},
+    process::ExitStatus,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use futures::FutureExt;
 use jsonrpsee::{
+    async_client,
     core::{
         client::{BatchResponse, ClientT},
         params::BatchRequestBuilder,
@@ -44,15 +46,17 @@ use reth_ipc::{
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::value::RawValue;
-use tokio::time::sleep;
+use tokio::{process::Child, time::sleep};
 use tokio_util::sync::CancellationToken;
 use tower::Layer;
-use tracing::{debug, debug_span, error, info, info_span, instrument, trace, Instrument};
+use tracing::{debug, debug_span, error, info, info_span, instrument, trace, warn, Instrument};
 use unionlabs::{ethereum::slot::keccak256, primitives::encoding::HexUnprefixed, ErrorReporter};
 use voyager_client::VoyagerClient;
 use voyager_rpc::VoyagerRpcServer;
 use voyager_vm::ItemId;
 
+pub mod remote;
+
 pub const INVALID_CONFIG_EXIT_CODE: u8 = 13;
 pub const STARTUP_ERROR_EXIT_CODE: u8 = 14;
 
@@ -93,15 +97,22 @@ pub async fn coordinator_server(
         .instrument(debug_span!("coordinator_server", %name)))
 }
 
+/// A [`VoyagerClient`] as provided to workers, connected to the coordinator.
+pub type WorkerVoyagerClient = VoyagerClient<IdThreadClient<ArcClient<async_client::Client>>>;
+
 /// Run the worker server.
 ///
 /// This will listen to messages from the coordinator on `coordinator_socket`, and send messages to the coordinator on `worker_socket`.
+///
+/// `connected` is called once the connection to the coordinator has been established, before the
+/// worker starts serving requests.
 #[instrument(skip_all, fields(%id))]
 pub async fn worker_server<T>(
     id: String,
     coordinator_socket: String,
     worker_socket: String,
     fut: impl Future<Output = anyhow::Result<T>>,
+    connected: impl FnOnce(&T, WorkerVoyagerClient),
     into_rpc: impl FnOnce(T) -> RpcModule<T>,
 ) {
     let worker_server = match fut.await {
@@ -125,6 +136,14 @@ pub async fn worker_server<T>(
 
     trace!("connected to voyager socket");
 
+    connected(
+        &worker_server,
+        VoyagerClient::new(IdThreadClient {
+            client: voyager_client.clone(),
+            item_id: None,
+        }),
+    );
+
     let ipc_server = reth_ipc::server::Builder::default()
         .set_rpc_middleware(
             RpcServiceBuilder::new()
@@ -553,12 +572,38 @@ impl<Inner: ClientT + Send + Sync> ClientT for IdThreadClient<Inner> {
     }
 }
 
+/// The minimum amount of time to wait before respawning a worker process that has exited.
+pub const MIN_RESPAWN_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The maximum amount of time to wait before respawning a worker process that has exited.
+pub const MAX_RESPAWN_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A worker process that stays up for at least this long is considered stable, resetting the
+/// respawn backoff to [`MIN_RESPAWN_BACKOFF`].
+pub const STABLE_RUN_DURATION: Duration = Duration::from_secs(60);
+
+/// Hooks into the lifecycle of a worker process managed by [`worker_child_process`].
+pub trait WorkerMonitor: Send + Sync {
+    /// Called every time the worker process is spawned, including every respawn.
+    fn spawned(&self, pid: u32);
+
+    /// Called every time the worker process exits or is killed.
+    ///
+    /// `exit_status` is `None` if the exit status of the process could not be retrieved.
+    fn exited(&self, exit_status: Option<ExitStatus>);
+
+    /// Resolves when the currently running worker process should be killed and respawned, for
+    /// example if it has become unresponsive.
+    fn restart_requested(&self) -> impl Future<Output = ()> + Send;
+}
+
 #[instrument(skip_all, fields(%name))]
 pub async fn worker_child_process(
     name: String,
     path: PathBuf,
     cancellation_token: CancellationToken,
     args: impl IntoIterator<Item: Into<String>>,
+    monitor: &impl WorkerMonitor,
 ) {
     let coordinator_to_worker_socket = worker_socket_path(&name);
     let worker_to_coordinator_socket = coordinator_socket_path(&name);
@@ -576,14 +621,26 @@ pub async fn worker_child_process(
         .chain(args.into_iter().map(Into::into))
         .collect(),
         cancellation_token,
+        monitor,
     )
     .await
 }
 
-/// Spawn a worker process with the given args, re-spawning it indefinitely unless it exits with [`INVALID_CONFIG_EXIT_CODE`] or the passed in cancellation token is cancelled.
+/// Spawn a worker process with the given args, re-spawning it indefinitely unless it exits with
+/// [`INVALID_CONFIG_EXIT_CODE`] or the passed in cancellation token is cancelled.
+///
+/// Respawns are delayed with an exponential backoff, starting at [`MIN_RESPAWN_BACKOFF`] and
+/// doubling on every consecutive crash up to [`MAX_RESPAWN_BACKOFF`]. The backoff is reset once a
+/// process has been up for [`STABLE_RUN_DURATION`].
 #[instrument(skip_all)]
-async fn lazarus_pit(cmd: &Path, args: Vec<String>, cancellation_token: CancellationToken) {
+async fn lazarus_pit(
+    cmd: &Path,
+    args: Vec<String>,
+    cancellation_token: CancellationToken,
+    monitor: &impl WorkerMonitor,
+) {
     let mut attempt = 0;
+    let mut backoff = RespawnBackoff::default();
 
     loop {
         let mut cmd = tokio::process::Command::new(cmd);
@@ -613,33 +670,28 @@ async fn lazarus_pit(cmd: &Path, args: Vec<String>, cancellation_token: Cancella
 
         let id = child.id().unwrap();
 
+        monitor.spawned(id);
+
+        let spawned_at = Instant::now();
+
         tokio::select! {
             _ = cancellation_token.cancelled() => {
-                debug!(%id, "killing plugin");
-                match child.kill().await {
-                    Ok(()) => {
-                        debug!(%id, "plugin received kill signal");
-                        match child.wait().await {
-                            Ok(exit_status) => {
-                                debug!(%id, %exit_status, "child exited successfully")
-                            }
-                            Err(err) => {
-                                error!(%id, err = %ErrorReporter(err), "child exited unsuccessfully")
-                            }
-                        }
-                    }
-                    Err(err) => {
-                        error!(%id, err = %ErrorReporter(err), "unable to kill plugin")
-                    }
-                }
+                monitor.exited(kill(&mut child, id).await);
 
                 break
             }
+            _ = monitor.restart_requested() => {
+                warn!(%id, "restart requested, killing plugin");
+
+                monitor.exited(kill(&mut child, id).await);
+            }
             res = child.wait() => {
                 match res {
                     Ok(exit_status) => {
                         info!(%id, %exit_status, "child exited");
 
+                        monitor.exited(Some(exit_status));
+
                         if exit_status
                             .code()
                             .is_some_and(|c| c == INVALID_CONFIG_EXIT_CODE as i32)
@@ -651,18 +703,76 @@ async fn lazarus_pit(cmd: &Path, args: Vec<String>, cancellation_token: Cancella
                     }
                     Err(err) => {
                         error!(%id, err = %ErrorReporter(err), "child exited");
+
+                        monitor.exited(None);
                     }
                 }
-
-                // TODO: Exponential backoff
-                sleep(Duration::from_secs(1)).await;
             }
         }
 
+        let delay = backoff.next(spawned_at.elapsed());
+
+        debug!(%id, backoff = ?delay, "respawning plugin after backoff");
+
+        tokio::select! {
+            _ = cancellation_token.cancelled() => break,
+            _ = sleep(delay) => {}
+        }
+
         attempt += 1;
     }
 }
 
+/// The backoff between respawns of a worker process, as described in [`lazarus_pit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RespawnBackoff(Duration);
+
+impl Default for RespawnBackoff {
+    fn default() -> Self {
+        Self(MIN_RESPAWN_BACKOFF)
+    }
+}
+
+impl RespawnBackoff {
+    /// The time to wait before respawning a process that exited after running for `uptime`.
+    fn next(&mut self, uptime: Duration) -> Duration {
+        if uptime >= STABLE_RUN_DURATION {
+            self.0 = MIN_RESPAWN_BACKOFF;
+        }
+
+        let backoff = self.0;
+
+        self.0 = (self.0 * 2).min(MAX_RESPAWN_BACKOFF);
+
+        backoff
+    }
+}
+
+/// Kill the child process and wait for it to exit, returning its exit status if available.
+async fn kill(child: &mut Child, id: u32) -> Option<ExitStatus> {
+    debug!(%id, "killing plugin");
+
+    match child.kill().await {
+        Ok(()) => {
+            debug!(%id, "plugin received kill signal");
+            match child.wait().await {
+                Ok(exit_status) => {
+                    debug!(%id, %exit_status, "child exited successfully");
+                    Some(exit_status)
+                }
+                Err(err) => {
+                    error!(%id, err = %ErrorReporter(err), "child exited unsuccessfully");
+                    None
+                }
+            }
+        }
+        Err(err) => {
+            error!(%id, err = %ErrorReporter(err), "unable to kill plugin");
+            None
+        }
+    }
+}
+
 // https://github.com/paritytech/jsonrpsee/issues/1578
 #[derive(Debug)]
 pub struct ArcClient<C>(Arc<C>);
@@ -727,3 +837,37 @@ macro_rules! delegate_client_impl {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRASH: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn respawn_backoff_doubles_on_consecutive_crashes() {
+        let mut backoff = RespawnBackoff::default();
+
+        assert_eq!(
+            (0..8).map(|_| backoff.next(CRASH)).collect::<Vec<_>>(),
+            [1, 2, 4, 8, 16, 32, 60, 60].map(Duration::from_secs)
+        );
+    }
+
+    #[test]
+    fn respawn_backoff_resets_after_stable_run() {
+        let mut backoff = RespawnBackoff::default();
+
+        for _ in 0..4 {
+            backoff.next(CRASH);
+        }
+
+        assert_eq!(backoff.next(STABLE_RUN_DURATION), MIN_RESPAWN_BACKOFF);
+        assert_eq!(backoff.next(CRASH), Duration::from_secs(2));
+
+        assert_eq!(
+            backoff.next(STABLE_RUN_DURATION - Duration::from_millis(1)),
+            Duration::from_secs(4)
+        );
+    }
+}
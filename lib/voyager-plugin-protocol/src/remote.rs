@@ -0,0 +1,513 @@
+//! Remote (network attached) workers.
+//!
+//! Instead of being spawned by the coordinator and communicating over IPC, a remote worker is run
+//! independently (possibly on another host) and serves its RPC over websocket with
+//! [`remote_worker_server`]. The coordinator connects to it with [`WorkerClient::remote`], and the
+//! worker calls back into the coordinator through the coordinator's public RPC server.
+//!
+//! Connections to a remote worker can be authenticated with a bearer token, TLS, and mutual TLS, or
+//! any combination thereof.
+
+use std::{future::Future, net::SocketAddr, path::Path, sync::Arc, time::Duration};
+
+use anyhow::{bail, Context as _};
+use http::{header::AUTHORIZATION, HeaderMap, HeaderValue, StatusCode};
+use jsonrpsee::{
+    core::client::ClientT,
+    rpc_params,
+    server::{
+        middleware::rpc::{RpcService, RpcServiceBuilder},
+        serve_with_graceful_shutdown, stop_channel, HttpBody, HttpRequest, HttpResponse,
+    },
+    ws_client::WsClientBuilder,
+    Methods, RpcModule,
+};
+use rustls::{
+    crypto::ring,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore,
+};
+use subtle::ConstantTimeEq;
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tower_http::validate_request::{ValidateRequest, ValidateRequestHeaderLayer};
+use tracing::{debug, debug_span, error, info, instrument, trace, Instrument};
+use unionlabs::ErrorReporter;
+use voyager_client::VoyagerClient;
+use voyager_rpc::types::PluginInfo;
+
+use crate::{
+    ArcClient, ErrorContextService, ExtractItemIdService, IdThreadClient,
+    InjectVoyagerClientService, WorkerClient, WorkerVoyagerClient, STARTUP_ERROR_EXIT_CODE,
+};
+
+/// The method that remote plugins serve their [`PluginInfo`] on.
+///
+/// Local plugins provide their info through the `info` subcommand, but the configuration of a
+/// remote plugin is only known to the plugin itself.
+pub const PLUGIN_INFO_METHOD: &str = "plugin_info";
+
+/// Authentication for the connection from the coordinator to a remote worker.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteAuth {
+    /// Sent as a bearer token in the `Authorization` header of the websocket handshake.
+    pub token: Option<String>,
+    /// A custom TLS configuration for `wss://` connections, built with [`client_tls_config`]. If
+    /// not set, the default TLS configuration of the websocket client is used.
+    pub tls: Option<rustls::ClientConfig>,
+}
+
+impl RemoteAuth {
+    fn ws_client_builder(&self, request_timeout: Duration) -> anyhow::Result<WsClientBuilder> {
+        let mut headers = HeaderMap::new();
+
+        if let Some(token) = &self.token {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {token}"))
+                    .context("invalid bearer token")?,
+            );
+        }
+
+        let mut builder = WsClientBuilder::default()
+            .request_timeout(request_timeout)
+            .set_headers(headers);
+
+        if let Some(tls) = self.tls.clone() {
+            builder = builder.with_custom_cert_store(tls);
+        }
+
+        Ok(builder)
+    }
+}
+
+impl WorkerClient {
+    /// Create a client connecting to a remote worker served at `url`.
+    ///
+    /// As with [`WorkerClient::new`], the connection is automatically re-established if it is lost.
+    pub fn remote(
+        name: &str,
+        url: String,
+        request_timeout: Duration,
+        auth: RemoteAuth,
+    ) -> anyhow::Result<Self> {
+        // ensure the auth is valid before moving it into the reconnect task
+        auth.ws_client_builder(request_timeout)?;
+
+        let client = reconnecting_jsonrpc_ws_client::Client::new({
+            let name = name.to_owned();
+            move || {
+                let url = url.clone();
+                let builder = auth
+                    .ws_client_builder(request_timeout)
+                    .expect("auth was validated on construction; qed;");
+
+                async move {
+                    trace!(%url, "connecting to remote worker");
+                    builder.build(url).await
+                }
+                .instrument(debug_span!("remote_worker_client", %name))
+            }
+        });
+
+        Ok(Self {
+            client,
+            name: name.to_owned(),
+        })
+    }
+}
+
+/// Query the [`PluginInfo`] of a remote plugin served at `url`.
+pub async fn remote_plugin_info(url: &str, auth: &RemoteAuth) -> anyhow::Result<PluginInfo> {
+    let client = auth
+        .ws_client_builder(Duration::from_secs(10))?
+        .build(url)
+        .await
+        .with_context(|| format!("connecting to remote plugin at {url}"))?;
+
+    client
+        .request(PLUGIN_INFO_METHOD, rpc_params![])
+        .await
+        .with_context(|| format!("querying info for remote plugin at {url}"))
+}
+
+/// Build a TLS client configuration that verifies the server against the CA certificate(s) in
+/// `ca_cert`, optionally authenticating with the client certificate chain and private key in
+/// `client_identity` (mutual TLS).
+pub fn client_tls_config(
+    ca_cert: &Path,
+    client_identity: Option<(&Path, &Path)>,
+) -> anyhow::Result<rustls::ClientConfig> {
+    let builder = rustls::ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(load_root_cert_store(ca_cert)?);
+
+    Ok(match client_identity {
+        Some((cert, key)) => {
+            builder.with_client_auth_cert(load_certs(cert)?, load_private_key(key)?)?
+        }
+        None => builder.with_no_client_auth(),
+    })
+}
+
+/// Build a TLS server configuration with the certificate chain and private key in `cert` and `key`.
+/// If `client_ca_cert` is set, clients are required to authenticate with a certificate signed by
+/// one of the CA certificate(s) in the file (mutual TLS).
+pub fn server_tls_config(
+    cert: &Path,
+    key: &Path,
+    client_ca_cert: Option<&Path>,
+) -> anyhow::Result<rustls::ServerConfig> {
+    let provider = Arc::new(ring::default_provider());
+
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+
+    let builder = match client_ca_cert {
+        Some(client_ca_cert) => builder.with_client_cert_verifier(
+            WebPkiClientVerifier::builder_with_provider(
+                Arc::new(load_root_cert_store(client_ca_cert)?),
+                provider,
+            )
+            .build()?,
+        ),
+        None => builder.with_no_client_auth(),
+    };
+
+    Ok(builder.with_single_cert(load_certs(cert)?, load_private_key(key)?)?)
+}
+
+fn load_certs(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("reading certificates from {}", path.display()))?;
+
+    if certs.is_empty() {
+        bail!("no certificates found in {}", path.display());
+    }
+
+    Ok(certs)
+}
+
+fn load_private_key(path: &Path) -> anyhow::Result<PrivateKeyDer<'static>> {
+    PrivateKeyDer::from_pem_file(path)
+        .with_context(|| format!("reading private key from {}", path.display()))
+}
+
+fn load_root_cert_store(path: &Path) -> anyhow::Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+
+    for cert in load_certs(path)? {
+        roots
+            .add(cert)
+            .with_context(|| format!("invalid CA certificate in {}", path.display()))?;
+    }
+
+    Ok(roots)
+}
+
+/// Authentication required by a remote worker server.
+#[derive(Debug, Clone, Default)]
+pub struct RemoteServerAuth {
+    /// If set, all connections must provide this bearer token in the `Authorization` header.
+    pub token: Option<String>,
+    /// If set, connections are served over TLS with this configuration, built with
+    /// [`server_tls_config`].
+    pub tls: Option<rustls::ServerConfig>,
+}
+
+/// Run the worker server for a remote worker.
+///
+/// This will listen for connections from the coordinator on `laddr`, and send messages to the
+/// coordinator through its public RPC server at `voyager_rpc_url`.
+///
+/// Since the coordinator does not manage the lifecycle of a remote worker, the process exits if the
+/// connection to the coordinator is lost, such that it can be restarted by whatever is supervising
+/// it.
+///
+/// `connected` is called once the connection to the coordinator has been established, before the
+/// worker starts serving requests.
+#[instrument(skip_all, fields(%id, %laddr))]
+pub async fn remote_worker_server<T>(
+    id: String,
+    laddr: SocketAddr,
+    voyager_rpc_url: String,
+    auth: RemoteServerAuth,
+    fut: impl Future<Output = anyhow::Result<T>>,
+    connected: impl FnOnce(&T, WorkerVoyagerClient),
+    into_rpc: impl FnOnce(T) -> RpcModule<T>,
+) {
+    let worker_server = match fut.await {
+        Ok(ctx) => ctx,
+        Err(err) => {
+            error!("startup error in {id}: {err:?}");
+            std::process::exit(STARTUP_ERROR_EXIT_CODE as i32);
+        }
+    };
+
+    let voyager_client = match WsClientBuilder::default().build(&voyager_rpc_url).await {
+        Ok(voyager_client) => Arc::new(voyager_client),
+        Err(err) => {
+            error!(
+                error = %ErrorReporter(err),
+                "unable to connect to voyager at {voyager_rpc_url}"
+            );
+            std::process::exit(STARTUP_ERROR_EXIT_CODE as i32);
+        }
+    };
+
+    trace!("connected to voyager rpc");
+
+    connected(
+        &worker_server,
+        VoyagerClient::new(IdThreadClient {
+            client: ArcClient(voyager_client.clone()),
+            item_id: None,
+        }),
+    );
+
+    let rpc_middleware = RpcServiceBuilder::new()
+        .layer_fn(move |service| ExtractItemIdService { service })
+        .layer_fn({
+            let voyager_client = voyager_client.clone();
+            move |service| InjectVoyagerClientService {
+                client: ArcClient(voyager_client.clone()),
+                service,
+            }
+        })
+        .layer_fn({
+            let id = id.clone();
+            move |service: RpcService| ErrorContextService {
+                service,
+                id: id.clone(),
+            }
+        });
+
+    let http_middleware = tower::ServiceBuilder::new()
+        .layer(ValidateRequestHeaderLayer::custom(BearerAuth(auth.token)));
+
+    let builder = jsonrpsee::server::Server::builder()
+        .set_http_middleware(http_middleware)
+        .set_rpc_middleware(rpc_middleware);
+
+    let rpcs = into_rpc(worker_server);
+
+    trace!(methods = ?*rpcs, "registered methods");
+
+    let server = async {
+        match auth.tls {
+            Some(tls) => {
+                let listener = TcpListener::bind(laddr).await?;
+                let acceptor = TlsAcceptor::from(Arc::new(tls));
+
+                let service_builder = builder.to_service_builder();
+                let methods = Methods::from(rpcs);
+
+                // the server handle must be held for as long as the server is running, since
+                // dropping it stops all connections
+                let (stop_handle, _server_handle) = stop_channel();
+
+                info!("listening on {} (tls)", listener.local_addr()?);
+
+                loop {
+                    let (stream, remote_addr) = listener.accept().await?;
+
+                    let acceptor = acceptor.clone();
+                    let service = service_builder
+                        .clone()
+                        .build(methods.clone(), stop_handle.clone());
+                    let stop_handle = stop_handle.clone();
+
+                    tokio::spawn(async move {
+                        let stream = match acceptor.accept(stream).await {
+                            Ok(stream) => stream,
+                            Err(err) => {
+                                debug!(
+                                    %remote_addr,
+                                    error = %ErrorReporter(err),
+                                    "tls handshake failed"
+                                );
+                                return;
+                            }
+                        };
+
+                        if let Err(err) =
+                            serve_with_graceful_shutdown(stream, service, stop_handle.shutdown())
+                                .await
+                        {
+                            debug!(%remote_addr, %err, "connection closed with error");
+                        }
+                    });
+                }
+            }
+            None => {
+                let server = builder.build(laddr).await?;
+
+                info!("listening on {}", server.local_addr()?);
+
+                server.start(rpcs).stopped().await;
+
+                Ok::<_, anyhow::Error>(())
+            }
+        }
+    };
+
+    tokio::select! {
+        res = server => {
+            if let Err(err) = res {
+                error!("error running remote worker server for {id}: {err:?}");
+                std::process::exit(STARTUP_ERROR_EXIT_CODE as i32);
+            }
+        }
+        _ = voyager_client.on_disconnect() => {
+            error!("lost connection to voyager at {voyager_rpc_url}, exiting");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Validates the bearer token of incoming requests, if one is configured.
+#[derive(Clone)]
+struct BearerAuth(Option<String>);
+
+impl<B> ValidateRequest<B> for BearerAuth {
+    type ResponseBody = HttpBody;
+
+    fn validate(&mut self, request: &mut HttpRequest<B>) -> Result<(), HttpResponse> {
+        let Some(token) = &self.0 else {
+            return Ok(());
+        };
+
+        let authorized = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|provided| bool::from(provided.as_bytes().ct_eq(token.as_bytes())));
+
+        if authorized {
+            Ok(())
+        } else {
+            let mut response = HttpResponse::new(HttpBody::empty());
+            *response.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(response)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::server::Server;
+
+    use super::*;
+
+    const TOKEN: &str = "hunter2";
+
+    fn plugin_info() -> PluginInfo {
+        PluginInfo {
+            name: "remote-plugin".to_owned(),
+            interest_filter: "false".to_owned(),
+        }
+    }
+
+    /// Serve the info of a remote plugin, requiring `token` in the same way as
+    /// [`remote_worker_server`].
+    async fn serve(token: Option<&str>) -> String {
+        let mut module = RpcModule::new(());
+
+        module
+            .register_method(PLUGIN_INFO_METHOD, |_, _, _| {
+                jsonrpsee::core::RpcResult::Ok(plugin_info())
+            })
+            .unwrap();
+
+        let server = Server::builder()
+            .set_http_middleware(tower::ServiceBuilder::new().layer(
+                ValidateRequestHeaderLayer::custom(BearerAuth(token.map(ToOwned::to_owned))),
+            ))
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+
+        let addr = server.local_addr().unwrap();
+
+        tokio::spawn(server.start(module).stopped());
+
+        format!("ws://{addr}")
+    }
+
+    fn auth(token: Option<&str>) -> RemoteAuth {
+        RemoteAuth {
+            token: token.map(ToOwned::to_owned),
+            tls: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn auth_accepted() {
+        let url = serve(Some(TOKEN)).await;
+
+        assert_eq!(
+            remote_plugin_info(&url, &auth(Some(TOKEN))).await.unwrap(),
+            plugin_info()
+        );
+
+        let client = WorkerClient::remote(
+            "remote-plugin",
+            url,
+            Duration::from_secs(10),
+            auth(Some(TOKEN)),
+        )
+        .unwrap();
+
+        client
+            .inner()
+            .wait_until_connected(Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            client
+                .request::<PluginInfo, _>(PLUGIN_INFO_METHOD, rpc_params![])
+                .await
+                .unwrap(),
+            plugin_info()
+        );
+    }
+
+    #[tokio::test]
+    async fn auth_rejected() {
+        let url = serve(Some(TOKEN)).await;
+
+        remote_plugin_info(&url, &auth(None)).await.unwrap_err();
+        remote_plugin_info(&url, &auth(Some("hunter3")))
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn no_auth_required() {
+        let url = serve(None).await;
+
+        assert_eq!(
+            remote_plugin_info(&url, &auth(None)).await.unwrap(),
+            plugin_info()
+        );
+        assert_eq!(
+            remote_plugin_info(&url, &auth(Some(TOKEN))).await.unwrap(),
+            plugin_info()
+        );
+    }
+
+    #[test]
+    fn invalid_token() {
+        assert!(WorkerClient::remote(
+            "remote-plugin",
+            "ws://127.0.0.1:1".to_owned(),
+            Duration::from_secs(10),
+            auth(Some("invalid\ntoken")),
+        )
+        .is_err());
+    }
+}
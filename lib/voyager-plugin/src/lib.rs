@@ -1,5 +1,6 @@
-use std::{env::VarError, time::Duration};
+use std::{env::VarError, net::SocketAddr, path::PathBuf, time::Duration};
 
+use jsonrpsee::core::RpcResult;
 use opentelemetry::KeyValue;
 use opentelemetry_otlp::WithExportConfig;
 use serde::de::DeserializeOwned;
@@ -7,7 +8,10 @@ use tracing::{debug_span, instrument, Instrument};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 use unionlabs::ErrorReporter;
 pub use voyager_plugin_protocol as protocol;
-use voyager_plugin_protocol::{worker_server, INVALID_CONFIG_EXIT_CODE};
+use voyager_plugin_protocol::{
+    remote::{remote_worker_server, server_tls_config, RemoteServerAuth, PLUGIN_INFO_METHOD},
+    worker_server, WorkerVoyagerClient, INVALID_CONFIG_EXIT_CODE,
+};
 use voyager_primitives::IbcSpec;
 use voyager_rpc::{
     types::{
@@ -32,6 +36,15 @@ pub trait Plugin: PluginServer<Self::Call, Self::Callback> + Sized {
 
     async fn cmd(config: Self::Config, cmd: Self::Cmd);
 
+    /// Called once the plugin has connected to voyager, before it starts serving requests.
+    ///
+    /// The provided client is not associated with any item in the queue, and can be used to
+    /// interact with voyager outside of the handling of a message (for example, from a background
+    /// task spawned here).
+    fn connected(&self, voyager_client: WorkerVoyagerClient) {
+        let _ = voyager_client;
+    }
+
     async fn run() {
         let app = <PluginApp<Self::Cmd> as clap::Parser>::parse();
 
@@ -55,11 +68,65 @@ pub trait Plugin: PluginServer<Self::Call, Self::Callback> + Sized {
                     coordinator_socket,
                     worker_socket,
                     Self::new(config),
+                    Self::connected,
                     Self::into_rpc,
                 )
                 .instrument(debug_span!("main", %name))
                 .await;
             }
+            PluginApp::Serve {
+                laddr,
+                voyager_rpc_url,
+                config,
+                auth_token,
+                tls_cert,
+                tls_key,
+                tls_client_ca_cert,
+                metrics_endpoint,
+            } => {
+                init(metrics_endpoint);
+
+                let config = must_parse::<Self::Config>(&config);
+
+                let info = Self::info(config.clone());
+
+                let name = info.name.clone();
+
+                let tls = match (tls_cert, tls_key) {
+                    (Some(cert), Some(key)) => {
+                        match server_tls_config(&cert, &key, tls_client_ca_cert.as_deref()) {
+                            Ok(tls) => Some(tls),
+                            Err(err) => {
+                                eprintln!("invalid tls config: {}", ErrorReporter(&*err));
+                                std::process::exit(INVALID_CONFIG_EXIT_CODE as i32);
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+
+                remote_worker_server(
+                    name.clone(),
+                    laddr,
+                    voyager_rpc_url,
+                    RemoteServerAuth {
+                        token: auth_token,
+                        tls,
+                    },
+                    Self::new(config),
+                    Self::connected,
+                    move |plugin| {
+                        let mut rpcs = Self::into_rpc(plugin);
+                        rpcs.register_method(PLUGIN_INFO_METHOD, move |_, _, _| {
+                            RpcResult::Ok(info.clone())
+                        })
+                        .expect("plugin info method does not conflict with plugin methods; qed;");
+                        rpcs
+                    },
+                )
+                .instrument(debug_span!("main", %name))
+                .await;
+            }
             PluginApp::Info { config } => {
                 let info = Self::info(must_parse(&config));
 
@@ -98,6 +165,7 @@ pub trait StateModule<V: IbcSpec>: StateModuleServer<V> + Sized {
                     coordinator_socket,
                     worker_socket,
                     Self::new(config, info),
+                    |_, _| {},
                     Self::into_rpc,
                 )
                 .instrument(debug_span!("run_state_module_server", %name))
@@ -135,6 +203,7 @@ pub trait ProofModule<V: IbcSpec>: ProofModuleServer<V> + Sized {
                     coordinator_socket,
                     worker_socket,
                     Self::new(config, info),
+                    |_, _| {},
                     Self::into_rpc,
                 )
                 .instrument(debug_span!("run_proof_module_server", %name))
@@ -172,6 +241,7 @@ pub trait FinalityModule: FinalityModuleServer + Sized {
                     coordinator_socket,
                     worker_socket,
                     Self::new(config, info),
+                    |_, _| {},
                     Self::into_rpc,
                 )
                 .instrument(debug_span!("run_finality_module_server", %name))
@@ -209,6 +279,7 @@ pub trait ClientModule: ClientModuleServer + Sized {
                     coordinator_socket,
                     worker_socket,
                     Self::new(config, info),
+                    |_, _| {},
                     Self::into_rpc,
                 )
                 .instrument(debug_span!("run_client_module_server", %name))
@@ -246,6 +317,7 @@ pub trait ClientBootstrapModule: ClientBootstrapModuleServer + Sized {
                     coordinator_socket,
                     worker_socket,
                     Self::new(config, info),
+                    |_, _| {},
                     Self::into_rpc,
                 )
                 .instrument(debug_span!("run_client_bootstrap_module_server", %name))
@@ -263,6 +335,32 @@ enum PluginApp<Cmd: clap::Subcommand> {
         config: String,
         metrics_endpoint: Option<String>,
     },
+    /// Serve the plugin over websocket, to be connected to by a remote voyager instance.
+    Serve {
+        /// The address to listen on.
+        #[arg(long)]
+        laddr: SocketAddr,
+        /// The websocket url of the rpc server of the voyager instance this plugin is attached to.
+        #[arg(long)]
+        voyager_rpc_url: String,
+        #[arg(long)]
+        config: String,
+        /// If set, voyager must provide this bearer token when connecting.
+        #[arg(long, env = "VOYAGER_PLUGIN_AUTH_TOKEN", hide_env_values = true)]
+        auth_token: Option<String>,
+        /// PEM file containing the certificate chain to serve TLS with.
+        #[arg(long, requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+        /// PEM file containing the private key of `--tls-cert`.
+        #[arg(long, requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+        /// If set, voyager must authenticate with a client certificate signed by one of the CA
+        /// certificates in this PEM file (mutual TLS).
+        #[arg(long, requires = "tls_cert")]
+        tls_client_ca_cert: Option<PathBuf>,
+        #[arg(long)]
+        metrics_endpoint: Option<String>,
+    },
     Info {
         config: String,
     },
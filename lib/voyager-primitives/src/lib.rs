@@ -99,18 +99,27 @@ impl ClientType {
     /// [Scroll]: https://github.com/scroll-tech/scroll
     pub const SCROLL: &'static str = "scroll";
 
+    /// A client tracking the state of the [Linea] zkEVM L2, settling on
+    /// Ethereum, verified by verifying the L2 settlement on the L1.
+    ///
+    /// [Linea]: https://github.com/Consensys/linea-contracts
+    pub const LINEA: &'static str = "linea";
+
     /// A client tracking the state of the [Arbitrum] optimistic L2, settling on
     /// Ethereum, verified by verifying the L2 settlement on the L1.
     ///
     /// [Arbitrum]: https://github.com/OffchainLabs/nitro-contracts
     pub const ARBITRUM: &'static str = "arbitrum";
 
-    /// A client tracking the state of a [BeaconKit] chain, verified by verifying the underlying
-    /// [CometBFT] consensus.
+    /// A client tracking the execution layer of [Berachain], verified by verifying the execution
+    /// payload header committed to the underlying [BeaconKit] consensus.
+    ///
+    /// This client type was previously named `beacon-kit`. Configs referring to the old name (i.e.
+    /// the client type of the berachain client update plugin's L2 clients) must be updated.
     ///
+    /// [Berachain]: https://github.com/berachain/bera-reth
     /// [BeaconKit]: https://github.com/berachain/beacon-kit
-    /// [CometBFT]: https://github.com/cometbft/cometbft
-    pub const BEACON_KIT: &'static str = "beacon-kit";
+    pub const BERACHAIN: &'static str = "berachain";
 
     /// A client tracking the state of a [Movement] chain.
     ///
@@ -162,7 +171,7 @@ impl ClientType {
     /// [fast finality]: https://docs.bnbchain.org/bnb-smart-chain/developers/json_rpc/bsc-api-list/#economic-finality-fast-finality
     pub const PARLIA: &'static str = "parlia";
 
-    // lots more to come - near, linea, polygon - stay tuned
+    // lots more to come - near, polygon - stay tuned
 }
 
 /// Newtype for consensus types. A consensus is verifiable by potentially many [`ClientType`]s.
@@ -190,6 +199,11 @@ impl ConsensusType {
     /// [Scroll]: https://github.com/scroll-tech/scroll
     pub const SCROLL: &'static str = "scroll";
 
+    /// [Linea] zkEVM L2, settling on Ethereum.
+    ///
+    /// [Linea]: https://github.com/Consensys/linea-contracts
+    pub const LINEA: &'static str = "linea";
+
     /// [Arbitrum] optimistic L2, settling on Ethereum.
     ///
     /// [Arbitrum]: https://github.com/OffchainLabs/nitro-contracts
@@ -228,7 +242,7 @@ impl ConsensusType {
     /// [BSC]: https://bnbchain.org
     pub const PARLIA: &'static str = "parlia";
 
-    // lots more to come - near, linea, polygon - stay tuned
+    // lots more to come - near, polygon - stay tuned
 }
 
 #[cfg(feature = "serde")]
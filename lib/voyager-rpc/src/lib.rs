@@ -248,6 +248,15 @@ pub trait VoyagerRpc {
         header: Value,
     ) -> RpcResult<Bytes>;
 
+    #[method(name = "encodeMisbehaviour", with_extensions)]
+    async fn encode_misbehaviour(
+        &self,
+        client_type: ClientType,
+        ibc_interface: IbcInterface,
+        ibc_spec_id: IbcSpecId,
+        misbehaviour: Value,
+    ) -> RpcResult<Bytes>;
+
     #[method(name = "decodeClientStateMeta", with_extensions)]
     async fn decode_client_state_meta(
         &self,
@@ -305,6 +314,15 @@ pub trait VoyagerRpc {
         method: String,
         params: Vec<Value>,
     ) -> RpcResult<Value>;
+
+    // =====
+    // queue
+    // =====
+
+    /// Enqueue an op into the queue, the same as sending it to the `/enqueue` endpoint of the REST
+    /// api.
+    #[method(name = "enqueue", with_extensions)]
+    async fn enqueue(&self, op: Op<VoyagerMessage>) -> RpcResult<()>;
 }
 
 #[rpc(client, server, namespace = "plugin")]
@@ -325,7 +343,7 @@ pub trait Plugin<C: Member, Cb: Member> {
 
     /// Handle a custom request for this module.
     ///
-    /// The default implementetion returns an error.
+    /// The default implementation returns an error.
     #[method(name = "custom", with_extensions)]
     async fn custom(&self, _method: String, _params: Vec<Value>) -> RpcResult<Value> {
         Err(ErrorObject::owned(
@@ -442,6 +460,20 @@ pub trait ClientModule {
     /// Encode the proof, provided as JSON.
     #[method(name = "encodeProof", with_extensions)]
     async fn encode_proof(&self, proof: Value) -> RpcResult<Bytes>;
+
+    /// Encode the misbehaviour, provided as JSON. This will be a
+    /// [`MisbehaviourEvidence`](voyager_types::MisbehaviourEvidence).
+    ///
+    /// The default implementation returns an error, since not all light clients support
+    /// misbehaviour.
+    #[method(name = "encodeMisbehaviour", with_extensions)]
+    async fn encode_misbehaviour(&self, _misbehaviour: Value) -> RpcResult<Bytes> {
+        Err(ErrorObject::owned(
+            FATAL_JSONRPC_ERROR_CODE,
+            "misbehaviour is not supported by this client module",
+            None::<()>,
+        ))
+    }
 }
 
 /// Client modules provide functionality for interacting with a specific chain
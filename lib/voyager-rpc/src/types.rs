@@ -16,6 +16,31 @@ pub struct InfoResponse {
     pub consensus: Vec<FinalityModuleInfo>,
     pub client: Vec<ClientModuleInfo>,
     pub client_bootstrap: Vec<ClientBootstrapModuleInfo>,
+    /// The status of all plugin and module subprocesses.
+    #[serde(default)]
+    pub workers: Vec<WorkerStatus>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WorkerStatus {
+    /// The name of the plugin, or the id of the module.
+    pub name: String,
+    pub state: WorkerState,
+    /// The pid of the currently running subprocess, if any.
+    pub pid: Option<u32>,
+    /// The number of times the subprocess has been respawned since it was started.
+    pub restarts: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerState {
+    /// The subprocess is running and connected.
+    Up,
+    /// The subprocess is running, but is not connected.
+    Unhealthy,
+    /// The subprocess is not running, and is waiting to be respawned.
+    Down,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -3,12 +3,17 @@ pub mod hook;
 use std::fmt::Debug;
 
 use jsonrpsee::{async_client, core::RpcResult, types::ErrorObject, Extensions};
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
 use tracing::error;
-use unionlabs::ErrorReporter;
+use unionlabs::{
+    encoding::{Decode, DecodeAs, Encode, EncodeAs, Encoding},
+    primitives::Bytes,
+    ErrorReporter,
+};
 use voyager_plugin::protocol::{ArcClient, IdThreadClient};
 use voyager_rpc::FATAL_JSONRPC_ERROR_CODE;
+use voyager_types::MisbehaviourEvidence;
 #[doc(no_inline)]
 pub use {
     anyhow, jsonrpsee, serde_json, voyager_client as client, voyager_message as message,
@@ -47,6 +52,61 @@ pub fn ensure_null(value: Value) -> RpcResult<()> {
     }
 }
 
+/// Encode [`MisbehaviourEvidence`] for a client whose misbehaviour consists of two conflicting
+/// headers, as built by `make_misbehaviour` from the header fetched from the counterparty chain and
+/// the conflicting client message submitted to the client.
+///
+/// The header is expected to be the JSON encoded header type of the client, and the conflicting
+/// client message is decoded with `E`, which is also the encoding used for the misbehaviour.
+pub fn encode_misbehaviour<E, Header, Misbehaviour>(
+    misbehaviour: Value,
+    make_misbehaviour: impl FnOnce(Header, Header) -> Misbehaviour,
+) -> RpcResult<Bytes>
+where
+    E: Encoding,
+    Header: DeserializeOwned + Decode<E, Error: core::error::Error>,
+    Misbehaviour: Encode<E>,
+{
+    let MisbehaviourEvidence {
+        header,
+        conflicting_client_message,
+        ..
+    } = serde_json::from_value(misbehaviour).map_err(|err| {
+        ErrorObject::owned(
+            FATAL_JSONRPC_ERROR_CODE,
+            format!(
+                "unable to deserialize misbehaviour evidence: {}",
+                ErrorReporter(err)
+            ),
+            None::<()>,
+        )
+    })?;
+
+    let header = serde_json::from_value::<Header>(header).map_err(|err| {
+        ErrorObject::owned(
+            FATAL_JSONRPC_ERROR_CODE,
+            format!("unable to deserialize header: {}", ErrorReporter(err)),
+            None::<()>,
+        )
+    })?;
+
+    let conflicting_header =
+        Header::decode_as::<E>(&conflicting_client_message).map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!(
+                    "unable to decode conflicting client message: {}",
+                    ErrorReporter(err)
+                ),
+                None::<()>,
+            )
+        })?;
+
+    Ok(make_misbehaviour(header, conflicting_header)
+        .encode_as::<E>()
+        .into())
+}
+
 pub type VoyagerClient =
     voyager_client::VoyagerClient<IdThreadClient<ArcClient<async_client::Client>>>;
 
@@ -3,7 +3,10 @@ use core::fmt;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use unionlabs::ibc::core::client::height::Height;
+use unionlabs::{
+    ibc::core::client::height::Height,
+    primitives::{Bytes, H256},
+};
 use voyager_primitives::IbcSpec;
 
 /// Simple wrapper around a [`Value`] for raw client ids.
@@ -63,3 +66,27 @@ pub enum ProofType {
     Membership,
     NonMembership,
 }
+
+/// Evidence that a client accepted a consensus state that conflicts with the chain it is tracking.
+///
+/// This is built by misbehaviour watchers and passed to the `encodeMisbehaviour` endpoint of the
+/// client module for the affected client, which is responsible for turning it into the
+/// misbehaviour type understood by the light client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MisbehaviourEvidence {
+    /// The height at which the client's consensus state diverges from the counterparty chain.
+    pub height: Height,
+    /// The trusted height that [`Self::header`] was built on top of.
+    pub trusted_height: Height,
+    /// The header for [`Self::height`], fetched directly from the counterparty chain. This is the
+    /// same value that would be passed to `encodeHeader`.
+    pub header: Value,
+    /// The consensus state stored by the client at [`Self::height`], decoded as JSON.
+    pub conflicting_consensus_state: Value,
+    /// The client message of the client update that produced
+    /// [`Self::conflicting_consensus_state`], exactly as it was submitted in [`Self::tx_hash`].
+    pub conflicting_client_message: Bytes,
+    /// The hash of the transaction that submitted the conflicting client update.
+    pub tx_hash: H256,
+}
@@ -8,13 +8,14 @@ use std::{
 };
 
 use either::Either;
+use itertools::Itertools;
 use tracing::{debug, error, info, info_span, trace, Instrument};
 use unionlabs::ErrorReporter;
 
 use crate::{
     filter::{FilterResult, Interest, InterestFilter},
     pass::Pass,
-    Captures, EnqueueResult, ItemId, Op, Queue, QueueError, QueueMessage,
+    Captures, EnqueueResult, ItemId, ItemStatus, Op, Queue, QueueError, QueueItem, QueueMessage,
 };
 
 #[derive(Debug, Clone)]
@@ -22,17 +23,39 @@ pub struct InMemoryQueue<T: QueueMessage> {
     idx: Arc<AtomicU32>,
     ready: Arc<Mutex<BTreeMap<u32, Item<T>>>>,
     done: Arc<Mutex<BTreeMap<u32, Item<T>>>>,
+    failed: Arc<Mutex<BTreeMap<u32, FailedItem<T>>>>,
     #[allow(clippy::type_complexity)]
     optimizer_queue: Arc<Mutex<BTreeMap<String, BTreeMap<u32, Item<T>>>>>,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct Item<T: QueueMessage> {
-    #[allow(dead_code)] // used in debug
     parents: Vec<u32>,
     op: Op<T>,
 }
 
+#[derive(Debug, Clone)]
+pub(crate) struct FailedItem<T: QueueMessage> {
+    item: Item<T>,
+    message: String,
+}
+
+impl<T: QueueMessage> Item<T> {
+    fn to_queue_item(&self, id: u32) -> QueueItem<T> {
+        QueueItem {
+            id: item_id(id),
+            parents: self.parents.iter().copied().map(item_id).collect(),
+            item: self.op.clone(),
+            tag: None,
+            message: None,
+        }
+    }
+}
+
+fn item_id(id: u32) -> ItemId {
+    ItemId::new(i64::from(id)).expect("infallible")
+}
+
 impl<T: QueueMessage> Queue<T> for InMemoryQueue<T> {
     type Error = std::convert::Infallible;
     type Config = ();
@@ -41,6 +64,7 @@ impl<T: QueueMessage> Queue<T> for InMemoryQueue<T> {
         futures::future::ok(Self {
             idx: Arc::new(AtomicU32::default()),
             done: Arc::new(Mutex::new(BTreeMap::default())),
+            failed: Arc::new(Mutex::new(BTreeMap::default())),
             ready: Arc::new(Mutex::new(BTreeMap::default())),
             optimizer_queue: Arc::new(Mutex::new(BTreeMap::default())),
         })
@@ -183,10 +207,24 @@ impl<T: QueueMessage> Queue<T> for InMemoryQueue<T> {
                     Err(why) => match why {
                         QueueError::Fatal(error) => {
                             error!(error = %ErrorReporter(&*error), "fatal error");
+                            self.failed.lock().expect("mutex is poisoned").insert(
+                                item_id,
+                                FailedItem {
+                                    item,
+                                    message: ErrorReporter(&*error).to_string(),
+                                },
+                            );
                             Ok(None)
                         }
                         QueueError::Unprocessable(error) => {
                             info!(error = %ErrorReporter(&*error), "unprocessable message");
+                            self.failed.lock().expect("mutex is poisoned").insert(
+                                item_id,
+                                FailedItem {
+                                    item,
+                                    message: ErrorReporter(&*error).to_string(),
+                                },
+                            );
                             Ok(None)
                         }
                         QueueError::Retry(error) => {
@@ -294,4 +332,88 @@ impl<T: QueueMessage> Queue<T> for InMemoryQueue<T> {
             Ok(())
         }
     }
+
+    fn query_items(
+        &self,
+        status: ItemStatus,
+        page: u32,
+        per_page: u32,
+    ) -> impl Future<Output = Result<Vec<QueueItem<T>>, Self::Error>> + Send + '_ {
+        let items = match status {
+            ItemStatus::Queued => self
+                .ready
+                .lock()
+                .expect("mutex is poisoned")
+                .iter()
+                .map(|(id, item)| item.to_queue_item(*id))
+                .collect::<Vec<_>>(),
+            ItemStatus::Optimize => self
+                .optimizer_queue
+                .lock()
+                .expect("mutex is poisoned")
+                .iter()
+                .flat_map(|(tag, items)| {
+                    items.iter().map(|(id, item)| QueueItem {
+                        tag: Some(tag.clone()),
+                        ..item.to_queue_item(*id)
+                    })
+                })
+                .collect(),
+            ItemStatus::Failed => self
+                .failed
+                .lock()
+                .expect("mutex is poisoned")
+                .iter()
+                .map(|(id, failed)| QueueItem {
+                    message: Some(failed.message.clone()),
+                    ..failed.item.to_queue_item(*id)
+                })
+                .collect(),
+        };
+
+        futures::future::ok(
+            items
+                .into_iter()
+                .sorted_by(|a, b| b.id.cmp(&a.id))
+                .skip((page.saturating_sub(1) as usize) * per_page as usize)
+                .take(per_page as usize)
+                .collect(),
+        )
+    }
+
+    fn requeue_failed_items(
+        &self,
+        ids: Vec<ItemId>,
+    ) -> impl Future<Output = Result<Vec<ItemId>, Self::Error>> + Send + '_ {
+        let mut ready = self.ready.lock().expect("mutex is poisoned");
+        let mut failed = self.failed.lock().expect("mutex is poisoned");
+
+        let mut requeued = vec![];
+
+        for id in ids {
+            let Ok(raw) = u32::try_from(id.raw()) else {
+                continue;
+            };
+
+            if let Some(FailedItem { item, .. }) = failed.remove(&raw) {
+                ready.insert(raw, item);
+                requeued.push(id);
+            }
+        }
+
+        futures::future::ok(requeued)
+    }
+
+    fn delete_failed_items(
+        &self,
+        ids: Vec<ItemId>,
+    ) -> impl Future<Output = Result<Vec<ItemId>, Self::Error>> + Send + '_ {
+        let mut failed = self.failed.lock().expect("mutex is poisoned");
+
+        futures::future::ok(
+            ids.into_iter()
+                .filter(|id| u32::try_from(id.raw()).is_ok_and(|raw| failed.remove(&raw).is_some()))
+                .collect(),
+        )
+    }
 }
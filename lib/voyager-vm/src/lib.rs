@@ -26,6 +26,7 @@ pub mod engine;
 pub mod filter;
 pub mod in_memory;
 pub mod pass;
+pub mod retry;
 
 #[cfg(test)]
 mod tests;
@@ -73,6 +74,57 @@ pub trait Queue<T: QueueMessage>: Debug + Clone + Send + Sync + Sized + 'static
     where
         O: Pass<T>,
         Filter: InterestFilter<T>;
+
+    /// Query the items with the specified status, ordered by id descending (i.e. newest first).
+    ///
+    /// `page` is 1-indexed.
+    fn query_items(
+        &self,
+        status: ItemStatus,
+        page: u32,
+        per_page: u32,
+    ) -> impl Future<Output = Result<Vec<QueueItem<T>>, Self::Error>> + Send + '_;
+
+    /// Move the specified failed items back into the queue, returning the ids of the items that
+    /// were requeued. Ids that do not refer to a failed item are ignored.
+    fn requeue_failed_items(
+        &self,
+        ids: Vec<ItemId>,
+    ) -> impl Future<Output = Result<Vec<ItemId>, Self::Error>> + Send + '_;
+
+    /// Permanently delete the specified failed items, returning the ids of the items that were
+    /// deleted. Ids that do not refer to a failed item are ignored.
+    fn delete_failed_items(
+        &self,
+        ids: Vec<ItemId>,
+    ) -> impl Future<Output = Result<Vec<ItemId>, Self::Error>> + Send + '_;
+}
+
+/// The status of an item in the queue, as used in [`Queue::query_items`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum ItemStatus {
+    /// The item is ready to be processed.
+    Queued,
+    /// The item is waiting to be picked up by an optimization pass.
+    Optimize,
+    /// Processing the item failed with an error that will not be retried.
+    Failed,
+}
+
+/// An item in the queue, as returned by [`Queue::query_items`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, bound(serialize = "", deserialize = ""))]
+pub struct QueueItem<T: QueueMessage> {
+    pub id: ItemId,
+    pub parents: Vec<ItemId>,
+    pub item: Op<T>,
+    /// The tag of the optimizer this item is waiting for. Only set for [`ItemStatus::Optimize`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// The error that caused this item to fail. Only set for [`ItemStatus::Failed`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
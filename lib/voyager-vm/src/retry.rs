@@ -0,0 +1,275 @@
+//! Retry policies for items that error while being processed, shared between the database backed
+//! queue implementations.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{BoxDynError, QueueError};
+
+/// A policy for retrying items in the queue that error.
+///
+/// Items matched by a policy will be retried until they have been attempted `max_attempts` times,
+/// after which they will be moved to the failed items.
+///
+/// Matching the filters against an item is left to the queue implementation, so that the filters
+/// behave exactly the same as the filters for querying failed items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// SQL `LIKE` filters for the item, with the same syntax as the filters for querying failed
+    /// items. This policy applies to the item if *any* of the filters match. If no filters are
+    /// provided, this policy applies to all items.
+    #[serde(default)]
+    pub item_filters: Vec<String>,
+    /// SQL `LIKE` filters for the error message. This policy applies to the error if *any* of
+    /// the filters match. If no filters are provided, this policy applies to all error messages.
+    #[serde(default)]
+    pub message_filters: Vec<String>,
+    /// The kinds of errors that this policy applies to. Errors of these kinds will be retried,
+    /// even if the error was not marked as retryable.
+    #[serde(default = "default_retry_policy_errors")]
+    pub errors: Vec<ErrorKind>,
+    /// The maximum amount of times to attempt the item. If not set, the item will be retried
+    /// indefinitely.
+    #[serde(default)]
+    pub max_attempts: Option<i64>,
+    /// Overrides the queue's default maximum backoff for items matched by this policy.
+    #[serde(default)]
+    pub backoff_max: Option<f64>,
+    /// Overrides the queue's default backoff multiplier for items matched by this policy.
+    #[serde(default)]
+    pub backoff_multiplier: Option<f64>,
+}
+
+fn default_retry_policy_errors() -> Vec<ErrorKind> {
+    vec![ErrorKind::Retry]
+}
+
+/// The kind of a [`QueueError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Fatal,
+    Unprocessable,
+    Retry,
+}
+
+impl ErrorKind {
+    #[must_use]
+    pub fn from_queue_error(error: QueueError) -> (Self, BoxDynError) {
+        match error {
+            QueueError::Fatal(error) => (Self::Fatal, error),
+            QueueError::Unprocessable(error) => (Self::Unprocessable, error),
+            QueueError::Retry(error) => (Self::Retry, error),
+        }
+    }
+}
+
+/// The exponential backoff applied to retried items.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// The maximum backoff, in seconds.
+    pub max: f64,
+    pub multiplier: f64,
+}
+
+impl Backoff {
+    /// The backoff for an item that has been attempted `attempt` times before.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // attempt counts are nowhere near 2^52
+    pub fn duration(&self, attempt: i64) -> Duration {
+        Duration::try_from_secs_f64(
+            (attempt as f64)
+                .powf(self.multiplier)
+                .clamp(f64::MIN, self.max),
+        )
+        .unwrap_or(Duration::MAX)
+    }
+}
+
+/// What to do with an item that errored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Retry {
+    /// Requeue the item, to be handled again after the contained backoff.
+    After(Duration),
+    /// Move the item to the failed items.
+    Fail,
+}
+
+impl RetryPolicy {
+    /// Whether this policy applies to errors of `kind`. The first policy that applies to an error
+    /// and whose filters match the errored item is the policy used for it.
+    #[must_use]
+    pub fn applies_to(&self, kind: ErrorKind) -> bool {
+        self.errors.contains(&kind)
+    }
+}
+
+/// Decide what to do with an item that errored with an error of `kind`, after having been
+/// attempted `attempt` times before.
+///
+/// Retryable errors that don't match any policy are retried indefinitely, and fatal and
+/// unprocessable errors that don't match any policy fail immediately.
+#[must_use]
+pub fn retry(
+    policy: Option<&RetryPolicy>,
+    kind: ErrorKind,
+    attempt: i64,
+    default_backoff: Backoff,
+) -> Retry {
+    match policy {
+        Some(RetryPolicy {
+            max_attempts: Some(max_attempts),
+            ..
+        }) if attempt.saturating_add(1) >= *max_attempts => {
+            info!(max_attempts, "max attempts reached, not retrying");
+
+            Retry::Fail
+        }
+        Some(policy) => Retry::After(
+            Backoff {
+                max: policy.backoff_max.unwrap_or(default_backoff.max),
+                multiplier: policy
+                    .backoff_multiplier
+                    .unwrap_or(default_backoff.multiplier),
+            }
+            .duration(attempt),
+        ),
+        None => match kind {
+            ErrorKind::Retry => Retry::After(default_backoff.duration(attempt)),
+            ErrorKind::Fatal | ErrorKind::Unprocessable => Retry::Fail,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKOFF: Backoff = Backoff {
+        max: 300.0,
+        multiplier: 2.0,
+    };
+
+    fn policy(errors: Vec<ErrorKind>, max_attempts: Option<i64>) -> RetryPolicy {
+        RetryPolicy {
+            item_filters: vec![],
+            message_filters: vec![],
+            errors,
+            max_attempts,
+            backoff_max: None,
+            backoff_multiplier: None,
+        }
+    }
+
+    #[test]
+    fn error_kind() {
+        for (error, kind) in [
+            (QueueError::Fatal("a".into()), ErrorKind::Fatal),
+            (
+                QueueError::Unprocessable("a".into()),
+                ErrorKind::Unprocessable,
+            ),
+            (QueueError::Retry("a".into()), ErrorKind::Retry),
+        ] {
+            let (k, error) = ErrorKind::from_queue_error(error);
+            assert_eq!(k, kind);
+            assert_eq!(error.to_string(), "a");
+        }
+    }
+
+    #[test]
+    fn policy_defaults() {
+        assert_eq!(
+            serde_json::from_str::<RetryPolicy>("{}").unwrap(),
+            policy(vec![ErrorKind::Retry], None)
+        );
+    }
+
+    #[test]
+    fn backoff() {
+        assert_eq!(BACKOFF.duration(0), Duration::ZERO);
+        assert_eq!(BACKOFF.duration(3), Duration::from_secs(9));
+        assert_eq!(BACKOFF.duration(100), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn no_policy() {
+        assert_eq!(
+            retry(None, ErrorKind::Retry, 3, BACKOFF),
+            Retry::After(Duration::from_secs(9))
+        );
+        assert_eq!(
+            retry(None, ErrorKind::Retry, i64::MAX, BACKOFF),
+            Retry::After(Duration::from_secs(300))
+        );
+        assert_eq!(retry(None, ErrorKind::Fatal, 0, BACKOFF), Retry::Fail);
+        assert_eq!(
+            retry(None, ErrorKind::Unprocessable, 0, BACKOFF),
+            Retry::Fail
+        );
+    }
+
+    #[test]
+    fn policy_retries_fatal_errors() {
+        let policy = policy(vec![ErrorKind::Fatal], None);
+
+        assert_eq!(
+            retry(Some(&policy), ErrorKind::Fatal, 2, BACKOFF),
+            Retry::After(Duration::from_secs(4))
+        );
+    }
+
+    #[test]
+    fn policy_max_attempts() {
+        let policy = policy(vec![ErrorKind::Retry], Some(3));
+
+        assert_eq!(
+            retry(Some(&policy), ErrorKind::Retry, 0, BACKOFF),
+            Retry::After(Duration::ZERO)
+        );
+        assert_eq!(
+            retry(Some(&policy), ErrorKind::Retry, 1, BACKOFF),
+            Retry::After(Duration::from_secs(1))
+        );
+        assert_eq!(
+            retry(Some(&policy), ErrorKind::Retry, 2, BACKOFF),
+            Retry::Fail
+        );
+        assert_eq!(
+            retry(Some(&policy), ErrorKind::Retry, 10, BACKOFF),
+            Retry::Fail
+        );
+    }
+
+    #[test]
+    fn policy_backoff_overrides() {
+        let policy = RetryPolicy {
+            backoff_max: Some(5.0),
+            backoff_multiplier: Some(3.0),
+            ..policy(vec![ErrorKind::Retry], None)
+        };
+
+        assert_eq!(
+            retry(Some(&policy), ErrorKind::Retry, 1, BACKOFF),
+            Retry::After(Duration::from_secs(1))
+        );
+        assert_eq!(
+            retry(Some(&policy), ErrorKind::Retry, 2, BACKOFF),
+            Retry::After(Duration::from_secs(5))
+        );
+    }
+
+    #[test]
+    fn policy_applies_to() {
+        let policy = policy(vec![ErrorKind::Fatal, ErrorKind::Retry], None);
+
+        assert!(policy.applies_to(ErrorKind::Fatal));
+        assert!(policy.applies_to(ErrorKind::Retry));
+        assert!(!policy.applies_to(ErrorKind::Unprocessable));
+    }
+}
@@ -678,8 +678,8 @@ fn move_type_to_output_type(typ: &MoveType) -> (Type, Vec<(Ident, Ident)>) {
 }
 
 #[allow(dead_code)]
-fn move_type_to_type_expression(typ: &MoveType) -> proc_macro2::TokenStream {
-    match typ {
+fn move_type_to_type_expression(typ: &MoveType) -> Result<proc_macro2::TokenStream, Bde> {
+    Ok(match typ {
         MoveType::Bool => {
             quote!(::move_bindgen::aptos_rest_client::aptos_api_types::MoveType::Bool)
         }
@@ -700,7 +700,7 @@ fn move_type_to_type_expression(typ: &MoveType) -> proc_macro2::TokenStream {
             quote!(::move_bindgen::aptos_rest_client::aptos_api_types::MoveType::Signer)
         }
         MoveType::Vector { items } => {
-            let items = move_type_to_type_expression(items);
+            let items = move_type_to_type_expression(items)?;
 
             quote!(::move_bindgen::aptos_rest_client::aptos_api_types::MoveType::Vector { items: #items })
         }
@@ -713,7 +713,10 @@ fn move_type_to_type_expression(typ: &MoveType) -> proc_macro2::TokenStream {
             let address = address.to_standard_string();
             let module = module.to_string();
             let name = name.to_string();
-            let generic_type_params = generic_type_params.iter().map(move_type_to_type_expression);
+            let generic_type_params = generic_type_params
+                .iter()
+                .map(move_type_to_type_expression)
+                .collect::<Result<Vec<_>, _>>()?;
 
             quote!(::move_bindgen::aptos_rest_client::aptos_api_types::MoveType::Struct(::move_bindgen::aptos_rest_client::aptos_api_types::MoveStructTag {
                 address: #address.parse().unwrap(),
@@ -725,9 +728,10 @@ fn move_type_to_type_expression(typ: &MoveType) -> proc_macro2::TokenStream {
         MoveType::GenericTypeParam { index } => {
             quote!(::move_bindgen::aptos_rest_client::aptos_api_types::MoveType::GenericTypeParam { index: #index })
         }
-        MoveType::Reference { mutable: _, to: _ } => todo!(),
-        MoveType::Unparsable(_) => todo!(),
-    }
+        MoveType::Reference { .. } | MoveType::Function { .. } | MoveType::Unparsable(_) => {
+            return Err(format!("unsupported type expression: {typ:?}").into())
+        }
+    })
 }
 
 fn is_signer(move_type: &MoveType) -> bool {
@@ -1,6 +1,18 @@
 { types, mkOption }:
 let
   definitions = {
+    "#/definitions/BalanceMonitorConfig" = types.submodule {
+      options = {
+        "interval" = mkOption {
+          type = types.int;
+          default = 300;
+        };
+        "plugins" = mkOption {
+          type = types.attrsOf definitions."#/definitions/SignerThresholds";
+          default = { };
+        };
+      };
+    };
     "#/definitions/CacheConfig" = types.submodule {
       options = {
         "capacity" = mkOption { type = types.int; };
@@ -128,16 +140,7 @@ let
         };
       };
     };
-    "#/definitions/PluginConfig" = types.submodule {
-      options = {
-        "config" = mkOption { type = types.attrs; };
-        "enabled" = mkOption {
-          type = types.bool;
-          default = true;
-        };
-        "path" = mkOption { type = types.str; };
-      };
-    };
+    "#/definitions/PluginConfig" = types.attrs;
     "#/definitions/ProofModuleInfo" = types.submodule {
       options = {
         "chain_id" = mkOption { type = types.str; };
@@ -145,6 +148,16 @@ let
       };
     };
     "#/definitions/QueueConfig" = types.attrs;
+    "#/definitions/SignerThresholds" = types.submodule {
+      options = {
+        "low_balance" = mkOption { type = types.str; };
+        "min_balance" = mkOption { type = types.str; };
+        "top_up_amount" = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+        };
+      };
+    };
     "#/definitions/StateModuleInfo" = types.submodule {
       options = {
         "chain_id" = mkOption { type = types.str; };
@@ -153,7 +166,19 @@ let
     };
     "#/definitions/VoyagerConfig" = types.submodule {
       options = {
+        "admin_token" = mkOption {
+          type = types.nullOr types.str;
+          default = null;
+        };
+        "balance_monitor" = mkOption {
+          type = types.nullOr definitions."#/definitions/BalanceMonitorConfig";
+          default = null;
+        };
         "cache" = mkOption { type = definitions."#/definitions/Config"; };
+        "config_reload_interval" = mkOption {
+          type = types.nullOr definitions."#/definitions/Duration";
+          default = null;
+        };
         "ipc_client_request_timeout" = mkOption {
           type = definitions."#/definitions/Duration";
           default = {
@@ -0,0 +1,224 @@
+use alloy::{
+    network::AnyNetwork,
+    providers::{layers::CacheLayer, DynProvider, Provider, ProviderBuilder},
+};
+use berachain_light_client_types::{ClientState, ConsensusState};
+use ibc_union_spec::{ClientId, Timestamp};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::instrument;
+use unionlabs::{ibc::core::client::height::Height, primitives::H160, ErrorReporter};
+use voyager_sdk::{
+    anyhow, into_value,
+    plugin::ClientBootstrapModule,
+    primitives::{ChainId, ClientType},
+    rpc::{
+        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
+};
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    Module::run().await
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub chain_id: ChainId,
+
+    /// The address of the `IBCHandler` smart contract.
+    pub ibc_handler_address: H160,
+
+    pub provider: DynProvider<AnyNetwork>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The address of the `IBCHandler` smart contract.
+    pub ibc_handler_address: H160,
+
+    /// The execution layer RPC endpoint.
+    pub rpc_url: String,
+
+    #[serde(default)]
+    pub max_cache_size: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientStateConfig {
+    /// The id of the tendermint client tracking the BeaconKit consensus layer of this chain.
+    pub l1_client_id: ClientId,
+}
+
+impl ClientBootstrapModule for Module {
+    type Config = Config;
+
+    async fn new(config: Self::Config, info: ClientBootstrapModuleInfo) -> anyhow::Result<Self> {
+        let provider = DynProvider::new(
+            ProviderBuilder::new()
+                .network::<AnyNetwork>()
+                .layer(CacheLayer::new(config.max_cache_size))
+                .connect(&config.rpc_url)
+                .await?,
+        );
+
+        let chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
+
+        info.ensure_chain_id(chain_id.to_string())?;
+        info.ensure_client_type(ClientType::BERACHAIN)?;
+
+        Ok(Self {
+            chain_id,
+            ibc_handler_address: config.ibc_handler_address,
+            provider,
+        })
+    }
+}
+
+impl Module {
+    pub fn make_client_state(
+        chain_id: &ChainId,
+        ibc_handler_address: H160,
+        height: Height,
+        config: ClientStateConfig,
+    ) -> ClientState {
+        ClientState {
+            l1_client_id: config.l1_client_id,
+            chain_id: chain_id
+                .as_str()
+                .parse()
+                .expect("chain_id is a valid u256; qed;"),
+            latest_height: height.height(),
+            ibc_contract_address: ibc_handler_address,
+        }
+    }
+}
+
+#[async_trait]
+impl ClientBootstrapModuleServer for Module {
+    #[instrument(skip_all, fields(chain_id = %self.chain_id, %height))]
+    async fn self_client_state(
+        &self,
+        _: &Extensions,
+        height: Height,
+        config: Value,
+    ) -> RpcResult<Value> {
+        let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!(
+                    "unable to deserialize client state config: {}",
+                    ErrorReporter(err)
+                ),
+                None::<()>,
+            )
+        })?;
+
+        Ok(into_value(Module::make_client_state(
+            &self.chain_id,
+            self.ibc_handler_address,
+            height,
+            config,
+        )))
+    }
+
+    /// The consensus state on this chain at the specified `Height`.
+    #[instrument(skip_all, fields(chain_id = %self.chain_id, %height))]
+    async fn self_consensus_state(
+        &self,
+        _: &Extensions,
+        height: Height,
+        _: Value,
+    ) -> RpcResult<Value> {
+        let block = self
+            .provider
+            .get_block(height.height().into())
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching block"),
+                    None::<()>,
+                )
+            })?
+            .ok_or_else(|| {
+                ErrorObject::owned(-1, format!("block {height} not found"), None::<()>)
+            })?;
+
+        let storage_root = self
+            .provider
+            .get_proof(self.ibc_handler_address.into(), vec![])
+            .block_id(block.header.number.into())
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching ibc handler account proof"),
+                    None::<()>,
+                )
+            })?
+            .storage_hash;
+
+        Ok(into_value(ConsensusState {
+            timestamp: Timestamp::from_secs(block.header.timestamp),
+            state_root: block.header.state_root.into(),
+            storage_root: storage_root.0.into(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn client_state() {
+        let config = serde_json::from_value::<ClientStateConfig>(json!({
+            "l1_client_id": 1,
+        }))
+        .unwrap();
+
+        assert_eq!(
+            Module::make_client_state(
+                &ChainId::new("80094"),
+                H160::new([0xAA; 20]),
+                Height::new(100),
+                config,
+            ),
+            ClientState {
+                l1_client_id: ClientId::from_raw(1).unwrap(),
+                chain_id: 80094_u64.into(),
+                latest_height: 100,
+                ibc_contract_address: H160::new([0xAA; 20]),
+            }
+        );
+    }
+
+    #[test]
+    fn client_state_config_unknown_fields() {
+        assert!(serde_json::from_value::<ClientStateConfig>(json!({
+            "l1_client_id": 1,
+            "chain_id": "80094",
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn client_state_config_zero_l1_client_id() {
+        assert!(serde_json::from_value::<ClientStateConfig>(json!({
+            "l1_client_id": 0,
+        }))
+        .is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use alloy::{
+    network::AnyNetwork,
+    providers::{layers::CacheLayer, DynProvider, Provider, ProviderBuilder},
+};
+use ibc_union_spec::{ClientId, Timestamp};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use linea_light_client_types::{ClientState, ConsensusState};
+use linea_types::{account::ZkAccount, proof::MerkleProof};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::instrument;
+use unionlabs::{
+    ibc::core::client::height::Height,
+    primitives::{H160, U256},
+    ErrorReporter,
+};
+use voyager_sdk::{
+    anyhow, into_value,
+    plugin::ClientBootstrapModule,
+    primitives::{ChainId, ClientType},
+    rpc::{
+        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
+};
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    Module::run().await
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub chain_id: ChainId,
+
+    pub rollup_contract_address: H160,
+    pub rollup_current_l2_block_number_slot: U256,
+    pub rollup_current_l2_timestamp_slot: U256,
+    pub rollup_l2_state_root_hashes_slot: U256,
+
+    /// The address of the `IBCHandler` smart contract.
+    pub ibc_handler_address: H160,
+
+    pub provider: DynProvider<AnyNetwork>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The linea rollup contract on the L1.
+    pub rollup_contract_address: H160,
+
+    /// The slot of `currentL2BlockNumber` in the rollup contract.
+    pub rollup_current_l2_block_number_slot: U256,
+
+    /// The slot of `currentL2Timestamp` in the rollup contract.
+    pub rollup_current_l2_timestamp_slot: U256,
+
+    /// The slot of `stateRootHashes` in the rollup contract.
+    pub rollup_l2_state_root_hashes_slot: U256,
+
+    /// The address of the `IBCHandler` smart contract.
+    pub ibc_handler_address: H160,
+
+    /// The RPC endpoint of the linea chain. This must support `linea_getProof`.
+    pub rpc_url: String,
+
+    #[serde(default)]
+    pub max_cache_size: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientStateConfig {
+    pub l1_client_id: ClientId,
+}
+
+impl ClientBootstrapModule for Module {
+    type Config = Config;
+
+    async fn new(config: Self::Config, info: ClientBootstrapModuleInfo) -> anyhow::Result<Self> {
+        let provider = DynProvider::new(
+            ProviderBuilder::new()
+                .network::<AnyNetwork>()
+                .layer(CacheLayer::new(config.max_cache_size))
+                .connect(&config.rpc_url)
+                .await?,
+        );
+
+        let l2_chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
+
+        info.ensure_chain_id(l2_chain_id.to_string())?;
+        info.ensure_client_type(ClientType::LINEA)?;
+
+        Ok(Self {
+            chain_id: l2_chain_id,
+            rollup_contract_address: config.rollup_contract_address,
+            rollup_current_l2_block_number_slot: config.rollup_current_l2_block_number_slot,
+            rollup_current_l2_timestamp_slot: config.rollup_current_l2_timestamp_slot,
+            rollup_l2_state_root_hashes_slot: config.rollup_l2_state_root_hashes_slot,
+            ibc_handler_address: config.ibc_handler_address,
+            provider,
+        })
+    }
+}
+
+#[async_trait]
+impl ClientBootstrapModuleServer for Module {
+    #[instrument(skip_all, fields(chain_id = %self.chain_id, %height))]
+    async fn self_client_state(
+        &self,
+        _: &Extensions,
+        height: Height,
+        config: Value,
+    ) -> RpcResult<Value> {
+        let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!(
+                    "unable to deserialize client state config: {}",
+                    ErrorReporter(err)
+                ),
+                None::<()>,
+            )
+        })?;
+
+        Ok(into_value(ClientState {
+            chain_id: self
+                .chain_id
+                .as_str()
+                .parse()
+                .expect("self.chain_id is a valid u64; qed;"),
+            latest_height: height.height(),
+            l1_client_id: config.l1_client_id,
+            l1_rollup_contract_address: self.rollup_contract_address,
+            l1_rollup_current_l2_timestamp_slot: self.rollup_current_l2_timestamp_slot,
+            l1_rollup_current_l2_block_number_slot: self.rollup_current_l2_block_number_slot,
+            l1_rollup_l2_state_root_hashes_slot: self.rollup_l2_state_root_hashes_slot,
+            l2_ibc_contract_address: self.ibc_handler_address,
+            frozen_height: Height::new(0),
+        }))
+    }
+
+    /// The consensus state on this chain at the specified `Height`.
+    #[instrument(skip_all, fields(chain_id = %self.chain_id, %height))]
+    async fn self_consensus_state(
+        &self,
+        _: &Extensions,
+        height: Height,
+        _: Value,
+    ) -> RpcResult<Value> {
+        let l2_block = self
+            .provider
+            .get_block(height.height().into())
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching l2 block"),
+                    None::<()>,
+                )
+            })?
+            .ok_or_else(|| {
+                ErrorObject::owned(-1, format!("l2 block {height} not found"), None::<()>)
+            })?;
+
+        let proof = linea_client::get_proof(
+            &self.provider,
+            self.ibc_handler_address,
+            [],
+            l2_block.header.number,
+        )
+        .await
+        .map_err(|e| {
+            ErrorObject::owned(
+                -1,
+                ErrorReporter(&*e).with_message("error fetching ibc account proof"),
+                None::<()>,
+            )
+        })?;
+
+        let MerkleProof::Inclusion(account_proof) = proof.account_proof else {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!(
+                    "ibc handler {} does not exist at l2 block {height}",
+                    self.ibc_handler_address
+                ),
+                None::<()>,
+            ));
+        };
+
+        let account = ZkAccount::decode(&account_proof.proof.value).map_err(|e| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                ErrorReporter(e).with_message("unable to decode ibc handler account"),
+                None::<()>,
+            )
+        })?;
+
+        Ok(into_value(ConsensusState {
+            ibc_storage_root: account.storage_root,
+            timestamp: Timestamp::from_secs(l2_block.header.timestamp),
+        }))
+    }
+}
@@ -2,6 +2,7 @@ use aptos_move_ibc::ibc::ClientExt;
 use aptos_rest_client::error::RestError;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
+    types::{ErrorObject, ErrorObjectOwned},
     Extensions,
 };
 use movement_light_client_types::{ClientState, ConsensusState};
@@ -15,6 +16,7 @@ use unionlabs::{
     },
     ibc::core::client::height::Height,
     primitives::{FixedBytes, H160, U256},
+    ErrorReporter,
 };
 use voyager_sdk::{
     anyhow, ensure_null,
@@ -116,20 +118,19 @@ pub struct Config {
 }
 
 impl Module {
-    pub async fn ledger_version_of_height(&self, height: u64) -> u64 {
+    pub async fn ledger_version_of_height(&self, height: u64) -> RpcResult<u64> {
         let ledger_version = self
             .aptos_client
             .get_block_by_height(height, false)
             .await
-            // .map_err(rest_error_to_rpc_error)?
-            .unwrap()
+            .map_err(rest_error_to_rpc_error)?
             .into_inner()
             .last_version
             .0;
 
         debug!("height {height} is ledger version {ledger_version}");
 
-        ledger_version
+        Ok(ledger_version)
     }
 }
 
@@ -150,7 +151,7 @@ impl ClientBootstrapModuleServer for Module {
     ) -> RpcResult<Value> {
         ensure_null(config)?;
 
-        let ledger_version = self.ledger_version_of_height(height.height()).await;
+        let ledger_version = self.ledger_version_of_height(height.height()).await?;
 
         let vault_addr = self
             .get_vault_addr(
@@ -158,7 +159,7 @@ impl ClientBootstrapModuleServer for Module {
                 Some(ledger_version),
             )
             .await
-            .unwrap();
+            .map_err(rest_error_to_rpc_error)?;
 
         let table_handle = self
             .aptos_client
@@ -167,7 +168,7 @@ impl ClientBootstrapModuleServer for Module {
                 &format!("0x{}::ibc::IBCStore", self.ibc_handler_address),
             )
             .await
-            .unwrap()
+            .map_err(rest_error_to_rpc_error)?
             .into_inner()
             .unwrap()
             .data["commitments"]["handle"]
@@ -196,17 +197,28 @@ impl ClientBootstrapModuleServer for Module {
     async fn self_consensus_state(
         &self,
         _: &Extensions,
-        _height: Height,
+        height: Height,
         config: Value,
     ) -> RpcResult<Value> {
         ensure_null(config)?;
 
+        let block = self
+            .aptos_client
+            .get_block_by_height(height.height(), false)
+            .await
+            .map_err(rest_error_to_rpc_error)?
+            .into_inner();
+
         Ok(serde_json::to_value(ConsensusState {
             state_root: Default::default(),
-            // TODO: Fix this - this is currently effectively broken.
-            timestamp: Timestamp::from_secs(1000),
+            // block timestamps are in microseconds
+            timestamp: Timestamp::from_nanos(block.block_timestamp.0 * 1_000),
             state_proof_hash: Default::default(),
         })
         .expect("infallible"))
     }
 }
+
+pub fn rest_error_to_rpc_error(e: RestError) -> ErrorObjectOwned {
+    ErrorObject::owned(-1, format!("rest error: {}", ErrorReporter(e)), None::<()>)
+}
@@ -0,0 +1,194 @@
+use alloy::{
+    network::AnyNetwork,
+    providers::{layers::CacheLayer, DynProvider, Provider, ProviderBuilder},
+};
+use ibc_union_spec::{ClientId, Timestamp};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use scroll_light_client_types::{ClientState, ConsensusState};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::instrument;
+use unionlabs::{
+    ibc::core::client::height::Height,
+    primitives::{H160, U256},
+    ErrorReporter,
+};
+use voyager_sdk::{
+    anyhow, into_value,
+    plugin::ClientBootstrapModule,
+    primitives::{ChainId, ClientType},
+    rpc::{
+        types::ClientBootstrapModuleInfo, ClientBootstrapModuleServer, FATAL_JSONRPC_ERROR_CODE,
+    },
+};
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    Module::run().await
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub chain_id: ChainId,
+
+    pub rollup_contract_address: H160,
+    pub rollup_committed_batches_slot: U256,
+    pub rollup_finalized_state_roots_slot: U256,
+    pub rollup_last_finalized_batch_index_slot: U256,
+
+    /// The address of the `IBCHandler` smart contract.
+    pub ibc_handler_address: H160,
+
+    pub provider: DynProvider<AnyNetwork>,
+    pub scroll_rpc_client: scroll_rpc::JsonRpcClient,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The scroll rollup contract on the L1.
+    pub rollup_contract_address: H160,
+
+    /// The slot of `committedBatches` in the rollup contract.
+    pub rollup_committed_batches_slot: U256,
+
+    /// The slot of `finalizedStateRoots` in the rollup contract.
+    pub rollup_finalized_state_roots_slot: U256,
+
+    /// The slot of `lastFinalizedBatchIndex` in the rollup contract.
+    pub rollup_last_finalized_batch_index_slot: U256,
+
+    /// The address of the `IBCHandler` smart contract.
+    pub ibc_handler_address: H160,
+
+    /// The websocket RPC endpoint of the scroll chain. This is also used to fetch the zktrie
+    /// account proofs, which can't be decoded by alloy.
+    pub rpc_url: String,
+
+    #[serde(default)]
+    pub max_cache_size: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClientStateConfig {
+    pub l1_client_id: ClientId,
+}
+
+impl ClientBootstrapModule for Module {
+    type Config = Config;
+
+    async fn new(config: Self::Config, info: ClientBootstrapModuleInfo) -> anyhow::Result<Self> {
+        let provider = DynProvider::new(
+            ProviderBuilder::new()
+                .network::<AnyNetwork>()
+                .layer(CacheLayer::new(config.max_cache_size))
+                .connect(&config.rpc_url)
+                .await?,
+        );
+
+        let l2_chain_id = ChainId::new(provider.get_chain_id().await?.to_string());
+
+        info.ensure_chain_id(l2_chain_id.to_string())?;
+        info.ensure_client_type(ClientType::SCROLL)?;
+
+        Ok(Self {
+            chain_id: l2_chain_id,
+            rollup_contract_address: config.rollup_contract_address,
+            rollup_committed_batches_slot: config.rollup_committed_batches_slot,
+            rollup_finalized_state_roots_slot: config.rollup_finalized_state_roots_slot,
+            rollup_last_finalized_batch_index_slot: config.rollup_last_finalized_batch_index_slot,
+            ibc_handler_address: config.ibc_handler_address,
+            provider,
+            scroll_rpc_client: scroll_rpc::JsonRpcClient::new(&config.rpc_url).await?,
+        })
+    }
+}
+
+#[async_trait]
+impl ClientBootstrapModuleServer for Module {
+    #[instrument(skip_all, fields(chain_id = %self.chain_id, %height))]
+    async fn self_client_state(
+        &self,
+        _: &Extensions,
+        height: Height,
+        config: Value,
+    ) -> RpcResult<Value> {
+        let config = serde_json::from_value::<ClientStateConfig>(config).map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!(
+                    "unable to deserialize client state config: {}",
+                    ErrorReporter(err)
+                ),
+                None::<()>,
+            )
+        })?;
+
+        Ok(into_value(ClientState {
+            chain_id: self
+                .chain_id
+                .as_str()
+                .parse()
+                .expect("self.chain_id is a valid u64; qed;"),
+            latest_height: height.height(),
+            l1_client_id: config.l1_client_id,
+            frozen_height: Height::new(0),
+            ibc_contract_address: self.ibc_handler_address,
+            l2_committed_batches_slot: self.rollup_committed_batches_slot,
+            l2_contract_address: self.rollup_contract_address,
+            l2_finalized_state_roots_slot: self.rollup_finalized_state_roots_slot,
+            latest_batch_index_slot: self.rollup_last_finalized_batch_index_slot,
+        }))
+    }
+
+    /// The consensus state on this chain at the specified `Height`.
+    #[instrument(skip_all, fields(chain_id = %self.chain_id, %height))]
+    async fn self_consensus_state(
+        &self,
+        _: &Extensions,
+        height: Height,
+        _: Value,
+    ) -> RpcResult<Value> {
+        let l2_block = self
+            .provider
+            .get_block(height.height().into())
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching l2 block"),
+                    None::<()>,
+                )
+            })?
+            .ok_or_else(|| {
+                ErrorObject::owned(-1, format!("l2 block {height} not found"), None::<()>)
+            })?;
+
+        let ibc_account_proof = self
+            .scroll_rpc_client
+            .get_proof(
+                self.ibc_handler_address,
+                [],
+                scroll_rpc::BlockId::Number(l2_block.header.number),
+            )
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching ibc account proof"),
+                    None::<()>,
+                )
+            })?;
+
+        Ok(into_value(ConsensusState {
+            state_root: l2_block.header.state_root.into(),
+            timestamp: Timestamp::from_secs(l2_block.header.timestamp),
+            ibc_storage_root: ibc_account_proof.storage_hash,
+        }))
+    }
+}
@@ -0,0 +1,303 @@
+use berachain_light_client_types::{ClientState, ConsensusState, Header};
+use ethereum_light_client_types::StorageProof;
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::instrument;
+use unionlabs::{
+    self,
+    encoding::{Bincode, DecodeAs, EncodeAs, EthAbi},
+    ibc::core::client::height::Height,
+    primitives::Bytes,
+    ErrorReporter,
+};
+use voyager_sdk::{
+    anyhow,
+    plugin::ClientModule,
+    primitives::{
+        ChainId, ClientStateMeta, ClientType, ConsensusStateMeta, ConsensusType, IbcInterface,
+    },
+    rpc::{types::ClientModuleInfo, ClientModuleServer, FATAL_JSONRPC_ERROR_CODE},
+};
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    Module::run().await
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {}
+
+impl ClientModule for Module {
+    type Config = Config;
+
+    async fn new(Config {}: Self::Config, info: ClientModuleInfo) -> anyhow::Result<Self> {
+        info.ensure_client_type(ClientType::BERACHAIN)?;
+        info.ensure_consensus_type(ConsensusType::BEACON_KIT)?;
+        info.ensure_ibc_interface(IbcInterface::IBC_COSMWASM)?;
+
+        Ok(Self {})
+    }
+}
+
+impl Module {
+    pub fn decode_consensus_state(consensus_state: &[u8]) -> RpcResult<ConsensusState> {
+        ConsensusState::decode_as::<EthAbi>(consensus_state).map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("unable to decode consensus state: {}", ErrorReporter(err)),
+                None::<()>,
+            )
+        })
+    }
+
+    pub fn decode_client_state(client_state: &[u8]) -> RpcResult<ClientState> {
+        ClientState::decode_as::<Bincode>(client_state).map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("unable to decode client state: {err}"),
+                None::<()>,
+            )
+        })
+    }
+
+    pub fn make_height(revision_height: u64) -> Height {
+        Height::new(revision_height)
+    }
+}
+
+#[async_trait]
+impl ClientModuleServer for Module {
+    #[instrument]
+    async fn decode_client_state_meta(
+        &self,
+        _: &Extensions,
+        client_state: Bytes,
+    ) -> RpcResult<ClientStateMeta> {
+        let cs = Module::decode_client_state(&client_state)?;
+
+        Ok(ClientStateMeta {
+            counterparty_chain_id: ChainId::new(cs.chain_id.to_string()),
+            counterparty_height: Module::make_height(cs.latest_height),
+        })
+    }
+
+    #[instrument]
+    async fn decode_consensus_state_meta(
+        &self,
+        _: &Extensions,
+        consensus_state: Bytes,
+    ) -> RpcResult<ConsensusStateMeta> {
+        let cs = Module::decode_consensus_state(&consensus_state)?;
+
+        Ok(ConsensusStateMeta {
+            timestamp: cs.timestamp,
+        })
+    }
+
+    #[instrument]
+    async fn decode_client_state(&self, _: &Extensions, client_state: Bytes) -> RpcResult<Value> {
+        Ok(serde_json::to_value(Module::decode_client_state(&client_state)?).unwrap())
+    }
+
+    #[instrument]
+    async fn decode_consensus_state(
+        &self,
+        _: &Extensions,
+        consensus_state: Bytes,
+    ) -> RpcResult<Value> {
+        Ok(serde_json::to_value(Module::decode_consensus_state(&consensus_state)?).unwrap())
+    }
+
+    #[instrument]
+    async fn encode_client_state(
+        &self,
+        _: &Extensions,
+        client_state: Value,
+        metadata: Value,
+    ) -> RpcResult<Bytes> {
+        if !metadata.is_null() {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                "metadata was provided, but this client type does not require \
+                metadata for client state encoding",
+                Some(json!({
+                    "provided_metadata": metadata,
+                })),
+            ));
+        }
+
+        serde_json::from_value::<ClientState>(client_state)
+            .map_err(|err| {
+                ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!("unable to deserialize client state: {}", ErrorReporter(err)),
+                    None::<()>,
+                )
+            })
+            .map(|cs| cs.encode_as::<Bincode>())
+            .map(Into::into)
+    }
+
+    #[instrument]
+    async fn encode_consensus_state(
+        &self,
+        _: &Extensions,
+        consensus_state: Value,
+    ) -> RpcResult<Bytes> {
+        serde_json::from_value::<ConsensusState>(consensus_state)
+            .map_err(|err| {
+                ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!(
+                        "unable to deserialize consensus state: {}",
+                        ErrorReporter(err)
+                    ),
+                    None::<()>,
+                )
+            })
+            .map(|cs| cs.encode_as::<EthAbi>())
+            .map(Into::into)
+    }
+
+    #[instrument]
+    async fn encode_header(&self, _: &Extensions, header: Value) -> RpcResult<Bytes> {
+        serde_json::from_value::<Header>(header)
+            .map_err(|err| {
+                ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!("unable to deserialize header: {}", ErrorReporter(err)),
+                    None::<()>,
+                )
+            })
+            .map(|header| header.encode_as::<Bincode>())
+            .map(Into::into)
+    }
+
+    #[instrument]
+    async fn encode_proof(&self, _: &Extensions, proof: Value) -> RpcResult<Bytes> {
+        serde_json::from_value::<StorageProof>(proof)
+            .map_err(|err| {
+                ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!("unable to deserialize proof: {}", ErrorReporter(err)),
+                    None::<()>,
+                )
+            })
+            .map(|storage_proof| storage_proof.encode_as::<Bincode>())
+            .map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use ibc_union_spec::{ClientId, Timestamp};
+    use unionlabs::primitives::{H160, H256};
+
+    use super::*;
+
+    fn client_state() -> ClientState {
+        ClientState {
+            l1_client_id: ClientId::from_raw(1).unwrap(),
+            chain_id: 80094_u64.into(),
+            latest_height: 100,
+            ibc_contract_address: H160::new([0xAA; 20]),
+        }
+    }
+
+    fn consensus_state() -> ConsensusState {
+        ConsensusState {
+            timestamp: Timestamp::from_secs(1_700_000_000),
+            state_root: H256::new([0x01; 32]),
+            storage_root: H256::new([0x02; 32]),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_state_round_trip() {
+        let module = Module {};
+
+        let encoded = module
+            .encode_client_state(
+                &Extensions::new(),
+                serde_json::to_value(client_state()).unwrap(),
+                Value::Null,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Module::decode_client_state(&encoded).unwrap(),
+            client_state()
+        );
+        assert_eq!(
+            module
+                .decode_client_state_meta(&Extensions::new(), encoded)
+                .await
+                .unwrap(),
+            ClientStateMeta {
+                counterparty_chain_id: ChainId::new("80094"),
+                counterparty_height: Height::new(100),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn encode_client_state_rejects_metadata() {
+        let err = Module {}
+            .encode_client_state(
+                &Extensions::new(),
+                serde_json::to_value(client_state()).unwrap(),
+                json!({ "some": "metadata" }),
+            )
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.code(), FATAL_JSONRPC_ERROR_CODE);
+    }
+
+    #[tokio::test]
+    async fn consensus_state_round_trip() {
+        let module = Module {};
+
+        let encoded = module
+            .encode_consensus_state(
+                &Extensions::new(),
+                serde_json::to_value(consensus_state()).unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            Module::decode_consensus_state(&encoded).unwrap(),
+            consensus_state()
+        );
+        assert_eq!(
+            module
+                .decode_consensus_state_meta(&Extensions::new(), encoded)
+                .await
+                .unwrap(),
+            ConsensusStateMeta {
+                timestamp: consensus_state().timestamp,
+            }
+        );
+    }
+
+    #[test]
+    fn decode_invalid_client_state() {
+        assert_eq!(
+            Module::decode_client_state(&[0xFF; 4]).unwrap_err().code(),
+            FATAL_JSONRPC_ERROR_CODE
+        );
+    }
+}
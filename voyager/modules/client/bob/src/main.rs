@@ -1,4 +1,4 @@
-use bob_light_client_types::{ClientState, ConsensusState, Header};
+use bob_light_client_types::{ClientState, ConsensusState, Header, Misbehaviour};
 use ethereum_light_client_types::StorageProof;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
@@ -16,7 +16,7 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow,
+    anyhow, encode_misbehaviour,
     plugin::ClientModule,
     primitives::{
         ChainId, ClientStateMeta, ClientType, ConsensusStateMeta, ConsensusType, IbcInterface,
@@ -195,4 +195,12 @@ impl ClientModuleServer for Module {
             .map(|storage_proof| storage_proof.encode_as::<Bincode>())
             .map(Into::into)
     }
+
+    #[instrument(skip_all)]
+    async fn encode_misbehaviour(&self, _: &Extensions, misbehaviour: Value) -> RpcResult<Bytes> {
+        encode_misbehaviour::<Bincode, Header, _>(misbehaviour, |header_a, header_b| Misbehaviour {
+            header_a,
+            header_b,
+        })
+    }
 }
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sui_light_client_types::{
     client_state::ClientState, consensus_state::ConsensusState, header::Header,
-    storage_proof::StorageProof,
+    misbehaviour::Misbehaviour, storage_proof::StorageProof, CertifiedCheckpointSummary,
 };
 use tracing::instrument;
 use unionlabs::{
@@ -18,7 +18,7 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_sdk::{
-    anyhow, ensure_null,
+    anyhow, encode_misbehaviour, ensure_null,
     plugin::ClientModule,
     primitives::{
         ChainId, ClientStateMeta, ClientType, ConsensusStateMeta, ConsensusType, IbcInterface,
@@ -192,4 +192,18 @@ impl ClientModuleServer for Module {
             .map(|cs| cs.encode_as::<Bincode>())
             .map(Into::into)
     }
+
+    #[instrument(skip_all)]
+    async fn encode_misbehaviour(&self, _: &Extensions, misbehaviour: Value) -> RpcResult<Bytes> {
+        encode_misbehaviour::<Bincode, Header, _>(misbehaviour, |header_a, header_b| Misbehaviour {
+            checkpoint_a: CertifiedCheckpointSummary {
+                data: header_a.checkpoint_summary,
+                auth_signature: header_a.sign_info,
+            },
+            checkpoint_b: CertifiedCheckpointSummary {
+                data: header_b.checkpoint_summary,
+                auth_signature: header_b.sign_info,
+            },
+        })
+    }
 }
@@ -6,7 +6,7 @@ use jsonrpsee::{
 use macros::model;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tendermint_light_client_types::{ClientState, ConsensusState, Header};
+use tendermint_light_client_types::{ClientState, ConsensusState, Header, Misbehaviour};
 use tracing::{debug, instrument};
 use unionlabs::{
     self,
@@ -17,6 +17,7 @@ use unionlabs::{
 };
 use voyager_sdk::{
     anyhow::{self, anyhow},
+    encode_misbehaviour,
     plugin::ClientModule,
     primitives::{
         ChainId, ClientStateMeta, ClientType, ConsensusStateMeta, ConsensusType, IbcInterface,
@@ -272,4 +273,23 @@ impl ClientModuleServer for Module {
                 SupportedIbcInterface::IbcCosmwasm => cs.encode_as::<Bincode>().into(),
             })
     }
+
+    #[instrument(skip_all)]
+    async fn encode_misbehaviour(&self, _: &Extensions, misbehaviour: Value) -> RpcResult<Bytes> {
+        if !matches!(self.ibc_interface, SupportedIbcInterface::IbcCosmwasm) {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!(
+                    "misbehaviour is only supported for {}",
+                    IbcInterface::IBC_COSMWASM
+                ),
+                None::<()>,
+            ));
+        }
+
+        encode_misbehaviour::<Bincode, Header, _>(misbehaviour, |header_a, header_b| Misbehaviour {
+            header_a,
+            header_b,
+        })
+    }
 }
@@ -156,11 +156,12 @@ impl FinalityModuleServer for Module {
             .await
         {
             Ok(block) => {
-                let timestamp = block.inner().block_timestamp.0;
+                // block timestamps are in microseconds
+                let timestamp = Timestamp::from_nanos(block.inner().block_timestamp.0 * 1_000);
 
                 debug!(%timestamp, %latest_height, "latest timestamp");
 
-                Ok(Timestamp::from_nanos(timestamp))
+                Ok(timestamp)
             }
             Err(err) => Err(ErrorObject::owned(
                 -1,
@@ -91,20 +91,19 @@ impl Module {
         Height::new(height)
     }
 
-    pub async fn ledger_version_of_height(&self, height: u64) -> u64 {
+    pub async fn ledger_version_of_height(&self, height: u64) -> RpcResult<u64> {
         let ledger_version = self
             .aptos_client
             .get_block_by_height(height, false)
             .await
-            // .map_err(rest_error_to_rpc_error)?
-            .unwrap()
+            .map_err(rest_error_to_rpc_error)?
             .into_inner()
             .last_version
             .0;
 
         debug!("height {height} is ledger version {ledger_version}");
 
-        ledger_version
+        Ok(ledger_version)
     }
 }
 
@@ -115,23 +114,23 @@ impl ProofModuleServer<IbcUnion> for Module {
         &self,
         _: &Extensions,
         at: Height,
-        _path: StorePath,
+        path: StorePath,
     ) -> RpcResult<Option<(Value, ProofType)>> {
-        let ledger_version = self.ledger_version_of_height(at.height()).await;
+        let ledger_version = self.ledger_version_of_height(at.height()).await?;
 
         let vault_addr = self
             .get_vault_addr(self.ibc_handler_address.into(), Some(ledger_version))
             .await
-            .unwrap();
+            .map_err(rest_error_to_rpc_error)?;
 
-        let _address_str = self
+        let commitments_handle_str = self
             .aptos_client
             .get_account_resource(
                 vault_addr.into(),
                 &format!("{}::ibc::IBCStore", self.ibc_handler_address),
             )
             .await
-            .unwrap()
+            .map_err(rest_error_to_rpc_error)?
             .into_inner()
             .unwrap()
             .data["commitments"]["handle"]
@@ -139,28 +138,32 @@ impl ProofModuleServer<IbcUnion> for Module {
             .as_str()
             .unwrap()
             .to_owned();
-        let _address = <H256>::new(U256::from_be_hex(_address_str).unwrap().to_be_bytes());
-
-        // NOTE(aeryz): This only works with Union's custom Movement node. When the following PR is merged,
-        // we will uncomment this: https://github.com/movementlabsxyz/movement/pull/645
-        // let storage_proof = get_storage_proof(
-        //     &self.ctx.movement_rpc_url,
-        //     address,
-        //     hex::encode(bcs::to_bytes(&path.to_string().as_bytes()).expect("won't fail")),
-        //     at.revision_height,
-        // ).await;
-
-        Ok(Some((
-            into_value(StorageProof {
-                state_value: None,
-                proof: SparseMerkleProof {
-                    leaf: None,
-                    siblings: Vec::new(),
-                },
-            }),
-            // TODO: Implement properly, see above
-            ProofType::Membership,
-        )))
+        let commitments_handle = <H256>::new(
+            U256::from_be_hex(commitments_handle_str)
+                .unwrap()
+                .to_be_bytes(),
+        );
+
+        // NOTE(aeryz): This only works with Union's custom Movement node, see https://github.com/movementlabsxyz/movement/pull/645
+        let storage_proof = get_storage_proof(
+            &self.movement_rpc_url,
+            commitments_handle,
+            // the commitments table is a `Table<vector<u8>, vector<u8>>`, keyed by the commitment key of the path
+            hex::encode(bcs::to_bytes(path.key().get().as_slice()).expect("won't fail")),
+            at.height(),
+        )
+        .await;
+
+        // the proof of a key that does not exist in the table is a non-inclusion proof in the sparse merkle tree
+        let proof_type = if storage_proof.state_value.is_some() {
+            ProofType::Membership
+        } else {
+            ProofType::NonMembership
+        };
+
+        debug!(?path, ?proof_type, "fetched storage proof");
+
+        Ok(Some((into_value(storage_proof), proof_type)))
     }
 }
 
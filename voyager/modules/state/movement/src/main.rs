@@ -97,20 +97,19 @@ impl Module {
         Height::new(height)
     }
 
-    pub async fn ledger_version_of_height(&self, height: u64) -> u64 {
+    pub async fn ledger_version_of_height(&self, height: u64) -> RpcResult<u64> {
         let ledger_version = self
             .aptos_client
             .get_block_by_height(height, false)
             .await
-            // .map_err(rest_error_to_rpc_error)?
-            .unwrap()
+            .map_err(rest_error_to_rpc_error)?
             .into_inner()
             .last_version
             .0;
 
         debug!("height {height} is ledger version {ledger_version}");
 
-        ledger_version
+        Ok(ledger_version)
     }
 
     /// Query the latest finalized height of this chain.
@@ -143,11 +142,12 @@ impl Module {
             .await
         {
             Ok(block) => {
-                let timestamp = block.inner().block_timestamp.0;
+                // block timestamps are in microseconds
+                let timestamp = Timestamp::from_nanos(block.inner().block_timestamp.0 * 1_000);
 
                 debug!(%timestamp, %latest_height, "latest timestamp");
 
-                Ok(Timestamp::from_nanos(timestamp))
+                Ok(timestamp)
             }
             Err(err) => Err(ErrorObject::owned(
                 -1,
@@ -187,7 +187,7 @@ impl StateModuleServer<IbcUnion> for Module {
         at: Height,
         path: StorePath,
     ) -> RpcResult<Value> {
-        let ledger_version = self.ledger_version_of_height(at.height()).await;
+        let ledger_version = self.ledger_version_of_height(at.height()).await?;
 
         Ok(match path {
             StorePath::ClientState(path) => {
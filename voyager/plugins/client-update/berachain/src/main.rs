@@ -104,7 +104,7 @@ impl Plugin for Module {
             name: plugin_name(&config.l2_chain_id),
             interest_filter: UpdateHook::filter(
                 &config.l2_chain_id,
-                &ClientType::new(ClientType::BEACON_KIT),
+                &ClientType::new(ClientType::BERACHAIN),
             ),
         }
     }
@@ -174,7 +174,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                 .map(|mut op| {
                     UpdateHook::new(
                         &self.l2_chain_id,
-                        &ClientType::new(ClientType::BEACON_KIT),
+                        &ClientType::new(ClientType::BERACHAIN),
                         |fetch| {
                             Call::Plugin(PluginMessage::new(
                                 self.plugin_name(),
@@ -252,7 +252,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                 // Recursively dispatch a L1 update before dispatching the L2 update.
                 Ok(conc([
                     call(FetchUpdateHeaders {
-                        client_type: ClientType::new(ClientType::BEACON_KIT),
+                        client_type: ClientType::new(ClientType::TENDERMINT),
                         counterparty_chain_id: counterparty_chain_id.clone(),
                         chain_id: self.l1_chain_id.clone(),
                         client_id: RawClientId::new(self.l1_client_id),
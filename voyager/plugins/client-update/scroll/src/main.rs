@@ -0,0 +1,584 @@
+// #![warn(clippy::unwrap_used)]
+
+use std::collections::VecDeque;
+
+use alloy::{
+    network::AnyNetwork,
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::EIP1186StorageProof,
+};
+use ethereum_light_client_types::{AccountProof, StorageProof};
+use ibc_union_spec::{path::ClientStatePath, ClientId, IbcUnion};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use scroll_api::{BatchResponseData, ScrollClient};
+use scroll_light_client_types::{ClientState, Header};
+use scroll_verifier::mapping_index_to_slot_key;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, instrument};
+use unionlabs::{ibc::core::client::height::Height, never::Never, primitives::U256, ErrorReporter};
+use voyager_sdk::{
+    anyhow,
+    hook::UpdateHook,
+    into_value,
+    message::{
+        call::{Call, FetchUpdateHeaders, WaitForHeightRelative, WaitForTrustedHeight},
+        callback::AggregateSubmitTxFromOrderedHeaders,
+        data::{Data, DecodedHeaderMeta, OrderedHeaders},
+        PluginMessage, VoyagerMessage,
+    },
+    plugin::Plugin,
+    primitives::{ChainId, ClientType, IbcSpec, QueryHeight},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
+    types::RawClientId,
+    vm::{call, conc, data, pass::PassResult, promise, seq, Op, Visit},
+    DefaultCmd, ExtensionsExt, VoyagerClient,
+};
+
+use crate::call::{FetchL2Update, FetchUpdate, ModuleCall};
+
+pub mod call;
+
+#[tokio::main]
+async fn main() {
+    Module::run().await
+}
+
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub chain_id: ChainId,
+
+    pub l1_chain_id: ChainId,
+
+    pub l1_provider: DynProvider,
+    pub l2_provider: DynProvider<AnyNetwork>,
+
+    pub scroll_rpc_client: scroll_rpc::JsonRpcClient,
+    pub scroll_api_client: ScrollClient,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub l2_chain_id: ChainId,
+
+    /// The chain id of the chain this scroll chain settles on.
+    pub l1_chain_id: ChainId,
+
+    /// The RPC endpoint for the settlement (L1) execution chain.
+    pub l1_rpc_url: String,
+
+    /// The websocket RPC endpoint for the main (L2) execution chain. This is also used to fetch the zktrie account proofs, which can't be decoded by alloy.
+    pub l2_rpc_url: String,
+
+    /// The base url of the scroll indexer api, used to map batch indices to L2 blocks and finalization transactions.
+    pub scroll_api: String,
+}
+
+fn plugin_name(chain_id: &ChainId) -> String {
+    pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+    format!("{PLUGIN_NAME}/{}", chain_id)
+}
+
+impl Module {
+    fn plugin_name(&self) -> String {
+        plugin_name(&self.chain_id)
+    }
+}
+
+impl Plugin for Module {
+    type Call = ModuleCall;
+    type Callback = Never;
+
+    type Config = Config;
+    type Cmd = DefaultCmd;
+
+    async fn new(config: Self::Config) -> anyhow::Result<Self> {
+        let l1_provider =
+            DynProvider::new(ProviderBuilder::new().connect(&config.l1_rpc_url).await?);
+
+        let l2_provider = DynProvider::new(
+            ProviderBuilder::new()
+                .network::<AnyNetwork>()
+                .connect(&config.l2_rpc_url)
+                .await?,
+        );
+
+        let l1_chain_id = ChainId::new(l1_provider.get_chain_id().await?.to_string());
+        let l2_chain_id = ChainId::new(l2_provider.get_chain_id().await?.to_string());
+
+        assert_eq!(l1_chain_id, config.l1_chain_id);
+        assert_eq!(l2_chain_id, config.l2_chain_id);
+
+        Ok(Self {
+            chain_id: l2_chain_id,
+            l1_chain_id,
+            l1_provider,
+            l2_provider,
+            scroll_rpc_client: scroll_rpc::JsonRpcClient::new(&config.l2_rpc_url).await?,
+            scroll_api_client: ScrollClient::new(config.scroll_api),
+        })
+    }
+
+    fn info(config: Self::Config) -> PluginInfo {
+        PluginInfo {
+            name: plugin_name(&config.l2_chain_id),
+            interest_filter: UpdateHook::filter(
+                &config.l2_chain_id,
+                &ClientType::new(ClientType::SCROLL),
+            ),
+        }
+    }
+
+    async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
+        match cmd {}
+    }
+}
+
+#[async_trait]
+impl PluginServer<ModuleCall, Never> for Module {
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn run_pass(
+        &self,
+        _: &Extensions,
+        msgs: Vec<Op<VoyagerMessage>>,
+    ) -> RpcResult<PassResult<VoyagerMessage>> {
+        Ok(PassResult {
+            optimize_further: vec![],
+            ready: msgs
+                .into_iter()
+                .map(|mut op| {
+                    UpdateHook::new(
+                        &self.chain_id,
+                        &ClientType::new(ClientType::SCROLL),
+                        |fetch| {
+                            Call::Plugin(PluginMessage::new(
+                                self.plugin_name(),
+                                ModuleCall::from(FetchUpdate {
+                                    from_height: fetch.update_from,
+                                    to_height: fetch.update_to,
+                                    counterparty_chain_id: fetch.counterparty_chain_id.clone(),
+                                    client_id: fetch.client_id.clone(),
+                                }),
+                            ))
+                        },
+                    )
+                    .visit_op(&mut op);
+
+                    op
+                })
+                .enumerate()
+                .map(|(i, op)| (vec![i], op))
+                .collect(),
+        })
+    }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
+        match msg {
+            ModuleCall::FetchUpdate(FetchUpdate {
+                from_height,
+                to_height,
+                counterparty_chain_id,
+                client_id,
+            }) => {
+                self.fetch_update(
+                    e.voyager_client()?,
+                    from_height,
+                    to_height,
+                    counterparty_chain_id,
+                    client_id,
+                )
+                .await
+            }
+            ModuleCall::FetchL2Update(FetchL2Update {
+                update_from,
+                counterparty_chain_id,
+                client_id,
+            }) => {
+                self.fetch_l2_update(
+                    e.voyager_client()?,
+                    update_from,
+                    counterparty_chain_id,
+                    client_id,
+                )
+                .await
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields(chain_id = %self.chain_id))]
+    async fn callback(
+        &self,
+        _: &Extensions,
+        cb: Never,
+        _data: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        match cb {}
+    }
+}
+
+impl Module {
+    async fn scroll_client_state(
+        &self,
+        voyager_client: &VoyagerClient,
+        counterparty_chain_id: &ChainId,
+        client_id: ClientId,
+    ) -> RpcResult<ClientState> {
+        let counterparty_latest_height = voyager_client
+            .query_latest_height(counterparty_chain_id.clone(), false)
+            .await?;
+
+        let scroll_client_state_raw = voyager_client
+            .query_ibc_state(
+                counterparty_chain_id.clone(),
+                QueryHeight::Specific(counterparty_latest_height),
+                ClientStatePath { client_id },
+            )
+            .await?;
+
+        let scroll_client_info = voyager_client
+            .client_info::<IbcUnion>(counterparty_chain_id.clone(), client_id)
+            .await?;
+
+        voyager_client
+            .decode_client_state::<IbcUnion, ClientState>(
+                scroll_client_info.client_type,
+                scroll_client_info.ibc_interface,
+                scroll_client_state_raw,
+            )
+            .await
+    }
+
+    /// Fetch the last batch finalized on the L1 as of the specified ***L1*** block number.
+    async fn finalized_batch_of_l1_height(
+        &self,
+        client_state: &ClientState,
+        l1_block_number: u64,
+    ) -> RpcResult<BatchResponseData> {
+        scroll_client::finalized_batch_of_l1_height(
+            &self.l1_provider,
+            &self.scroll_api_client,
+            client_state.l2_contract_address,
+            client_state.latest_batch_index_slot,
+            l1_block_number,
+        )
+        .await
+        .map_err(|e| {
+            ErrorObject::owned(
+                -1,
+                ErrorReporter(&*e).with_message("error fetching finalized batch of l1 height"),
+                None::<()>,
+            )
+        })
+    }
+
+    #[instrument(
+        skip_all,
+        fields(
+            chain_id = %self.chain_id,
+            %counterparty_chain_id,
+            %update_from,
+            %update_to,
+            %client_id,
+        )
+    )]
+    async fn fetch_update(
+        &self,
+        voyager_client: &VoyagerClient,
+        update_from: Height,
+        update_to: Height,
+        counterparty_chain_id: ChainId,
+        client_id: RawClientId,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let client_id = client_id.decode_spec::<IbcUnion>().map_err(|e| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                ErrorReporter(e).with_message("invalid client id"),
+                None::<()>,
+            )
+        })?;
+
+        let scroll_client_state = self
+            .scroll_client_state(voyager_client, &counterparty_chain_id, client_id)
+            .await?;
+
+        // the client on the counterparty chain tracking the L1 that the L2 being tracked by the client we're updating settles on
+        let l1_client_meta = voyager_client
+            .client_state_meta::<IbcUnion>(
+                counterparty_chain_id.clone(),
+                QueryHeight::Latest,
+                scroll_client_state.l1_client_id,
+            )
+            .await?;
+
+        let l1_client_info = voyager_client
+            .client_info::<IbcUnion>(
+                counterparty_chain_id.clone(),
+                scroll_client_state.l1_client_id,
+            )
+            .await?;
+
+        let l1_latest_height = voyager_client
+            .query_latest_height(l1_client_meta.counterparty_chain_id.clone(), true)
+            .await?;
+
+        let batch = self
+            .finalized_batch_of_l1_height(&scroll_client_state, l1_latest_height.height())
+            .await?;
+
+        info!(
+            index = batch.index,
+            end_block_number = batch.end_block_number,
+            "finalized batch"
+        );
+
+        if batch.end_block_number == scroll_client_state.latest_height {
+            info!("update is a noop, l2 client will not be updated either");
+
+            return Ok(data(OrderedHeaders { headers: vec![] }));
+        }
+
+        Ok(conc([
+            promise(
+                [call(FetchUpdateHeaders {
+                    client_type: l1_client_info.client_type,
+                    chain_id: l1_client_meta.counterparty_chain_id.clone(),
+                    counterparty_chain_id: counterparty_chain_id.clone(),
+                    client_id: RawClientId::new(scroll_client_state.l1_client_id),
+                    update_from: l1_client_meta.counterparty_height,
+                    update_to: l1_latest_height,
+                })],
+                [],
+                AggregateSubmitTxFromOrderedHeaders {
+                    ibc_spec_id: IbcUnion::ID,
+                    chain_id: counterparty_chain_id.clone(),
+                    client_id: RawClientId::new(scroll_client_state.l1_client_id),
+                },
+            ),
+            seq([
+                call(WaitForTrustedHeight {
+                    chain_id: counterparty_chain_id.clone(),
+                    ibc_spec_id: IbcUnion::ID,
+                    client_id: RawClientId::new(scroll_client_state.l1_client_id),
+                    height: l1_latest_height,
+                    finalized: false,
+                }),
+                // wait for 1 extra block to ensure that the L1 update is in state, and this update will not end up in the same block (and potentially get reordered)
+                call(WaitForHeightRelative {
+                    chain_id: counterparty_chain_id.clone(),
+                    height_diff: 1,
+                    finalized: false,
+                }),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::from(FetchL2Update {
+                        update_from,
+                        counterparty_chain_id,
+                        client_id,
+                    }),
+                )),
+            ]),
+        ]))
+    }
+
+    #[instrument(
+        skip_all,
+        fields(
+            chain_id = %self.chain_id,
+            %counterparty_chain_id,
+            %update_from,
+            %client_id,
+        )
+    )]
+    async fn fetch_l2_update(
+        &self,
+        voyager_client: &VoyagerClient,
+        update_from: Height,
+        counterparty_chain_id: ChainId,
+        client_id: ClientId,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let scroll_client_state = self
+            .scroll_client_state(voyager_client, &counterparty_chain_id, client_id)
+            .await?;
+
+        debug!(?scroll_client_state, "scroll client state");
+
+        // the client on the counterparty chain tracking the L1 that the L2 being tracked by the client we're updating settles on
+        let l1_client_meta = voyager_client
+            .client_state_meta::<IbcUnion>(
+                counterparty_chain_id.clone(),
+                QueryHeight::Latest,
+                scroll_client_state.l1_client_id,
+            )
+            .await?;
+
+        debug!(
+            %l1_client_meta.counterparty_height,
+            %l1_client_meta.counterparty_chain_id,
+            "l1 client meta"
+        );
+
+        let l1_height = l1_client_meta.counterparty_height.height();
+
+        let batch = self
+            .finalized_batch_of_l1_height(&scroll_client_state, l1_height)
+            .await?;
+
+        debug!(?batch, "finalized batch");
+
+        if batch.end_block_number < update_from.height() {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!(
+                    "attempted to update to a height ({to_height}) \
+                    < the intended update_from height {update_from}",
+                    to_height = batch.end_block_number
+                ),
+                None::<()>,
+            ));
+        }
+
+        if batch.end_block_number == scroll_client_state.latest_height {
+            info!("update is a noop");
+
+            return Ok(data(OrderedHeaders { headers: vec![] }));
+        }
+
+        let finalize_tx_hash = batch.finalize_tx_hash.ok_or_else(|| {
+            ErrorObject::owned(
+                -1,
+                format!("batch {} has no finalize tx hash", batch.index),
+                None::<()>,
+            )
+        })?;
+
+        let batch_header =
+            scroll_client::batch_header_of_finalize_tx(&self.l1_provider, finalize_tx_hash)
+                .await
+                .map_err(|e| {
+                    ErrorObject::owned(
+                        -1,
+                        ErrorReporter(&*e).with_message("error fetching batch header"),
+                        None::<()>,
+                    )
+                })?;
+
+        let last_chunk_proof = scroll_client::last_chunk_proof_of_commit_tx(
+            &self.l1_provider,
+            &self.l2_provider,
+            batch.commit_tx_hash,
+        )
+        .await
+        .map_err(|e| {
+            ErrorObject::owned(
+                -1,
+                ErrorReporter(&*e).with_message("error building last chunk proof"),
+                None::<()>,
+            )
+        })?;
+
+        let batch_index = U256::from(batch.index);
+
+        let l1_rollup_proof = self
+            .l1_provider
+            .get_proof(
+                scroll_client_state.l2_contract_address.into(),
+                vec![
+                    scroll_client_state
+                        .latest_batch_index_slot
+                        .to_be_bytes()
+                        .into(),
+                    mapping_index_to_slot_key(
+                        scroll_client_state.l2_finalized_state_roots_slot,
+                        batch_index,
+                    )
+                    .to_be_bytes()
+                    .into(),
+                    mapping_index_to_slot_key(
+                        scroll_client_state.l2_committed_batches_slot,
+                        batch_index,
+                    )
+                    .to_be_bytes()
+                    .into(),
+                ],
+            )
+            .block_id(l1_height.into())
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching rollup contract proof"),
+                    None::<()>,
+                )
+            })?;
+
+        debug!(storage_hash = %l1_rollup_proof.storage_hash, "fetched rollup account proof");
+
+        let [last_batch_index_proof, l2_state_root_proof, batch_hash_proof]: [_; 3] =
+            l1_rollup_proof
+                .storage_proof
+                .try_into()
+                .expect("requested three storage proofs; qed;");
+
+        let l2_ibc_account_proof = self
+            .scroll_rpc_client
+            .get_proof(
+                scroll_client_state.ibc_contract_address,
+                [],
+                scroll_rpc::BlockId::Number(batch.end_block_number),
+            )
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching ibc account proof"),
+                    None::<()>,
+                )
+            })?;
+
+        Ok(data(OrderedHeaders {
+            headers: vec![(
+                DecodedHeaderMeta {
+                    height: Height::new(batch.end_block_number),
+                },
+                into_value(Header {
+                    l1_height: l1_client_meta.counterparty_height,
+                    l1_account_proof: AccountProof {
+                        storage_root: l1_rollup_proof.storage_hash.into(),
+                        proof: l1_rollup_proof
+                            .account_proof
+                            .into_iter()
+                            .map(|x| x.into())
+                            .collect(),
+                    },
+                    l2_state_root_proof: convert_storage_proof(l2_state_root_proof),
+                    last_batch_index_proof: convert_storage_proof(last_batch_index_proof),
+                    batch_hash_proof: convert_storage_proof(batch_hash_proof),
+                    l2_ibc_account_proof: AccountProof {
+                        storage_root: l2_ibc_account_proof.storage_hash,
+                        proof: l2_ibc_account_proof
+                            .account_proof
+                            .into_iter()
+                            .map(Into::into)
+                            .collect(),
+                    },
+                    batch_header,
+                    last_chunk_proof,
+                }),
+            )],
+        }))
+    }
+}
+
+fn convert_storage_proof(proof: EIP1186StorageProof) -> StorageProof {
+    StorageProof {
+        key: U256::from_be_bytes(proof.key.as_b256().0),
+        value: proof.value.into(),
+        proof: proof.proof.into_iter().map(|bytes| bytes.into()).collect(),
+    }
+}
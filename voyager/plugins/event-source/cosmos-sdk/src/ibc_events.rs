@@ -206,6 +206,12 @@ pub enum IbcEvent {
         counterparty_height: u64,
     },
 
+    #[serde(rename = "wasm-client_misbehaviour")]
+    WasmClientMisbehaviour {
+        #[serde(with = "serde_utils::string")]
+        client_id: ClientId,
+    },
+
     #[serde(rename = "wasm-connection_open_init")]
     WasmConnectionOpenInit {
         #[serde(with = "serde_utils::string")]
@@ -447,7 +453,7 @@ impl IbcEvent {
 
             IbcEvent::WasmCreateClient { .. } => "create_client",
             IbcEvent::WasmUpdateClient { .. } => "update_client",
-            // IbcEvent::UnionClientMisbehaviour{..} => "client_misbehaviour",
+            IbcEvent::WasmClientMisbehaviour { .. } => "client_misbehaviour",
             // IbcEvent::UnionSubmitEvidence{..} => "submit_evidence",
             IbcEvent::WasmConnectionOpenInit { .. } => "connection_open_init",
             IbcEvent::WasmConnectionOpenTry { .. } => "connection_open_try",
@@ -1261,6 +1261,32 @@ impl Module {
                     event,
                 )))
             }
+            IbcEvent::WasmClientMisbehaviour { client_id } => {
+                let client_info = voyager_client
+                    .client_info::<IbcUnion>(self.chain_id.clone(), client_id)
+                    .await?;
+
+                let client_state_meta = voyager_client
+                    .client_state_meta::<IbcUnion>(self.chain_id.clone(), height.into(), client_id)
+                    .await?;
+
+                let event = ibc_union_spec::event::ClientMisbehaviour {
+                    client_id,
+                    client_type: client_info.client_type.clone(),
+                }
+                .into();
+
+                ibc_union_spec::log_event(&event, &self.chain_id);
+
+                Ok(data(ChainEvent::new::<IbcUnion>(
+                    self.chain_id.clone(),
+                    client_info,
+                    client_state_meta.counterparty_chain_id,
+                    tx_hash,
+                    provable_height,
+                    event,
+                )))
+            }
             IbcEvent::WasmConnectionOpenInit {
                 connection_id,
                 client_id,
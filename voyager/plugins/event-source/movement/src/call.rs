@@ -11,16 +11,21 @@ pub enum ModuleCall {
     MakeFullEvent(MakeFullEvent),
 }
 
+/// Fetch a block at the specified height, requeuing a seq(wait(H+1), fetch(H+1)).
 #[model]
 pub struct FetchBlocks {
     pub height: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<u64>,
 }
 
+/// Fetch all events emitted by the IBC module in the block at `height`.
 #[model]
 pub struct FetchTransactions {
     pub height: u64,
 }
 
+/// Construct a full ChainEvent from the given Move event and associated metadata.
 #[model]
 pub struct MakeFullEvent {
     pub event: crate::events::IbcEvent,
@@ -17,6 +17,7 @@ pub enum IbcEvent {
     ChannelOpenConfirm(ibc::ChannelOpenConfirm),
     WriteAcknowledgement(ibc::WriteAck),
     RecvPacket(ibc::PacketRecv),
+    RecvIntentPacket(ibc::RecvIntentPacket),
     SendPacket(ibc::PacketSend),
     AcknowledgePacket(ibc::PacketAck),
     TimeoutPacket(ibc::TimeoutPacket),
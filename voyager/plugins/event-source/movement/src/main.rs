@@ -13,8 +13,8 @@ use ibc_union_spec::{
     event::{
         ChannelMetadata, ChannelOpenAck, ChannelOpenConfirm, ChannelOpenInit, ChannelOpenTry,
         ConnectionMetadata, ConnectionOpenAck, ConnectionOpenConfirm, ConnectionOpenInit,
-        ConnectionOpenTry, CreateClient, FullEvent, PacketAck, PacketMetadata, PacketRecv,
-        PacketSend, UpdateClient, WriteAck,
+        ConnectionOpenTry, CreateClient, FullEvent, IntentPacketRecv, PacketAck, PacketMetadata,
+        PacketRecv, PacketSend, PacketTimeout, UpdateClient, WriteAck,
     },
     path::{ChannelPath, ConnectionPath},
     ChannelId, ClientId, Connection, ConnectionState, IbcUnion, Timestamp,
@@ -27,7 +27,7 @@ use jsonrpsee::{
 use move_bindgen::MoveOutputType;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, instrument, warn};
 use unionlabs::{ibc::core::client::height::Height, never::Never, primitives::H256, ErrorReporter};
 use voyager_sdk::{
     anyhow,
@@ -40,7 +40,7 @@ use voyager_sdk::{
     },
     plugin::Plugin,
     primitives::{ChainId, ClientInfo, ClientType, IbcSpec, QueryHeight},
-    rpc::{types::PluginInfo, PluginServer},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
     vm::{call, conc, data, pass::PassResult, seq, Op},
     DefaultCmd, ExtensionsExt, VoyagerClient,
 };
@@ -98,7 +98,7 @@ impl Plugin for Module {
         PluginInfo {
             name: plugin_name(&config.chain_id),
             interest_filter: simple_take_filter(format!(
-                r#"[.. | ."@type"? == "fetch_blocks" and ."@value".chain_id == "{}"] | any"#,
+                r#"[.. | (."@type"? == "index" or ."@type"? == "index_range") and ."@value".chain_id == "{}"] | any"#,
                 config.chain_id
             )),
         }
@@ -146,20 +146,98 @@ impl Module {
         Height::new(height)
     }
 
-    pub async fn ledger_version_of_height(&self, height: u64) -> u64 {
+    pub async fn ledger_version_of_height(&self, height: u64) -> RpcResult<u64> {
         let ledger_version = self
             .aptos_client
             .get_block_by_height(height, false)
             .await
-            // .map_err(rest_error_to_rpc_error)?
-            .unwrap()
+            .map_err(rest_error_to_rpc_error)?
             .into_inner()
             .last_version
             .0;
 
         debug!("height {height} is ledger version {ledger_version}");
 
-        ledger_version
+        Ok(ledger_version)
+    }
+
+    #[instrument(skip_all, fields(%height))]
+    async fn fetch_blocks(
+        &self,
+        voyager_client: &VoyagerClient,
+        height: u64,
+        until: Option<u64>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        if let Some(until) = until {
+            if height > until {
+                return Err(ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!("height {height} cannot be greater than the until height {until}"),
+                    None::<()>,
+                ));
+            } else if height == until {
+                // if this is a ranged fetch, we need to fetch the upper bound of the range individually since FetchBlocks is exclusive on the upper bound
+                return Ok(call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::from(FetchTransactions { height }),
+                )));
+            }
+        }
+
+        let latest_height = voyager_client
+            .query_latest_height(self.chain_id.clone(), true)
+            .await?
+            .height();
+
+        info!(%latest_height, %height, ?until, "fetching blocks");
+
+        let continuation = |next_height: u64| {
+            seq([
+                call(WaitForHeight {
+                    chain_id: self.chain_id.clone(),
+                    height: Height::new(next_height),
+                    finalized: true,
+                }),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::from(FetchBlocks {
+                        height: next_height,
+                        until,
+                    }),
+                )),
+            ])
+        };
+
+        match height.cmp(&latest_height) {
+            // height <= latest_height
+            // fetch transactions on all blocks height..next_height (*exclusive* on the upper bound!)
+            // and then queue the continuation starting at next_height
+            Ordering::Equal | Ordering::Less => {
+                let next_height = (latest_height - height).clamp(1, 10) + height;
+
+                let next_height = next_height.min(until.unwrap_or(next_height));
+
+                Ok(conc(
+                    (height..next_height)
+                        .map(|height| {
+                            call(PluginMessage::new(
+                                self.plugin_name(),
+                                ModuleCall::from(FetchTransactions { height }),
+                            ))
+                        })
+                        .chain([continuation(next_height)]),
+                ))
+            }
+            // height > latest_height
+            Ordering::Greater => {
+                warn!(
+                    "the latest finalized height ({latest_height}) \
+                    is less than the requested height ({height})"
+                );
+
+                Ok(continuation(height))
+            }
+        }
     }
 
     async fn make_packet_metadata(
@@ -255,11 +333,21 @@ impl PluginServer<ModuleCall, Never> for Module {
             ready: msgs
                 .into_iter()
                 .map(|op| match op {
-                    Op::Call(Call::FetchBlocks(fetch)) if fetch.chain_id == self.chain_id => {
+                    Op::Call(Call::Index(fetch)) if fetch.chain_id == self.chain_id => {
                         call(PluginMessage::new(
                             self.plugin_name(),
-                            ModuleCall::FetchBlocks(FetchBlocks {
+                            ModuleCall::from(FetchBlocks {
                                 height: fetch.start_height.height(),
+                                until: None,
+                            }),
+                        ))
+                    }
+                    Op::Call(Call::IndexRange(fetch)) if fetch.chain_id == self.chain_id => {
+                        call(PluginMessage::new(
+                            self.plugin_name(),
+                            ModuleCall::from(FetchBlocks {
+                                height: fetch.range.from_height().height(),
+                                until: Some(fetch.range.to_height().height()),
                             }),
                         ))
                     }
@@ -311,13 +399,12 @@ impl PluginServer<ModuleCall, Never> for Module {
                             .map(move |events| (events, tx.info.hash))
                     })
                     .filter_map(|(e, hash)| match e.typ {
-                        MoveType::Struct(s) => {
-                            (s.address == self.ibc_handler_address).then_some((s, e.data, hash))
-                        }
+                        MoveType::Struct(s) => (s.address == self.ibc_handler_address
+                            && s.module.0.as_str() == "ibc")
+                            .then_some((s, e.data, hash)),
                         _ => None,
                     })
-                    .filter(|(typ, _, _)| typ.name.0.as_str() != "CreateLensClient")
-                    .map(|(typ, data, hash)| {
+                    .filter_map(|(typ, data, hash)| {
                         let event_type = typ.name.0.as_str();
                         let event = match event_type {
                             "CreateClient" => from_raw_event::<ibc::CreateClient>(data),
@@ -334,74 +421,38 @@ impl PluginServer<ModuleCall, Never> for Module {
                             "ChannelOpenConfirm" => from_raw_event::<ibc::ChannelOpenConfirm>(data),
                             "WriteAck" => from_raw_event::<ibc::WriteAck>(data),
                             "PacketRecv" => from_raw_event::<ibc::PacketRecv>(data),
+                            "RecvIntentPacket" => from_raw_event::<ibc::RecvIntentPacket>(data),
                             "PacketSend" => from_raw_event::<ibc::PacketSend>(data),
                             "PacketAck" => from_raw_event::<ibc::PacketAck>(data),
                             "TimeoutPacket" => from_raw_event::<ibc::TimeoutPacket>(data),
-                            unknown => panic!("unknown event `{unknown}`"),
+                            // these events have no counterpart in the ibc-union spec
+                            "CreateLensClient" | "SubmitMisbehaviour" => return None,
+                            unknown => {
+                                warn!(event_type = %unknown, tx_hash = %hash, height = %height, "unknown ibc event");
+                                return None;
+                            }
                         };
 
                         info!(event_type = %event_type, tx_hash = %hash, height = %height, "raw movement event");
 
-                        // TODO: Check the type before deserializing
-                        call(PluginMessage::new(
-                            self.plugin_name(),
-                            ModuleCall::from(MakeFullEvent {
-                                event,
-                                tx_hash: H256::new(*hash.0),
-                                height,
-                            }),
-                        ))
-                    });
+                        Some(event.map(|event| {
+                            call(PluginMessage::new(
+                                self.plugin_name(),
+                                ModuleCall::from(MakeFullEvent {
+                                    event,
+                                    tx_hash: H256::new(*hash.0),
+                                    height,
+                                }),
+                            ))
+                        }))
+                    })
+                    .collect::<RpcResult<Vec<_>>>()?;
 
                 Ok(conc(events))
             }
-            ModuleCall::FetchBlocks(FetchBlocks { height }) => Ok(conc([
-                call(PluginMessage::new(
-                    self.plugin_name(),
-                    ModuleCall::from(FetchTransactions { height }),
-                )),
-                {
-                    let latest_height = self
-                        .aptos_client
-                        .get_index()
-                        .await
-                        .unwrap()
-                        .into_inner()
-                        .block_height
-                        .0;
-                    match height.cmp(&latest_height) {
-                        Ordering::Less => {
-                            let next_height = (latest_height - height).clamp(1, 10) + height;
-                            conc(
-                                ((height + 1)..next_height)
-                                    .map(|height| {
-                                        call(PluginMessage::new(
-                                            self.plugin_name(),
-                                            ModuleCall::from(FetchTransactions { height }),
-                                        ))
-                                    })
-                                    .chain([call(PluginMessage::new(
-                                        self.plugin_name(),
-                                        ModuleCall::from(FetchBlocks {
-                                            height: next_height,
-                                        }),
-                                    ))]),
-                            )
-                        }
-                        Ordering::Equal | Ordering::Greater => seq([
-                            call(WaitForHeight {
-                                chain_id: self.chain_id.clone(),
-                                height: Height::new(height + 1),
-                                finalized: true,
-                            }),
-                            call(PluginMessage::new(
-                                self.plugin_name(),
-                                ModuleCall::from(FetchBlocks { height: height + 1 }),
-                            )),
-                        ]),
-                    }
-                },
-            ])),
+            ModuleCall::FetchBlocks(FetchBlocks { height, until }) => {
+                self.fetch_blocks(e.voyager_client()?, height, until).await
+            }
             ModuleCall::MakeFullEvent(MakeFullEvent {
                 event,
                 tx_hash,
@@ -486,7 +537,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                         event.client_id.try_into().unwrap(),
                     ),
                     events::IbcEvent::ChannelOpenInit(event) => {
-                        let ledger_version = self.ledger_version_of_height(height).await;
+                        let ledger_version = self.ledger_version_of_height(height).await?;
 
                         let connection = self
                             .get_connection(
@@ -514,7 +565,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                         )
                     }
                     events::IbcEvent::ChannelOpenTry(event) => {
-                        let ledger_version = self.ledger_version_of_height(height).await;
+                        let ledger_version = self.ledger_version_of_height(height).await?;
 
                         let connection = self
                             .get_connection(
@@ -547,7 +598,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                         )
                     }
                     events::IbcEvent::ChannelOpenAck(event) => {
-                        let ledger_version = self.ledger_version_of_height(height).await;
+                        let ledger_version = self.ledger_version_of_height(height).await?;
 
                         let connection = self
                             .get_connection(
@@ -590,7 +641,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                         )
                     }
                     events::IbcEvent::ChannelOpenConfirm(event) => {
-                        let ledger_version = self.ledger_version_of_height(height).await;
+                        let ledger_version = self.ledger_version_of_height(height).await?;
 
                         let connection = self
                             .get_connection(
@@ -692,7 +743,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                                         event.packet.timeout_timestamp,
                                     ),
                                 },
-                                maker_msg: Default::default(),
+                                maker_msg: event.maker_msg.into(),
                             }
                             .into(),
                             client_id,
@@ -763,7 +814,71 @@ impl PluginServer<ModuleCall, Never> for Module {
                             client_id,
                         )
                     }
-                    events::IbcEvent::TimeoutPacket(_) => todo!(),
+                    events::IbcEvent::RecvIntentPacket(event) => {
+                        let (
+                            _counterparty_chain_id,
+                            _client_info,
+                            destination_channel,
+                            source_channel,
+                        ) = self
+                            .make_packet_metadata(
+                                self.make_height(height),
+                                event.packet.destination_channel_id.try_into().unwrap(),
+                                e.voyager_client()?,
+                            )
+                            .await?;
+
+                        let client_id = destination_channel.connection.client_id;
+
+                        (
+                            IntentPacketRecv {
+                                packet_data: event.packet.data.into(),
+                                packet: PacketMetadata {
+                                    source_channel,
+                                    destination_channel,
+                                    timeout_height: event.packet.timeout_height,
+                                    timeout_timestamp: Timestamp::from_nanos(
+                                        event.packet.timeout_timestamp,
+                                    ),
+                                },
+                                market_maker_msg: event.maker_msg.into(),
+                            }
+                            .into(),
+                            client_id,
+                        )
+                    }
+                    events::IbcEvent::TimeoutPacket(event) => {
+                        let (
+                            _counterparty_chain_id,
+                            _client_info,
+                            source_channel,
+                            destination_channel,
+                        ) = self
+                            .make_packet_metadata(
+                                self.make_height(height),
+                                event.packet.source_channel_id.try_into().unwrap(),
+                                e.voyager_client()?,
+                            )
+                            .await?;
+
+                        let client_id = source_channel.connection.client_id;
+
+                        (
+                            PacketTimeout {
+                                packet_data: event.packet.data.into(),
+                                packet: PacketMetadata {
+                                    source_channel,
+                                    destination_channel,
+                                    timeout_height: event.packet.timeout_height,
+                                    timeout_timestamp: Timestamp::from_nanos(
+                                        event.packet.timeout_timestamp,
+                                    ),
+                                },
+                            }
+                            .into(),
+                            client_id,
+                        )
+                    }
                 };
 
                 let voyager_client = e.voyager_client()?;
@@ -815,7 +930,16 @@ fn convert_connection(connection: ConnectionEnd) -> Connection {
     }
 }
 
-fn from_raw_event<T: MoveOutputType + Into<events::IbcEvent>>(data: Value) -> events::IbcEvent {
-    let raw_event = serde_json::from_value::<T::Raw>(data).unwrap();
-    T::from_raw(raw_event).into()
+fn from_raw_event<T: MoveOutputType + Into<events::IbcEvent>>(
+    data: Value,
+) -> RpcResult<events::IbcEvent> {
+    let raw_event = serde_json::from_value::<T::Raw>(data).map_err(|e| {
+        ErrorObject::owned(
+            FATAL_JSONRPC_ERROR_CODE,
+            ErrorReporter(e).with_message("unable to decode event"),
+            None::<()>,
+        )
+    })?;
+
+    Ok(T::from_raw(raw_event).into())
 }
@@ -0,0 +1,28 @@
+use enumorph::Enumorph;
+use ibc_union_spec::ClientId;
+use macros::model;
+use unionlabs::{ibc::core::client::height::Height, primitives::H256};
+use voyager_sdk::primitives::ChainId;
+
+#[model]
+#[derive(Enumorph)]
+pub enum ModuleCall {
+    CheckForMisbehaviour(CheckForMisbehaviour),
+}
+
+/// Compare the consensus state stored by `client_id` on `chain_id` at `height` against the
+/// consensus state of `counterparty_chain_id` at the same height.
+#[model]
+pub struct CheckForMisbehaviour {
+    /// The chain the client is on.
+    pub chain_id: ChainId,
+    /// The chain tracked by the client.
+    pub counterparty_chain_id: ChainId,
+    pub client_id: ClientId,
+    /// The counterparty height the client was updated to.
+    pub height: u64,
+    /// The height on `chain_id` that the client update is provable at.
+    pub provable_height: Height,
+    /// The hash of the transaction that submitted the client update.
+    pub tx_hash: H256,
+}
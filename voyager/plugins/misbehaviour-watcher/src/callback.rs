@@ -0,0 +1,29 @@
+use enumorph::Enumorph;
+use ibc_union_spec::ClientId;
+use macros::model;
+use serde_json::Value;
+use unionlabs::{
+    ibc::core::client::height::Height,
+    primitives::{Bytes, H256},
+};
+use voyager_sdk::primitives::ChainId;
+
+#[model]
+#[derive(Enumorph)]
+pub enum ModuleCallback {
+    MakeMsgSubmitMisbehaviour(MakeMsgSubmitMisbehaviour),
+}
+
+/// Build a [`MsgSubmitMisbehaviour`](ibc_union_spec::datagram::MsgSubmitMisbehaviour) out of the
+/// header fetched from the counterparty chain and the conflicting consensus state stored by the
+/// client.
+#[model]
+pub struct MakeMsgSubmitMisbehaviour {
+    pub chain_id: ChainId,
+    pub client_id: ClientId,
+    pub height: Height,
+    pub trusted_height: Height,
+    pub conflicting_consensus_state: Value,
+    pub conflicting_client_message: Bytes,
+    pub tx_hash: H256,
+}
@@ -0,0 +1,708 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use ibc_union_msg::msg::{ExecuteMsg, MsgUpdateClient};
+use ibc_union_spec::{
+    datagram::{Datagram, MsgSubmitMisbehaviour},
+    event::FullEvent,
+    path::ConsensusStatePath,
+    ClientId, IbcUnion,
+};
+use jsonrpsee::{
+    core::{async_trait, RpcResult},
+    types::ErrorObject,
+    Extensions,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{debug, error, info, instrument, warn};
+use unionlabs::{
+    cosmos::tx::{tx_body::TxBody, tx_raw::TxRaw},
+    cosmwasm::wasm::msg_execute_contract::MsgExecuteContract,
+    encoding::{DecodeAs, Proto},
+    google::protobuf::any::RawAny,
+    ibc::core::client::height::Height,
+    primitives::{Bytes, H256},
+    ErrorReporter,
+};
+use voyager_sdk::{
+    anyhow::{self, Context as _},
+    into_value,
+    message::{
+        call::{FetchUpdateHeaders, SubmitTx},
+        data::{Data, IbcDatagram, OrderedHeaders},
+        PluginMessage, VoyagerMessage,
+    },
+    plugin::Plugin,
+    primitives::{ChainId, ClientType, IbcSpec, QueryHeight},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
+    types::{MisbehaviourEvidence, RawClientId},
+    vm::{call, noop, pass::PassResult, promise, Op},
+    DefaultCmd, ExtensionsExt, VoyagerClient,
+};
+
+use crate::{
+    call::{CheckForMisbehaviour, ModuleCall},
+    callback::{MakeMsgSubmitMisbehaviour, ModuleCallback},
+};
+
+pub mod call;
+pub mod callback;
+
+#[tokio::main]
+async fn main() {
+    Module::run().await
+}
+
+pub struct Module {
+    pub cometbft_clients: BTreeMap<ChainId, cometbft_rpc::Client>,
+    pub self_consensus_state_config: BTreeMap<ClientType, Value>,
+    pub ignored_consensus_state_fields: BTreeMap<ClientType, BTreeSet<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The chains to watch client updates on, with the CometBFT RPC endpoint used to fetch the
+    /// transactions that submitted conflicting client updates.
+    ///
+    /// Only cosmos chains (hosting ibc-union as a cosmwasm contract) are supported, since the
+    /// conflicting client message is read out of the cosmos-sdk transaction that submitted it.
+    pub chains: BTreeMap<ChainId, String>,
+    /// Config passed to the client bootstrap module when querying the consensus state of the
+    /// counterparty chain, keyed by client type. Client types not present here will use `null`.
+    #[serde(default)]
+    pub self_consensus_state_config: BTreeMap<ClientType, Value>,
+    /// Top level consensus state fields that are not compared, keyed by client type. This is
+    /// intended for fields that are not derived from the counterparty chain, and as such can
+    /// differ between the stored and the expected consensus state without any misbehaviour.
+    #[serde(default)]
+    pub ignored_consensus_state_fields: BTreeMap<ClientType, BTreeSet<String>>,
+}
+
+impl Plugin for Module {
+    type Call = ModuleCall;
+    type Callback = ModuleCallback;
+
+    type Config = Config;
+    type Cmd = DefaultCmd;
+
+    async fn new(config: Self::Config) -> anyhow::Result<Self> {
+        let mut cometbft_clients = BTreeMap::new();
+
+        for (chain_id, rpc_url) in config.chains {
+            let not_cosmos = || {
+                format!(
+                    "unable to query the CometBFT status of {chain_id} at {rpc_url}, only cosmos \
+                    chains are supported"
+                )
+            };
+
+            let client = cometbft_rpc::Client::new(&rpc_url)
+                .await
+                .with_context(not_cosmos)?;
+
+            let found_chain_id = client
+                .status()
+                .await
+                .with_context(not_cosmos)?
+                .node_info
+                .network
+                .to_string();
+
+            anyhow::ensure!(
+                found_chain_id == chain_id.as_str(),
+                "incorrect chain id for {chain_id}: expected `{chain_id}`, but found \
+                `{found_chain_id}`"
+            );
+
+            cometbft_clients.insert(chain_id, client);
+        }
+
+        Ok(Self {
+            cometbft_clients,
+            self_consensus_state_config: config.self_consensus_state_config,
+            ignored_consensus_state_fields: config.ignored_consensus_state_fields,
+        })
+    }
+
+    fn info(config: Self::Config) -> PluginInfo {
+        PluginInfo {
+            name: plugin_name(),
+            // TODO: Support IBC classic
+            interest_filter: format!(
+                r#"
+if ."@type" == "data"
+    and ."@value"."@type" == "ibc_event"
+    and ."@value"."@value".ibc_spec_id == "{ibc_union_id}"
+    and ."@value"."@value".event."@type" == "update_client"
+    and ({chain_ids})
+then
+    false # interest, but only copy
+else
+    null
+end
+"#,
+                ibc_union_id = IbcUnion::ID,
+                chain_ids = config
+                    .chains
+                    .keys()
+                    .map(|chain_id| format!(r#"."@value"."@value".chain_id == "{chain_id}""#))
+                    .chain(["false".to_owned()])
+                    .collect::<Vec<_>>()
+                    .join(" or "),
+            ),
+        }
+    }
+
+    async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
+        match cmd {}
+    }
+}
+
+pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+fn plugin_name() -> String {
+    PLUGIN_NAME.to_string()
+}
+
+impl Module {
+    fn plugin_name(&self) -> String {
+        plugin_name()
+    }
+}
+
+#[async_trait]
+impl PluginServer<ModuleCall, ModuleCallback> for Module {
+    #[instrument(skip_all, fields())]
+    async fn run_pass(
+        &self,
+        _: &Extensions,
+        msgs: Vec<Op<VoyagerMessage>>,
+    ) -> RpcResult<PassResult<VoyagerMessage>> {
+        let ready = msgs
+            .into_iter()
+            .enumerate()
+            .map(|(idx, msg)| match msg {
+                Op::Data(Data::IbcEvent(ref chain_event)) => match chain_event
+                    .decode_event::<IbcUnion>()
+                    .ok_or_else(|| {
+                        ErrorObject::owned(
+                            FATAL_JSONRPC_ERROR_CODE,
+                            "unexpected data message in queue",
+                            Some(json!({
+                                "msg": msg.clone(),
+                            })),
+                        )
+                    })?
+                    .map_err(|err| {
+                        ErrorObject::owned(
+                            FATAL_JSONRPC_ERROR_CODE,
+                            "unable to parse ibc datagram",
+                            Some(json!({
+                                "err": ErrorReporter(err).to_string(),
+                                "msg": msg,
+                            })),
+                        )
+                    })? {
+                    FullEvent::UpdateClient(update_client) => Ok((
+                        vec![idx],
+                        call(PluginMessage::new(
+                            self.plugin_name(),
+                            ModuleCall::from(CheckForMisbehaviour {
+                                chain_id: chain_event.chain_id.clone(),
+                                counterparty_chain_id: chain_event.counterparty_chain_id.clone(),
+                                client_id: update_client.client_id,
+                                height: update_client.height,
+                                provable_height: *chain_event.provable_height.height(),
+                                tx_hash: chain_event.tx_hash,
+                            }),
+                        )),
+                    )),
+                    datagram => Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        format!("unexpected ibc datagram {}", datagram.name()),
+                        Some(json!({
+                            "msg": msg,
+                        })),
+                    )),
+                },
+                _ => Err(ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    "unexpected message in queue",
+                    Some(json!({
+                        "msg": msg,
+                    })),
+                )),
+            })
+            .collect::<RpcResult<Vec<_>>>()?;
+
+        Ok(PassResult {
+            optimize_further: vec![],
+            ready,
+        })
+    }
+
+    #[instrument(skip_all, fields())]
+    async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
+        let voyager_client = e.voyager_client()?;
+
+        match msg {
+            ModuleCall::CheckForMisbehaviour(call) => {
+                self.check_for_misbehaviour(voyager_client, call).await
+            }
+        }
+    }
+
+    #[instrument(skip_all, fields())]
+    async fn callback(
+        &self,
+        e: &Extensions,
+        cb: ModuleCallback,
+        datas: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let voyager_client = e.voyager_client()?;
+
+        match cb {
+            ModuleCallback::MakeMsgSubmitMisbehaviour(cb) => {
+                self.make_msg_submit_misbehaviour(voyager_client, cb, datas)
+                    .await
+            }
+        }
+    }
+}
+
+impl Module {
+    #[instrument(
+        skip_all,
+        fields(
+            %chain_id,
+            %counterparty_chain_id,
+            %client_id,
+            %height,
+            %tx_hash,
+        )
+    )]
+    async fn check_for_misbehaviour(
+        &self,
+        voyager_client: &VoyagerClient,
+        CheckForMisbehaviour {
+            chain_id,
+            counterparty_chain_id,
+            client_id,
+            height,
+            provable_height,
+            tx_hash,
+        }: CheckForMisbehaviour,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        // the interest filter only matches the configured chains, but this can also be called
+        // directly
+        if !self.cometbft_clients.contains_key(&chain_id) {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!(
+                    "misbehaviour can only be checked for the configured cosmos chains, and \
+                    {chain_id} is not configured"
+                ),
+                None::<()>,
+            ));
+        }
+
+        let client_info = voyager_client
+            .client_info::<IbcUnion>(chain_id.clone(), client_id)
+            .await?;
+
+        // the trusted height of the client immediately before the update was applied
+        let trusted_height = voyager_client
+            .client_state_meta::<IbcUnion>(
+                chain_id.clone(),
+                QueryHeight::Specific(height_before(provable_height)?),
+                client_id,
+            )
+            .await?
+            .counterparty_height;
+
+        let height = Height::new_with_revision(trusted_height.revision(), height);
+
+        let stored_consensus_state = voyager_client
+            .query_ibc_state(
+                chain_id.clone(),
+                QueryHeight::Specific(provable_height),
+                ConsensusStatePath {
+                    client_id,
+                    height: height.height(),
+                },
+            )
+            .await?;
+
+        let self_consensus_state = voyager_client
+            .self_consensus_state(
+                counterparty_chain_id.clone(),
+                client_info.client_type.clone(),
+                QueryHeight::Specific(height),
+                self.self_consensus_state_config
+                    .get(&client_info.client_type)
+                    .cloned()
+                    .unwrap_or_default(),
+            )
+            .await?;
+
+        // the consensus states are compared decoded, since the encoding is not necessarily
+        // canonical
+        let conflicting_consensus_state = voyager_client
+            .decode_consensus_state::<IbcUnion, Value>(
+                client_info.client_type.clone(),
+                client_info.ibc_interface,
+                stored_consensus_state,
+            )
+            .await?;
+
+        let conflicting_fields = conflicting_fields(
+            &conflicting_consensus_state,
+            &self_consensus_state.state,
+            self.ignored_consensus_state_fields
+                .get(&client_info.client_type)
+                .unwrap_or(&BTreeSet::new()),
+        );
+
+        if conflicting_fields.is_empty() {
+            debug!("consensus state matches counterparty");
+
+            return Ok(noop());
+        }
+
+        warn!(
+            ?conflicting_fields,
+            %conflicting_consensus_state,
+            expected_consensus_state = %self_consensus_state.state,
+            "consensus state does not match counterparty, submitting misbehaviour"
+        );
+
+        let conflicting_client_message = self
+            .conflicting_client_message(&chain_id, client_id, tx_hash)
+            .await?;
+
+        let Some(conflicting_client_message) = conflicting_client_message else {
+            error!(
+                "no client update for this client was found in the transaction, unable to submit \
+                misbehaviour"
+            );
+
+            return Ok(noop());
+        };
+
+        Ok(promise(
+            [call(FetchUpdateHeaders {
+                client_type: client_info.client_type,
+                chain_id: counterparty_chain_id,
+                counterparty_chain_id: chain_id.clone(),
+                client_id: RawClientId::new(client_id),
+                update_from: trusted_height,
+                update_to: height,
+            })],
+            [],
+            PluginMessage::new(
+                self.plugin_name(),
+                ModuleCallback::from(MakeMsgSubmitMisbehaviour {
+                    chain_id,
+                    client_id,
+                    height,
+                    trusted_height,
+                    conflicting_consensus_state,
+                    conflicting_client_message,
+                    tx_hash,
+                }),
+            ),
+        ))
+    }
+
+    #[instrument(
+        skip_all,
+        fields(
+            %chain_id,
+            %client_id,
+            %height,
+            %trusted_height,
+            %tx_hash,
+        )
+    )]
+    async fn make_msg_submit_misbehaviour(
+        &self,
+        voyager_client: &VoyagerClient,
+        MakeMsgSubmitMisbehaviour {
+            chain_id,
+            client_id,
+            height,
+            trusted_height,
+            conflicting_consensus_state,
+            conflicting_client_message,
+            tx_hash,
+        }: MakeMsgSubmitMisbehaviour,
+        datas: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let Some(OrderedHeaders { headers }) = datas
+            .into_iter()
+            .find_map(|data| OrderedHeaders::try_from(data).ok())
+        else {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                "expected ordered headers in callback data",
+                None::<()>,
+            ));
+        };
+
+        // the misbehaviour must be proven with a header for exactly the conflicting height
+        let Some((_, header)) = headers.into_iter().find(|(meta, _)| meta.height == height) else {
+            error!(
+                "no header was fetched for the conflicting height, unable to submit misbehaviour"
+            );
+
+            return Ok(noop());
+        };
+
+        let client_info = voyager_client
+            .client_info::<IbcUnion>(chain_id.clone(), client_id)
+            .await?;
+
+        let client_message = voyager_client
+            .encode_misbehaviour::<IbcUnion>(
+                client_info.client_type,
+                client_info.ibc_interface,
+                into_value(MisbehaviourEvidence {
+                    height,
+                    trusted_height,
+                    header,
+                    conflicting_consensus_state,
+                    conflicting_client_message,
+                    tx_hash,
+                }),
+            )
+            .await?;
+
+        info!("submitting misbehaviour");
+
+        Ok(call(SubmitTx {
+            chain_id,
+            datagrams: vec![IbcDatagram::new::<IbcUnion>(Datagram::from(
+                MsgSubmitMisbehaviour {
+                    client_id,
+                    client_message,
+                },
+            ))],
+        }))
+    }
+}
+
+impl Module {
+    /// Fetch the client message of the client update for `client_id` submitted in `tx_hash`.
+    async fn conflicting_client_message(
+        &self,
+        chain_id: &ChainId,
+        client_id: ClientId,
+        tx_hash: H256,
+    ) -> RpcResult<Option<Bytes>> {
+        let client = self.cometbft_clients.get(chain_id).ok_or_else(|| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("unknown chain {chain_id}"),
+                None::<()>,
+            )
+        })?;
+
+        let tx = client.tx(tx_hash, false).await.map_err(|err| {
+            ErrorObject::owned(
+                -1,
+                ErrorReporter(err).with_message("error fetching client update tx"),
+                Some(json!({
+                    "tx_hash": tx_hash,
+                })),
+            )
+        })?;
+
+        update_client_message(&tx.tx, client_id).map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("unable to decode client update tx: {err:#}"),
+                Some(json!({
+                    "tx_hash": tx_hash,
+                })),
+            )
+        })
+    }
+}
+
+/// The height immediately before `provable_height`, which the client state before the update is
+/// provable at.
+fn height_before(provable_height: Height) -> RpcResult<Height> {
+    provable_height
+        .height()
+        .checked_sub(1)
+        .map(|height| Height::new_with_revision(provable_height.revision(), height))
+        .ok_or_else(|| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("client update is provable at {provable_height}, which has no parent"),
+                None::<()>,
+            )
+        })
+}
+
+/// The top level fields (as `jq` paths) that differ between the stored and the expected consensus
+/// states, excluding the `ignored` fields. If either consensus state is not an object, they are
+/// compared as a whole.
+fn conflicting_fields(stored: &Value, expected: &Value, ignored: &BTreeSet<String>) -> Vec<String> {
+    match (stored, expected) {
+        (Value::Object(stored), Value::Object(expected)) => stored
+            .keys()
+            .chain(expected.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|field| !ignored.contains(*field))
+            .filter(|field| stored.get(*field) != expected.get(*field))
+            .map(|field| format!(".{field}"))
+            .collect(),
+        (stored, expected) if stored == expected => vec![],
+        _ => vec![".".to_owned()],
+    }
+}
+
+/// Extract the client message of the first `update_client` call for `client_id` in a cosmos-sdk
+/// transaction.
+fn update_client_message(tx_bytes: &[u8], client_id: ClientId) -> anyhow::Result<Option<Bytes>> {
+    let tx_raw = TxRaw::decode_as::<Proto>(tx_bytes)?;
+
+    let tx_body = <TxBody<RawAny>>::decode_as::<Proto>(&tx_raw.body_bytes)?;
+
+    Ok(tx_body
+        .messages
+        .iter()
+        // other messages can't contain a client update
+        .filter_map(|msg| msg.decode::<MsgExecuteContract>().ok())
+        .filter_map(|msg| serde_json::from_slice::<ExecuteMsg>(&msg.msg).ok())
+        .find_map(|msg| match msg {
+            ExecuteMsg::UpdateClient(MsgUpdateClient {
+                client_id: update_client_id,
+                client_message,
+                ..
+            }) if update_client_id == client_id => Some(client_message),
+            _ => None,
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use unionlabs::{encoding::EncodeAs, google::protobuf::any::Any, primitives::Bech32};
+
+    use super::*;
+
+    fn execute_contract(msg: &ExecuteMsg) -> RawAny {
+        Any(MsgExecuteContract {
+            sender: Bech32::new("union".to_owned(), Bytes::from(vec![1; 20])),
+            contract: Bech32::new("union".to_owned(), H256::new([2; 32])),
+            msg: serde_json::to_vec(msg).unwrap().into(),
+            funds: vec![],
+        })
+        .into()
+    }
+
+    fn update_client(client_id: u32, client_message: &[u8]) -> RawAny {
+        execute_contract(&ExecuteMsg::UpdateClient(MsgUpdateClient {
+            client_id: ClientId::from_raw(client_id).unwrap(),
+            client_message: client_message.to_vec().into(),
+            relayer: "union1relayer".to_owned(),
+        }))
+    }
+
+    fn tx(messages: Vec<RawAny>) -> Vec<u8> {
+        TxRaw {
+            body_bytes: TxBody {
+                messages,
+                memo: String::new(),
+                timeout_height: 0,
+                extension_options: vec![],
+                non_critical_extension_options: vec![],
+            }
+            .encode_as::<Proto>(),
+            auth_info_bytes: vec![],
+            signatures: vec![],
+        }
+        .encode_as::<Proto>()
+    }
+
+    #[test]
+    fn height_before_genesis_is_an_error() {
+        assert_eq!(
+            height_before(Height::new_with_revision(1, 10)).unwrap(),
+            Height::new_with_revision(1, 9)
+        );
+
+        assert!(height_before(Height::new_with_revision(1, 0)).is_err());
+    }
+
+    #[test]
+    fn equal_consensus_states_do_not_conflict() {
+        let consensus_state = json!({ "timestamp": "1", "root": "0xaa" });
+
+        assert!(
+            conflicting_fields(&consensus_state, &consensus_state, &BTreeSet::new()).is_empty()
+        );
+    }
+
+    #[test]
+    fn conflicting_fields_are_reported() {
+        let stored = json!({ "timestamp": "1", "root": "0xaa", "extra": 1 });
+        let expected = json!({ "timestamp": "1", "root": "0xbb" });
+
+        assert_eq!(
+            conflicting_fields(&stored, &expected, &BTreeSet::new()),
+            [".extra", ".root"]
+        );
+    }
+
+    #[test]
+    fn ignored_fields_are_not_compared() {
+        let stored = json!({ "timestamp": "1", "root": "0xaa" });
+        let expected = json!({ "timestamp": "2", "root": "0xaa" });
+
+        assert!(conflicting_fields(
+            &stored,
+            &expected,
+            &["timestamp".to_owned()].into_iter().collect()
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn non_object_consensus_states_are_compared_as_a_whole() {
+        assert_eq!(
+            conflicting_fields(&json!("0xaa"), &json!("0xbb"), &BTreeSet::new()),
+            ["."]
+        );
+        assert!(conflicting_fields(&json!("0xaa"), &json!("0xaa"), &BTreeSet::new()).is_empty());
+    }
+
+    #[test]
+    fn update_client_message_is_extracted() {
+        let tx = tx(vec![
+            update_client(1, b"other client"),
+            update_client(2, b"conflicting header"),
+            update_client(2, b"later header"),
+        ]);
+
+        assert_eq!(
+            update_client_message(&tx, ClientId::from_raw(2).unwrap()).unwrap(),
+            Some(b"conflicting header".to_vec().into())
+        );
+    }
+
+    #[test]
+    fn missing_update_client_message() {
+        let tx = tx(vec![update_client(1, b"other client")]);
+
+        assert_eq!(
+            update_client_message(&tx, ClientId::from_raw(2).unwrap()).unwrap(),
+            None
+        );
+
+        assert!(update_client_message(b"not a tx", ClientId::from_raw(2).unwrap()).is_err());
+    }
+}
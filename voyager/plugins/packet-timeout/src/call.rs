@@ -1,4 +1,5 @@
 use enumorph::Enumorph;
+use ibc_classic_spec::SendPacket;
 use ibc_union_spec::event::PacketSend;
 use macros::model;
 use voyager_sdk::primitives::ChainId;
@@ -8,6 +9,8 @@ use voyager_sdk::primitives::ChainId;
 pub enum ModuleCall {
     WaitForTimeoutOrReceipt(WaitForTimeoutOrReceipt),
     MakeMsgTimeout(MakeMsgTimeout),
+    WaitForTimeoutOrReceiptClassic(WaitForTimeoutOrReceiptClassic),
+    MakeMsgTimeoutClassic(MakeMsgTimeoutClassic),
 }
 
 #[model]
@@ -22,4 +25,24 @@ pub struct MakeMsgTimeout {
     pub event: PacketSend,
     pub chain_id: ChainId,
     pub counterparty_chain_id: ChainId,
+    /// Whether the grace period of the timeout policy for this packet has already been waited for.
+    #[serde(default)]
+    pub grace_period_elapsed: bool,
+}
+
+#[model]
+pub struct WaitForTimeoutOrReceiptClassic {
+    pub event: SendPacket,
+    pub chain_id: ChainId,
+    pub counterparty_chain_id: ChainId,
+}
+
+#[model]
+pub struct MakeMsgTimeoutClassic {
+    pub event: SendPacket,
+    pub chain_id: ChainId,
+    pub counterparty_chain_id: ChainId,
+    /// Whether the grace period of the timeout policy for this packet has already been waited for.
+    #[serde(default)]
+    pub grace_period_elapsed: bool,
 }
@@ -1,9 +1,14 @@
-use std::collections::VecDeque;
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, VecDeque},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
+use ibc_classic_spec::{CommitmentPath, IbcClassic, NextSequenceRecvPath, ReceiptPath, SendPacket};
 use ibc_union_spec::{
     datagram::{Datagram, MsgPacketTimeout},
     event::{FullEvent, PacketSend},
-    path::BatchReceiptsPath,
+    path::{BatchPacketsPath, BatchReceiptsPath, ChannelPath, COMMITMENT_MAGIC},
     IbcUnion,
 };
 use jsonrpsee::{
@@ -15,7 +20,15 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{debug, info, instrument, warn};
 use unionlabs::{
-    self, ibc::core::client::height::Height, never::Never, traits::Member, ErrorReporter,
+    self,
+    ibc::core::{
+        channel::{msg_timeout::MsgTimeout, order::Order, packet::Packet},
+        client::height::Height,
+    },
+    never::Never,
+    primitives::H256,
+    traits::Member,
+    ErrorReporter,
 };
 use voyager_sdk::{
     anyhow,
@@ -25,26 +38,74 @@ use voyager_sdk::{
         PluginMessage, VoyagerMessage,
     },
     plugin::Plugin,
-    primitives::{ChainId, IbcSpec, QueryHeight},
+    primitives::{ChainId, IbcSpec, QueryHeight, Timestamp},
     rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
     types::{ProofType, RawClientId},
-    vm::{call, conc, noop, pass::PassResult, seq, Op},
+    vm::{call, conc, defer, noop, now, pass::PassResult, seq, Op},
     DefaultCmd, ExtensionsExt, VoyagerClient,
 };
 
-use crate::call::{MakeMsgTimeout, ModuleCall, WaitForTimeoutOrReceipt};
+use crate::{
+    call::{
+        MakeMsgTimeout, MakeMsgTimeoutClassic, ModuleCall, WaitForTimeoutOrReceipt,
+        WaitForTimeoutOrReceiptClassic,
+    },
+    queued::QueuedPackets,
+};
 
 pub mod call;
+pub mod queued;
+
+/// The maximum amount of recently queued packets that are tracked for deduplication.
+const MAX_QUEUED_PACKETS: usize = 100_000;
 
 #[tokio::main]
 async fn main() {
     Module::run().await
 }
 
-pub struct Module {}
+pub struct Module {
+    pub policies: Vec<TimeoutPolicy>,
+    pub queued: Mutex<QueuedPackets>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The timeout policies for the chains and channels to watch. If no policies are configured,
+    /// all packets on all chains will be timed out.
+    #[serde(default)]
+    pub policies: Vec<TimeoutPolicy>,
+    /// How long (in seconds) a queued packet is remembered, so that copies of it seen in later
+    /// passes (i.e. if a block is reindexed) are not waited for again.
+    #[serde(default = "default_dedup_window")]
+    pub dedup_window: u64,
+}
+
+fn default_dedup_window() -> u64 {
+    3600
+}
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Config {}
+#[serde(deny_unknown_fields)]
+pub struct TimeoutPolicy {
+    /// The chain the packets are sent from.
+    pub chain_id: ChainId,
+    /// The channel on `chain_id` the packets are sent on. For IBC classic, this is the prefixed
+    /// channel id (i.e. `channel-0`). If not set, this policy applies to all channels on the chain
+    /// that do not have a more specific policy.
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    /// The amount of seconds to wait after a packet has timed out before submitting the timeout.
+    /// This gives other relayers a chance to time out the packet first.
+    #[serde(default)]
+    pub grace_period: u64,
+    /// Only timeout packets sent from one of these ports, i.e. packets sent by our own
+    /// applications. For IBC union, this is the hex encoded address of the application. If not
+    /// set, packets sent from any port will be timed out.
+    #[serde(default)]
+    pub source_ports: Option<BTreeSet<String>>,
+}
 
 impl Plugin for Module {
     type Call = ModuleCall;
@@ -62,13 +123,20 @@ impl Plugin for Module {
 
         PluginInfo {
             name: module.plugin_name(),
-            // TODO: Support IBC classic
             interest_filter: format!(
                 r#"
 if ."@type" == "data"
     and ."@value"."@type" == "ibc_event"
-    and ."@value"."@value".ibc_spec_id == "{ibc_union_id}"
-    and ."@value"."@value".event."@type" == "packet_send"
+    and (
+        (
+            ."@value"."@value".ibc_spec_id == "{ibc_union_id}"
+                and ."@value"."@value".event."@type" == "packet_send"
+        ) or (
+            ."@value"."@value".ibc_spec_id == "{ibc_classic_id}"
+                and ."@value"."@value".event."@type" == "send_packet"
+        )
+    )
+    and ({chain_ids})
 then
     false # interest, but only copy
 else
@@ -76,6 +144,20 @@ else
 end
 "#,
                 ibc_union_id = IbcUnion::ID,
+                ibc_classic_id = IbcClassic::ID,
+                chain_ids = if module.policies.is_empty() {
+                    "true".to_owned()
+                } else {
+                    module
+                        .policies
+                        .iter()
+                        .map(|policy| policy.chain_id.clone())
+                        .collect::<BTreeSet<_>>()
+                        .iter()
+                        .map(|chain_id| format!(r#"."@value"."@value".chain_id == "{chain_id}""#))
+                        .collect::<Vec<_>>()
+                        .join(" or ")
+                },
             ),
         }
     }
@@ -92,8 +174,45 @@ impl Module {
         PLUGIN_NAME.to_string()
     }
 
-    pub fn new(Config {}: Config) -> Self {
-        Self {}
+    pub fn new(
+        Config {
+            policies,
+            dedup_window,
+        }: Config,
+    ) -> Self {
+        Self {
+            policies,
+            queued: Mutex::new(QueuedPackets::new(dedup_window, MAX_QUEUED_PACKETS)),
+        }
+    }
+
+    /// Find the timeout policy for packets sent on `channel_id` on `chain_id`, if any.
+    ///
+    /// A policy for the exact channel takes precedence over a chain-wide policy. If no policies
+    /// are configured at all, all packets are timed out with the default policy.
+    fn policy(&self, chain_id: &ChainId, channel_id: &str) -> Option<TimeoutPolicy> {
+        if self.policies.is_empty() {
+            return Some(TimeoutPolicy {
+                chain_id: chain_id.clone(),
+                channel_id: None,
+                grace_period: 0,
+                source_ports: None,
+            });
+        }
+
+        let for_chain = || self.policies.iter().filter(|p| &p.chain_id == chain_id);
+
+        for_chain()
+            .find(|p| p.channel_id.as_deref() == Some(channel_id))
+            .or_else(|| for_chain().find(|p| p.channel_id.is_none()))
+            .cloned()
+    }
+
+    /// The grace period (in seconds) of the timeout policy for packets sent on `channel_id` on
+    /// `chain_id`.
+    fn grace_period(&self, chain_id: &ChainId, channel_id: &str) -> u64 {
+        self.policy(chain_id, channel_id)
+            .map_or(0, |policy| policy.grace_period)
     }
 }
 
@@ -105,63 +224,170 @@ impl PluginServer<ModuleCall, Never> for Module {
         _: &Extensions,
         msgs: Vec<Op<VoyagerMessage>>,
     ) -> RpcResult<PassResult<VoyagerMessage>> {
-        let ready = msgs
-            .into_iter()
-            .enumerate()
-            .map(|(idx, msg)| match msg {
-                Op::Data(Data::IbcEvent(ref chain_event)) => match chain_event
-                    .decode_event::<IbcUnion>()
-                    .ok_or_else(|| {
-                        ErrorObject::owned(
-                            FATAL_JSONRPC_ERROR_CODE,
-                            "unexpected data message in queue",
-                            Some(json!({
-                                "msg": msg.clone(),
-                            })),
+        // the same packet may be seen multiple times (i.e. if a block is reindexed), only wait for
+        // it once. packets are deduplicated within the pass, and against the packets queued in
+        // previous passes (see `Config::dedup_window`). a packet that is seen again after it has
+        // been forgotten is waited for again, this is harmless since the packet commitment is
+        // checked before a timeout is submitted.
+        let mut packets = BTreeMap::<String, (Vec<usize>, Op<VoyagerMessage>)>::new();
+        let mut ignored = vec![];
+
+        for (idx, msg) in msgs.into_iter().enumerate() {
+            let Op::Data(Data::IbcEvent(ref chain_event)) = msg else {
+                return Err(ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    "unexpected message in queue",
+                    Some(json!({
+                        "msg": msg,
+                    })),
+                ));
+            };
+
+            let parse_error = |err: String| {
+                ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    "unable to parse ibc datagram",
+                    Some(json!({
+                        "err": err,
+                        "msg": msg,
+                    })),
+                )
+            };
+
+            let unexpected_datagram = |name: &str| {
+                ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!("unexpected ibc datagram {name}"),
+                    Some(json!({
+                        "msg": msg,
+                    })),
+                )
+            };
+
+            let (key, op) = if let Some(event) = chain_event.decode_event::<IbcUnion>() {
+                match event.map_err(|err| parse_error(ErrorReporter(err).to_string()))? {
+                    FullEvent::PacketSend(packet_send) => {
+                        if self
+                            .policy(
+                                &chain_event.chain_id,
+                                &packet_send.packet.source_channel.channel_id.to_string(),
+                            )
+                            .is_none()
+                        {
+                            ignored.push(idx);
+                            continue;
+                        }
+
+                        (
+                            format!("{}/{}", chain_event.chain_id, packet_send.packet().hash()),
+                            call(PluginMessage::new(
+                                self.plugin_name(),
+                                ModuleCall::from(WaitForTimeoutOrReceipt {
+                                    event: packet_send,
+                                    chain_id: chain_event.chain_id.clone(),
+                                    counterparty_chain_id: chain_event
+                                        .counterparty_chain_id
+                                        .clone(),
+                                }),
+                            )),
                         )
-                    })?
-                    .map_err(|err| {
-                        ErrorObject::owned(
-                            FATAL_JSONRPC_ERROR_CODE,
-                            "unable to parse ibc datagram",
-                            Some(json!({
-                                "err": ErrorReporter(err).to_string(),
-                                "msg": msg,
-                            })),
+                    }
+                    datagram => return Err(unexpected_datagram(datagram.name())),
+                }
+            } else if let Some(event) = chain_event.decode_event::<IbcClassic>() {
+                match event.map_err(|err| parse_error(ErrorReporter(err).to_string()))? {
+                    ibc_classic_spec::FullEvent::SendPacket(send_packet) => {
+                        if self
+                            .policy(
+                                &chain_event.chain_id,
+                                &send_packet
+                                    .packet
+                                    .source_channel
+                                    .channel_id
+                                    .to_string_prefixed(),
+                            )
+                            .is_none()
+                        {
+                            ignored.push(idx);
+                            continue;
+                        }
+
+                        (
+                            format!(
+                                "{}/{}/{:#}/{}",
+                                chain_event.chain_id,
+                                send_packet.packet.source_channel.port_id,
+                                send_packet.packet.source_channel.channel_id,
+                                send_packet.packet.sequence,
+                            ),
+                            call(PluginMessage::new(
+                                self.plugin_name(),
+                                ModuleCall::from(WaitForTimeoutOrReceiptClassic {
+                                    event: send_packet,
+                                    chain_id: chain_event.chain_id.clone(),
+                                    counterparty_chain_id: chain_event
+                                        .counterparty_chain_id
+                                        .clone(),
+                                }),
+                            )),
                         )
-                    })? {
-                    FullEvent::PacketSend(packet_send) => Ok((
-                        vec![idx],
-                        call(PluginMessage::new(
-                            self.plugin_name(),
-                            ModuleCall::WaitForTimeoutOrReceipt(WaitForTimeoutOrReceipt {
-                                event: packet_send,
-                                chain_id: chain_event.chain_id.clone(),
-                                counterparty_chain_id: chain_event.counterparty_chain_id.clone(),
-                            }),
-                        )),
-                    )),
-                    datagram => Err(ErrorObject::owned(
-                        FATAL_JSONRPC_ERROR_CODE,
-                        format!("unexpected ibc datagram {}", datagram.name()),
-                        Some(json!({
-                            "msg": msg,
-                        })),
-                    )),
-                },
-                _ => Err(ErrorObject::owned(
+                    }
+                    datagram => return Err(unexpected_datagram(datagram.name())),
+                }
+            } else {
+                return Err(ErrorObject::owned(
                     FATAL_JSONRPC_ERROR_CODE,
-                    "unexpected message in queue",
+                    "unexpected data message in queue",
                     Some(json!({
                         "msg": msg,
                     })),
-                )),
+                ));
+            };
+
+            match packets.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert((vec![idx], op));
+                }
+                Entry::Occupied(mut entry) => {
+                    debug!(packet = %entry.key(), "duplicate packet");
+
+                    entry.get_mut().0.push(idx);
+                }
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let mut queued = self.queued.lock().unwrap();
+
+        let packets = packets
+            .into_iter()
+            .filter_map(|(key, (idxs, op))| {
+                if queued.insert(key.clone(), now) {
+                    Some((idxs, op))
+                } else {
+                    debug!(packet = %key, "packet already queued");
+
+                    ignored.extend(idxs);
+
+                    None
+                }
             })
-            .collect::<RpcResult<Vec<_>>>()?;
+            .collect::<Vec<_>>();
+
+        drop(queued);
+
+        ignored.sort_unstable();
 
         Ok(PassResult {
             optimize_further: vec![],
-            ready,
+            ready: packets
+                .into_iter()
+                .chain((!ignored.is_empty()).then(|| (ignored, noop())))
+                .collect(),
         })
     }
 
@@ -173,65 +399,13 @@ impl PluginServer<ModuleCall, Never> for Module {
             ModuleCall::WaitForTimeoutOrReceipt(call) => {
                 self.wait_for_timeout_or_receipt(voyager_client, call).await
             }
-            ModuleCall::MakeMsgTimeout(MakeMsgTimeout {
-                event,
-                chain_id,
-                counterparty_chain_id,
-            }) => {
-                let client_meta = voyager_client
-                    .client_state_meta::<IbcUnion>(
-                        chain_id.clone(),
-                        QueryHeight::Latest,
-                        event.packet.source_channel.connection.client_id,
-                    )
-                    .await?;
-
-                let proof_unreceived = voyager_client
-                    .query_ibc_proof(
-                        counterparty_chain_id,
-                        QueryHeight::Specific(client_meta.counterparty_height),
-                        BatchReceiptsPath::from_packets(&[event.packet().clone()]),
-                    )
-                    .await?
-                    .into_result()?;
-
-                match proof_unreceived.proof_type {
-                    ProofType::NonMembership => {
-                        let client_info = voyager_client
-                            .client_info::<IbcUnion>(
-                                chain_id.clone(),
-                                event.packet.source_channel.connection.client_id,
-                            )
-                            .await?;
-
-                        let encoded_proof_commitment = voyager_client
-                            .encode_proof::<IbcUnion>(
-                                client_info.client_type,
-                                client_info.ibc_interface,
-                                proof_unreceived.proof,
-                            )
-                            .await?;
-
-                        Ok(call(SubmitTx {
-                            chain_id,
-                            datagrams: vec![IbcDatagram::new::<IbcUnion>(Datagram::from(
-                                MsgPacketTimeout {
-                                    packet: event.packet(),
-                                    proof: encoded_proof_commitment,
-                                    proof_height: client_meta.counterparty_height.height(),
-                                },
-                            ))],
-                        }))
-                    }
-                    ProofType::Membership => {
-                        warn!(
-                            packet_hash = %event.packet().hash(),
-                            "packet timed out, but it was already received on the counterparty"
-                        );
-
-                        Ok(noop())
-                    }
-                }
+            ModuleCall::MakeMsgTimeout(call) => self.make_msg_timeout(voyager_client, call).await,
+            ModuleCall::WaitForTimeoutOrReceiptClassic(call) => {
+                self.wait_for_timeout_or_receipt_classic(voyager_client, call)
+                    .await
+            }
+            ModuleCall::MakeMsgTimeoutClassic(call) => {
+                self.make_msg_timeout_classic(voyager_client, call).await
             }
         }
     }
@@ -265,6 +439,34 @@ impl Module {
             counterparty_chain_id,
         }: WaitForTimeoutOrReceipt,
     ) -> RpcResult<Op<VoyagerMessage>> {
+        let Some(policy) = self.policy(
+            &chain_id,
+            &event.packet.source_channel.channel_id.to_string(),
+        ) else {
+            debug!("no timeout policy for packet");
+            return Ok(noop());
+        };
+
+        if let Some(source_ports) = &policy.source_ports {
+            // the source port is only stored in the counterparty end of the channel
+            let source_port = voyager_client
+                .query_ibc_state(
+                    counterparty_chain_id.clone(),
+                    QueryHeight::Latest,
+                    ChannelPath {
+                        channel_id: event.packet.destination_channel.channel_id,
+                    },
+                )
+                .await?
+                .counterparty_port_id
+                .to_string();
+
+            if !source_ports.contains(&source_port) {
+                debug!(%source_port, "packet was not sent from a configured source port");
+                return Ok(noop());
+            }
+        }
+
         let counterparty_latest_height = voyager_client
             .query_latest_height(counterparty_chain_id.clone(), false)
             .await?;
@@ -285,40 +487,13 @@ impl Module {
                 Ok(noop())
             }
             None => {
-                debug!("packet not received yet");
-
-                if event.packet.timeout_height != 0
-                    && event.packet.timeout_height > counterparty_latest_height.height()
-                {
-                    info!(
-                        "packet timed out (height): {} <= {}",
-                        event.packet.timeout_height, counterparty_latest_height
-                    );
-
-                    Ok(self.mk_wait(chain_id, counterparty_chain_id, event))
-                } else if !event.packet.timeout_timestamp.is_zero() {
-                    let counterparty_timestamp = voyager_client
-                        .query_latest_timestamp(counterparty_chain_id.clone(), false)
-                        .await?;
-
-                    if event.packet.timeout_timestamp <= counterparty_timestamp {
-                        info!(
-                            "packet timed out (timestamp): {} <= {}",
-                            event.packet.timeout_timestamp, counterparty_timestamp
-                        );
-                    }
+                debug!(
+                    timeout_height = event.packet.timeout_height,
+                    timeout_timestamp = %event.packet.timeout_timestamp,
+                    "packet not received yet, waiting for timeout"
+                );
 
-                    Ok(self.mk_wait(chain_id, counterparty_chain_id, event))
-                } else {
-                    Ok(call(PluginMessage::new(
-                        self.plugin_name(),
-                        ModuleCall::WaitForTimeoutOrReceipt(WaitForTimeoutOrReceipt {
-                            event,
-                            chain_id,
-                            counterparty_chain_id,
-                        }),
-                    )))
-                }
+                Ok(self.mk_wait(chain_id, counterparty_chain_id, event))
             }
         }
     }
@@ -362,8 +537,729 @@ impl Module {
                     event,
                     chain_id,
                     counterparty_chain_id,
+                    grace_period_elapsed: false,
                 }),
             )),
         ])
     }
+
+    #[instrument(
+        skip_all,
+        fields(
+            %chain_id,
+            %counterparty_chain_id,
+            packet_hash = %event.packet().hash()
+        )
+    )]
+    async fn make_msg_timeout(
+        &self,
+        voyager_client: &VoyagerClient,
+        MakeMsgTimeout {
+            event,
+            chain_id,
+            counterparty_chain_id,
+            grace_period_elapsed,
+        }: MakeMsgTimeout,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let grace_period = self.grace_period(
+            &chain_id,
+            &event.packet.source_channel.channel_id.to_string(),
+        );
+
+        if !grace_period_elapsed && grace_period > 0 {
+            info!(grace_period, "packet timed out, waiting for grace period");
+
+            return Ok(seq([
+                defer(now() + grace_period),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::from(MakeMsgTimeout {
+                        event,
+                        chain_id,
+                        counterparty_chain_id,
+                        grace_period_elapsed: true,
+                    }),
+                )),
+            ]));
+        }
+
+        // the packet may have already been timed out (or acknowledged) by someone else
+        let commitment = voyager_client
+            .maybe_query_ibc_state(
+                chain_id.clone(),
+                QueryHeight::Latest,
+                BatchPacketsPath::from_packets(&[event.packet()]),
+            )
+            .await?
+            .state;
+
+        if commitment != Some(COMMITMENT_MAGIC) {
+            info!(
+                commitment = ?commitment,
+                "packet has already been acknowledged or timed out"
+            );
+
+            return Ok(noop());
+        }
+
+        let client_meta = voyager_client
+            .client_state_meta::<IbcUnion>(
+                chain_id.clone(),
+                QueryHeight::Latest,
+                event.packet.source_channel.connection.client_id,
+            )
+            .await?;
+
+        let proof_unreceived = voyager_client
+            .query_ibc_proof(
+                counterparty_chain_id,
+                QueryHeight::Specific(client_meta.counterparty_height),
+                BatchReceiptsPath::from_packets(&[event.packet().clone()]),
+            )
+            .await?
+            .into_result()?;
+
+        match proof_unreceived.proof_type {
+            ProofType::NonMembership => {
+                let client_info = voyager_client
+                    .client_info::<IbcUnion>(
+                        chain_id.clone(),
+                        event.packet.source_channel.connection.client_id,
+                    )
+                    .await?;
+
+                let encoded_proof_commitment = voyager_client
+                    .encode_proof::<IbcUnion>(
+                        client_info.client_type,
+                        client_info.ibc_interface,
+                        proof_unreceived.proof,
+                    )
+                    .await?;
+
+                Ok(call(SubmitTx {
+                    chain_id,
+                    datagrams: vec![IbcDatagram::new::<IbcUnion>(Datagram::from(
+                        MsgPacketTimeout {
+                            packet: event.packet(),
+                            proof: encoded_proof_commitment,
+                            proof_height: client_meta.counterparty_height.height(),
+                        },
+                    ))],
+                }))
+            }
+            ProofType::Membership => {
+                warn!("packet timed out, but it was already received on the counterparty");
+
+                Ok(noop())
+            }
+        }
+    }
+
+    #[instrument(
+        skip_all,
+        fields(
+            %chain_id,
+            %counterparty_chain_id,
+            port_id = %event.packet.source_channel.port_id,
+            channel_id = %event.packet.source_channel.channel_id,
+            sequence = %event.packet.sequence,
+        )
+    )]
+    async fn wait_for_timeout_or_receipt_classic(
+        &self,
+        voyager_client: &VoyagerClient,
+        WaitForTimeoutOrReceiptClassic {
+            event,
+            chain_id,
+            counterparty_chain_id,
+        }: WaitForTimeoutOrReceiptClassic,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let Some(policy) = self.policy(
+            &chain_id,
+            &event.packet.source_channel.channel_id.to_string_prefixed(),
+        ) else {
+            debug!("no timeout policy for packet");
+            return Ok(noop());
+        };
+
+        if let Some(source_ports) = &policy.source_ports {
+            let source_port = event.packet.source_channel.port_id.to_string();
+
+            if !source_ports.contains(&source_port) {
+                debug!(%source_port, "packet was not sent from a configured source port");
+                return Ok(noop());
+            }
+        }
+
+        let received = match event.packet.channel_ordering {
+            Order::Ordered => {
+                let next_sequence_recv = voyager_client
+                    .query_ibc_state(
+                        counterparty_chain_id.clone(),
+                        QueryHeight::Latest,
+                        NextSequenceRecvPath {
+                            port_id: event.packet.destination_channel.port_id.clone(),
+                            channel_id: event.packet.destination_channel.channel_id.clone(),
+                        },
+                    )
+                    .await?;
+
+                next_sequence_recv > event.packet.sequence.get()
+            }
+            _ => voyager_client
+                .maybe_query_ibc_state(
+                    counterparty_chain_id.clone(),
+                    QueryHeight::Latest,
+                    ReceiptPath {
+                        port_id: event.packet.destination_channel.port_id.clone(),
+                        channel_id: event.packet.destination_channel.channel_id.clone(),
+                        sequence: event.packet.sequence,
+                    },
+                )
+                .await?
+                .state
+                .unwrap_or_default(),
+        };
+
+        if received {
+            info!("packet received");
+            return Ok(noop());
+        }
+
+        debug!(
+            timeout_height = %event.packet.timeout_height,
+            timeout_timestamp = event.packet.timeout_timestamp,
+            "packet not received yet, waiting for timeout"
+        );
+
+        Ok(self.mk_wait_classic(chain_id, counterparty_chain_id, event))
+    }
+
+    fn mk_wait_classic(
+        &self,
+        chain_id: ChainId,
+        counterparty_chain_id: ChainId,
+        event: SendPacket,
+    ) -> Op<VoyagerMessage> {
+        let client_id = RawClientId::new(event.packet.source_channel.connection.client_id.clone());
+
+        seq([
+            conc(
+                [
+                    (event.packet.timeout_height.height() != 0).then(|| {
+                        call(WaitForTrustedHeight {
+                            chain_id: chain_id.clone(),
+                            ibc_spec_id: IbcClassic::ID,
+                            client_id: client_id.clone(),
+                            height: event.packet.timeout_height,
+                            finalized: false,
+                        })
+                    }),
+                    (event.packet.timeout_timestamp != 0).then(|| {
+                        call(WaitForTrustedTimestamp {
+                            chain_id: chain_id.clone(),
+                            ibc_spec_id: IbcClassic::ID,
+                            client_id: client_id.clone(),
+                            timestamp: Timestamp::from_nanos(event.packet.timeout_timestamp),
+                            finalized: false,
+                        })
+                    }),
+                ]
+                .into_iter()
+                .flatten(),
+            ),
+            call(PluginMessage::new(
+                self.plugin_name(),
+                ModuleCall::from(MakeMsgTimeoutClassic {
+                    event,
+                    chain_id,
+                    counterparty_chain_id,
+                    grace_period_elapsed: false,
+                }),
+            )),
+        ])
+    }
+
+    #[instrument(
+        skip_all,
+        fields(
+            %chain_id,
+            %counterparty_chain_id,
+            port_id = %event.packet.source_channel.port_id,
+            channel_id = %event.packet.source_channel.channel_id,
+            sequence = %event.packet.sequence,
+        )
+    )]
+    async fn make_msg_timeout_classic(
+        &self,
+        voyager_client: &VoyagerClient,
+        MakeMsgTimeoutClassic {
+            event,
+            chain_id,
+            counterparty_chain_id,
+            grace_period_elapsed,
+        }: MakeMsgTimeoutClassic,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let grace_period = self.grace_period(
+            &chain_id,
+            &event.packet.source_channel.channel_id.to_string_prefixed(),
+        );
+
+        if !grace_period_elapsed && grace_period > 0 {
+            info!(grace_period, "packet timed out, waiting for grace period");
+
+            return Ok(seq([
+                defer(now() + grace_period),
+                call(PluginMessage::new(
+                    self.plugin_name(),
+                    ModuleCall::from(MakeMsgTimeoutClassic {
+                        event,
+                        chain_id,
+                        counterparty_chain_id,
+                        grace_period_elapsed: true,
+                    }),
+                )),
+            ]));
+        }
+
+        // the packet commitment is deleted once the packet is acknowledged or timed out
+        let commitment = voyager_client
+            .maybe_query_ibc_state(
+                chain_id.clone(),
+                QueryHeight::Latest,
+                CommitmentPath {
+                    port_id: event.packet.source_channel.port_id.clone(),
+                    channel_id: event.packet.source_channel.channel_id.clone(),
+                    sequence: event.packet.sequence,
+                },
+            )
+            .await?
+            .state;
+
+        if commitment.is_none_or(|commitment| commitment == H256::default()) {
+            info!("packet has already been acknowledged or timed out");
+
+            return Ok(noop());
+        }
+
+        let client_id = event.packet.source_channel.connection.client_id.clone();
+
+        let client_meta = voyager_client
+            .client_state_meta::<IbcClassic>(
+                chain_id.clone(),
+                QueryHeight::Latest,
+                client_id.clone(),
+            )
+            .await?;
+
+        let at = QueryHeight::Specific(client_meta.counterparty_height);
+
+        let destination_port_id = event.packet.destination_channel.port_id.clone();
+        let destination_channel_id = event.packet.destination_channel.channel_id.clone();
+
+        let (proof_unreceived, next_sequence_recv) = match event.packet.channel_ordering {
+            Order::Ordered => {
+                let path = NextSequenceRecvPath {
+                    port_id: destination_port_id,
+                    channel_id: destination_channel_id,
+                };
+
+                let next_sequence_recv = voyager_client
+                    .query_ibc_state(counterparty_chain_id.clone(), at, path.clone())
+                    .await?;
+
+                if next_sequence_recv > event.packet.sequence.get() {
+                    warn!("packet timed out, but it was already received on the counterparty");
+
+                    return Ok(noop());
+                }
+
+                let proof = voyager_client
+                    .query_ibc_proof(counterparty_chain_id, at, path)
+                    .await?
+                    .into_result()?;
+
+                (proof.proof, next_sequence_recv)
+            }
+            _ => {
+                let proof = voyager_client
+                    .query_ibc_proof(
+                        counterparty_chain_id,
+                        at,
+                        ReceiptPath {
+                            port_id: destination_port_id,
+                            channel_id: destination_channel_id,
+                            sequence: event.packet.sequence,
+                        },
+                    )
+                    .await?
+                    .into_result()?;
+
+                if proof.proof_type == ProofType::Membership {
+                    warn!("packet timed out, but it was already received on the counterparty");
+
+                    return Ok(noop());
+                }
+
+                // unused for unordered channels
+                (proof.proof, event.packet.sequence.get())
+            }
+        };
+
+        let Some(next_sequence_recv) = next_sequence_recv.try_into().ok() else {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                "next sequence recv of the counterparty channel is zero",
+                None::<()>,
+            ));
+        };
+
+        let client_info = voyager_client
+            .client_info::<IbcClassic>(chain_id.clone(), client_id)
+            .await?;
+
+        let encoded_proof = voyager_client
+            .encode_proof::<IbcClassic>(
+                client_info.client_type,
+                client_info.ibc_interface,
+                proof_unreceived,
+            )
+            .await?;
+
+        Ok(call(SubmitTx {
+            chain_id,
+            datagrams: vec![IbcDatagram::new::<IbcClassic>(
+                ibc_classic_spec::Datagram::from(MsgTimeout {
+                    packet: classic_packet(&event),
+                    proof_unreceived: encoded_proof.into(),
+                    proof_height: client_meta.counterparty_height,
+                    next_sequence_recv,
+                }),
+            )],
+        }))
+    }
+}
+
+fn classic_packet(event: &SendPacket) -> Packet {
+    Packet {
+        sequence: event.packet.sequence,
+        source_port: event.packet.source_channel.port_id.clone(),
+        source_channel: event.packet.source_channel.channel_id.clone(),
+        destination_port: event.packet.destination_channel.port_id.clone(),
+        destination_channel: event.packet.destination_channel.channel_id.clone(),
+        data: event.packet_data.clone(),
+        timeout_height: event.packet.timeout_height,
+        timeout_timestamp: event.packet.timeout_timestamp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ibc_union_spec::{
+        event::{ChannelMetadata, ConnectionMetadata, PacketMetadata},
+        ChannelId, ClientId, ConnectionId,
+    };
+    use unionlabs::id::PortId;
+    use voyager_sdk::{
+        message::data::{ChainEvent, EventProvableHeight},
+        primitives::{ClientInfo, ClientType, IbcInterface},
+    };
+
+    use super::*;
+
+    fn policy(chain_id: &str, channel_id: Option<&str>, grace_period: u64) -> TimeoutPolicy {
+        TimeoutPolicy {
+            chain_id: ChainId::new(chain_id.to_owned()),
+            channel_id: channel_id.map(ToOwned::to_owned),
+            grace_period,
+            source_ports: None,
+        }
+    }
+
+    fn module() -> Module {
+        Module::new(Config {
+            policies: vec![
+                policy("chain-a", None, 10),
+                policy("chain-a", Some("1"), 20),
+                policy("chain-b", Some("channel-0"), 30),
+            ],
+            dedup_window: default_dedup_window(),
+        })
+    }
+
+    fn packet_send() -> PacketSend {
+        let channel = |id| ChannelMetadata {
+            channel_id: ChannelId::from_raw(id).unwrap(),
+            version: "ucs03-zkgm-0".to_owned(),
+            connection: ConnectionMetadata {
+                client_id: ClientId::from_raw(id).unwrap(),
+                connection_id: ConnectionId::from_raw(id).unwrap(),
+            },
+        };
+
+        PacketSend {
+            packet_data: b"data".to_vec().into(),
+            packet: PacketMetadata {
+                source_channel: channel(1),
+                destination_channel: channel(2),
+                timeout_height: 0,
+                timeout_timestamp: Timestamp::from_nanos(1),
+            },
+        }
+    }
+
+    fn send_packet(timeout_height: Height, timeout_timestamp: u64) -> SendPacket {
+        let channel = |port_id, id| ibc_classic_spec::ChannelMetadata {
+            port_id: PortId::new_static(port_id).unwrap(),
+            channel_id: unionlabs::id::ChannelId::new(id),
+            version: "ics20-1".to_owned(),
+            connection: ibc_classic_spec::ConnectionMetadata {
+                client_id: unionlabs::id::ClientId::new_static("07-tendermint", id),
+                connection_id: unionlabs::id::ConnectionId::new(id),
+            },
+        };
+
+        SendPacket {
+            packet_data: b"data".to_vec().into(),
+            packet: ibc_classic_spec::PacketMetadata {
+                sequence: 1.try_into().unwrap(),
+                source_channel: channel("transfer", 0),
+                destination_channel: channel("transfer", 5),
+                channel_ordering: Order::Unordered,
+                timeout_height,
+                timeout_timestamp,
+            },
+        }
+    }
+
+    fn chain_event<V: IbcSpec>(chain_id: &str, event: impl Into<V::Event>) -> Op<VoyagerMessage> {
+        Op::Data(Data::IbcEvent(ChainEvent::new::<V>(
+            ChainId::new(chain_id.to_owned()),
+            ClientInfo {
+                client_type: ClientType::new_static(ClientType::TENDERMINT),
+                ibc_interface: IbcInterface::new_static(IbcInterface::IBC_COSMWASM),
+                metadata: Default::default(),
+            },
+            ChainId::new("counterparty"),
+            H256::default(),
+            EventProvableHeight::Min(Height::new(1)),
+            event.into(),
+        )))
+    }
+
+    #[test]
+    fn policy_precedence() {
+        let module = module();
+
+        assert_eq!(
+            module.policy(&ChainId::new("chain-a"), "1"),
+            Some(policy("chain-a", Some("1"), 20))
+        );
+        assert_eq!(
+            module.policy(&ChainId::new("chain-a"), "2"),
+            Some(policy("chain-a", None, 10))
+        );
+        assert_eq!(
+            module.policy(&ChainId::new("chain-b"), "channel-0"),
+            Some(policy("chain-b", Some("channel-0"), 30))
+        );
+        assert_eq!(module.policy(&ChainId::new("chain-b"), "channel-1"), None);
+        assert_eq!(module.policy(&ChainId::new("chain-c"), "1"), None);
+
+        assert_eq!(module.grace_period(&ChainId::new("chain-a"), "2"), 10);
+        assert_eq!(module.grace_period(&ChainId::new("chain-c"), "1"), 0);
+    }
+
+    #[test]
+    fn no_policies_times_out_all_packets() {
+        let module = Module::new(Config {
+            policies: vec![],
+            dedup_window: default_dedup_window(),
+        });
+
+        assert_eq!(
+            module.policy(&ChainId::new("chain-c"), "channel-7"),
+            Some(policy("chain-c", None, 0))
+        );
+    }
+
+    #[test]
+    fn parse_config() {
+        let config = serde_json::from_value::<Config>(json!({
+            "policies": [
+                { "chain_id": "chain-a", "grace_period": 10 },
+                {
+                    "chain_id": "chain-b",
+                    "channel_id": "channel-0",
+                    "source_ports": ["transfer"]
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            config.policies,
+            [
+                policy("chain-a", None, 10),
+                TimeoutPolicy {
+                    source_ports: Some(["transfer".to_owned()].into()),
+                    ..policy("chain-b", Some("channel-0"), 0)
+                }
+            ]
+        );
+
+        assert_eq!(
+            serde_json::from_value::<Config>(json!({})).unwrap(),
+            Config {
+                policies: vec![],
+                dedup_window: default_dedup_window(),
+            }
+        );
+    }
+
+    #[test]
+    fn wait_classic() {
+        let module = module();
+
+        let wait = |event: SendPacket| {
+            module.mk_wait_classic(ChainId::new("chain-b"), ChainId::new("counterparty"), event)
+        };
+
+        let make_msg_timeout = |event: SendPacket| {
+            call(PluginMessage::new(
+                PLUGIN_NAME,
+                ModuleCall::from(MakeMsgTimeoutClassic {
+                    event,
+                    chain_id: ChainId::new("chain-b"),
+                    counterparty_chain_id: ChainId::new("counterparty"),
+                    grace_period_elapsed: false,
+                }),
+            ))
+        };
+
+        let client_id = RawClientId::new(unionlabs::id::ClientId::new_static("07-tendermint", 0));
+
+        let event = send_packet(Height::new_with_revision(1, 100), 0);
+        assert_eq!(
+            wait(event.clone()),
+            seq([
+                conc([call(WaitForTrustedHeight {
+                    chain_id: ChainId::new("chain-b"),
+                    ibc_spec_id: IbcClassic::ID,
+                    client_id: client_id.clone(),
+                    height: Height::new_with_revision(1, 100),
+                    finalized: false,
+                })]),
+                make_msg_timeout(event),
+            ])
+        );
+
+        let event = send_packet(Height::default(), 1_000);
+        assert_eq!(
+            wait(event.clone()),
+            seq([
+                conc([call(WaitForTrustedTimestamp {
+                    chain_id: ChainId::new("chain-b"),
+                    ibc_spec_id: IbcClassic::ID,
+                    client_id,
+                    timestamp: Timestamp::from_nanos(1_000),
+                    finalized: false,
+                })]),
+                make_msg_timeout(event),
+            ])
+        );
+    }
+
+    #[test]
+    fn classic_packet_from_event() {
+        let event = send_packet(Height::new_with_revision(1, 100), 1_000);
+
+        assert_eq!(
+            classic_packet(&event),
+            Packet {
+                sequence: 1.try_into().unwrap(),
+                source_port: PortId::new_static("transfer").unwrap(),
+                source_channel: unionlabs::id::ChannelId::new(0),
+                destination_port: PortId::new_static("transfer").unwrap(),
+                destination_channel: unionlabs::id::ChannelId::new(5),
+                data: b"data".to_vec().into(),
+                timeout_height: Height::new_with_revision(1, 100),
+                timeout_timestamp: 1_000,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn deduplicates_packets_within_a_pass() {
+        let module = module();
+
+        let classic = send_packet(Height::new_with_revision(1, 100), 0);
+
+        let res = module
+            .run_pass(
+                &Extensions::new(),
+                vec![
+                    chain_event::<IbcUnion>("chain-a", packet_send()),
+                    chain_event::<IbcClassic>("chain-b", classic.clone()),
+                    chain_event::<IbcUnion>("chain-a", packet_send()),
+                    // no policy for this chain
+                    chain_event::<IbcUnion>("chain-c", packet_send()),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert!(res.optimize_further.is_empty());
+        assert_eq!(
+            res.ready,
+            [
+                (
+                    vec![0, 2],
+                    call(PluginMessage::new(
+                        PLUGIN_NAME,
+                        ModuleCall::from(WaitForTimeoutOrReceipt {
+                            event: packet_send(),
+                            chain_id: ChainId::new("chain-a"),
+                            counterparty_chain_id: ChainId::new("counterparty"),
+                        }),
+                    ))
+                ),
+                (
+                    vec![1],
+                    call(PluginMessage::new(
+                        PLUGIN_NAME,
+                        ModuleCall::from(WaitForTimeoutOrReceiptClassic {
+                            event: classic,
+                            chain_id: ChainId::new("chain-b"),
+                            counterparty_chain_id: ChainId::new("counterparty"),
+                        }),
+                    ))
+                ),
+                (vec![3], noop()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn deduplicates_packets_across_passes() {
+        let module = module();
+
+        let extensions = Extensions::new();
+        let run_pass = |msgs| module.run_pass(&extensions, msgs);
+
+        let res = run_pass(vec![chain_event::<IbcUnion>("chain-a", packet_send())])
+            .await
+            .unwrap();
+        assert_eq!(res.ready.len(), 1);
+        assert_ne!(res.ready[0].1, noop());
+
+        // the packet was already queued in the previous pass
+        let res = run_pass(vec![
+            chain_event::<IbcUnion>("chain-a", packet_send()),
+            chain_event::<IbcUnion>("chain-a", packet_send()),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(res.ready, [(vec![0, 1], noop())]);
+    }
 }
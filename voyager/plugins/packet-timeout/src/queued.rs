@@ -0,0 +1,111 @@
+use std::collections::{HashMap, VecDeque};
+
+/// The packets that have been queued to be timed out recently, used to deduplicate packets across
+/// passes.
+///
+/// Packets are forgotten once they have been tracked for longer than the window, or when more than
+/// `capacity` packets are tracked (oldest first).
+#[derive(Debug)]
+pub struct QueuedPackets {
+    /// How long (in seconds) a packet is tracked after it was queued.
+    window: u64,
+    capacity: usize,
+    /// packet -> unix timestamp (in seconds) at which it was queued
+    queued: HashMap<String, u64>,
+    /// The packets in the order they were queued.
+    order: VecDeque<(u64, String)>,
+}
+
+impl QueuedPackets {
+    pub fn new(window: u64, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity,
+            queued: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Track `packet` as queued at `now`. Returns `false` if the packet was already queued within
+    /// the window.
+    pub fn insert(&mut self, packet: String, now: u64) -> bool {
+        self.expire(now);
+
+        if self.queued.contains_key(&packet) {
+            return false;
+        }
+
+        while self.queued.len() >= self.capacity {
+            let Some((queued_at, oldest)) = self.order.pop_front() else {
+                break;
+            };
+
+            self.remove(&oldest, queued_at);
+        }
+
+        self.queued.insert(packet.clone(), now);
+        self.order.push_back((now, packet));
+
+        true
+    }
+
+    /// Forget all packets that were queued longer than the window ago.
+    fn expire(&mut self, now: u64) {
+        while let Some((queued_at, _)) = self.order.front() {
+            if queued_at.saturating_add(self.window) > now {
+                break;
+            }
+
+            let (queued_at, packet) = self.order.pop_front().expect("front exists; qed;");
+
+            self.remove(&packet, queued_at);
+        }
+    }
+
+    fn remove(&mut self, packet: &str, queued_at: u64) {
+        if self.queued.get(packet) == Some(&queued_at) {
+            self.queued.remove(packet);
+        }
+    }
+
+    #[cfg(test)]
+    fn tracked(&self) -> usize {
+        self.queued.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deduplicates_within_window() {
+        let mut queued = QueuedPackets::new(10, 100);
+
+        assert!(queued.insert("a".to_owned(), 0));
+        assert!(!queued.insert("a".to_owned(), 9));
+        assert!(queued.insert("b".to_owned(), 9));
+
+        // a expires, b is still tracked
+        assert!(queued.insert("a".to_owned(), 10));
+        assert!(!queued.insert("b".to_owned(), 18));
+        assert!(queued.insert("b".to_owned(), 19));
+
+        assert_eq!(queued.tracked(), 2);
+    }
+
+    #[test]
+    fn bounded() {
+        let mut queued = QueuedPackets::new(10, 2);
+
+        assert!(queued.insert("a".to_owned(), 0));
+        assert!(queued.insert("b".to_owned(), 1));
+        // a is evicted to make room for c
+        assert!(queued.insert("c".to_owned(), 2));
+        assert_eq!(queued.tracked(), 2);
+
+        assert!(!queued.insert("b".to_owned(), 3));
+        assert!(!queued.insert("c".to_owned(), 3));
+        assert!(queued.insert("a".to_owned(), 3));
+    }
+}
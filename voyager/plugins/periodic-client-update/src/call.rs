@@ -20,4 +20,9 @@ pub struct CheckForClientAge {
     pub client_id: RawClientId,
     /// The maximum amount of blocks this client can lag behind the latest finalized height of the chain it's tracking.
     pub max_age: u64,
+    /// The percentage of the client's trusting period that the latest consensus state of this client
+    /// can lag behind the latest finalized timestamp of the chain it's tracking.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=100))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trusting_period_percent: Option<u8>,
 }
@@ -1,25 +1,31 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
+use ibc_classic_spec::IbcClassic;
+use ibc_union_spec::IbcUnion;
 use jsonrpsee::{
     core::{async_trait, RpcResult},
+    types::ErrorObject,
     Extensions,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{error, info, instrument};
-use unionlabs::never::Never;
+use tracing::{debug, error, info, info_span, instrument, Instrument};
+use unionlabs::{never::Never, ErrorReporter};
 use voyager_sdk::{
-    anyhow, into_value,
+    anyhow::{self, bail},
+    into_value,
     message::{
-        call::{FetchUpdateHeaders, WaitForTrustedHeight},
-        callback::AggregateSubmitTxFromOrderedHeaders,
-        data::Data,
+        call::FetchUpdateHeaders, callback::AggregateSubmitTxFromOrderedHeaders, data::Data,
         PluginMessage, VoyagerMessage,
     },
     plugin::Plugin,
-    primitives::{ChainId, IbcSpecId, QueryHeight},
-    rpc::{types::PluginInfo, PluginServer},
+    primitives::{ChainId, ClientInfo, ClientType, IbcSpecId, QueryHeight},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
+    serde_json::{self, Value},
     types::RawClientId,
-    vm::{call, conc, defer, now, pass::PassResult, promise, seq, Op},
+    vm::{call, noop, pass::PassResult, promise, Op},
     ExtensionsExt, VoyagerClient,
 };
 
@@ -32,11 +38,79 @@ async fn main() {
     Module::run().await
 }
 
-pub struct Module {}
+pub struct Module {
+    pub targets: Vec<ClientUpdateTarget>,
+    pub check_interval: u64,
+    pub update_timeout: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The clients to keep updated. The age of each of these clients is checked every
+    /// `check_interval` seconds for as long as the plugin is running.
+    #[serde(default)]
+    pub targets: Vec<ClientUpdateTarget>,
+
+    /// How often to check the age of the configured clients, in seconds.
+    #[serde(default = "default_check_interval")]
+    pub check_interval: u64,
+
+    /// How long to wait for an update to a client to land before enqueueing another one, in
+    /// seconds.
+    #[serde(default = "default_update_timeout")]
+    pub update_timeout: u64,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub struct Config {}
+pub struct ClientUpdateTarget {
+    pub chain_id: ChainId,
+    pub ibc_spec_id: IbcSpecId,
+    pub client_id: RawClientId,
+    /// The maximum amount of blocks this client can lag behind the latest finalized height of the
+    /// chain it's tracking.
+    pub max_age: u64,
+    /// The percentage of the client's trusting period after which the client will be updated. The
+    /// trusting period is read from the client state.
+    #[serde(default)]
+    pub trusting_period_percent: Option<u8>,
+}
+
+impl ClientUpdateTarget {
+    fn check(&self) -> CheckForClientAge {
+        CheckForClientAge {
+            chain_id: self.chain_id.clone(),
+            ibc_spec_id: self.ibc_spec_id.clone(),
+            client_id: self.client_id.clone(),
+            max_age: self.max_age,
+            trusting_period_percent: self.trusting_period_percent,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(percent) = self.trusting_period_percent {
+            if !(1..=100).contains(&percent) {
+                bail!(
+                    "invalid trusting period percentage {percent} for client {} on {}, must be \
+                    in the range [1, 100]",
+                    self.client_id.as_raw(),
+                    self.chain_id
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn default_check_interval() -> u64 {
+    60
+}
+
+fn default_update_timeout() -> u64 {
+    600
+}
 
 impl Plugin for Module {
     type Call = ModuleCall;
@@ -46,140 +120,339 @@ impl Plugin for Module {
     type Cmd = Cmd;
 
     async fn new(config: Self::Config) -> anyhow::Result<Self> {
-        Ok(Module::new(config))
-    }
+        for target in &config.targets {
+            target.validate()?;
+        }
 
-    fn info(config: Self::Config) -> PluginInfo {
-        let module = Module::new(config);
+        Ok(Module {
+            targets: config.targets,
+            check_interval: config.check_interval,
+            update_timeout: config.update_timeout,
+        })
+    }
 
+    fn info(_: Self::Config) -> PluginInfo {
         PluginInfo {
-            name: module.plugin_name(),
+            name: plugin_name(),
             // never interested in any messages since this plugin does not utilize a queue
             interest_filter: "null".to_owned(),
         }
     }
 
-    async fn cmd(config: Self::Config, cmd: Self::Cmd) {
-        let module = Self::new(config);
-
+    async fn cmd(_: Self::Config, cmd: Self::Cmd) {
         match cmd {
             Cmd::MakeMessage(msg) => {
-                let op = call::<VoyagerMessage>(PluginMessage::new(
-                    module.plugin_name(),
-                    ModuleCall::CheckForClientAge(msg),
-                ));
-
-                println!("{}", into_value(op));
+                println!("{}", into_value(check_for_client_age(msg)));
             }
         }
     }
+
+    fn connected(&self, voyager_client: VoyagerClient) {
+        if self.targets.is_empty() {
+            return;
+        }
+
+        tokio::spawn(
+            keep_clients_updated(
+                voyager_client,
+                self.targets.clone(),
+                Duration::from_secs(self.check_interval),
+                Duration::from_secs(self.update_timeout),
+            )
+            .instrument(info_span!("keep_clients_updated")),
+        );
+    }
 }
 
 #[derive(clap::Parser)]
 pub enum Cmd {
+    /// Make an op that checks the age of a client once, updating it if it is older than the
+    /// provided thresholds.
     MakeMessage(CheckForClientAge),
 }
 
-impl Module {
-    fn plugin_name(&self) -> String {
-        pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+fn plugin_name() -> String {
+    pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+    PLUGIN_NAME.to_owned()
+}
+
+fn check_for_client_age(check: CheckForClientAge) -> Op<VoyagerMessage> {
+    call(PluginMessage::new(
+        plugin_name(),
+        ModuleCall::CheckForClientAge(check),
+    ))
+}
+
+/// Check the age of all of the configured targets every `check_interval`, enqueueing an update for
+/// every client that is older than its thresholds.
+///
+/// This runs for as long as the plugin is running, and is started again whenever the plugin is
+/// (re)started, so there is always exactly one check in flight per target. Since the updates are
+/// enqueued independently of the checks, a failed update does not stop the client from being
+/// checked; once `update_timeout` has passed, the next check will enqueue another update if the
+/// client is still too old.
+async fn keep_clients_updated(
+    voyager_client: VoyagerClient,
+    targets: Vec<ClientUpdateTarget>,
+    check_interval: Duration,
+    update_timeout: Duration,
+) {
+    let mut last_update = vec![None::<Instant>; targets.len()];
 
-        PLUGIN_NAME.to_owned()
+    loop {
+        for (target, last_update) in targets.iter().zip(&mut last_update) {
+            if update_pending(*last_update, Instant::now(), update_timeout) {
+                debug!(
+                    chain_id = %target.chain_id,
+                    client_id = %target.client_id.as_raw(),
+                    "update already pending"
+                );
+
+                continue;
+            }
+
+            let op = match check_client_age(&voyager_client, target.check()).await {
+                Ok(Some(op)) => op,
+                Ok(None) => continue,
+                Err(err) => {
+                    error!(
+                        error = %ErrorReporter(err),
+                        chain_id = %target.chain_id,
+                        client_id = %target.client_id.as_raw(),
+                        "error checking client age"
+                    );
+
+                    continue;
+                }
+            };
+
+            match voyager_client.enqueue(op).await {
+                Ok(()) => *last_update = Some(Instant::now()),
+                Err(err) => {
+                    error!(
+                        error = %ErrorReporter(err),
+                        chain_id = %target.chain_id,
+                        client_id = %target.client_id.as_raw(),
+                        "error enqueueing client update"
+                    );
+                }
+            }
+        }
+
+        tokio::time::sleep(check_interval).await;
     }
+}
+
+/// Whether an update enqueued at `last_update` may still land, and as such another update should
+/// not be enqueued yet.
+fn update_pending(last_update: Option<Instant>, now: Instant, update_timeout: Duration) -> bool {
+    last_update
+        .is_some_and(|last_update| now.saturating_duration_since(last_update) < update_timeout)
+}
+
+/// Read the trusting period, in seconds, out of the decoded client state of a client of type
+/// `client_type`.
+fn trusting_period(client_type: &ClientType, client_state: Value) -> anyhow::Result<u64> {
+    match client_type.as_str() {
+        ClientType::TENDERMINT => {
+            let client_state =
+                serde_json::from_value::<tendermint_light_client_types::ClientState>(client_state)?;
+
+            Ok(client_state.trusting_period.seconds().inner().try_into()?)
+        }
+        ClientType::COMETBLS => {
+            let client_state =
+                serde_json::from_value::<cometbls_light_client_types::ClientState>(client_state)?;
 
-    pub fn new(_: Config) -> Self {
-        Self {}
+            Ok(client_state.trusting_period.as_secs())
+        }
+        _ => bail!("reading the trusting period of a {client_type} client is not supported"),
     }
+}
+
+/// Whether a client whose latest consensus state is `age` seconds old has exceeded `percent`% of its
+/// `trusting_period`.
+fn exceeds_trusting_period(age: u64, trusting_period: u64, percent: u8) -> bool {
+    u128::from(age) * 100 >= u128::from(trusting_period) * u128::from(percent)
+}
 
-    #[instrument(
-        skip_all,
-        fields(
-            %chain_id,
-            %ibc_spec_id,
-            client_id = %client_id.as_raw(),
-            max_age
+/// Check the age of a client, returning an op to update it if it is older than the thresholds of
+/// the check.
+#[instrument(
+    skip_all,
+    fields(
+        chain_id = %check.chain_id,
+        ibc_spec_id = %check.ibc_spec_id,
+        client_id = %check.client_id.as_raw(),
+        max_age = check.max_age,
+        trusting_period_percent = check.trusting_period_percent
+    )
+)]
+async fn check_client_age(
+    voyager_client: &VoyagerClient,
+    check: CheckForClientAge,
+) -> RpcResult<Option<Op<VoyagerMessage>>> {
+    let CheckForClientAge {
+        chain_id,
+        ibc_spec_id,
+        client_id,
+        max_age,
+        trusting_period_percent,
+    } = &check;
+
+    let client_state_meta = voyager_client
+        .client_state_meta_raw(
+            chain_id.clone(),
+            ibc_spec_id.clone(),
+            QueryHeight::Latest,
+            client_id.clone(),
         )
-    )]
-    async fn check_for_client_age(
-        &self,
-        voyager_client: &VoyagerClient,
-        chain_id: ChainId,
-        ibc_spec_id: IbcSpecId,
-        client_id: RawClientId,
-        max_age: u64,
-    ) -> RpcResult<Op<VoyagerMessage>> {
-        let client_state_meta = voyager_client
-            .client_state_meta_raw(
+        .await?;
+
+    let client_info = voyager_client
+        .client_info_raw(chain_id.clone(), ibc_spec_id.clone(), client_id.clone())
+        .await?;
+
+    let latest_finalized_height = voyager_client
+        .query_latest_height(client_state_meta.counterparty_chain_id.clone(), true)
+        .await?;
+
+    let too_many_blocks =
+        client_state_meta.counterparty_height.height() + max_age < latest_finalized_height.height();
+
+    let too_old = match trusting_period_percent {
+        Some(percent) => {
+            let client_state =
+                query_client_state(voyager_client, &check, client_info.clone()).await?;
+
+            let trusting_period =
+                trusting_period(&client_info.client_type, client_state).map_err(|err| {
+                    ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        ErrorReporter(&*err).with_message(&format!(
+                            "unable to read the trusting period from the client state of a {} \
+                            client",
+                            client_info.client_type
+                        )),
+                        None::<()>,
+                    )
+                })?;
+
+            let consensus_state_meta = voyager_client
+                .consensus_state_meta_raw(
+                    chain_id.clone(),
+                    ibc_spec_id.clone(),
+                    QueryHeight::Latest,
+                    client_id.clone(),
+                    client_state_meta.counterparty_height,
+                )
+                .await?;
+
+            let latest_finalized_timestamp = voyager_client
+                .query_latest_timestamp(client_state_meta.counterparty_chain_id.clone(), true)
+                .await?;
+
+            let age = latest_finalized_timestamp
+                .as_secs()
+                .saturating_sub(consensus_state_meta.timestamp.as_secs());
+
+            debug!(%age, %trusting_period, "client age");
+
+            exceeds_trusting_period(age, trusting_period, *percent)
+        }
+        None => false,
+    };
+
+    if too_many_blocks || too_old {
+        info!(too_many_blocks, too_old, "client is older than threshold");
+
+        Ok(Some(promise(
+            [call(FetchUpdateHeaders {
+                client_type: client_info.client_type,
+                chain_id: client_state_meta.counterparty_chain_id,
+                counterparty_chain_id: chain_id.clone(),
+                client_id: client_id.clone(),
+                update_from: client_state_meta.counterparty_height,
+                update_to: latest_finalized_height,
+            })],
+            [],
+            AggregateSubmitTxFromOrderedHeaders {
+                ibc_spec_id: ibc_spec_id.clone(),
+                chain_id: chain_id.clone(),
+                client_id: client_id.clone(),
+            },
+        )))
+    } else {
+        debug!("client is within threshold");
+
+        Ok(None)
+    }
+}
+
+/// Query the latest client state of the client being checked, decoded by its client module.
+async fn query_client_state(
+    voyager_client: &VoyagerClient,
+    CheckForClientAge {
+        chain_id,
+        ibc_spec_id,
+        client_id,
+        ..
+    }: &CheckForClientAge,
+    ClientInfo {
+        client_type,
+        ibc_interface,
+        ..
+    }: ClientInfo,
+) -> RpcResult<Value> {
+    let invalid_client_id = |e| {
+        ErrorObject::owned(
+            FATAL_JSONRPC_ERROR_CODE,
+            ErrorReporter(e).with_message("invalid client id"),
+            None::<()>,
+        )
+    };
+
+    if *ibc_spec_id == IbcUnion::ID {
+        let client_state = voyager_client
+            .query_ibc_state(
                 chain_id.clone(),
-                ibc_spec_id.clone(),
                 QueryHeight::Latest,
-                client_id.clone(),
+                ibc_union_spec::path::ClientStatePath {
+                    client_id: client_id
+                        .clone()
+                        .decode_spec::<IbcUnion>()
+                        .map_err(invalid_client_id)?,
+                },
             )
             .await?;
 
-        let client_info = voyager_client
-            .client_info_raw(chain_id.clone(), ibc_spec_id.clone(), client_id.clone())
-            .await?;
-
-        let latest_finalized_height = voyager_client
-            .query_latest_height(client_state_meta.counterparty_chain_id.clone(), true)
+        voyager_client
+            .decode_client_state::<IbcUnion, Value>(client_type, ibc_interface, client_state)
+            .await
+    } else if *ibc_spec_id == IbcClassic::ID {
+        let client_state = voyager_client
+            .query_ibc_state(
+                chain_id.clone(),
+                QueryHeight::Latest,
+                ibc_classic_spec::ClientStatePath {
+                    client_id: client_id
+                        .clone()
+                        .decode_spec::<IbcClassic>()
+                        .map_err(invalid_client_id)?,
+                },
+            )
             .await?;
 
-        if client_state_meta.counterparty_height.height() + max_age
-            < latest_finalized_height.height()
-        {
-            info!("client is older than threshold");
-
-            Ok(conc([
-                promise(
-                    [call(FetchUpdateHeaders {
-                        client_type: client_info.client_type,
-                        chain_id: client_state_meta.counterparty_chain_id,
-                        counterparty_chain_id: chain_id.clone(),
-                        client_id: client_id.clone(),
-                        update_from: client_state_meta.counterparty_height,
-                        update_to: latest_finalized_height,
-                    })],
-                    [],
-                    AggregateSubmitTxFromOrderedHeaders {
-                        ibc_spec_id: ibc_spec_id.clone(),
-                        chain_id: chain_id.clone(),
-                        client_id: client_id.clone(),
-                    },
-                ),
-                seq([
-                    call(WaitForTrustedHeight {
-                        chain_id: chain_id.clone(),
-                        ibc_spec_id: ibc_spec_id.clone(),
-                        client_id: client_id.clone(),
-                        height: client_state_meta.counterparty_height.increment_by(max_age),
-                        finalized: false,
-                    }),
-                    call(PluginMessage::new(
-                        self.plugin_name(),
-                        ModuleCall::CheckForClientAge(CheckForClientAge {
-                            chain_id,
-                            ibc_spec_id,
-                            client_id,
-                            max_age,
-                        }),
-                    )),
-                ]),
-            ]))
-        } else {
-            Ok(seq([
-                defer(now() + 60),
-                call(PluginMessage::new(
-                    self.plugin_name(),
-                    ModuleCall::CheckForClientAge(CheckForClientAge {
-                        chain_id,
-                        ibc_spec_id,
-                        client_id,
-                        max_age,
-                    }),
-                )),
-            ]))
-        }
+        voyager_client
+            .decode_client_state::<IbcClassic, Value>(client_type, ibc_interface, client_state)
+            .await
+    } else {
+        Err(ErrorObject::owned(
+            FATAL_JSONRPC_ERROR_CODE,
+            format!("unable to query the client state of a client on IBC spec {ibc_spec_id}"),
+            None::<()>,
+        ))
     }
 }
 
@@ -197,21 +470,9 @@ impl PluginServer<ModuleCall, Never> for Module {
 
     async fn call(&self, e: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
         match msg {
-            ModuleCall::CheckForClientAge(CheckForClientAge {
-                chain_id,
-                ibc_spec_id,
-                client_id,
-                max_age,
-            }) => {
-                self.check_for_client_age(
-                    e.voyager_client()?,
-                    chain_id,
-                    ibc_spec_id,
-                    client_id,
-                    max_age,
-                )
+            ModuleCall::CheckForClientAge(check) => check_client_age(e.voyager_client()?, check)
                 .await
-            }
+                .map(|op| op.unwrap_or_else(noop)),
         }
     }
 
@@ -224,3 +485,134 @@ impl PluginServer<ModuleCall, Never> for Module {
         match cb {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use unionlabs::{ibc::core::client::height::Height, primitives::H256};
+
+    use super::*;
+
+    fn target(trusting_period_percent: Option<u8>) -> ClientUpdateTarget {
+        ClientUpdateTarget {
+            chain_id: ChainId::new("union-1"),
+            ibc_spec_id: IbcUnion::ID,
+            client_id: RawClientId::new(1),
+            max_age: 100,
+            trusting_period_percent,
+        }
+    }
+
+    #[test]
+    fn trusting_period_tendermint() {
+        let client_state = tendermint_light_client_types::ClientState {
+            chain_id: "union-1".to_owned(),
+            trust_level: tendermint_light_client_types::Fraction {
+                numerator: 1,
+                denominator: 3.try_into().unwrap(),
+            },
+            trusting_period: "1209600.5s".parse().unwrap(),
+            unbonding_period: "1814400s".parse().unwrap(),
+            max_clock_drift: "10s".parse().unwrap(),
+            frozen_height: None,
+            latest_height: Height::new(1),
+            proof_specs: vec![],
+            upgrade_path: vec![],
+            contract_address: H256::default(),
+        };
+
+        assert_eq!(
+            trusting_period(
+                &ClientType::new(ClientType::TENDERMINT),
+                into_value(client_state)
+            )
+            .unwrap(),
+            1_209_600
+        );
+    }
+
+    #[test]
+    fn trusting_period_cometbls() {
+        let client_state = cometbls_light_client_types::ClientState {
+            chain_id: cometbls_light_client_types::ChainId::from_string("union-1").unwrap(),
+            trusting_period: consensus_primitives::Duration::from_secs(1_209_600),
+            max_clock_drift: consensus_primitives::Duration::from_secs(10),
+            frozen_height: Height::new(0),
+            latest_height: Height::new(1),
+            contract_address: H256::default(),
+        };
+
+        assert_eq!(
+            trusting_period(
+                &ClientType::new(ClientType::COMETBLS),
+                into_value(client_state)
+            )
+            .unwrap(),
+            1_209_600
+        );
+    }
+
+    #[test]
+    fn trusting_period_unsupported() {
+        // the client state must match the client type
+        trusting_period(
+            &ClientType::new(ClientType::TENDERMINT),
+            serde_json::json!({ "trusting_period": "1209600s" }),
+        )
+        .unwrap_err();
+
+        // client types without a trusting period are not supported
+        trusting_period(
+            &ClientType::new(ClientType::ETHEREUM),
+            serde_json::json!({ "trusting_period": "1209600s" }),
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn trusting_period_threshold() {
+        assert!(!exceeds_trusting_period(0, 300, 50));
+        assert!(!exceeds_trusting_period(149, 300, 50));
+        assert!(exceeds_trusting_period(150, 300, 50));
+        assert!(exceeds_trusting_period(300, 300, 100));
+        assert!(!exceeds_trusting_period(299, 300, 100));
+        assert!(exceeds_trusting_period(u64::MAX, u64::MAX, 100));
+    }
+
+    #[test]
+    fn update_timeout() {
+        let timeout = Duration::from_secs(600);
+        let now = Instant::now();
+
+        assert!(!update_pending(None, now, timeout));
+        assert!(update_pending(Some(now), now, timeout));
+        assert!(update_pending(
+            Some(now),
+            now + Duration::from_secs(599),
+            timeout
+        ));
+        assert!(!update_pending(Some(now), now + timeout, timeout));
+    }
+
+    #[test]
+    fn target_validation() {
+        target(None).validate().unwrap();
+        target(Some(1)).validate().unwrap();
+        target(Some(100)).validate().unwrap();
+        target(Some(0)).validate().unwrap_err();
+        target(Some(101)).validate().unwrap_err();
+    }
+
+    #[test]
+    fn target_check() {
+        assert_eq!(
+            target(Some(50)).check(),
+            CheckForClientAge {
+                chain_id: ChainId::new("union-1"),
+                ibc_spec_id: IbcUnion::ID,
+                client_id: RawClientId::new(1),
+                max_age: 100,
+                trusting_period_percent: Some(50),
+            }
+        );
+    }
+}
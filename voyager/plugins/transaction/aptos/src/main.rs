@@ -1,6 +1,5 @@
-use std::{collections::VecDeque, panic::AssertUnwindSafe, sync::Arc};
+use std::{collections::VecDeque, panic::AssertUnwindSafe};
 
-use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
 use aptos_rest_client::aptos_api_types::Address;
 use aptos_types::{
     account_address::AccountAddress,
@@ -10,6 +9,7 @@ use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
 use ibc_union_spec::{datagram::Datagram, ChannelId, IbcUnion};
 use jsonrpsee::{
     core::{async_trait, RpcResult},
+    types::ErrorObject,
     Extensions,
 };
 use move_core_types::{
@@ -17,23 +17,23 @@ use move_core_types::{
     language_storage::{StructTag, TypeTag},
 };
 use serde::{Deserialize, Serialize};
-use sha3::Digest;
 use tracing::instrument;
-use unionlabs::{never::Never, primitives::H256};
+use unionlabs::{never::Never, ErrorReporter};
 use voyager_sdk::{
     anyhow,
     hook::SubmitTxHook,
     message::{data::Data, PluginMessage, VoyagerMessage},
     plugin::Plugin,
     primitives::ChainId,
-    rpc::{types::PluginInfo, PluginServer},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
     vm::{self, call, noop, pass::PassResult, Op, Visit},
     DefaultCmd,
 };
 
-use crate::call::ModuleCall;
+use crate::{call::ModuleCall, signer::AptosSigner};
 
 pub mod call;
+pub mod signer;
 
 #[tokio::main]
 async fn main() {
@@ -48,7 +48,7 @@ pub struct Module {
 
     pub aptos_client: aptos_rest_client::Client,
 
-    pub keyring: ConcurrentKeyring<AccountAddress, Arc<Ed25519PrivateKey>>,
+    pub keyring: ConcurrentKeyring<AccountAddress, AptosSigner>,
 }
 
 impl Plugin for Module {
@@ -63,31 +63,22 @@ impl Plugin for Module {
 
         let chain_id = aptos_client.get_index().await?.inner().chain_id;
 
+        let mut keyring_entries = vec![];
+
+        for entry in config.keyring.keys {
+            let signer = AptosSigner::new(entry.signer()?).await?;
+
+            keyring_entries.push(KeyringEntry {
+                address: signer.address(),
+                signer,
+            });
+        }
+
         Ok(Self {
             chain_id: ChainId::new(chain_id.to_string()),
             ibc_handler_address: config.ibc_handler_address,
             aptos_client,
-            keyring: ConcurrentKeyring::new(
-                config.keyring.name,
-                config.keyring.keys.into_iter().map(|config| {
-                    let pk = aptos_crypto::ed25519::Ed25519PrivateKey::try_from(&*config.value())
-                        .unwrap();
-
-                    let address = (*<H256>::from(
-                        sha3::Sha3_256::new()
-                            .chain_update(pk.public_key().to_bytes())
-                            .chain_update([0])
-                            .finalize(),
-                    )
-                    .get())
-                    .into();
-
-                    KeyringEntry {
-                        address,
-                        signer: Arc::new(pk),
-                    }
-                }),
-            ),
+            keyring: ConcurrentKeyring::new(config.keyring.name, keyring_entries.into_iter()),
         })
     }
 
@@ -191,17 +182,10 @@ impl PluginServer<ModuleCall, Never> for Module {
         match msg {
             ModuleCall::SubmitTransaction(msgs) => self
                 .keyring
-                .with(|pk| {
+                .with(|signer| {
                     let msgs = msgs.clone();
                     AssertUnwindSafe(async move {
-                        let sender = (*<H256>::from(
-                            sha3::Sha3_256::new()
-                                .chain_update(pk.public_key().to_bytes())
-                                .chain_update([0])
-                                .finalize(),
-                        )
-                        .get())
-                        .into();
+                        let sender = signer.address();
 
                         let account = self
                             .aptos_client
@@ -218,7 +202,7 @@ impl PluginServer<ModuleCall, Never> for Module {
                             msgs.clone(),
                             sender,
                         )
-                        .await;
+                        .await?;
 
                         let mut txs = vec![];
 
@@ -233,19 +217,21 @@ impl PluginServer<ModuleCall, Never> for Module {
                                 self.chain_id.as_str().parse().unwrap(),
                             );
 
-                            let signed_tx = raw.sign(pk, pk.public_key()).unwrap();
+                            let signed_tx = signer.sign(raw).await.map_err(|err| {
+                                ErrorObject::owned(
+                                    FATAL_JSONRPC_ERROR_CODE,
+                                    ErrorReporter(&*err).with_message("error signing transaction"),
+                                    None::<()>,
+                                )
+                            })?;
 
                             // TODO(aeryz): we normally should've send a batch transaction but
                             // movement don't allow it now.
                             dbg!(&signed_tx);
-                            let res = self
-                                .aptos_client
-                                .submit(&signed_tx.clone().into_inner())
-                                .await
-                                .unwrap();
+                            let res = self.aptos_client.submit(&signed_tx).await.unwrap();
 
                             dbg!(&res);
-                            txs.push(signed_tx.into_inner());
+                            txs.push(signed_tx);
                         }
 
                         // res.into_inner().transaction_failures
@@ -294,7 +280,7 @@ async fn process_msgs<
     client: &T,
     msgs: Vec<Datagram>,
     relayer: AccountAddress,
-) -> Vec<(Datagram, EntryFunction)> {
+) -> RpcResult<Vec<(Datagram, EntryFunction)>> {
     let mut data = vec![];
     for msg in msgs {
         let item = match msg.clone() {
@@ -316,6 +302,13 @@ async fn process_msgs<
                     (data.client_id.raw(), data.client_message.into_vec()),
                 ),
             ),
+            Datagram::SubmitMisbehaviour(data) => (
+                msg,
+                client.submit_misbehaviour(
+                    ibc_handler_address,
+                    (data.client_id.raw(), data.client_message.into_vec()),
+                ),
+            ),
             Datagram::ConnectionOpenInit(data) => (
                 msg,
                 client.connection_open_init(
@@ -529,10 +522,16 @@ async fn process_msgs<
                 )
             }
 
-            _ => todo!(),
+            _ => {
+                return Err(ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!("unsupported datagram {}", msg.name()),
+                    None::<()>,
+                ))
+            }
         };
         data.push(item);
     }
 
-    data
+    Ok(data)
 }
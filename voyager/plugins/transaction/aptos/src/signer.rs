@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    traits::{signing_message, Signature},
+    PrivateKey,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{RawTransaction, SignedTransaction},
+};
+use concurrent_keyring::{remote::RemoteSigner, KeyringSigner};
+use sha3::Digest;
+use unionlabs::primitives::H256;
+use voyager_sdk::anyhow::{self, anyhow};
+
+/// A signer for transactions submitted by this plugin, either holding the private key in memory or
+/// delegating to a [`RemoteSigner`]. Remote signers must hold an ed25519 key, and support signing
+/// the full transaction signing message.
+#[derive(Debug, Clone)]
+pub enum AptosSigner {
+    Local(Arc<Ed25519PrivateKey>),
+    Remote {
+        signer: RemoteSigner,
+        public_key: Ed25519PublicKey,
+    },
+}
+
+impl AptosSigner {
+    pub async fn new(signer: KeyringSigner) -> anyhow::Result<Self> {
+        match signer {
+            KeyringSigner::Local(key) => Ok(Self::Local(Arc::new(
+                Ed25519PrivateKey::try_from(key.as_ref())
+                    .map_err(|e| anyhow!("invalid private key: {e}"))?,
+            ))),
+            KeyringSigner::Remote(signer) => {
+                let public_key = Ed25519PublicKey::try_from(&*signer.public_key().await?)
+                    .map_err(|e| anyhow!("invalid remote signer public key: {e}"))?;
+
+                Ok(Self::Remote { signer, public_key })
+            }
+        }
+    }
+
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        match self {
+            AptosSigner::Local(pk) => pk.public_key(),
+            AptosSigner::Remote { public_key, .. } => public_key.clone(),
+        }
+    }
+
+    /// The address of the account controlled by this signer's (single ed25519) key.
+    pub fn address(&self) -> AccountAddress {
+        (*<H256>::from(
+            sha3::Sha3_256::new()
+                .chain_update(self.public_key().to_bytes())
+                .chain_update([0])
+                .finalize(),
+        )
+        .get())
+        .into()
+    }
+
+    pub async fn sign(&self, raw: RawTransaction) -> anyhow::Result<SignedTransaction> {
+        match self {
+            AptosSigner::Local(pk) => Ok(raw.sign(pk, pk.public_key())?.into_inner()),
+            AptosSigner::Remote { signer, public_key } => {
+                let signature = signer.sign_message(signing_message(&raw)?.into()).await?;
+
+                let signature = Ed25519Signature::try_from(&*signature)
+                    .map_err(|e| anyhow!("invalid remote signer signature: {e}"))?;
+
+                // ensure that the remote signer signed with the expected key, since a transaction
+                // with an invalid signature would otherwise only be rejected by the node
+                signature
+                    .verify(&raw, public_key)
+                    .map_err(|e| anyhow!("invalid remote signer signature: {e}"))?;
+
+                Ok(SignedTransaction::new(raw, public_key.clone(), signature))
+            }
+        }
+    }
+}
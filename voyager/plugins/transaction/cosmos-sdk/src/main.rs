@@ -10,11 +10,13 @@ use std::{
 };
 
 use cometbft_rpc::rpc_types::GrpcAbciQueryError;
-use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
+use concurrent_keyring::{
+    ChainKeyring, ConcurrentKeyring, KeyringConfig, KeyringConfigEntry, KeyringEntry, SignerBalance,
+};
 use cosmos_client::{
     gas::{any, feemarket, fixed, osmosis_eip1559_feemarket, GasFillerT},
     rpc::{Rpc, RpcT},
-    wallet::{LocalSigner, WalletT},
+    wallet::{AnyWallet, WalletT},
     BroadcastTxCommitError, TxClient,
 };
 use ibc_union::ContractErrorKind;
@@ -34,7 +36,7 @@ use unionlabs::{
     google::protobuf::any::mk_any,
     never::Never,
     option_unwrap,
-    primitives::{Bech32, Bytes, H160, H256},
+    primitives::{Bech32, Bytes, H160, H256, U256},
     ErrorReporter,
 };
 use voyager_sdk::{
@@ -65,7 +67,7 @@ pub struct Module(Arc<ModuleInner>);
 pub struct ModuleInner {
     pub chain_id: ChainId,
     pub ibc_host_contract_address: Bech32<H256>,
-    pub keyring: ConcurrentKeyring<Bech32<H160>, LocalSigner>,
+    pub keyring: ConcurrentKeyring<Bech32<H160>, AnyWallet>,
     pub rpc: Rpc,
     pub gas_config: any::GasFiller,
     pub bech32_prefix: String,
@@ -73,6 +75,8 @@ pub struct ModuleInner {
     pub gas_station_config: Vec<Coin>,
     pub fee_recipient: Option<Bech32<Bytes>>,
     pub max_tx_size: u32,
+    /// The wallet used to top up the keyring's signers. See [`TransactionPluginServer::top_up_signer`].
+    pub treasury: Option<AnyWallet>,
 }
 
 impl Deref for Module {
@@ -99,6 +103,9 @@ pub struct Config {
     #[serde(default)]
     pub fee_recipient: Option<Bech32<Bytes>>,
     pub max_tx_size: u32,
+    /// The key used to top up signers in the keyring when their balance runs low. If not set, signers cannot be topped up.
+    #[serde(default)]
+    pub treasury: Option<KeyringConfigEntry>,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -208,20 +215,31 @@ impl Plugin for Module {
             .unwrap()
             .bech32_prefix;
 
+        let mut keyring_entries = vec![];
+
+        for entry in config.keyring.keys {
+            let signer = AnyWallet::from_keyring_signer(entry.signer()?, bech32_prefix.clone())
+                .await
+                .map_err(|e| anyhow!(e))?;
+
+            keyring_entries.push(KeyringEntry {
+                address: signer.address(),
+                signer,
+            });
+        }
+
+        let treasury = match config.treasury {
+            Some(treasury) => Some(
+                AnyWallet::from_keyring_signer(treasury.signer()?, bech32_prefix.clone())
+                    .await
+                    .map_err(|e| anyhow!(e))?,
+            ),
+            None => None,
+        };
+
         Ok(Self(Arc::new(ModuleInner {
             ibc_host_contract_address: config.ibc_host_contract_address,
-            keyring: ConcurrentKeyring::new(
-                config.keyring.name,
-                config.keyring.keys.into_iter().map(|entry| {
-                    let signer =
-                        LocalSigner::new(entry.value().try_into().unwrap(), bech32_prefix.clone());
-
-                    KeyringEntry {
-                        address: signer.address(),
-                        signer,
-                    }
-                }),
-            ),
+            keyring: ConcurrentKeyring::new(config.keyring.name, keyring_entries.into_iter()),
             rpc,
             chain_id: ChainId::new(chain_id),
             gas_config: config
@@ -242,6 +260,7 @@ impl Plugin for Module {
             gas_station_config: config.gas_station_config,
             fee_recipient: config.fee_recipient,
             max_tx_size: config.max_tx_size,
+            treasury,
         })))
     }
 
@@ -265,6 +284,26 @@ trait TransactionPlugin {
 
     #[method(name = "signerBalances")]
     async fn signer_balances(&self) -> RpcResult<BTreeMap<Bech32<H160>, String>>;
+
+    /// The balance (in the gas denom) of each signer in the keyring, and whether it is part of the rotation.
+    #[method(name = "keyringBalances")]
+    async fn keyring_balances(&self) -> RpcResult<Vec<SignerBalance<Bech32<H160>>>>;
+
+    /// Remove a signer from the rotation, returning whether it was previously enabled.
+    #[method(name = "disableSigner")]
+    async fn disable_signer(&self, address: Bech32<H160>) -> RpcResult<bool>;
+
+    /// Add a signer back into the rotation, returning whether it was previously disabled.
+    #[method(name = "enableSigner")]
+    async fn enable_signer(&self, address: Bech32<H160>) -> RpcResult<bool>;
+
+    /// Transfer `amount` of the gas denom from the configured treasury to a signer in the keyring, returning the hash of the transfer.
+    #[method(name = "topUpSigner")]
+    async fn top_up_signer(&self, address: Bech32<H160>, amount: U256) -> RpcResult<H256>;
+
+    /// Estimate the fee (in the gas denom) of a transaction using `gas` gas, as calculated by the configured gas filler.
+    #[method(name = "estimateFee")]
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<String>;
 }
 
 #[async_trait]
@@ -274,51 +313,90 @@ impl TransactionPluginServer for Module {
     }
 
     async fn signer_balances(&self) -> RpcResult<BTreeMap<Bech32<H160>, String>> {
+        let denom = self.gas_denom().await;
+
         let mut out = BTreeMap::new();
 
         for address in self.keyring.keys() {
-            let balance = self
-                .rpc
-                .client()
-                .grpc_abci_query::<_, protos::cosmos::bank::v1beta1::QueryBalanceResponse>(
-                    "/cosmos.bank.v1beta1.Query/Balance",
-                    &protos::cosmos::bank::v1beta1::QueryBalanceRequest {
-                        address: address.to_string(),
-                        denom: self.gas_config.mk_fee(0).await.amount[0].denom.clone(),
-                    },
-                    None,
-                    false,
-                )
-                .await
-                .map_err(|e| {
-                    ErrorObject::owned(
-                        -1,
-                        ErrorReporter(e).with_message("error fetching balance"),
-                        None::<()>,
-                    )
-                })?
-                .into_result()
-                .map_err(|e| {
-                    ErrorObject::owned(
-                        -1,
-                        ErrorReporter(e).with_message("error fetching balance"),
-                        None::<()>,
-                    )
-                })?
-                .ok_or_else(|| {
-                    ErrorObject::owned(-1, "empty response when fetching balance", None::<()>)
-                })?
-                .balance
-                .ok_or_else(|| {
-                    ErrorObject::owned(-1, "empty balance when fetching balance", None::<()>)
-                })?
-                .amount;
-
-            out.insert(address.clone(), balance);
+            out.insert(address.clone(), self.query_balance(address, &denom).await?);
         }
 
         Ok(out)
     }
+
+    async fn keyring_balances(&self) -> RpcResult<Vec<SignerBalance<Bech32<H160>>>> {
+        Ok(self.balances().await)
+    }
+
+    async fn disable_signer(&self, address: Bech32<H160>) -> RpcResult<bool> {
+        Ok(self.keyring.disable(&address))
+    }
+
+    async fn enable_signer(&self, address: Bech32<H160>) -> RpcResult<bool> {
+        Ok(self.keyring.enable(&address))
+    }
+
+    async fn top_up_signer(&self, address: Bech32<H160>, amount: U256) -> RpcResult<H256> {
+        let Some(treasury) = &self.treasury else {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                "no treasury is configured",
+                None::<()>,
+            ));
+        };
+
+        if !self.keyring.keys().any(|signer| *signer == address) {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("{address} is not a signer in this keyring"),
+                None::<()>,
+            ));
+        }
+
+        let tx_response = TxClient::new(treasury, &self.rpc, &self.gas_config)
+            .broadcast_tx_commit(
+                [mk_any(&protos::cosmos::bank::v1beta1::MsgSend {
+                    from_address: treasury.address().to_string(),
+                    to_address: address.to_string(),
+                    amount: vec![protos::cosmos::base::v1beta1::Coin {
+                        denom: self.gas_denom().await,
+                        amount: amount.to_string(),
+                    }],
+                })],
+                format!("Voyager {} top up", env!("CARGO_PKG_VERSION")),
+                true,
+            )
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error sending top up"),
+                    None::<()>,
+                )
+            })?;
+
+        info!(
+            tx_hash = %tx_response.hash,
+            %address,
+            %amount,
+            treasury = %treasury.address(),
+            "topped up signer"
+        );
+
+        Ok(tx_response.hash.into_encoding())
+    }
+
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<String> {
+        Ok(self
+            .gas_config
+            .mk_fee(gas)
+            .await
+            .amount
+            .iter()
+            .map(|coin| coin.amount)
+            .sum::<u128>()
+            .to_string())
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -327,11 +405,95 @@ fn plugin_name(chain_id: &ChainId) -> String {
     format!("{PLUGIN_NAME}/{}", chain_id)
 }
 
+impl ChainKeyring for Module {
+    type Address = Bech32<H160>;
+    type Signer = AnyWallet;
+
+    fn keyring(&self) -> &ConcurrentKeyring<Self::Address, Self::Signer> {
+        &self.keyring
+    }
+
+    async fn balances(&self) -> Vec<SignerBalance<Self::Address>> {
+        let denom = self.gas_denom().await;
+
+        let mut out = vec![];
+
+        for address in self.keyring.keys() {
+            let balance = match self.query_balance(address, &denom).await {
+                Ok(balance) => balance,
+                Err(err) => {
+                    warn!(%address, error = %err.message(), "error fetching balance");
+                    continue;
+                }
+            };
+
+            match balance.parse() {
+                Ok(balance) => out.push(SignerBalance {
+                    key_name: self.keyring.name.to_string(),
+                    address: address.clone(),
+                    balance,
+                    denom: denom.clone(),
+                    enabled: self.keyring.is_enabled(address),
+                }),
+                Err(_) => {
+                    warn!(%address, %balance, "invalid balance");
+                }
+            }
+        }
+
+        out
+    }
+}
+
 impl Module {
     fn plugin_name(&self) -> String {
         plugin_name(&self.chain_id)
     }
 
+    /// The denom that fees are paid in, as configured by the gas filler.
+    async fn gas_denom(&self) -> String {
+        self.gas_config.mk_fee(0).await.amount[0].denom.clone()
+    }
+
+    async fn query_balance(&self, address: &Bech32<H160>, denom: &str) -> RpcResult<String> {
+        Ok(self
+            .rpc
+            .client()
+            .grpc_abci_query::<_, protos::cosmos::bank::v1beta1::QueryBalanceResponse>(
+                "/cosmos.bank.v1beta1.Query/Balance",
+                &protos::cosmos::bank::v1beta1::QueryBalanceRequest {
+                    address: address.to_string(),
+                    denom: denom.to_owned(),
+                },
+                None,
+                false,
+            )
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching balance"),
+                    None::<()>,
+                )
+            })?
+            .into_result()
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error fetching balance"),
+                    None::<()>,
+                )
+            })?
+            .ok_or_else(|| {
+                ErrorObject::owned(-1, "empty response when fetching balance", None::<()>)
+            })?
+            .balance
+            .ok_or_else(|| {
+                ErrorObject::owned(-1, "empty balance when fetching balance", None::<()>)
+            })?
+            .amount)
+    }
+
     pub async fn do_send_transaction(
         &self,
         msgs: Vec<IbcMessage>,
@@ -728,7 +890,7 @@ impl PluginServer<ModuleCall, Never> for Module {
 
 fn process_msgs(
     msgs: Vec<IbcMessage>,
-    signer: &LocalSigner,
+    signer: &AnyWallet,
     ibc_host_contract_address: Bech32<H256>,
     gas_station_config: Vec<Coin>,
     fee_recipient: Option<&Bech32<Bytes>>,
@@ -917,6 +1079,24 @@ fn process_msgs(
                             funds: vec![],
                         })
                     }
+                    ibc_union_spec::datagram::Datagram::SubmitMisbehaviour(
+                        msg_submit_misbehaviour,
+                    ) => mk_any(&protos::cosmwasm::wasm::v1::MsgExecuteContract {
+                        sender: signer.to_string(),
+                        contract: ibc_host_contract_address.to_string(),
+                        msg: serde_json::to_vec(
+                            &ibc_union_msg::msg::ExecuteMsg::SubmitMisbehaviour(
+                                ibc_union_msg::msg::MsgSubmitMisbehaviour {
+                                    client_id: msg_submit_misbehaviour.client_id,
+                                    client_message: msg_submit_misbehaviour.client_message,
+                                    relayer: fee_recipient
+                                        .map_or(signer.to_string(), |s| s.to_string()),
+                                },
+                            ),
+                        )
+                        .unwrap(),
+                        funds: vec![],
+                    }),
                     ibc_union_spec::datagram::Datagram::ConnectionOpenInit(
                         msg_connection_open_init,
                     ) => mk_any(&protos::cosmwasm::wasm::v1::MsgExecuteContract {
@@ -1194,8 +1374,6 @@ fn parse_msg_idx_from_log(log: &str) -> Option<(usize, &str)> {
 
 #[cfg(test)]
 mod tests {
-    use concurrent_keyring::KeyringConfigEntry;
-
     use super::*;
 
     #[test]
@@ -1257,7 +1435,8 @@ mod tests {
                 fatal_errors: HashMap::default(),
                 gas_station_config: vec![],
                 fee_recipient: None,
-                max_tx_size: 1000000
+                max_tx_size: 1000000,
+                treasury: None,
             }
         );
     }
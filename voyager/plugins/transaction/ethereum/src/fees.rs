@@ -0,0 +1,180 @@
+use alloy::network::{Network, TransactionBuilder};
+
+/// The minimum percentage that the fees of a transaction must be increased by for it to replace a
+/// pending transaction with the same nonce. This is the default price bump enforced by geth, and
+/// is also used by most other clients.
+pub const MIN_REPLACEMENT_BUMP_PERCENTAGE: u128 = 10;
+
+/// The fees paid by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fees {
+    Legacy {
+        gas_price: u128,
+    },
+    Eip1559 {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    },
+}
+
+impl Fees {
+    /// The maximum price that will be paid per unit of gas.
+    pub fn max_gas_price(&self) -> u128 {
+        match *self {
+            Fees::Legacy { gas_price } => gas_price,
+            Fees::Eip1559 {
+                max_fee_per_gas, ..
+            } => max_fee_per_gas,
+        }
+    }
+
+    /// `(max_fee_per_gas, max_priority_fee_per_gas)`. For legacy transactions, the gas price is
+    /// used for both.
+    fn components(&self) -> (u128, u128) {
+        match *self {
+            Fees::Legacy { gas_price } => (gas_price, gas_price),
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => (max_fee_per_gas, max_priority_fee_per_gas),
+        }
+    }
+
+    fn with_components(&self, max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> Self {
+        match self {
+            Fees::Legacy { .. } => Fees::Legacy {
+                gas_price: max_fee_per_gas,
+            },
+            Fees::Eip1559 { .. } => Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas: max_priority_fee_per_gas.min(max_fee_per_gas),
+            },
+        }
+    }
+
+    /// Increase all fees by `percentage`, rounding up.
+    pub fn bump(&self, percentage: u128) -> Self {
+        let bump = |fee: u128| fee.saturating_mul(100 + percentage).div_ceil(100);
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) = self.components();
+
+        self.with_components(bump(max_fee_per_gas), bump(max_priority_fee_per_gas))
+    }
+
+    /// The highest of each fee in `self` and `other`, keeping the transaction type of `self`.
+    pub fn max(&self, other: &Self) -> Self {
+        let (max_fee_per_gas, max_priority_fee_per_gas) = self.components();
+        let (other_max_fee_per_gas, other_max_priority_fee_per_gas) = other.components();
+
+        self.with_components(
+            max_fee_per_gas.max(other_max_fee_per_gas),
+            max_priority_fee_per_gas.max(other_max_priority_fee_per_gas),
+        )
+    }
+
+    /// Limit the fees such that no more than `max_gas_price` will be paid per unit of gas.
+    pub fn cap(&self, max_gas_price: u128) -> Self {
+        let (max_fee_per_gas, max_priority_fee_per_gas) = self.components();
+
+        self.with_components(
+            max_fee_per_gas.min(max_gas_price),
+            max_priority_fee_per_gas.min(max_gas_price),
+        )
+    }
+
+    /// Whether a transaction paying these fees is able to replace a pending transaction paying
+    /// `previous`.
+    pub fn replaces(&self, previous: &Self) -> bool {
+        let (max_fee_per_gas, max_priority_fee_per_gas) = self.components();
+        let (min_max_fee_per_gas, min_max_priority_fee_per_gas) =
+            previous.bump(MIN_REPLACEMENT_BUMP_PERCENTAGE).components();
+
+        max_fee_per_gas >= min_max_fee_per_gas
+            && max_priority_fee_per_gas >= min_max_priority_fee_per_gas
+    }
+
+    pub fn apply<N: Network>(&self, tx: N::TransactionRequest) -> N::TransactionRequest {
+        match *self {
+            Fees::Legacy { gas_price } => tx.with_gas_price(gas_price),
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => tx
+                .with_max_fee_per_gas(max_fee_per_gas)
+                .with_max_priority_fee_per_gas(max_priority_fee_per_gas),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EIP1559: Fees = Fees::Eip1559 {
+        max_fee_per_gas: 100,
+        max_priority_fee_per_gas: 10,
+    };
+
+    #[test]
+    fn bump_rounds_up() {
+        assert_eq!(
+            EIP1559.bump(15),
+            Fees::Eip1559 {
+                max_fee_per_gas: 115,
+                max_priority_fee_per_gas: 12,
+            }
+        );
+
+        assert_eq!(
+            Fees::Legacy { gas_price: 1 }.bump(10),
+            Fees::Legacy { gas_price: 2 }
+        );
+    }
+
+    #[test]
+    fn replaces() {
+        assert!(EIP1559
+            .bump(MIN_REPLACEMENT_BUMP_PERCENTAGE)
+            .replaces(&EIP1559));
+        assert!(EIP1559.bump(25).replaces(&EIP1559));
+        assert!(!EIP1559.bump(5).replaces(&EIP1559));
+        assert!(!EIP1559.replaces(&EIP1559));
+
+        // only the max fee was bumped
+        assert!(!Fees::Eip1559 {
+            max_fee_per_gas: 200,
+            max_priority_fee_per_gas: 10,
+        }
+        .replaces(&EIP1559));
+    }
+
+    #[test]
+    fn capped_bump_does_not_replace() {
+        let bumped = EIP1559.bump(20).cap(105);
+
+        assert_eq!(
+            bumped,
+            Fees::Eip1559 {
+                max_fee_per_gas: 105,
+                max_priority_fee_per_gas: 12,
+            }
+        );
+        assert!(!bumped.replaces(&EIP1559));
+    }
+
+    #[test]
+    fn max_keeps_transaction_type() {
+        assert_eq!(
+            EIP1559.max(&Fees::Legacy { gas_price: 50 }),
+            Fees::Eip1559 {
+                max_fee_per_gas: 100,
+                max_priority_fee_per_gas: 50,
+            }
+        );
+
+        assert_eq!(
+            Fees::Legacy { gas_price: 50 }.max(&EIP1559),
+            Fees::Legacy { gas_price: 100 }
+        );
+    }
+}
@@ -1,25 +1,27 @@
 use std::{
     collections::{BTreeMap, VecDeque},
+    future::Future,
     ops::Deref,
     panic::AssertUnwindSafe,
     sync::Arc,
+    time::Duration,
 };
 
 use alloy::{
     contract::{Error, RawCallBuilder},
-    network::{AnyNetwork, EthereumWallet},
-    primitives::Address,
+    network::{AnyNetwork, EthereumWallet, Network, TransactionBuilder},
+    primitives::{Address, TxHash},
     providers::{
-        fillers::RecommendedFillers, layers::CacheLayer, DynProvider, PendingTransactionError,
-        Provider, ProviderBuilder,
+        fillers::RecommendedFillers, layers::CacheLayer, DynProvider, PendingTransactionBuilder,
+        PendingTransactionError, Provider, ProviderBuilder, WatchTxError,
     },
-    signers::local::LocalSigner,
     sol_types::{SolEvent, SolInterface},
     transports::TransportError,
 };
-use bip32::secp256k1::ecdsa::{self, SigningKey};
 use clap::Subcommand;
-use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
+use concurrent_keyring::{
+    ChainKeyring, ConcurrentKeyring, KeyringConfig, KeyringConfigEntry, KeyringEntry, SignerBalance,
+};
 use ibc_solidity::Ibc::{self, IbcErrors};
 use ibc_union_spec::{datagram::Datagram, IbcUnion};
 use jsonrpsee::{
@@ -49,10 +51,18 @@ use voyager_sdk::{
 
 use crate::{
     call::ModuleCall,
+    fees::{Fees, MIN_REPLACEMENT_BUMP_PERCENTAGE},
+    metrics::Metrics,
     multicall::{Call3, Multicall, MulticallResult},
+    nonce::NonceManager,
+    signer::EthSigner,
 };
 
 pub mod call;
+pub mod fees;
+pub mod metrics;
+pub mod nonce;
+pub mod signer;
 
 #[tokio::main]
 async fn main() {
@@ -81,10 +91,12 @@ pub struct ModuleInner {
 
     pub provider: DynProvider<AnyNetwork>,
 
-    pub keyring: ConcurrentKeyring<alloy::primitives::Address, LocalSigner<SigningKey>>,
+    pub keyring: ConcurrentKeyring<alloy::primitives::Address, EthSigner>,
 
     pub max_gas_price: Option<u128>,
 
+    pub max_cancellation_gas_price: Option<u128>,
+
     pub fixed_gas_price: Option<u128>,
 
     pub gas_multiplier: f64,
@@ -92,6 +104,19 @@ pub struct ModuleInner {
     pub legacy: bool,
 
     pub fee_recipient: Option<alloy::primitives::Address>,
+
+    pub receipt_timeout: Duration,
+
+    pub fee_bump_percentage: u128,
+
+    pub max_replacements: u32,
+
+    pub nonces: NonceManager,
+
+    pub metrics: Metrics,
+
+    /// The signer used to top up the keyring's signers. See [`TransactionPluginServer::top_up_signer`].
+    pub treasury: Option<EthSigner>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,9 +135,22 @@ pub struct Config {
 
     pub keyring: KeyringConfig,
 
+    /// The maximum gas price to submit transactions with. Transactions are not submitted while the
+    /// gas price is above this, and stuck transactions are not replaced with fees higher than this.
+    ///
+    /// Transactions cancelling a stuck nonce are limited by `max_cancellation_gas_price` instead,
+    /// since they must outbid the stuck transaction to replace it.
     #[serde(default)]
     pub max_gas_price: Option<u128>,
 
+    /// The maximum gas price to cancel a stuck nonce with. Must be at least `max_gas_price`.
+    ///
+    /// Defaults to [`DEFAULT_CANCELLATION_GAS_PRICE_MULTIPLIER`] times `max_gas_price`, which leaves
+    /// enough room to outbid a transaction that got stuck at `max_gas_price`. If neither this nor
+    /// `max_gas_price` is set, cancellations are not limited.
+    #[serde(default)]
+    pub max_cancellation_gas_price: Option<u128>,
+
     /// Temporary fix for 0g until they fix their eth_feeHistory endpoint
     #[serde(default)]
     pub fixed_gas_price: Option<u128>,
@@ -128,8 +166,42 @@ pub struct Config {
 
     #[serde(default)]
     pub fee_recipient: Option<alloy::primitives::Address>,
+
+    /// How long to wait for a transaction to be included before replacing it with higher fees, in
+    /// seconds.
+    #[serde(default = "default_receipt_timeout")]
+    pub receipt_timeout: u64,
+
+    /// The percentage that fees are increased by when replacing a transaction. Must be at least
+    /// 10, the minimum accepted by most clients.
+    #[serde(default = "default_fee_bump_percentage")]
+    pub fee_bump_percentage: u128,
+
+    /// The maximum number of times a transaction is replaced before its nonce is cancelled.
+    #[serde(default = "default_max_replacements")]
+    pub max_replacements: u32,
+
+    /// The key used to top up signers in the keyring when their balance runs low. If not set, signers cannot be topped up.
+    #[serde(default)]
+    pub treasury: Option<KeyringConfigEntry>,
+}
+
+fn default_receipt_timeout() -> u64 {
+    60
+}
+
+fn default_fee_bump_percentage() -> u128 {
+    15
 }
 
+fn default_max_replacements() -> u32 {
+    5
+}
+
+/// The multiple of the max gas price that cancellations are limited to by default. See
+/// [`Config::max_cancellation_gas_price`].
+pub const DEFAULT_CANCELLATION_GAS_PRICE_MULTIPLIER: u128 = 2;
+
 #[derive(Subcommand)]
 pub enum Cmd {
     SignerAddresses,
@@ -163,33 +235,64 @@ impl Plugin for Module {
             );
         }
 
+        if config.fee_bump_percentage < MIN_REPLACEMENT_BUMP_PERCENTAGE {
+            bail!(
+                "fee_bump_percentage must be at least {MIN_REPLACEMENT_BUMP_PERCENTAGE}, found {}",
+                config.fee_bump_percentage
+            );
+        }
+
+        let max_cancellation_gas_price = match config.max_cancellation_gas_price {
+            Some(max_cancellation_gas_price) => Some(max_cancellation_gas_price),
+            None => config.max_gas_price.map(|max_gas_price| {
+                max_gas_price.saturating_mul(DEFAULT_CANCELLATION_GAS_PRICE_MULTIPLIER)
+            }),
+        };
+
+        if let (Some(max_cancellation_gas_price), Some(max_gas_price)) =
+            (max_cancellation_gas_price, config.max_gas_price)
+        {
+            if max_cancellation_gas_price < max_gas_price {
+                bail!(
+                    "max_cancellation_gas_price must be at least max_gas_price ({max_gas_price}), \
+                    found {max_cancellation_gas_price}"
+                );
+            }
+        }
+
+        let mut keyring_entries = vec![];
+
+        for entry in config.keyring.keys {
+            let signer = EthSigner::new(entry.signer()?).await?;
+
+            keyring_entries.push(KeyringEntry {
+                address: signer.address(),
+                signer,
+            });
+        }
+
         Ok(Self(Arc::new(ModuleInner {
             chain_id,
             additional_chain_ids: config.additional_chain_ids,
             ibc_handler_address: config.ibc_handler_address,
             multicall_address: config.multicall_address,
             provider,
-            keyring: ConcurrentKeyring::new(
-                config.keyring.name,
-                config.keyring.keys.into_iter().map(|config| {
-                    let signing_key = <ecdsa::SigningKey as bip32::PrivateKey>::from_bytes(
-                        &config.value().as_slice().try_into().unwrap(),
-                    )
-                    .unwrap();
-
-                    let signer = LocalSigner::from_signing_key(signing_key);
-
-                    KeyringEntry {
-                        address: signer.address(),
-                        signer,
-                    }
-                }),
-            ),
+            keyring: ConcurrentKeyring::new(config.keyring.name, keyring_entries.into_iter()),
             max_gas_price: config.max_gas_price,
+            max_cancellation_gas_price,
             fixed_gas_price: config.fixed_gas_price,
             legacy: config.legacy,
             gas_multiplier: config.gas_multiplier,
             fee_recipient: config.fee_recipient,
+            receipt_timeout: Duration::from_secs(config.receipt_timeout),
+            fee_bump_percentage: config.fee_bump_percentage,
+            max_replacements: config.max_replacements,
+            nonces: NonceManager::default(),
+            metrics: Metrics::new(),
+            treasury: match config.treasury {
+                Some(treasury) => Some(EthSigner::new(treasury.signer()?).await?),
+                None => None,
+            },
         })))
     }
 
@@ -233,6 +336,26 @@ trait TransactionPlugin {
 
     #[method(name = "signerBalances")]
     async fn signer_balances(&self) -> RpcResult<BTreeMap<Address, U256>>;
+
+    /// The balance (in wei) of each signer in the keyring, and whether it is part of the rotation.
+    #[method(name = "keyringBalances")]
+    async fn keyring_balances(&self) -> RpcResult<Vec<SignerBalance<Address>>>;
+
+    /// Remove a signer from the rotation, returning whether it was previously enabled.
+    #[method(name = "disableSigner")]
+    async fn disable_signer(&self, address: Address) -> RpcResult<bool>;
+
+    /// Add a signer back into the rotation, returning whether it was previously disabled.
+    #[method(name = "enableSigner")]
+    async fn enable_signer(&self, address: Address) -> RpcResult<bool>;
+
+    /// Transfer `amount` wei from the configured treasury to a signer in the keyring, returning the hash of the transfer.
+    #[method(name = "topUpSigner")]
+    async fn top_up_signer(&self, address: Address, amount: U256) -> RpcResult<H256>;
+
+    /// Estimate the fee (in wei) of a transaction using `gas` gas, applying the gas multiplier and the maximum fee per gas used when submitting transactions.
+    #[method(name = "estimateFee")]
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<U256>;
 }
 
 #[async_trait]
@@ -258,6 +381,99 @@ impl TransactionPluginServer for Module {
 
         Ok(out)
     }
+
+    async fn keyring_balances(&self) -> RpcResult<Vec<SignerBalance<Address>>> {
+        Ok(self.balances().await)
+    }
+
+    async fn disable_signer(&self, address: Address) -> RpcResult<bool> {
+        Ok(self.keyring.disable(&address))
+    }
+
+    async fn enable_signer(&self, address: Address) -> RpcResult<bool> {
+        Ok(self.keyring.enable(&address))
+    }
+
+    async fn top_up_signer(&self, address: Address, amount: U256) -> RpcResult<H256> {
+        let Some(treasury) = &self.treasury else {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                "no treasury is configured",
+                None::<()>,
+            ));
+        };
+
+        if !self.keyring.keys().any(|signer| *signer == address) {
+            return Err(ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                format!("{address} is not a signer in this keyring"),
+                None::<()>,
+            ));
+        }
+
+        let provider = DynProvider::new(
+            ProviderBuilder::new()
+                .network::<AnyNetwork>()
+                .filler(AnyNetwork::recommended_fillers())
+                .wallet(EthereumWallet::new(treasury.clone()))
+                .connect_provider(self.provider.clone()),
+        );
+
+        let receipt = provider
+            .send_transaction(
+                <AnyNetwork as Network>::TransactionRequest::default()
+                    .with_from(treasury.address())
+                    .with_to(address)
+                    .with_value(amount.into()),
+            )
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error sending top up"),
+                    None::<()>,
+                )
+            })?
+            .with_timeout(Some(self.receipt_timeout))
+            .get_receipt()
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error waiting for top up"),
+                    None::<()>,
+                )
+            })?;
+
+        let tx_hash = <H256>::from(receipt.transaction_hash);
+
+        info!(%tx_hash, %address, %amount, treasury = %treasury.address(), "topped up signer");
+
+        Ok(tx_hash)
+    }
+
+    async fn estimate_fee(&self, gas: u64) -> RpcResult<U256> {
+        // the same fees as used for a new transaction, such that the estimate is the most that a
+        // transaction using `gas` gas can cost
+        let gas_price = self
+            .estimate_fees()
+            .await
+            .map_err(|e| {
+                ErrorObject::owned(
+                    -1,
+                    ErrorReporter(e).with_message("error estimating fees"),
+                    None::<()>,
+                )
+            })?
+            .max_gas_price();
+
+        let gas_to_use = ((gas as f64) * self.gas_multiplier) as u64;
+
+        Ok(
+            (alloy::primitives::U256::from(gas_price) * alloy::primitives::U256::from(gas_to_use))
+                .into(),
+        )
+    }
 }
 
 fn plugin_name(chain_id: &ChainId) -> String {
@@ -272,6 +488,36 @@ impl Module {
     }
 }
 
+impl ChainKeyring for Module {
+    type Address = Address;
+    type Signer = EthSigner;
+
+    fn keyring(&self) -> &ConcurrentKeyring<Self::Address, Self::Signer> {
+        &self.keyring
+    }
+
+    async fn balances(&self) -> Vec<SignerBalance<Self::Address>> {
+        let mut out = vec![];
+
+        for address in self.keyring.keys() {
+            match self.provider.get_balance(*address).await {
+                Ok(balance) => out.push(SignerBalance {
+                    key_name: self.keyring.name.to_string(),
+                    address: *address,
+                    balance: balance.try_into().unwrap_or(u128::MAX),
+                    denom: "wei".to_owned(),
+                    enabled: self.keyring.is_enabled(address),
+                }),
+                Err(err) => {
+                    warn!(%address, error = %ErrorReporter(err), "error fetching balance");
+                }
+            }
+        }
+
+        out
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum TxSubmitError {
     #[error(transparent)]
@@ -290,6 +536,16 @@ pub enum TxSubmitError {
     RpcError(#[from] ErrorObjectOwned),
     #[error("batch too large")]
     BatchTooLarge,
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+    #[error("transaction with nonce {nonce} could not be included")]
+    Stuck {
+        nonce: u64,
+        fees: Fees,
+        tx_hashes: Vec<TxHash>,
+    },
+    #[error("transaction with nonce {nonce} was cancelled")]
+    Cancelled { nonce: u64 },
 }
 
 #[async_trait]
@@ -429,7 +685,7 @@ impl PluginServer<ModuleCall, Never> for Module {
 impl Module {
     async fn submit_transaction(
         &self,
-        wallet: &LocalSigner<SigningKey>,
+        wallet: &EthSigner,
         ibc_messages: Vec<Datagram>,
     ) -> Result<(), TxSubmitError> {
         let signer = DynProvider::new(
@@ -479,7 +735,7 @@ impl Module {
             .map(|x| (x.0.clone(), x.0.name()))
             .collect::<Vec<_>>();
 
-        let mut call = multicall.multicall(
+        let call = multicall.multicall(
             msgs.clone()
                 .into_iter()
                 .map(|(_, call)| Call3 {
@@ -512,16 +768,56 @@ impl Module {
             "gas estimatation successful"
         );
 
-        if let Some(fixed_gas_price) = self.fixed_gas_price {
-            call = call.gas_price(fixed_gas_price);
+        let address = wallet.address();
+
+        let (nonce, in_flight) = self.nonces.next(&self.provider, address).await?;
+
+        let mut fees = self.estimate_fees().await?;
+
+        if let Some(in_flight) = in_flight {
+            fees = fees.max(&in_flight.fees.bump(self.fee_bump_percentage));
+
+            if let Some(max_gas_price) = self.max_gas_price {
+                fees = fees.cap(max_gas_price);
+            }
+
+            if !fees.replaces(&in_flight.fees) {
+                warn!(
+                    %nonce,
+                    "a transaction is stuck at this nonce and cannot be replaced \
+                    without exceeding the max gas price, cancelling it"
+                );
+
+                return self
+                    .cancel_nonce(&signer, address, nonce, in_flight.fees)
+                    .await;
+            }
+
+            info!(%nonce, "replacing transaction stuck at this nonce");
         }
 
-        match call.gas(gas_to_use).send().await {
-            Ok(ok) => {
-                let tx_hash = <H256>::from(*ok.tx_hash());
-                async move {
-                    let receipt = ok.get_receipt().await?;
+        let res = self
+            .send_with_replacement(address, nonce, fees, self.max_gas_price, |fees| {
+                let call = call.clone().nonce(nonce).gas(gas_to_use);
+
+                let call = match fees {
+                    Fees::Legacy { gas_price } => call.gas_price(gas_price),
+                    Fees::Eip1559 {
+                        max_fee_per_gas,
+                        max_priority_fee_per_gas,
+                    } => call
+                        .max_fee_per_gas(max_fee_per_gas)
+                        .max_priority_fee_per_gas(max_priority_fee_per_gas),
+                };
+
+                async move { call.send().await }
+            })
+            .await;
 
+        match res {
+            Ok(receipt) => {
+                let tx_hash = <H256>::from(receipt.transaction_hash);
+                async move {
                     info!(%tx_hash, "tx included");
 
                     let result = MulticallResult::decode_log_data(
@@ -591,38 +887,295 @@ impl Module {
                 ))
                 .await
             }
-            Err(
-                Error::PendingTransactionError(PendingTransactionError::TransportError(
-                    TransportError::ErrorResp(e),
-                ))
-                | Error::TransportError(TransportError::ErrorResp(e)),
-            ) if e
-                .message
-                .contains("insufficient funds for gas * price + value") =>
+            Err(TxSubmitError::Error(err))
+                if error_message(&err)
+                    .is_some_and(|e| e.contains("insufficient funds for gas * price + value")) =>
             {
                 error!("out of gas");
                 Err(TxSubmitError::OutOfGas)
             }
-            Err(
-                Error::PendingTransactionError(PendingTransactionError::TransportError(
-                    TransportError::ErrorResp(e),
-                ))
-                | Error::TransportError(TransportError::ErrorResp(e)),
-            ) if e.message.contains("oversized data")
-                || e.message.contains("exceeds block gas limit")
-                || e.message.contains("gas required exceeds") =>
+            Err(TxSubmitError::Error(err))
+                if error_message(&err).is_some_and(|e| {
+                    e.contains("oversized data")
+                        || e.contains("exceeds block gas limit")
+                        || e.contains("gas required exceeds")
+                }) =>
             {
+                let error = error_message(&err).unwrap_or_default();
+
                 if msgs.len() == 1 {
-                    error!(error = %e.message, msg = ?msgs[0], "message is too large");
+                    error!(%error, msg = ?msgs[0], "message is too large");
                     Ok(()) // drop the message
                 } else {
-                    warn!(error = %e.message, "batch is too large");
+                    warn!(%error, "batch is too large");
                     Err(TxSubmitError::BatchTooLarge)
                 }
             }
-            Err(err) => Err(TxSubmitError::Error(err)),
+            Err(TxSubmitError::Stuck {
+                nonce,
+                fees,
+                tx_hashes,
+            }) => {
+                warn!(
+                    %nonce,
+                    ?tx_hashes,
+                    "transaction could not be included, cancelling it"
+                );
+
+                self.cancel_nonce(&signer, address, nonce, fees).await
+            }
+            Err(err) => Err(err),
         }
     }
+
+    /// Estimate the fees for a new transaction, limited to the max gas price.
+    async fn estimate_fees(&self) -> Result<Fees, TxSubmitError> {
+        let fees = match self.fixed_gas_price {
+            Some(fixed_gas_price) => Fees::Legacy {
+                gas_price: fixed_gas_price,
+            },
+            None if self.legacy => Fees::Legacy {
+                gas_price: self.provider.get_gas_price().await?,
+            },
+            None => {
+                let estimate = self.provider.estimate_eip1559_fees().await?;
+
+                Fees::Eip1559 {
+                    max_fee_per_gas: estimate.max_fee_per_gas,
+                    max_priority_fee_per_gas: estimate.max_priority_fee_per_gas,
+                }
+            }
+        };
+
+        Ok(match self.max_gas_price {
+            Some(max_gas_price) => fees.cap(max_gas_price),
+            None => fees,
+        })
+    }
+
+    /// Send a transaction from `address` with the provided nonce, replacing it with higher fees
+    /// every [`ModuleInner::receipt_timeout`] until it (or one of its replacements) is included.
+    ///
+    /// If the transaction is not included after [`ModuleInner::max_replacements`] replacements,
+    /// or it cannot be replaced without exceeding `max_gas_price`, [`TxSubmitError::Stuck`] is
+    /// returned.
+    async fn send_with_replacement<Fut>(
+        &self,
+        address: Address,
+        nonce: u64,
+        mut fees: Fees,
+        max_gas_price: Option<u128>,
+        send: impl Fn(Fees) -> Fut,
+    ) -> Result<<AnyNetwork as Network>::ReceiptResponse, TxSubmitError>
+    where
+        Fut: Future<Output = Result<PendingTransactionBuilder<AnyNetwork>, Error>>,
+    {
+        let mut tx_hashes = vec![];
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+
+            match send(fees).await {
+                Ok(pending) => {
+                    let tx_hash = *pending.tx_hash();
+
+                    info!(%tx_hash, %nonce, ?fees, "sent transaction");
+
+                    tx_hashes.push(tx_hash);
+
+                    self.nonces.sent(address, nonce, fees);
+                    self.metrics
+                        .record_pending(&self.chain_id, self.nonces.in_flight_count());
+
+                    match pending
+                        .with_timeout(Some(self.receipt_timeout))
+                        .get_receipt()
+                        .await
+                    {
+                        Ok(receipt) => {
+                            self.included(address, nonce);
+
+                            return Ok(receipt);
+                        }
+                        Err(PendingTransactionError::TxWatcher(WatchTxError::Timeout)) => {
+                            warn!(
+                                %tx_hash,
+                                %nonce,
+                                "transaction was not included within {:?}",
+                                self.receipt_timeout
+                            );
+                        }
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                Err(err) if error_message(&err).is_some_and(|e| e.contains("nonce too low")) => {
+                    // one of the previous attempts has been included since it was last checked
+                    return match self.find_receipt(&tx_hashes).await? {
+                        Some(receipt) => {
+                            self.included(address, nonce);
+
+                            Ok(receipt)
+                        }
+                        None => Err(err.into()),
+                    };
+                }
+                Err(err)
+                    if error_message(&err).is_some_and(|e| {
+                        e.contains("replacement transaction underpriced")
+                            || e.contains("transaction underpriced")
+                    }) =>
+                {
+                    warn!(
+                        %nonce,
+                        ?fees,
+                        "fees are too low to replace the transaction pending with this nonce"
+                    );
+                }
+                Err(err) => return Err(err.into()),
+            }
+
+            // a previous attempt may have been included while waiting for the latest one
+            if let Some(receipt) = self.find_receipt(&tx_hashes).await? {
+                self.included(address, nonce);
+
+                return Ok(receipt);
+            }
+
+            let mut bumped = fees
+                .bump(self.fee_bump_percentage)
+                .max(&self.estimate_fees().await?);
+
+            if let Some(max_gas_price) = max_gas_price {
+                bumped = bumped.cap(max_gas_price);
+            }
+
+            if attempts > self.max_replacements || !bumped.replaces(&fees) {
+                return Err(TxSubmitError::Stuck {
+                    nonce,
+                    fees,
+                    tx_hashes,
+                });
+            }
+
+            info!(%nonce, ?fees, ?bumped, "replacing transaction");
+
+            self.metrics.record_replaced(&self.chain_id);
+
+            fees = bumped;
+        }
+    }
+
+    /// Cancel the transaction stuck at `nonce` by replacing it with an empty transfer to
+    /// `address`.
+    ///
+    /// The cancellation is limited by [`ModuleInner::max_cancellation_gas_price`] rather than the
+    /// max gas price, since it must outbid the stuck transaction. If it cannot outbid the stuck
+    /// transaction within that limit, [`TxSubmitError::Stuck`] is returned. Even if the
+    /// cancellation succeeds, an error is returned so that the messages of the cancelled
+    /// transaction are retried.
+    async fn cancel_nonce(
+        &self,
+        signer: &DynProvider<AnyNetwork>,
+        address: Address,
+        nonce: u64,
+        stuck_fees: Fees,
+    ) -> Result<(), TxSubmitError> {
+        let mut fees = stuck_fees
+            .bump(self.fee_bump_percentage)
+            .max(&self.estimate_fees().await?);
+
+        if let Some(max_cancellation_gas_price) = self.max_cancellation_gas_price {
+            fees = fees.cap(max_cancellation_gas_price);
+        }
+
+        if !fees.replaces(&stuck_fees) {
+            warn!(
+                %nonce,
+                ?stuck_fees,
+                "the transaction stuck at this nonce cannot be cancelled without exceeding \
+                the max cancellation gas price"
+            );
+
+            return Err(TxSubmitError::Stuck {
+                nonce,
+                fees: stuck_fees,
+                tx_hashes: vec![],
+            });
+        }
+
+        let receipt = self
+            .send_with_replacement(
+                address,
+                nonce,
+                fees,
+                self.max_cancellation_gas_price,
+                |fees| {
+                    let tx = fees.apply::<AnyNetwork>(
+                        <AnyNetwork as Network>::TransactionRequest::default()
+                            .with_from(address)
+                            .with_to(address)
+                            .with_value(alloy::primitives::U256::ZERO)
+                            .with_nonce(nonce)
+                            .with_gas_limit(CANCELLATION_GAS),
+                    );
+
+                    let signer = signer.clone();
+
+                    async move {
+                        signer
+                            .send_transaction(tx)
+                            .await
+                            .map_err(Error::TransportError)
+                    }
+                },
+            )
+            .await?;
+
+        info!(
+            tx_hash = %<H256>::from(receipt.transaction_hash),
+            %nonce,
+            "cancelled stuck nonce"
+        );
+
+        self.metrics.record_cancelled(&self.chain_id);
+
+        Err(TxSubmitError::Cancelled { nonce })
+    }
+
+    /// Find the receipt of the first of `tx_hashes` that has been included, if any.
+    async fn find_receipt(
+        &self,
+        tx_hashes: &[TxHash],
+    ) -> Result<Option<<AnyNetwork as Network>::ReceiptResponse>, TxSubmitError> {
+        for tx_hash in tx_hashes {
+            if let Some(receipt) = self.provider.get_transaction_receipt(*tx_hash).await? {
+                return Ok(Some(receipt));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn included(&self, address: Address, nonce: u64) {
+        self.nonces.included(address, nonce);
+        self.metrics
+            .record_pending(&self.chain_id, self.nonces.in_flight_count());
+    }
+}
+
+/// The gas used by a transaction cancelling a stuck nonce (a plain transfer).
+const CANCELLATION_GAS: u64 = 21_000;
+
+/// The message of the JSON-RPC error response contained in `err`, if any.
+fn error_message(err: &Error) -> Option<&str> {
+    match err {
+        Error::PendingTransactionError(PendingTransactionError::TransportError(
+            TransportError::ErrorResp(e),
+        ))
+        | Error::TransportError(TransportError::ErrorResp(e)) => Some(&e.message),
+        _ => None,
+    }
 }
 
 #[allow(clippy::type_complexity)]
@@ -663,6 +1216,16 @@ fn process_msgs<'a>(
                         })
                         .clear_decoder(),
                 ),
+                Datagram::SubmitMisbehaviour(data) => (
+                    msg,
+                    ibc_handler
+                        .misbehaviour(ibc_solidity::MsgMisbehaviour {
+                            client_id: data.client_id.raw(),
+                            client_message: data.client_message.into(),
+                            relayer: relayer.into(),
+                        })
+                        .clear_decoder(),
+                ),
                 Datagram::ConnectionOpenInit(data) => (
                     msg,
                     ibc_handler
@@ -801,7 +1364,16 @@ fn process_msgs<'a>(
                         })
                         .clear_decoder(),
                 ),
-                _ => todo!(),
+                Datagram::ChannelCloseInit(_)
+                | Datagram::ChannelCloseConfirm(_)
+                | Datagram::IntentPacketRecv(_)
+                | Datagram::BatchAcks(_) => {
+                    return Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        format!("unsupported datagram {}", msg.name()),
+                        None::<()>,
+                    ))
+                }
             })
         })
         .collect()
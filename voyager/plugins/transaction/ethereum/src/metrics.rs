@@ -0,0 +1,51 @@
+use opentelemetry::{
+    metrics::{Counter, Gauge},
+    KeyValue,
+};
+use voyager_sdk::primitives::ChainId;
+
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    pub pending: Gauge<u64>,
+    pub replaced_count: Counter<u64>,
+    pub cancelled_count: Counter<u64>,
+}
+
+impl Metrics {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            pending: opentelemetry::global::meter("ethereum_transaction")
+                .u64_gauge("ethereum_transaction_pending")
+                .with_description(
+                    "Number of signers with a transaction that has not yet been included.",
+                )
+                .build(),
+            replaced_count: opentelemetry::global::meter("ethereum_transaction")
+                .u64_counter("ethereum_transaction_replaced_count")
+                .with_description("Total count of transactions replaced with higher fees.")
+                .build(),
+            cancelled_count: opentelemetry::global::meter("ethereum_transaction")
+                .u64_counter("ethereum_transaction_cancelled_count")
+                .with_description("Total count of stuck nonces cancelled.")
+                .build(),
+        }
+    }
+
+    pub fn record_pending(&self, chain_id: &ChainId, pending: usize) {
+        self.pending.record(
+            pending.try_into().unwrap_or(u64::MAX),
+            &[KeyValue::new("chain_id", chain_id.to_string())],
+        );
+    }
+
+    pub fn record_replaced(&self, chain_id: &ChainId) {
+        self.replaced_count
+            .add(1, &[KeyValue::new("chain_id", chain_id.to_string())]);
+    }
+
+    pub fn record_cancelled(&self, chain_id: &ChainId) {
+        self.cancelled_count
+            .add(1, &[KeyValue::new("chain_id", chain_id.to_string())]);
+    }
+}
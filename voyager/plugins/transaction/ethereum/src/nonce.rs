@@ -0,0 +1,210 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+};
+
+use alloy::{
+    network::AnyNetwork,
+    primitives::Address,
+    providers::{DynProvider, Provider},
+    transports::TransportResult,
+};
+use tracing::{debug, warn};
+
+use crate::fees::Fees;
+
+/// Tracks the nonce of the transaction sent by each signer that has not yet been included.
+///
+/// The keyring only ever hands out a signer to one submission at a time, so each signer has at
+/// most one transaction in flight. If that transaction is never included (i.e. it was abandoned
+/// after exhausting all replacements), the next submission by the same signer reuses its nonce
+/// and must outbid it to replace it.
+#[derive(Debug, Default)]
+pub struct NonceManager {
+    in_flight: Mutex<HashMap<Address, InFlight>>,
+}
+
+/// A transaction that has been sent, but not yet included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InFlight {
+    pub nonce: u64,
+    pub fees: Fees,
+}
+
+impl NonceManager {
+    /// The nonce to use for the next transaction sent by `address`, along with the transaction
+    /// that is still in flight with that nonce, if any.
+    pub async fn next(
+        &self,
+        provider: &DynProvider<AnyNetwork>,
+        address: Address,
+    ) -> TransportResult<(u64, Option<InFlight>)> {
+        let nonce = provider.get_transaction_count(address).latest().await?;
+
+        Ok((nonce, self.in_flight_at(address, nonce)))
+    }
+
+    /// The transaction in flight for `address` if it has `nonce`, the current nonce of `address`.
+    ///
+    /// If the transaction in flight has a different nonce, it is either included or was dropped,
+    /// and is no longer tracked.
+    fn in_flight_at(&self, address: Address, nonce: u64) -> Option<InFlight> {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        match in_flight.get(&address).copied() {
+            Some(tx) if tx.nonce == nonce => {
+                debug!(%address, %nonce, "transaction is still in flight");
+
+                Some(tx)
+            }
+            Some(tx) => {
+                if tx.nonce > nonce {
+                    warn!(
+                        %address,
+                        %nonce,
+                        in_flight_nonce = %tx.nonce,
+                        "in flight transaction has a nonce higher than the signer's nonce"
+                    );
+                }
+
+                in_flight.remove(&address);
+
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Record that a transaction has been sent by `address`.
+    pub fn sent(&self, address: Address, nonce: u64, fees: Fees) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(address, InFlight { nonce, fees });
+    }
+
+    /// Record that the transaction with `nonce` sent by `address` has been included.
+    pub fn included(&self, address: Address, nonce: u64) {
+        let mut in_flight = self
+            .in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if in_flight.get(&address).is_some_and(|tx| tx.nonce <= nonce) {
+            in_flight.remove(&address);
+        }
+    }
+
+    /// The number of signers with a transaction in flight.
+    pub fn in_flight_count(&self) -> usize {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: Address = Address::repeat_byte(0xA1);
+    const BOB: Address = Address::repeat_byte(0xB0);
+
+    const FEES: Fees = Fees::Eip1559 {
+        max_fee_per_gas: 100,
+        max_priority_fee_per_gas: 10,
+    };
+
+    #[test]
+    fn sent_is_in_flight_until_included() {
+        let nonces = NonceManager::default();
+
+        assert_eq!(nonces.in_flight_at(ALICE, 3), None);
+
+        nonces.sent(ALICE, 3, FEES);
+        assert_eq!(nonces.in_flight_count(), 1);
+
+        // the transaction has not been included yet, so the nonce is unchanged
+        assert_eq!(
+            nonces.in_flight_at(ALICE, 3),
+            Some(InFlight {
+                nonce: 3,
+                fees: FEES
+            })
+        );
+        assert_eq!(nonces.in_flight_at(BOB, 3), None);
+
+        nonces.included(ALICE, 3);
+        assert_eq!(nonces.in_flight_count(), 0);
+        assert_eq!(nonces.in_flight_at(ALICE, 3), None);
+    }
+
+    #[test]
+    fn replacement_updates_fees() {
+        let nonces = NonceManager::default();
+
+        nonces.sent(ALICE, 3, FEES);
+        nonces.sent(ALICE, 3, FEES.bump(15));
+
+        assert_eq!(nonces.in_flight_count(), 1);
+        assert_eq!(
+            nonces.in_flight_at(ALICE, 3),
+            Some(InFlight {
+                nonce: 3,
+                fees: FEES.bump(15)
+            })
+        );
+    }
+
+    #[test]
+    fn included_ignores_older_nonces() {
+        let nonces = NonceManager::default();
+
+        nonces.sent(ALICE, 3, FEES);
+
+        nonces.included(ALICE, 2);
+        assert_eq!(nonces.in_flight_count(), 1);
+
+        // a later nonce can only be included after this one
+        nonces.included(ALICE, 4);
+        assert_eq!(nonces.in_flight_count(), 0);
+    }
+
+    #[test]
+    fn next_forgets_transactions_with_a_different_nonce() {
+        let nonces = NonceManager::default();
+
+        // included without being recorded, i.e. the signer's nonce has moved past it
+        nonces.sent(ALICE, 3, FEES);
+        assert_eq!(nonces.in_flight_at(ALICE, 4), None);
+        assert_eq!(nonces.in_flight_count(), 0);
+
+        // dropped from the mempool and the nonce reset, i.e. the nonce is behind it
+        nonces.sent(BOB, 3, FEES);
+        assert_eq!(nonces.in_flight_at(BOB, 2), None);
+        assert_eq!(nonces.in_flight_count(), 0);
+    }
+
+    #[test]
+    fn signers_are_tracked_separately() {
+        let nonces = NonceManager::default();
+
+        nonces.sent(ALICE, 3, FEES);
+        nonces.sent(BOB, 7, FEES);
+        assert_eq!(nonces.in_flight_count(), 2);
+
+        nonces.included(BOB, 7);
+        assert_eq!(nonces.in_flight_count(), 1);
+        assert_eq!(
+            nonces.in_flight_at(ALICE, 3),
+            Some(InFlight {
+                nonce: 3,
+                fees: FEES
+            })
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use alloy::{
+    consensus::SignableTransaction,
+    network::TxSigner,
+    primitives::{Address, Signature},
+    signers::{local::LocalSigner, utils::public_key_to_address},
+};
+use bip32::secp256k1::ecdsa::{SigningKey, VerifyingKey};
+use concurrent_keyring::{remote::RemoteSigner, KeyringSigner};
+use jsonrpsee::core::async_trait;
+use voyager_sdk::anyhow::{self, anyhow};
+
+/// A signer for transactions submitted by this plugin, either holding the private key in memory or
+/// delegating to a [`RemoteSigner`].
+#[derive(Debug, Clone)]
+pub enum EthSigner {
+    Local(LocalSigner<SigningKey>),
+    Remote {
+        signer: RemoteSigner,
+        address: Address,
+    },
+}
+
+impl EthSigner {
+    pub async fn new(signer: KeyringSigner) -> anyhow::Result<Self> {
+        match signer {
+            KeyringSigner::Local(key) => Ok(Self::Local(LocalSigner::from_signing_key(
+                <SigningKey as bip32::PrivateKey>::from_bytes(
+                    &key.as_ref()
+                        .try_into()
+                        .map_err(|_| anyhow!("private key must be 32 bytes"))?,
+                )?,
+            ))),
+            KeyringSigner::Remote(signer) => {
+                let public_key = VerifyingKey::from_sec1_bytes(&signer.public_key().await?)?;
+
+                Ok(Self::Remote {
+                    address: public_key_to_address(&public_key),
+                    signer,
+                })
+            }
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        match self {
+            EthSigner::Local(signer) => signer.address(),
+            EthSigner::Remote { address, .. } => *address,
+        }
+    }
+}
+
+#[async_trait]
+impl TxSigner<Signature> for EthSigner {
+    fn address(&self) -> Address {
+        self.address()
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> alloy::signers::Result<Signature> {
+        match self {
+            EthSigner::Local(signer) => signer.sign_transaction(tx).await,
+            EthSigner::Remote { signer, address } => {
+                let signature_hash = tx.signature_hash();
+
+                let signature = signer
+                    .sign_digest(signature_hash.0.into())
+                    .await
+                    .map_err(alloy::signers::Error::other)?;
+
+                let signature =
+                    Signature::from_raw(&signature).map_err(alloy::signers::Error::other)?;
+
+                // ensure that the remote signer signed with the expected key, since a transaction
+                // signed by any other key would be submitted from a different account
+                let recovered = signature
+                    .recover_address_from_prehash(&signature_hash)
+                    .map_err(alloy::signers::Error::other)?;
+
+                if recovered != *address {
+                    return Err(alloy::signers::Error::other(format!(
+                        "remote signer signature recovers to {recovered}, expected {address}"
+                    )));
+                }
+
+                Ok(signature)
+            }
+        }
+    }
+}
@@ -1,11 +1,10 @@
 use std::{
-    collections::VecDeque, fmt::Debug, panic::AssertUnwindSafe, str::FromStr, sync::Arc,
-    time::Duration,
+    collections::VecDeque, fmt::Debug, panic::AssertUnwindSafe, str::FromStr, time::Duration,
 };
 
 use alloy::sol_types::SolValue;
 use concurrent_keyring::{ConcurrentKeyring, KeyringConfig, KeyringEntry};
-use fastcrypto::{hash::HashFunction, traits::Signer};
+use fastcrypto::hash::HashFunction;
 use hex_literal::hex;
 use ibc_union_spec::{datagram::Datagram, ChannelId, IbcUnion};
 use jsonrpsee::{
@@ -29,7 +28,7 @@ use sui_sdk::{
     },
     types::{
         base_types::{ObjectID, SequenceNumber, SuiAddress},
-        crypto::{DefaultHash, SignatureScheme, SuiKeyPair, SuiSignature},
+        crypto::{DefaultHash, SignatureScheme, SuiSignature},
         programmable_transaction_builder::ProgrammableTransactionBuilder,
         signature::GenericSignature,
         transaction::{
@@ -42,26 +41,24 @@ use sui_sdk::{
 };
 use tracing::{info, instrument};
 use ucs03_zkgm::com::{FungibleAssetOrder, ZkgmPacket};
-use unionlabs::{
-    primitives::{encoding::HexPrefixed, Bytes, U256},
-    ErrorReporter,
-};
+use unionlabs::{primitives::U256, ErrorReporter};
 use voyager_sdk::{
     anyhow,
     hook::SubmitTxHook,
     message::{data::Data, PluginMessage, VoyagerMessage},
     plugin::Plugin,
     primitives::ChainId,
-    rpc::{types::PluginInfo, PluginServer},
+    rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
     vm::{call, noop, pass::PassResult, Op, Visit},
     DefaultCmd,
 };
 
-use crate::{call::ModuleCall, callback::ModuleCallback};
+use crate::{call::ModuleCall, callback::ModuleCallback, signer::SuiSigner};
 
 pub mod call;
 pub mod callback;
 pub mod data;
+pub mod signer;
 
 const TOKEN_BYTECODE: [&[u8]; 2] = [
     hex!("a11ceb0b060000000a01000e020e1e032c27045308055b5607b101d1010882036006e2034b0aad04050cb2042b000a010d020602070212021302140001020001020701000003000c01000103030c0100010504020006050700000b000100010c010601000211030400030808090102040e0b01010c040f0e01010c05100c030001050307040a050d02080007080400020b020108000b030108000105010f010805010b01010900010800070900020a020a020a020b01010805070804020b030109000b02010900010b0201080001090001060804010b03010800020900050c436f696e4d657461646174610e46554e4749424c455f544f4b454e064f7074696f6e0b5472656173757279436170095478436f6e746578740355726c076164647265737304636f696e0f6372656174655f63757272656e63790b64756d6d795f6669656c640e66756e6769626c655f746f6b656e04696e6974046e6f6e65066f7074696f6e137075626c69635f73686172655f6f626a6563740f7075626c69635f7472616e736665720673656e64657207746f5f75323536087472616e736665720a74785f636f6e746578740375726c0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000020520").as_slice(),
@@ -83,7 +80,7 @@ pub struct Module {
 
     pub sui_client: sui_sdk::SuiClient,
 
-    pub keyring: ConcurrentKeyring<SuiAddress, Arc<SuiKeyPair>>,
+    pub keyring: ConcurrentKeyring<SuiAddress, SuiSigner>,
 
     pub ibc_store_initial_seq: SequenceNumber,
 }
@@ -115,28 +112,23 @@ impl Plugin for Module {
             .start_version()
             .expect("ibc store is shared, hence it has a start version");
 
+        let mut keyring_entries = vec![];
+
+        for entry in config.keyring.keys {
+            let signer = SuiSigner::new(entry.signer()?).await?;
+
+            keyring_entries.push(KeyringEntry {
+                address: SuiAddress::from(&signer.public()),
+                signer,
+            });
+        }
+
         Ok(Self {
             chain_id: ChainId::new(chain_id.to_string()),
             ibc_handler_address: config.ibc_handler_address,
             sui_client,
             ibc_store_initial_seq,
-            keyring: ConcurrentKeyring::new(
-                config.keyring.name,
-                config.keyring.keys.into_iter().map(|config| {
-                    println!("{}", Bytes::<HexPrefixed>::new(config.value()));
-                    let pk = SuiKeyPair::decode(
-                        &String::from_utf8(config.value()).expect("priv keys are utf8 strings"),
-                    )
-                    .expect("private key is valid");
-
-                    let address = SuiAddress::from(&pk.public());
-
-                    KeyringEntry {
-                        address,
-                        signer: Arc::new(pk),
-                    }
-                }),
-            ),
+            keyring: ConcurrentKeyring::new(config.keyring.name, keyring_entries.into_iter()),
             ibc_store: config.ibc_store,
         })
     }
@@ -221,7 +213,7 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
                     let sender = SuiAddress::from(&pk.public());
                     let msgs = msgs.clone();
                     AssertUnwindSafe(async move {
-                        let msgs = process_msgs(self, pk, msgs, sender).await;
+                        let msgs = process_msgs(self, pk, msgs, sender).await?;
 
                         let mut ptb = ProgrammableTransactionBuilder::new();
 
@@ -274,17 +266,19 @@ impl PluginServer<ModuleCall, ModuleCallback> for Module {
 #[allow(clippy::type_complexity)]
 async fn process_msgs(
     module: &Module,
-    pk: &Arc<SuiKeyPair>,
+    pk: &SuiSigner,
     msgs: Vec<Datagram>,
     fee_recipient: SuiAddress,
-) -> Vec<(
-    SuiAddress,
-    Datagram,
-    Identifier,
-    Identifier,
-    Vec<CallArg>,
-    Vec<TypeTag>,
-)> {
+) -> RpcResult<
+    Vec<(
+        SuiAddress,
+        Datagram,
+        Identifier,
+        Identifier,
+        Vec<CallArg>,
+        Vec<TypeTag>,
+    )>,
+> {
     let mut data = vec![];
     for msg in msgs {
         let item = match msg.clone() {
@@ -321,6 +315,22 @@ async fn process_msgs(
                 ],
                 vec![],
             ),
+            Datagram::SubmitMisbehaviour(data) => (
+                module.ibc_handler_address,
+                msg,
+                Identifier::new("ibc").unwrap(),
+                Identifier::new("submit_misbehaviour").unwrap(),
+                vec![
+                    CallArg::Object(ObjectArg::SharedObject {
+                        id: module.ibc_store.into(),
+                        initial_shared_version: module.ibc_store_initial_seq,
+                        mutable: true,
+                    }),
+                    CallArg::Pure(bcs::to_bytes(&data.client_id).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&data.client_message).unwrap()),
+                ],
+                vec![],
+            ),
             Datagram::ConnectionOpenInit(data) => (
                 module.ibc_handler_address,
                 msg,
@@ -631,12 +641,18 @@ async fn process_msgs(
                     }))],
                 )
             }
-            _ => todo!(),
+            _ => {
+                return Err(ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!("unsupported datagram {}", msg.name()),
+                    None::<()>,
+                ))
+            }
         };
         data.push(item);
     }
 
-    data
+    Ok(data)
 }
 
 fn predict_wrapped_denom(path: U256, channel: ChannelId, base_token: Vec<u8>) -> Vec<u8> {
@@ -650,7 +666,7 @@ fn predict_wrapped_denom(path: U256, channel: ChannelId, base_token: Vec<u8>) ->
 
 async fn register_token_if_zkgm(
     module: &Module,
-    pk: &Arc<SuiKeyPair>,
+    pk: &SuiSigner,
     packet: &ibc_union_spec::Packet,
     module_info: &ModuleInfo,
     store_initial_seq: SequenceNumber,
@@ -880,7 +896,7 @@ pub async fn parse_port(_: &SuiClient, port_id: &str) -> ModuleInfo {
 
 pub async fn send_transactions(
     module: &Module,
-    pk: &Arc<SuiKeyPair>,
+    pk: &SuiSigner,
     ptb: ProgrammableTransaction,
 ) -> RpcResult<SuiTransactionBlockResponse> {
     let sender = SuiAddress::from(&pk.public());
@@ -923,8 +939,13 @@ pub async fn send_transactions(
     hasher.update(raw_tx.clone());
     let digest = hasher.finalize().digest;
 
-    // use SuiKeyPair to sign the digest.
-    let sui_sig = pk.sign(&digest);
+    let sui_sig = pk.sign(digest).await.map_err(|e| {
+        ErrorObject::owned(
+            -1,
+            ErrorReporter(&*e).with_message("error signing the tx"),
+            None::<()>,
+        )
+    })?;
 
     sui_sig
         .verify_secure(&intent_msg, sender, SignatureScheme::ED25519)
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use concurrent_keyring::{remote::RemoteSigner, KeyringSigner};
+use fastcrypto::{
+    ed25519::Ed25519PublicKey,
+    traits::{Signer, ToFromBytes},
+};
+use sui_sdk::types::crypto::{
+    Ed25519SuiSignature, PublicKey, Signature, SignatureScheme, SuiKeyPair,
+};
+use unionlabs::primitives::H256;
+use voyager_sdk::anyhow::{self, anyhow};
+
+/// A signer for transactions submitted by this plugin, either holding the keypair in memory or
+/// delegating to a [`RemoteSigner`]. Remote signers must hold an ed25519 key.
+#[derive(Debug, Clone)]
+pub enum SuiSigner {
+    Local(Arc<SuiKeyPair>),
+    Remote {
+        signer: RemoteSigner,
+        public_key: Ed25519PublicKey,
+    },
+}
+
+impl SuiSigner {
+    pub async fn new(signer: KeyringSigner) -> anyhow::Result<Self> {
+        match signer {
+            KeyringSigner::Local(key) => Ok(Self::Local(Arc::new(
+                SuiKeyPair::decode(&String::from_utf8(key.into())?)
+                    .map_err(|e| anyhow!("invalid private key: {e}"))?,
+            ))),
+            KeyringSigner::Remote(signer) => {
+                let public_key = Ed25519PublicKey::from_bytes(&signer.public_key().await?)
+                    .map_err(|e| anyhow!("invalid remote signer public key: {e}"))?;
+
+                Ok(Self::Remote { signer, public_key })
+            }
+        }
+    }
+
+    pub fn public(&self) -> PublicKey {
+        match self {
+            SuiSigner::Local(pk) => pk.public(),
+            SuiSigner::Remote { public_key, .. } => PublicKey::Ed25519(public_key.into()),
+        }
+    }
+
+    /// Sign the digest of an intent message.
+    pub async fn sign(&self, digest: [u8; 32]) -> anyhow::Result<Signature> {
+        match self {
+            SuiSigner::Local(pk) => Ok(pk.sign(&digest)),
+            SuiSigner::Remote { signer, public_key } => {
+                let signature = signer.sign_digest(H256::new(digest)).await?;
+
+                // flag || signature || public key
+                let bytes = [SignatureScheme::ED25519.flag()]
+                    .into_iter()
+                    .chain(signature.iter().copied())
+                    .chain(public_key.as_bytes().iter().copied())
+                    .collect::<Vec<_>>();
+
+                Ok(Ed25519SuiSignature::from_bytes(&bytes)
+                    .map_err(|e| anyhow!("invalid remote signer signature: {e}"))?
+                    .into())
+            }
+        }
+    }
+}
@@ -0,0 +1,681 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use ibc_union_spec::{event::PacketSend, IbcUnion};
+use jsonrpsee::{
+    core::{async_trait, client::ClientT, RpcResult},
+    rpc_params,
+    types::ErrorObject,
+    Extensions,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, info, instrument, trace, warn};
+use unionlabs::{never::Never, primitives::Bytes, ErrorReporter};
+use voyager_plugin_transaction_batch::data::{BatchableEvent, EventBatch, EventUnion, ModuleData};
+use voyager_plugin_zkgm_filter::{decode_order_fee, decode_packet, flatten_instruction, OrderFee};
+use voyager_sdk::{
+    anyhow::{self, bail},
+    message::{data::Data, PluginMessage, VoyagerMessage},
+    plugin::Plugin,
+    primitives::ChainId,
+    rpc::{
+        json_rpc_error_to_error_object, types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE,
+    },
+    vm::{data, pass::PassResult, Op},
+    DefaultCmd, ExtensionsExt, VoyagerClient,
+};
+
+#[tokio::main]
+async fn main() {
+    Module::run().await
+}
+
+pub struct Module {
+    /// destination chain id -> fee config
+    destinations: BTreeMap<ChainId, DestinationConfig>,
+    unprofitable: UnprofitablePolicy,
+    relay_without_orders: bool,
+    fee_estimate_ttl: Duration,
+    /// (destination chain id, gas) -> (estimated at, estimated fee)
+    fee_estimates: Mutex<HashMap<(ChainId, u64), (Instant, f64)>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct Config {
+    /// destination chain id -> fee config
+    ///
+    /// only packets sent to configured chains will be checked, packets to any unconfigured chain
+    /// will be passed through.
+    pub destinations: BTreeMap<ChainId, DestinationConfig>,
+    /// What to do with packets that are not profitable to relay.
+    pub unprofitable: UnprofitablePolicy,
+    /// Relay packets that don't contain any fungible asset orders without checking their
+    /// profitability.
+    #[serde(default = "default_relay_without_orders")]
+    pub relay_without_orders: bool,
+    /// How long (in seconds) a fee estimate from a transaction plugin is reused for.
+    #[serde(default = "default_fee_estimate_ttl")]
+    pub fee_estimate_ttl: u64,
+}
+
+fn default_relay_without_orders() -> bool {
+    true
+}
+
+fn default_fee_estimate_ttl() -> u64 {
+    10
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct DestinationConfig {
+    /// The name of the transaction plugin submitting transactions to this chain. The `estimateFee`
+    /// method of this plugin is used to estimate the cost of relaying a packet.
+    pub transaction_plugin: String,
+    /// The gas used to receive a packet, excluding the execution of the contained instructions.
+    pub base_gas: u64,
+    /// The gas used to execute a single instruction. Batches count as the amount of instructions
+    /// they contain.
+    pub gas_per_instruction: u64,
+    /// base token -> price
+    ///
+    /// The price of the smallest unit of a base token (the token sent on the source chain, as it
+    /// appears in the order), denominated in the smallest unit of the gas token of this chain. The
+    /// decimals of both tokens are part of the price: a token with 6 decimals that is worth as much
+    /// as a gas token with 18 decimals has a price of `1e12`.
+    pub base_token_prices: BTreeMap<Bytes, f64>,
+    /// quote token -> price
+    ///
+    /// The price of the smallest unit of a quote token (the token the order is filled with on this
+    /// chain), denominated in the same way as [`Self::base_token_prices`].
+    ///
+    /// Orders are only counted towards the profitability of a packet if the prices of both their
+    /// base and quote token are listed.
+    pub quote_token_prices: BTreeMap<Bytes, f64>,
+    /// The minimum ratio of fees to the estimated cost of relaying for a packet to be considered
+    /// profitable.
+    #[serde(default = "default_min_profit_ratio")]
+    pub min_profit_ratio: f64,
+}
+
+fn default_min_profit_ratio() -> f64 {
+    1.0
+}
+
+impl DestinationConfig {
+    /// The value of the fee paid to the filler of `order`, denominated in the smallest unit of the
+    /// gas token of this chain.
+    ///
+    /// The filler receives the base amount and pays out the quote amount, so the fee is the value of
+    /// the former minus the value of the latter. Orders that can only be filled at a loss pay no
+    /// fee, as do orders with an unpriced base or quote token.
+    fn order_value(&self, order: &OrderFee) -> f64 {
+        let price = |prices: &BTreeMap<Bytes, f64>, token: &[u8]| {
+            prices.get(&Bytes::from(token.to_vec())).copied()
+        };
+
+        let (Some(base_price), Some(quote_price)) = (
+            price(&self.base_token_prices, &order.base_token),
+            price(&self.quote_token_prices, &order.quote_token),
+        ) else {
+            debug!(
+                base_token = %order.base_token,
+                quote_token = %order.quote_token,
+                "order has an unpriced token, it will not be counted"
+            );
+
+            return 0.0;
+        };
+
+        let (Ok(base_amount), Ok(quote_amount)) = (
+            u128::try_from(order.base_amount),
+            u128::try_from(order.quote_amount),
+        ) else {
+            debug!(
+                base_amount = %order.base_amount,
+                quote_amount = %order.quote_amount,
+                "order amount does not fit in a u128, it will not be counted"
+            );
+
+            return 0.0;
+        };
+
+        (base_amount as f64 * base_price - quote_amount as f64 * quote_price).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    deny_unknown_fields,
+    rename_all = "snake_case",
+    tag = "type",
+    content = "config"
+)]
+pub enum UnprofitablePolicy {
+    /// Drop unprofitable packets.
+    Drop,
+    /// Hold on to unprofitable packets, checking them again on every pass until they become
+    /// profitable (i.e. gas prices drop) or `max_defer` seconds have passed since they were first
+    /// seen, after which they are dropped.
+    Defer { max_defer: u64 },
+}
+
+impl UnprofitablePolicy {
+    /// The verdict for an unprofitable packet that was first seen at `first_seen_at`. Both
+    /// timestamps are in milliseconds.
+    fn verdict(&self, first_seen_at: u64, now: u64) -> Verdict {
+        match self {
+            UnprofitablePolicy::Drop => Verdict::Drop,
+            UnprofitablePolicy::Defer { max_defer } => {
+                if now.saturating_sub(first_seen_at) > max_defer.saturating_mul(1000) {
+                    Verdict::Drop
+                } else {
+                    Verdict::Defer
+                }
+            }
+        }
+    }
+}
+
+impl Plugin for Module {
+    type Call = Never;
+    type Callback = Never;
+
+    type Config = Config;
+    type Cmd = DefaultCmd;
+
+    async fn new(config: Self::Config) -> anyhow::Result<Self> {
+        for (chain_id, destination) in &config.destinations {
+            if !(destination.min_profit_ratio.is_finite() && destination.min_profit_ratio >= 0.0) {
+                bail!("min_profit_ratio for {chain_id} must be a non-negative finite number");
+            }
+
+            if let Some((token, price)) = destination
+                .base_token_prices
+                .iter()
+                .chain(&destination.quote_token_prices)
+                .find(|(_, price)| !(price.is_finite() && **price >= 0.0))
+            {
+                bail!("price of {token} on {chain_id} must be a non-negative finite number, found {price}");
+            }
+        }
+
+        Ok(Self {
+            destinations: config.destinations,
+            unprofitable: config.unprofitable,
+            relay_without_orders: config.relay_without_orders,
+            fee_estimate_ttl: Duration::from_secs(config.fee_estimate_ttl),
+            fee_estimates: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn info(config: Self::Config) -> PluginInfo {
+        let plugins = config
+            .destinations
+            .keys()
+            .map(|chain_id| {
+                format!(
+                    r#"$plugin == "{}""#,
+                    voyager_plugin_transaction_batch::plugin_name(chain_id)
+                )
+            })
+            .chain(["false".to_owned()])
+            .collect::<Vec<_>>()
+            .join(" or ");
+
+        PluginInfo {
+            name: PLUGIN_NAME.to_owned(),
+            interest_filter: format!(
+                r#"
+if ."@type" == "data" and ."@value"."@type" == "plugin" then
+    ."@value"."@value".plugin as $plugin |
+    ."@value"."@value".message as $message |
+    if ({plugins})
+        and $message."@type" == "batch_events_union"
+        and ([
+            $message."@value".events[]
+            | .event."@type" == "packet_send"
+                and .event."@value".packet.source_channel.version == "{zkgm_version}"
+        ] | any)
+    then
+        true
+    else
+        null
+    end
+else
+    null
+end
+"#,
+                zkgm_version = ucs03_zkgm::contract::PROTOCOL_VERSION,
+            ),
+        }
+    }
+
+    async fn cmd(_config: Self::Config, cmd: Self::Cmd) {
+        match cmd {}
+    }
+}
+
+pub const PLUGIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// The outcome of checking the profitability of a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Relay,
+    Defer,
+    Drop,
+}
+
+#[async_trait]
+impl PluginServer<Never, Never> for Module {
+    #[instrument(skip_all, fields())]
+    async fn run_pass(
+        &self,
+        e: &Extensions,
+        msgs: Vec<Op<VoyagerMessage>>,
+    ) -> RpcResult<PassResult<VoyagerMessage>> {
+        let voyager_client = e.voyager_client()?;
+
+        let now: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+            .try_into()
+            .expect("how many milliseconds can there be man");
+
+        let mut optimize_further = vec![];
+
+        for (idx, msg) in msgs.into_iter().enumerate() {
+            let PluginMessage { plugin, message } = match msg {
+                Op::Data(Data::Plugin(plugin_message)) => plugin_message,
+                msg => {
+                    return Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        "unexpected message in queue",
+                        Some(json!({
+                            "msg": msg,
+                        })),
+                    ))
+                }
+            };
+
+            let Some((chain_id, destination)) = self.destinations.iter().find(|(chain_id, _)| {
+                voyager_plugin_transaction_batch::plugin_name(chain_id) == plugin
+            }) else {
+                return Err(ErrorObject::owned(
+                    FATAL_JSONRPC_ERROR_CODE,
+                    format!("unexpected plugin message for {plugin}"),
+                    None::<()>,
+                ));
+            };
+
+            let EventBatch { client_id, events } = match serde_json::from_value(message.clone()) {
+                Ok(ModuleData::BatchEventsUnion(batch)) => batch,
+                Ok(_) => {
+                    return Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        "unexpected plugin message in queue",
+                        Some(json!({
+                            "msg": message,
+                        })),
+                    ))
+                }
+                Err(err) => {
+                    return Err(ErrorObject::owned(
+                        FATAL_JSONRPC_ERROR_CODE,
+                        ErrorReporter(err).with_message("unable to parse plugin message"),
+                        Some(json!({
+                            "msg": message,
+                        })),
+                    ))
+                }
+            };
+
+            let mut relay = vec![];
+            let mut deferred = vec![];
+
+            for event in events {
+                let verdict = match &event.event {
+                    EventUnion::PacketSend(packet_send)
+                        if packet_send.packet.source_channel.version
+                            == ucs03_zkgm::contract::PROTOCOL_VERSION =>
+                    {
+                        self.check_packet(
+                            voyager_client,
+                            chain_id,
+                            destination,
+                            packet_send,
+                            &event,
+                            now,
+                        )
+                        .await?
+                    }
+                    _ => Verdict::Relay,
+                };
+
+                match verdict {
+                    Verdict::Relay => relay.push(event),
+                    Verdict::Defer => deferred.push(event),
+                    Verdict::Drop => {}
+                }
+            }
+
+            let batch = |events: Vec<BatchableEvent<IbcUnion>>| {
+                data(PluginMessage::new(
+                    plugin.clone(),
+                    ModuleData::BatchEventsUnion(EventBatch { client_id, events }),
+                ))
+            };
+
+            // these are inserted directly into the optimization queues, bypassing the interest
+            // filters (including our own)
+            if !relay.is_empty() {
+                optimize_further.push((vec![idx], batch(relay), plugin.clone()));
+            }
+
+            if !deferred.is_empty() {
+                optimize_further.push((vec![idx], batch(deferred), PLUGIN_NAME.to_owned()));
+            }
+        }
+
+        Ok(PassResult {
+            optimize_further,
+            ready: vec![],
+        })
+    }
+
+    #[instrument(skip_all, fields())]
+    async fn call(&self, _: &Extensions, msg: Never) -> RpcResult<Op<VoyagerMessage>> {
+        match msg {}
+    }
+
+    #[instrument(skip_all, fields())]
+    async fn callback(
+        &self,
+        _: &Extensions,
+        cb: Never,
+        _datas: VecDeque<Data>,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        match cb {}
+    }
+}
+
+impl Module {
+    #[instrument(
+        skip_all,
+        fields(
+            %chain_id,
+            packet_hash = %packet_send.packet().hash()
+        )
+    )]
+    async fn check_packet(
+        &self,
+        voyager_client: &VoyagerClient,
+        chain_id: &ChainId,
+        destination: &DestinationConfig,
+        packet_send: &PacketSend,
+        event: &BatchableEvent<IbcUnion>,
+        now: u64,
+    ) -> RpcResult<Verdict> {
+        let zkgm_packet = match decode_packet(&packet_send.packet_data) {
+            Ok(ok) => ok,
+            Err(err) => {
+                warn!(
+                    err = %ErrorReporter(err),
+                    "unable to decode zkgm packet, profitability will not be checked"
+                );
+
+                return Ok(Verdict::Relay);
+            }
+        };
+
+        let instructions = match flatten_instruction(zkgm_packet.instruction) {
+            Ok(ok) => ok,
+            Err(err) => {
+                warn!(
+                    err = %ErrorReporter(err),
+                    "unable to decode zkgm instruction, profitability will not be checked"
+                );
+
+                return Ok(Verdict::Relay);
+            }
+        };
+
+        let orders = instructions
+            .iter()
+            .filter_map(|instruction| match decode_order_fee(instruction) {
+                Ok(order) => order,
+                Err(err) => {
+                    warn!(
+                        err = %ErrorReporter(err),
+                        "unable to decode fungible asset order, it will not be counted"
+                    );
+
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if orders.is_empty() && self.relay_without_orders {
+            debug!("packet contains no fungible asset orders");
+
+            return Ok(Verdict::Relay);
+        }
+
+        let gas = destination.base_gas.saturating_add(
+            destination
+                .gas_per_instruction
+                .saturating_mul(instructions.len().try_into().unwrap_or(u64::MAX)),
+        );
+
+        let cost = self
+            .estimate_fee(voyager_client, chain_id, destination, gas)
+            .await?;
+
+        let value = orders
+            .iter()
+            .map(|order| destination.order_value(order))
+            .sum::<f64>();
+
+        if value >= cost * destination.min_profit_ratio {
+            info!(%gas, %cost, %value, "packet is profitable");
+
+            return Ok(Verdict::Relay);
+        }
+
+        let verdict = self.unprofitable.verdict(event.first_seen_at, now);
+
+        match verdict {
+            Verdict::Defer => debug!(%gas, %cost, %value, "deferring unprofitable packet"),
+            _ => info!(
+                %gas,
+                %cost,
+                %value,
+                first_seen_at = event.first_seen_at,
+                "dropping unprofitable packet"
+            ),
+        }
+
+        Ok(verdict)
+    }
+
+    /// Estimate the fee of a transaction using `gas` gas on `chain_id`, in the smallest unit of the
+    /// gas token of the chain.
+    async fn estimate_fee(
+        &self,
+        voyager_client: &VoyagerClient,
+        chain_id: &ChainId,
+        destination: &DestinationConfig,
+        gas: u64,
+    ) -> RpcResult<f64> {
+        let cached = self
+            .fee_estimates
+            .lock()
+            .unwrap()
+            .get(&(chain_id.clone(), gas))
+            .copied()
+            .filter(|(estimated_at, _)| estimated_at.elapsed() < self.fee_estimate_ttl);
+
+        if let Some((_, fee)) = cached {
+            trace!(%gas, %fee, "using cached fee estimate");
+
+            return Ok(fee);
+        }
+
+        let fee = voyager_client
+            .plugin_client(&destination.transaction_plugin)
+            .request::<String, _>("estimateFee", rpc_params![gas])
+            .await
+            .map_err(json_rpc_error_to_error_object)?;
+
+        let fee = fee.parse::<u128>().map_err(|err| {
+            ErrorObject::owned(
+                FATAL_JSONRPC_ERROR_CODE,
+                ErrorReporter(err).with_message("invalid fee estimate"),
+                Some(json!({
+                    "fee": fee,
+                    "transaction_plugin": destination.transaction_plugin,
+                })),
+            )
+        })? as f64;
+
+        self.fee_estimates
+            .lock()
+            .unwrap()
+            .insert((chain_id.clone(), gas), (Instant::now(), fee));
+
+        Ok(fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::U256;
+
+    use super::*;
+
+    const USDC: &[u8] = b"usdc";
+    const WRAPPED_USDC: &[u8] = b"wrapped-usdc";
+    const UNPRICED: &[u8] = b"unpriced";
+
+    fn destination() -> DestinationConfig {
+        DestinationConfig {
+            transaction_plugin: "transaction".to_owned(),
+            base_gas: 100_000,
+            gas_per_instruction: 50_000,
+            // 6 decimals on the source chain, worth 0.0005 of the 18 decimal gas token
+            base_token_prices: [(USDC.to_vec().into(), 5e8)].into_iter().collect(),
+            // 18 decimals on the destination chain
+            quote_token_prices: [(WRAPPED_USDC.to_vec().into(), 5e-4)].into_iter().collect(),
+            min_profit_ratio: 1.0,
+        }
+    }
+
+    fn order(
+        base_token: &[u8],
+        base_amount: u128,
+        quote_token: &[u8],
+        quote_amount: u128,
+    ) -> OrderFee {
+        OrderFee {
+            base_token: base_token.to_vec().into(),
+            base_amount: U256::from(base_amount),
+            quote_token: quote_token.to_vec().into(),
+            quote_amount: U256::from(quote_amount),
+        }
+    }
+
+    #[test]
+    fn order_value_accounts_for_decimals() {
+        // 1 usdc for 0.99 wrapped usdc leaves 0.01 usdc, worth 5e-6 of the gas token
+        let value = destination().order_value(&order(
+            USDC,
+            1_000_000,
+            WRAPPED_USDC,
+            990_000_000_000_000_000,
+        ));
+
+        assert!((value - 5e12).abs() < 1.0, "{value}");
+    }
+
+    #[test]
+    fn order_value_filled_at_a_loss() {
+        assert_eq!(
+            destination().order_value(&order(
+                USDC,
+                1_000_000,
+                WRAPPED_USDC,
+                1_010_000_000_000_000_000
+            )),
+            0.0
+        );
+    }
+
+    #[test]
+    fn order_value_unpriced_token() {
+        assert_eq!(
+            destination().order_value(&order(UNPRICED, 1_000_000, WRAPPED_USDC, 0)),
+            0.0
+        );
+        assert_eq!(
+            destination().order_value(&order(USDC, 1_000_000, UNPRICED, 0)),
+            0.0
+        );
+        // tokens are priced by side, the base token is not a quote token
+        assert_eq!(
+            destination().order_value(&order(WRAPPED_USDC, 1_000_000, USDC, 0)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn order_value_overflow() {
+        let mut order = order(USDC, 0, WRAPPED_USDC, 0);
+        order.base_amount = U256::from(u128::MAX) + U256::from(1);
+
+        assert_eq!(destination().order_value(&order), 0.0);
+    }
+
+    #[test]
+    fn unprofitable_verdict() {
+        assert_eq!(UnprofitablePolicy::Drop.verdict(0, 0), Verdict::Drop);
+
+        let defer = UnprofitablePolicy::Defer { max_defer: 60 };
+
+        assert_eq!(defer.verdict(1_000, 1_000), Verdict::Defer);
+        assert_eq!(defer.verdict(1_000, 61_000), Verdict::Defer);
+        assert_eq!(defer.verdict(1_000, 61_001), Verdict::Drop);
+        // clock skew between voyager instances
+        assert_eq!(defer.verdict(2_000, 1_000), Verdict::Defer);
+    }
+
+    #[test]
+    fn parse_config() {
+        let config = serde_json::from_value::<Config>(serde_json::json!({
+            "destinations": {
+                "union-1": {
+                    "transaction_plugin": "transaction",
+                    "base_gas": 100000,
+                    "gas_per_instruction": 50000,
+                    "base_token_prices": { "0x75736463": 5e8 },
+                    "quote_token_prices": { "0x777261707065642d75736463": 5e-4 }
+                }
+            },
+            "unprofitable": { "type": "defer", "config": { "max_defer": 60 } }
+        }))
+        .unwrap();
+
+        assert_eq!(config.destinations[&ChainId::new("union-1")], destination());
+        assert_eq!(
+            config.unprofitable,
+            UnprofitablePolicy::Defer { max_defer: 60 }
+        );
+        assert!(config.relay_without_orders);
+        assert_eq!(config.fee_estimate_ttl, 10);
+    }
+}
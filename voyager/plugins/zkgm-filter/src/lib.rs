@@ -0,0 +1,184 @@
+//! Decoding helpers for `ucs03-zkgm` packets and acknowledgements, shared between the zkgm voyager
+//! plugins.
+
+use alloy::{
+    primitives::{Bytes, U256},
+    sol_types::SolValue,
+};
+use tracing::{debug, info};
+use ucs03_zkgm::com::{
+    Ack, Batch, BatchAck, FungibleAssetOrder, FungibleAssetOrderAck, FungibleAssetOrderV2,
+    Instruction, ZkgmPacket, FILL_TYPE_PROTOCOL, INSTR_VERSION_0, INSTR_VERSION_1, INSTR_VERSION_2,
+    OP_BATCH, OP_FUNGIBLE_ASSET_ORDER, TAG_ACK_SUCCESS,
+};
+
+/// Check whether the provided acknowledgement is a successful `ucs03-zkgm` acknowledgement of a
+/// protocol-filled [`FungibleAssetOrder`] (either directly or as the only instruction in a batch).
+pub fn is_successful_protocol_fill_ack(acknowledgement: &[u8]) -> bool {
+    let Ok(ack) = Ack::abi_decode_params_validate(acknowledgement) else {
+        // not a zkgm ack
+        return false;
+    };
+
+    info!(%ack.tag, %ack.inner_ack, "zkgm ack");
+
+    if ack.tag != TAG_ACK_SUCCESS {
+        debug!("ack is not {TAG_ACK_SUCCESS}");
+        return false;
+    }
+
+    let ack = match FungibleAssetOrderAck::abi_decode_params_validate(&ack.inner_ack) {
+        Ok(ack) => ack,
+        Err(_) => match BatchAck::abi_decode_params_validate(&ack.inner_ack) {
+            Ok(BatchAck { acknowledgements }) if acknowledgements.len() == 1 => {
+                let Ok(ack) =
+                    FungibleAssetOrderAck::abi_decode_params_validate(&acknowledgements[0])
+                else {
+                    return false;
+                };
+                ack
+            }
+            _ => return false,
+        },
+    };
+
+    info!(%ack.fill_type, %ack.market_maker, "fungible asset order ack");
+
+    ack.fill_type == FILL_TYPE_PROTOCOL
+}
+
+/// Decode the data of a `ucs03-zkgm` packet.
+pub fn decode_packet(data: &[u8]) -> Result<ZkgmPacket, alloy::sol_types::Error> {
+    ZkgmPacket::abi_decode_params_validate(data)
+}
+
+/// Flatten an instruction into the instructions that will be executed on the destination chain.
+///
+/// Batches are expanded into their contained instructions; all other instructions (including
+/// forwards, which are executed on a further hop) are returned as-is.
+pub fn flatten_instruction(
+    instruction: Instruction,
+) -> Result<Vec<Instruction>, alloy::sol_types::Error> {
+    if instruction.opcode == OP_BATCH && instruction.version == INSTR_VERSION_0 {
+        Batch::abi_decode_params_validate(&instruction.operand)?
+            .instructions
+            .into_iter()
+            .map(flatten_instruction)
+            .try_fold(vec![], |mut acc, instructions| {
+                acc.extend(instructions?);
+                Ok(acc)
+            })
+    } else {
+        Ok(vec![instruction])
+    }
+}
+
+/// The parts of a [`FungibleAssetOrder`] or [`FungibleAssetOrderV2`] relevant to the fee paid to
+/// the filler of the order.
+///
+/// The base and quote amounts are denominated in the smallest unit of their respective tokens,
+/// which can have different decimals. They can only be compared after pricing both tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderFee {
+    /// The token sent on the source chain.
+    pub base_token: Bytes,
+    pub base_amount: U256,
+    /// The token the order is filled with on the destination chain.
+    pub quote_token: Bytes,
+    pub quote_amount: U256,
+}
+
+/// Decode the fee of a fungible asset order instruction, returning `None` if the instruction is not
+/// a fungible asset order.
+pub fn decode_order_fee(
+    instruction: &Instruction,
+) -> Result<Option<OrderFee>, alloy::sol_types::Error> {
+    if instruction.opcode != OP_FUNGIBLE_ASSET_ORDER {
+        return Ok(None);
+    }
+
+    match instruction.version {
+        INSTR_VERSION_1 => {
+            let order = FungibleAssetOrder::abi_decode_params_validate(&instruction.operand)?;
+
+            Ok(Some(OrderFee {
+                base_token: order.base_token,
+                base_amount: order.base_amount,
+                quote_token: order.quote_token,
+                quote_amount: order.quote_amount,
+            }))
+        }
+        INSTR_VERSION_2 => {
+            let order = FungibleAssetOrderV2::abi_decode_params_validate(&instruction.operand)?;
+
+            Ok(Some(OrderFee {
+                base_token: order.base_token,
+                base_amount: order.base_amount,
+                quote_token: order.quote_token,
+                quote_amount: order.quote_amount,
+            }))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::hex;
+
+    use super::*;
+
+    #[test]
+    fn successful_protocol_fill() {
+        let ack = hex!("0x0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000e000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000600000000000000000000000000000000000000000000000000000000000b0cad000000000000000000000000000000000000000000000000000000000000000400000000000000000000000000000000000000000000000000000000000000000");
+
+        assert!(is_successful_protocol_fill_ack(&ack));
+    }
+
+    #[test]
+    fn batch_order_fees() {
+        let order = |base_amount: u64, quote_amount: u64| Instruction {
+            version: INSTR_VERSION_1,
+            opcode: OP_FUNGIBLE_ASSET_ORDER,
+            operand: FungibleAssetOrder {
+                sender: Bytes::new(),
+                receiver: Bytes::new(),
+                base_token: Bytes::from_static(b"base"),
+                base_amount: U256::from(base_amount),
+                base_token_symbol: "".into(),
+                base_token_name: "".into(),
+                base_token_decimals: 6,
+                base_token_path: U256::ZERO,
+                quote_token: Bytes::from_static(b"quote"),
+                quote_amount: U256::from(quote_amount),
+            }
+            .abi_encode_params()
+            .into(),
+        };
+
+        let batch = Instruction {
+            version: INSTR_VERSION_0,
+            opcode: OP_BATCH,
+            operand: Batch {
+                instructions: vec![order(100, 90), order(100, 110)],
+            }
+            .abi_encode_params()
+            .into(),
+        };
+
+        let fees = flatten_instruction(batch)
+            .unwrap()
+            .iter()
+            .map(|instruction| decode_order_fee(instruction).unwrap().unwrap())
+            .collect::<Vec<_>>();
+
+        let fee = |base_amount: u64, quote_amount: u64| OrderFee {
+            base_token: Bytes::from_static(b"base"),
+            base_amount: U256::from(base_amount),
+            quote_token: Bytes::from_static(b"quote"),
+            quote_amount: U256::from(quote_amount),
+        };
+
+        assert_eq!(fees, [fee(100, 90), fee(100, 110)]);
+    }
+}
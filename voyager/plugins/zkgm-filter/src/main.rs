@@ -2,6 +2,7 @@
 
 use std::{
     collections::{BTreeMap, HashSet, VecDeque},
+    sync::Mutex,
     time::{SystemTime, UNIX_EPOCH},
 };
 
@@ -9,7 +10,7 @@ use alloy::{
     consensus::Transaction,
     hex,
     providers::{DynProvider, Provider, ProviderBuilder},
-    sol_types::{SolCall, SolValue},
+    sol_types::SolCall,
 };
 use crc::{Crc, CRC_32_ISO_HDLC};
 use futures::TryStreamExt;
@@ -29,8 +30,7 @@ use sqlx::{
     prelude::FromRow,
     Executor, PgPool, Row,
 };
-use tracing::{debug, info, info_span, instrument, trace, warn, Instrument};
-use ucs03_zkgm::com::{Ack, BatchAck, FungibleAssetOrderAck, FILL_TYPE_PROTOCOL, TAG_ACK_SUCCESS};
+use tracing::{info, info_span, instrument, trace, warn, Instrument};
 use unionlabs::{
     self,
     cosmos::tx::{tx_body::TxBody, tx_raw::TxRaw},
@@ -43,6 +43,7 @@ use unionlabs::{
     ErrorReporter,
 };
 use voyager_plugin_transaction_batch::data::{BatchableEvent, EventBatch};
+use voyager_plugin_zkgm_filter::is_successful_protocol_fill_ack;
 use voyager_sdk::{
     anyhow::{self, bail},
     message::{
@@ -52,14 +53,19 @@ use voyager_sdk::{
     plugin::Plugin,
     primitives::{ChainId, IbcSpec},
     rpc::{types::PluginInfo, PluginServer, FATAL_JSONRPC_ERROR_CODE},
-    vm::{call, data, noop, pass::PassResult, Op},
+    vm::{call, data, defer, noop, pass::PassResult, seq, Op},
 };
 
 use crate::{
-    call::{CheckSendPacket, ModuleCall},
+    call::{CheckSendPacket, ModuleCall, ReleaseRateLimited},
+    metrics::{Metrics, Outcome},
+    rate_limit::{RateLimitConfig, RateLimiter, Schedule},
     IZkgm::sendCall,
 };
 
+pub mod metrics;
+pub mod rate_limit;
+
 #[tokio::main]
 async fn main() {
     Module::run().await
@@ -69,13 +75,17 @@ pub mod call {
     use enumorph::Enumorph;
     use ibc_union_spec::event::PacketSend;
     use macros::model;
-    use unionlabs::{ibc::core::client::height::Height, primitives::H256};
+    use unionlabs::{
+        ibc::core::client::height::Height,
+        primitives::{Bytes, H256},
+    };
     use voyager_sdk::primitives::ChainId;
 
     #[model]
     #[derive(Enumorph)]
     pub enum ModuleCall {
         CheckSendPacket(CheckSendPacket),
+        ReleaseRateLimited(ReleaseRateLimited),
     }
 
     #[model]
@@ -86,6 +96,18 @@ pub mod call {
         pub tx_hash: H256,
         pub provable_height: Height,
     }
+
+    /// Poll a packet waiting on its channel rate limit, relaying it once it is released.
+    #[model]
+    pub struct ReleaseRateLimited {
+        pub packet: CheckSendPacket,
+        pub sender: Option<Bytes>,
+        /// The ticket handed out by the rate limiter. Tickets from before a restart are not
+        /// recognized, and the packet is queued again.
+        pub ticket: u64,
+        /// Unix timestamp (in ms) at which the packet was first rate limited.
+        pub queued_at: u64,
+    }
 }
 
 pub struct Module {
@@ -98,6 +120,8 @@ pub struct Module {
     whitelisted_addresses: HashSet<Bytes>,
     // i64 for ease of use with the sqlx types
     max_invalid_per_address: i64,
+    rate_limiter: Mutex<RateLimiter>,
+    metrics: Metrics,
 }
 
 pub enum ChainProvider {
@@ -122,6 +146,16 @@ pub struct Config {
     #[serde(default)]
     whitelisted_addresses_cosmos: HashSet<Bech32<Bytes>>,
     max_invalid_per_address: usize,
+    /// Rate limits applied to the packets that pass the checksum checks. Whitelisted addresses are
+    /// not rate limited.
+    ///
+    /// Rate limited packets are delayed until the rate limit allows them through. Packets waiting
+    /// on a channel rate limit are released round-robin across their senders.
+    #[serde(default)]
+    rate_limit: RateLimitConfig,
+    /// Attach the sender address to the recorded metrics.
+    #[serde(default)]
+    sender_metrics: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -144,6 +178,15 @@ impl Plugin for Module {
     type Cmd = Cmd;
 
     async fn new(config: Self::Config) -> anyhow::Result<Self> {
+        for (name, bucket) in [
+            ("per_sender", config.rate_limit.per_sender),
+            ("per_channel", config.rate_limit.per_channel),
+        ] {
+            if let Some(Err(err)) = bucket.map(|bucket| bucket.validate()) {
+                bail!("invalid {name} rate limit: {err}");
+            }
+        }
+
         let mut providers = BTreeMap::new();
 
         let db = PgPoolOptions::new().connect(&config.db_url).await?;
@@ -223,6 +266,8 @@ impl Plugin for Module {
                 )
                 .collect(),
             max_invalid_per_address: config.max_invalid_per_address.try_into().unwrap(),
+            rate_limiter: Mutex::new(RateLimiter::new(config.rate_limit)),
+            metrics: Metrics::new(config.sender_metrics),
         })
     }
 
@@ -516,6 +561,7 @@ impl PluginServer<ModuleCall, Never> for Module {
     async fn call(&self, _: &Extensions, msg: ModuleCall) -> RpcResult<Op<VoyagerMessage>> {
         match msg {
             ModuleCall::CheckSendPacket(p) => self.check_send_packet(p).await,
+            ModuleCall::ReleaseRateLimited(p) => self.release_rate_limited(p),
         }
     }
 
@@ -601,39 +647,20 @@ impl Module {
             }
         };
 
-        let continuation = || {
-            let first_seen_at: u64 = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_millis()
-                .try_into()
-                .expect("how many milliseconds can there be man");
-
-            let client_id = event.packet.destination_channel.connection.client_id;
-
-            let batchable_event = BatchableEvent::<IbcUnion> {
-                first_seen_at,
-                provable_height: EventProvableHeight::Min(provable_height),
-                event: event.into(),
-            };
-
-            Ok(data(PluginMessage::new(
-                voyager_plugin_transaction_batch::plugin_name(&counterparty_chain_id),
-                voyager_plugin_transaction_batch::data::ModuleData::BatchEventsUnion(EventBatch {
-                    client_id,
-                    events: vec![batchable_event],
-                }),
-            )))
-        };
+        let source_channel_id = event.packet.source_channel.channel_id;
+
+        let whitelisted = address
+            .as_ref()
+            .is_some_and(|address| self.whitelisted_addresses.contains(address));
 
-        if valid {
+        let dropped = if valid {
             info!("valid checksum");
-            continuation()
+            None
         } else if let Some(ref address) = address
-            && self.whitelisted_addresses.contains(address)
+            && whitelisted
         {
             info!(%address, "invalid checksum from whitelisted address");
-            continuation()
+            None
         } else {
             warn!("invalid checksum");
 
@@ -642,7 +669,7 @@ impl Module {
                 count: i64,
             }
 
-            if let Some(address) = address {
+            if let Some(ref address) = address {
                 let res = sqlx::query(
                     r#"
                     WITH res AS (
@@ -675,23 +702,180 @@ impl Module {
                 if res.count > self.max_invalid_per_address {
                     info!(total_invalid = %res.count, %address, "suspicious address");
 
-                    if self.drop_suspicious || self.drop_invalid_checksum {
-                        Ok(noop())
-                    } else {
-                        continuation()
-                    }
+                    (self.drop_suspicious || self.drop_invalid_checksum)
+                        .then_some(Outcome::Suspicious)
                 } else {
-                    continuation()
+                    None
                 }
-            } else if self.drop_invalid_checksum {
-                Ok(noop())
             } else {
-                continuation()
+                self.drop_invalid_checksum
+                    .then_some(Outcome::InvalidChecksum)
+            }
+        };
+
+        if let Some(outcome) = dropped {
+            self.metrics.record(&chain_id, address.as_ref(), outcome);
+
+            return Ok(noop());
+        }
+
+        let packet = CheckSendPacket {
+            event,
+            chain_id: chain_id.clone(),
+            counterparty_chain_id,
+            tx_hash,
+            provable_height,
+        };
+
+        if whitelisted {
+            self.metrics
+                .record(&chain_id, address.as_ref(), Outcome::Relayed);
+
+            return Ok(relay(packet));
+        }
+
+        let now = now_millis();
+
+        let schedule = self.rate_limiter.lock().unwrap().schedule(
+            &chain_id,
+            source_channel_id,
+            address.as_ref(),
+            now,
+        );
+
+        let outcome = match schedule {
+            Schedule::Dropped => {
+                warn!("rate limit queue is full, dropping packet");
+
+                Outcome::RateLimited
+            }
+            Schedule::At(ready_at) if ready_at <= now => Outcome::Relayed,
+            Schedule::At(_) | Schedule::Wait { .. } => Outcome::Delayed,
+        };
+
+        self.metrics.record(&chain_id, address.as_ref(), outcome);
+
+        self.rate_limited(packet, address, now, now, schedule)
+    }
+
+    #[instrument(
+        skip_all,
+        fields(
+            chain_id = %packet.chain_id,
+            packet_hash = %packet.event.packet().hash(),
+            %ticket
+        )
+    )]
+    fn release_rate_limited(
+        &self,
+        ReleaseRateLimited {
+            packet,
+            sender,
+            ticket,
+            queued_at,
+        }: ReleaseRateLimited,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        let now = now_millis();
+
+        let schedule = self.rate_limiter.lock().unwrap().poll(
+            &packet.chain_id,
+            packet.event.packet.source_channel.channel_id,
+            sender.as_ref(),
+            ticket,
+            now,
+        );
+
+        self.rate_limited(packet, sender, queued_at, now, schedule)
+    }
+
+    /// Relay, delay or drop a packet according to the [`Schedule`] returned by the rate limiter.
+    fn rate_limited(
+        &self,
+        packet: CheckSendPacket,
+        sender: Option<Bytes>,
+        queued_at: u64,
+        now: u64,
+        schedule: Schedule,
+    ) -> RpcResult<Op<VoyagerMessage>> {
+        match schedule {
+            Schedule::Dropped => Ok(noop()),
+            Schedule::At(ready_at) => {
+                let delay = ready_at.saturating_sub(queued_at);
+
+                if delay > 0 {
+                    self.metrics.record_delay(&packet.chain_id, sender.as_ref(), delay);
+                }
+
+                if ready_at > now {
+                    info!(
+                        delay_ms = ready_at - now,
+                        "packet is rate limited, delaying"
+                    );
+
+                    Ok(seq([defer(ready_at.div_ceil(1000)), relay(packet)]))
+                } else {
+                    Ok(relay(packet))
+                }
+            }
+            Schedule::Wait { ticket, poll_at } => {
+                info!(
+                    delay_ms = poll_at.saturating_sub(now),
+                    %ticket,
+                    "packet is waiting on the channel rate limit, delaying"
+                );
+
+                Ok(seq([
+                    defer(poll_at.div_ceil(1000)),
+                    call(PluginMessage::new(
+                        self.plugin_name(),
+                        ModuleCall::from(ReleaseRateLimited {
+                            packet,
+                            sender,
+                            ticket,
+                            queued_at,
+                        }),
+                    )),
+                ]))
             }
         }
     }
 }
 
+/// Hand the packet over to the transaction batch plugin of the counterparty chain.
+fn relay(
+    CheckSendPacket {
+        event,
+        counterparty_chain_id,
+        provable_height,
+        ..
+    }: CheckSendPacket,
+) -> Op<VoyagerMessage> {
+    let client_id = event.packet.destination_channel.connection.client_id;
+
+    let batchable_event = BatchableEvent::<IbcUnion> {
+        first_seen_at: now_millis(),
+        provable_height: EventProvableHeight::Min(provable_height),
+        event: event.into(),
+    };
+
+    data(PluginMessage::new(
+        voyager_plugin_transaction_batch::plugin_name(&counterparty_chain_id),
+        voyager_plugin_transaction_batch::data::ModuleData::BatchEventsUnion(EventBatch {
+            client_id,
+            events: vec![batchable_event],
+        }),
+    ))
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+        .try_into()
+        .expect("how many milliseconds can there be man")
+}
+
 /// The `ucs03-zkgm` protocol-filled packets do nothing on acknowledgement, so there's no need to relay those messages.
 #[instrument(
     skip_all,
@@ -703,39 +887,6 @@ pub fn is_successful_protocol_fill(event: &WriteAck) -> bool {
     is_successful_protocol_fill_ack(&event.acknowledgement)
 }
 
-fn is_successful_protocol_fill_ack(acknowledgement: &[u8]) -> bool {
-    let Ok(ack) = Ack::abi_decode_params_validate(acknowledgement) else {
-        // not a zkgm ack
-        return false;
-    };
-
-    info!(%ack.tag, %ack.inner_ack, "zkgm ack");
-
-    if ack.tag != TAG_ACK_SUCCESS {
-        debug!("ack is not {TAG_ACK_SUCCESS}");
-        return false;
-    }
-
-    let ack = match FungibleAssetOrderAck::abi_decode_params_validate(&ack.inner_ack) {
-        Ok(ack) => ack,
-        Err(_) => match BatchAck::abi_decode_params_validate(&ack.inner_ack) {
-            Ok(BatchAck { acknowledgements }) if acknowledgements.len() == 1 => {
-                let Ok(ack) =
-                    FungibleAssetOrderAck::abi_decode_params_validate(&acknowledgements[0])
-                else {
-                    return false;
-                };
-                ack
-            }
-            _ => return false,
-        },
-    };
-
-    info!(%ack.fill_type, %ack.market_maker, "fungible asset order ack");
-
-    ack.fill_type == FILL_TYPE_PROTOCOL
-}
-
 /// NOTE: Assumes the tx contains only one MsgExecuteContract.
 fn valid_checksum_cosmos(tx_bytes: &[u8]) -> (bool, Option<Bech32<Bytes>>) {
     let tx_raw = TxRaw::decode_as::<Proto>(tx_bytes).expect("invalid transaction?");
@@ -866,11 +1017,4 @@ mod tests {
 
         assert!(ok);
     }
-
-    #[test]
-    fn successful_protocol_fill() {
-        let ack = hex!("0x0000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000e000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000001000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000600000000000000000000000000000000000000000000000000000000000b0cad000000000000000000000000000000000000000000000000000000000000000400000000000000000000000000000000000000000000000000000000000000000");
-
-        assert!(is_successful_protocol_fill_ack(&ack));
-    }
 }
@@ -0,0 +1,74 @@
+use opentelemetry::{metrics::Counter, KeyValue};
+use unionlabs::primitives::Bytes;
+use voyager_sdk::primitives::ChainId;
+
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    /// Whether to attach the sender address to the recorded metrics.
+    per_sender: bool,
+    pub packets_count: Counter<u64>,
+    pub rate_limit_delay: Counter<u64>,
+}
+
+/// What happened to a packet checked by this plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Relayed,
+    Delayed,
+    RateLimited,
+    InvalidChecksum,
+    Suspicious,
+}
+
+impl Outcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Relayed => "relayed",
+            Outcome::Delayed => "delayed",
+            Outcome::RateLimited => "rate_limited",
+            Outcome::InvalidChecksum => "invalid_checksum",
+            Outcome::Suspicious => "suspicious",
+        }
+    }
+}
+
+impl Metrics {
+    pub fn new(per_sender: bool) -> Self {
+        Self {
+            per_sender,
+            packets_count: opentelemetry::global::meter("zkgm_filter")
+                .u64_counter("zkgm_filter_packets_count")
+                .with_description("Total count of zkgm packets checked, by outcome.")
+                .build(),
+            rate_limit_delay: opentelemetry::global::meter("zkgm_filter")
+                .u64_counter("zkgm_filter_rate_limit_delay_milliseconds")
+                .with_description("Total time zkgm packets have been delayed by rate limits.")
+                .build(),
+        }
+    }
+
+    fn attributes(&self, chain_id: &ChainId, sender: Option<&Bytes>) -> Vec<KeyValue> {
+        let mut attributes = vec![KeyValue::new("chain_id", chain_id.to_string())];
+
+        if self.per_sender {
+            attributes.push(KeyValue::new(
+                "sender",
+                sender.map(|s| s.to_string()).unwrap_or_default(),
+            ));
+        }
+
+        attributes
+    }
+
+    pub fn record(&self, chain_id: &ChainId, sender: Option<&Bytes>, outcome: Outcome) {
+        let mut attributes = self.attributes(chain_id, sender);
+        attributes.push(KeyValue::new("outcome", outcome.as_str()));
+
+        self.packets_count.add(1, &attributes);
+    }
+
+    pub fn record_delay(&self, chain_id: &ChainId, sender: Option<&Bytes>, delay: u64) {
+        self.rate_limit_delay
+            .add(delay, &self.attributes(chain_id, sender));
+    }
+}
@@ -0,0 +1,771 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
+
+use ibc_union_spec::ChannelId;
+use serde::{Deserialize, Serialize};
+use unionlabs::primitives::Bytes;
+use voyager_sdk::primitives::ChainId;
+
+/// How long (in ms) a packet waiting on a channel rate limit can go without being polled before
+/// it is considered lost (i.e. its message was removed from the queue).
+///
+/// Lost packets are removed so that they don't hold up the round-robin for the other senders on
+/// the channel. If a lost packet is polled after all, it is queued again.
+const LOST_AFTER: u64 = 60_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct RateLimitConfig {
+    /// The rate limit applied to the packets sent by each individual sender.
+    #[serde(default)]
+    pub per_sender: Option<TokenBucketConfig>,
+    /// The rate limit applied to the packets sent through each individual channel.
+    ///
+    /// Packets waiting on this rate limit are released round-robin across their senders, so a
+    /// single sender flooding a channel only delays its own packets.
+    #[serde(default)]
+    pub per_channel: Option<TokenBucketConfig>,
+    /// The maximum amount of packets a single sender can have waiting on a single rate limit. Any
+    /// packets exceeding this will be dropped.
+    #[serde(default = "default_max_queued")]
+    pub max_queued: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_sender: None,
+            per_channel: None,
+            max_queued: default_max_queued(),
+        }
+    }
+}
+
+fn default_max_queued() -> u64 {
+    1000
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub struct TokenBucketConfig {
+    /// The maximum amount of packets that can be relayed in a burst.
+    pub capacity: u64,
+    /// The amount of packets the bucket is refilled with per second.
+    pub refill_rate: u64,
+}
+
+impl TokenBucketConfig {
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.capacity == 0 {
+            Err("token bucket capacity must not be zero")
+        } else if self.refill_rate == 0 {
+            Err("token bucket refill rate must not be zero")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A token bucket, similar to the one used on chain by `ucs03-zkgm`.
+///
+/// Instead of rejecting packets once the bucket is empty, the bucket can go into debt with
+/// [`Self::reserve`], scheduling packets for the time at which the bucket would have refilled
+/// enough to pay for them. This queues rate limited packets in the order they were seen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenBucket {
+    config: TokenBucketConfig,
+    /// Available tokens, scaled by 1000 to allow for millisecond precision refills. Negative
+    /// values are the amount of tokens owed by queued packets.
+    available: i128,
+    /// Unix timestamp (in ms) of the last refill.
+    last_refill: u64,
+}
+
+impl TokenBucket {
+    pub fn new(config: TokenBucketConfig, now: u64) -> Self {
+        Self {
+            config,
+            available: i128::from(config.capacity) * 1000,
+            last_refill: now,
+        }
+    }
+
+    pub fn refill(&mut self, now: u64) {
+        let elapsed = now.saturating_sub(self.last_refill);
+
+        self.available = (self.available
+            + i128::from(elapsed) * i128::from(self.config.refill_rate))
+        .min(i128::from(self.config.capacity) * 1000);
+        self.last_refill = self.last_refill.max(now);
+    }
+
+    /// Whether the bucket is full, i.e. has not been used since it was last refilled completely.
+    pub fn is_full(&self) -> bool {
+        self.available >= i128::from(self.config.capacity) * 1000
+    }
+
+    /// The amount of packets currently waiting on this bucket.
+    pub fn queued(&self) -> u64 {
+        (-self.available)
+            .max(0)
+            .div_ceil(1000)
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Take a token out of the bucket, returning the unix timestamp (in ms) at which the packet
+    /// paying with this token can be relayed.
+    pub fn reserve(&mut self, now: u64) -> u64 {
+        self.refill(now);
+
+        self.available -= 1000;
+
+        if self.available >= 0 {
+            now
+        } else {
+            let wait = (-self.available).div_ceil(i128::from(self.config.refill_rate));
+
+            now.saturating_add(wait.try_into().unwrap_or(u64::MAX))
+        }
+    }
+
+    /// Take a token out of the bucket if one is available, without going into debt.
+    pub fn try_take(&mut self, now: u64) -> bool {
+        self.refill(now);
+
+        if self.available >= 1000 {
+            self.available -= 1000;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The unix timestamp (in ms) at which the bucket will have refilled to `tokens` available
+    /// tokens, assuming none are taken out in the meantime.
+    pub fn available_at(&mut self, now: u64, tokens: u64) -> u64 {
+        self.refill(now);
+
+        let missing = (i128::from(tokens) * 1000 - self.available).max(0);
+
+        now.saturating_add(
+            missing
+                .div_ceil(i128::from(self.config.refill_rate))
+                .try_into()
+                .unwrap_or(u64::MAX),
+        )
+    }
+}
+
+/// The result of scheduling a packet with the [`RateLimiter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// The packet can be relayed at this unix timestamp (in ms).
+    At(u64),
+    /// The packet is waiting on its channel rate limit, and must be polled with
+    /// [`RateLimiter::poll`] at `poll_at` (a unix timestamp in ms) to check whether it can be
+    /// relayed.
+    Wait { ticket: u64, poll_at: u64 },
+    /// The sender already has [`RateLimitConfig::max_queued`] packets waiting on one of the rate
+    /// limits, the packet is to be dropped.
+    Dropped,
+}
+
+/// The rate limits for all senders and channels on all chains.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    senders: HashMap<(ChainId, Bytes), TokenBucket>,
+    channels: HashMap<(ChainId, ChannelId), ChannelQueue>,
+    /// Tickets are prefixed with a random epoch in the upper 32 bits, so that the tickets of
+    /// packets that were waiting before a restart don't collide with the tickets handed out after
+    /// it.
+    next_ticket: u64,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            senders: HashMap::new(),
+            channels: HashMap::new(),
+            next_ticket: u64::from(rand::random::<u32>()) << 32,
+        }
+    }
+
+    /// Schedule a packet sent on `chain_id` through `channel_id` by `sender`.
+    ///
+    /// The per-sender rate limit is applied first, scheduling the packet in the order it was
+    /// seen. Once through that, the packet waits on the channel rate limit until it is its
+    /// sender's turn, see [`Schedule::Wait`].
+    pub fn schedule(
+        &mut self,
+        chain_id: &ChainId,
+        channel_id: ChannelId,
+        sender: Option<&Bytes>,
+        now: u64,
+    ) -> Schedule {
+        let max_queued = self.config.max_queued;
+
+        let sender_bucket = match (self.config.per_sender, sender) {
+            (Some(config), Some(sender)) => Some(bucket(
+                &mut self.senders,
+                (chain_id.clone(), sender.clone()),
+                config,
+                now,
+            )),
+            _ => None,
+        };
+
+        let channel = self.config.per_channel.map(|config| {
+            let channel = self
+                .channels
+                .entry((chain_id.clone(), channel_id))
+                .or_insert_with(|| ChannelQueue::new(config, now));
+
+            channel.expire(now);
+
+            channel
+        });
+
+        if sender_bucket
+            .as_ref()
+            .is_some_and(|b| b.queued() >= max_queued)
+            || channel
+                .as_ref()
+                .is_some_and(|c| c.waiting_for(sender) >= max_queued)
+        {
+            return Schedule::Dropped;
+        }
+
+        let not_before = sender_bucket.map_or(now, |bucket| bucket.reserve(now));
+
+        let schedule = match channel {
+            Some(channel) => {
+                let ticket = self.next_ticket();
+
+                channel.push(ticket, sender.cloned(), not_before);
+
+                channel
+                    .poll(ticket, sender, now)
+                    .expect("ticket was just pushed; qed;")
+            }
+            None => Schedule::At(not_before),
+        };
+
+        self.prune(now);
+
+        schedule
+    }
+
+    /// Poll a packet waiting on the channel rate limit, returning [`Schedule::At`] once the packet
+    /// can be relayed.
+    ///
+    /// Packets unknown to the rate limiter (i.e. they were lost, or voyager was restarted while
+    /// they were waiting) are queued again, without being charged to the per-sender rate limit
+    /// again.
+    pub fn poll(
+        &mut self,
+        chain_id: &ChainId,
+        channel_id: ChannelId,
+        sender: Option<&Bytes>,
+        ticket: u64,
+        now: u64,
+    ) -> Schedule {
+        let Some(config) = self.config.per_channel else {
+            return Schedule::At(now);
+        };
+
+        let channel = self
+            .channels
+            .entry((chain_id.clone(), channel_id))
+            .or_insert_with(|| ChannelQueue::new(config, now));
+
+        channel.expire(now);
+
+        let schedule = match channel.poll(ticket, sender, now) {
+            Some(schedule) => schedule,
+            None => {
+                let ticket = self.next_ticket();
+
+                channel.push(ticket, sender.cloned(), now);
+
+                channel
+                    .poll(ticket, sender, now)
+                    .expect("ticket was just pushed; qed;")
+            }
+        };
+
+        self.prune(now);
+
+        schedule
+    }
+
+    /// Hand out the next ticket, keeping the epoch in the upper 32 bits.
+    fn next_ticket(&mut self) -> u64 {
+        let ticket = self.next_ticket;
+        self.next_ticket =
+            (ticket & !u64::from(u32::MAX)) | u64::from((ticket as u32).wrapping_add(1));
+        ticket
+    }
+
+    /// Remove all buckets that have refilled completely and have no packets waiting on them, as
+    /// they are equivalent to a new bucket.
+    fn prune(&mut self, now: u64) {
+        let is_idle = |bucket: &mut TokenBucket| {
+            bucket.refill(now);
+            bucket.is_full()
+        };
+
+        self.senders.retain(|_, bucket| !is_idle(bucket));
+        self.channels.retain(|_, channel| {
+            channel.expire(now);
+            !(channel.waiting.is_empty() && is_idle(&mut channel.bucket))
+        });
+    }
+}
+
+/// The packets waiting on the rate limit of a single channel.
+///
+/// Packets are released round-robin across their senders: a sender only gets the next token once
+/// every other sender with a packet ready to go has been released as many packets as it has.
+#[derive(Debug, Clone)]
+struct ChannelQueue {
+    bucket: TokenBucket,
+    /// ticket -> packet
+    waiting: BTreeMap<u64, Waiting>,
+    /// The round each sender with waiting packets is in, i.e. the amount of its packets that have
+    /// been released.
+    rounds: HashMap<Option<Bytes>, u64>,
+}
+
+#[derive(Debug, Clone)]
+struct Waiting {
+    sender: Option<Bytes>,
+    /// Unix timestamp (in ms) at which the packet is through its per-sender rate limit.
+    not_before: u64,
+    /// Unix timestamp (in ms) at which the packet is expected to be polled next.
+    poll_at: u64,
+}
+
+impl ChannelQueue {
+    fn new(config: TokenBucketConfig, now: u64) -> Self {
+        Self {
+            bucket: TokenBucket::new(config, now),
+            waiting: BTreeMap::new(),
+            rounds: HashMap::new(),
+        }
+    }
+
+    fn waiting_for(&self, sender: Option<&Bytes>) -> u64 {
+        self.waiting
+            .values()
+            .filter(|w| w.sender.as_ref() == sender)
+            .count()
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+
+    fn push(&mut self, ticket: u64, sender: Option<Bytes>, not_before: u64) {
+        // senders start out in the current round, otherwise a new sender could take all tokens
+        // until it has caught up with the senders that were already waiting
+        let current_round = self.rounds.values().copied().min().unwrap_or(0);
+
+        self.rounds.entry(sender.clone()).or_insert(current_round);
+
+        self.waiting.insert(
+            ticket,
+            Waiting {
+                sender,
+                not_before,
+                poll_at: not_before,
+            },
+        );
+    }
+
+    /// Remove the packets that have not been polled in time, see [`LOST_AFTER`].
+    fn expire(&mut self, now: u64) {
+        self.waiting
+            .retain(|_, w| w.poll_at.saturating_add(LOST_AFTER) >= now);
+
+        let waiting = &self.waiting;
+        self.rounds
+            .retain(|sender, _| waiting.values().any(|w| &w.sender == sender));
+    }
+
+    /// Release the packet if it is its sender's turn and a token is available, otherwise return
+    /// when to poll again. Returns `None` if the ticket is not waiting on this channel.
+    fn poll(&mut self, ticket: u64, sender: Option<&Bytes>, now: u64) -> Option<Schedule> {
+        let not_before = self
+            .waiting
+            .get(&ticket)
+            .filter(|w| w.sender.as_ref() == sender)?
+            .not_before;
+
+        let poll_at = if now < not_before {
+            not_before
+        } else {
+            let round = self.rounds[&sender.cloned()];
+
+            // the packets of the senders that are behind this one in the round-robin, all of
+            // which have to be released before this packet
+            let mut ready = HashMap::<&Option<Bytes>, u64>::new();
+            for w in self.waiting.values().filter(|w| w.not_before <= now) {
+                *ready.entry(&w.sender).or_default() += 1;
+            }
+
+            let ahead = ready
+                .into_iter()
+                .map(|(sender, count)| count.min(round.saturating_sub(self.rounds[sender])))
+                .sum::<u64>();
+
+            if ahead == 0 && self.bucket.try_take(now) {
+                let sender = self
+                    .waiting
+                    .remove(&ticket)
+                    .expect("ticket is waiting; qed;")
+                    .sender;
+
+                if self.waiting.values().any(|w| w.sender == sender) {
+                    *self
+                        .rounds
+                        .get_mut(&sender)
+                        .expect("sender is waiting; qed;") += 1;
+                } else {
+                    self.rounds.remove(&sender);
+                }
+
+                return Some(Schedule::At(now));
+            }
+
+            self.bucket.available_at(now, ahead + 1)
+        };
+
+        self.waiting
+            .get_mut(&ticket)
+            .expect("ticket is waiting; qed;")
+            .poll_at = poll_at;
+
+        Some(Schedule::Wait { ticket, poll_at })
+    }
+}
+
+fn bucket<K: Hash + Eq>(
+    buckets: &mut HashMap<K, TokenBucket>,
+    key: K,
+    config: TokenBucketConfig,
+    now: u64,
+) -> &mut TokenBucket {
+    let bucket = buckets
+        .entry(key)
+        .or_insert_with(|| TokenBucket::new(config, now));
+
+    bucket.refill(now);
+
+    bucket
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limiter(
+        per_sender: Option<TokenBucketConfig>,
+        per_channel: Option<TokenBucketConfig>,
+        max_queued: u64,
+    ) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            per_sender,
+            per_channel,
+            max_queued,
+        })
+    }
+
+    /// Poll `ticket` at the requested times until it is released, returning the release time.
+    fn poll_until_released(
+        rate_limiter: &mut RateLimiter,
+        sender: &Bytes,
+        mut schedule: Schedule,
+    ) -> u64 {
+        let chain_id = ChainId::new("chain");
+        let channel_id = ChannelId::from_raw(1).unwrap();
+
+        loop {
+            match schedule {
+                Schedule::At(at) => return at,
+                Schedule::Wait { ticket, poll_at } => {
+                    schedule =
+                        rate_limiter.poll(&chain_id, channel_id, Some(sender), ticket, poll_at);
+                }
+                Schedule::Dropped => panic!("packet was dropped"),
+            }
+        }
+    }
+
+    #[test]
+    fn token_bucket_queues_in_order() {
+        let mut bucket = TokenBucket::new(
+            TokenBucketConfig {
+                capacity: 2,
+                refill_rate: 1,
+            },
+            0,
+        );
+
+        assert_eq!(bucket.reserve(0), 0);
+        assert_eq!(bucket.reserve(0), 0);
+        assert_eq!(bucket.reserve(0), 1000);
+        assert_eq!(bucket.reserve(0), 2000);
+        assert_eq!(bucket.queued(), 2);
+
+        assert_eq!(bucket.reserve(500), 3000);
+
+        bucket.refill(10_000);
+        assert!(bucket.is_full());
+        assert_eq!(bucket.queued(), 0);
+    }
+
+    #[test]
+    fn token_bucket_try_take() {
+        let mut bucket = TokenBucket::new(
+            TokenBucketConfig {
+                capacity: 1,
+                refill_rate: 2,
+            },
+            0,
+        );
+
+        assert!(bucket.try_take(0));
+        assert!(!bucket.try_take(0));
+        assert_eq!(bucket.queued(), 0);
+        assert_eq!(bucket.available_at(0, 1), 500);
+        assert!(!bucket.try_take(499));
+        assert!(bucket.try_take(500));
+    }
+
+    #[test]
+    fn senders_are_rate_limited_independently() {
+        let mut rate_limiter = rate_limiter(
+            Some(TokenBucketConfig {
+                capacity: 1,
+                refill_rate: 1,
+            }),
+            None,
+            1,
+        );
+
+        let chain_id = ChainId::new("chain");
+        let channel_id = ChannelId::from_raw(1).unwrap();
+        let flooder = Bytes::from(vec![1]);
+        let other = Bytes::from(vec![2]);
+
+        assert_eq!(
+            rate_limiter.schedule(&chain_id, channel_id, Some(&flooder), 0),
+            Schedule::At(0)
+        );
+        assert_eq!(
+            rate_limiter.schedule(&chain_id, channel_id, Some(&flooder), 0),
+            Schedule::At(1000)
+        );
+        assert_eq!(
+            rate_limiter.schedule(&chain_id, channel_id, Some(&flooder), 0),
+            Schedule::Dropped
+        );
+
+        assert_eq!(
+            rate_limiter.schedule(&chain_id, channel_id, Some(&other), 0),
+            Schedule::At(0)
+        );
+    }
+
+    #[test]
+    fn channel_flood_does_not_block_other_senders() {
+        let mut rate_limiter = rate_limiter(
+            None,
+            Some(TokenBucketConfig {
+                capacity: 1,
+                refill_rate: 1,
+            }),
+            100,
+        );
+
+        let chain_id = ChainId::new("chain");
+        let channel_id = ChannelId::from_raw(1).unwrap();
+        let flooder = Bytes::from(vec![1]);
+        let other = Bytes::from(vec![2]);
+
+        // the first packet of the flood takes the only token in the bucket
+        assert_eq!(
+            rate_limiter.schedule(&chain_id, channel_id, Some(&flooder), 0),
+            Schedule::At(0)
+        );
+
+        let flood = (0..50)
+            .map(|_| rate_limiter.schedule(&chain_id, channel_id, Some(&flooder), 0))
+            .collect::<Vec<_>>();
+        assert!(flood
+            .iter()
+            .all(|schedule| matches!(schedule, Schedule::Wait { .. })));
+
+        let schedule = rate_limiter.schedule(&chain_id, channel_id, Some(&other), 10);
+        assert!(matches!(schedule, Schedule::Wait { .. }), "{schedule:?}");
+
+        // the flood is polled first, but only one of its packets is released before it's the
+        // other sender's turn
+        let mut released = vec![];
+        for schedule in flood.iter().take(2) {
+            let Schedule::Wait { ticket, .. } = schedule else {
+                unreachable!()
+            };
+
+            released.push(rate_limiter.poll(&chain_id, channel_id, Some(&flooder), *ticket, 1000));
+        }
+        assert_eq!(released[0], Schedule::At(1000));
+        assert!(
+            matches!(released[1], Schedule::Wait { poll_at, .. } if poll_at == 3000),
+            "{:?}",
+            released[1]
+        );
+
+        assert_eq!(
+            poll_until_released(&mut rate_limiter, &other, schedule),
+            2000
+        );
+    }
+
+    #[test]
+    fn channel_senders_are_released_round_robin() {
+        let mut rate_limiter = rate_limiter(
+            None,
+            Some(TokenBucketConfig {
+                capacity: 1,
+                refill_rate: 1,
+            }),
+            100,
+        );
+
+        let chain_id = ChainId::new("chain");
+        let channel_id = ChannelId::from_raw(1).unwrap();
+        let a = Bytes::from(vec![1]);
+        let b = Bytes::from(vec![2]);
+
+        let mut waiting = vec![];
+        for sender in [&a, &a, &a, &b, &b, &b] {
+            match rate_limiter.schedule(&chain_id, channel_id, Some(sender), 0) {
+                Schedule::At(0) => {}
+                Schedule::Wait { ticket, .. } => waiting.push((sender, ticket)),
+                schedule => panic!("unexpected schedule {schedule:?}"),
+            }
+        }
+        assert_eq!(waiting.len(), 5);
+
+        // a token is available every second, poll the waiting packets in the order they were seen
+        // until one of them is released
+        let mut order = vec![];
+        for now in (1..=5).map(|s| s * 1000) {
+            let idx = waiting
+                .iter()
+                .position(|(sender, ticket)| {
+                    rate_limiter.poll(&chain_id, channel_id, Some(sender), *ticket, now)
+                        == Schedule::At(now)
+                })
+                .expect("a packet is released every second");
+
+            order.push(waiting.remove(idx).0.clone());
+        }
+
+        assert_eq!(order, vec![a.clone(), b.clone(), a, b.clone(), b]);
+    }
+
+    #[test]
+    fn channel_max_queued_is_per_sender() {
+        let mut rate_limiter = rate_limiter(
+            None,
+            Some(TokenBucketConfig {
+                capacity: 1,
+                refill_rate: 1,
+            }),
+            2,
+        );
+
+        let chain_id = ChainId::new("chain");
+        let channel_id = ChannelId::from_raw(1).unwrap();
+        let flooder = Bytes::from(vec![1]);
+        let other = Bytes::from(vec![2]);
+
+        let schedules = (0..4)
+            .map(|_| rate_limiter.schedule(&chain_id, channel_id, Some(&flooder), 0))
+            .collect::<Vec<_>>();
+        assert_eq!(schedules[0], Schedule::At(0));
+        assert!(matches!(schedules[1], Schedule::Wait { .. }));
+        assert!(matches!(schedules[2], Schedule::Wait { .. }));
+        assert_eq!(schedules[3], Schedule::Dropped);
+
+        assert!(matches!(
+            rate_limiter.schedule(&chain_id, channel_id, Some(&other), 0),
+            Schedule::Wait { .. }
+        ));
+    }
+
+    #[test]
+    fn lost_packets_are_requeued() {
+        let mut rate_limiter = rate_limiter(
+            None,
+            Some(TokenBucketConfig {
+                capacity: 1,
+                refill_rate: 1,
+            }),
+            100,
+        );
+
+        let chain_id = ChainId::new("chain");
+        let channel_id = ChannelId::from_raw(1).unwrap();
+        let a = Bytes::from(vec![1]);
+        let b = Bytes::from(vec![2]);
+
+        assert_eq!(
+            rate_limiter.schedule(&chain_id, channel_id, Some(&b), 0),
+            Schedule::At(0)
+        );
+
+        // this packet is never polled (until much later)
+        let Schedule::Wait { ticket: lost, .. } =
+            rate_limiter.schedule(&chain_id, channel_id, Some(&a), 0)
+        else {
+            panic!("packet should be waiting")
+        };
+
+        let first = rate_limiter.schedule(&chain_id, channel_id, Some(&b), 0);
+        let second = rate_limiter.schedule(&chain_id, channel_id, Some(&b), 0);
+        assert_eq!(poll_until_released(&mut rate_limiter, &b, first), 1000);
+
+        // b is a round ahead of a now, so it has to wait for a until a's packet is considered lost
+        assert_eq!(
+            poll_until_released(&mut rate_limiter, &b, second),
+            2000 + LOST_AFTER
+        );
+
+        // polling the lost packet queues it again
+        assert_eq!(
+            rate_limiter.poll(&chain_id, channel_id, Some(&a), lost, 100_000),
+            Schedule::At(100_000)
+        );
+    }
+
+    #[test]
+    fn ticket_epoch() {
+        let mut rate_limiter = rate_limiter(None, None, 100);
+
+        let epoch = rate_limiter.next_ticket & !u64::from(u32::MAX);
+
+        assert_eq!(rate_limiter.next_ticket(), epoch);
+        assert_eq!(rate_limiter.next_ticket(), epoch + 1);
+
+        // the counter wraps around within the epoch
+        rate_limiter.next_ticket = epoch | u64::from(u32::MAX);
+        assert_eq!(rate_limiter.next_ticket(), epoch | u64::from(u32::MAX));
+        assert_eq!(rate_limiter.next_ticket(), epoch);
+    }
+}
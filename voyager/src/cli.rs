@@ -8,7 +8,12 @@ use std::{
 use anyhow::{anyhow, Context};
 use clap::{self, Parser, Subcommand};
 use ibc_union_spec::IbcUnion;
-use unionlabs::{self, bounded::BoundedI64, ibc::core::client::height::Height, result_unwrap};
+use unionlabs::{
+    self,
+    bounded::{BoundedI32, BoundedI64},
+    ibc::core::client::height::Height,
+    result_unwrap,
+};
 use voyager_message::VoyagerMessage;
 use voyager_primitives::{ChainId, ClientType, IbcInterface, IbcSpec, IbcSpecId, QueryHeight};
 use voyager_types::RawClientId;
@@ -148,6 +153,7 @@ pub enum ConfigCmd {
     Schema,
 }
 
+type Pg32 = BoundedI32<1, { i32::MAX }>;
 type Pg64 = BoundedI64<1, { i64::MAX }>;
 
 #[derive(Debug, Subcommand)]
@@ -162,11 +168,12 @@ pub enum QueueCmd {
         rest_url: Option<String>,
     },
 
-    // History {
-    //     id: PgId,
-    //     #[arg(long, default_value_t = result_unwrap!(Pg32::new(10)))]
-    //     max_depth: Pg32,
-    // },
+    /// Print the tree of ops that led to the specified item.
+    History {
+        id: Pg64,
+        #[arg(long, default_value_t = result_unwrap!(Pg32::new_const(10)))]
+        max_depth: Pg32,
+    },
     /// Query all failed messages.
     QueryFailed {
         #[arg(long, default_value_t = result_unwrap!(Pg64::new_const(1)))]
@@ -184,6 +191,23 @@ pub enum QueueCmd {
         ///  {"a": {"b": "c"}}
         /// ````
         ///
+        /// When using the `sqlite-queue` backend, the item is stored as fully compact JSON (`{"a":{"b":"c"}}`).
+        ///
+        /// This can be specified multiple times to specify multiple filters.
+        #[arg(long = "item-filter", short = 'i')]
+        item_filters: Vec<String>,
+        /// SQL filters for failure message.
+        ///
+        /// This can be specified multiple times to specify multiple filters.
+        #[arg(long = "message-filter", short = 'm')]
+        message_filters: Vec<String>,
+    },
+    /// Move all failed messages matching the provided filters back into the queue.
+    ///
+    /// The filters have the same syntax as `query-failed`.
+    RequeueFailed {
+        /// SQL filters for the item.
+        ///
         /// This can be specified multiple times to specify multiple filters.
         #[arg(long = "item-filter", short = 'i')]
         item_filters: Vec<String>,
@@ -3,6 +3,7 @@ use std::{net::SocketAddr, time::Duration};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use voyager_core::{
+    balance_monitor::BalanceMonitorConfig,
     context::{ModulesConfig, PluginConfig},
     default_ipc_client_request_timeout, default_metrics_endpoint,
     default_optimizer_delay_milliseconds, default_rest_laddr, default_rpc_laddr,
@@ -41,5 +42,17 @@ pub struct VoyagerConfig {
     pub optimizer_delay_milliseconds: u64,
     #[serde(default = "default_ipc_client_request_timeout")]
     pub ipc_client_request_timeout: Duration,
+    /// If set, the config file will be checked for changes at this interval, and any changes to
+    /// the configured plugins and modules will be applied to the running instance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_reload_interval: Option<Duration>,
+    /// Bearer token required to access the admin api on the REST server. The admin api is disabled
+    /// if this is not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_token: Option<String>,
+    /// If set, the balances of the signers of all transaction plugins will be monitored. See
+    /// [`BalanceMonitorConfig`] for more information.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance_monitor: Option<BalanceMonitorConfig>,
     pub cache: voyager_core::cache::Config,
 }
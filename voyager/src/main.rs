@@ -1,4 +1,4 @@
-#![feature(trait_alias, try_find)]
+#![feature(trait_alias)]
 #![warn(
     clippy::pedantic,
     clippy::unwrap_used,
@@ -13,7 +13,7 @@
 
 use std::{collections::HashMap, fmt::Write, iter, process::ExitCode, time::Duration};
 
-use anyhow::{anyhow, Context as _};
+use anyhow::{anyhow, bail, Context as _};
 use clap::Parser;
 use ibc_classic_spec::IbcClassic;
 use ibc_union_spec::IbcUnion;
@@ -26,11 +26,11 @@ use schemars::gen::{SchemaGenerator, SchemaSettings};
 use serde::Serialize;
 use serde_json::Value;
 use tikv_jemallocator::Jemalloc;
-use tracing::info;
+use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 use voyager_client::VoyagerClient;
 use voyager_core::{
-    context::ModulesConfig,
+    context::{ModulesConfig, PluginConfig},
     default_metrics_endpoint, default_rest_laddr, default_rpc_laddr,
     equivalent_chain_ids::EquivalentChainIds,
     filter::{make_filter, run_filter, JaqFilterResult},
@@ -44,8 +44,11 @@ use voyager_message::{
     VoyagerMessage,
 };
 use voyager_primitives::{IbcSpec, QueryHeight};
-use voyager_rpc::{types::IbcStateResponse, VoyagerRpcClient};
-use voyager_vm::{call, promise, Op, Queue};
+use voyager_rpc::{
+    types::{IbcStateResponse, PluginInfo},
+    VoyagerRpcClient,
+};
+use voyager_vm::{call, promise, Op};
 
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
@@ -55,7 +58,7 @@ use crate::{
         get_voyager_config, App, Command, ConfigCmd, LogFormat, MsgCmd, PluginCmd, QueueCmd, RpcCmd,
     },
     config::{Config, VoyagerConfig},
-    queue::{QueueConfig, QueueImpl},
+    queue::{DbQueue, QueueConfig, QueueImpl},
 };
 
 #[cfg(windows)]
@@ -169,9 +172,13 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                         ),
                         retryable_error_expo_backoff_multiplier:
                             default_retryable_error_expo_backoff_multiplier(),
+                        retry_policies: vec![],
                     }),
                     optimizer_delay_milliseconds: 100,
                     ipc_client_request_timeout: Duration::new(60, 0),
+                    config_reload_interval: None,
+                    admin_token: None,
+                    balance_monitor: None,
                     cache: voyager_core::cache::Config::default(),
                 },
             }),
@@ -188,10 +195,12 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
 
             metrics::init(&config.voyager.metrics_endpoint);
 
+            let config_reload_interval = config.voyager.config_reload_interval;
+
             let voyager = Engine::builder()
                 .with_equivalent_chain_ids(config.equivalent_chain_ids)
-                .with_plugins(config.plugins)
-                .with_modules(config.modules)
+                .with_plugins(config.plugins.clone())
+                .with_modules(config.modules.clone())
                 .with_ipc_client_request_timeout(config.voyager.ipc_client_request_timeout)
                 .with_cache_config(config.voyager.cache)
                 .with_metrics_endpoint(config.voyager.metrics_endpoint)
@@ -199,6 +208,8 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                 .with_rest_laddr(config.voyager.rest_laddr)
                 .with_rpc_laddr(config.voyager.rpc_laddr)
                 .with_optimizer_delay_milliseconds(config.voyager.optimizer_delay_milliseconds)
+                .with_admin_token(config.voyager.admin_token)
+                .with_balance_monitor(config.voyager.balance_monitor)
                 .with_queue::<QueueImpl>(config.voyager.queue)
                 .register_ibc_spec_handler::<IbcUnion>()
                 .register_ibc_spec_handler::<IbcClassic>()
@@ -207,22 +218,31 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
 
             info!("starting relay service");
 
-            voyager.run().await;
+            match config_reload_interval {
+                Some(config_reload_interval) => {
+                    tokio::select! {
+                        () = voyager.run() => {}
+                        () = watch_config(
+                            &voyager,
+                            get_voyager_config,
+                            config_reload_interval,
+                            config.plugins,
+                            config.modules,
+                        ) => {}
+                    }
+                }
+                None => voyager.run().await,
+            }
         }
         Command::Plugin(cmd) => match cmd {
             PluginCmd::Interest {
                 plugin_name,
                 message,
             } => {
-                let plugin_config = get_voyager_config()?
-                    .plugins
-                    .into_iter()
-                    .try_find(|plugin_config| {
-                        <anyhow::Result<_>>::Ok(plugin_name == get_plugin_info(plugin_config)?.name)
-                    })?
-                    .ok_or(anyhow!("plugin not found"))?;
+                let (_, plugin_info) =
+                    find_plugin(get_voyager_config()?.plugins, &plugin_name).await?;
 
-                let (filter, plugin_name) = make_filter(get_plugin_info(&plugin_config)?)?;
+                let (filter, plugin_name) = make_filter(plugin_info)?;
 
                 let result = run_filter(
                     &filter,
@@ -242,24 +262,19 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                 }
             }
             PluginCmd::Info { plugin_name } => {
-                let plugin_config = get_voyager_config()?
-                    .plugins
-                    .into_iter()
-                    .try_find(|plugin_config| {
-                        <anyhow::Result<_>>::Ok(plugin_name == get_plugin_info(plugin_config)?.name)
-                    })?
-                    .ok_or(anyhow!("plugin not found"))?;
-
-                print_json(&get_plugin_info(&plugin_config)?);
+                let (_, plugin_info) =
+                    find_plugin(get_voyager_config()?.plugins, &plugin_name).await?;
+
+                print_json(&plugin_info);
             }
             PluginCmd::Call { plugin_name, args } => {
-                let plugin_config = get_voyager_config()?
-                    .plugins
-                    .into_iter()
-                    .try_find(|plugin_config| {
-                        <anyhow::Result<_>>::Ok(plugin_name == get_plugin_info(plugin_config)?.name)
-                    })?
-                    .ok_or(anyhow!("plugin not found"))?;
+                let PluginConfig::Local(plugin_config) =
+                    find_plugin(get_voyager_config()?.plugins, &plugin_name)
+                        .await?
+                        .0
+                else {
+                    bail!("plugin commands are only supported for local plugins");
+                };
 
                 tokio::process::Command::new(&plugin_config.path)
                     .arg("cmd")
@@ -271,26 +286,25 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                     .await?;
             }
             PluginCmd::List => {
-                let list = get_voyager_config()?
-                    .plugins
-                    .into_iter()
-                    .map(|module_config| get_plugin_info(&module_config).map(|p| p.name))
-                    .collect::<Result<Vec<_>, _>>()?;
+                let mut list = vec![];
+
+                for plugin_config in get_voyager_config()?.plugins {
+                    list.push(get_plugin_info(&plugin_config).await?.name);
+                }
 
                 print_json(&list);
             }
         },
         Command::Queue(cli_msg) => {
-            let db = || {
-                Ok(match get_voyager_config()?.voyager.queue {
-                    QueueConfig::PgQueue(cfg) => pg_queue::PgQueue::<VoyagerMessage>::new(cfg),
-                    QueueConfig::InMemory => {
-                        return Err(anyhow!(
-                            "no database set in config, queue commands \
-                            require the `pg-queue` database backend"
-                        ))
-                    }
-                })
+            let db = async {
+                DbQueue::new(get_voyager_config()?.voyager.queue)
+                    .await?
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "no database set in config, queue commands require \
+                            either the `pg-queue` or `sqlite-queue` database backend"
+                        )
+                    })
             };
 
             match cli_msg {
@@ -299,34 +313,37 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
 
                     send_enqueue(&rest_url, op).await?;
                 }
-                // NOTE: Temporarily disabled until i figure out a better way to implement this with the new queue design
-                // cli::QueueCmd::History { id, max_depth } => {
-                //     // let results = query_as!(
-                //     //     Record,
-                //     //     r#"SELECT id as "id!", parent, item as "item!: Item" FROM get_list($1, $2) ORDER BY id ASC"#,
-                //     //     id.inner(),
-                //     //     max_depth.inner()
-                //     // )
-                //     // .fetch_all(&db)
-                //     // .await
-                //     // .unwrap();
-
-                //     // println!("{}", serde_json::to_string_pretty(&results).unwrap());
-
-                //     todo!();
-                // }
+                QueueCmd::History { id, max_depth } => {
+                    let history = db
+                        .await?
+                        .query_history(id.inner(), max_depth.inner())
+                        .await?;
+
+                    print_json(&history);
+                }
                 QueueCmd::QueryFailed {
                     page,
                     per_page,
                     item_filters,
                     message_filters,
                 } => {
-                    let record = db()?
+                    let records = db
                         .await?
                         .query_failed(page.into(), per_page.into(), item_filters, message_filters)
                         .await?;
 
-                    print_json(&record);
+                    print_json(&records);
+                }
+                QueueCmd::RequeueFailed {
+                    item_filters,
+                    message_filters,
+                } => {
+                    let ids = db
+                        .await?
+                        .requeue_failed(item_filters, message_filters)
+                        .await?;
+
+                    print_json(&ids);
                 }
                 QueueCmd::QueryFailedById {
                     id,
@@ -335,12 +352,10 @@ async fn do_main(app: cli::App) -> anyhow::Result<()> {
                 } => {
                     let rest_url = get_rest_url(rest_url);
 
-                    let q = db()?.await?;
-
-                    let record = q.query_failed_by_id(id.inner()).await?;
+                    let record = db.await?.query_failed_by_id(id.inner()).await?;
 
                     if requeue {
-                        if let Some(op) = record.as_ref().map(|r| r.item.0.clone()) {
+                        if let Some(op) = record.map(|r| r.item) {
                             send_enqueue(&rest_url, op).await?;
                             println!("requeued");
                         }
@@ -756,6 +771,61 @@ async fn send_enqueue(
         .await?)
 }
 
+/// Poll the config file, reloading the plugins and modules of the running instance whenever they
+/// change. Changes to any other part of the config require a restart to take effect.
+async fn watch_config(
+    voyager: &Engine<QueueImpl>,
+    get_voyager_config: impl Fn() -> anyhow::Result<Config>,
+    config_reload_interval: Duration,
+    mut plugins: Vec<PluginConfig>,
+    mut modules: ModulesConfig,
+) {
+    loop {
+        tokio::time::sleep(config_reload_interval).await;
+
+        let config = match get_voyager_config() {
+            Ok(config) => config,
+            Err(err) => {
+                warn!("unable to read config file, not reloading: {err:#}");
+                continue;
+            }
+        };
+
+        if config.plugins == plugins && config.modules == modules {
+            continue;
+        }
+
+        info!("config file changed, reloading");
+
+        if let Err(err) = voyager
+            .reload(config.plugins.clone(), config.modules.clone())
+            .await
+        {
+            error!("unable to reload config: {err:#}");
+        }
+
+        // only retry once the config changes again
+        plugins = config.plugins;
+        modules = config.modules;
+    }
+}
+
+/// Find the configured plugin with the provided name, returning its config and info.
+async fn find_plugin(
+    plugins: Vec<PluginConfig>,
+    plugin_name: &str,
+) -> anyhow::Result<(PluginConfig, PluginInfo)> {
+    for plugin_config in plugins {
+        let plugin_info = get_plugin_info(&plugin_config).await?;
+
+        if plugin_info.name == plugin_name {
+            return Ok((plugin_config, plugin_info));
+        }
+    }
+
+    Err(anyhow!("plugin not found"))
+}
+
 fn print_json<T: Serialize>(t: &T) {
     println!(
         "{}",
@@ -3,13 +3,14 @@
 use std::fmt::Debug;
 
 use futures::Future;
-use pg_queue::{PgQueue, PgQueueConfig};
+use pg_queue::{HistoryNode, HistoryRecord, PgQueue, PgQueueConfig};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sqlite_queue::{SqliteQueue, SqliteQueueConfig};
 use voyager_message::VoyagerMessage;
 use voyager_vm::{
     filter::InterestFilter, in_memory::InMemoryQueue, pass::Pass, Captures, EnqueueResult, ItemId,
-    Op, Queue, QueueError,
+    ItemStatus, Op, Queue, QueueError, QueueItem,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -17,12 +18,14 @@ use voyager_vm::{
 pub enum QueueConfig {
     InMemory,
     PgQueue(PgQueueConfig),
+    SqliteQueue(SqliteQueueConfig),
 }
 
 #[derive(Debug, Clone)]
 pub enum QueueImpl {
     InMemory(InMemoryQueue<VoyagerMessage>),
     PgQueue(PgQueue<VoyagerMessage>),
+    SqliteQueue(SqliteQueue<VoyagerMessage>),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -30,6 +33,7 @@ pub enum QueueImpl {
 pub enum AnyQueueError {
     InMemory(std::convert::Infallible),
     PgQueue(sqlx::Error),
+    SqliteQueue(sqlx::Error),
 }
 
 impl Queue<VoyagerMessage> for QueueImpl {
@@ -46,6 +50,10 @@ impl Queue<VoyagerMessage> for QueueImpl {
                 .await
                 .map_err(AnyQueueError::PgQueue)
                 .map(Self::PgQueue),
+            QueueConfig::SqliteQueue(cfg) => SqliteQueue::new(cfg)
+                .await
+                .map_err(AnyQueueError::SqliteQueue)
+                .map(Self::SqliteQueue),
         }
     }
 
@@ -63,6 +71,10 @@ impl Queue<VoyagerMessage> for QueueImpl {
                 .enqueue(item, filter)
                 .await
                 .map_err(AnyQueueError::PgQueue),
+            QueueImpl::SqliteQueue(queue) => queue
+                .enqueue(item, filter)
+                .await
+                .map_err(AnyQueueError::SqliteQueue),
         }
     }
 
@@ -86,6 +98,10 @@ impl Queue<VoyagerMessage> for QueueImpl {
                 .process(filter, f)
                 .await
                 .map_err(AnyQueueError::PgQueue),
+            QueueImpl::SqliteQueue(queue) => queue
+                .process(filter, f)
+                .await
+                .map_err(AnyQueueError::SqliteQueue),
         }
     }
 
@@ -104,6 +120,189 @@ impl Queue<VoyagerMessage> for QueueImpl {
                 .optimize(tag, filter, optimizer)
                 .await
                 .map_err(|e| e.map_left(AnyQueueError::PgQueue)),
+            QueueImpl::SqliteQueue(queue) => queue
+                .optimize(tag, filter, optimizer)
+                .await
+                .map_err(|e| e.map_left(AnyQueueError::SqliteQueue)),
+        }
+    }
+
+    async fn query_items(
+        &self,
+        status: ItemStatus,
+        page: u32,
+        per_page: u32,
+    ) -> Result<Vec<QueueItem<VoyagerMessage>>, Self::Error> {
+        match self {
+            QueueImpl::InMemory(queue) => queue
+                .query_items(status, page, per_page)
+                .await
+                .map_err(AnyQueueError::InMemory),
+            QueueImpl::PgQueue(queue) => queue
+                .query_items(status, page, per_page)
+                .await
+                .map_err(AnyQueueError::PgQueue),
+            QueueImpl::SqliteQueue(queue) => queue
+                .query_items(status, page, per_page)
+                .await
+                .map_err(AnyQueueError::SqliteQueue),
+        }
+    }
+
+    async fn requeue_failed_items(&self, ids: Vec<ItemId>) -> Result<Vec<ItemId>, Self::Error> {
+        match self {
+            QueueImpl::InMemory(queue) => queue
+                .requeue_failed_items(ids)
+                .await
+                .map_err(AnyQueueError::InMemory),
+            QueueImpl::PgQueue(queue) => queue
+                .requeue_failed_items(ids)
+                .await
+                .map_err(AnyQueueError::PgQueue),
+            QueueImpl::SqliteQueue(queue) => queue
+                .requeue_failed_items(ids)
+                .await
+                .map_err(AnyQueueError::SqliteQueue),
+        }
+    }
+
+    async fn delete_failed_items(&self, ids: Vec<ItemId>) -> Result<Vec<ItemId>, Self::Error> {
+        match self {
+            QueueImpl::InMemory(queue) => queue
+                .delete_failed_items(ids)
+                .await
+                .map_err(AnyQueueError::InMemory),
+            QueueImpl::PgQueue(queue) => queue
+                .delete_failed_items(ids)
+                .await
+                .map_err(AnyQueueError::PgQueue),
+            QueueImpl::SqliteQueue(queue) => queue
+                .delete_failed_items(ids)
+                .await
+                .map_err(AnyQueueError::SqliteQueue),
+        }
+    }
+}
+
+/// A queue backed by a database, as required by the `queue` subcommands to inspect the queue
+/// outside of a running voyager instance.
+#[derive(Debug, Clone)]
+pub enum DbQueue {
+    PgQueue(PgQueue<VoyagerMessage>),
+    SqliteQueue(SqliteQueue<VoyagerMessage>),
+}
+
+/// A failed item, as stored by any [`DbQueue`] backend.
+#[derive(Debug, Serialize)]
+pub struct FailedRecord {
+    pub id: i64,
+    pub parents: Vec<i64>,
+    pub item: Op<VoyagerMessage>,
+    pub message: String,
+}
+
+impl From<pg_queue::FailedRecord<VoyagerMessage>> for FailedRecord {
+    fn from(record: pg_queue::FailedRecord<VoyagerMessage>) -> Self {
+        Self {
+            id: record.id,
+            parents: record.parents,
+            item: record.item.0,
+            message: record.message,
+        }
+    }
+}
+
+impl From<sqlite_queue::FailedRecord<VoyagerMessage>> for FailedRecord {
+    fn from(record: sqlite_queue::FailedRecord<VoyagerMessage>) -> Self {
+        Self {
+            id: record.id,
+            parents: record.parents,
+            item: record.item.0,
+            message: record.message,
         }
     }
 }
+
+impl DbQueue {
+    /// Connect to the database configured in `cfg`, returning `None` for the in-memory queue.
+    ///
+    /// This does not release any claimed items, and as such is safe to use against the database of
+    /// a running voyager instance.
+    pub async fn new(cfg: QueueConfig) -> Result<Option<Self>, sqlx::Error> {
+        Ok(match cfg {
+            QueueConfig::InMemory => None,
+            QueueConfig::PgQueue(cfg) => Some(Self::PgQueue(PgQueue::new(cfg).await?)),
+            QueueConfig::SqliteQueue(cfg) => {
+                Some(Self::SqliteQueue(SqliteQueue::connect(cfg).await?))
+            }
+        })
+    }
+
+    pub async fn query_failed(
+        &self,
+        page: i64,
+        per_page: i64,
+        item_filters: Vec<String>,
+        message_filters: Vec<String>,
+    ) -> Result<Vec<FailedRecord>, sqlx::Error> {
+        Ok(match self {
+            DbQueue::PgQueue(queue) => queue
+                .query_failed(page, per_page, item_filters, message_filters)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            DbQueue::SqliteQueue(queue) => queue
+                .query_failed(page, per_page, item_filters, message_filters)
+                .await?
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        })
+    }
+
+    pub async fn query_failed_by_id(&self, id: i64) -> Result<Option<FailedRecord>, sqlx::Error> {
+        Ok(match self {
+            DbQueue::PgQueue(queue) => queue.query_failed_by_id(id).await?.map(Into::into),
+            DbQueue::SqliteQueue(queue) => queue.query_failed_by_id(id).await?.map(Into::into),
+        })
+    }
+
+    pub async fn requeue_failed(
+        &self,
+        item_filters: Vec<String>,
+        message_filters: Vec<String>,
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        match self {
+            DbQueue::PgQueue(queue) => queue.requeue_failed(item_filters, message_filters).await,
+            DbQueue::SqliteQueue(queue) => {
+                queue.requeue_failed(item_filters, message_filters).await
+            }
+        }
+    }
+
+    /// Build the tree of items that led to the item with the specified id, up to `max_depth`
+    /// generations back. Returns `None` if the item does not exist.
+    pub async fn query_history(
+        &self,
+        id: i64,
+        max_depth: i32,
+    ) -> Result<Option<HistoryNode<VoyagerMessage>>, sqlx::Error> {
+        let records = match self {
+            DbQueue::PgQueue(queue) => queue.query_history(id, max_depth).await?,
+            DbQueue::SqliteQueue(queue) => queue
+                .query_history(id, max_depth)
+                .await?
+                .into_iter()
+                .map(|record| HistoryRecord {
+                    id: record.id,
+                    parents: record.parents,
+                    item: record.item,
+                    depth: record.depth,
+                })
+                .collect(),
+        };
+
+        Ok(HistoryNode::from_records(id, records))
+    }
+}